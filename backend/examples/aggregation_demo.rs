@@ -34,8 +34,9 @@ async fn main() -> Result<()> {
     // Configure aggregation service
     let config = AggregationConfig {
         interval_hours: 1, // Run every hour
-        lookback_hours: 2, // Process last 2 hours
+        lookback_hours: 2, // First run aggregates the last 2 whole hours
         batch_size: 10000, // Process 10k payments at a time
+        grace_minutes: 15, // Hours close a quarter hour after they end
     };
 
     // Create aggregation service
@@ -45,7 +46,9 @@ async fn main() -> Result<()> {
 
     // Option 1: Run aggregation once
     info!("Running one-time aggregation...");
-    aggregation_service.run_hourly_aggregation().await?;
+    aggregation_service
+        .run_hourly_aggregation(chrono::Utc::now())
+        .await?;
     info!("One-time aggregation completed");
 
     // Option 2: Start scheduler (runs continuously)
@@ -11,19 +11,8 @@ async fn main() -> Result<()> {
     println!("🚀 Stellar RPC Integration Demo\n");
     println!("================================\n");
 
-    // Initialize client in mock mode for demo
-    let mock_mode = std::env::var("MOCK_MODE")
-        .unwrap_or_else(|_| "true".to_string())
-        .parse::<bool>()
-        .unwrap_or(true);
-
-    let client = StellarRpcClient::new_with_defaults(mock_mode);
-
-    if mock_mode {
-        println!("📊 Running in MOCK MODE (use MOCK_MODE=false for real data)\n");
-    } else {
-        println!("🌐 Connecting to LIVE Stellar Network\n");
-    }
+    let client = StellarRpcClient::new_with_defaults();
+    println!("🌐 Connecting to LIVE Stellar Network\n");
 
     // 1. Health Check
     println!("1️⃣  Checking RPC Health...");
@@ -166,11 +155,5 @@ async fn main() -> Result<()> {
     println!("================================");
     println!("✨ Demo Complete!\n");
 
-    if mock_mode {
-        println!("💡 Tip: Set MOCK_MODE=false to fetch real data from Stellar network");
-    } else {
-        println!("✅ Successfully connected to live Stellar network!");
-    }
-
     Ok(())
 }
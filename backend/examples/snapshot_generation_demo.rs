@@ -82,7 +82,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
             // Demonstrate hash determinism
             info!("🔍 Testing hash determinism...");
-            let snapshot = snapshot_service.aggregate_all_metrics(epoch).await?;
+            let snapshot = snapshot_service.aggregate_all_metrics(epoch, chrono::Utc::now()).await?;
             let json1 = SnapshotService::serialize_deterministically(snapshot.clone())?;
             let json2 = SnapshotService::serialize_deterministically(snapshot)?;
 
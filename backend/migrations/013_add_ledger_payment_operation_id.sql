@@ -0,0 +1,6 @@
+-- Operation IDs (Horizon TOIDs) make ledger payment inserts idempotent, so
+-- backfill workers and the live tail can overlap without duplicating rows
+ALTER TABLE ledger_payments ADD COLUMN operation_id TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_ledger_payments_operation_id ON ledger_payments(operation_id);
+CREATE INDEX IF NOT EXISTS idx_ledger_payments_ledger ON ledger_payments(ledger_sequence);
@@ -0,0 +1,16 @@
+-- Path payments debit one asset and deliver another. The existing asset
+-- columns hold the delivered asset; these hold what the sender paid and the
+-- assets the payment was routed through (a JSON array of CODE:ISSUER keys).
+ALTER TABLE ledger_payments ADD COLUMN source_asset_code TEXT;
+ALTER TABLE ledger_payments ADD COLUMN source_asset_issuer TEXT;
+ALTER TABLE ledger_payments ADD COLUMN source_amount TEXT;
+ALTER TABLE ledger_payments ADD COLUMN path TEXT;
+
+ALTER TABLE payments ADD COLUMN operation_type TEXT;
+ALTER TABLE payments ADD COLUMN source_asset_code TEXT;
+ALTER TABLE payments ADD COLUMN source_asset_issuer TEXT;
+ALTER TABLE payments ADD COLUMN source_amount REAL;
+ALTER TABLE payments ADD COLUMN path TEXT;
+
+-- Volume-weighted FX rate (asset_b per asset_a) for cross-asset corridors
+ALTER TABLE corridor_metrics_hourly ADD COLUMN avg_fx_rate REAL;
@@ -0,0 +1,14 @@
+-- Failed transactions are now ingested alongside successful ones so success
+-- rates reflect reality. result_code is the Horizon-style code for the
+-- operation (op_success, op_underfunded, op_no_trust, ...) or, when the
+-- transaction was rejected as a whole, the transaction code (tx_bad_seq, ...).
+ALTER TABLE ledger_payments ADD COLUMN successful BOOLEAN NOT NULL DEFAULT 1;
+ALTER TABLE ledger_payments ADD COLUMN result_code TEXT;
+
+ALTER TABLE payments ADD COLUMN successful BOOLEAN NOT NULL DEFAULT 1;
+ALTER TABLE payments ADD COLUMN result_code TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_payments_failed ON payments(created_at) WHERE successful = 0;
+
+-- Failure counts per result code for the hour, as a JSON object
+ALTER TABLE corridor_metrics_hourly ADD COLUMN failure_breakdown TEXT;
@@ -0,0 +1,38 @@
+-- Settlement latency per payment. confirmation_time is the close time of the
+-- ledger the payment landed in; submission_time is when the flow started:
+-- the transaction's valid_after bound for on-chain payments, or the anchor's
+-- started_at for SEP-24/SEP-31 flows. latency_source says which one applied.
+ALTER TABLE ledger_payments ADD COLUMN submission_time TEXT;
+ALTER TABLE ledger_payments ADD COLUMN confirmation_time TEXT;
+ALTER TABLE ledger_payments ADD COLUMN settlement_latency_ms INTEGER;
+ALTER TABLE ledger_payments ADD COLUMN latency_source TEXT;
+
+ALTER TABLE payments ADD COLUMN submission_time TEXT;
+ALTER TABLE payments ADD COLUMN confirmation_time TEXT;
+ALTER TABLE payments ADD COLUMN settlement_latency_ms INTEGER;
+ALTER TABLE payments ADD COLUMN latency_source TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_payments_transaction_hash ON payments(transaction_hash);
+CREATE INDEX IF NOT EXISTS idx_ledger_payments_transaction_hash ON ledger_payments(transaction_hash);
+
+-- SEP-24/SEP-31 anchor transactions seen through the proxies, kept so the
+-- Stellar payment they settle with can be timed from the anchor's start
+CREATE TABLE IF NOT EXISTS anchor_transactions (
+    id TEXT NOT NULL,
+    protocol TEXT NOT NULL, -- 'sep24' or 'sep31'
+    transfer_server TEXT NOT NULL,
+    kind TEXT,
+    status TEXT,
+    started_at TEXT NOT NULL,
+    completed_at TEXT,
+    stellar_transaction_id TEXT,
+    updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (transfer_server, id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_anchor_transactions_stellar_tx ON anchor_transactions(stellar_transaction_id);
+
+-- Settlement latency percentiles for the hour
+ALTER TABLE corridor_metrics_hourly ADD COLUMN p50_settlement_latency_ms INTEGER;
+ALTER TABLE corridor_metrics_hourly ADD COLUMN p90_settlement_latency_ms INTEGER;
+ALTER TABLE corridor_metrics_hourly ADD COLUMN p99_settlement_latency_ms INTEGER;
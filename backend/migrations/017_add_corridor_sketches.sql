@@ -0,0 +1,5 @@
+-- Mergeable HDR histogram sketches (V2 encoding) of settlement latency in
+-- milliseconds and payment size in US cents, so percentiles can be served
+-- for any window built from hourly buckets
+ALTER TABLE corridor_metrics_hourly ADD COLUMN settlement_latency_sketch BLOB;
+ALTER TABLE corridor_metrics_hourly ADD COLUMN payment_size_sketch BLOB;
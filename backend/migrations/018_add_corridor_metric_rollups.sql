@@ -0,0 +1,48 @@
+-- corridor_metrics holds daily, weekly and monthly rollups of
+-- corridor_metrics_hourly. The period joins the primary key, so the table is
+-- rebuilt; existing rows are daily.
+CREATE TABLE corridor_metrics_rollup (
+    id TEXT NOT NULL,
+    corridor_key TEXT NOT NULL,
+    asset_a_code TEXT NOT NULL,
+    asset_a_issuer TEXT NOT NULL,
+    asset_b_code TEXT NOT NULL,
+    asset_b_issuer TEXT NOT NULL,
+    period TEXT NOT NULL DEFAULT 'daily', -- 'daily', 'weekly' or 'monthly'
+    date TEXT NOT NULL, -- Start of the period at UTC midnight; weeks start on Monday
+    hours INTEGER DEFAULT 0, -- Hourly buckets rolled up
+    total_transactions INTEGER DEFAULT 0,
+    successful_transactions INTEGER DEFAULT 0,
+    failed_transactions INTEGER DEFAULT 0,
+    success_rate REAL DEFAULT 0,
+    volume_usd REAL DEFAULT 0,
+    avg_slippage_bps REAL,
+    avg_fx_rate REAL,
+    avg_settlement_latency_ms INTEGER,
+    median_settlement_latency_ms INTEGER,
+    p90_settlement_latency_ms INTEGER,
+    p99_settlement_latency_ms INTEGER,
+    liquidity_depth_usd REAL DEFAULT 0,
+    failure_breakdown TEXT,
+    settlement_latency_sketch BLOB,
+    payment_size_sketch BLOB,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (corridor_key, period, date)
+);
+
+INSERT INTO corridor_metrics_rollup (
+    id, corridor_key, asset_a_code, asset_a_issuer, asset_b_code, asset_b_issuer,
+    period, date, total_transactions, successful_transactions, failed_transactions,
+    success_rate, volume_usd, created_at, updated_at
+)
+SELECT
+    lower(hex(randomblob(16))), corridor_key, asset_a_code, asset_a_issuer, asset_b_code, asset_b_issuer,
+    'daily', date, total_transactions, successful_transactions, failed_transactions,
+    success_rate, volume_usd, created_at, updated_at
+FROM corridor_metrics;
+
+DROP TABLE corridor_metrics;
+ALTER TABLE corridor_metrics_rollup RENAME TO corridor_metrics;
+
+CREATE INDEX idx_corridor_metrics_period_date ON corridor_metrics(period, date);
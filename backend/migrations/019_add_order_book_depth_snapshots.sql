@@ -0,0 +1,15 @@
+-- Periodic order book depth per corridor, for charting liquidity over time
+CREATE TABLE IF NOT EXISTS order_book_depth_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    corridor_key TEXT NOT NULL,
+    mid_price REAL NOT NULL, -- Counter units per base unit
+    base_usd_price REAL NOT NULL,
+    counter_usd_price REAL NOT NULL,
+    bid_depth_usd REAL NOT NULL DEFAULT 0.0, -- Whole bid side
+    ask_depth_usd REAL NOT NULL DEFAULT 0.0, -- Whole ask side
+    quotes TEXT NOT NULL, -- JSON slippage quotes at 10, 50, 100 and 200 bps
+    snapshot_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_book_depth_corridor_time
+    ON order_book_depth_snapshots(corridor_key, snapshot_at);
@@ -0,0 +1,19 @@
+-- Composite reliability score per anchor, recomputed periodically from its
+-- assets' payments, with the components that went into it
+CREATE TABLE IF NOT EXISTS anchor_reliability_scores (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    anchor_id TEXT NOT NULL REFERENCES anchors(id) ON DELETE CASCADE,
+    composite_score REAL NOT NULL,
+    asset_performance_score REAL NOT NULL,
+    volume_score REAL NOT NULL,
+    asset_diversity_score REAL NOT NULL,
+    weighted_success_rate REAL NOT NULL,
+    total_assets INTEGER NOT NULL,
+    total_volume_usd REAL NOT NULL,
+    asset_performance TEXT NOT NULL, -- JSON per-asset transaction counts and volume
+    window_start DATETIME NOT NULL,
+    computed_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_anchor_reliability_scores_anchor_time
+    ON anchor_reliability_scores(anchor_id, computed_at DESC);
@@ -0,0 +1,5 @@
+-- Version of the scoring model each stored score was computed with; NULL for
+-- scores from before scoring models were versioned
+ALTER TABLE anchors ADD COLUMN scoring_version TEXT;
+ALTER TABLE anchor_metrics_history ADD COLUMN scoring_version TEXT;
+ALTER TABLE anchor_reliability_scores ADD COLUMN scoring_version TEXT;
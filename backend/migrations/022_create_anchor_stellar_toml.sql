@@ -0,0 +1,37 @@
+-- Each anchor's stellar.toml (SEP-1) as last crawled, with the endpoints and
+-- organization details the other SEPs need
+CREATE TABLE IF NOT EXISTS anchor_stellar_toml (
+    anchor_id TEXT PRIMARY KEY REFERENCES anchors(id) ON DELETE CASCADE,
+    home_domain TEXT NOT NULL,
+    signing_key TEXT,
+    transfer_server TEXT,
+    transfer_server_sep0024 TEXT,
+    direct_payment_server TEXT,
+    web_auth_endpoint TEXT,
+    kyc_server TEXT,
+    anchor_quote_server TEXT,
+    org_name TEXT,
+    toml_hash TEXT NOT NULL,
+    toml TEXT NOT NULL, -- JSON of the parsed file
+    validation_errors TEXT NOT NULL, -- JSON array of SEP-1 problems found
+    fetch_error TEXT, -- why the latest crawl failed, kept until one succeeds
+    changed_at DATETIME NOT NULL,
+    checked_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_anchor_stellar_toml_home_domain
+    ON anchor_stellar_toml(home_domain);
+
+-- A row each time an anchor's stellar.toml changes
+CREATE TABLE IF NOT EXISTS anchor_stellar_toml_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    anchor_id TEXT NOT NULL REFERENCES anchors(id) ON DELETE CASCADE,
+    home_domain TEXT NOT NULL,
+    toml_hash TEXT NOT NULL,
+    toml TEXT NOT NULL,
+    validation_errors TEXT NOT NULL,
+    fetched_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_anchor_stellar_toml_history_anchor_time
+    ON anchor_stellar_toml_history(anchor_id, fetched_at DESC);
@@ -0,0 +1,23 @@
+-- Results of probing the SEP endpoints listed in each anchor's stellar.toml
+CREATE TABLE IF NOT EXISTS anchor_endpoint_probes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    anchor_id TEXT NOT NULL REFERENCES anchors(id) ON DELETE CASCADE,
+    sep TEXT NOT NULL, -- sep1, sep6, sep10, sep24, sep31 or sep38
+    url TEXT NOT NULL,
+    status_code INTEGER, -- NULL when no response was received
+    latency_ms INTEGER NOT NULL,
+    up INTEGER NOT NULL, -- 2xx response
+    schema_valid INTEGER NOT NULL, -- response body has the fields the SEP requires
+    error TEXT,
+    probed_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_anchor_endpoint_probes_anchor_time
+    ON anchor_endpoint_probes(anchor_id, probed_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_anchor_endpoint_probes_time
+    ON anchor_endpoint_probes(probed_at);
+
+-- Share of probes over the uptime window that got a 2xx response; NULL until
+-- the anchor's endpoints have been probed
+ALTER TABLE anchors ADD COLUMN uptime_percentage REAL;
@@ -0,0 +1,5 @@
+-- Merkle root committed on-chain for each analytics snapshot, and the
+-- transaction that committed it
+ALTER TABLE snapshots ADD COLUMN merkle_root TEXT;
+ALTER TABLE snapshots ADD COLUMN transaction_hash TEXT;
+ALTER TABLE snapshots ADD COLUMN ledger INTEGER;
@@ -0,0 +1,18 @@
+-- On-chain submission queue for scheduled analytics snapshots, one row per
+-- epoch. Rows outlive restarts so failed submissions are retried.
+CREATE TABLE IF NOT EXISTS snapshot_submissions (
+    epoch INTEGER PRIMARY KEY,
+    snapshot_id TEXT NOT NULL REFERENCES snapshots(id),
+    merkle_root TEXT NOT NULL,
+    status TEXT NOT NULL, -- pending, submitted, confirmed or failed
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    transaction_hash TEXT,
+    ledger INTEGER,
+    next_attempt_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_snapshot_submissions_due
+    ON snapshot_submissions(status, next_attempt_at);
@@ -0,0 +1,8 @@
+-- Ledgers the live tail couldn't decode. The tail records them here and moves
+-- on instead of retrying the same ledger forever; rows are kept for replay.
+CREATE TABLE IF NOT EXISTS ledger_decode_errors (
+    ledger_sequence INTEGER PRIMARY KEY,
+    ledger_hash TEXT NOT NULL,
+    error TEXT NOT NULL,
+    recorded_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
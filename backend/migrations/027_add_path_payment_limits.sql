@@ -0,0 +1,8 @@
+-- The bound each path payment was submitted with: send_max caps what a
+-- strict-receive payment may debit, dest_min floors what a strict-send payment
+-- must deliver. Realised slippage is measured against them.
+ALTER TABLE ledger_payments ADD COLUMN send_max TEXT;
+ALTER TABLE ledger_payments ADD COLUMN dest_min TEXT;
+
+ALTER TABLE payments ADD COLUMN send_max REAL;
+ALTER TABLE payments ADD COLUMN dest_min REAL;
@@ -1,20 +1,26 @@
 use crate::models::{AnchorMetrics, AnchorStatus};
+use crate::scoring::{active_model, ScoringModel};
+use serde::{Deserialize, Serialize};
 
 pub mod corridor;
 
 /// Performance metrics for an anchor's individual asset
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnchorAssetPerformance {
     pub asset_code: String,
     pub asset_issuer: String,
     pub total_transactions: i64,
     pub successful_transactions: i64,
     pub failed_transactions: i64,
+    /// Successful volume in the asset's own units
+    #[serde(default)]
+    pub total_volume: f64,
+    /// `total_volume` priced in USD; zero when the asset has no price
     pub total_volume_usd: f64,
 }
 
 /// Comprehensive reliability score for an anchor
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnchorReliabilityScore {
     pub anchor_address: String,
     pub composite_score: f64, // 0-100 scale
@@ -24,15 +30,35 @@ pub struct AnchorReliabilityScore {
     pub total_assets: usize,
     pub total_volume_usd: f64,
     pub weighted_success_rate: f64,
+    /// Version of the scoring model the score was computed with
+    pub scoring_version: String,
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
-/// Compute anchor reliability metrics based on transaction data
+/// Compute anchor reliability metrics based on transaction data, with the
+/// active scoring model
 pub fn compute_anchor_metrics(
     total_transactions: i64,
     successful_transactions: i64,
     failed_transactions: i64,
     avg_settlement_time_ms: Option<i32>,
+) -> AnchorMetrics {
+    compute_anchor_metrics_with(
+        active_model(),
+        total_transactions,
+        successful_transactions,
+        failed_transactions,
+        avg_settlement_time_ms,
+    )
+}
+
+/// Compute anchor reliability metrics based on transaction data
+pub fn compute_anchor_metrics_with(
+    model: &ScoringModel,
+    total_transactions: i64,
+    successful_transactions: i64,
+    failed_transactions: i64,
+    avg_settlement_time_ms: Option<i32>,
 ) -> AnchorMetrics {
     if total_transactions == 0 {
         return AnchorMetrics {
@@ -44,6 +70,8 @@ pub fn compute_anchor_metrics(
             failed_transactions: 0,
             avg_settlement_time_ms: None,
             status: AnchorStatus::Red,
+            scoring_version: model.version.clone(),
+            uptime_percentage: None,
         };
     }
 
@@ -54,13 +82,10 @@ pub fn compute_anchor_metrics(
     let success_rate = (success_rate * 100.0).round() / 100.0;
     let failure_rate = (failure_rate * 100.0).round() / 100.0;
 
-    // Compute reliability score (0-100)
-    // Formula: (success_rate * 0.5) + (settlement_time_score * 0.25) + (volume_consistency * 0.25)
-    // For MVP, we'll use a simplified formula focused on success rate and settlement time
-    let settlement_time_score = calculate_settlement_time_score(avg_settlement_time_ms);
-    let reliability_score = (success_rate * 0.7) + (settlement_time_score * 0.3);
+    // Compute reliability score (0-100) from success rate and settlement time
+    let reliability_score = model.anchor_reliability_score(success_rate, avg_settlement_time_ms);
 
-    let status = AnchorStatus::from_metrics(success_rate, failure_rate);
+    let status = model.anchor_status(success_rate, failure_rate);
 
     AnchorMetrics {
         success_rate,
@@ -71,25 +96,36 @@ pub fn compute_anchor_metrics(
         failed_transactions,
         avg_settlement_time_ms,
         status,
+        scoring_version: model.version.clone(),
+        uptime_percentage: None,
     }
 }
 
-/// Calculate settlement time score (0-100)
-/// Lower settlement time = higher score
-fn calculate_settlement_time_score(avg_settlement_time_ms: Option<i32>) -> f64 {
-    const MAX_SETTLEMENT_TIME_MS: f64 = 10000.0; // 10 seconds
-    const MIN_SETTLEMENT_TIME_MS: f64 = 1000.0; // 1 second
-
-    match avg_settlement_time_ms {
-        Some(time_ms) if time_ms <= MIN_SETTLEMENT_TIME_MS as i32 => 100.0,
-        Some(time_ms) if time_ms >= MAX_SETTLEMENT_TIME_MS as i32 => 0.0,
-        Some(time_ms) => {
-            let normalized = (MAX_SETTLEMENT_TIME_MS - time_ms as f64)
-                / (MAX_SETTLEMENT_TIME_MS - MIN_SETTLEMENT_TIME_MS);
-            normalized * 100.0
-        }
-        None => 50.0, // Default middle score if no data
+/// Fold SEP endpoint uptime into anchor metrics, with the active scoring model
+pub fn apply_anchor_uptime(metrics: AnchorMetrics, uptime_percentage: Option<f64>) -> AnchorMetrics {
+    apply_anchor_uptime_with(active_model(), metrics, uptime_percentage)
+}
+
+/// Fold SEP endpoint uptime into anchor metrics. The status becomes the worse
+/// of the on-chain and uptime statuses, or the uptime status alone when the
+/// anchor has no transactions to judge it by.
+pub fn apply_anchor_uptime_with(
+    model: &ScoringModel,
+    mut metrics: AnchorMetrics,
+    uptime_percentage: Option<f64>,
+) -> AnchorMetrics {
+    metrics.uptime_percentage = uptime_percentage;
+
+    if let Some(uptime) = uptime_percentage {
+        let uptime_status = model.uptime_status(uptime);
+        metrics.status = if metrics.total_transactions == 0 {
+            uptime_status
+        } else {
+            metrics.status.worst(uptime_status)
+        };
     }
+
+    metrics
 }
 
 /// Calculate assets issued per anchor
@@ -109,10 +145,20 @@ pub fn count_assets_per_anchor(assets: &[String]) -> usize {
 /// * `network_max_volume` - Maximum volume across all anchors in the network for normalization
 ///
 /// # Returns
-/// `AnchorReliabilityScore` with composite score (0-100) and component scores
+/// `AnchorReliabilityScore` with composite score (0-100) and component scores,
+/// weighted by the active scoring model
 pub fn compute_anchor_reliability_score(
     asset_performances: &[AnchorAssetPerformance],
     network_max_volume: f64,
+) -> AnchorReliabilityScore {
+    compute_anchor_reliability_score_with(active_model(), asset_performances, network_max_volume)
+}
+
+/// Compute comprehensive anchor reliability score with the given scoring model
+pub fn compute_anchor_reliability_score_with(
+    model: &ScoringModel,
+    asset_performances: &[AnchorAssetPerformance],
+    network_max_volume: f64,
 ) -> AnchorReliabilityScore {
     // Handle empty asset list
     if asset_performances.is_empty() {
@@ -125,6 +171,7 @@ pub fn compute_anchor_reliability_score(
             total_assets: 0,
             total_volume_usd: 0.0,
             weighted_success_rate: 0.0,
+            scoring_version: model.version.clone(),
             timestamp: chrono::Utc::now(),
         };
     }
@@ -132,9 +179,13 @@ pub fn compute_anchor_reliability_score(
     // Calculate total volume and weighted success rate
     let mut total_volume_usd = 0.0;
     let mut weighted_success_sum = 0.0;
+    let mut total_transactions = 0;
+    let mut successful_transactions = 0;
 
     for asset in asset_performances {
         total_volume_usd += asset.total_volume_usd;
+        total_transactions += asset.total_transactions;
+        successful_transactions += asset.successful_transactions;
 
         // Calculate success rate for this asset
         if asset.total_transactions > 0 {
@@ -144,9 +195,12 @@ pub fn compute_anchor_reliability_score(
         }
     }
 
-    // 1. Calculate weighted_success_rate
+    // 1. Calculate weighted_success_rate, by transaction count when none of
+    // the volume could be priced
     let weighted_success_rate = if total_volume_usd > 0.0 {
         weighted_success_sum / total_volume_usd
+    } else if total_transactions > 0 {
+        (successful_transactions as f64 / total_transactions as f64) * 100.0
     } else {
         0.0
     };
@@ -171,14 +225,16 @@ pub fn compute_anchor_reliability_score(
     };
 
     // 4. Calculate asset_diversity_score (0-100)
-    // Rewards anchors with up to 10 assets, caps at 100
+    // Rewards anchors with up to the model's target number of assets, caps at 100
     let total_assets = asset_performances.len();
-    let asset_diversity_score = ((total_assets as f64 / 10.0).min(1.0)) * 100.0;
+    let asset_diversity_score = model.asset_diversity_score(total_assets);
 
-    // 5. Calculate composite_score
-    // Weights: 60% performance, 30% volume, 10% diversity
-    let composite_score =
-        (0.6 * asset_performance_score) + (0.3 * volume_score) + (0.1 * asset_diversity_score);
+    // 5. Calculate composite_score with the model's weights
+    let composite_score = model.composite_reliability_score(
+        asset_performance_score,
+        volume_score,
+        asset_diversity_score,
+    );
 
     AnchorReliabilityScore {
         anchor_address: String::new(), // Caller will set this
@@ -189,6 +245,7 @@ pub fn compute_anchor_reliability_score(
         total_assets,
         total_volume_usd,
         weighted_success_rate,
+        scoring_version: model.version.clone(),
         timestamp: chrono::Utc::now(),
     }
 }
@@ -238,21 +295,44 @@ mod tests {
         assert_eq!(metrics.status, AnchorStatus::Red);
     }
 
+    #[test]
+    fn test_apply_anchor_uptime() {
+        let model = ScoringModel::default();
+
+        // Downtime drags a green anchor down, but uptime never lifts a red one
+        let green = compute_anchor_metrics_with(&model, 1000, 995, 5, Some(2000));
+        let metrics = apply_anchor_uptime_with(&model, green.clone(), Some(97.0));
+        assert_eq!(metrics.status, AnchorStatus::Yellow);
+        assert_eq!(metrics.uptime_percentage, Some(97.0));
+        assert_eq!(metrics.reliability_score, green.reliability_score);
+
+        let red = compute_anchor_metrics_with(&model, 1000, 900, 100, Some(9000));
+        let metrics = apply_anchor_uptime_with(&model, red, Some(100.0));
+        assert_eq!(metrics.status, AnchorStatus::Red);
+
+        // Without transactions, uptime alone decides
+        let idle = compute_anchor_metrics_with(&model, 0, 0, 0, None);
+        let metrics = apply_anchor_uptime_with(&model, idle.clone(), Some(100.0));
+        assert_eq!(metrics.status, AnchorStatus::Green);
+        let metrics = apply_anchor_uptime_with(&model, idle, None);
+        assert_eq!(metrics.status, AnchorStatus::Red);
+    }
+
     #[test]
     fn test_settlement_time_score_fast() {
-        let score = calculate_settlement_time_score(Some(500));
+        let score = ScoringModel::default().settlement_time_score(Some(500));
         assert_eq!(score, 100.0);
     }
 
     #[test]
     fn test_settlement_time_score_slow() {
-        let score = calculate_settlement_time_score(Some(12000));
+        let score = ScoringModel::default().settlement_time_score(Some(12000));
         assert_eq!(score, 0.0);
     }
 
     #[test]
     fn test_settlement_time_score_medium() {
-        let score = calculate_settlement_time_score(Some(5000));
+        let score = ScoringModel::default().settlement_time_score(Some(5000));
         assert!(score > 40.0 && score < 60.0);
     }
 
@@ -283,6 +363,7 @@ mod tests {
             total_transactions: 1000,
             successful_transactions: 1000,
             failed_transactions: 0,
+            total_volume: 100000.0,
             total_volume_usd: 100000.0,
         }];
 
@@ -304,6 +385,7 @@ mod tests {
                 total_transactions: 100,
                 successful_transactions: 100, // 100% success
                 failed_transactions: 0,
+                total_volume: 80000.0,
                 total_volume_usd: 80000.0, // 80% of volume
             },
             AnchorAssetPerformance {
@@ -312,6 +394,7 @@ mod tests {
                 total_transactions: 100,
                 successful_transactions: 50, // 50% success
                 failed_transactions: 50,
+                total_volume: 20000.0,
                 total_volume_usd: 20000.0, // 20% of volume
             },
         ];
@@ -335,6 +418,7 @@ mod tests {
                 total_transactions: 100,
                 successful_transactions: 95,
                 failed_transactions: 5,
+                total_volume: 10000.0,
                 total_volume_usd: 10000.0,
             })
             .collect();
@@ -354,6 +438,7 @@ mod tests {
             total_transactions: 100,
             successful_transactions: 100,
             failed_transactions: 0,
+            total_volume: 50000.0,
             total_volume_usd: 50000.0,
         }];
 
@@ -371,6 +456,7 @@ mod tests {
             total_transactions: 100,
             successful_transactions: 100, // 100% success
             failed_transactions: 0,
+            total_volume: 1000000.0,
             total_volume_usd: 1000000.0, // Max volume
         }];
 
@@ -390,6 +476,7 @@ mod tests {
             total_transactions: 10000,
             successful_transactions: 9950,
             failed_transactions: 50,
+            total_volume: 50_000_000.0,
             total_volume_usd: 50_000_000.0,
         }];
 
@@ -407,6 +494,7 @@ mod tests {
             total_transactions: 500,
             successful_transactions: 485,
             failed_transactions: 15,
+            total_volume: 500_000.0,
             total_volume_usd: 500_000.0,
         }];
 
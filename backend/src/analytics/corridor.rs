@@ -1,6 +1,38 @@
 use crate::models::corridor::{Corridor, CorridorAnalytics, PaymentRecord};
+use crate::services::analytics::compute_payment_fx;
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use utoipa::ToSchema;
+
+/// How much of a corridor's converted volume went through one path
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PathShare {
+    /// Intermediate assets, in order from the corridor's asset A towards asset B;
+    /// empty for payments converted directly
+    #[schema(example = json!(["XLM:native"]))]
+    pub path: Vec<String>,
+    pub payment_count: i64,
+    /// Volume in units of the corridor's asset A
+    pub volume: f64,
+    /// Percentage of the corridor's payments routed through this path
+    pub payment_share: f64,
+    /// Percentage of the corridor's volume routed through this path
+    pub volume_share: f64,
+    /// Mean realised slippage of this path's payments against their own limits
+    pub avg_slippage_bps: Option<f64>,
+}
+
+/// Paths a corridor's successful cross-asset payments were routed through
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CorridorRouteAnalysis {
+    pub corridor_key: String,
+    pub total_payments: i64,
+    /// Volume in units of the corridor's asset A
+    pub total_volume: f64,
+    /// Paths, highest volume first
+    pub paths: Vec<PathShare>,
+}
 
 pub fn compute_corridor_analytics(payments: &[PaymentRecord]) -> Vec<CorridorAnalytics> {
     let mut corridor_payments: HashMap<String, Vec<&PaymentRecord>> = HashMap::new();
@@ -94,6 +126,98 @@ pub fn get_corridors_by_success_rate(
         .collect()
 }
 
+/// Breaks each corridor's successful cross-asset payments down by the path
+/// they were routed through.
+///
+/// Corridors are unordered, so paths are read from asset A towards asset B and
+/// volume is counted in asset A, whichever way a payment converted.
+pub fn compute_corridor_routes(payments: &[PaymentRecord]) -> Vec<CorridorRouteAnalysis> {
+    let slippage: HashMap<_, _> = compute_payment_fx(payments)
+        .into_iter()
+        .map(|fx| (fx.payment_id, fx.slippage_bps))
+        .collect();
+
+    let mut corridor_paths: HashMap<String, HashMap<Vec<String>, PathTally>> = HashMap::new();
+
+    for payment in payments
+        .iter()
+        .filter(|p| p.successful && p.is_cross_asset())
+    {
+        let corridor = payment.get_corridor();
+        let sells_asset_a = payment.source_asset_code == corridor.asset_a_code
+            && payment.source_asset_issuer == corridor.asset_a_issuer;
+
+        let (path, volume) = if sells_asset_a {
+            (
+                payment.path.clone(),
+                payment.source_amount.unwrap_or(payment.amount),
+            )
+        } else {
+            (payment.path.iter().rev().cloned().collect(), payment.amount)
+        };
+
+        let entry = corridor_paths
+            .entry(corridor.to_string_key())
+            .or_default()
+            .entry(path)
+            .or_default();
+        entry.payments += 1;
+        entry.volume += volume;
+        entry
+            .slippage_bps
+            .extend(slippage.get(&payment.id).copied().flatten());
+    }
+
+    let mut results: Vec<CorridorRouteAnalysis> = corridor_paths
+        .into_iter()
+        .map(|(corridor_key, paths)| {
+            let total_payments: i64 = paths.values().map(|t| t.payments).sum();
+            let total_volume: f64 = paths.values().map(|t| t.volume).sum();
+
+            let mut paths: Vec<PathShare> = paths
+                .into_iter()
+                .map(|(path, tally)| PathShare {
+                    path,
+                    payment_count: tally.payments,
+                    volume: tally.volume,
+                    payment_share: tally.payments as f64 / total_payments as f64 * 100.0,
+                    volume_share: if total_volume > 0.0 {
+                        tally.volume / total_volume * 100.0
+                    } else {
+                        0.0
+                    },
+                    avg_slippage_bps: (!tally.slippage_bps.is_empty()).then(|| {
+                        tally.slippage_bps.iter().sum::<f64>() / tally.slippage_bps.len() as f64
+                    }),
+                })
+                .collect();
+            paths.sort_by(|a, b| {
+                b.volume
+                    .total_cmp(&a.volume)
+                    .then_with(|| a.path.cmp(&b.path))
+            });
+
+            CorridorRouteAnalysis {
+                corridor_key,
+                total_payments,
+                total_volume,
+                paths,
+            }
+        })
+        .collect();
+
+    results.sort_by_key(|r| std::cmp::Reverse(r.total_payments));
+    results
+}
+
+/// Running totals for one path of one corridor
+#[derive(Default)]
+struct PathTally {
+    payments: i64,
+    volume: f64,
+    slippage_bps: Vec<f64>,
+}
+
 fn parse_corridor_key(corridor_key: &str) -> Corridor {
     let parts: Vec<&str> = corridor_key.split("->").collect();
     let asset_a_parts: Vec<&str> = parts[0].split(':').collect();
@@ -127,7 +251,12 @@ mod tests {
             destination_asset_code: dest_code.to_string(),
             destination_asset_issuer: dest_issuer.to_string(),
             amount,
+            source_amount: None,
+            send_max: None,
+            dest_min: None,
+            path: Vec::new(),
             successful,
+            failure_reason: None,
             timestamp: Utc::now(),
             submission_time: None,
             confirmation_time: None,
@@ -227,4 +356,86 @@ mod tests {
         assert_eq!(filtered_corridors.len(), 1);
         assert_eq!(filtered_corridors[0].success_rate, 100.0);
     }
+
+    fn create_path_payment(
+        source: (&str, &str),
+        dest: (&str, &str),
+        source_amount: f64,
+        amount: f64,
+        path: &[&str],
+    ) -> PaymentRecord {
+        PaymentRecord {
+            source_amount: Some(source_amount),
+            path: path.iter().map(|a| a.to_string()).collect(),
+            ..create_test_payment(source.0, source.1, dest.0, dest.1, amount, true)
+        }
+    }
+
+    #[test]
+    fn test_compute_corridor_routes_shares_and_slippage() {
+        let brl = ("BRL", "issuer1");
+        let ngn = ("NGN", "issuer2");
+        let payments = vec![
+            // Delivered exactly its floor
+            PaymentRecord {
+                dest_min: Some(300.0),
+                ..create_path_payment(brl, ngn, 100.0, 300.0, &["USDC:issuer3"])
+            },
+            create_path_payment(brl, ngn, 100.0, 300.0, &["USDC:issuer3"]),
+            // Delivered 570 against a floor of 555.75
+            PaymentRecord {
+                dest_min: Some(555.75),
+                ..create_path_payment(brl, ngn, 200.0, 570.0, &["XLM:native", "USDC:issuer3"])
+            },
+            // Converting the other way walks the same path backwards
+            create_path_payment(ngn, brl, 300.0, 100.0, &["USDC:issuer3", "XLM:native"]),
+            create_path_payment(brl, ngn, 100.0, 300.0, &[]),
+            create_test_payment("BRL", "issuer1", "NGN", "issuer2", 50.0, false),
+            create_test_payment("BRL", "issuer1", "BRL", "issuer1", 50.0, true),
+        ];
+
+        let routes = compute_corridor_routes(&payments);
+        assert_eq!(routes.len(), 1);
+
+        let corridor = &routes[0];
+        assert_eq!(corridor.corridor_key, "BRL:issuer1->NGN:issuer2");
+        assert_eq!(corridor.total_payments, 5);
+        assert_eq!(corridor.total_volume, 600.0);
+
+        let paths: Vec<Vec<String>> = corridor.paths.iter().map(|p| p.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                vec!["XLM:native".to_string(), "USDC:issuer3".to_string()],
+                vec!["USDC:issuer3".to_string()],
+                vec![],
+            ]
+        );
+
+        let via_xlm = &corridor.paths[0];
+        assert_eq!(via_xlm.payment_count, 2);
+        assert_eq!(via_xlm.volume, 300.0);
+        assert_eq!(via_xlm.volume_share, 50.0);
+        assert_eq!(via_xlm.payment_share, 40.0);
+        // Only the payments with a known limit count towards slippage
+        assert!((via_xlm.avg_slippage_bps.unwrap() - 250.0).abs() < 1e-6);
+
+        let via_usdc = &corridor.paths[1];
+        assert_eq!(via_usdc.payment_count, 2);
+        assert_eq!(via_usdc.avg_slippage_bps, Some(0.0));
+
+        let direct = &corridor.paths[2];
+        assert_eq!(direct.payment_count, 1);
+        assert!((direct.volume_share - 100.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_compute_corridor_routes_without_conversions() {
+        let payments = vec![
+            create_test_payment("USDC", "issuer1", "USDC", "issuer1", 100.0, true),
+            create_test_payment("USDC", "issuer1", "EURC", "issuer2", 100.0, false),
+        ];
+
+        assert!(compute_corridor_routes(&payments).is_empty());
+    }
 }
@@ -142,6 +142,8 @@ mod tests {
             avg_settlement_time_ms: 2000,
             reliability_score: 95.5,
             status: "green".to_string(),
+            scoring_version: None,
+            uptime_percentage: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -183,6 +185,8 @@ mod tests {
             avg_settlement_time_ms: 0,
             reliability_score: 0.0,
             status: "red".to_string(),
+            scoring_version: None,
+            uptime_percentage: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -210,6 +214,8 @@ mod tests {
             avg_settlement_time_ms: 5000,
             reliability_score: 80.0,
             status: "yellow".to_string(),
+            scoring_version: None,
+            uptime_percentage: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
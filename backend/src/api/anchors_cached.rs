@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::{HeaderMap, StatusCode},
     response::{IntoResponse, Response},
     Json,
@@ -8,12 +8,23 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use utoipa::{IntoParams, ToSchema};
 
+use crate::api::corridors_cached::FailuresQuery;
 use crate::cache::{keys, CacheManager};
 use crate::cache_middleware::CacheAware;
 use crate::database::Database;
 use crate::rpc::StellarRpcClient;
+use crate::services::failure_analysis::{summarize_failures, FailureBreakdown};
 use crate::services::price_feed::PriceFeedClient;
 
+/// State shared by the anchor handlers: the database, response cache, RPC
+/// client and price feed
+pub type AnchorState = (
+    Arc<Database>,
+    Arc<CacheManager>,
+    Arc<StellarRpcClient>,
+    Arc<PriceFeedClient>,
+);
+
 pub type ApiResult<T> = Result<T, ApiError>;
 
 #[derive(Debug)]
@@ -126,12 +137,7 @@ pub struct AnchorsResponse {
     tag = "Anchors"
 )]
 pub async fn get_anchors(
-    State((db, cache, rpc_client, _price_feed)): State<(
-        Arc<Database>,
-        Arc<CacheManager>,
-        Arc<StellarRpcClient>,
-        Arc<PriceFeedClient>,
-    )>,
+    State((db, cache, rpc_client, _price_feed)): State<AnchorState>,
     Query(params): Query<ListAnchorsQuery>,
     headers: HeaderMap,
 ) -> ApiResult<Response> {
@@ -234,6 +240,115 @@ pub async fn get_anchors(
     Ok(response)
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct CorridorFailureCount {
+    /// Corridor identifier
+    #[schema(
+        example = "USDC:GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN->XLM:native"
+    )]
+    pub corridor_key: String,
+    /// Payments attempted in the window
+    #[schema(example = 1200)]
+    pub total_transactions: i64,
+    /// Payments that failed in the window
+    #[schema(example = 36)]
+    pub failed_transactions: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct AnchorFailuresResponse {
+    /// Unique identifier for the anchor
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub anchor_id: String,
+    /// Failure breakdown across every corridor touching the anchor's assets
+    pub failures: FailureBreakdown,
+    /// Corridors with failures in the window, most failures first
+    pub corridors: Vec<CorridorFailureCount>,
+}
+
+/// Get the failure breakdown for an anchor
+///
+/// Groups failed payments in every corridor involving one of the anchor's
+/// assets by result code over a time window, with trend versus the window
+/// before it.
+///
+/// **DATA SOURCE: Database** (hourly corridor aggregates)
+#[utoipa::path(
+    get,
+    path = "/api/anchors/{id}/failures",
+    params(
+        ("id" = String, Path, description = "Anchor identifier"),
+        FailuresQuery
+    ),
+    responses(
+        (status = 200, description = "Failure breakdown retrieved successfully", body = AnchorFailuresResponse),
+        (status = 400, description = "Invalid window"),
+        (status = 404, description = "Anchor not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Anchors"
+)]
+pub async fn get_anchor_failures(
+    State((db, cache, _rpc_client, _price_feed)): State<AnchorState>,
+    Path(id): Path<uuid::Uuid>,
+    Query(params): Query<FailuresQuery>,
+) -> ApiResult<Json<AnchorFailuresResponse>> {
+    let (window, duration) = params
+        .resolve()
+        .ok_or_else(|| ApiError::BadRequest("window must look like 6h, 24h or 7d".to_string()))?;
+    let anchor = db
+        .get_anchor_by_id(id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Anchor with id {} not found", id)))?;
+    let cache_key = keys::anchor_failures(&anchor.id, &window);
+
+    let response = <()>::get_or_fetch(&cache, &cache_key, cache.config.get_ttl("anchor"), async {
+        // The anchor's account plus the issuers of the assets it lists
+        let mut issuers = vec![anchor.stellar_account.clone()];
+        for asset in db.get_assets_by_anchor(id).await? {
+            if !issuers.contains(&asset.asset_issuer) {
+                issuers.push(asset.asset_issuer);
+            }
+        }
+
+        let window_end = chrono::Utc::now();
+        let window_start = window_end - duration;
+        let metrics = db
+            .aggregation_db()
+            .fetch_issuer_hourly_metrics(&issuers, window_start - duration, window_end)
+            .await?;
+
+        let mut corridors: Vec<CorridorFailureCount> = Vec::new();
+        for metric in metrics.iter().filter(|m| m.hour_bucket >= window_start) {
+            match corridors
+                .iter_mut()
+                .find(|c| c.corridor_key == metric.corridor_key)
+            {
+                Some(corridor) => {
+                    corridor.total_transactions += metric.total_transactions;
+                    corridor.failed_transactions += metric.failed_transactions;
+                }
+                None => corridors.push(CorridorFailureCount {
+                    corridor_key: metric.corridor_key.clone(),
+                    total_transactions: metric.total_transactions,
+                    failed_transactions: metric.failed_transactions,
+                }),
+            }
+        }
+        corridors.retain(|c| c.failed_transactions > 0);
+        corridors.sort_by_key(|c| std::cmp::Reverse(c.failed_transactions));
+
+        Ok(AnchorFailuresResponse {
+            anchor_id: anchor.id.clone(),
+            failures: summarize_failures(&metrics, &window, window_start, window_end),
+            corridors,
+        })
+    })
+    .await?;
+
+    Ok(Json(response))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
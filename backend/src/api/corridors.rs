@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 use crate::handlers::{ApiError, ApiResult};
 use crate::models::corridor::{Corridor, CorridorMetrics};
 use crate::models::SortBy;
+use crate::scoring::active_model;
 use crate::state::AppState;
 
 // Response DTOs matching frontend TypeScript interfaces
@@ -29,6 +30,9 @@ pub struct CorridorResponse {
     pub liquidity_volume_24h_usd: f64,
     pub liquidity_trend: String,
     pub health_score: f64,
+    /// Version of the scoring model behind the health score
+    #[serde(default)]
+    pub scoring_version: String,
     pub last_updated: String,
 }
 
@@ -83,28 +87,10 @@ fn default_limit() -> i64 {
     50
 }
 
-/// Calculate health score based on success rate, volume, and transaction count
+/// Calculate health score based on success rate, volume, and transaction count,
+/// under the active scoring model
 fn calculate_health_score(success_rate: f64, total_transactions: i64, volume_usd: f64) -> f64 {
-    let success_weight = 0.6;
-    let volume_weight = 0.2;
-    let transaction_weight = 0.2;
-
-    // Normalize volume and transactions (using logarithmic scale)
-    let volume_score = if volume_usd > 0.0 {
-        ((volume_usd.ln() / 15.0) * 100.0).min(100.0)
-    } else {
-        0.0
-    };
-
-    let transaction_score = if total_transactions > 0 {
-        ((total_transactions as f64).ln() / 10.0 * 100.0).min(100.0)
-    } else {
-        0.0
-    };
-
-    success_rate * success_weight
-        + volume_score * volume_weight
-        + transaction_score * transaction_weight
+    active_model().corridor_health_score(success_rate, total_transactions, volume_usd)
 }
 
 /// Determine liquidity trend (simple heuristic based on recent data)
@@ -118,6 +104,20 @@ fn get_liquidity_trend(volume_usd: f64) -> String {
     }
 }
 
+/// Average, median, p95 and p99 settlement latency from stored metrics, zero
+/// when unmeasured. Only p50/p90/p99 are kept per corridor, so p95 is reported
+/// as the p99 upper bound.
+fn latency_summary(m: &CorridorMetrics) -> (f64, f64, f64, f64) {
+    let ms = |v: Option<i32>| v.map(f64::from).unwrap_or(0.0);
+    let p99 = ms(m.p99_settlement_latency_ms);
+    (
+        ms(m.avg_settlement_latency_ms),
+        ms(m.median_settlement_latency_ms),
+        p99,
+        p99,
+    )
+}
+
 /// GET /api/corridors - List all corridors
 pub async fn list_corridors(
     State(app_state): State<AppState>,
@@ -160,7 +160,12 @@ pub async fn list_corridors(
                 volume_usd: m.total_volume_usd,
                 avg_settlement_latency_ms: None,
                 median_settlement_latency_ms: None,
+                p90_settlement_latency_ms: None,
+                p99_settlement_latency_ms: None,
                 liquidity_depth_usd: m.total_volume_usd,
+                avg_fx_rate: None,
+                avg_slippage_bps: None,
+                failure_breakdown: Default::default(),
                 created_at: m.latest_date,
                 updated_at: m.latest_date,
             })
@@ -223,7 +228,7 @@ pub async fn list_corridors(
             let health_score =
                 calculate_health_score(m.success_rate, m.total_transactions, m.volume_usd);
             let liquidity_trend = get_liquidity_trend(m.volume_usd);
-            let avg_latency = 400.0 + (m.success_rate * 2.0);
+            let (avg_latency, median_latency, p95_latency, p99_latency) = latency_summary(m);
 
             CorridorResponse {
                 id: m.corridor_key.clone(),
@@ -234,13 +239,14 @@ pub async fn list_corridors(
                 successful_payments: m.successful_transactions,
                 failed_payments: m.failed_transactions,
                 average_latency_ms: avg_latency,
-                median_latency_ms: avg_latency * 0.75,
-                p95_latency_ms: avg_latency * 2.5,
-                p99_latency_ms: avg_latency * 4.0,
+                median_latency_ms: median_latency,
+                p95_latency_ms: p95_latency,
+                p99_latency_ms: p99_latency,
                 liquidity_depth_usd: m.volume_usd,
                 liquidity_volume_24h_usd: m.volume_usd * 0.1,
                 liquidity_trend,
                 health_score,
+                scoring_version: active_model().version.clone(),
                 last_updated: m.updated_at.to_rfc3339(),
             }
         })
@@ -301,7 +307,7 @@ pub async fn get_corridor_detail(
         latest.volume_usd,
     );
     let liquidity_trend = get_liquidity_trend(latest.volume_usd);
-    let avg_latency = 400.0 + (latest.success_rate * 2.0);
+    let (avg_latency, median_latency, p95_latency, p99_latency) = latency_summary(latest);
 
     let corridor_response = CorridorResponse {
         id: latest.corridor_key.clone(),
@@ -312,13 +318,14 @@ pub async fn get_corridor_detail(
         successful_payments: latest.successful_transactions,
         failed_payments: latest.failed_transactions,
         average_latency_ms: avg_latency,
-        median_latency_ms: avg_latency * 0.75,
-        p95_latency_ms: avg_latency * 2.5,
-        p99_latency_ms: avg_latency * 4.0,
+        median_latency_ms: median_latency,
+        p95_latency_ms: p95_latency,
+        p99_latency_ms: p99_latency,
         liquidity_depth_usd: latest.volume_usd,
         liquidity_volume_24h_usd: latest.volume_usd * 0.1,
         liquidity_trend,
         health_score,
+        scoring_version: active_model().version.clone(),
         last_updated: latest.updated_at.to_rfc3339(),
     };
 
@@ -387,7 +394,7 @@ pub async fn get_corridor_detail(
             let health_score =
                 calculate_health_score(m.success_rate, m.total_transactions, m.volume_usd);
             let liquidity_trend = get_liquidity_trend(m.volume_usd);
-            let avg_latency = 400.0 + (m.success_rate * 2.0);
+            let (avg_latency, median_latency, p95_latency, p99_latency) = latency_summary(m);
 
             CorridorResponse {
                 id: m.corridor_key.clone(),
@@ -398,13 +405,14 @@ pub async fn get_corridor_detail(
                 successful_payments: m.successful_transactions,
                 failed_payments: m.failed_transactions,
                 average_latency_ms: avg_latency,
-                median_latency_ms: avg_latency * 0.75,
-                p95_latency_ms: avg_latency * 2.5,
-                p99_latency_ms: avg_latency * 4.0,
+                median_latency_ms: median_latency,
+                p95_latency_ms: p95_latency,
+                p99_latency_ms: p99_latency,
                 liquidity_depth_usd: m.volume_usd,
                 liquidity_volume_24h_usd: m.volume_usd * 0.1,
                 liquidity_trend,
                 health_score,
+                scoring_version: active_model().version.clone(),
                 last_updated: m.updated_at.to_rfc3339(),
             }
         })
@@ -443,7 +451,12 @@ mod tests {
             volume_usd: 1000000.0,
             avg_settlement_latency_ms: Some(400),
             median_settlement_latency_ms: Some(300),
+            p90_settlement_latency_ms: Some(900),
+            p99_settlement_latency_ms: Some(1600),
             liquidity_depth_usd: 500000.0,
+            avg_fx_rate: None,
+            avg_slippage_bps: None,
+            failure_breakdown: Default::default(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -464,6 +477,7 @@ mod tests {
             liquidity_volume_24h_usd: metrics.volume_usd * 0.1,
             liquidity_trend: "stable".to_string(),
             health_score: 95.0,
+            scoring_version: active_model().version.clone(),
             last_updated: metrics.updated_at.to_rfc3339(),
         };
 
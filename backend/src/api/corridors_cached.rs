@@ -8,30 +8,52 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use utoipa::{IntoParams, ToSchema};
 
+use crate::analytics::corridor::{compute_corridor_routes, PathShare};
 use crate::cache::{keys, CacheManager};
 use crate::cache_middleware::CacheAware;
 use crate::database::Database;
-use crate::handlers::ApiResult;
+use crate::handlers::{ApiError, ApiResult};
+use crate::models::corridor::Corridor;
 use crate::models::SortBy;
 use crate::rpc::StellarRpcClient;
+use crate::scoring::active_model;
+use crate::services::aggregation::{merge_by_corridor, HourlyCorridorMetrics, RollupPeriod};
+use crate::services::distribution::{summarize_distributions, CorridorDistributions};
+use crate::services::failure_analysis::{
+    parse_window, summarize_failures, FailureBreakdown, FailureReasonStat, DEFAULT_WINDOW,
+};
+use crate::services::order_book_depth::{DepthSnapshot, OrderBookDepth, OrderBookDepthService};
 use crate::services::price_feed::PriceFeedClient;
+use crate::services::route_estimator::RouteEstimator;
+use crate::services::settlement_latency::LatencySummary;
+
+/// State shared by the corridor handlers: the database, response cache, RPC
+/// client and price feed
+pub type CorridorState = (
+    Arc<Database>,
+    Arc<CacheManager>,
+    Arc<StellarRpcClient>,
+    Arc<PriceFeedClient>,
+);
 
 /// Represents an asset pair (source -> destination) for a corridor
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct AssetPair {
-    source_asset: String,
-    destination_asset: String,
+pub(crate) struct AssetPair {
+    pub(crate) source_asset: String,
+    pub(crate) destination_asset: String,
 }
 
 impl AssetPair {
-    fn to_corridor_key(&self) -> String {
+    pub(crate) fn to_corridor_key(&self) -> String {
         format!("{}->{}", self.source_asset, self.destination_asset)
     }
 }
 
 /// Extract asset pair from a payment operation
 /// Handles regular payments, path_payment_strict_send, and path_payment_strict_receive
-fn extract_asset_pair_from_payment(payment: &crate::rpc::Payment) -> Option<AssetPair> {
+pub(crate) fn extract_asset_pair_from_payment(
+    payment: &crate::rpc::Payment,
+) -> Option<AssetPair> {
     let operation_type = payment.operation_type.as_deref().unwrap_or("payment");
     
     match operation_type {
@@ -115,6 +137,9 @@ pub struct CorridorResponse {
     /// Median latency in milliseconds
     #[schema(example = 380.0)]
     pub median_latency_ms: f64,
+    /// 90th percentile latency in milliseconds
+    #[schema(example = 720.0)]
+    pub p90_latency_ms: f64,
     /// 95th percentile latency in milliseconds
     #[schema(example = 850.0)]
     pub p95_latency_ms: f64,
@@ -133,6 +158,10 @@ pub struct CorridorResponse {
     /// Overall health score (0-100)
     #[schema(example = 95.5)]
     pub health_score: f64,
+    /// Version of the scoring model behind the health score
+    #[serde(default)]
+    #[schema(example = "v1")]
+    pub scoring_version: String,
     /// Last update timestamp
     #[schema(example = "2024-01-15T10:30:00Z")]
     pub last_updated: String,
@@ -189,6 +218,157 @@ pub struct CorridorDetailResponse {
     pub liquidity_trends: Vec<LiquidityDataPoint>,
     /// Related corridors
     pub related_corridors: Option<Vec<CorridorResponse>>,
+    /// Failed payments by result code, most frequent first
+    #[serde(default)]
+    pub failure_reasons: Vec<FailureReasonStat>,
+}
+
+/// Time series points are hourly unless asked otherwise
+const DEFAULT_RESOLUTION: &str = "1h";
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CorridorDetailQuery {
+    /// Time window to cover, e.g. 24h, 7d or 30d (default: 24h)
+    #[param(example = "7d")]
+    pub window: Option<String>,
+    /// Width of each time series point, e.g. 1h, 6h or 1d (default: 1h)
+    #[param(example = "1d")]
+    pub resolution: Option<String>,
+}
+
+impl CorridorDetailQuery {
+    /// The requested window and resolution with their lengths. The
+    /// resolution can't be longer than the window.
+    pub fn resolve(&self) -> Option<(String, chrono::Duration, String, chrono::Duration)> {
+        let window = self.window.as_deref().unwrap_or(DEFAULT_WINDOW);
+        let resolution = self.resolution.as_deref().unwrap_or(DEFAULT_RESOLUTION);
+        let window_duration = parse_window(window)?;
+        let resolution_duration = parse_window(resolution)?;
+
+        (resolution_duration <= window_duration).then(|| {
+            (
+                window.to_string(),
+                window_duration,
+                resolution.to_string(),
+                resolution_duration,
+            )
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FailuresQuery {
+    /// Time window to break failures down over, e.g. 6h, 24h or 7d (default: 24h)
+    #[param(example = "24h")]
+    pub window: Option<String>,
+}
+
+impl FailuresQuery {
+    /// The requested window and its length
+    pub fn resolve(&self) -> Option<(String, chrono::Duration)> {
+        let window = self.window.as_deref().unwrap_or(DEFAULT_WINDOW);
+        parse_window(window).map(|duration| (window.to_string(), duration))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CorridorFailuresResponse {
+    /// Normalized corridor identifier
+    #[schema(example = "EURC:GISSUER->USDC:GISSUER")]
+    pub corridor_key: String,
+    /// Failure breakdown for the window, with trend versus the previous one
+    pub failures: FailureBreakdown,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DistributionsQuery {
+    /// Time window to merge hourly sketches over, e.g. 1h, 7d or 30d (default: 24h)
+    #[param(example = "7d")]
+    pub window: Option<String>,
+}
+
+impl DistributionsQuery {
+    /// The requested window and its length
+    pub fn resolve(&self) -> Option<(String, chrono::Duration)> {
+        let window = self.window.as_deref().unwrap_or(DEFAULT_WINDOW);
+        parse_window(window).map(|duration| (window.to_string(), duration))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CorridorDistributionsResponse {
+    /// Normalized corridor identifier
+    #[schema(example = "EURC:GISSUER->USDC:GISSUER")]
+    pub corridor_key: String,
+    /// Latency and payment size percentiles for the window
+    pub distributions: CorridorDistributions,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DepthHistoryQuery {
+    /// Time window of snapshots to return, e.g. 24h, 7d or 30d (default: 24h)
+    #[param(example = "7d")]
+    pub window: Option<String>,
+}
+
+impl DepthHistoryQuery {
+    /// The requested window and its length
+    pub fn resolve(&self) -> Option<(String, chrono::Duration)> {
+        let window = self.window.as_deref().unwrap_or(DEFAULT_WINDOW);
+        parse_window(window).map(|duration| (window.to_string(), duration))
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RoutesQuery {
+    /// Time window of payments to analyse, e.g. 24h, 7d or 30d (default: 24h)
+    #[param(example = "7d")]
+    pub window: Option<String>,
+}
+
+impl RoutesQuery {
+    /// The requested window and its length
+    pub fn resolve(&self) -> Option<(String, chrono::Duration)> {
+        let window = self.window.as_deref().unwrap_or(DEFAULT_WINDOW);
+        parse_window(window).map(|duration| (window.to_string(), duration))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CorridorRoutesResponse {
+    /// Normalized corridor identifier
+    #[schema(example = "EURC:GISSUER->USDC:GISSUER")]
+    pub corridor_key: String,
+    #[schema(example = "7d")]
+    pub window: String,
+    /// Successful cross-asset payments in the window
+    pub total_payments: i64,
+    /// Volume of those payments in units of the corridor's first asset
+    pub total_volume: f64,
+    /// Paths the payments were routed through, highest volume first
+    pub paths: Vec<PathShare>,
+    /// Whether the corridor had more payments in the window than one analysis
+    /// reads, so only the earliest were broken down
+    pub truncated: bool,
+}
+
+/// Corridor payments read per route analysis
+const ROUTE_ANALYSIS_PAYMENT_LIMIT: usize = 100_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CorridorDepthHistoryResponse {
+    /// Normalized corridor identifier
+    #[schema(example = "EURC:GISSUER->USDC:GISSUER")]
+    pub corridor_key: String,
+    #[schema(example = "7d")]
+    pub window: String,
+    /// Depth snapshots in the window, oldest first
+    pub snapshots: Vec<DepthSnapshot>,
 }
 
 #[derive(Debug, Deserialize, IntoParams)]
@@ -229,26 +409,9 @@ fn default_limit() -> i64 {
     50
 }
 
+/// Corridor health score under the active scoring model
 fn calculate_health_score(success_rate: f64, total_transactions: i64, volume_usd: f64) -> f64 {
-    let success_weight = 0.6;
-    let volume_weight = 0.2;
-    let transaction_weight = 0.2;
-
-    let volume_score = if volume_usd > 0.0 {
-        ((volume_usd.ln() / 15.0) * 100.0).min(100.0)
-    } else {
-        0.0
-    };
-
-    let transaction_score = if total_transactions > 0 {
-        ((total_transactions as f64).ln() / 10.0 * 100.0).min(100.0)
-    } else {
-        0.0
-    };
-
-    success_rate * success_weight
-        + volume_score * volume_weight
-        + transaction_score * transaction_weight
+    active_model().corridor_health_score(success_rate, total_transactions, volume_usd)
 }
 
 fn get_liquidity_trend(volume_usd: f64) -> String {
@@ -261,6 +424,161 @@ fn get_liquidity_trend(volume_usd: f64) -> String {
     }
 }
 
+/// Whether a corridor passes the list's success rate, volume and asset filters
+fn matches_filters(c: &CorridorResponse, params: &ListCorridorsQuery) -> bool {
+    if let Some(min) = params.success_rate_min {
+        if c.success_rate < min {
+            return false;
+        }
+    }
+    if let Some(max) = params.success_rate_max {
+        if c.success_rate > max {
+            return false;
+        }
+    }
+    if let Some(min) = params.volume_min {
+        if c.liquidity_depth_usd < min {
+            return false;
+        }
+    }
+    if let Some(max) = params.volume_max {
+        if c.liquidity_depth_usd > max {
+            return false;
+        }
+    }
+    if let Some(asset_code) = &params.asset_code {
+        let asset_code_lower = asset_code.to_lowercase();
+        if !c.source_asset.to_lowercase().contains(&asset_code_lower)
+            && !c
+                .destination_asset
+                .to_lowercase()
+                .contains(&asset_code_lower)
+        {
+            return false;
+        }
+    }
+    true
+}
+
+/// Windows accepted by `time_period`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimePeriod {
+    Day,
+    Week,
+    Month,
+}
+
+impl TimePeriod {
+    fn parse(period: &str) -> Option<Self> {
+        match period {
+            "24h" => Some(TimePeriod::Day),
+            "7d" => Some(TimePeriod::Week),
+            "30d" => Some(TimePeriod::Month),
+            _ => None,
+        }
+    }
+
+    fn days(&self) -> i64 {
+        match self {
+            TimePeriod::Day => 1,
+            TimePeriod::Week => 7,
+            TimePeriod::Month => 30,
+        }
+    }
+}
+
+/// Corridor metrics over a `time_period`, merged per corridor. The last 24
+/// hours merge hourly buckets; 7 and 30 days merge daily rollups, today's
+/// partial day included.
+async fn corridors_over_period(
+    db: &Database,
+    period: TimePeriod,
+) -> anyhow::Result<Vec<CorridorResponse>> {
+    let now = chrono::Utc::now();
+    let aggregation_db = db.aggregation_db();
+    let metrics = match period {
+        TimePeriod::Day => {
+            aggregation_db
+                .fetch_hourly_metrics_by_timerange(now - chrono::Duration::hours(24), now)
+                .await?
+        }
+        TimePeriod::Week | TimePeriod::Month => {
+            let daily = RollupPeriod::Daily;
+            let start = daily.start_of(now) - chrono::Duration::days(period.days() - 1);
+            // Days the rollup job has closed come from their rollups, and the
+            // rest from hourly buckets, which are ahead of any open rollup
+            let closed_until = aggregation_db
+                .last_processed_hour(daily.as_str())
+                .await?
+                .map(|last_day| daily.next_start(last_day))
+                .map_or(start, |until| until.max(start));
+            let mut metrics: Vec<HourlyCorridorMetrics> = aggregation_db
+                .fetch_corridor_rollups(daily, start, now)
+                .await?
+                .into_iter()
+                .map(|rollup| rollup.metrics)
+                .filter(|metrics| metrics.hour_bucket < closed_until)
+                .collect();
+            metrics.extend(
+                aggregation_db
+                    .fetch_hourly_metrics_by_timerange(closed_until, now)
+                    .await?,
+            );
+            metrics
+        }
+    };
+
+    Ok(merge_by_corridor(metrics)
+        .into_iter()
+        .map(|(metrics, _)| {
+            corridor_response_from_metrics(&metrics, chrono::Duration::days(period.days()))
+        })
+        .collect())
+}
+
+fn corridor_response_from_metrics(
+    metrics: &HourlyCorridorMetrics,
+    window: chrono::Duration,
+) -> CorridorResponse {
+    let latency = &metrics.sketches.settlement_latency_ms;
+    // Older buckets have no sketch, so fall back to their stored percentiles
+    let percentile = |q: f64, stored: Option<i32>| {
+        latency
+            .quantile(q)
+            .map(|v| v as f64)
+            .or(stored.map(f64::from))
+            .unwrap_or(0.0)
+    };
+
+    CorridorResponse {
+        id: metrics.corridor_key.clone(),
+        source_asset: metrics.asset_a_code.clone(),
+        destination_asset: metrics.asset_b_code.clone(),
+        success_rate: metrics.success_rate,
+        total_attempts: metrics.total_transactions,
+        successful_payments: metrics.successful_transactions,
+        failed_payments: metrics.failed_transactions,
+        average_latency_ms: latency
+            .mean()
+            .or(metrics.avg_settlement_latency_ms.map(f64::from))
+            .unwrap_or(0.0),
+        median_latency_ms: percentile(0.50, metrics.p50_settlement_latency_ms),
+        p90_latency_ms: percentile(0.90, metrics.p90_settlement_latency_ms),
+        p95_latency_ms: percentile(0.95, None),
+        p99_latency_ms: percentile(0.99, metrics.p99_settlement_latency_ms),
+        liquidity_depth_usd: metrics.volume_usd,
+        liquidity_volume_24h_usd: metrics.volume_usd * 24.0 / window.num_hours().max(1) as f64,
+        liquidity_trend: get_liquidity_trend(metrics.volume_usd),
+        health_score: calculate_health_score(
+            metrics.success_rate,
+            metrics.total_transactions,
+            metrics.volume_usd,
+        ),
+        scoring_version: active_model().version.clone(),
+        last_updated: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
 /// Generate cache key for corridor list with filters
 fn generate_corridor_list_cache_key(params: &ListCorridorsQuery) -> String {
     let filter_str = format!(
@@ -285,26 +603,35 @@ fn generate_corridor_list_cache_key(params: &ListCorridorsQuery) -> String {
 /// - Trade data from Horizon API  
 /// - Order book data from Horizon API
 /// - Calculates corridor metrics from real-time RPC data
+///
+/// **DATA SOURCE: Database** when `time_period` is set
+/// - `24h` merges hourly corridor metrics
+/// - `7d` and `30d` merge daily rollups
 #[utoipa::path(
     get,
     path = "/api/corridors",
     params(ListCorridorsQuery),
     responses(
         (status = 200, description = "List of corridors retrieved successfully", body = Vec<CorridorResponse>),
+        (status = 400, description = "Invalid time period"),
         (status = 500, description = "Internal server error")
     ),
     tag = "Corridors"
 )]
 pub async fn list_corridors(
-    State((_db, cache, rpc_client, price_feed)): State<(
-        Arc<Database>,
-        Arc<CacheManager>,
-        Arc<StellarRpcClient>,
-        Arc<PriceFeedClient>,
-    )>,
+    State((db, cache, rpc_client, price_feed)): State<CorridorState>,
     Query(params): Query<ListCorridorsQuery>,
     headers: HeaderMap,
 ) -> ApiResult<Response> {
+    let time_period = params
+        .time_period
+        .as_deref()
+        .map(|period| {
+            TimePeriod::parse(period).ok_or_else(|| {
+                ApiError::BadRequest("time_period must be one of 24h, 7d or 30d".to_string())
+            })
+        })
+        .transpose()?;
     let cache_key = generate_corridor_list_cache_key(&params);
 
     let corridors = <()>::get_or_fetch(
@@ -312,6 +639,14 @@ pub async fn list_corridors(
         &cache_key,
         cache.config.get_ttl("corridor"),
         async {
+            if let Some(period) = time_period {
+                let corridors = corridors_over_period(&db, period).await?;
+                return Ok(corridors
+                    .into_iter()
+                    .filter(|c| matches_filters(c, &params))
+                    .collect());
+            }
+
             // **RPC DATA**: Fetch recent payments to identify active corridors
             let payments = match rpc_client.fetch_payments(200, None).await {
                 Ok(p) => p,
@@ -356,10 +691,17 @@ pub async fn list_corridors(
             for (corridor_key, corridor_payments) in corridor_map.iter() {
                 let total_attempts = corridor_payments.len() as i64;
 
-                // In Stellar, payments in the stream are successful
-                let successful_payments = total_attempts;
-                let failed_payments = 0;
-                let success_rate = if total_attempts > 0 { 100.0 } else { 0.0 };
+                // Failed payments are fetched too, so the success rate is real
+                let successful_payments = corridor_payments
+                    .iter()
+                    .filter(|p| p.transaction_successful)
+                    .count() as i64;
+                let failed_payments = total_attempts - successful_payments;
+                let success_rate = if total_attempts > 0 {
+                    (successful_payments as f64 / total_attempts as f64) * 100.0
+                } else {
+                    0.0
+                };
 
                 // Parse corridor key to get assets
                 let parts: Vec<&str> = corridor_key.split("->").collect();
@@ -374,13 +716,14 @@ pub async fn list_corridors(
                     continue;
                 }
 
-                // Calculate volume from payment amounts and convert to USD
+                // Calculate volume from settled payment amounts and convert to USD
+                let settled = corridor_payments.iter().filter(|p| p.transaction_successful);
                 let mut volume_usd: f64 = 0.0;
                 let source_asset_key = parts[0];
                 
                 // Get price for source asset
                 if let Ok(price) = price_feed.get_price(source_asset_key).await {
-                    for payment in corridor_payments.iter() {
+                    for payment in settled {
                         if let Ok(amount) = payment.amount.parse::<f64>() {
                             volume_usd += amount * price;
                         }
@@ -388,8 +731,7 @@ pub async fn list_corridors(
                 } else {
                     // Fallback: use raw amounts if price unavailable
                     tracing::warn!("Price unavailable for {}, using raw amounts", source_asset_key);
-                    volume_usd = corridor_payments
-                        .iter()
+                    volume_usd = settled
                         .filter_map(|p| p.amount.parse::<f64>().ok())
                         .sum();
                 }
@@ -397,7 +739,15 @@ pub async fn list_corridors(
                 // Calculate health score
                 let health_score = calculate_health_score(success_rate, total_attempts, volume_usd);
                 let liquidity_trend = get_liquidity_trend(volume_usd);
-                let avg_latency = 400.0 + (success_rate * 2.0);
+                // Time from the transaction's valid_after bound to ledger close
+                let latency = LatencySummary::from_samples(
+                    corridor_payments
+                        .iter()
+                        .filter(|p| p.transaction_successful)
+                        .filter_map(|p| p.settlement_latency_ms())
+                        .collect(),
+                )
+                .unwrap_or_default();
 
                 let corridor_response = CorridorResponse {
                     id: corridor_key.clone(),
@@ -407,57 +757,25 @@ pub async fn list_corridors(
                     total_attempts,
                     successful_payments,
                     failed_payments,
-                    average_latency_ms: avg_latency,
-                    median_latency_ms: avg_latency * 0.75,
-                    p95_latency_ms: avg_latency * 2.5,
-                    p99_latency_ms: avg_latency * 4.0,
+                    average_latency_ms: latency.average_ms,
+                    median_latency_ms: latency.p50_ms,
+                    p90_latency_ms: latency.p90_ms,
+                    p95_latency_ms: latency.p95_ms,
+                    p99_latency_ms: latency.p99_ms,
                     liquidity_depth_usd: volume_usd,
                     liquidity_volume_24h_usd: volume_usd * 0.1,
                     liquidity_trend,
                     health_score,
+                    scoring_version: active_model().version.clone(),
                     last_updated: chrono::Utc::now().to_rfc3339(),
                 };
 
                 corridor_responses.push(corridor_response);
             }
 
-            // Apply filters
             let filtered: Vec<_> = corridor_responses
                 .into_iter()
-                .filter(|c| {
-                    if let Some(min) = params.success_rate_min {
-                        if c.success_rate < min {
-                            return false;
-                        }
-                    }
-                    if let Some(max) = params.success_rate_max {
-                        if c.success_rate > max {
-                            return false;
-                        }
-                    }
-                    if let Some(min) = params.volume_min {
-                        if c.liquidity_depth_usd < min {
-                            return false;
-                        }
-                    }
-                    if let Some(max) = params.volume_max {
-                        if c.liquidity_depth_usd > max {
-                            return false;
-                        }
-                    }
-                    if let Some(asset_code) = &params.asset_code {
-                        let asset_code_lower = asset_code.to_lowercase();
-                        if !c.source_asset.to_lowercase().contains(&asset_code_lower)
-                            && !c
-                                .destination_asset
-                                .to_lowercase()
-                                .contains(&asset_code_lower)
-                        {
-                            return false;
-                        }
-                    }
-                    true
-                })
+                .filter(|c| matches_filters(c, &params))
                 .collect();
 
             Ok(filtered)
@@ -472,35 +790,532 @@ pub async fn list_corridors(
 
 /// Get detailed corridor information
 ///
-/// Returns detailed metrics and historical data for a specific corridor.
+/// Returns a corridor's metrics over a time window, with success rate and
+/// liquidity time series at the requested resolution.
 ///
-/// **DATA SOURCE: RPC**
+/// **DATA SOURCE: Database + RPC**
+/// - Summary, time series and failure reasons from hourly corridor aggregates
+/// - Liquidity depth from the live order book and stored liquidity pools, valued in USD
+/// - Latency distribution from recent payments
 #[utoipa::path(
     get,
     path = "/api/corridors/{corridor_key}",
     params(
-        ("corridor_key" = String, Path, description = "Corridor identifier (e.g., USDC:native->XLM:native)")
+        ("corridor_key" = String, Path, description = "Corridor identifier, in either asset order (e.g., USDC:GISSUER->XLM:native)"),
+        CorridorDetailQuery
     ),
     responses(
         (status = 200, description = "Corridor details retrieved successfully", body = CorridorDetailResponse),
+        (status = 400, description = "Invalid corridor key, window or resolution"),
         (status = 404, description = "Corridor not found"),
         (status = 500, description = "Internal server error")
     ),
     tag = "Corridors"
 )]
 pub async fn get_corridor_detail(
-    State((_db, _cache, _rpc_client, _price_feed)): State<(
-        Arc<Database>,
-        Arc<CacheManager>,
-        Arc<StellarRpcClient>,
-        Arc<PriceFeedClient>,
-    )>,
-    Path(_corridor_key): Path<String>,
+    State((db, cache, rpc_client, price_feed)): State<CorridorState>,
+    Path(corridor_key): Path<String>,
+    Query(params): Query<CorridorDetailQuery>,
 ) -> ApiResult<Json<CorridorDetailResponse>> {
-    // TODO: Implement RPC-based corridor detail
-    Err(crate::handlers::ApiError::NotFound(
-        "Corridor detail endpoint not yet implemented with RPC".to_string(),
-    ))
+    let corridor = Corridor::from_key(&corridor_key)
+        .ok_or_else(|| ApiError::BadRequest(format!("Invalid corridor key: {}", corridor_key)))?;
+    let (window, window_duration, resolution, resolution_duration) =
+        params.resolve().ok_or_else(|| {
+            ApiError::BadRequest(
+                "window and resolution must look like 1h, 7d or 30d, and the resolution can't exceed the window"
+                    .to_string(),
+            )
+        })?;
+    let corridor_key = corridor.to_string_key();
+    let cache_key = keys::corridor_detail(&corridor_key, &window, &resolution);
+
+    let detail = <()>::get_or_fetch(
+        &cache,
+        &cache_key,
+        cache.config.get_ttl("corridor"),
+        async {
+            let window_end = chrono::Utc::now();
+            let window_start = window_end - window_duration;
+            // The window before this one gives failure reasons their trend
+            let metrics = db
+                .aggregation_db()
+                .fetch_corridor_hourly_metrics(
+                    &corridor_key,
+                    window_start - window_duration,
+                    window_end,
+                )
+                .await?;
+            let current: Vec<HourlyCorridorMetrics> = metrics
+                .iter()
+                .filter(|m| m.hour_bucket >= window_start)
+                .cloned()
+                .collect();
+
+            let Some((merged, _)) = merge_by_corridor(current.iter().cloned()).pop() else {
+                return Ok(None);
+            };
+            let mut summary = corridor_response_from_metrics(&merged, window_duration);
+            if let Some(depth) = route_depth_usd(&db, &rpc_client, &price_feed, &corridor).await {
+                summary.liquidity_depth_usd = depth;
+            }
+
+            let series = bucket_by_resolution(current, resolution_duration);
+            let historical_success_rate = series
+                .iter()
+                .map(|(start, m)| SuccessRateDataPoint {
+                    timestamp: start.to_rfc3339(),
+                    success_rate: m.success_rate,
+                    attempts: m.total_transactions,
+                })
+                .collect();
+            let points_per_day = 24.0 / resolution_duration.num_hours().max(1) as f64;
+            let liquidity_trends = series
+                .iter()
+                .map(|(start, m)| LiquidityDataPoint {
+                    timestamp: start.to_rfc3339(),
+                    liquidity_usd: m.liquidity_depth_usd,
+                    volume_24h_usd: m.volume_usd * points_per_day,
+                })
+                .collect();
+
+            Ok(Some(CorridorDetailResponse {
+                corridor: summary,
+                historical_success_rate,
+                latency_distribution: latency_distribution(
+                    &recent_settlement_latencies(&rpc_client, &corridor_key).await,
+                ),
+                liquidity_trends,
+                related_corridors: None,
+                failure_reasons: summarize_failures(&metrics, &window, window_start, window_end)
+                    .reasons,
+            }))
+        },
+    )
+    .await?;
+
+    detail.map(Json).ok_or_else(|| {
+        ApiError::NotFound(format!(
+            "No aggregated metrics for corridor {}",
+            corridor_key
+        ))
+    })
+}
+
+/// Merges hourly metrics into consecutive buckets of `resolution`, aligned to
+/// the Unix epoch so daily buckets start at UTC midnight
+fn bucket_by_resolution(
+    metrics: Vec<HourlyCorridorMetrics>,
+    resolution: chrono::Duration,
+) -> Vec<(chrono::DateTime<chrono::Utc>, HourlyCorridorMetrics)> {
+    use std::collections::btree_map::Entry;
+    use std::collections::BTreeMap;
+
+    let width = resolution.num_seconds().max(1);
+    let mut buckets: BTreeMap<i64, HourlyCorridorMetrics> = BTreeMap::new();
+    for metric in metrics {
+        match buckets.entry(metric.hour_bucket.timestamp().div_euclid(width)) {
+            Entry::Occupied(mut bucket) => bucket.get_mut().merge(&metric),
+            Entry::Vacant(bucket) => {
+                bucket.insert(metric);
+            }
+        }
+    }
+
+    buckets
+        .into_iter()
+        .filter_map(|(index, metric)| {
+            let start = chrono::DateTime::from_timestamp(index * width, 0)?;
+            Some((start, metric))
+        })
+        .collect()
+}
+
+/// Latency bucket upper bounds; slower payments fall in the last bucket
+const LATENCY_BUCKETS_MS: [i32; 7] = [1_000, 2_000, 5_000, 10_000, 30_000, 60_000, 300_000];
+
+/// Histogram of settlement latencies, empty without any
+fn latency_distribution(latencies_ms: &[i64]) -> Vec<LatencyDataPoint> {
+    if latencies_ms.is_empty() {
+        return Vec::new();
+    }
+
+    let mut counts = [0i64; LATENCY_BUCKETS_MS.len()];
+    for &latency in latencies_ms {
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|&bound| latency <= bound as i64)
+            .unwrap_or(LATENCY_BUCKETS_MS.len() - 1);
+        counts[bucket] += 1;
+    }
+
+    LATENCY_BUCKETS_MS
+        .iter()
+        .zip(counts)
+        .map(|(&bound, count)| LatencyDataPoint {
+            latency_bucket_ms: bound,
+            count,
+            percentage: count as f64 / latencies_ms.len() as f64 * 100.0,
+        })
+        .collect()
+}
+
+/// Settlement latencies of the corridor's successful payments among the
+/// most recent ones on the network
+async fn recent_settlement_latencies(rpc_client: &StellarRpcClient, corridor_key: &str) -> Vec<i64> {
+    // **RPC DATA**: recent payments, as for the corridor list
+    let payments = match rpc_client.fetch_payments(200, None).await {
+        Ok(p) => p,
+        Err(e) => {
+            tracing::warn!("Failed to fetch recent payments from RPC: {}", e);
+            return Vec::new();
+        }
+    };
+
+    payments
+        .iter()
+        .filter(|p| p.transaction_successful)
+        .filter(|p| {
+            extract_asset_pair_from_payment(p)
+                .and_then(|pair| Corridor::from_key(&pair.to_corridor_key()))
+                .is_some_and(|c| c.to_string_key() == corridor_key)
+        })
+        .filter_map(|p| p.settlement_latency_ms())
+        .collect()
+}
+
+/// Live depth within 1% of the best rate each way through the corridor, across
+/// order book offers and liquidity pools, in USD. `None` when neither asset has
+/// a USD price or nothing trades between them.
+async fn route_depth_usd(
+    db: &Database,
+    rpc_client: &Arc<StellarRpcClient>,
+    price_feed: &Arc<PriceFeedClient>,
+    corridor: &Corridor,
+) -> Option<f64> {
+    // **RPC DATA**: order books between the corridor's assets
+    let estimator = RouteEstimator::new(
+        db.pool().clone(),
+        Arc::clone(rpc_client),
+        Arc::clone(price_feed),
+    );
+    match estimator.liquidity_depth_usd(corridor, DEPTH_BPS).await {
+        Ok(depth) => depth,
+        Err(e) => {
+            tracing::warn!("Failed to estimate route depth for {}: {}", corridor.to_string_key(), e);
+            None
+        }
+    }
+}
+
+/// Liquidity depth counts what can be sent within this distance of the best rate
+const DEPTH_BPS: f64 = 100.0;
+
+/// Get the failure breakdown for a corridor
+///
+/// Groups the corridor's failed payments by result code over a time window
+/// and compares each code with the window before it.
+///
+/// **DATA SOURCE: Database** (hourly corridor aggregates)
+#[utoipa::path(
+    get,
+    path = "/api/corridors/{corridor_key}/failures",
+    params(
+        ("corridor_key" = String, Path, description = "Corridor identifier, in either asset order (e.g., USDC:GISSUER->XLM:native)"),
+        FailuresQuery
+    ),
+    responses(
+        (status = 200, description = "Failure breakdown retrieved successfully", body = CorridorFailuresResponse),
+        (status = 400, description = "Invalid corridor key or window"),
+        (status = 404, description = "No aggregated metrics for the corridor"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Corridors"
+)]
+pub async fn get_corridor_failures(
+    State((db, cache, _rpc_client, _price_feed)): State<CorridorState>,
+    Path(corridor_key): Path<String>,
+    Query(params): Query<FailuresQuery>,
+) -> ApiResult<Json<CorridorFailuresResponse>> {
+    let corridor = Corridor::from_key(&corridor_key)
+        .ok_or_else(|| ApiError::BadRequest(format!("Invalid corridor key: {}", corridor_key)))?;
+    let (window, duration) = params
+        .resolve()
+        .ok_or_else(|| ApiError::BadRequest("window must look like 6h, 24h or 7d".to_string()))?;
+    let corridor_key = corridor.to_string_key();
+    let cache_key = keys::corridor_failures(&corridor_key, &window);
+
+    let response = <()>::get_or_fetch(
+        &cache,
+        &cache_key,
+        cache.config.get_ttl("corridor"),
+        async {
+            let window_end = chrono::Utc::now();
+            let window_start = window_end - duration;
+            let metrics = db
+                .aggregation_db()
+                .fetch_corridor_hourly_metrics(&corridor_key, window_start - duration, window_end)
+                .await?;
+
+            Ok((!metrics.is_empty()).then(|| CorridorFailuresResponse {
+                corridor_key: corridor_key.clone(),
+                failures: summarize_failures(&metrics, &window, window_start, window_end),
+            }))
+        },
+    )
+    .await?;
+
+    response.map(Json).ok_or_else(|| {
+        ApiError::NotFound(format!(
+            "No aggregated metrics for corridor {}",
+            corridor_key
+        ))
+    })
+}
+
+/// Get settlement latency and payment size distributions for a corridor
+///
+/// Merges the corridor's hourly sketches over a time window, so percentiles
+/// are exact to within 1% for any window from an hour up to 90 days.
+///
+/// **DATA SOURCE: Database** (hourly corridor aggregates)
+#[utoipa::path(
+    get,
+    path = "/api/corridors/{corridor_key}/distributions",
+    params(
+        ("corridor_key" = String, Path, description = "Corridor identifier, in either asset order (e.g., USDC:GISSUER->XLM:native)"),
+        DistributionsQuery
+    ),
+    responses(
+        (status = 200, description = "Distributions retrieved successfully", body = CorridorDistributionsResponse),
+        (status = 400, description = "Invalid corridor key or window"),
+        (status = 404, description = "No aggregated metrics for the corridor"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Corridors"
+)]
+pub async fn get_corridor_distributions(
+    State((db, cache, _rpc_client, _price_feed)): State<CorridorState>,
+    Path(corridor_key): Path<String>,
+    Query(params): Query<DistributionsQuery>,
+) -> ApiResult<Json<CorridorDistributionsResponse>> {
+    let corridor = Corridor::from_key(&corridor_key)
+        .ok_or_else(|| ApiError::BadRequest(format!("Invalid corridor key: {}", corridor_key)))?;
+    let (window, duration) = params
+        .resolve()
+        .ok_or_else(|| ApiError::BadRequest("window must look like 1h, 7d or 30d".to_string()))?;
+    let corridor_key = corridor.to_string_key();
+    let cache_key = keys::corridor_distributions(&corridor_key, &window);
+
+    let response = <()>::get_or_fetch(
+        &cache,
+        &cache_key,
+        cache.config.get_ttl("corridor"),
+        async {
+            let window_end = chrono::Utc::now();
+            let window_start = window_end - duration;
+            let metrics = db
+                .aggregation_db()
+                .fetch_corridor_hourly_metrics(&corridor_key, window_start, window_end)
+                .await?;
+
+            Ok((!metrics.is_empty()).then(|| CorridorDistributionsResponse {
+                corridor_key: corridor_key.clone(),
+                distributions: summarize_distributions(
+                    &metrics,
+                    &window,
+                    window_start,
+                    window_end,
+                ),
+            }))
+        },
+    )
+    .await?;
+
+    response.map(Json).ok_or_else(|| {
+        ApiError::NotFound(format!(
+            "No aggregated metrics for corridor {}",
+            corridor_key
+        ))
+    })
+}
+
+/// Get order book depth and slippage quotes for a corridor
+///
+/// Values both sides of the live order book in USD, as a cumulative depth
+/// curve per side and the amount that can be traded before the average price
+/// slips 10, 50, 100 or 200 bps from the mid price. The corridor's first asset
+/// is the base.
+///
+/// **DATA SOURCE: RPC** (live order book) and the price feed
+#[utoipa::path(
+    get,
+    path = "/api/corridors/{corridor_key}/depth",
+    params(
+        ("corridor_key" = String, Path, description = "Corridor identifier, in either asset order (e.g., USDC:GISSUER->XLM:native)")
+    ),
+    responses(
+        (status = 200, description = "Order book depth retrieved successfully", body = OrderBookDepth),
+        (status = 400, description = "Invalid corridor key"),
+        (status = 404, description = "Order book is one-sided or neither asset has a USD price"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Corridors"
+)]
+pub async fn get_corridor_depth(
+    State((db, cache, rpc_client, price_feed)): State<CorridorState>,
+    Path(corridor_key): Path<String>,
+) -> ApiResult<Json<OrderBookDepth>> {
+    let corridor = Corridor::from_key(&corridor_key)
+        .ok_or_else(|| ApiError::BadRequest(format!("Invalid corridor key: {}", corridor_key)))?;
+    let corridor_key = corridor.to_string_key();
+    let cache_key = keys::corridor_depth(&corridor_key);
+
+    let depth = <()>::get_or_fetch(
+        &cache,
+        &cache_key,
+        cache.config.get_ttl("corridor"),
+        async {
+            OrderBookDepthService::new(db.pool().clone(), rpc_client, price_feed)
+                .fetch_depth(&corridor)
+                .await
+        },
+    )
+    .await?;
+
+    depth.map(Json).ok_or_else(|| {
+        ApiError::NotFound(format!(
+            "No priced two-sided order book for corridor {}",
+            corridor_key
+        ))
+    })
+}
+
+/// Get order book depth snapshots for a corridor
+///
+/// The busiest corridors are snapshotted every few minutes, so their
+/// liquidity can be charted over time.
+///
+/// **DATA SOURCE: Database** (order book depth snapshots)
+#[utoipa::path(
+    get,
+    path = "/api/corridors/{corridor_key}/depth/history",
+    params(
+        ("corridor_key" = String, Path, description = "Corridor identifier, in either asset order (e.g., USDC:GISSUER->XLM:native)"),
+        DepthHistoryQuery
+    ),
+    responses(
+        (status = 200, description = "Depth snapshots retrieved successfully", body = CorridorDepthHistoryResponse),
+        (status = 400, description = "Invalid corridor key or window"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Corridors"
+)]
+pub async fn get_corridor_depth_history(
+    State((db, cache, rpc_client, price_feed)): State<CorridorState>,
+    Path(corridor_key): Path<String>,
+    Query(params): Query<DepthHistoryQuery>,
+) -> ApiResult<Json<CorridorDepthHistoryResponse>> {
+    let corridor = Corridor::from_key(&corridor_key)
+        .ok_or_else(|| ApiError::BadRequest(format!("Invalid corridor key: {}", corridor_key)))?;
+    let (window, duration) = params
+        .resolve()
+        .ok_or_else(|| ApiError::BadRequest("window must look like 24h, 7d or 30d".to_string()))?;
+    let corridor_key = corridor.to_string_key();
+    let cache_key = keys::corridor_depth_history(&corridor_key, &window);
+
+    let response = <()>::get_or_fetch(
+        &cache,
+        &cache_key,
+        cache.config.get_ttl("corridor"),
+        async {
+            let snapshots = OrderBookDepthService::new(db.pool().clone(), rpc_client, price_feed)
+                .get_snapshots(&corridor_key, chrono::Utc::now() - duration)
+                .await?;
+
+            Ok(CorridorDepthHistoryResponse {
+                corridor_key: corridor_key.clone(),
+                window: window.clone(),
+                snapshots,
+            })
+        },
+    )
+    .await?;
+
+    Ok(Json(response))
+}
+
+/// Get the paths a corridor's payments were routed through
+///
+/// Breaks the corridor's successful path payments in a time window down by
+/// the intermediate assets they crossed, with each path's share of payments
+/// and volume and its average slippage. Direct conversions appear as an empty
+/// path.
+///
+/// **DATA SOURCE: Database** (stored payments)
+#[utoipa::path(
+    get,
+    path = "/api/corridors/{corridor_key}/routes",
+    params(
+        ("corridor_key" = String, Path, description = "Corridor identifier, in either asset order (e.g., USDC:GISSUER->XLM:native)"),
+        RoutesQuery
+    ),
+    responses(
+        (status = 200, description = "Route analysis retrieved successfully", body = CorridorRoutesResponse),
+        (status = 400, description = "Invalid corridor key or window"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Corridors"
+)]
+pub async fn get_corridor_routes(
+    State((db, cache, _rpc_client, _price_feed)): State<CorridorState>,
+    Path(corridor_key): Path<String>,
+    Query(params): Query<RoutesQuery>,
+) -> ApiResult<Json<CorridorRoutesResponse>> {
+    let corridor = Corridor::from_key(&corridor_key)
+        .ok_or_else(|| ApiError::BadRequest(format!("Invalid corridor key: {}", corridor_key)))?;
+    let (window, duration) = params
+        .resolve()
+        .ok_or_else(|| ApiError::BadRequest("window must look like 24h, 7d or 30d".to_string()))?;
+    let corridor_key = corridor.to_string_key();
+    let cache_key = keys::corridor_routes(&corridor_key, &window);
+
+    let response = <()>::get_or_fetch(
+        &cache,
+        &cache_key,
+        cache.config.get_ttl("corridor"),
+        async {
+            let window_end = chrono::Utc::now();
+            // One payment past the limit tells whether the window was cut short
+            let mut payments = db
+                .aggregation_db()
+                .fetch_corridor_payments_by_timerange(
+                    &corridor,
+                    window_end - duration,
+                    window_end,
+                    ROUTE_ANALYSIS_PAYMENT_LIMIT as i64 + 1,
+                )
+                .await?;
+            let truncated = payments.len() > ROUTE_ANALYSIS_PAYMENT_LIMIT;
+            payments.truncate(ROUTE_ANALYSIS_PAYMENT_LIMIT);
+
+            let (total_payments, total_volume, paths) = compute_corridor_routes(&payments)
+                .pop()
+                .map(|r| (r.total_payments, r.total_volume, r.paths))
+                .unwrap_or_default();
+
+            Ok(CorridorRoutesResponse {
+                corridor_key: corridor_key.clone(),
+                window: window.clone(),
+                total_payments,
+                total_volume,
+                paths,
+                truncated,
+            })
+        },
+    )
+    .await?;
+
+    Ok(Json(response))
 }
 
 #[cfg(test)]
@@ -520,6 +1335,20 @@ mod tests {
         assert_eq!(get_liquidity_trend(500_000.0), "decreasing");
     }
 
+    #[test]
+    fn test_latency_distribution() {
+        assert!(latency_distribution(&[]).is_empty());
+
+        let distribution = latency_distribution(&[800, 4_000, 4_500, 900_000]);
+        assert_eq!(distribution.len(), LATENCY_BUCKETS_MS.len());
+        assert_eq!(distribution[0].count, 1);
+        assert_eq!(distribution[2].latency_bucket_ms, 5_000);
+        assert_eq!(distribution[2].count, 2);
+        assert_eq!(distribution[2].percentage, 50.0);
+        // Slower than every bound
+        assert_eq!(distribution[6].count, 1);
+    }
+
     #[test]
     fn test_extract_asset_pair_regular_payment_native() {
         let payment = crate::rpc::Payment {
@@ -538,8 +1367,13 @@ mod tests {
             source_asset_code: None,
             source_asset_issuer: None,
             source_amount: None,
+            source_max: None,
+            destination_min: None,
+            path: vec![],
             from: Some("GTEST".to_string()),
             to: Some("GDEST".to_string()),
+            transaction_successful: true,
+            transaction: None,
         };
 
         let pair = extract_asset_pair_from_payment(&payment).unwrap();
@@ -566,8 +1400,13 @@ mod tests {
             source_asset_code: None,
             source_asset_issuer: None,
             source_amount: None,
+            source_max: None,
+            destination_min: None,
+            path: vec![],
             from: Some("GTEST".to_string()),
             to: Some("GDEST".to_string()),
+            transaction_successful: true,
+            transaction: None,
         };
 
         let pair = extract_asset_pair_from_payment(&payment).unwrap();
@@ -594,8 +1433,13 @@ mod tests {
             source_asset_code: Some("USD".to_string()),
             source_asset_issuer: Some("GUSDISSUER".to_string()),
             source_amount: Some("105.0".to_string()),
+            source_max: None,
+            destination_min: None,
+            path: vec![],
             from: Some("GTEST".to_string()),
             to: Some("GDEST".to_string()),
+            transaction_successful: true,
+            transaction: None,
         };
 
         let pair = extract_asset_pair_from_payment(&payment).unwrap();
@@ -622,8 +1466,13 @@ mod tests {
             source_asset_code: None,
             source_asset_issuer: None,
             source_amount: Some("150.0".to_string()),
+            source_max: None,
+            destination_min: None,
+            path: vec![],
             from: Some("GTEST".to_string()),
             to: Some("GDEST".to_string()),
+            transaction_successful: true,
+            transaction: None,
         };
 
         let pair = extract_asset_pair_from_payment(&payment).unwrap();
@@ -650,8 +1499,13 @@ mod tests {
             source_asset_code: Some("BRL".to_string()),
             source_asset_issuer: Some("GBRLISSUER".to_string()),
             source_amount: Some("500.0".to_string()),
+            source_max: None,
+            destination_min: None,
+            path: vec![],
             from: Some("GTEST".to_string()),
             to: Some("GDEST".to_string()),
+            transaction_successful: true,
+            transaction: None,
         };
 
         let pair = extract_asset_pair_from_payment(&payment).unwrap();
@@ -679,8 +1533,13 @@ mod tests {
             source_asset_code: None,
             source_asset_issuer: None,
             source_amount: None,
+            source_max: None,
+            destination_min: None,
+            path: vec![],
             from: Some("GTEST".to_string()),
             to: Some("GDEST".to_string()),
+            transaction_successful: true,
+            transaction: None,
         };
 
         let pair = extract_asset_pair_from_payment(&payment).unwrap();
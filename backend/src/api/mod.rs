@@ -10,9 +10,13 @@ pub mod liquidity_pools;
 pub mod metrics;
 pub mod metrics_cached;
 pub mod network;
+pub mod paths;
 pub mod prediction;
 pub mod price_feed;
+pub mod route_estimator;
+pub mod scoring;
 pub mod sep10;
+pub mod sep6_proxy;
 pub mod sep24_proxy;
 pub mod sep31_proxy;
 pub mod trustlines;
@@ -0,0 +1,165 @@
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::api::route_estimator::validate_conversion;
+use crate::handlers::{ApiError, ApiResult};
+use crate::services::path_finder::{AssetGraph, PathFinder, PathQuote};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StrictSendQuery {
+    /// Asset sent (e.g., "BRL:GISSUER")
+    #[param(example = "XLM:native")]
+    pub source_asset: String,
+    /// Exact amount of the source asset to send
+    #[param(example = 1000.0)]
+    pub source_amount: f64,
+    /// Asset received
+    #[param(example = "USDC:GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN")]
+    pub destination_asset: String,
+    /// Maximum number of paths to return (default: 5, max: 20)
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StrictReceiveQuery {
+    /// Asset sent
+    #[param(example = "XLM:native")]
+    pub source_asset: String,
+    /// Asset received
+    #[param(example = "USDC:GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN")]
+    pub destination_asset: String,
+    /// Exact amount of the destination asset to deliver
+    #[param(example = 100.0)]
+    pub destination_amount: f64,
+    /// Maximum number of paths to return (default: 5, max: 20)
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PathsResponse {
+    /// When the asset graph the paths were found in was built
+    pub graph_built_at: Option<DateTime<Utc>>,
+    /// Paths, best first
+    pub paths: Vec<PathQuote>,
+}
+
+fn limit(limit: Option<usize>) -> usize {
+    limit.unwrap_or(5).clamp(1, 20)
+}
+
+/// Runs a search over the current graph on the blocking pool, since large
+/// graphs can keep it busy for a while
+async fn search<F>(path_finder: &PathFinder, find: F) -> ApiResult<PathsResponse>
+where
+    F: FnOnce(&AssetGraph) -> Vec<PathQuote> + Send + 'static,
+{
+    let (graph, graph_built_at) = path_finder.graph().await;
+    let paths = tokio::task::spawn_blocking(move || find(&graph))
+        .await
+        .map_err(|e| ApiError::InternalError(format!("Path search failed: {}", e)))?;
+
+    Ok(PathsResponse {
+        graph_built_at,
+        paths,
+    })
+}
+
+/// Find paths that send an exact amount
+///
+/// Searches the in-memory asset graph for routes of up to five intermediate
+/// assets, ranked by the amount received, as Horizon's strict-send path
+/// endpoint does. The search gives up on further routes after a fixed number
+/// of conversions, returning the best found by then.
+///
+/// **DATA SOURCE: In-memory graph** (order books and liquidity pools, rebuilt
+/// every few minutes)
+#[utoipa::path(
+    get,
+    path = "/api/paths/strict-send",
+    params(StrictSendQuery),
+    responses(
+        (status = 200, description = "Paths found", body = PathsResponse),
+        (status = 400, description = "Invalid asset or amount")
+    ),
+    tag = "Routes"
+)]
+pub async fn strict_send(
+    State(path_finder): State<Arc<PathFinder>>,
+    Query(params): Query<StrictSendQuery>,
+) -> ApiResult<Json<PathsResponse>> {
+    validate_conversion(
+        &params.source_asset,
+        &params.destination_asset,
+        params.source_amount,
+    )?;
+
+    let limit = limit(params.limit);
+    search(&path_finder, move |graph| {
+        graph.strict_send(
+            &params.source_asset,
+            params.source_amount,
+            &params.destination_asset,
+            limit,
+        )
+    })
+    .await
+    .map(Json)
+}
+
+/// Find paths that deliver an exact amount
+///
+/// Searches the in-memory asset graph for routes of up to five intermediate
+/// assets, ranked by the amount sent, as Horizon's strict-receive path
+/// endpoint does. The search gives up on further routes after a fixed number
+/// of conversions, returning the best found by then.
+///
+/// **DATA SOURCE: In-memory graph** (order books and liquidity pools, rebuilt
+/// every few minutes)
+#[utoipa::path(
+    get,
+    path = "/api/paths/strict-receive",
+    params(StrictReceiveQuery),
+    responses(
+        (status = 200, description = "Paths found", body = PathsResponse),
+        (status = 400, description = "Invalid asset or amount")
+    ),
+    tag = "Routes"
+)]
+pub async fn strict_receive(
+    State(path_finder): State<Arc<PathFinder>>,
+    Query(params): Query<StrictReceiveQuery>,
+) -> ApiResult<Json<PathsResponse>> {
+    validate_conversion(
+        &params.source_asset,
+        &params.destination_asset,
+        params.destination_amount,
+    )?;
+
+    let limit = limit(params.limit);
+    search(&path_finder, move |graph| {
+        graph.strict_receive(
+            &params.source_asset,
+            &params.destination_asset,
+            params.destination_amount,
+            limit,
+        )
+    })
+    .await
+    .map(Json)
+}
+
+pub fn routes(path_finder: Arc<PathFinder>) -> Router {
+    Router::new()
+        .route("/strict-send", get(strict_send))
+        .route("/strict-receive", get(strict_receive))
+        .with_state(path_finder)
+}
@@ -0,0 +1,98 @@
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+
+use crate::handlers::{ApiError, ApiResult};
+use crate::services::route_estimator::{parse_asset, RouteEstimate, RouteEstimator};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EstimateRouteQuery {
+    /// Asset sent (e.g., "USDC:GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN")
+    #[param(example = "USDC:GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN")]
+    pub source_asset: String,
+    /// Asset received (e.g., "XLM:native")
+    #[param(example = "XLM:native")]
+    pub destination_asset: String,
+    /// Amount of the source asset to send
+    #[param(example = 1000.0)]
+    pub amount: f64,
+}
+
+/// Checks a conversion request: two distinct `CODE:ISSUER` assets and a
+/// positive amount of either one
+pub(crate) fn validate_conversion(
+    source_asset: &str,
+    destination_asset: &str,
+    amount: f64,
+) -> ApiResult<()> {
+    for asset in [source_asset, destination_asset] {
+        if parse_asset(asset).is_none() {
+            return Err(ApiError::BadRequest(format!("Invalid asset: {}", asset)));
+        }
+    }
+    if source_asset == destination_asset {
+        return Err(ApiError::BadRequest(
+            "source_asset and destination_asset must differ".to_string(),
+        ));
+    }
+    if !amount.is_finite() || amount <= 0.0 {
+        return Err(ApiError::BadRequest("amount must be positive".to_string()));
+    }
+    Ok(())
+}
+
+/// Estimate the best execution for sending an amount between two assets
+///
+/// Splits the amount across order book offers and constant-product liquidity
+/// pools, net of pool fees, the way strict-send path payments fill.
+///
+/// **DATA SOURCE: RPC** (live order book) and stored liquidity pool reserves
+#[utoipa::path(
+    get,
+    path = "/api/routes/estimate",
+    params(EstimateRouteQuery),
+    responses(
+        (status = 200, description = "Route estimated successfully", body = RouteEstimate),
+        (status = 400, description = "Invalid asset or amount"),
+        (status = 404, description = "No order book offers or liquidity pools between the assets"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Routes"
+)]
+pub async fn estimate_route(
+    State(estimator): State<Arc<RouteEstimator>>,
+    Query(params): Query<EstimateRouteQuery>,
+) -> ApiResult<Json<RouteEstimate>> {
+    validate_conversion(
+        &params.source_asset,
+        &params.destination_asset,
+        params.amount,
+    )?;
+
+    estimator
+        .estimate(
+            &params.source_asset,
+            &params.destination_asset,
+            params.amount,
+        )
+        .await?
+        .map(Json)
+        .ok_or_else(|| {
+            ApiError::NotFound(format!(
+                "No order book offers or liquidity pools for {}->{}",
+                params.source_asset, params.destination_asset
+            ))
+        })
+}
+
+pub fn routes(estimator: Arc<RouteEstimator>) -> Router {
+    Router::new()
+        .route("/estimate", get(estimate_route))
+        .with_state(estimator)
+}
@@ -0,0 +1,345 @@
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::database::Database;
+use crate::handlers::{ApiError, ApiResult};
+use crate::models::{AnchorMetricsHistory, AnchorReliabilityRecord};
+use crate::scoring::{ScoringModel, ScoringModels};
+use crate::services::aggregation::{merge_by_corridor, HourlyCorridorMetrics};
+use crate::services::failure_analysis::{parse_window, DEFAULT_WINDOW};
+
+#[derive(Clone)]
+pub struct ScoringState {
+    pub db: Arc<Database>,
+    pub models: Arc<ScoringModels>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScoringModelsResponse {
+    /// Version new scores are computed with
+    #[schema(example = "v1")]
+    pub active: String,
+    pub models: Vec<ScoringModel>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CompareQuery {
+    /// Version to evaluate
+    #[param(example = "v2")]
+    pub candidate: String,
+    /// Version to compare against (default: the active version)
+    #[param(example = "v1")]
+    pub baseline: Option<String>,
+    /// How far back to recompute stored scores, e.g. 24h or 7d (default: 24h)
+    #[param(example = "7d")]
+    pub window: Option<String>,
+}
+
+/// An anchor metrics snapshot rescored under both versions
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AnchorMetricsComparison {
+    pub anchor_id: String,
+    pub timestamp: DateTime<Utc>,
+    /// Version the stored score was computed with, unknown for scores from
+    /// before versions were recorded
+    pub recorded_version: Option<String>,
+    pub recorded_score: f64,
+    pub baseline_score: f64,
+    pub candidate_score: f64,
+    #[schema(example = "green")]
+    pub baseline_status: String,
+    #[schema(example = "yellow")]
+    pub candidate_status: String,
+}
+
+/// A stored composite anchor reliability score rescored under both versions
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AnchorReliabilityComparison {
+    pub anchor_id: String,
+    pub computed_at: DateTime<Utc>,
+    pub recorded_version: Option<String>,
+    pub recorded_score: f64,
+    pub baseline_score: f64,
+    pub candidate_score: f64,
+}
+
+/// A corridor's health over the window under both versions
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CorridorHealthComparison {
+    pub corridor_key: String,
+    pub success_rate: f64,
+    pub total_transactions: i64,
+    pub volume_usd: f64,
+    pub baseline_score: f64,
+    pub candidate_score: f64,
+}
+
+/// How far candidate scores move from baseline scores
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct ScoreDeltaSummary {
+    pub compared: usize,
+    /// Mean of candidate minus baseline
+    pub mean_delta: f64,
+    pub mean_abs_delta: f64,
+    pub max_abs_delta: f64,
+}
+
+impl ScoreDeltaSummary {
+    fn from_pairs(pairs: impl IntoIterator<Item = (f64, f64)>) -> Self {
+        let mut summary = Self::default();
+        let mut total_delta = 0.0;
+        let mut total_abs_delta = 0.0;
+
+        for (baseline, candidate) in pairs {
+            let delta = candidate - baseline;
+            summary.compared += 1;
+            total_delta += delta;
+            total_abs_delta += delta.abs();
+            summary.max_abs_delta = summary.max_abs_delta.max(delta.abs());
+        }
+
+        if summary.compared > 0 {
+            summary.mean_delta = total_delta / summary.compared as f64;
+            summary.mean_abs_delta = total_abs_delta / summary.compared as f64;
+        }
+        summary
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ComparisonSummary {
+    pub anchor_metrics: ScoreDeltaSummary,
+    /// Anchor metrics snapshots whose status differs between the versions
+    pub anchor_status_changes: usize,
+    pub anchor_reliability: ScoreDeltaSummary,
+    pub corridor_health: ScoreDeltaSummary,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScoringComparisonResponse {
+    #[schema(example = "v1")]
+    pub baseline_version: String,
+    #[schema(example = "v2")]
+    pub candidate_version: String,
+    #[schema(example = "7d")]
+    pub window: String,
+    pub summary: ComparisonSummary,
+    pub anchor_metrics: Vec<AnchorMetricsComparison>,
+    pub anchor_reliability: Vec<AnchorReliabilityComparison>,
+    pub corridor_health: Vec<CorridorHealthComparison>,
+}
+
+/// List scoring models
+///
+/// Every configured scoring model with its weights and thresholds, and the
+/// version currently used for new scores.
+#[utoipa::path(
+    get,
+    path = "/api/scoring/models",
+    responses(
+        (status = 200, description = "Scoring models", body = ScoringModelsResponse)
+    ),
+    tag = "Scoring"
+)]
+pub async fn list_models(State(state): State<ScoringState>) -> Json<ScoringModelsResponse> {
+    Json(ScoringModelsResponse {
+        active: state.models.active().version.clone(),
+        models: state.models.models().to_vec(),
+    })
+}
+
+/// Compare a candidate scoring model against the active one
+///
+/// Recomputes the anchor metrics, anchor reliability and corridor health
+/// scores stored over the window under both versions, so the effect of a
+/// candidate can be reviewed before it is made active. Nothing is written.
+///
+/// **DATA SOURCE: Database** (anchor metrics history, anchor reliability
+/// scores and hourly corridor metrics)
+#[utoipa::path(
+    get,
+    path = "/api/scoring/compare",
+    params(CompareQuery),
+    responses(
+        (status = 200, description = "Scores under both versions", body = ScoringComparisonResponse),
+        (status = 400, description = "Invalid window"),
+        (status = 404, description = "Unknown scoring model version"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Scoring"
+)]
+pub async fn compare_models(
+    State(state): State<ScoringState>,
+    Query(params): Query<CompareQuery>,
+) -> ApiResult<Json<ScoringComparisonResponse>> {
+    let model = |version: &str| {
+        state
+            .models
+            .get(version)
+            .ok_or_else(|| ApiError::NotFound(format!("Scoring model {} is not defined", version)))
+    };
+    let baseline = match params.baseline.as_deref() {
+        Some(version) => model(version)?,
+        None => state.models.active(),
+    };
+    let candidate = model(&params.candidate)?;
+
+    let window = params.window.as_deref().unwrap_or(DEFAULT_WINDOW);
+    let window_duration = parse_window(window).ok_or_else(|| {
+        ApiError::BadRequest(format!(
+            "Invalid window: {} (expected e.g. 24h or 7d, up to 90d)",
+            window
+        ))
+    })?;
+    let now = Utc::now();
+    let since = now - window_duration;
+
+    let history = state
+        .db
+        .get_anchor_metrics_history_since(since)
+        .await
+        .map_err(|e| ApiError::InternalError(e.to_string()))?;
+    let reliability = state
+        .db
+        .get_anchor_reliability_scores_since(since)
+        .await
+        .map_err(|e| ApiError::InternalError(e.to_string()))?;
+    let corridor_metrics = state
+        .db
+        .fetch_hourly_metrics_by_timerange(since, now)
+        .await
+        .map_err(|e| ApiError::InternalError(e.to_string()))?;
+
+    let anchor_metrics: Vec<_> = history
+        .iter()
+        .map(|h| compare_anchor_metrics(baseline, candidate, h))
+        .collect();
+    let anchor_reliability: Vec<_> = reliability
+        .iter()
+        .map(|r| compare_anchor_reliability(baseline, candidate, r))
+        .collect();
+    let corridor_health: Vec<_> = merge_by_corridor(corridor_metrics)
+        .iter()
+        .map(|(metrics, _)| compare_corridor_health(baseline, candidate, metrics))
+        .collect();
+
+    let summary = ComparisonSummary {
+        anchor_metrics: ScoreDeltaSummary::from_pairs(
+            anchor_metrics
+                .iter()
+                .map(|c| (c.baseline_score, c.candidate_score)),
+        ),
+        anchor_status_changes: anchor_metrics
+            .iter()
+            .filter(|c| c.baseline_status != c.candidate_status)
+            .count(),
+        anchor_reliability: ScoreDeltaSummary::from_pairs(
+            anchor_reliability
+                .iter()
+                .map(|c| (c.baseline_score, c.candidate_score)),
+        ),
+        corridor_health: ScoreDeltaSummary::from_pairs(
+            corridor_health
+                .iter()
+                .map(|c| (c.baseline_score, c.candidate_score)),
+        ),
+    };
+
+    Ok(Json(ScoringComparisonResponse {
+        baseline_version: baseline.version.clone(),
+        candidate_version: candidate.version.clone(),
+        window: window.to_string(),
+        summary,
+        anchor_metrics,
+        anchor_reliability,
+        corridor_health,
+    }))
+}
+
+fn compare_anchor_metrics(
+    baseline: &ScoringModel,
+    candidate: &ScoringModel,
+    history: &AnchorMetricsHistory,
+) -> AnchorMetricsComparison {
+    // History stores unknown settlement times as 0
+    let settlement_ms = history.avg_settlement_time_ms.filter(|ms| *ms > 0);
+
+    AnchorMetricsComparison {
+        anchor_id: history.anchor_id.clone(),
+        timestamp: history.timestamp,
+        recorded_version: history.scoring_version.clone(),
+        recorded_score: history.reliability_score,
+        baseline_score: baseline.anchor_reliability_score(history.success_rate, settlement_ms),
+        candidate_score: candidate.anchor_reliability_score(history.success_rate, settlement_ms),
+        baseline_status: baseline
+            .anchor_status(history.success_rate, history.failure_rate)
+            .as_str()
+            .to_string(),
+        candidate_status: candidate
+            .anchor_status(history.success_rate, history.failure_rate)
+            .as_str()
+            .to_string(),
+    }
+}
+
+/// Component scores don't depend on the model, apart from asset diversity
+fn compare_anchor_reliability(
+    baseline: &ScoringModel,
+    candidate: &ScoringModel,
+    record: &AnchorReliabilityRecord,
+) -> AnchorReliabilityComparison {
+    let composite = |model: &ScoringModel| {
+        model.composite_reliability_score(
+            record.asset_performance_score,
+            record.volume_score,
+            model.asset_diversity_score(record.total_assets.max(0) as usize),
+        )
+    };
+
+    AnchorReliabilityComparison {
+        anchor_id: record.anchor_id.clone(),
+        computed_at: record.computed_at,
+        recorded_version: record.scoring_version.clone(),
+        recorded_score: record.composite_score,
+        baseline_score: composite(baseline),
+        candidate_score: composite(candidate),
+    }
+}
+
+fn compare_corridor_health(
+    baseline: &ScoringModel,
+    candidate: &ScoringModel,
+    metrics: &HourlyCorridorMetrics,
+) -> CorridorHealthComparison {
+    let health = |model: &ScoringModel| {
+        model.corridor_health_score(
+            metrics.success_rate,
+            metrics.total_transactions,
+            metrics.volume_usd,
+        )
+    };
+
+    CorridorHealthComparison {
+        corridor_key: metrics.corridor_key.clone(),
+        success_rate: metrics.success_rate,
+        total_transactions: metrics.total_transactions,
+        volume_usd: metrics.volume_usd,
+        baseline_score: health(baseline),
+        candidate_score: health(candidate),
+    }
+}
+
+pub fn routes(db: Arc<Database>, models: Arc<ScoringModels>) -> Router {
+    Router::new()
+        .route("/models", get(list_models))
+        .route("/compare", get(compare_models))
+        .with_state(ScoringState { db, models })
+}
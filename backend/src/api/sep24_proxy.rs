@@ -13,12 +13,21 @@ use serde_json::Value;
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::services::settlement_latency::{
+    anchor_transactions_from_response, LatencySource, SettlementLatencyService,
+};
+
 /// Allowed transfer server hosts (env: SEP24_ALLOWED_ORIGINS, comma-separated).
-/// If unset, any origin is allowed (use in dev only).
+/// If unset, any origin is allowed (use in dev only), but no timings are recorded.
 fn allowed_origins() -> Vec<String> {
     std::env::var("SEP24_ALLOWED_ORIGINS")
         .ok()
-        .map(|s| s.split(',').map(|x| x.trim().to_string()).collect())
+        .map(|s| {
+            s.split(',')
+                .map(|x| x.trim().to_string())
+                .filter(|x| !x.is_empty())
+                .collect()
+        })
         .unwrap_or_default()
 }
 
@@ -37,6 +46,8 @@ fn is_origin_allowed(transfer_server: &str) -> bool {
 #[derive(Clone)]
 pub struct Sep24State {
     pub client: Arc<Client>,
+    /// Records anchor transaction timings for settlement latency, when set
+    pub settlement: Option<Arc<SettlementLatencyService>>,
 }
 
 impl Sep24State {
@@ -47,6 +58,47 @@ impl Sep24State {
             .unwrap_or_else(|_| Client::new());
         Self {
             client: Arc::new(client),
+            settlement: None,
+        }
+    }
+
+    /// Record the `started_at` of transactions passing through the proxy so
+    /// the Stellar payments settling them can be timed from the anchor's start
+    pub fn with_settlement_tracking(mut self, settlement: Arc<SettlementLatencyService>) -> Self {
+        self.settlement = Some(settlement);
+        self
+    }
+
+    /// Records timings only from known anchors' transfer servers behind a
+    /// configured allowlist, since callers choose `transfer_server` freely
+    async fn record_timings(&self, transfer_server: &str, data: &Value) {
+        let Some(settlement) = &self.settlement else {
+            return;
+        };
+        if allowed_origins().is_empty() {
+            return;
+        }
+        match settlement
+            .is_known_transfer_server(LatencySource::Sep24, transfer_server)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                tracing::debug!(
+                    "Not recording SEP-24 timings from unknown transfer server {}",
+                    transfer_server
+                );
+                return;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to check SEP-24 transfer server: {}", e);
+                return;
+            }
+        }
+        let timings =
+            anchor_transactions_from_response(LatencySource::Sep24, transfer_server, data);
+        if let Err(e) = settlement.record_anchor_transactions(&timings).await {
+            tracing::warn!("Failed to record SEP-24 transaction timings: {}", e);
         }
     }
 }
@@ -289,6 +341,7 @@ pub async fn get_transactions(
     if !status.is_success() {
         return Err(Sep24Error::Anchor(status.as_u16(), data));
     }
+    state.record_timings(&q.transfer_server, &data).await;
     Ok(Json(data))
 }
 
@@ -331,6 +384,7 @@ pub async fn get_transaction(
     if !status.is_success() {
         return Err(Sep24Error::Anchor(status.as_u16(), data));
     }
+    state.record_timings(&q.transfer_server, &data).await;
     Ok(Json(data))
 }
 
@@ -383,7 +437,11 @@ impl IntoResponse for Sep24Error {
 
 /// Build SEP-24 API router
 pub fn routes() -> axum::Router {
-    let state = Sep24State::new();
+    router(Sep24State::new())
+}
+
+/// Build the SEP-24 API router over the given state
+pub fn router(state: Sep24State) -> axum::Router {
     axum::Router::new()
         .route("/api/sep24/info", axum::routing::get(get_info))
         .route(
@@ -13,10 +13,21 @@ use serde_json::Value;
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::services::settlement_latency::{
+    anchor_transactions_from_response, LatencySource, SettlementLatencyService,
+};
+
+/// Allowed direct payment servers (env: SEP31_ALLOWED_ORIGINS, comma-separated).
+/// If unset, any origin is allowed, but no timings are recorded.
 fn allowed_origins() -> Vec<String> {
     std::env::var("SEP31_ALLOWED_ORIGINS")
         .ok()
-        .map(|s| s.split(',').map(|x| x.trim().to_string()).collect())
+        .map(|s| {
+            s.split(',')
+                .map(|x| x.trim().to_string())
+                .filter(|x| !x.is_empty())
+                .collect()
+        })
         .unwrap_or_default()
 }
 
@@ -32,6 +43,8 @@ fn is_origin_allowed(transfer_server: &str) -> bool {
 #[derive(Clone)]
 pub struct Sep31State {
     pub client: Arc<Client>,
+    /// Records anchor transaction timings for settlement latency, when set
+    pub settlement: Option<Arc<SettlementLatencyService>>,
 }
 
 impl Sep31State {
@@ -42,6 +55,47 @@ impl Sep31State {
             .unwrap_or_else(|_| Client::new());
         Self {
             client: Arc::new(client),
+            settlement: None,
+        }
+    }
+
+    /// Record the `started_at` of transactions passing through the proxy so
+    /// the Stellar payments settling them can be timed from the anchor's start
+    pub fn with_settlement_tracking(mut self, settlement: Arc<SettlementLatencyService>) -> Self {
+        self.settlement = Some(settlement);
+        self
+    }
+
+    /// Records timings only from known anchors' servers behind a configured
+    /// allowlist, since callers choose `transfer_server` freely
+    async fn record_timings(&self, transfer_server: &str, data: &Value) {
+        let Some(settlement) = &self.settlement else {
+            return;
+        };
+        if allowed_origins().is_empty() {
+            return;
+        }
+        match settlement
+            .is_known_transfer_server(LatencySource::Sep31, transfer_server)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                tracing::debug!(
+                    "Not recording SEP-31 timings from unknown server {}",
+                    transfer_server
+                );
+                return;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to check SEP-31 server: {}", e);
+                return;
+            }
+        }
+        let timings =
+            anchor_transactions_from_response(LatencySource::Sep31, transfer_server, data);
+        if let Err(e) = settlement.record_anchor_transactions(&timings).await {
+            tracing::warn!("Failed to record SEP-31 transaction timings: {}", e);
         }
     }
 }
@@ -220,6 +274,7 @@ pub async fn get_transactions(
     if !status.is_success() {
         return Err(Sep31Error::Anchor(status.as_u16(), data));
     }
+    state.record_timings(&q.transfer_server, &data).await;
     Ok(Json(data))
 }
 
@@ -258,6 +313,7 @@ pub async fn get_transaction(
     if !status.is_success() {
         return Err(Sep31Error::Anchor(status.as_u16(), data));
     }
+    state.record_timings(&q.transfer_server, &data).await;
     Ok(Json(data))
 }
 
@@ -390,7 +446,11 @@ impl IntoResponse for Sep31Error {
 }
 
 pub fn routes() -> axum::Router {
-    let state = Sep31State::new();
+    router(Sep31State::new())
+}
+
+/// Build the SEP-31 API router over the given state
+pub fn router(state: Sep31State) -> axum::Router {
     axum::Router::new()
         .route("/api/sep31/info", axum::routing::get(get_info))
         .route("/api/sep31/quote", axum::routing::post(post_quote))
@@ -0,0 +1,321 @@
+//! SEP-6 (Deposit and Withdrawal API) proxy API.
+//! Proxies programmatic deposit and withdrawal requests to anchor transfer
+//! servers to avoid CORS and centralize auth.
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::services::settlement_latency::{
+    anchor_transactions_from_response, LatencySource, SettlementLatencyService,
+};
+
+/// Allowed transfer server hosts (env: SEP6_ALLOWED_ORIGINS, comma-separated).
+/// If unset, any origin is allowed (use in dev only), but no timings are recorded.
+fn allowed_origins() -> Vec<String> {
+    std::env::var("SEP6_ALLOWED_ORIGINS")
+        .ok()
+        .map(|s| {
+            s.split(',')
+                .map(|x| x.trim().to_string())
+                .filter(|x| !x.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn is_origin_allowed(transfer_server: &str) -> bool {
+    let allowed = allowed_origins();
+    if allowed.is_empty() {
+        return true;
+    }
+    let url = transfer_server.trim().trim_end_matches('/');
+    allowed.iter().any(|o| url.starts_with(o) || o == "*")
+}
+
+#[derive(Clone)]
+pub struct Sep6State {
+    pub client: Arc<Client>,
+    /// Records anchor transaction timings for settlement latency, when set
+    pub settlement: Option<Arc<SettlementLatencyService>>,
+}
+
+impl Default for Sep6State {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sep6State {
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        Self {
+            client: Arc::new(client),
+            settlement: None,
+        }
+    }
+
+    /// Record the `started_at` of transactions passing through the proxy so
+    /// the Stellar payments settling them can be timed from the anchor's start
+    pub fn with_settlement_tracking(mut self, settlement: Arc<SettlementLatencyService>) -> Self {
+        self.settlement = Some(settlement);
+        self
+    }
+
+    /// Records timings only from known anchors' transfer servers behind a
+    /// configured allowlist, since callers choose `transfer_server` freely
+    async fn record_timings(&self, transfer_server: &str, data: &Value) {
+        let Some(settlement) = &self.settlement else {
+            return;
+        };
+        if allowed_origins().is_empty() {
+            return;
+        }
+        match settlement
+            .is_known_transfer_server(LatencySource::Sep6, transfer_server)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                tracing::debug!(
+                    "Not recording SEP-6 timings from unknown transfer server {}",
+                    transfer_server
+                );
+                return;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to check SEP-6 transfer server: {}", e);
+                return;
+            }
+        }
+        let timings = anchor_transactions_from_response(LatencySource::Sep6, transfer_server, data);
+        if let Err(e) = settlement.record_anchor_transactions(&timings).await {
+            tracing::warn!("Failed to record SEP-6 transaction timings: {}", e);
+        }
+    }
+
+    /// GET `path` on the transfer server with the anchor's query parameters,
+    /// forwarding the JWT when given
+    async fn forward(&self, path: &str, q: &ProxyQuery) -> Result<Value, Sep6Error> {
+        if !is_origin_allowed(&q.transfer_server) {
+            return Err(Sep6Error::Forbidden(
+                "Transfer server not in allowed list".to_string(),
+            ));
+        }
+        let url = format!("{}{}", base_url(&q.transfer_server), path);
+
+        let mut req = self.client.get(&url).query(&q.params);
+        if let Some(jwt) = &q.jwt {
+            req = req.header("Authorization", format!("Bearer {}", jwt));
+        }
+        let resp = req
+            .send()
+            .await
+            .map_err(|e| Sep6Error::Proxy(e.to_string()))?;
+
+        let status = resp.status();
+        let data = resp
+            .json::<Value>()
+            .await
+            .map_err(|e| Sep6Error::Proxy(e.to_string()))?;
+
+        if !status.is_success() {
+            return Err(Sep6Error::Anchor(status.as_u16(), data));
+        }
+        Ok(data)
+    }
+}
+
+fn base_url(transfer_server: &str) -> String {
+    transfer_server.trim().trim_end_matches('/').to_string()
+}
+
+/// Query for every SEP-6 endpoint: the transfer server, an optional SEP-10
+/// JWT, and the parameters passed on to the anchor as-is (`asset_code`,
+/// `account`, `type`, `amount`, `quote_id`, `kind`, `id`, ...)
+#[derive(Debug, Deserialize)]
+pub struct ProxyQuery {
+    pub transfer_server: String,
+    #[serde(default)]
+    pub jwt: Option<String>,
+    #[serde(flatten)]
+    pub params: BTreeMap<String, String>,
+}
+
+/// GET /api/sep6/info?transfer_server=<url>&lang=
+pub async fn get_info(
+    State(state): State<Sep6State>,
+    Query(q): Query<ProxyQuery>,
+) -> Result<Json<Value>, Sep6Error> {
+    Ok(Json(state.forward("/info", &q).await?))
+}
+
+/// GET /api/sep6/deposit?transfer_server=&asset_code=&account=&jwt=&...
+pub async fn get_deposit(
+    State(state): State<Sep6State>,
+    Query(q): Query<ProxyQuery>,
+) -> Result<Json<Value>, Sep6Error> {
+    Ok(Json(state.forward("/deposit", &q).await?))
+}
+
+/// GET /api/sep6/withdraw?transfer_server=&asset_code=&type=&jwt=&...
+pub async fn get_withdraw(
+    State(state): State<Sep6State>,
+    Query(q): Query<ProxyQuery>,
+) -> Result<Json<Value>, Sep6Error> {
+    Ok(Json(state.forward("/withdraw", &q).await?))
+}
+
+/// GET /api/sep6/deposit-exchange?transfer_server=&destination_asset=&source_asset=&amount=&account=&jwt=&...
+pub async fn get_deposit_exchange(
+    State(state): State<Sep6State>,
+    Query(q): Query<ProxyQuery>,
+) -> Result<Json<Value>, Sep6Error> {
+    Ok(Json(state.forward("/deposit-exchange", &q).await?))
+}
+
+/// GET /api/sep6/withdraw-exchange?transfer_server=&source_asset=&destination_asset=&amount=&type=&jwt=&...
+pub async fn get_withdraw_exchange(
+    State(state): State<Sep6State>,
+    Query(q): Query<ProxyQuery>,
+) -> Result<Json<Value>, Sep6Error> {
+    Ok(Json(state.forward("/withdraw-exchange", &q).await?))
+}
+
+/// GET /api/sep6/transactions?transfer_server=&asset_code=&jwt=&...
+pub async fn get_transactions(
+    State(state): State<Sep6State>,
+    Query(q): Query<ProxyQuery>,
+) -> Result<Json<Value>, Sep6Error> {
+    let data = state.forward("/transactions", &q).await?;
+    state.record_timings(&q.transfer_server, &data).await;
+    Ok(Json(data))
+}
+
+/// GET /api/sep6/transaction?transfer_server=&id=&jwt=
+pub async fn get_transaction(
+    State(state): State<Sep6State>,
+    Query(q): Query<ProxyQuery>,
+) -> Result<Json<Value>, Sep6Error> {
+    let data = state.forward("/transaction", &q).await?;
+    state.record_timings(&q.transfer_server, &data).await;
+    Ok(Json(data))
+}
+
+/// List known SEP-6-enabled anchors (from env or static list).
+/// GET /api/sep6/anchors
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Sep6AnchorInfo {
+    pub name: String,
+    pub transfer_server: String,
+    pub home_domain: Option<String>,
+}
+
+pub async fn list_anchors() -> Json<Value> {
+    // Env: SEP6_ANCHORS = JSON array of { "name", "transfer_server", "home_domain" }
+    let anchors: Vec<Sep6AnchorInfo> = if let Ok(s) = std::env::var("SEP6_ANCHORS") {
+        serde_json::from_str(&s).unwrap_or_default()
+    } else {
+        vec![]
+    };
+    Json(serde_json::json!({ "anchors": anchors }))
+}
+
+#[derive(Debug)]
+pub enum Sep6Error {
+    Forbidden(String),
+    Proxy(String),
+    Anchor(u16, Value),
+}
+
+impl IntoResponse for Sep6Error {
+    fn into_response(self) -> axum::response::Response {
+        let (status, body) = match &self {
+            Sep6Error::Forbidden(msg) => (
+                StatusCode::FORBIDDEN,
+                serde_json::json!({ "error": "forbidden", "message": msg }),
+            ),
+            Sep6Error::Proxy(msg) => (
+                StatusCode::BAD_GATEWAY,
+                serde_json::json!({ "error": "proxy", "message": msg }),
+            ),
+            Sep6Error::Anchor(code, data) => {
+                let status = StatusCode::from_u16(*code).unwrap_or(StatusCode::BAD_GATEWAY);
+                (status, data.clone())
+            }
+        };
+        (status, Json(body)).into_response()
+    }
+}
+
+/// Build SEP-6 API router
+pub fn routes() -> axum::Router {
+    router(Sep6State::new())
+}
+
+/// Build the SEP-6 API router over the given state
+pub fn router(state: Sep6State) -> axum::Router {
+    axum::Router::new()
+        .route("/api/sep6/info", axum::routing::get(get_info))
+        .route("/api/sep6/deposit", axum::routing::get(get_deposit))
+        .route("/api/sep6/withdraw", axum::routing::get(get_withdraw))
+        .route(
+            "/api/sep6/deposit-exchange",
+            axum::routing::get(get_deposit_exchange),
+        )
+        .route(
+            "/api/sep6/withdraw-exchange",
+            axum::routing::get(get_withdraw_exchange),
+        )
+        .route(
+            "/api/sep6/transactions",
+            axum::routing::get(get_transactions),
+        )
+        .route("/api/sep6/transaction", axum::routing::get(get_transaction))
+        .route("/api/sep6/anchors", axum::routing::get(list_anchors))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_url() {
+        assert_eq!(
+            base_url("https://api.example.com/sep6/"),
+            "https://api.example.com/sep6"
+        );
+        assert_eq!(
+            base_url("  https://api.example.com  "),
+            "https://api.example.com"
+        );
+    }
+
+    #[test]
+    fn test_proxy_query_keeps_anchor_params() {
+        let uri: axum::http::Uri = "/api/sep6/withdraw?transfer_server=https%3A%2F%2Fapi.test.com&jwt=token&asset_code=USDC&type=bank_account"
+            .parse()
+            .unwrap();
+        let Query(q) = Query::<ProxyQuery>::try_from_uri(&uri).unwrap();
+        assert_eq!(q.transfer_server, "https://api.test.com");
+        assert_eq!(q.jwt.as_deref(), Some("token"));
+        assert_eq!(
+            q.params.keys().collect::<Vec<_>>(),
+            vec!["asset_code", "type"]
+        );
+        assert_eq!(q.params["type"], "bank_account");
+    }
+}
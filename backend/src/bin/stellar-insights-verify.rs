@@ -0,0 +1,104 @@
+//! Verify a published analytics snapshot against the on-chain snapshot
+//! contract
+//!
+//! Usage:
+//!   stellar-insights-verify --file <snapshot.json> [options]
+//!   stellar-insights-verify --api <url> --epoch <n> [options]
+//!
+//! Options:
+//!   --rpc-url <url>       Soroban RPC endpoint (env: SOROBAN_RPC_URL)
+//!   --contract-id <id>    Snapshot contract ID (env: SNAPSHOT_CONTRACT_ID)
+//!   --json                Print the report as JSON
+//!
+//! Exits 0 when the snapshot matches the contract, 1 when it doesn't and 2
+//! when verification couldn't run.
+
+use anyhow::{anyhow, Context, Result};
+use std::process::ExitCode;
+use stellar_insights_backend::services::contract::{ContractConfig, ContractService};
+use stellar_insights_backend::snapshot::verify::{verify_snapshot, PublishedSnapshot};
+
+const USAGE: &str = "Usage: stellar-insights-verify (--file <snapshot.json> | --api <url> --epoch <n>) [--rpc-url <url>] [--contract-id <id>] [--json]";
+
+#[derive(Debug, Default)]
+struct Args {
+    file: Option<String>,
+    api: Option<String>,
+    epoch: Option<u64>,
+    rpc_url: Option<String>,
+    contract_id: Option<String>,
+    json: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args> {
+    let mut parsed = Args::default();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow!("{} needs a value", arg));
+        match arg.as_str() {
+            "--file" => parsed.file = Some(value()?),
+            "--api" => parsed.api = Some(value()?),
+            "--epoch" => parsed.epoch = Some(value()?.parse().context("--epoch must be a number")?),
+            "--rpc-url" => parsed.rpc_url = Some(value()?),
+            "--contract-id" => parsed.contract_id = Some(value()?),
+            "--json" => parsed.json = true,
+            _ => return Err(anyhow!("Unknown argument {}", arg)),
+        }
+    }
+    Ok(parsed)
+}
+
+async fn run(args: Args) -> Result<bool> {
+    let published = match (&args.file, &args.api, args.epoch) {
+        (Some(path), None, _) => {
+            let json = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path))?;
+            PublishedSnapshot::from_json(&json)?
+        }
+        (None, Some(api), Some(epoch)) => PublishedSnapshot::fetch(api, epoch).await?,
+        _ => return Err(anyhow!("{}", USAGE)),
+    };
+
+    let config = ContractConfig {
+        rpc_url: args
+            .rpc_url
+            .or_else(|| std::env::var("SOROBAN_RPC_URL").ok())
+            .unwrap_or_else(|| "https://soroban-testnet.stellar.org".to_string()),
+        contract_id: args
+            .contract_id
+            .or_else(|| std::env::var("SNAPSHOT_CONTRACT_ID").ok())
+            .ok_or_else(|| anyhow!("--contract-id or SNAPSHOT_CONTRACT_ID is required"))?,
+        network_passphrase: std::env::var("STELLAR_NETWORK_PASSPHRASE")
+            .unwrap_or_else(|_| "Test SDF Network ; September 2015".to_string()),
+        // Verification only reads contract state
+        source_secret_key: String::new(),
+    };
+    let contract = ContractService::new(config)?;
+
+    let report = verify_snapshot(published, &contract).await?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{}", report);
+    }
+    Ok(report.passed())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(args).await {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("Verification failed to run: {:#}", e);
+            ExitCode::from(2)
+        }
+    }
+}
@@ -10,6 +10,7 @@ pub fn broadcast_anchor_update(ws_state: &Arc<WsState>, anchor: &Anchor) {
         name: anchor.name.clone(),
         reliability_score: anchor.reliability_score,
         status: anchor.status.clone(),
+        uptime_percentage: anchor.uptime_percentage,
     };
     ws_state.broadcast(message);
 }
@@ -49,6 +50,8 @@ mod tests {
             avg_settlement_time_ms: 500,
             reliability_score: 95.0,
             status: "active".to_string(),
+            scoring_version: None,
+            uptime_percentage: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
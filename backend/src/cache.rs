@@ -259,8 +259,37 @@ pub mod keys {
         format!("corridor:list:{}:{}:{}", limit, offset, filters)
     }
 
-    pub fn corridor_detail(corridor_key: &str) -> String {
-        format!("corridor:detail:{}", corridor_key)
+    pub fn corridor_detail(corridor_key: &str, window: &str, resolution: &str) -> String {
+        format!("corridor:detail:{}:{}:{}", corridor_key, window, resolution)
+    }
+
+    /// Pattern for invalidating a corridor's detail at every window and resolution
+    pub fn corridor_detail_pattern(corridor_key: &str) -> String {
+        format!("corridor:detail:{}:*", corridor_key)
+    }
+
+    pub fn corridor_failures(corridor_key: &str, window: &str) -> String {
+        format!("corridor:failures:{}:{}", corridor_key, window)
+    }
+
+    pub fn corridor_distributions(corridor_key: &str, window: &str) -> String {
+        format!("corridor:distributions:{}:{}", corridor_key, window)
+    }
+
+    pub fn corridor_routes(corridor_key: &str, window: &str) -> String {
+        format!("corridor:routes:{}:{}", corridor_key, window)
+    }
+
+    pub fn corridor_depth(corridor_key: &str) -> String {
+        format!("corridor:depth:{}", corridor_key)
+    }
+
+    pub fn corridor_depth_history(corridor_key: &str, window: &str) -> String {
+        format!("corridor:depth_history:{}:{}", corridor_key, window)
+    }
+
+    pub fn anchor_failures(anchor_id: &str, window: &str) -> String {
+        format!("anchor:failures:{}:{}", anchor_id, window)
     }
 
     pub fn dashboard_stats() -> String {
@@ -44,7 +44,7 @@ impl CacheInvalidationService {
     pub async fn invalidate_corridor(&self, corridor_key: &str) -> anyhow::Result<()> {
         tracing::info!("Invalidating cache for corridor: {}", corridor_key);
         self.cache
-            .delete(&keys::corridor_detail(corridor_key))
+            .delete_pattern(&keys::corridor_detail_pattern(corridor_key))
             .await?;
         // Also invalidate the list caches since they contain this corridor
         self.cache.delete_pattern(&keys::corridor_pattern()).await
@@ -3,11 +3,14 @@ use chrono::Utc;
 use sqlx::SqlitePool;
 use uuid::Uuid;
 
-use crate::analytics::compute_anchor_metrics;
+use crate::analytics::{apply_anchor_uptime, compute_anchor_metrics, AnchorAssetPerformance};
 use crate::models::{
-    Anchor, AnchorDetailResponse, AnchorMetricsHistory, Asset, CorridorRecord, CreateAnchorRequest,
+    Anchor, AnchorDetailResponse, AnchorEndpointProbe, AnchorEndpointUptime, AnchorMetricsHistory,
+    AnchorReliabilityRecord, AnchorStellarTomlChange, AnchorStellarTomlRecord, Asset, CorridorRecord, CreateAnchorRequest,
     MetricRecord, MuxedAccountAnalytics, MuxedAccountUsage, SnapshotRecord,
 };
+use crate::services::settlement_latency::{settlement_latency_ms, LatencySource};
+use crate::services::stellar_toml::StellarToml;
 
 /// Parameters for updating anchor from RPC data
 pub struct AnchorRpcUpdate {
@@ -19,6 +22,8 @@ pub struct AnchorRpcUpdate {
     pub avg_settlement_time_ms: i32,
     pub reliability_score: f64,
     pub status: String,
+    /// Version of the scoring model behind `reliability_score` and `status`
+    pub scoring_version: String,
 }
 
 /// Parameters for recording anchor metrics history
@@ -32,6 +37,97 @@ pub struct AnchorMetricsParams {
     pub failed_transactions: i64,
     pub avg_settlement_time_ms: Option<i32>,
     pub volume_usd: Option<f64>,
+    pub scoring_version: Option<String>,
+}
+
+/// Outcome of the latest persisted payments sent or received by an account
+#[derive(Debug, Clone, Default, sqlx::FromRow)]
+pub struct AccountPaymentSummary {
+    pub total_transactions: i64,
+    pub successful_transactions: i64,
+    /// Amount moved by the successful payments
+    pub total_volume: f64,
+}
+
+#[derive(sqlx::FromRow)]
+struct AnchorAssetPerformanceRow {
+    anchor_id: String,
+    asset_code: String,
+    asset_issuer: String,
+    total_transactions: i64,
+    successful_transactions: i64,
+    total_volume: f64,
+}
+
+#[derive(sqlx::FromRow)]
+struct AnchorReliabilityRow {
+    anchor_id: String,
+    composite_score: f64,
+    asset_performance_score: f64,
+    volume_score: f64,
+    asset_diversity_score: f64,
+    weighted_success_rate: f64,
+    total_assets: i64,
+    total_volume_usd: f64,
+    asset_performance: String,
+    window_start: chrono::DateTime<Utc>,
+    computed_at: chrono::DateTime<Utc>,
+    scoring_version: Option<String>,
+}
+
+impl From<AnchorReliabilityRow> for AnchorReliabilityRecord {
+    fn from(row: AnchorReliabilityRow) -> Self {
+        Self {
+            anchor_id: row.anchor_id,
+            composite_score: row.composite_score,
+            asset_performance_score: row.asset_performance_score,
+            volume_score: row.volume_score,
+            asset_diversity_score: row.asset_diversity_score,
+            weighted_success_rate: row.weighted_success_rate,
+            total_assets: row.total_assets,
+            total_volume_usd: row.total_volume_usd,
+            scoring_version: row.scoring_version,
+            assets: serde_json::from_str(&row.asset_performance).unwrap_or_default(),
+            window_start: row.window_start,
+            computed_at: row.computed_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct AnchorStellarTomlRow {
+    anchor_id: String,
+    home_domain: String,
+    toml_hash: String,
+    toml: String,
+    validation_errors: String,
+    fetch_error: Option<String>,
+    changed_at: chrono::DateTime<Utc>,
+    checked_at: chrono::DateTime<Utc>,
+}
+
+impl From<AnchorStellarTomlRow> for AnchorStellarTomlRecord {
+    fn from(row: AnchorStellarTomlRow) -> Self {
+        Self {
+            anchor_id: row.anchor_id,
+            home_domain: row.home_domain,
+            toml: serde_json::from_str(&row.toml).unwrap_or_default(),
+            toml_hash: row.toml_hash,
+            validation_errors: serde_json::from_str(&row.validation_errors).unwrap_or_default(),
+            fetch_error: row.fetch_error,
+            changed_at: row.changed_at,
+            checked_at: row.checked_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct AnchorStellarTomlChangeRow {
+    home_domain: String,
+    toml_hash: String,
+    toml: String,
+    validation_errors: String,
+    fetched_at: chrono::DateTime<Utc>,
 }
 
 pub struct Database {
@@ -125,12 +221,21 @@ impl Database {
         avg_settlement_time_ms: Option<i32>,
         volume_usd: Option<f64>,
     ) -> Result<Anchor> {
-        // Compute metrics
-        let metrics = compute_anchor_metrics(
-            total_transactions,
-            successful_transactions,
-            failed_transactions,
-            avg_settlement_time_ms,
+        // Compute metrics, keeping the status in line with endpoint uptime
+        let uptime_percentage: Option<f64> =
+            sqlx::query_scalar("SELECT uptime_percentage FROM anchors WHERE id = $1")
+                .bind(anchor_id.to_string())
+                .fetch_optional(&self.pool)
+                .await?
+                .flatten();
+        let metrics = apply_anchor_uptime(
+            compute_anchor_metrics(
+                total_transactions,
+                successful_transactions,
+                failed_transactions,
+                avg_settlement_time_ms,
+            ),
+            uptime_percentage,
         );
 
         // Update anchor
@@ -144,7 +249,8 @@ impl Database {
                 reliability_score = $5,
                 status = $6,
                 total_volume_usd = COALESCE($7, total_volume_usd),
-                updated_at = $8
+                updated_at = $8,
+                scoring_version = $10
             WHERE id = $9
             RETURNING *
             "#,
@@ -158,6 +264,7 @@ impl Database {
         .bind(volume_usd.unwrap_or(0.0))
         .bind(Utc::now())
         .bind(anchor_id.to_string())
+        .bind(&metrics.scoring_version)
         .fetch_one(&self.pool)
         .await?;
 
@@ -172,6 +279,7 @@ impl Database {
             failed_transactions,
             avg_settlement_time_ms,
             volume_usd,
+            scoring_version: Some(metrics.scoring_version),
         })
         .await?;
 
@@ -245,7 +353,8 @@ impl Database {
                 avg_settlement_time_ms = $5,
                 reliability_score = $6,
                 status = $7,
-                updated_at = $8
+                updated_at = $8,
+                scoring_version = $10
             WHERE stellar_account = $9
             "#,
         )
@@ -258,12 +367,42 @@ impl Database {
         .bind(&params.status)
         .bind(Utc::now())
         .bind(&params.stellar_account)
+        .bind(&params.scoring_version)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
+    /// Summarize the latest `limit` persisted payments sent or received by
+    /// `account`, telling failures apart by their `successful` flag
+    pub async fn get_account_payment_summary(
+        &self,
+        account: &str,
+        limit: i64,
+    ) -> Result<AccountPaymentSummary> {
+        let summary = sqlx::query_as::<_, AccountPaymentSummary>(
+            r#"
+            SELECT
+                COUNT(*) AS total_transactions,
+                COALESCE(SUM(CASE WHEN successful THEN 1 ELSE 0 END), 0) AS successful_transactions,
+                COALESCE(SUM(CASE WHEN successful THEN amount ELSE 0.0 END), 0.0) AS total_volume
+            FROM (
+                SELECT successful, amount FROM payments
+                WHERE source_account = $1 OR destination_account = $1
+                ORDER BY created_at DESC
+                LIMIT $2
+            )
+            "#,
+        )
+        .bind(account)
+        .bind(limit)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(summary)
+    }
+
     // Metrics history operations
     pub async fn record_anchor_metrics_history(
         &self,
@@ -275,9 +414,9 @@ impl Database {
             INSERT INTO anchor_metrics_history (
                 id, anchor_id, timestamp, success_rate, failure_rate, reliability_score,
                 total_transactions, successful_transactions, failed_transactions,
-                avg_settlement_time_ms, volume_usd
+                avg_settlement_time_ms, volume_usd, scoring_version
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
             RETURNING *
             "#,
         )
@@ -292,6 +431,7 @@ impl Database {
         .bind(params.failed_transactions)
         .bind(params.avg_settlement_time_ms.unwrap_or(0))
         .bind(params.volume_usd.unwrap_or(0.0))
+        .bind(&params.scoring_version)
         .fetch_one(&self.pool)
         .await?;
 
@@ -319,6 +459,25 @@ impl Database {
         Ok(history)
     }
 
+    /// Metrics history of every anchor recorded since `since`, oldest first
+    pub async fn get_anchor_metrics_history_since(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<Vec<AnchorMetricsHistory>> {
+        let history = sqlx::query_as::<_, AnchorMetricsHistory>(
+            r#"
+            SELECT * FROM anchor_metrics_history
+            WHERE timestamp >= $1
+            ORDER BY timestamp ASC
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(history)
+    }
+
     pub async fn get_anchor_detail(&self, anchor_id: Uuid) -> Result<Option<AnchorDetailResponse>> {
         let anchor = match self.get_anchor_by_id(anchor_id).await? {
             Some(a) => a,
@@ -327,14 +486,598 @@ impl Database {
 
         let assets = self.get_assets_by_anchor(anchor_id).await?;
         let metrics_history = self.get_anchor_metrics_history(anchor_id, 30).await?;
+        let reliability_history = self.get_anchor_reliability_history(anchor_id, 30).await?;
+        let stellar_toml = self.get_anchor_stellar_toml(anchor_id).await?;
+        let stellar_toml_history = self.get_anchor_stellar_toml_history(anchor_id, 10).await?;
+        let endpoint_uptime = self
+            .get_anchor_endpoint_uptime(anchor_id, Utc::now() - chrono::Duration::hours(24))
+            .await?;
 
         Ok(Some(AnchorDetailResponse {
             anchor,
             assets,
             metrics_history,
+            reliability: reliability_history.first().cloned(),
+            reliability_history,
+            stellar_toml,
+            stellar_toml_history,
+            endpoint_uptime,
         }))
     }
 
+    // Reliability score operations
+
+    /// Payment counts and volume for every registered asset since `since`,
+    /// grouped by the anchor that issues it. Path payments count towards both
+    /// the asset sent and the asset delivered, in that asset's units; USD
+    /// volume is left for the caller to price.
+    pub async fn get_anchor_asset_performance(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<std::collections::HashMap<String, Vec<AnchorAssetPerformance>>> {
+        let rows: Vec<AnchorAssetPerformanceRow> = sqlx::query_as(
+            r#"
+            SELECT
+                a.anchor_id,
+                a.asset_code,
+                a.asset_issuer,
+                COUNT(p.id) AS total_transactions,
+                COALESCE(SUM(CASE WHEN p.successful THEN 1 ELSE 0 END), 0) AS successful_transactions,
+                COALESCE(SUM(CASE
+                    WHEN NOT p.successful THEN 0
+                    WHEN p.asset_code = a.asset_code AND p.asset_issuer = a.asset_issuer THEN p.amount
+                    ELSE COALESCE(p.source_amount, p.amount)
+                END), 0.0) AS total_volume
+            FROM assets a
+            LEFT JOIN payments p
+                ON p.created_at >= $1
+               AND ((p.asset_code = a.asset_code AND p.asset_issuer = a.asset_issuer)
+                 OR (p.source_asset_code = a.asset_code AND p.source_asset_issuer = a.asset_issuer))
+            GROUP BY a.anchor_id, a.asset_code, a.asset_issuer
+            ORDER BY a.anchor_id, a.asset_code
+            "#,
+        )
+        .bind(since.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut performance: std::collections::HashMap<String, Vec<AnchorAssetPerformance>> =
+            std::collections::HashMap::new();
+        for row in rows {
+            performance
+                .entry(row.anchor_id)
+                .or_default()
+                .push(AnchorAssetPerformance {
+                    asset_code: row.asset_code,
+                    asset_issuer: row.asset_issuer,
+                    total_transactions: row.total_transactions,
+                    successful_transactions: row.successful_transactions,
+                    failed_transactions: row.total_transactions - row.successful_transactions,
+                    total_volume: row.total_volume,
+                    total_volume_usd: 0.0,
+                });
+        }
+
+        Ok(performance)
+    }
+
+    pub async fn record_anchor_reliability_score(
+        &self,
+        record: &AnchorReliabilityRecord,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO anchor_reliability_scores (
+                anchor_id, composite_score, asset_performance_score, volume_score,
+                asset_diversity_score, weighted_success_rate, total_assets, total_volume_usd,
+                asset_performance, window_start, computed_at, scoring_version
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            "#,
+        )
+        .bind(&record.anchor_id)
+        .bind(record.composite_score)
+        .bind(record.asset_performance_score)
+        .bind(record.volume_score)
+        .bind(record.asset_diversity_score)
+        .bind(record.weighted_success_rate)
+        .bind(record.total_assets)
+        .bind(record.total_volume_usd)
+        .bind(serde_json::to_string(&record.assets)?)
+        .bind(record.window_start)
+        .bind(record.computed_at)
+        .bind(&record.scoring_version)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_anchor_reliability_history(
+        &self,
+        anchor_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<AnchorReliabilityRecord>> {
+        let rows: Vec<AnchorReliabilityRow> = sqlx::query_as(
+            r#"
+            SELECT anchor_id, composite_score, asset_performance_score, volume_score,
+                   asset_diversity_score, weighted_success_rate, total_assets, total_volume_usd,
+                   asset_performance, window_start, computed_at, scoring_version
+            FROM anchor_reliability_scores
+            WHERE anchor_id = $1
+            ORDER BY computed_at DESC, id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(anchor_id.to_string())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(AnchorReliabilityRecord::from).collect())
+    }
+
+    /// Every anchor reliability score computed since `since`, oldest first
+    pub async fn get_anchor_reliability_scores_since(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<Vec<AnchorReliabilityRecord>> {
+        let rows: Vec<AnchorReliabilityRow> = sqlx::query_as(
+            r#"
+            SELECT anchor_id, composite_score, asset_performance_score, volume_score,
+                   asset_diversity_score, weighted_success_rate, total_assets, total_volume_usd,
+                   asset_performance, window_start, computed_at, scoring_version
+            FROM anchor_reliability_scores
+            WHERE computed_at >= $1
+            ORDER BY computed_at ASC, id ASC
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(AnchorReliabilityRecord::from).collect())
+    }
+
+    // stellar.toml operations
+
+    /// Issuer accounts of assets with trustline stats or with payments since
+    /// `since`
+    pub async fn get_known_issuers(
+        &self,
+        since: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<String>> {
+        let issuers: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT issuer FROM (
+                SELECT asset_issuer AS issuer FROM trustline_stats
+                UNION
+                SELECT asset_issuer FROM payments
+                WHERE created_at >= $1 AND asset_issuer IS NOT NULL
+                UNION
+                SELECT source_asset_issuer FROM payments
+                WHERE created_at >= $1 AND source_asset_issuer IS NOT NULL
+            )
+            WHERE issuer LIKE 'G%'
+            ORDER BY issuer
+            LIMIT $2
+            "#,
+        )
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(issuers.into_iter().map(|(issuer,)| issuer).collect())
+    }
+
+    pub async fn get_anchor_home_domains(&self) -> Result<Vec<String>> {
+        let domains: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT DISTINCT home_domain FROM anchors
+            WHERE home_domain IS NOT NULL AND home_domain != ''
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(domains.into_iter().map(|(domain,)| domain).collect())
+    }
+
+    pub async fn get_anchor_id_by_home_domain(&self, home_domain: &str) -> Result<Option<Uuid>> {
+        let id: Option<(String,)> = sqlx::query_as(
+            r#"
+            SELECT id FROM anchors WHERE LOWER(home_domain) = LOWER($1) LIMIT 1
+            "#,
+        )
+        .bind(home_domain)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(id.and_then(|(id,)| Uuid::parse_str(&id).ok()))
+    }
+
+    /// The anchor for a discovered home domain, matched by domain or by
+    /// account and created when neither is known. Names of existing anchors
+    /// are left as they are.
+    pub async fn upsert_discovered_anchor(
+        &self,
+        name: &str,
+        stellar_account: &str,
+        home_domain: &str,
+    ) -> Result<Uuid> {
+        let existing: Option<(String,)> = sqlx::query_as(
+            r#"
+            SELECT id FROM anchors
+            WHERE LOWER(home_domain) = LOWER($1) OR stellar_account = $2
+            ORDER BY LOWER(home_domain) = LOWER($1) DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(home_domain)
+        .bind(stellar_account)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let id = match existing {
+            Some((id,)) => {
+                sqlx::query(
+                    r#"
+                    UPDATE anchors
+                    SET home_domain = $1, updated_at = CURRENT_TIMESTAMP
+                    WHERE id = $2
+                    "#,
+                )
+                .bind(home_domain)
+                .bind(&id)
+                .execute(&self.pool)
+                .await?;
+                id
+            }
+            None => {
+                let id = Uuid::new_v4().to_string();
+                sqlx::query(
+                    r#"
+                    INSERT INTO anchors (id, name, stellar_account, home_domain)
+                    VALUES ($1, $2, $3, $4)
+                    "#,
+                )
+                .bind(&id)
+                .bind(name)
+                .bind(stellar_account)
+                .bind(home_domain)
+                .execute(&self.pool)
+                .await?;
+                id
+            }
+        };
+
+        Ok(Uuid::parse_str(&id)?)
+    }
+
+    /// Register a currency listed in an anchor's stellar.toml as its asset
+    pub async fn upsert_discovered_asset(
+        &self,
+        anchor_id: Uuid,
+        asset_code: &str,
+        asset_issuer: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO assets (id, anchor_id, asset_code, asset_issuer)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (asset_code, asset_issuer) DO UPDATE
+            SET anchor_id = excluded.anchor_id,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(anchor_id.to_string())
+        .bind(asset_code)
+        .bind(asset_issuer)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store a successfully crawled stellar.toml, adding it to the history
+    /// when it differs from the last one. Returns whether it changed.
+    pub async fn record_stellar_toml(
+        &self,
+        anchor_id: Uuid,
+        home_domain: &str,
+        toml: &StellarToml,
+        validation_errors: &[String],
+        fetched_at: chrono::DateTime<Utc>,
+    ) -> Result<bool> {
+        let toml_hash = toml.content_hash();
+        let toml_json = serde_json::to_string(toml)?;
+        let validation_errors = serde_json::to_string(validation_errors)?;
+
+        let mut tx = self.pool.begin().await?;
+
+        let previous: Option<(String,)> =
+            sqlx::query_as("SELECT toml_hash FROM anchor_stellar_toml WHERE anchor_id = $1")
+                .bind(anchor_id.to_string())
+                .fetch_optional(&mut *tx)
+                .await?;
+        let changed = !matches!(previous, Some((hash,)) if hash == toml_hash);
+
+        if changed {
+            sqlx::query(
+                r#"
+                INSERT INTO anchor_stellar_toml_history (
+                    anchor_id, home_domain, toml_hash, toml, validation_errors, fetched_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#,
+            )
+            .bind(anchor_id.to_string())
+            .bind(home_domain)
+            .bind(&toml_hash)
+            .bind(&toml_json)
+            .bind(&validation_errors)
+            .bind(fetched_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO anchor_stellar_toml (
+                anchor_id, home_domain, signing_key, transfer_server, transfer_server_sep0024,
+                direct_payment_server, web_auth_endpoint, kyc_server, anchor_quote_server,
+                org_name, toml_hash, toml, validation_errors, fetch_error, changed_at, checked_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, NULL, $14, $14)
+            ON CONFLICT (anchor_id) DO UPDATE SET
+                home_domain = excluded.home_domain,
+                signing_key = excluded.signing_key,
+                transfer_server = excluded.transfer_server,
+                transfer_server_sep0024 = excluded.transfer_server_sep0024,
+                direct_payment_server = excluded.direct_payment_server,
+                web_auth_endpoint = excluded.web_auth_endpoint,
+                kyc_server = excluded.kyc_server,
+                anchor_quote_server = excluded.anchor_quote_server,
+                org_name = excluded.org_name,
+                toml_hash = excluded.toml_hash,
+                toml = excluded.toml,
+                validation_errors = excluded.validation_errors,
+                fetch_error = NULL,
+                changed_at = CASE WHEN $15 THEN excluded.changed_at ELSE changed_at END,
+                checked_at = excluded.checked_at
+            "#,
+        )
+        .bind(anchor_id.to_string())
+        .bind(home_domain)
+        .bind(&toml.signing_key)
+        .bind(&toml.transfer_server)
+        .bind(&toml.transfer_server_sep0024)
+        .bind(&toml.direct_payment_server)
+        .bind(&toml.web_auth_endpoint)
+        .bind(&toml.kyc_server)
+        .bind(&toml.anchor_quote_server)
+        .bind(&toml.documentation.org_name)
+        .bind(&toml_hash)
+        .bind(&toml_json)
+        .bind(&validation_errors)
+        .bind(fetched_at)
+        .bind(changed)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(changed)
+    }
+
+    /// Note a failed crawl on every anchor served by `home_domain`, keeping
+    /// their last good stellar.toml
+    pub async fn record_stellar_toml_error(
+        &self,
+        home_domain: &str,
+        error: &str,
+        checked_at: chrono::DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE anchor_stellar_toml
+            SET fetch_error = $1, checked_at = $2
+            WHERE home_domain = $3
+            "#,
+        )
+        .bind(error)
+        .bind(checked_at)
+        .bind(home_domain)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_anchor_stellar_toml(
+        &self,
+        anchor_id: Uuid,
+    ) -> Result<Option<AnchorStellarTomlRecord>> {
+        let row: Option<AnchorStellarTomlRow> = sqlx::query_as(
+            r#"
+            SELECT anchor_id, home_domain, toml_hash, toml, validation_errors, fetch_error,
+                   changed_at, checked_at
+            FROM anchor_stellar_toml
+            WHERE anchor_id = $1
+            "#,
+        )
+        .bind(anchor_id.to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(AnchorStellarTomlRecord::from))
+    }
+
+    /// Every anchor's latest stellar.toml
+    pub async fn get_anchor_stellar_tomls(&self) -> Result<Vec<AnchorStellarTomlRecord>> {
+        let rows: Vec<AnchorStellarTomlRow> = sqlx::query_as(
+            r#"
+            SELECT anchor_id, home_domain, toml_hash, toml, validation_errors, fetch_error,
+                   changed_at, checked_at
+            FROM anchor_stellar_toml
+            ORDER BY anchor_id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(AnchorStellarTomlRecord::from).collect())
+    }
+
+    pub async fn get_anchor_stellar_toml_history(
+        &self,
+        anchor_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<AnchorStellarTomlChange>> {
+        let rows: Vec<AnchorStellarTomlChangeRow> = sqlx::query_as(
+            r#"
+            SELECT home_domain, toml_hash, toml, validation_errors, fetched_at
+            FROM anchor_stellar_toml_history
+            WHERE anchor_id = $1
+            ORDER BY fetched_at DESC, id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(anchor_id.to_string())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| AnchorStellarTomlChange {
+                home_domain: row.home_domain,
+                toml: serde_json::from_str(&row.toml).unwrap_or_default(),
+                toml_hash: row.toml_hash,
+                validation_errors: serde_json::from_str(&row.validation_errors)
+                    .unwrap_or_default(),
+                fetched_at: row.fetched_at,
+            })
+            .collect())
+    }
+
+    // SEP endpoint probe operations
+
+    pub async fn record_anchor_endpoint_probe(&self, probe: &AnchorEndpointProbe) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO anchor_endpoint_probes (
+                anchor_id, sep, url, status_code, latency_ms, up, schema_valid, error, probed_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(&probe.anchor_id)
+        .bind(&probe.sep)
+        .bind(&probe.url)
+        .bind(probe.status_code)
+        .bind(probe.latency_ms)
+        .bind(probe.up)
+        .bind(probe.schema_valid)
+        .bind(&probe.error)
+        .bind(probe.probed_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Uptime and latency of each of an anchor's probed endpoints since `since`
+    pub async fn get_anchor_endpoint_uptime(
+        &self,
+        anchor_id: Uuid,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<Vec<AnchorEndpointUptime>> {
+        let uptime = sqlx::query_as::<_, AnchorEndpointUptime>(
+            r#"
+            SELECT
+                sep,
+                url,
+                COUNT(*) AS probes,
+                SUM(up) AS up_probes,
+                SUM(schema_valid) AS schema_valid_probes,
+                SUM(up) * 100.0 / COUNT(*) AS uptime_percentage,
+                AVG(CASE WHEN up THEN latency_ms END) AS avg_latency_ms,
+                MAX(probed_at) AS last_probed_at
+            FROM anchor_endpoint_probes
+            WHERE anchor_id = $1 AND probed_at >= $2
+            GROUP BY sep, url
+            ORDER BY sep, url
+            "#,
+        )
+        .bind(anchor_id.to_string())
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(uptime)
+    }
+
+    /// Share of an anchor's endpoint probes since `since` that got a 2xx
+    /// response, or `None` when none were probed
+    pub async fn get_anchor_uptime(
+        &self,
+        anchor_id: Uuid,
+        since: chrono::DateTime<Utc>,
+    ) -> Result<Option<f64>> {
+        let uptime: Option<f64> = sqlx::query_scalar(
+            r#"
+            SELECT SUM(up) * 100.0 / COUNT(*)
+            FROM anchor_endpoint_probes
+            WHERE anchor_id = $1 AND probed_at >= $2
+            "#,
+        )
+        .bind(anchor_id.to_string())
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(uptime)
+    }
+
+    /// Store an anchor's uptime along with the status it was folded into
+    pub async fn update_anchor_uptime(
+        &self,
+        anchor_id: Uuid,
+        uptime_percentage: Option<f64>,
+        status: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE anchors
+            SET uptime_percentage = $1, status = $2, updated_at = $3
+            WHERE id = $4
+            "#,
+        )
+        .bind(uptime_percentage)
+        .bind(status)
+        .bind(Utc::now())
+        .bind(anchor_id.to_string())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_anchor_endpoint_probes_before(
+        &self,
+        before: chrono::DateTime<Utc>,
+    ) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM anchor_endpoint_probes WHERE probed_at < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
     // Corridor operations
     pub async fn create_corridor(
         &self,
@@ -570,14 +1313,31 @@ impl Database {
     }
 
     pub async fn save_payments(&self, payments: Vec<crate::models::PaymentRecord>) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        self.save_payments_in(&mut tx, &payments).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Insert payments inside a caller's transaction, so they commit together
+    /// with whatever else it writes
+    pub async fn save_payments_in(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        payments: &[crate::models::PaymentRecord],
+    ) -> Result<()> {
         for payment in payments {
             sqlx::query(
                 r#"
                 INSERT INTO payments (
                     id, transaction_hash, source_account, destination_account,
-                    asset_type, asset_code, asset_issuer, amount, created_at
+                    asset_type, asset_code, asset_issuer, amount, created_at,
+                    operation_type, source_asset_code, source_asset_issuer, source_amount, path,
+                    successful, result_code,
+                    submission_time, confirmation_time, settlement_latency_ms, latency_source,
+                    send_max, dest_min
                 )
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22)
                 ON CONFLICT (id) DO NOTHING
                 "#,
             )
@@ -590,7 +1350,24 @@ impl Database {
             .bind(&payment.asset_issuer)
             .bind(payment.amount)
             .bind(payment.created_at)
-            .execute(&self.pool)
+            .bind(&payment.operation_type)
+            .bind(&payment.source_asset_code)
+            .bind(&payment.source_asset_issuer)
+            .bind(payment.source_amount)
+            .bind(&payment.path)
+            .bind(payment.successful)
+            .bind(&payment.result_code)
+            .bind(payment.submission_time)
+            .bind(payment.confirmation_time)
+            .bind(
+                payment
+                    .confirmation_time
+                    .and_then(|confirmed| settlement_latency_ms(payment.submission_time, confirmed)),
+            )
+            .bind(LatencySource::OnChain.as_str())
+            .bind(payment.send_max)
+            .bind(payment.dest_min)
+            .execute(&mut **tx)
             .await?;
         }
         Ok(())
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use chrono::NaiveDate;
 use sqlx::SqlitePool;
+use uuid::Uuid;
 
 use crate::models::corridor::{Corridor, CorridorAnalytics, CorridorMetrics};
 
@@ -26,10 +27,10 @@ impl CorridorAggregates {
             INSERT INTO corridor_metrics (
                 corridor_key, asset_a_code, asset_a_issuer, asset_b_code, asset_b_issuer,
                 date, total_transactions, successful_transactions, failed_transactions,
-                success_rate, volume_usd
+                success_rate, volume_usd, id, period
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
-            ON CONFLICT (corridor_key, date) DO UPDATE SET
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, 'daily')
+            ON CONFLICT (corridor_key, period, date) DO UPDATE SET
                 total_transactions = EXCLUDED.total_transactions,
                 successful_transactions = EXCLUDED.successful_transactions,
                 failed_transactions = EXCLUDED.failed_transactions,
@@ -50,6 +51,7 @@ impl CorridorAggregates {
         .bind(analytics.failed_transactions)
         .bind(analytics.success_rate)
         .bind(analytics.volume_usd)
+        .bind(Uuid::new_v4().to_string())
         .fetch_one(&self.pool)
         .await?;
 
@@ -69,7 +71,7 @@ impl CorridorAggregates {
         let metrics = sqlx::query_as::<_, CorridorMetrics>(
             r#"
             SELECT * FROM corridor_metrics
-            WHERE corridor_key = $1 AND date >= $2 AND date <= $3
+            WHERE period = 'daily' AND corridor_key = $1 AND date >= $2 AND date <= $3
             ORDER BY date DESC
             "#,
         )
@@ -92,7 +94,7 @@ impl CorridorAggregates {
         let metrics = sqlx::query_as::<_, CorridorMetrics>(
             r#"
             SELECT * FROM corridor_metrics
-            WHERE date >= $1 AND date < $2
+            WHERE period = 'daily' AND date >= $1 AND date < $2
             ORDER BY volume_usd DESC
             "#,
         )
@@ -127,7 +129,7 @@ impl CorridorAggregates {
                 SUM(volume_usd) as total_volume_usd,
                 MAX(date) as latest_date
             FROM corridor_metrics
-            WHERE date >= $1 AND date <= $2
+            WHERE period = 'daily' AND date >= $1 AND date <= $2
             GROUP BY corridor_key, asset_a_code, asset_a_issuer, asset_b_code, asset_b_issuer
             ORDER BY total_volume_usd DESC
             "#,
@@ -151,7 +153,7 @@ impl CorridorAggregates {
         let metrics = sqlx::query_as::<_, CorridorMetrics>(
             r#"
             SELECT * FROM corridor_metrics
-            WHERE date >= $1 AND date < $2
+            WHERE period = 'daily' AND date >= $1 AND date < $2
             ORDER BY volume_usd DESC
             LIMIT $3
             "#,
@@ -176,7 +178,7 @@ impl CorridorAggregates {
         let metrics = sqlx::query_as::<_, CorridorMetrics>(
             r#"
             SELECT * FROM corridor_metrics
-            WHERE date >= $1 AND date < $2
+            WHERE period = 'daily' AND date >= $1 AND date < $2
             ORDER BY total_transactions DESC
             LIMIT $3
             "#,
@@ -202,7 +204,7 @@ impl CorridorAggregates {
         let metrics = sqlx::query_as::<_, CorridorMetrics>(
             r#"
             SELECT * FROM corridor_metrics
-            WHERE date >= $1 AND date < $2
+            WHERE period = 'daily' AND date >= $1 AND date < $2
             AND success_rate >= $3
             AND total_transactions >= $4
             ORDER BY success_rate DESC, total_transactions DESC
@@ -236,7 +238,7 @@ impl CorridorAggregates {
                 SUM(volume_usd) as total_volume_usd,
                 AVG(success_rate) as avg_success_rate
             FROM corridor_metrics
-            WHERE date >= $1 AND date <= $2
+            WHERE period = 'daily' AND date >= $1 AND date <= $2
             "#,
         )
         .bind(start_datetime)
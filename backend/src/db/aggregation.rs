@@ -1,8 +1,12 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-use sqlx::SqlitePool;
+use sqlx::query::Query;
+use sqlx::sqlite::SqliteArguments;
+use sqlx::{Sqlite, SqlitePool};
 
-use crate::services::aggregation::HourlyCorridorMetrics;
+use crate::models::corridor::Corridor;
+use crate::services::aggregation::{CorridorRollup, HourlyCorridorMetrics, RollupPeriod};
+use crate::services::distribution::{CorridorSketches, Sketch};
 
 pub struct AggregationDb {
     pool: SqlitePool,
@@ -31,6 +35,17 @@ impl AggregationDb {
                 asset_code,
                 asset_issuer,
                 amount,
+                operation_type,
+                source_asset_code,
+                source_asset_issuer,
+                source_amount,
+                send_max,
+                dest_min,
+                path,
+                successful,
+                result_code,
+                submission_time,
+                confirmation_time,
                 created_at
             FROM payments
             WHERE created_at >= ? AND created_at <= ?
@@ -45,52 +60,220 @@ impl AggregationDb {
         .await
         .context("Failed to fetch payments by timerange")?;
 
-        // Convert to PaymentRecord with corridor information
-        let payment_records: Vec<crate::models::corridor::PaymentRecord> = records
+        Ok(records
             .into_iter()
-            .filter_map(|row| {
-                // Parse the created_at timestamp
-                let timestamp = DateTime::parse_from_rfc3339(&row.created_at)
-                    .ok()?
-                    .with_timezone(&Utc);
-
-                // For now, assume all payments are successful
-                // In a real system, you'd have a status field
-                let successful = true;
-
-                Some(crate::models::corridor::PaymentRecord {
-                    id: uuid::Uuid::parse_str(&row.id).ok()?,
-                    source_asset_code: row.asset_code.clone().unwrap_or_else(|| "XLM".to_string()),
-                    source_asset_issuer: row
-                        .asset_issuer
-                        .clone()
-                        .unwrap_or_else(|| "native".to_string()),
-                    destination_asset_code: row.asset_code.unwrap_or_else(|| "XLM".to_string()),
-                    destination_asset_issuer: row
-                        .asset_issuer
-                        .unwrap_or_else(|| "native".to_string()),
-                    amount: row.amount,
-                    successful,
-                    timestamp,
-                    submission_time: None,
-                    confirmation_time: None,
-                })
-            })
-            .collect();
+            .filter_map(payment_record_from_row)
+            .collect())
+    }
+
+    /// Fetch one corridor's payments within a time range, oldest first. The
+    /// corridor matches in either direction, with each payment's assets
+    /// resolved the way `payment_record_from_row` resolves them.
+    pub async fn fetch_corridor_payments_by_timerange(
+        &self,
+        corridor: &Corridor,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<crate::models::corridor::PaymentRecord>> {
+        let records = sqlx::query_as::<_, PaymentRecordRow>(
+            r#"
+            SELECT 
+                id,
+                transaction_hash,
+                source_account,
+                destination_account,
+                asset_type,
+                asset_code,
+                asset_issuer,
+                amount,
+                operation_type,
+                source_asset_code,
+                source_asset_issuer,
+                source_amount,
+                send_max,
+                dest_min,
+                path,
+                successful,
+                result_code,
+                submission_time,
+                confirmation_time,
+                created_at
+            FROM (
+                SELECT
+                    *,
+                    COALESCE(source_asset_code, asset_code, 'XLM') AS from_code,
+                    CASE
+                        WHEN source_asset_code IS NULL THEN COALESCE(asset_issuer, 'native')
+                        ELSE COALESCE(source_asset_issuer, 'native')
+                    END AS from_issuer,
+                    COALESCE(asset_code, 'XLM') AS to_code,
+                    COALESCE(asset_issuer, 'native') AS to_issuer
+                FROM payments
+                WHERE created_at >= ? AND created_at <= ?
+            )
+            WHERE (from_code = ? AND from_issuer = ? AND to_code = ? AND to_issuer = ?)
+               OR (from_code = ? AND from_issuer = ? AND to_code = ? AND to_issuer = ?)
+            ORDER BY created_at ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(start_time.to_rfc3339())
+        .bind(end_time.to_rfc3339())
+        .bind(&corridor.asset_a_code)
+        .bind(&corridor.asset_a_issuer)
+        .bind(&corridor.asset_b_code)
+        .bind(&corridor.asset_b_issuer)
+        .bind(&corridor.asset_b_code)
+        .bind(&corridor.asset_b_issuer)
+        .bind(&corridor.asset_a_code)
+        .bind(&corridor.asset_a_issuer)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch corridor payments by timerange")?;
 
-        Ok(payment_records)
+        Ok(records
+            .into_iter()
+            .filter_map(payment_record_from_row)
+            .collect())
     }
 
-    /// Upsert hourly corridor metric
+    /// Fetch one page of the payments created in `[start_time, end_time)`,
+    /// oldest first
+    pub async fn fetch_payments_page(
+        &self,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<crate::models::corridor::PaymentRecord>> {
+        let records = sqlx::query_as::<_, PaymentRecordRow>(
+            r#"
+            SELECT 
+                id,
+                transaction_hash,
+                source_account,
+                destination_account,
+                asset_type,
+                asset_code,
+                asset_issuer,
+                amount,
+                operation_type,
+                source_asset_code,
+                source_asset_issuer,
+                source_amount,
+                send_max,
+                dest_min,
+                path,
+                successful,
+                result_code,
+                submission_time,
+                confirmation_time,
+                created_at
+            FROM payments
+            WHERE created_at >= ? AND created_at < ?
+            ORDER BY created_at ASC, id ASC
+            LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(start_time.to_rfc3339())
+        .bind(end_time.to_rfc3339())
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch payments page")?;
+
+        Ok(records
+            .into_iter()
+            .filter_map(payment_record_from_row)
+            .collect())
+    }
+
+    /// Upsert hourly corridor metric, replacing any row already stored for
+    /// the corridor and hour
     pub async fn upsert_hourly_corridor_metric(
         &self,
         metric: &HourlyCorridorMetrics,
     ) -> Result<()> {
         let now = Utc::now().to_rfc3339();
 
-        sqlx::query(
+        bind_hourly_metric(
+            sqlx::query(
+                r#"
+                INSERT INTO corridor_metrics_hourly (
+                    id,
+                    corridor_key,
+                    asset_a_code,
+                    asset_a_issuer,
+                    asset_b_code,
+                    asset_b_issuer,
+                    hour_bucket,
+                    total_transactions,
+                    successful_transactions,
+                    failed_transactions,
+                    success_rate,
+                    volume_usd,
+                    avg_slippage_bps,
+                    avg_fx_rate,
+                    avg_settlement_latency_ms,
+                    p50_settlement_latency_ms,
+                    p90_settlement_latency_ms,
+                    p99_settlement_latency_ms,
+                    liquidity_depth_usd,
+                    failure_breakdown,
+                    settlement_latency_sketch,
+                    payment_size_sketch,
+                    created_at,
+                    updated_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(corridor_key, hour_bucket) DO UPDATE SET
+                    total_transactions = excluded.total_transactions,
+                    successful_transactions = excluded.successful_transactions,
+                    failed_transactions = excluded.failed_transactions,
+                    success_rate = excluded.success_rate,
+                    volume_usd = excluded.volume_usd,
+                    avg_slippage_bps = excluded.avg_slippage_bps,
+                    avg_fx_rate = excluded.avg_fx_rate,
+                    avg_settlement_latency_ms = excluded.avg_settlement_latency_ms,
+                    p50_settlement_latency_ms = excluded.p50_settlement_latency_ms,
+                    p90_settlement_latency_ms = excluded.p90_settlement_latency_ms,
+                    p99_settlement_latency_ms = excluded.p99_settlement_latency_ms,
+                    liquidity_depth_usd = excluded.liquidity_depth_usd,
+                    failure_breakdown = excluded.failure_breakdown,
+                    settlement_latency_sketch = excluded.settlement_latency_sketch,
+                    payment_size_sketch = excluded.payment_size_sketch,
+                    updated_at = excluded.updated_at
+                "#,
+            )
+            .bind(&metric.id)
+            .bind(&metric.corridor_key)
+            .bind(&metric.asset_a_code)
+            .bind(&metric.asset_a_issuer)
+            .bind(&metric.asset_b_code)
+            .bind(&metric.asset_b_issuer)
+            .bind(metric.hour_bucket.to_rfc3339()),
+            metric,
+        )?
+        .bind(&now)
+        .bind(&now)
+        .execute(&self.pool)
+        .await
+        .context("Failed to upsert hourly corridor metric")?;
+
+        Ok(())
+    }
+
+    /// Fetch hourly metrics by time range
+    pub async fn fetch_hourly_metrics_by_timerange(
+        &self,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<Vec<HourlyCorridorMetrics>> {
+        let rows = sqlx::query_as::<_, HourlyCorridorMetricsRow>(
             r#"
-            INSERT INTO corridor_metrics_hourly (
+            SELECT 
                 id,
                 corridor_key,
                 asset_a_code,
@@ -104,55 +287,33 @@ impl AggregationDb {
                 success_rate,
                 volume_usd,
                 avg_slippage_bps,
+                avg_fx_rate,
                 avg_settlement_latency_ms,
+                p50_settlement_latency_ms,
+                p90_settlement_latency_ms,
+                p99_settlement_latency_ms,
                 liquidity_depth_usd,
-                created_at,
-                updated_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-            ON CONFLICT(corridor_key, hour_bucket) DO UPDATE SET
-                total_transactions = total_transactions + excluded.total_transactions,
-                successful_transactions = successful_transactions + excluded.successful_transactions,
-                failed_transactions = failed_transactions + excluded.failed_transactions,
-                success_rate = (successful_transactions * 100.0) / NULLIF(total_transactions, 0),
-                volume_usd = volume_usd + excluded.volume_usd,
-                avg_slippage_bps = (avg_slippage_bps + excluded.avg_slippage_bps) / 2.0,
-                avg_settlement_latency_ms = COALESCE(
-                    (avg_settlement_latency_ms + excluded.avg_settlement_latency_ms) / 2,
-                    avg_settlement_latency_ms,
-                    excluded.avg_settlement_latency_ms
-                ),
-                liquidity_depth_usd = (liquidity_depth_usd + excluded.liquidity_depth_usd) / 2.0,
-                updated_at = ?
+                failure_breakdown,
+                settlement_latency_sketch,
+                payment_size_sketch
+            FROM corridor_metrics_hourly
+            WHERE hour_bucket >= ? AND hour_bucket <= ?
+            ORDER BY hour_bucket ASC
             "#,
         )
-        .bind(&metric.id)
-        .bind(&metric.corridor_key)
-        .bind(&metric.asset_a_code)
-        .bind(&metric.asset_a_issuer)
-        .bind(&metric.asset_b_code)
-        .bind(&metric.asset_b_issuer)
-        .bind(metric.hour_bucket.to_rfc3339())
-        .bind(metric.total_transactions)
-        .bind(metric.successful_transactions)
-        .bind(metric.failed_transactions)
-        .bind(metric.success_rate)
-        .bind(metric.volume_usd)
-        .bind(metric.avg_slippage_bps)
-        .bind(metric.avg_settlement_latency_ms)
-        .bind(metric.liquidity_depth_usd)
-        .bind(&now)
-        .bind(&now)
-        .bind(&now)
-        .execute(&self.pool)
+        .bind(start_time.to_rfc3339())
+        .bind(end_time.to_rfc3339())
+        .fetch_all(&self.pool)
         .await
-        .context("Failed to upsert hourly corridor metric")?;
+        .context("Failed to fetch hourly metrics by timerange")?;
 
-        Ok(())
+        Ok(hourly_metrics_from_rows(rows))
     }
 
-    /// Fetch hourly metrics by time range
-    pub async fn fetch_hourly_metrics_by_timerange(
+    /// Fetch hourly metrics for one corridor by time range
+    pub async fn fetch_corridor_hourly_metrics(
         &self,
+        corridor_key: &str,
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
     ) -> Result<Vec<HourlyCorridorMetrics>> {
@@ -172,47 +333,86 @@ impl AggregationDb {
                 success_rate,
                 volume_usd,
                 avg_slippage_bps,
+                avg_fx_rate,
                 avg_settlement_latency_ms,
-                liquidity_depth_usd
+                p50_settlement_latency_ms,
+                p90_settlement_latency_ms,
+                p99_settlement_latency_ms,
+                liquidity_depth_usd,
+                failure_breakdown,
+                settlement_latency_sketch,
+                payment_size_sketch
             FROM corridor_metrics_hourly
-            WHERE hour_bucket >= ? AND hour_bucket <= ?
+            WHERE corridor_key = ? AND hour_bucket >= ? AND hour_bucket <= ?
             ORDER BY hour_bucket ASC
             "#,
         )
+        .bind(corridor_key)
         .bind(start_time.to_rfc3339())
         .bind(end_time.to_rfc3339())
         .fetch_all(&self.pool)
         .await
-        .context("Failed to fetch hourly metrics by timerange")?;
+        .context("Failed to fetch corridor hourly metrics")?;
 
-        let metrics: Vec<HourlyCorridorMetrics> = rows
-            .into_iter()
-            .filter_map(|row| {
-                let hour_bucket = DateTime::parse_from_rfc3339(&row.hour_bucket)
-                    .ok()?
-                    .with_timezone(&Utc);
-
-                Some(HourlyCorridorMetrics {
-                    id: row.id,
-                    corridor_key: row.corridor_key,
-                    asset_a_code: row.asset_a_code,
-                    asset_a_issuer: row.asset_a_issuer,
-                    asset_b_code: row.asset_b_code,
-                    asset_b_issuer: row.asset_b_issuer,
-                    hour_bucket,
-                    total_transactions: row.total_transactions,
-                    successful_transactions: row.successful_transactions,
-                    failed_transactions: row.failed_transactions,
-                    success_rate: row.success_rate,
-                    volume_usd: row.volume_usd,
-                    avg_slippage_bps: row.avg_slippage_bps,
-                    avg_settlement_latency_ms: row.avg_settlement_latency_ms,
-                    liquidity_depth_usd: row.liquidity_depth_usd,
-                })
-            })
-            .collect();
+        Ok(hourly_metrics_from_rows(rows))
+    }
+
+    /// Fetch hourly metrics by time range for every corridor with an asset
+    /// issued by one of `issuers`
+    pub async fn fetch_issuer_hourly_metrics(
+        &self,
+        issuers: &[String],
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<Vec<HourlyCorridorMetrics>> {
+        if issuers.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let issuers_json = serde_json::to_string(issuers)?;
+        let rows = sqlx::query_as::<_, HourlyCorridorMetricsRow>(
+            r#"
+            SELECT 
+                id,
+                corridor_key,
+                asset_a_code,
+                asset_a_issuer,
+                asset_b_code,
+                asset_b_issuer,
+                hour_bucket,
+                total_transactions,
+                successful_transactions,
+                failed_transactions,
+                success_rate,
+                volume_usd,
+                avg_slippage_bps,
+                avg_fx_rate,
+                avg_settlement_latency_ms,
+                p50_settlement_latency_ms,
+                p90_settlement_latency_ms,
+                p99_settlement_latency_ms,
+                liquidity_depth_usd,
+                failure_breakdown,
+                settlement_latency_sketch,
+                payment_size_sketch
+            FROM corridor_metrics_hourly
+            WHERE hour_bucket >= ? AND hour_bucket <= ?
+              AND (
+                asset_a_issuer IN (SELECT value FROM json_each(?))
+                OR asset_b_issuer IN (SELECT value FROM json_each(?))
+              )
+            ORDER BY hour_bucket ASC
+            "#,
+        )
+        .bind(start_time.to_rfc3339())
+        .bind(end_time.to_rfc3339())
+        .bind(&issuers_json)
+        .bind(&issuers_json)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch issuer hourly metrics")?;
 
-        Ok(metrics)
+        Ok(hourly_metrics_from_rows(rows))
     }
 
     /// Create aggregation job record
@@ -328,6 +528,146 @@ impl AggregationDb {
 
         Ok(())
     }
+
+    /// Last hour a completed job of `job_type` processed: the last hour the
+    /// hourly job aggregated, or the start of the last period a rollup closed
+    pub async fn last_processed_hour(&self, job_type: &str) -> Result<Option<DateTime<Utc>>> {
+        let row: Option<(String,)> = sqlx::query_as(
+            r#"
+            SELECT last_processed_hour FROM aggregation_jobs
+            WHERE job_type = ? AND status = 'completed' AND last_processed_hour IS NOT NULL
+            ORDER BY last_processed_hour DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(job_type)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch last processed hour")?;
+
+        Ok(row
+            .and_then(|(hour,)| DateTime::parse_from_rfc3339(&hour).ok())
+            .map(|hour| hour.with_timezone(&Utc)))
+    }
+
+    /// Earliest hour bucket with corridor metrics
+    pub async fn earliest_hour_bucket(&self) -> Result<Option<DateTime<Utc>>> {
+        let row: (Option<String>,) =
+            sqlx::query_as("SELECT MIN(hour_bucket) FROM corridor_metrics_hourly")
+                .fetch_one(&self.pool)
+                .await
+                .context("Failed to fetch earliest hour bucket")?;
+
+        Ok(row
+            .0
+            .and_then(|hour| DateTime::parse_from_rfc3339(&hour).ok())
+            .map(|hour| hour.with_timezone(&Utc)))
+    }
+
+    /// Close time of the latest ingested ledger, if any ledgers were ingested
+    pub async fn latest_ledger_close_time(&self) -> Result<Option<DateTime<Utc>>> {
+        let row: (Option<DateTime<Utc>>,) = sqlx::query_as("SELECT MAX(close_time) FROM ledgers")
+            .fetch_one(&self.pool)
+            .await
+            .context("Failed to fetch latest ledger close time")?;
+
+        Ok(row.0)
+    }
+
+    /// Insert a corridor rollup, replacing any earlier one for the same period
+    pub async fn upsert_corridor_rollup(&self, rollup: &CorridorRollup) -> Result<()> {
+        let metric = &rollup.metrics;
+        let query = sqlx::query(
+            r#"
+            INSERT INTO corridor_metrics (
+                id, corridor_key, asset_a_code, asset_a_issuer, asset_b_code, asset_b_issuer,
+                period, date, hours,
+                total_transactions, successful_transactions, failed_transactions,
+                success_rate, volume_usd, avg_slippage_bps, avg_fx_rate,
+                avg_settlement_latency_ms, median_settlement_latency_ms,
+                p90_settlement_latency_ms, p99_settlement_latency_ms,
+                liquidity_depth_usd, failure_breakdown,
+                settlement_latency_sketch, payment_size_sketch
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (corridor_key, period, date) DO UPDATE SET
+                hours = excluded.hours,
+                total_transactions = excluded.total_transactions,
+                successful_transactions = excluded.successful_transactions,
+                failed_transactions = excluded.failed_transactions,
+                success_rate = excluded.success_rate,
+                volume_usd = excluded.volume_usd,
+                avg_slippage_bps = excluded.avg_slippage_bps,
+                avg_fx_rate = excluded.avg_fx_rate,
+                avg_settlement_latency_ms = excluded.avg_settlement_latency_ms,
+                median_settlement_latency_ms = excluded.median_settlement_latency_ms,
+                p90_settlement_latency_ms = excluded.p90_settlement_latency_ms,
+                p99_settlement_latency_ms = excluded.p99_settlement_latency_ms,
+                liquidity_depth_usd = excluded.liquidity_depth_usd,
+                failure_breakdown = excluded.failure_breakdown,
+                settlement_latency_sketch = excluded.settlement_latency_sketch,
+                payment_size_sketch = excluded.payment_size_sketch,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(&metric.id)
+        .bind(&metric.corridor_key)
+        .bind(&metric.asset_a_code)
+        .bind(&metric.asset_a_issuer)
+        .bind(&metric.asset_b_code)
+        .bind(&metric.asset_b_issuer)
+        .bind(rollup.period.as_str())
+        .bind(metric.hour_bucket.to_rfc3339())
+        .bind(rollup.hours);
+
+        bind_hourly_metric(query, metric)?
+            .execute(&self.pool)
+            .await
+            .context("Failed to upsert corridor rollup")?;
+
+        Ok(())
+    }
+
+    /// Fetch rollups of `period` starting within a time range
+    pub async fn fetch_corridor_rollups(
+        &self,
+        period: RollupPeriod,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<Vec<CorridorRollup>> {
+        let rows = sqlx::query_as::<_, CorridorRollupRow>(
+            r#"
+            SELECT
+                hours, id, corridor_key, asset_a_code, asset_a_issuer, asset_b_code, asset_b_issuer,
+                date AS hour_bucket, total_transactions, successful_transactions, failed_transactions,
+                success_rate, volume_usd, COALESCE(avg_slippage_bps, 0) AS avg_slippage_bps, avg_fx_rate,
+                avg_settlement_latency_ms, median_settlement_latency_ms AS p50_settlement_latency_ms,
+                p90_settlement_latency_ms, p99_settlement_latency_ms,
+                COALESCE(liquidity_depth_usd, 0) AS liquidity_depth_usd, failure_breakdown,
+                settlement_latency_sketch, payment_size_sketch
+            FROM corridor_metrics
+            WHERE period = ? AND date >= ? AND date <= ?
+            ORDER BY date ASC, corridor_key ASC
+            "#,
+        )
+        .bind(period.as_str())
+        .bind(start_time.to_rfc3339())
+        .bind(end_time.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch corridor rollups")?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(CorridorRollup {
+                    period,
+                    hours: row.hours,
+                    metrics: hourly_metric_from_row(row.metric)?,
+                })
+            })
+            .collect())
+    }
 }
 
 // Database row structures
@@ -342,9 +682,153 @@ struct PaymentRecordRow {
     asset_code: Option<String>,
     asset_issuer: Option<String>,
     amount: f64,
+    operation_type: Option<String>,
+    source_asset_code: Option<String>,
+    source_asset_issuer: Option<String>,
+    source_amount: Option<f64>,
+    send_max: Option<f64>,
+    dest_min: Option<f64>,
+    path: Option<String>,
+    successful: bool,
+    result_code: Option<String>,
+    submission_time: Option<DateTime<Utc>>,
+    confirmation_time: Option<DateTime<Utc>>,
     created_at: String,
 }
 
+/// A payment row with its corridor assets, or `None` if its id or timestamp
+/// don't parse
+fn payment_record_from_row(
+    row: PaymentRecordRow,
+) -> Option<crate::models::corridor::PaymentRecord> {
+    // Parse the created_at timestamp
+    let timestamp = DateTime::parse_from_rfc3339(&row.created_at)
+        .ok()?
+        .with_timezone(&Utc);
+
+    let destination_asset_code = row.asset_code.unwrap_or_else(|| "XLM".to_string());
+    let destination_asset_issuer = row.asset_issuer.unwrap_or_else(|| "native".to_string());
+
+    // Path payments debit their own source asset; plain payments move a
+    // single asset
+    let (source_asset_code, source_asset_issuer) = match row.source_asset_code {
+        Some(code) => (
+            code,
+            row.source_asset_issuer
+                .unwrap_or_else(|| "native".to_string()),
+        ),
+        None => (
+            destination_asset_code.clone(),
+            destination_asset_issuer.clone(),
+        ),
+    };
+
+    Some(crate::models::corridor::PaymentRecord {
+        // Payments ingested from Horizon or ledger meta are keyed by their
+        // operation id, which maps onto a stable UUID
+        id: uuid::Uuid::parse_str(&row.id)
+            .unwrap_or_else(|_| uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_OID, row.id.as_bytes())),
+        source_asset_code,
+        source_asset_issuer,
+        destination_asset_code,
+        destination_asset_issuer,
+        amount: row.amount,
+        source_amount: row.source_amount,
+        send_max: row.send_max,
+        dest_min: row.dest_min,
+        path: row
+            .path
+            .and_then(|p| serde_json::from_str(&p).ok())
+            .unwrap_or_default(),
+        successful: row.successful,
+        failure_reason: (!row.successful)
+            .then(|| row.result_code.unwrap_or_else(|| "tx_failed".to_string())),
+        timestamp,
+        submission_time: row.submission_time,
+        confirmation_time: row.confirmation_time,
+    })
+}
+
+/// Binds an hourly metric's measurements, from `total_transactions` through
+/// the sketches, in column order
+fn bind_hourly_metric<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    metric: &HourlyCorridorMetrics,
+) -> Result<Query<'q, Sqlite, SqliteArguments<'q>>> {
+    Ok(query
+        .bind(metric.total_transactions)
+        .bind(metric.successful_transactions)
+        .bind(metric.failed_transactions)
+        .bind(metric.success_rate)
+        .bind(metric.volume_usd)
+        .bind(metric.avg_slippage_bps)
+        .bind(metric.avg_fx_rate)
+        .bind(metric.avg_settlement_latency_ms)
+        .bind(metric.p50_settlement_latency_ms)
+        .bind(metric.p90_settlement_latency_ms)
+        .bind(metric.p99_settlement_latency_ms)
+        .bind(metric.liquidity_depth_usd)
+        .bind(serde_json::to_string(&metric.failure_breakdown)?)
+        .bind(metric.sketches.settlement_latency_ms.to_bytes()?)
+        .bind(metric.sketches.payment_size.to_bytes()?))
+}
+
+/// Parses hourly rows, dropping any whose hour bucket isn't RFC 3339
+fn hourly_metrics_from_rows(rows: Vec<HourlyCorridorMetricsRow>) -> Vec<HourlyCorridorMetrics> {
+    rows.into_iter()
+        .filter_map(hourly_metric_from_row)
+        .collect()
+}
+
+fn hourly_metric_from_row(row: HourlyCorridorMetricsRow) -> Option<HourlyCorridorMetrics> {
+    let hour_bucket = DateTime::parse_from_rfc3339(&row.hour_bucket)
+        .ok()?
+        .with_timezone(&Utc);
+    // Rows from before sketches were kept start out empty
+    let sketch = |bytes: Option<Vec<u8>>| {
+        bytes
+            .and_then(|b| Sketch::from_bytes(&b).ok())
+            .unwrap_or_default()
+    };
+
+    Some(HourlyCorridorMetrics {
+        id: row.id,
+        corridor_key: row.corridor_key,
+        asset_a_code: row.asset_a_code,
+        asset_a_issuer: row.asset_a_issuer,
+        asset_b_code: row.asset_b_code,
+        asset_b_issuer: row.asset_b_issuer,
+        hour_bucket,
+        total_transactions: row.total_transactions,
+        successful_transactions: row.successful_transactions,
+        failed_transactions: row.failed_transactions,
+        success_rate: row.success_rate,
+        volume_usd: row.volume_usd,
+        avg_slippage_bps: row.avg_slippage_bps,
+        avg_fx_rate: row.avg_fx_rate,
+        avg_settlement_latency_ms: row.avg_settlement_latency_ms,
+        p50_settlement_latency_ms: row.p50_settlement_latency_ms,
+        p90_settlement_latency_ms: row.p90_settlement_latency_ms,
+        p99_settlement_latency_ms: row.p99_settlement_latency_ms,
+        liquidity_depth_usd: row.liquidity_depth_usd,
+        failure_breakdown: row
+            .failure_breakdown
+            .and_then(|b| serde_json::from_str(&b).ok())
+            .unwrap_or_default(),
+        sketches: CorridorSketches {
+            settlement_latency_ms: sketch(row.settlement_latency_sketch),
+            payment_size: sketch(row.payment_size_sketch),
+        },
+    })
+}
+
+#[derive(sqlx::FromRow)]
+struct CorridorRollupRow {
+    hours: i64,
+    #[sqlx(flatten)]
+    metric: HourlyCorridorMetricsRow,
+}
+
 #[derive(sqlx::FromRow)]
 struct HourlyCorridorMetricsRow {
     id: String,
@@ -360,6 +844,13 @@ struct HourlyCorridorMetricsRow {
     success_rate: f64,
     volume_usd: f64,
     avg_slippage_bps: f64,
+    avg_fx_rate: Option<f64>,
     avg_settlement_latency_ms: Option<i32>,
+    p50_settlement_latency_ms: Option<i32>,
+    p90_settlement_latency_ms: Option<i32>,
+    p99_settlement_latency_ms: Option<i32>,
     liquidity_depth_usd: f64,
+    failure_breakdown: Option<String>,
+    settlement_latency_sketch: Option<Vec<u8>>,
+    payment_size_sketch: Option<Vec<u8>>,
 }
@@ -4,6 +4,7 @@ use chrono::{Utc, Datelike};
 
 use crate::cache::CacheManager;
 use crate::rpc::StellarRpcClient;
+use crate::services::settlement_latency::LatencySummary;
 use crate::email::service::EmailService;
 use crate::email::report::{DigestReport, CorridorSummary, AnchorSummary, generate_html_report};
 
@@ -78,14 +79,21 @@ impl DigestScheduler {
 
         let mut corridors: Vec<CorridorSummary> = corridor_map.iter()
             .map(|(id, payments)| {
-                let volume: f64 = payments.iter()
+                let successful: Vec<_> = payments.iter()
+                    .filter(|p| p.transaction_successful)
+                    .collect();
+                let volume: f64 = successful.iter()
                     .filter_map(|p| p.amount.parse::<f64>().ok())
                     .sum();
                 CorridorSummary {
                     id: id.clone(),
-                    success_rate: 100.0,
+                    success_rate: successful.len() as f64 / payments.len() as f64 * 100.0,
                     volume_usd: volume,
-                    avg_latency_ms: 450.0,
+                    avg_latency_ms: LatencySummary::from_samples(
+                        successful.iter().filter_map(|p| p.settlement_latency_ms()).collect(),
+                    )
+                    .map(|summary| summary.average_ms)
+                    .unwrap_or(0.0),
                     change_pct: 5.2,
                 }
             })
@@ -1,18 +1,27 @@
 use anyhow::{Context, Result};
-use chrono::{DateTime, TimeZone, Utc};
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
 use sqlx::SqlitePool;
 use std::sync::Arc;
-use tracing::{info, warn};
+use tracing::{error, info, warn};
 
-use crate::rpc::{GetLedgersResult, RpcLedger, StellarRpcClient};
+use crate::database::Database;
+use crate::ingestion::ledger_meta::{decode_ledger_close_meta, DecodedLedger};
+use crate::models::PaymentRecord;
+use crate::rpc::{LedgerSource, RpcLedger};
 use crate::services::account_merge_detector::AccountMergeDetector;
 use crate::services::fee_bump_tracker::FeeBumpTrackerService;
+use crate::services::settlement_latency::{
+    settlement_latency_ms, LatencySource, SettlementLatencyService,
+};
 
 /// Ledger ingestion service that fetches and persists ledgers sequentially
 pub struct LedgerIngestionService {
-    rpc_client: Arc<StellarRpcClient>,
+    ledger_source: Arc<dyn LedgerSource>,
     fee_bump_tracker: Arc<FeeBumpTrackerService>,
     account_merge_detector: Arc<AccountMergeDetector>,
+    db: Database,
     pool: SqlitePool,
 }
 
@@ -20,26 +29,187 @@ pub struct LedgerIngestionService {
 #[derive(Debug, Clone)]
 pub struct ExtractedPayment {
     pub ledger_sequence: u64,
+    pub operation_id: String,
     pub transaction_hash: String,
     pub operation_type: String,
     pub source_account: String,
     pub destination: String,
+    /// Delivered asset; for path payments this is the destination asset
     pub asset_code: Option<String>,
     pub asset_issuer: Option<String>,
+    /// Delivered amount
     pub amount: String,
+    /// Asset and amount debited from the sender, set for path payments only
+    pub source_asset_code: Option<String>,
+    pub source_asset_issuer: Option<String>,
+    pub source_amount: Option<String>,
+    /// Most a strict-receive path payment allowed to be debited
+    pub send_max: Option<String>,
+    /// Least a strict-send path payment allowed to be delivered
+    pub dest_min: Option<String>,
+    /// Intermediate assets a path payment crossed, as `CODE:ISSUER` keys
+    pub path: Vec<String>,
+    /// Whether the enclosing transaction was applied
+    pub successful: bool,
+    /// Horizon-style result code, e.g. `op_success`, `op_underfunded` or `tx_bad_seq`
+    pub result_code: String,
+    /// The transaction's `valid_after` bound, when the sender set one
+    pub submission_time: Option<DateTime<Utc>>,
+    /// Close time of the ledger the payment landed in
+    pub confirmation_time: DateTime<Utc>,
+}
+
+impl ExtractedPayment {
+    /// The payment as a `payments` row, keyed by operation id like Horizon's,
+    /// so corridor aggregation sees it exactly once whichever path stored it
+    pub fn to_payment_record(&self) -> Result<PaymentRecord> {
+        let asset_type = match &self.asset_code {
+            None => "native",
+            Some(code) if code.len() <= 4 => "credit_alphanum4",
+            Some(_) => "credit_alphanum12",
+        };
+        let amount = self
+            .amount
+            .parse()
+            .with_context(|| format!("Invalid amount {}", self.amount))?;
+        let source_amount = self
+            .source_amount
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .with_context(|| format!("Invalid source amount {:?}", self.source_amount))?;
+        let send_max = self
+            .send_max
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .with_context(|| format!("Invalid send max {:?}", self.send_max))?;
+        let dest_min = self
+            .dest_min
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .with_context(|| format!("Invalid destination min {:?}", self.dest_min))?;
+
+        Ok(PaymentRecord {
+            id: self.operation_id.clone(),
+            transaction_hash: self.transaction_hash.clone(),
+            source_account: self.source_account.clone(),
+            destination_account: self.destination.clone(),
+            asset_type: asset_type.to_string(),
+            asset_code: self.asset_code.clone(),
+            asset_issuer: self.asset_issuer.clone(),
+            source_asset_code: self
+                .source_asset_code
+                .clone()
+                .or_else(|| self.asset_code.clone())
+                .unwrap_or_else(|| "XLM".to_string()),
+            source_asset_issuer: self
+                .source_asset_issuer
+                .clone()
+                .or_else(|| self.asset_issuer.clone())
+                .unwrap_or_else(|| "native".to_string()),
+            destination_asset_code: self.asset_code.clone().unwrap_or_else(|| "XLM".to_string()),
+            destination_asset_issuer: self
+                .asset_issuer
+                .clone()
+                .unwrap_or_else(|| "native".to_string()),
+            amount,
+            operation_type: Some(self.operation_type.clone()),
+            source_amount,
+            send_max,
+            dest_min,
+            path: (!self.path.is_empty())
+                .then(|| serde_json::to_string(&self.path))
+                .transpose()?,
+            successful: self.successful,
+            result_code: Some(self.result_code.clone()),
+            timestamp: Some(self.confirmation_time),
+            submission_time: self.submission_time,
+            confirmation_time: Some(self.confirmation_time),
+            created_at: self.confirmation_time,
+        })
+    }
+}
+
+/// Historical backfill over a closed ledger range, split across concurrent workers
+#[derive(Debug, Clone)]
+pub struct BackfillConfig {
+    pub start_ledger: u64,
+    pub end_ledger: u64,
+    pub chunk_size: u64,
+    pub workers: usize,
+    pub batch_size: u32,
+}
+
+impl BackfillConfig {
+    /// I'm reading backfill settings from the environment; no range means no backfill
+    pub fn from_env() -> Option<Self> {
+        let start_ledger = std::env::var("BACKFILL_START_LEDGER").ok()?.parse().ok()?;
+        let end_ledger = std::env::var("BACKFILL_END_LEDGER").ok()?.parse().ok()?;
+
+        Some(Self {
+            start_ledger,
+            end_ledger,
+            chunk_size: std::env::var("BACKFILL_CHUNK_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1_000),
+            workers: std::env::var("BACKFILL_WORKERS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(4),
+            batch_size: std::env::var("BACKFILL_BATCH_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(100),
+        })
+    }
+
+    /// I'm splitting the range into inclusive chunks; boundaries are stable across restarts
+    pub fn chunks(&self) -> Vec<(u64, u64)> {
+        let chunk_size = self.chunk_size.max(1);
+        let mut chunks = Vec::new();
+        let mut start = self.start_ledger;
+
+        while start <= self.end_ledger {
+            let end = start.saturating_add(chunk_size - 1).min(self.end_ledger);
+            chunks.push((start, end));
+            if end == u64::MAX {
+                break;
+            }
+            start = end + 1;
+        }
+
+        chunks
+    }
+}
+
+/// Outcome of a backfill run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BackfillSummary {
+    pub chunks_total: usize,
+    pub chunks_completed: usize,
+    pub chunks_skipped: usize,
+    pub chunks_failed: usize,
+    pub ledgers_processed: u64,
+    /// First and last ledger close time of each chunk completed by this run,
+    /// for re-aggregating the hours it filled in
+    pub completed_windows: Vec<(DateTime<Utc>, DateTime<Utc>)>,
 }
 
 impl LedgerIngestionService {
     pub fn new(
-        rpc_client: Arc<StellarRpcClient>,
+        ledger_source: Arc<dyn LedgerSource>,
         fee_bump_tracker: Arc<FeeBumpTrackerService>,
         account_merge_detector: Arc<AccountMergeDetector>,
         pool: SqlitePool,
     ) -> Self {
         Self {
-            rpc_client,
+            ledger_source,
             fee_bump_tracker,
             account_merge_detector,
+            db: Database::new(pool.clone()),
             pool,
         }
     }
@@ -51,7 +221,7 @@ impl LedgerIngestionService {
             Some(l) => Some(l + 1),
             None => {
                 let health = self
-                    .rpc_client
+                    .ledger_source
                     .check_health()
                     .await
                     .context("Failed to check health")?;
@@ -65,99 +235,204 @@ impl LedgerIngestionService {
         );
 
         let result = self
-            .rpc_client
+            .ledger_source
             .fetch_ledgers(start_ledger, batch_size, cursor.as_deref())
             .await
             .context("Failed to fetch ledgers")?;
 
-        let count = self.process_ledgers(&result).await?;
+        let count = self
+            .process_ledgers(&result.ledgers, DecodeFailure::RecordAndSkip)
+            .await?;
+        self.reconcile_settlement_latency().await;
 
-        // I'm saving cursor for restart safety
-        if let Some(new_cursor) = &result.cursor {
-            self.save_cursor(new_cursor, result.ledgers.last().map(|l| l.sequence))
-                .await?;
+        // I'm saving cursor for restart safety; an empty page keeps the last ledger
+        if let (Some(new_cursor), Some(last)) = (&result.cursor, result.ledgers.last()) {
+            self.save_cursor(new_cursor, last.sequence).await?;
         }
 
         Ok(count)
     }
 
-    /// I'm processing and persisting fetched ledgers
-    async fn process_ledgers(&self, result: &GetLedgersResult) -> Result<u64> {
-        let mut count = 0u64;
+    /// I'm backfilling a historical range with `workers` chunks in flight at once.
+    ///
+    /// Each chunk records its last processed ledger in `ingestion_state`, keyed
+    /// by the chunk's configured range, so a restarted backfill with the same
+    /// config resumes where it stopped. A chunk stops at the first ledger it
+    /// can't ingest or that the source is missing, leaving its checkpoint
+    /// before that ledger so the next run retries it. Chunks are clipped to the
+    /// live tail cursor: everything after it belongs to `run_ingestion`, and
+    /// inserts are idempotent where the two overlap.
+    pub async fn run_backfill(&self, config: &BackfillConfig) -> Result<BackfillSummary> {
+        let last_live = self.get_last_ledger().await?;
+        let chunks: Vec<(u64, u64, u64)> = config
+            .chunks()
+            .into_iter()
+            .map(|(start, end)| (start, end, last_live.map_or(end, |live| end.min(live))))
+            .filter(|(start, _, clipped_end)| start <= clipped_end)
+            .collect();
+        let mut summary = BackfillSummary {
+            chunks_total: chunks.len(),
+            ..Default::default()
+        };
 
-        for ledger in &result.ledgers {
-            if let Err(e) = self.persist_ledger(ledger).await {
-                warn!("Failed to persist ledger {}: {}", ledger.sequence, e);
-                continue;
-            }
+        info!(
+            "Starting backfill of ledgers {}..={} in {} chunks with {} workers",
+            config.start_ledger,
+            chunks.last().map_or(config.end_ledger, |(_, _, end)| *end),
+            chunks.len(),
+            config.workers
+        );
 
-            // Fetch real payments from Horizon
-            match self
-                .rpc_client
-                .fetch_payments_for_ledger(ledger.sequence)
-                .await
-            {
-                Ok(payments) => {
-                    for payment in payments {
-                        // Convert RPC Payment to ExtractedPayment
-                        let extracted = ExtractedPayment {
-                            ledger_sequence: ledger.sequence,
-                            transaction_hash: payment.transaction_hash,
-                            operation_type: "payment".to_string(), // Horizon 'payments' endpoint returns payments
-                            source_account: payment.source_account,
-                            destination: payment.destination,
-                            asset_code: payment.asset_code,
-                            asset_issuer: payment.asset_issuer,
-                            amount: payment.amount,
-                        };
-
-                        if let Err(e) = self.persist_payment(&extracted).await {
-                            warn!("Failed to persist payment: {}", e);
-                        }
+        let outcomes: Vec<_> = stream::iter(chunks)
+            .map(|(start, chunk_end, end)| async move {
+                let task_name = backfill_task_name(start, chunk_end);
+                let outcome = self
+                    .backfill_chunk(&task_name, start, end, config.batch_size)
+                    .await;
+                (start, end, outcome)
+            })
+            .buffer_unordered(config.workers.max(1))
+            .collect()
+            .await;
+
+        for (start, end, outcome) in outcomes {
+            match outcome {
+                Ok(Some(count)) => {
+                    summary.chunks_completed += 1;
+                    summary.ledgers_processed += count;
+                    if let Some(window) = self.close_time_window(start, end).await? {
+                        summary.completed_windows.push(window);
                     }
                 }
+                Ok(None) => summary.chunks_skipped += 1,
                 Err(e) => {
-                    warn!(
-                        "Failed to fetch payments for ledger {}: {}",
-                        ledger.sequence, e
-                    );
-                    // Non-fatal, continue ingesting ledgers
+                    error!("Backfill chunk {}..={} failed: {:#}", start, end, e);
+                    summary.chunks_failed += 1;
                 }
             }
+        }
+
+        if summary.chunks_completed > 0 {
+            self.reconcile_settlement_latency().await;
+        }
+
+        info!("Backfill finished: {:?}", summary);
+        Ok(summary)
+    }
+
+    /// I'm ingesting `start..=end` of a chunk from its saved progress; `None`
+    /// means it was already complete
+    async fn backfill_chunk(
+        &self,
+        task_name: &str,
+        start: u64,
+        end: u64,
+        batch_size: u32,
+    ) -> Result<Option<u64>> {
+        let mut next = match self.get_backfill_progress(task_name).await? {
+            Some(last) if last >= end => return Ok(None),
+            Some(last) => last + 1,
+            None => start,
+        };
 
-            // Fetch and process transactions for fee bumps
-            match self
-                .rpc_client
-                .fetch_transactions_for_ledger(ledger.sequence)
+        let mut count = 0u64;
+        while next <= end {
+            let limit = (end - next + 1).min(batch_size.max(1) as u64) as u32;
+            let result = self
+                .ledger_source
+                .fetch_ledgers(Some(next), limit, None)
                 .await
-            {
-                Ok(transactions) => {
-                    if let Err(e) = self
-                        .fee_bump_tracker
-                        .process_transactions(&transactions)
-                        .await
-                    {
-                        warn!("Failed to process transactions for fee bumps: {}", e);
-                    }
-                }
-                Err(e) => {
-                    warn!(
-                        "Failed to fetch transactions for ledger {}: {}",
-                        ledger.sequence, e
-                    );
-                }
+                .with_context(|| format!("Failed to fetch ledgers from {}", next))?;
+
+            let ledgers: Vec<RpcLedger> = result
+                .ledgers
+                .into_iter()
+                .filter(|l| l.sequence >= next && l.sequence <= end)
+                .collect();
+
+            // Only the run of ledgers that continues from `next` can be
+            // checkpointed; a gap means the source is missing ledgers
+            let contiguous = ledgers
+                .iter()
+                .zip(next..)
+                .take_while(|(ledger, expected)| ledger.sequence == *expected)
+                .count();
+
+            if contiguous > 0 {
+                let ledgers = &ledgers[..contiguous];
+                count += self.process_ledgers(ledgers, DecodeFailure::Halt).await?;
+                let last = ledgers[contiguous - 1].sequence;
+                self.save_backfill_progress(task_name, last).await?;
+                next = last + 1;
             }
 
-            if let Err(e) = self
-                .account_merge_detector
-                .process_ledger_operations(ledger.sequence)
-                .await
-            {
-                warn!(
-                    "Failed to process account merge operations for ledger {}: {}",
-                    ledger.sequence, e
+            if contiguous == 0 || contiguous < ledgers.len() {
+                anyhow::bail!(
+                    "Ledger {} missing from source (source retains {}..={})",
+                    next,
+                    result.oldest_ledger,
+                    result.latest_ledger
                 );
             }
+        }
+
+        Ok(Some(count))
+    }
+
+    /// I'm reading the close time span of the stored ledgers in `start..=end`
+    async fn close_time_window(
+        &self,
+        start: u64,
+        end: u64,
+    ) -> Result<Option<(DateTime<Utc>, DateTime<Utc>)>> {
+        let (first, last): (Option<DateTime<Utc>>, Option<DateTime<Utc>>) = sqlx::query_as(
+            "SELECT MIN(close_time), MAX(close_time) FROM ledgers WHERE sequence BETWEEN $1 AND $2",
+        )
+        .bind(start as i64)
+        .bind(end as i64)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(first.zip(last))
+    }
+
+    /// I'm processing and persisting fetched ledgers in order, stopping at the
+    /// first one that can't be persisted so callers never move their checkpoint
+    /// past it. Ledgers that can't be decoded either stop the run too or, for
+    /// the live tail, are recorded in `ledger_decode_errors` and skipped.
+    async fn process_ledgers(
+        &self,
+        ledgers: &[RpcLedger],
+        on_decode: DecodeFailure,
+    ) -> Result<u64> {
+        let mut count = 0u64;
+        let network_passphrase = self.ledger_source.network_passphrase();
+
+        for ledger in ledgers {
+            let decoded = ledger
+                .metadata_xdr
+                .as_deref()
+                .with_context(|| format!("Ledger {} has no metadataXdr", ledger.sequence))
+                .and_then(|xdr| {
+                    decode_ledger_close_meta(xdr, network_passphrase).with_context(|| {
+                        format!("Failed to decode ledger {} meta", ledger.sequence)
+                    })
+                });
+            let decoded = match (decoded, on_decode) {
+                (Ok(decoded), _) => decoded,
+                (Err(e), DecodeFailure::Halt) => return Err(e),
+                (Err(e), DecodeFailure::RecordAndSkip) => {
+                    error!("Skipping undecodable ledger {}: {:#}", ledger.sequence, e);
+                    self.record_decode_error(ledger, &e).await?;
+                    continue;
+                }
+            };
+
+            self.persist_ledger(ledger, &decoded)
+                .await
+                .with_context(|| format!("Failed to persist ledger {}", ledger.sequence))?;
+            self.persist_decoded(&decoded)
+                .await
+                .with_context(|| format!("Failed to persist ledger {}", ledger.sequence))?;
 
             count += 1;
         }
@@ -166,65 +441,96 @@ impl LedgerIngestionService {
         Ok(count)
     }
 
-    /// I'm persisting a single ledger to the database
-    async fn persist_ledger(&self, ledger: &RpcLedger) -> Result<()> {
-        let close_time = self.parse_ledger_time(&ledger.ledger_close_time)?;
-
+    /// I'm recording a ledger the live tail skipped so it can be replayed later
+    async fn record_decode_error(&self, ledger: &RpcLedger, error: &anyhow::Error) -> Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO ledgers (sequence, hash, close_time, transaction_count, operation_count)
-            VALUES ($1, $2, $3, $4, $5)
-            ON CONFLICT (sequence) DO NOTHING
+            INSERT INTO ledger_decode_errors (ledger_sequence, ledger_hash, error, recorded_at)
+            VALUES ($1, $2, $3, CURRENT_TIMESTAMP)
+            ON CONFLICT (ledger_sequence) DO UPDATE SET
+                error = EXCLUDED.error,
+                recorded_at = CURRENT_TIMESTAMP
             "#,
         )
         .bind(ledger.sequence as i64)
         .bind(&ledger.hash)
-        .bind(close_time)
-        .bind(0i32) // I'd get real counts from XDR parsing
-        .bind(0i32)
+        .bind(format!("{:#}", error))
         .execute(&self.pool)
         .await?;
+        Ok(())
+    }
 
-        // I'm also storing a placeholder transaction for the ledger
-        let tx_hash = format!("tx_{}", ledger.sequence);
-        sqlx::query(
-            r#"
-            INSERT INTO transactions (hash, ledger_sequence, source_account, fee, operation_count, successful)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            ON CONFLICT (hash) DO NOTHING
-            "#,
-        )
-        .bind(&tx_hash)
-        .bind(ledger.sequence as i64)
-        .bind("GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF")
-        .bind(100i64)
-        .bind(1i32)
-        .bind(true)
-        .execute(&self.pool)
-        .await?;
+    /// I'm re-timing payments that settle anchor transactions seen before them
+    async fn reconcile_settlement_latency(&self) {
+        if let Err(e) = SettlementLatencyService::new(self.pool.clone())
+            .reconcile()
+            .await
+        {
+            warn!("Failed to time payments from anchor transactions: {}", e);
+        }
+    }
+
+    /// I'm persisting the payments, fee bumps and merges decoded from a ledger
+    async fn persist_decoded(&self, decoded: &DecodedLedger) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let mut records = Vec::with_capacity(decoded.payments.len());
+        for payment in &decoded.payments {
+            persist_ledger_payment(&mut tx, payment)
+                .await
+                .with_context(|| format!("Failed to persist payment {}", payment.operation_id))?;
+            records.push(payment.to_payment_record()?);
+        }
+        self.db.save_payments_in(&mut tx, &records).await?;
+        tx.commit().await?;
+
+        self.fee_bump_tracker
+            .record_fee_bumps(&decoded.fee_bumps)
+            .await
+            .context("Failed to process transactions for fee bumps")?;
+
+        self.account_merge_detector
+            .record_merges(&decoded.account_merges)
+            .await
+            .context("Failed to process account merge operations")?;
 
         Ok(())
     }
 
-    /// I'm persisting an extracted payment to the database
-    async fn persist_payment(&self, payment: &ExtractedPayment) -> Result<()> {
+    /// I'm persisting a single ledger and its transactions to the database
+    async fn persist_ledger(&self, ledger: &RpcLedger, decoded: &DecodedLedger) -> Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO ledger_payments (ledger_sequence, transaction_hash, operation_type, source_account, destination, asset_code, asset_issuer, amount)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            INSERT INTO ledgers (sequence, hash, close_time, transaction_count, operation_count)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (sequence) DO NOTHING
             "#,
         )
-        .bind(payment.ledger_sequence as i64)
-        .bind(&payment.transaction_hash)
-        .bind(&payment.operation_type)
-        .bind(&payment.source_account)
-        .bind(&payment.destination)
-        .bind(&payment.asset_code)
-        .bind(&payment.asset_issuer)
-        .bind(&payment.amount)
+        .bind(ledger.sequence as i64)
+        .bind(&ledger.hash)
+        .bind(decoded.close_time)
+        .bind(decoded.transactions.len() as i32)
+        .bind(decoded.operation_count as i32)
         .execute(&self.pool)
         .await?;
 
+        for tx in &decoded.transactions {
+            sqlx::query(
+                r#"
+                INSERT INTO transactions (hash, ledger_sequence, source_account, fee, operation_count, successful)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (hash) DO NOTHING
+                "#,
+            )
+            .bind(&tx.hash)
+            .bind(ledger.sequence as i64)
+            .bind(&tx.source_account)
+            .bind(tx.fee_charged)
+            .bind(tx.operation_count as i32)
+            .bind(tx.successful)
+            .execute(&self.pool)
+            .await?;
+        }
+
         Ok(())
     }
 
@@ -247,8 +553,8 @@ impl LedgerIngestionService {
     }
 
     /// I'm saving cursor and last ledger for restart safety
-    async fn save_cursor(&self, cursor: &str, last_ledger: Option<u64>) -> Result<()> {
-        let seq = last_ledger.unwrap_or(0) as i64;
+    async fn save_cursor(&self, cursor: &str, last_ledger: u64) -> Result<()> {
+        let seq = last_ledger as i64;
         sqlx::query(
             r#"
             INSERT INTO ingestion_cursor (id, last_ledger_sequence, cursor, updated_at)
@@ -266,9 +572,92 @@ impl LedgerIngestionService {
         Ok(())
     }
 
-    fn parse_ledger_time(&self, timestamp_str: &str) -> Result<DateTime<Utc>> {
-        // I'm parsing unix timestamp string to DateTime
-        let ts: i64 = timestamp_str.parse().unwrap_or(0);
-        Ok(Utc.timestamp_opt(ts, 0).single().unwrap_or_else(Utc::now))
+    /// I'm reading the last ledger a backfill chunk has processed
+    async fn get_backfill_progress(&self, task_name: &str) -> Result<Option<u64>> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT last_cursor FROM ingestion_state WHERE task_name = $1")
+                .bind(task_name)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(row.and_then(|r| r.0.parse().ok()))
+    }
+
+    /// I'm recording backfill chunk progress for crash-safe resume
+    async fn save_backfill_progress(&self, task_name: &str, last_ledger: u64) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO ingestion_state (task_name, last_cursor, updated_at)
+            VALUES ($1, $2, CURRENT_TIMESTAMP)
+            ON CONFLICT (task_name) DO UPDATE SET
+                last_cursor = EXCLUDED.last_cursor,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(task_name)
+        .bind(last_ledger.to_string())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
     }
 }
+
+/// What `process_ledgers` does with a ledger whose meta is missing or undecodable
+#[derive(Debug, Clone, Copy)]
+enum DecodeFailure {
+    /// Fail the run so the checkpoint stays before the ledger (backfill)
+    Halt,
+    /// Record it in `ledger_decode_errors` and keep going (live tail)
+    RecordAndSkip,
+}
+
+/// Insert an extracted payment into `ledger_payments`. Its `payments` row, where
+/// corridor aggregation reads it, is written in the same transaction.
+async fn persist_ledger_payment(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    payment: &ExtractedPayment,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO ledger_payments (ledger_sequence, operation_id, transaction_hash, operation_type, source_account, destination, asset_code, asset_issuer, amount, source_asset_code, source_asset_issuer, source_amount, send_max, dest_min, path, successful, result_code, submission_time, confirmation_time, settlement_latency_ms, latency_source)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)
+        ON CONFLICT (operation_id) DO NOTHING
+        "#,
+    )
+    .bind(payment.ledger_sequence as i64)
+    .bind(&payment.operation_id)
+    .bind(&payment.transaction_hash)
+    .bind(&payment.operation_type)
+    .bind(&payment.source_account)
+    .bind(&payment.destination)
+    .bind(&payment.asset_code)
+    .bind(&payment.asset_issuer)
+    .bind(&payment.amount)
+    .bind(&payment.source_asset_code)
+    .bind(&payment.source_asset_issuer)
+    .bind(&payment.source_amount)
+    .bind(&payment.send_max)
+    .bind(&payment.dest_min)
+    .bind(
+        (!payment.path.is_empty())
+            .then(|| serde_json::to_string(&payment.path))
+            .transpose()?,
+    )
+    .bind(payment.successful)
+    .bind(&payment.result_code)
+    .bind(payment.submission_time)
+    .bind(payment.confirmation_time)
+    .bind(settlement_latency_ms(
+        payment.submission_time,
+        payment.confirmation_time,
+    ))
+    .bind(LatencySource::OnChain.as_str())
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// `ingestion_state` key for a backfill chunk
+pub fn backfill_task_name(start: u64, end: u64) -> String {
+    format!("ledger_backfill:{}-{}", start, end)
+}
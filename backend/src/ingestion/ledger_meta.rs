@@ -0,0 +1,666 @@
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chrono::{DateTime, TimeZone, Utc};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use stellar_xdr::curr::{
+    AccountMergeResult, Asset, ClaimAtom, FeeBumpTransactionEnvelope, FeeBumpTransactionInnerTx,
+    GeneralizedTransactionSet, Hash, InnerTransactionResultResult, LedgerCloseMeta,
+    LedgerHeaderHistoryEntry, Limits, MuxedAccount, Operation, OperationBody, OperationResult,
+    OperationResultTr, PathPaymentStrictReceiveResult, PathPaymentStrictSendResult, PaymentResult,
+    Preconditions, ReadXdr, Transaction, TransactionEnvelope, TransactionExt, TransactionPhase,
+    TransactionResult, TransactionResultPair, TransactionResultResult, TransactionSignaturePayload,
+    TransactionSignaturePayloadTaggedTransaction, TransactionV0, TxSetComponent, WriteXdr,
+};
+use tracing::warn;
+
+use crate::ingestion::ledger::ExtractedPayment;
+use crate::models::FeeBumpTransaction;
+use crate::services::account_merge_detector::AccountMergeEvent;
+use crate::services::settlement_latency::submission_time_from_valid_after;
+
+const STROOPS_PER_UNIT: i64 = 10_000_000;
+
+/// Everything ledger ingestion needs from a single `LedgerCloseMeta`
+#[derive(Debug, Clone)]
+pub struct DecodedLedger {
+    pub sequence: u64,
+    pub close_time: DateTime<Utc>,
+    pub operation_count: u32,
+    pub transactions: Vec<DecodedTransaction>,
+    pub payments: Vec<ExtractedPayment>,
+    pub fee_bumps: Vec<FeeBumpTransaction>,
+    pub account_merges: Vec<AccountMergeEvent>,
+}
+
+/// Transaction-level summary taken from the envelope and its result
+#[derive(Debug, Clone)]
+pub struct DecodedTransaction {
+    pub hash: String,
+    pub source_account: String,
+    pub fee_charged: i64,
+    pub operation_count: u32,
+    pub successful: bool,
+}
+
+/// Decode the base64 `metadataXdr` returned by RPC `getLedgers`
+pub fn decode_ledger_close_meta(
+    metadata_xdr: &str,
+    network_passphrase: &str,
+) -> Result<DecodedLedger> {
+    let bytes = BASE64
+        .decode(metadata_xdr.trim())
+        .context("metadataXdr is not valid base64")?;
+    let meta = LedgerCloseMeta::from_xdr(bytes, Limits::none())
+        .context("Failed to decode LedgerCloseMeta XDR")?;
+
+    decode_meta(&meta, network_passphrase)
+}
+
+/// Extract transactions, payments, fee bumps and account merges from decoded meta
+pub fn decode_meta(meta: &LedgerCloseMeta, network_passphrase: &str) -> Result<DecodedLedger> {
+    // Only the result pairs are read from the processing meta, which V2 (from
+    // protocol 23) wraps in a different type
+    let (header, envelopes, results): (_, _, Vec<&TransactionResultPair>) = match meta {
+        LedgerCloseMeta::V0(v0) => (
+            &v0.ledger_header,
+            v0.tx_set.txs.iter().collect::<Vec<_>>(),
+            v0.tx_processing.iter().map(|meta| &meta.result).collect(),
+        ),
+        LedgerCloseMeta::V1(v1) => (
+            &v1.ledger_header,
+            generalized_set_envelopes(&v1.tx_set),
+            v1.tx_processing.iter().map(|meta| &meta.result).collect(),
+        ),
+        LedgerCloseMeta::V2(v2) => (
+            &v2.ledger_header,
+            generalized_set_envelopes(&v2.tx_set),
+            v2.tx_processing.iter().map(|meta| &meta.result).collect(),
+        ),
+    };
+
+    let sequence = header.header.ledger_seq as u64;
+    let close_time = ledger_close_time(header)?;
+    let network_id = network_id(network_passphrase);
+
+    // The tx set is hash-ordered while tx_processing is in apply order, so
+    // envelopes are matched to their results by transaction hash.
+    let mut by_hash: HashMap<[u8; 32], &TransactionEnvelope> = HashMap::new();
+    for envelope in envelopes {
+        by_hash.insert(hash_with_network_id(envelope, &network_id)?, envelope);
+    }
+
+    let mut decoded = DecodedLedger {
+        sequence,
+        close_time,
+        operation_count: 0,
+        transactions: Vec::new(),
+        payments: Vec::new(),
+        fee_bumps: Vec::new(),
+        account_merges: Vec::new(),
+    };
+
+    for (index, result_pair) in results.into_iter().enumerate() {
+        let hash = result_pair.transaction_hash.0;
+        let Some(envelope) = by_hash.get(&hash) else {
+            warn!(
+                "Ledger {} has a result for unknown transaction {}",
+                sequence,
+                hex::encode(hash)
+            );
+            continue;
+        };
+
+        // Horizon's TOID uses a 1-based application order
+        let tx_order = index as u64 + 1;
+        decode_transaction(&mut decoded, envelope, result_pair, tx_order);
+    }
+
+    Ok(decoded)
+}
+
+fn decode_transaction(
+    decoded: &mut DecodedLedger,
+    envelope: &TransactionEnvelope,
+    result_pair: &TransactionResultPair,
+    tx_order: u64,
+) {
+    let hash = hex::encode(result_pair.transaction_hash.0);
+    let result = &result_pair.result;
+    let (source, operations) = envelope_source_and_operations(envelope);
+    let source_account = source.to_string();
+    let op_results = successful_operation_results(&result.result);
+    let successful = op_results.is_some();
+    let submission_time = submission_time_from_valid_after(valid_after(envelope));
+
+    decoded.operation_count += operations.len() as u32;
+    decoded.transactions.push(DecodedTransaction {
+        hash: hash.clone(),
+        source_account: source_account.clone(),
+        fee_charged: result.fee_charged,
+        operation_count: operations.len() as u32,
+        successful,
+    });
+
+    if let TransactionEnvelope::TxFeeBump(fee_bump) = envelope {
+        decoded.fee_bumps.push(fee_bump_record(
+            fee_bump,
+            &hash,
+            &result.result,
+            result.fee_charged,
+            decoded.sequence,
+            decoded.close_time,
+        ));
+    }
+
+    // Failed transactions have no effects on-chain, but their payments are
+    // still recorded with the result code so success rates can count them.
+    let all_op_results = operation_results(&result.result);
+
+    for (op_index, operation) in operations.iter().enumerate() {
+        let op_source = operation
+            .source_account
+            .as_ref()
+            .map(|account| account.to_string())
+            .unwrap_or_else(|| source_account.clone());
+        let op_result = all_op_results.and_then(|results| results.get(op_index));
+        let result_code = payment_result_code(&result.result, op_index).to_string();
+        let operation_id = toid(decoded.sequence, tx_order, op_index as u64 + 1);
+
+        match &operation.body {
+            OperationBody::Payment(op) => decoded.payments.push(ExtractedPayment {
+                ledger_sequence: decoded.sequence,
+                operation_id: operation_id.to_string(),
+                transaction_hash: hash.clone(),
+                operation_type: "payment".to_string(),
+                source_account: op_source,
+                destination: op.destination.to_string(),
+                asset_code: asset_code(&op.asset),
+                asset_issuer: asset_issuer(&op.asset),
+                amount: format_amount(op.amount),
+                source_asset_code: None,
+                source_asset_issuer: None,
+                source_amount: None,
+                send_max: None,
+                dest_min: None,
+                path: Vec::new(),
+                successful,
+                result_code,
+                submission_time,
+                confirmation_time: decoded.close_time,
+            }),
+            OperationBody::PathPaymentStrictReceive(op) => {
+                // The amount sent is only known from the offers crossed
+                let sent = match op_result {
+                    Some(OperationResult::OpInner(
+                        OperationResultTr::PathPaymentStrictReceive(
+                            PathPaymentStrictReceiveResult::Success(success),
+                        ),
+                    )) => amount_sent(&success.offers, &op.send_asset, op.dest_amount),
+                    _ => op.send_max,
+                };
+                decoded.payments.push(ExtractedPayment {
+                    ledger_sequence: decoded.sequence,
+                    operation_id: operation_id.to_string(),
+                    transaction_hash: hash.clone(),
+                    operation_type: "path_payment_strict_receive".to_string(),
+                    source_account: op_source,
+                    destination: op.destination.to_string(),
+                    asset_code: asset_code(&op.dest_asset),
+                    asset_issuer: asset_issuer(&op.dest_asset),
+                    amount: format_amount(op.dest_amount),
+                    source_asset_code: Some(asset_code_or_native(&op.send_asset)),
+                    source_asset_issuer: Some(asset_issuer_or_native(&op.send_asset)),
+                    source_amount: Some(format_amount(sent)),
+                    send_max: Some(format_amount(op.send_max)),
+                    dest_min: None,
+                    path: op.path.iter().map(asset_key).collect(),
+                    successful,
+                    result_code,
+                    submission_time,
+                    confirmation_time: decoded.close_time,
+                })
+            }
+            OperationBody::PathPaymentStrictSend(op) => {
+                // The delivered amount is only known from the result
+                let delivered = match op_result {
+                    Some(OperationResult::OpInner(OperationResultTr::PathPaymentStrictSend(
+                        PathPaymentStrictSendResult::Success(success),
+                    ))) => success.last.amount,
+                    _ => op.dest_min,
+                };
+                decoded.payments.push(ExtractedPayment {
+                    ledger_sequence: decoded.sequence,
+                    operation_id: operation_id.to_string(),
+                    transaction_hash: hash.clone(),
+                    operation_type: "path_payment_strict_send".to_string(),
+                    source_account: op_source,
+                    destination: op.destination.to_string(),
+                    asset_code: asset_code(&op.dest_asset),
+                    asset_issuer: asset_issuer(&op.dest_asset),
+                    amount: format_amount(delivered),
+                    source_asset_code: Some(asset_code_or_native(&op.send_asset)),
+                    source_asset_issuer: Some(asset_issuer_or_native(&op.send_asset)),
+                    source_amount: Some(format_amount(op.send_amount)),
+                    send_max: None,
+                    dest_min: Some(format_amount(op.dest_min)),
+                    path: op.path.iter().map(asset_key).collect(),
+                    successful,
+                    result_code,
+                    submission_time,
+                    confirmation_time: decoded.close_time,
+                })
+            }
+            OperationBody::AccountMerge(destination) if successful => {
+                let merged_stroops = match op_result {
+                    Some(OperationResult::OpInner(OperationResultTr::AccountMerge(
+                        AccountMergeResult::Success(balance),
+                    ))) => *balance,
+                    _ => 0,
+                };
+                decoded.account_merges.push(AccountMergeEvent {
+                    operation_id: operation_id.to_string(),
+                    transaction_hash: hash.clone(),
+                    ledger_sequence: decoded.sequence as i64,
+                    source_account: op_source,
+                    destination_account: destination.to_string(),
+                    merged_balance: merged_stroops as f64 / STROOPS_PER_UNIT as f64,
+                    created_at: decoded.close_time,
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+fn fee_bump_record(
+    envelope: &FeeBumpTransactionEnvelope,
+    hash: &str,
+    result: &TransactionResultResult,
+    fee_charged: i64,
+    ledger_sequence: u64,
+    created_at: DateTime<Utc>,
+) -> FeeBumpTransaction {
+    let inner_transaction_hash = match result {
+        TransactionResultResult::TxFeeBumpInnerSuccess(inner)
+        | TransactionResultResult::TxFeeBumpInnerFailed(inner) => {
+            hex::encode(inner.transaction_hash.0)
+        }
+        _ => String::new(),
+    };
+    let FeeBumpTransactionInnerTx::Tx(inner) = &envelope.tx.inner_tx;
+
+    FeeBumpTransaction {
+        transaction_hash: hash.to_string(),
+        ledger_sequence: ledger_sequence as i64,
+        fee_source: envelope.tx.fee_source.to_string(),
+        fee_charged,
+        max_fee: envelope.tx.fee,
+        inner_transaction_hash,
+        inner_max_fee: inner.tx.fee as i64,
+        signatures_count: envelope.signatures.len() as i32,
+        created_at,
+    }
+}
+
+fn generalized_set_envelopes(set: &GeneralizedTransactionSet) -> Vec<&TransactionEnvelope> {
+    let GeneralizedTransactionSet::V1(set) = set;
+    let mut envelopes = Vec::new();
+    for phase in set.phases.iter() {
+        match phase {
+            TransactionPhase::V0(components) => {
+                for component in components.iter() {
+                    let TxSetComponent::TxsetCompTxsMaybeDiscountedFee(component) = component;
+                    envelopes.extend(component.txs.iter());
+                }
+            }
+            // Soroban phase of protocol 23+ tx sets, in parallel execution stages
+            TransactionPhase::V1(parallel) => {
+                for stage in parallel.execution_stages.iter() {
+                    for cluster in stage.0.iter() {
+                        envelopes.extend(cluster.0.iter());
+                    }
+                }
+            }
+        }
+    }
+    envelopes
+}
+
+fn ledger_close_time(header: &LedgerHeaderHistoryEntry) -> Result<DateTime<Utc>> {
+    let close_time = header.header.scp_value.close_time.0;
+    i64::try_from(close_time)
+        .ok()
+        .and_then(|seconds| Utc.timestamp_opt(seconds, 0).single())
+        .with_context(|| {
+            format!(
+                "Ledger {} has an unrepresentable close time {}",
+                header.header.ledger_seq, close_time
+            )
+        })
+}
+
+/// Operation results for a transaction, whether or not it was applied. Transactions
+/// rejected before their operations ran (e.g. `tx_bad_seq`) have none.
+fn operation_results(result: &TransactionResultResult) -> Option<&[OperationResult]> {
+    match result {
+        TransactionResultResult::TxSuccess(results)
+        | TransactionResultResult::TxFailed(results) => Some(results.as_slice()),
+        TransactionResultResult::TxFeeBumpInnerSuccess(inner)
+        | TransactionResultResult::TxFeeBumpInnerFailed(inner) => match &inner.result.result {
+            InnerTransactionResultResult::TxSuccess(results)
+            | InnerTransactionResultResult::TxFailed(results) => Some(results.as_slice()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Horizon-style result code for a payment operation: `op_success` when the
+/// transaction applied, the operation's own failure code when it is the one
+/// that failed, and otherwise the transaction-level code.
+pub fn payment_result_code(result: &TransactionResultResult, op_index: usize) -> &'static str {
+    if successful_operation_results(result).is_some() {
+        return "op_success";
+    }
+
+    operation_results(result)
+        .and_then(|results| results.get(op_index))
+        .and_then(operation_result_code)
+        .filter(|code| *code != "op_success")
+        .unwrap_or_else(|| transaction_result_code(result))
+}
+
+/// Result code for a transaction, unwrapping fee bumps to the inner result
+pub fn transaction_result_code(result: &TransactionResultResult) -> &'static str {
+    match result {
+        TransactionResultResult::TxFeeBumpInnerSuccess(inner)
+        | TransactionResultResult::TxFeeBumpInnerFailed(inner) => {
+            inner_transaction_result_code(&inner.result.result)
+        }
+        TransactionResultResult::TxSuccess(_) => "tx_success",
+        TransactionResultResult::TxFailed(_) => "tx_failed",
+        TransactionResultResult::TxTooEarly => "tx_too_early",
+        TransactionResultResult::TxTooLate => "tx_too_late",
+        TransactionResultResult::TxMissingOperation => "tx_missing_operation",
+        TransactionResultResult::TxBadSeq => "tx_bad_seq",
+        TransactionResultResult::TxBadAuth => "tx_bad_auth",
+        TransactionResultResult::TxInsufficientBalance => "tx_insufficient_balance",
+        TransactionResultResult::TxNoAccount => "tx_no_source_account",
+        TransactionResultResult::TxInsufficientFee => "tx_insufficient_fee",
+        TransactionResultResult::TxBadAuthExtra => "tx_bad_auth_extra",
+        TransactionResultResult::TxInternalError => "tx_internal_error",
+        TransactionResultResult::TxNotSupported => "tx_not_supported",
+        TransactionResultResult::TxBadSponsorship => "tx_bad_sponsorship",
+        TransactionResultResult::TxBadMinSeqAgeOrGap => "tx_bad_minseq_age_or_gap",
+        TransactionResultResult::TxMalformed => "tx_malformed",
+        TransactionResultResult::TxSorobanInvalid => "tx_soroban_invalid",
+    }
+}
+
+fn inner_transaction_result_code(result: &InnerTransactionResultResult) -> &'static str {
+    match result {
+        InnerTransactionResultResult::TxSuccess(_) => "tx_success",
+        InnerTransactionResultResult::TxFailed(_) => "tx_failed",
+        InnerTransactionResultResult::TxTooEarly => "tx_too_early",
+        InnerTransactionResultResult::TxTooLate => "tx_too_late",
+        InnerTransactionResultResult::TxMissingOperation => "tx_missing_operation",
+        InnerTransactionResultResult::TxBadSeq => "tx_bad_seq",
+        InnerTransactionResultResult::TxBadAuth => "tx_bad_auth",
+        InnerTransactionResultResult::TxInsufficientBalance => "tx_insufficient_balance",
+        InnerTransactionResultResult::TxNoAccount => "tx_no_source_account",
+        InnerTransactionResultResult::TxInsufficientFee => "tx_insufficient_fee",
+        InnerTransactionResultResult::TxBadAuthExtra => "tx_bad_auth_extra",
+        InnerTransactionResultResult::TxInternalError => "tx_internal_error",
+        InnerTransactionResultResult::TxNotSupported => "tx_not_supported",
+        InnerTransactionResultResult::TxBadSponsorship => "tx_bad_sponsorship",
+        InnerTransactionResultResult::TxBadMinSeqAgeOrGap => "tx_bad_minseq_age_or_gap",
+        InnerTransactionResultResult::TxMalformed => "tx_malformed",
+        InnerTransactionResultResult::TxSorobanInvalid => "tx_soroban_invalid",
+    }
+}
+
+/// Result code for a single operation. Only payment operations are mapped;
+/// other operation types yield `None`.
+fn operation_result_code(result: &OperationResult) -> Option<&'static str> {
+    let tr = match result {
+        OperationResult::OpInner(tr) => tr,
+        OperationResult::OpBadAuth => return Some("op_bad_auth"),
+        OperationResult::OpNoAccount => return Some("op_no_source_account"),
+        OperationResult::OpNotSupported => return Some("op_not_supported"),
+        OperationResult::OpTooManySubentries => return Some("op_too_many_subentries"),
+        OperationResult::OpExceededWorkLimit => return Some("op_exceeded_work_limit"),
+        OperationResult::OpTooManySponsoring => return Some("op_too_many_sponsoring"),
+    };
+
+    let code = match tr {
+        OperationResultTr::Payment(result) => match result {
+            PaymentResult::Success => "op_success",
+            PaymentResult::Malformed => "op_malformed",
+            PaymentResult::Underfunded => "op_underfunded",
+            PaymentResult::SrcNoTrust => "op_src_no_trust",
+            PaymentResult::SrcNotAuthorized => "op_src_not_authorized",
+            PaymentResult::NoDestination => "op_no_destination",
+            PaymentResult::NoTrust => "op_no_trust",
+            PaymentResult::NotAuthorized => "op_not_authorized",
+            PaymentResult::LineFull => "op_line_full",
+            PaymentResult::NoIssuer => "op_no_issuer",
+        },
+        OperationResultTr::PathPaymentStrictReceive(result) => match result {
+            PathPaymentStrictReceiveResult::Success(_) => "op_success",
+            PathPaymentStrictReceiveResult::Malformed => "op_malformed",
+            PathPaymentStrictReceiveResult::Underfunded => "op_underfunded",
+            PathPaymentStrictReceiveResult::SrcNoTrust => "op_src_no_trust",
+            PathPaymentStrictReceiveResult::SrcNotAuthorized => "op_src_not_authorized",
+            PathPaymentStrictReceiveResult::NoDestination => "op_no_destination",
+            PathPaymentStrictReceiveResult::NoTrust => "op_no_trust",
+            PathPaymentStrictReceiveResult::NotAuthorized => "op_not_authorized",
+            PathPaymentStrictReceiveResult::LineFull => "op_line_full",
+            PathPaymentStrictReceiveResult::NoIssuer(_) => "op_no_issuer",
+            PathPaymentStrictReceiveResult::TooFewOffers => "op_too_few_offers",
+            PathPaymentStrictReceiveResult::OfferCrossSelf => "op_cross_self",
+            PathPaymentStrictReceiveResult::OverSendmax => "op_over_source_max",
+        },
+        OperationResultTr::PathPaymentStrictSend(result) => match result {
+            PathPaymentStrictSendResult::Success(_) => "op_success",
+            PathPaymentStrictSendResult::Malformed => "op_malformed",
+            PathPaymentStrictSendResult::Underfunded => "op_underfunded",
+            PathPaymentStrictSendResult::SrcNoTrust => "op_src_no_trust",
+            PathPaymentStrictSendResult::SrcNotAuthorized => "op_src_not_authorized",
+            PathPaymentStrictSendResult::NoDestination => "op_no_destination",
+            PathPaymentStrictSendResult::NoTrust => "op_no_trust",
+            PathPaymentStrictSendResult::NotAuthorized => "op_not_authorized",
+            PathPaymentStrictSendResult::LineFull => "op_line_full",
+            PathPaymentStrictSendResult::NoIssuer(_) => "op_no_issuer",
+            PathPaymentStrictSendResult::TooFewOffers => "op_too_few_offers",
+            PathPaymentStrictSendResult::OfferCrossSelf => "op_cross_self",
+            PathPaymentStrictSendResult::UnderDestmin => "op_under_dest_min",
+        },
+        _ => return None,
+    };
+
+    Some(code)
+}
+
+/// Result code for the payment at `op_index` of a base64 `TransactionResult`,
+/// as returned by Horizon's `result_xdr`
+pub fn payment_result_code_from_xdr(result_xdr: &str, op_index: usize) -> Result<&'static str> {
+    let bytes = BASE64
+        .decode(result_xdr.trim())
+        .context("result_xdr is not valid base64")?;
+    let result = TransactionResult::from_xdr(bytes, Limits::none())
+        .context("Failed to decode TransactionResult XDR")?;
+    Ok(payment_result_code(&result.result, op_index))
+}
+
+/// Operation results for a transaction whose operations were applied
+fn successful_operation_results(result: &TransactionResultResult) -> Option<&[OperationResult]> {
+    match result {
+        TransactionResultResult::TxSuccess(results) => Some(results.as_slice()),
+        TransactionResultResult::TxFeeBumpInnerSuccess(inner) => match &inner.result.result {
+            InnerTransactionResultResult::TxSuccess(results) => Some(results.as_slice()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Lower time bound (`valid_after`) of a transaction in unix seconds, 0 when unset
+fn valid_after(envelope: &TransactionEnvelope) -> u64 {
+    let cond = match envelope {
+        TransactionEnvelope::TxV0(v0) => {
+            return v0.tx.time_bounds.as_ref().map_or(0, |tb| tb.min_time.0)
+        }
+        TransactionEnvelope::Tx(v1) => &v1.tx.cond,
+        TransactionEnvelope::TxFeeBump(fee_bump) => {
+            let FeeBumpTransactionInnerTx::Tx(inner) = &fee_bump.tx.inner_tx;
+            &inner.tx.cond
+        }
+    };
+
+    match cond {
+        Preconditions::None => 0,
+        Preconditions::Time(time_bounds) => time_bounds.min_time.0,
+        Preconditions::V2(preconditions) => preconditions
+            .time_bounds
+            .as_ref()
+            .map_or(0, |tb| tb.min_time.0),
+    }
+}
+
+fn envelope_source_and_operations(envelope: &TransactionEnvelope) -> (MuxedAccount, &[Operation]) {
+    match envelope {
+        TransactionEnvelope::TxV0(v0) => (
+            MuxedAccount::Ed25519(v0.tx.source_account_ed25519.clone()),
+            v0.tx.operations.as_slice(),
+        ),
+        TransactionEnvelope::Tx(v1) => (v1.tx.source_account.clone(), v1.tx.operations.as_slice()),
+        TransactionEnvelope::TxFeeBump(fee_bump) => {
+            let FeeBumpTransactionInnerTx::Tx(inner) = &fee_bump.tx.inner_tx;
+            (
+                inner.tx.source_account.clone(),
+                inner.tx.operations.as_slice(),
+            )
+        }
+    }
+}
+
+/// Network-bound transaction hash, as used by Horizon and in result pairs
+pub fn transaction_hash(
+    envelope: &TransactionEnvelope,
+    network_passphrase: &str,
+) -> Result<[u8; 32]> {
+    hash_with_network_id(envelope, &network_id(network_passphrase))
+}
+
+fn network_id(network_passphrase: &str) -> Hash {
+    Hash(Sha256::digest(network_passphrase.as_bytes()).into())
+}
+
+fn hash_with_network_id(envelope: &TransactionEnvelope, network_id: &Hash) -> Result<[u8; 32]> {
+    let tagged_transaction = match envelope {
+        TransactionEnvelope::TxV0(v0) => {
+            TransactionSignaturePayloadTaggedTransaction::Tx(v0_to_transaction(&v0.tx))
+        }
+        TransactionEnvelope::Tx(v1) => {
+            TransactionSignaturePayloadTaggedTransaction::Tx(v1.tx.clone())
+        }
+        TransactionEnvelope::TxFeeBump(fee_bump) => {
+            TransactionSignaturePayloadTaggedTransaction::TxFeeBump(fee_bump.tx.clone())
+        }
+    };
+
+    let payload = TransactionSignaturePayload {
+        network_id: network_id.clone(),
+        tagged_transaction,
+    };
+    let bytes = payload
+        .to_xdr(Limits::none())
+        .context("Failed to encode transaction signature payload")?;
+
+    Ok(Sha256::digest(bytes).into())
+}
+
+/// V0 envelopes are hashed as the equivalent V1 transaction
+fn v0_to_transaction(tx: &TransactionV0) -> Transaction {
+    Transaction {
+        source_account: MuxedAccount::Ed25519(tx.source_account_ed25519.clone()),
+        fee: tx.fee,
+        seq_num: tx.seq_num.clone(),
+        cond: match &tx.time_bounds {
+            Some(time_bounds) => Preconditions::Time(time_bounds.clone()),
+            None => Preconditions::None,
+        },
+        memo: tx.memo.clone(),
+        operations: tx.operations.clone(),
+        ext: TransactionExt::V0,
+    }
+}
+
+/// Horizon-compatible operation ID (TOID)
+fn toid(ledger_sequence: u64, tx_order: u64, op_index: u64) -> u64 {
+    (ledger_sequence << 32) | (tx_order << 12) | op_index
+}
+
+pub(crate) fn asset_code(asset: &Asset) -> Option<String> {
+    match asset {
+        Asset::Native => None,
+        Asset::CreditAlphanum4(a) => Some(a.asset_code.to_string()),
+        Asset::CreditAlphanum12(a) => Some(a.asset_code.to_string()),
+    }
+}
+
+pub(crate) fn asset_issuer(asset: &Asset) -> Option<String> {
+    match asset {
+        Asset::Native => None,
+        Asset::CreditAlphanum4(a) => Some(a.issuer.to_string()),
+        Asset::CreditAlphanum12(a) => Some(a.issuer.to_string()),
+    }
+}
+
+/// Asset code with native XLM spelled out, as used in corridor keys
+pub(crate) fn asset_code_or_native(asset: &Asset) -> String {
+    asset_code(asset).unwrap_or_else(|| "XLM".to_string())
+}
+
+pub(crate) fn asset_issuer_or_native(asset: &Asset) -> String {
+    asset_issuer(asset).unwrap_or_else(|| "native".to_string())
+}
+
+/// `CODE:ISSUER` key for an asset, `XLM:native` for lumens
+pub(crate) fn asset_key(asset: &Asset) -> String {
+    format!(
+        "{}:{}",
+        asset_code_or_native(asset),
+        asset_issuer_or_native(asset)
+    )
+}
+
+/// Amount of `send_asset` a strict-receive path payment paid into the offers
+/// it crossed. A direct payment crosses no offers and sends what it delivers.
+fn amount_sent(offers: &[ClaimAtom], send_asset: &Asset, dest_amount: i64) -> i64 {
+    if offers.is_empty() {
+        return dest_amount;
+    }
+
+    offers
+        .iter()
+        .filter_map(|offer| {
+            let (asset_bought, amount_bought) = match offer {
+                ClaimAtom::V0(a) => (&a.asset_bought, a.amount_bought),
+                ClaimAtom::OrderBook(a) => (&a.asset_bought, a.amount_bought),
+                ClaimAtom::LiquidityPool(a) => (&a.asset_bought, a.amount_bought),
+            };
+            (asset_bought == send_asset).then_some(amount_bought)
+        })
+        .sum()
+}
+
+/// Format stroops the way Horizon does (7 decimal places)
+pub(crate) fn format_amount(stroops: i64) -> String {
+    format!(
+        "{}.{:07}",
+        stroops / STROOPS_PER_UNIT,
+        (stroops % STROOPS_PER_UNIT).abs()
+    )
+}
@@ -1,12 +1,15 @@
 // I'm exporting the ledger ingestion module as required by issue #2
 pub mod ledger;
+pub mod ledger_meta;
 
 use anyhow::{Context, Result};
 use serde::Serialize;
 use std::sync::Arc;
 use tracing::{info, warn};
 
+use crate::analytics::{apply_anchor_uptime, compute_anchor_metrics};
 use crate::database::Database;
+use crate::models::Anchor;
 use crate::rpc::StellarRpcClient;
 
 pub struct DataIngestionService {
@@ -29,14 +32,14 @@ impl DataIngestionService {
         Ok(())
     }
 
-    /// Fetch and process anchor metrics from RPC
+    /// Refresh anchor metrics from the payments ingested so far
     pub async fn sync_anchor_metrics(&self) -> Result<()> {
         info!("Syncing anchor metrics from Stellar network");
 
-        let anchors = self.db.list_anchors(0, 100).await?;
+        let anchors = self.db.list_anchors(100, 0).await?;
 
         for anchor in anchors {
-            match self.process_anchor_metrics(&anchor.stellar_account).await {
+            match self.process_anchor_metrics(&anchor).await {
                 Ok(_) => info!("Updated metrics for anchor: {}", anchor.name),
                 Err(e) => warn!("Failed to update anchor {}: {}", anchor.name, e),
             }
@@ -45,75 +48,46 @@ impl DataIngestionService {
         Ok(())
     }
 
-    /// Process metrics for a single anchor
-    async fn process_anchor_metrics(&self, account_id: &str) -> Result<()> {
-        let payments = self
-            .rpc_client
-            .fetch_account_payments(account_id, 100)
+    /// Process metrics for a single anchor from its latest persisted payments,
+    /// scored with the active scoring model
+    async fn process_anchor_metrics(&self, anchor: &Anchor) -> Result<()> {
+        let summary = self
+            .db
+            .get_account_payment_summary(&anchor.stellar_account, 100)
             .await
-            .context("Failed to fetch payments")?;
+            .context("Failed to summarize payments")?;
 
-        if payments.is_empty() {
+        if summary.total_transactions == 0 {
             return Ok(());
         }
 
-        let mut successful = 0;
-        let failed = 0;
-        let mut total_volume = 0.0;
-        let settlement_times = Vec::new(); // Removed mut as it's never pushed to
-
-        for payment in &payments {
-            let amount: f64 = payment.amount.parse().unwrap_or(0.0);
-            total_volume += amount;
-
-            successful += 1;
-        }
-
-        let total_transactions = (successful + failed) as i64;
-        let success_rate = if total_transactions > 0 {
-            (successful as f64 / total_transactions as f64) * 100.0
-        } else {
-            0.0
-        };
-
-        let reliability_score = self.calculate_reliability_score(success_rate, failed as i64);
-
-        let avg_settlement_time = if !settlement_times.is_empty() {
-            settlement_times.iter().sum::<i32>() / settlement_times.len() as i32
-        } else {
-            1000
-        };
-
-        let status = if success_rate >= 98.0 {
-            "green"
-        } else if success_rate >= 95.0 {
-            "yellow"
-        } else {
-            "red"
-        };
+        let metrics = apply_anchor_uptime(
+            compute_anchor_metrics(
+                summary.total_transactions,
+                summary.successful_transactions,
+                summary.total_transactions - summary.successful_transactions,
+                None,
+            ),
+            anchor.uptime_percentage,
+        );
 
         self.db
             .update_anchor_from_rpc(crate::database::AnchorRpcUpdate {
-                stellar_account: account_id.to_string(),
-                total_transactions,
-                successful_transactions: successful as i64,
-                failed_transactions: failed as i64,
-                total_volume_usd: total_volume,
-                avg_settlement_time_ms: avg_settlement_time,
-                reliability_score,
-                status: status.to_string(),
+                stellar_account: anchor.stellar_account.clone(),
+                total_transactions: metrics.total_transactions,
+                successful_transactions: metrics.successful_transactions,
+                failed_transactions: metrics.failed_transactions,
+                total_volume_usd: summary.total_volume,
+                avg_settlement_time_ms: metrics.avg_settlement_time_ms.unwrap_or(0),
+                reliability_score: metrics.reliability_score,
+                status: metrics.status.as_str().to_string(),
+                scoring_version: metrics.scoring_version,
             })
             .await?;
 
         Ok(())
     }
 
-    fn calculate_reliability_score(&self, success_rate: f64, failed_count: i64) -> f64 {
-        let base_score = success_rate / 100.0;
-        let penalty = (failed_count as f64 * 0.01).min(0.2);
-        (base_score - penalty).clamp(0.0, 1.0)
-    }
-
     /// Get current network health status
     pub async fn get_network_health(&self) -> Result<NetworkHealth> {
         let health = self.rpc_client.check_health().await?;
@@ -18,6 +18,7 @@ pub mod muxed;
 pub mod network;
 pub mod openapi;
 pub mod rate_limit;
+pub mod scoring;
 pub mod services;
 pub mod shutdown;
 pub mod snapshot;
@@ -14,33 +14,62 @@ use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 use stellar_insights_backend::api::account_merges;
-use stellar_insights_backend::api::anchors_cached::get_anchors;
+use stellar_insights_backend::api::anchors_cached::{get_anchor_failures, get_anchors};
 use stellar_insights_backend::api::cache_stats;
-use stellar_insights_backend::api::corridors_cached::{get_corridor_detail, list_corridors};
+use stellar_insights_backend::api::corridors_cached::{
+    get_corridor_depth, get_corridor_depth_history, get_corridor_detail,
+    get_corridor_distributions, get_corridor_failures, get_corridor_routes, list_corridors,
+};
 use stellar_insights_backend::api::fee_bump;
 use stellar_insights_backend::api::liquidity_pools;
 use stellar_insights_backend::api::metrics_cached;
+use stellar_insights_backend::api::sep6_proxy::Sep6State;
 use stellar_insights_backend::auth::AuthService;
 use stellar_insights_backend::auth_middleware::auth_middleware;
 use stellar_insights_backend::cache::{CacheConfig, CacheManager};
 use stellar_insights_backend::cache_invalidation::CacheInvalidationService;
 use stellar_insights_backend::database::Database;
 use stellar_insights_backend::handlers::*;
-use stellar_insights_backend::ingestion::ledger::LedgerIngestionService;
+use stellar_insights_backend::ingestion::ledger::{BackfillConfig, LedgerIngestionService};
 use stellar_insights_backend::ingestion::DataIngestionService;
 use stellar_insights_backend::network::NetworkConfig;
 use stellar_insights_backend::openapi::ApiDoc;
 use stellar_insights_backend::rate_limit::{rate_limit_middleware, RateLimitConfig, RateLimiter};
-use stellar_insights_backend::rpc::StellarRpcClient;
+use stellar_insights_backend::rpc::{
+    Cassette, CassetteConfig, CassetteMode, CassetteRecorder, LedgerSourceConfig, ReplayServer,
+    StellarRpcClient,
+};
 use stellar_insights_backend::rpc_handlers;
+use stellar_insights_backend::scoring::{self, ScoringModels};
+use stellar_insights_backend::services::aggregation::{AggregationConfig, AggregationService};
+use stellar_insights_backend::services::anchor_prober::{
+    AnchorProber, AnchorProberConfig, HttpProbeClient,
+};
+use stellar_insights_backend::services::anchor_reliability::{
+    AnchorReliabilityConfig, AnchorReliabilityService,
+};
 use stellar_insights_backend::services::account_merge_detector::AccountMergeDetector;
+use stellar_insights_backend::services::contract::ContractService;
 use stellar_insights_backend::services::fee_bump_tracker::FeeBumpTrackerService;
+use stellar_insights_backend::services::indexing::IndexingService;
 use stellar_insights_backend::services::liquidity_pool_analyzer::LiquidityPoolAnalyzer;
+use stellar_insights_backend::services::order_book_depth::OrderBookDepthService;
+use stellar_insights_backend::services::path_finder::PathFinder;
+use stellar_insights_backend::services::route_estimator::RouteEstimator;
 use stellar_insights_backend::services::price_feed::{
     default_asset_mapping, PriceFeedClient, PriceFeedConfig,
 };
 use stellar_insights_backend::services::realtime_broadcaster::RealtimeBroadcaster;
+use stellar_insights_backend::services::settlement_latency::SettlementLatencyService;
+use stellar_insights_backend::services::snapshot::SnapshotService;
+use stellar_insights_backend::services::snapshot_scheduler::{
+    SnapshotContractClient, SnapshotScheduler, SnapshotSchedulerConfig,
+};
+use stellar_insights_backend::services::stellar_toml::{
+    HttpStellarTomlSource, StellarTomlCrawler, StellarTomlCrawlerConfig,
+};
 use stellar_insights_backend::services::trustline_analyzer::TrustlineAnalyzer;
+use stellar_insights_backend::snapshot_handlers::{self, SnapshotAppState};
 use stellar_insights_backend::shutdown::{ShutdownConfig, ShutdownCoordinator};
 use stellar_insights_backend::state::AppState;
 use stellar_insights_backend::websocket::WsState;
@@ -74,6 +103,11 @@ async fn main() -> Result<()> {
     );
     let _shutdown_coordinator = Arc::new(ShutdownCoordinator::new(shutdown_config));
 
+    // Scoring models, installed before anything computes a score
+    let scoring_models = ScoringModels::from_env()?;
+    scoring::install(scoring_models.clone())?;
+    let scoring_models = Arc::new(scoring_models);
+
     // Database connection
     let database_url = std::env::var("DATABASE_URL")
         .unwrap_or_else(|_| "sqlite:./stellar_insights.db".to_string());
@@ -86,29 +120,44 @@ async fn main() -> Result<()> {
 
     let db = Arc::new(Database::new(pool.clone()));
 
-    // Initialize Stellar RPC Client
-    let mock_mode = std::env::var("RPC_MOCK_MODE")
-        .unwrap_or_else(|_| "false".to_string())
-        .parse::<bool>()
-        .unwrap_or(false);
-
     // Initialize Stellar RPC Client with network configuration
     let network_config = NetworkConfig::from_env();
     tracing::info!(
-        "Initializing Stellar RPC client for {} (mock_mode: {})",
-        network_config.display_name(),
-        mock_mode
+        "Initializing Stellar RPC client for {}",
+        network_config.display_name()
     );
 
-    let rpc_client = if mock_mode {
-        Arc::new(StellarRpcClient::new_with_network(network_config.network, true))
-    } else {
-        Arc::new(StellarRpcClient::new(
-            network_config.rpc_url.clone(),
-            network_config.horizon_url.clone(),
-            false,
-        ))
-    };
+    // Optional capture of RPC/Horizon traffic to a cassette, or offline replay of one
+    let cassette_config = CassetteConfig::from_env();
+    let mut _replay_server = None;
+
+    let mut client_config = network_config.clone();
+    if let Some(cassette) = cassette_config
+        .as_ref()
+        .filter(|c| c.mode == CassetteMode::Replay)
+    {
+        let server = ReplayServer::start(Cassette::load(&cassette.path)?).await?;
+        tracing::info!("Replaying RPC traffic from {}", cassette.path.display());
+        client_config.rpc_url = server.rpc_url();
+        client_config.horizon_url = server.horizon_url();
+        _replay_server = Some(server);
+    }
+
+    let mut client = StellarRpcClient::new_with_config(client_config);
+    if let Some(cassette) = cassette_config
+        .as_ref()
+        .filter(|c| c.mode == CassetteMode::Record)
+    {
+        tracing::info!("Recording RPC traffic to {}", cassette.path.display());
+        client = client.with_recorder(Arc::new(CassetteRecorder::open(&cassette.path)?));
+    }
+    let rpc_client = Arc::new(client);
+
+    // Initialize the ledger data source (RPC, Horizon, or archived ledger files).
+    // Horizon serves pools and trades but no ledger meta, so ingestion needs RPC or files.
+    let ledger_source_config = LedgerSourceConfig::from_env();
+    let ledger_source = ledger_source_config.build(Arc::clone(&rpc_client))?;
+    tracing::info!("Ledger data source: {}", ledger_source.name());
 
     // Initialize WebSocket state
     let ws_state = Arc::new(WsState::new());
@@ -124,15 +173,12 @@ async fn main() -> Result<()> {
     let fee_bump_tracker = Arc::new(FeeBumpTrackerService::new(pool.clone()));
 
     // Initialize Account Merge Detector Service
-    let account_merge_detector = Arc::new(AccountMergeDetector::new(
-        pool.clone(),
-        Arc::clone(&rpc_client),
-    ));
+    let account_merge_detector = Arc::new(AccountMergeDetector::new(pool.clone()));
 
     // Initialize Liquidity Pool Analyzer
     let lp_analyzer = Arc::new(LiquidityPoolAnalyzer::new(
         pool.clone(),
-        Arc::clone(&rpc_client),
+        Arc::clone(&ledger_source),
     ));
 
     // Initialize Price Feed Client
@@ -141,6 +187,16 @@ async fn main() -> Result<()> {
     let price_feed = Arc::new(PriceFeedClient::new(price_feed_config, asset_mapping));
     tracing::info!("Price feed client initialized");
 
+    // Initialize Route Estimator
+    let route_estimator = Arc::new(RouteEstimator::new(
+        pool.clone(),
+        Arc::clone(&rpc_client),
+        Arc::clone(&price_feed),
+    ));
+
+    // Initialize Path Finder
+    let path_finder = Arc::new(PathFinder::new(pool.clone(), Arc::clone(&rpc_client)));
+
     // Initialize Trustline Analyzer
     let trustline_analyzer = Arc::new(TrustlineAnalyzer::new(
         pool.clone(),
@@ -149,7 +205,7 @@ async fn main() -> Result<()> {
 
     // Initialize Ledger Ingestion Service
     let ledger_ingestion_service = Arc::new(LedgerIngestionService::new(
-        Arc::clone(&rpc_client),
+        Arc::clone(&ledger_source),
         Arc::clone(&fee_bump_tracker),
         Arc::clone(&account_merge_detector),
         pool.clone(),
@@ -253,25 +309,63 @@ async fn main() -> Result<()> {
     */
 
     // Ledger ingestion task
-    let ledger_ingestion_clone = Arc::clone(&ledger_ingestion_service);
-    tokio::spawn(async move {
-        tracing::info!("Starting ledger ingestion background task");
-        loop {
-            match ledger_ingestion_clone.run_ingestion(5).await {
-                Ok(count) => {
-                    if count == 0 {
-                        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-                    } else {
-                        tokio::task::yield_now().await;
+    let serves_ledgers = ledger_source.serves_ledgers();
+    if serves_ledgers {
+        let ledger_ingestion_clone = Arc::clone(&ledger_ingestion_service);
+        tokio::spawn(async move {
+            tracing::info!("Starting ledger ingestion background task");
+            loop {
+                match ledger_ingestion_clone.run_ingestion(5).await {
+                    Ok(count) => {
+                        if count == 0 {
+                            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                        } else {
+                            tokio::task::yield_now().await;
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("Ledger ingestion failed: {}", e);
+                        tokio::time::sleep(std::time::Duration::from_secs(10)).await;
                     }
                 }
-                Err(e) => {
-                    tracing::error!("Ledger ingestion failed: {}", e);
-                    tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+            }
+        });
+    } else {
+        tracing::warn!(
+            "Ledger source {} serves no ledger meta; ledger ingestion and backfill are disabled",
+            ledger_source.name()
+        );
+    }
+
+    // Corridor aggregation, shared by the schedulers and the backfill
+    let aggregation_service = Arc::new(AggregationService::new(
+        Arc::clone(&db),
+        AggregationConfig::default(),
+    ));
+
+    // Optional historical backfill, runs alongside the live tail
+    if let Some(backfill_config) = BackfillConfig::from_env().filter(|_| serves_ledgers) {
+        let backfill_clone = Arc::clone(&ledger_ingestion_service);
+        let backfill_aggregation = Arc::clone(&aggregation_service);
+        tokio::spawn(async move {
+            tracing::info!(
+                "Starting ledger backfill of {}..={}",
+                backfill_config.start_ledger,
+                backfill_config.end_ledger
+            );
+            match backfill_clone.run_backfill(&backfill_config).await {
+                Ok(summary) => {
+                    // The hourly job has usually moved past backfilled hours
+                    for (start, end) in summary.completed_windows {
+                        if let Err(e) = backfill_aggregation.reaggregate_hours(start, end).await {
+                            tracing::error!("Backfill re-aggregation failed: {}", e);
+                        }
+                    }
                 }
+                Err(e) => tracing::error!("Ledger backfill failed: {}", e),
             }
-        }
-    });
+        });
+    }
 
     // Liquidity pool sync background task
     let lp_analyzer_clone = Arc::clone(&lp_analyzer);
@@ -305,6 +399,86 @@ async fn main() -> Result<()> {
         }
     });
 
+    // Order book depth snapshot background task
+    let depth_service = OrderBookDepthService::new(
+        pool.clone(),
+        Arc::clone(&rpc_client),
+        Arc::clone(&price_feed),
+    );
+    tokio::spawn(async move {
+        tracing::info!("Starting order book depth snapshot background task");
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(300)); // 5 minutes
+        loop {
+            interval.tick().await;
+            if let Err(e) = depth_service.take_snapshots().await {
+                tracing::error!("Order book depth snapshot failed: {}", e);
+            }
+        }
+    });
+
+    // Path graph refresh background task
+    let path_finder_clone = Arc::clone(&path_finder);
+    tokio::spawn(async move {
+        tracing::info!("Starting path graph refresh background task");
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(300)); // 5 minutes
+        loop {
+            interval.tick().await;
+            if let Err(e) = path_finder_clone.refresh().await {
+                tracing::error!("Path graph refresh failed: {}", e);
+            }
+        }
+    });
+
+    // Ledger ingestion records the payments corridor aggregation reads; without
+    // ledger meta they are polled from Horizon instead
+    if !serves_ledgers {
+        let indexing_service = IndexingService::new(Arc::clone(&rpc_client), Arc::clone(&db));
+        tokio::spawn(async move {
+            tracing::info!("Starting Horizon payment ingestion background task");
+            loop {
+                match indexing_service.run_payment_ingestion().await {
+                    Ok(0) => tokio::time::sleep(std::time::Duration::from_secs(5)).await,
+                    Ok(_) => tokio::task::yield_now().await,
+                    Err(e) => {
+                        tracing::error!("Payment ingestion failed: {}", e);
+                        tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+                    }
+                }
+            }
+        });
+    }
+
+    // Hourly corridor aggregation from the ingested payments
+    tokio::spawn(Arc::clone(&aggregation_service).start_scheduler());
+
+    // Corridor rollup scheduler: daily, weekly and monthly metrics from the hourly buckets
+    tokio::spawn(aggregation_service.start_rollup_scheduler());
+
+    // Anchor reliability scoring from each anchor's asset payments
+    let anchor_reliability_service = Arc::new(AnchorReliabilityService::new(
+        Arc::clone(&db),
+        Arc::clone(&price_feed),
+        AnchorReliabilityConfig::default(),
+    ));
+    tokio::spawn(anchor_reliability_service.start_scheduler());
+
+    // Anchor discovery and SEP-1 metadata from issuers' stellar.toml files
+    let stellar_toml_crawler = Arc::new(StellarTomlCrawler::new(
+        Arc::clone(&db),
+        Arc::new(HttpStellarTomlSource::new(Arc::clone(&rpc_client))),
+        StellarTomlCrawlerConfig::default(),
+    ));
+    tokio::spawn(stellar_toml_crawler.start_scheduler());
+
+    // Anchor availability from probes of their SEP endpoints
+    let anchor_prober = Arc::new(AnchorProber::new(
+        Arc::clone(&db),
+        Arc::new(HttpProbeClient::new()),
+        Arc::clone(&ws_state),
+        AnchorProberConfig::default(),
+    ));
+    tokio::spawn(anchor_prober.start_scheduler());
+
     // Start RealtimeBroadcaster background task
     tokio::spawn(async move {
         tracing::info!("Starting RealtimeBroadcaster background task");
@@ -498,11 +672,26 @@ async fn main() -> Result<()> {
     // Build auth router
     let auth_routes = stellar_insights_backend::api::auth::routes(auth_service.clone());
 
-    // Build cached routes (anchors list, corridors list/detail) with cache state
+    // Build cached routes (anchors, corridors, failure breakdowns, distributions, depth, routes) with cache state
     let cached_routes = Router::new()
         .route("/api/anchors", get(get_anchors))
         .route("/api/corridors", get(list_corridors))
         .route("/api/corridors/:corridor_key", get(get_corridor_detail))
+        .route(
+            "/api/corridors/:corridor_key/failures",
+            get(get_corridor_failures),
+        )
+        .route(
+            "/api/corridors/:corridor_key/distributions",
+            get(get_corridor_distributions),
+        )
+        .route("/api/corridors/:corridor_key/depth", get(get_corridor_depth))
+        .route(
+            "/api/corridors/:corridor_key/depth/history",
+            get(get_corridor_depth_history),
+        )
+        .route("/api/corridors/:corridor_key/routes", get(get_corridor_routes))
+        .route("/api/anchors/:id/failures", get(get_anchor_failures))
         .with_state(cached_state.clone())
         .layer(ServiceBuilder::new().layer(middleware::from_fn_with_state(
             rate_limiter.clone(),
@@ -612,6 +801,45 @@ async fn main() -> Result<()> {
         )))
         .layer(cors.clone());
 
+    // Build route estimation routes
+    let route_routes = Router::new()
+        .nest(
+            "/api/routes",
+            stellar_insights_backend::api::route_estimator::routes(Arc::clone(&route_estimator)),
+        )
+        .layer(ServiceBuilder::new().layer(middleware::from_fn_with_state(
+            rate_limiter.clone(),
+            rate_limit_middleware,
+        )))
+        .layer(cors.clone());
+
+    // Build path finding routes
+    let path_routes = Router::new()
+        .nest(
+            "/api/paths",
+            stellar_insights_backend::api::paths::routes(Arc::clone(&path_finder)),
+        )
+        .layer(ServiceBuilder::new().layer(middleware::from_fn_with_state(
+            rate_limiter.clone(),
+            rate_limit_middleware,
+        )))
+        .layer(cors.clone());
+
+    // Build scoring model routes
+    let scoring_routes = Router::new()
+        .nest(
+            "/api/scoring",
+            stellar_insights_backend::api::scoring::routes(
+                Arc::clone(&db),
+                Arc::clone(&scoring_models),
+            ),
+        )
+        .layer(ServiceBuilder::new().layer(middleware::from_fn_with_state(
+            rate_limiter.clone(),
+            rate_limit_middleware,
+        )))
+        .layer(cors.clone());
+
     // Build price feed routes
     let price_routes = Router::new()
         .nest(
@@ -633,6 +861,55 @@ async fn main() -> Result<()> {
         )))
         .layer(cors.clone());
 
+    // Build SEP-6 proxy routes; transactions seen through the proxy time the
+    // payments that settle them
+    let sep6_routes = stellar_insights_backend::api::sep6_proxy::router(
+        Sep6State::new()
+            .with_settlement_tracking(Arc::new(SettlementLatencyService::new(pool.clone()))),
+    )
+    .layer(ServiceBuilder::new().layer(middleware::from_fn_with_state(
+        rate_limiter.clone(),
+        rate_limit_middleware,
+    )))
+    .layer(cors.clone());
+
+    // Build snapshot proof routes; the contract service is optional so proofs
+    // are served even where on-chain submission isn't configured
+    let contract_service = match ContractService::from_env() {
+        Ok(service) => Some(Arc::new(service)),
+        Err(e) => {
+            tracing::info!("Snapshot contract service not configured: {}", e);
+            None
+        }
+    };
+    let snapshot_service = Arc::new(SnapshotService::new(
+        Arc::clone(&db),
+        contract_service.clone(),
+    ));
+
+    // Snapshot for each ended epoch, committed on-chain through the
+    // persisted submission queue
+    let snapshot_scheduler = Arc::new(SnapshotScheduler::new(
+        Arc::clone(&db),
+        Arc::clone(&snapshot_service),
+        contract_service
+            .clone()
+            .map(|service| service as Arc<dyn SnapshotContractClient>),
+        SnapshotSchedulerConfig::default(),
+    ));
+    tokio::spawn(snapshot_scheduler.start_scheduler());
+
+    let snapshot_routes = snapshot_handlers::routes(SnapshotAppState {
+        db: Arc::clone(&db),
+        contract_service,
+        snapshot_service,
+    })
+    .layer(ServiceBuilder::new().layer(middleware::from_fn_with_state(
+        rate_limiter.clone(),
+        rate_limit_middleware,
+    )))
+    .layer(cors.clone());
+
     // Build trustline routes
     let trustline_routes = Router::new()
         .nest(
@@ -666,8 +943,13 @@ async fn main() -> Result<()> {
         .merge(account_merge_routes)
         .merge(lp_routes)
         .merge(price_routes)
+        .merge(route_routes)
+        .merge(path_routes)
+        .merge(scoring_routes)
         .merge(trustline_routes)
         .merge(network_routes)
+        .merge(sep6_routes)
+        .merge(snapshot_routes)
         .merge(cache_routes)
         .merge(metrics_routes)
         .merge(ws_routes)
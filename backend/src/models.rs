@@ -24,6 +24,14 @@ pub struct Anchor {
     pub avg_settlement_time_ms: i32,
     pub reliability_score: f64,
     pub status: String,
+    /// Version of the scoring model behind `reliability_score` and `status`
+    #[serde(default)]
+    #[sqlx(default)]
+    pub scoring_version: Option<String>,
+    /// Share of SEP endpoint probes answered over the last day, once probed
+    #[serde(default)]
+    #[sqlx(default)]
+    pub uptime_percentage: Option<f64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -53,6 +61,10 @@ pub struct AnchorMetricsHistory {
     pub failed_transactions: i64,
     pub avg_settlement_time_ms: Option<i32>,
     pub volume_usd: Option<f64>,
+    /// Version of the scoring model the score was computed with
+    #[serde(default)]
+    #[sqlx(default)]
+    pub scoring_version: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -66,6 +78,12 @@ pub struct AnchorMetrics {
     pub failed_transactions: i64,
     pub avg_settlement_time_ms: Option<i32>,
     pub status: AnchorStatus,
+    /// Version of the scoring model the metrics were computed with
+    #[serde(default)]
+    pub scoring_version: String,
+    /// Share of SEP endpoint probes answered; folded into `status` when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uptime_percentage: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -84,13 +102,17 @@ impl AnchorStatus {
         }
     }
 
+    /// Status under the active scoring model's thresholds
     pub fn from_metrics(success_rate: f64, failure_rate: f64) -> Self {
-        if success_rate > 98.0 && failure_rate <= 1.0 {
-            AnchorStatus::Green
-        } else if success_rate >= 95.0 && failure_rate <= 5.0 {
-            AnchorStatus::Yellow
-        } else {
-            AnchorStatus::Red
+        crate::scoring::active_model().anchor_status(success_rate, failure_rate)
+    }
+
+    /// The more severe of two statuses
+    pub fn worst(self, other: Self) -> Self {
+        match (self, other) {
+            (AnchorStatus::Red, _) | (_, AnchorStatus::Red) => AnchorStatus::Red,
+            (AnchorStatus::Yellow, _) | (_, AnchorStatus::Yellow) => AnchorStatus::Yellow,
+            _ => AnchorStatus::Green,
         }
     }
 }
@@ -102,11 +124,107 @@ pub struct AnchorWithAssets {
     pub assets: Vec<Asset>,
 }
 
+/// A stored reliability score with the components and per-asset performance
+/// it was computed from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnchorReliabilityRecord {
+    pub anchor_id: String,
+    /// 60% asset performance, 30% volume and 10% asset diversity, on a 0-100 scale
+    pub composite_score: f64,
+    pub asset_performance_score: f64,
+    pub volume_score: f64,
+    pub asset_diversity_score: f64,
+    /// Success rate across the anchor's assets, weighted by volume
+    pub weighted_success_rate: f64,
+    pub total_assets: i64,
+    pub total_volume_usd: f64,
+    /// Version of the scoring model the score was computed with
+    pub scoring_version: Option<String>,
+    pub assets: Vec<crate::analytics::AnchorAssetPerformance>,
+    /// Start of the window of payments the score covers
+    pub window_start: DateTime<Utc>,
+    pub computed_at: DateTime<Utc>,
+}
+
+/// An anchor's stellar.toml as of the latest crawl
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnchorStellarTomlRecord {
+    pub anchor_id: String,
+    pub home_domain: String,
+    pub toml: crate::services::stellar_toml::StellarToml,
+    pub toml_hash: String,
+    /// SEP-1 problems found in the file
+    pub validation_errors: Vec<String>,
+    /// Why the latest crawl couldn't fetch or parse the file; the rest is
+    /// from the last crawl that could
+    pub fetch_error: Option<String>,
+    /// When the file last changed
+    pub changed_at: DateTime<Utc>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// A version of an anchor's stellar.toml
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnchorStellarTomlChange {
+    pub home_domain: String,
+    pub toml: crate::services::stellar_toml::StellarToml,
+    pub toml_hash: String,
+    pub validation_errors: Vec<String>,
+    pub fetched_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnchorDetailResponse {
     pub anchor: Anchor,
     pub assets: Vec<Asset>,
     pub metrics_history: Vec<AnchorMetricsHistory>,
+    /// Latest reliability score and its breakdown, once the scoring job has run
+    #[serde(default)]
+    pub reliability: Option<AnchorReliabilityRecord>,
+    /// Previous reliability scores, newest first
+    #[serde(default)]
+    pub reliability_history: Vec<AnchorReliabilityRecord>,
+    /// SEP-1 metadata, once the anchor's stellar.toml has been crawled
+    #[serde(default)]
+    pub stellar_toml: Option<AnchorStellarTomlRecord>,
+    /// Earlier versions of the stellar.toml, newest first
+    #[serde(default)]
+    pub stellar_toml_history: Vec<AnchorStellarTomlChange>,
+    /// Uptime and latency of each probed SEP endpoint over the last day
+    #[serde(default)]
+    pub endpoint_uptime: Vec<AnchorEndpointUptime>,
+}
+
+/// One request to an anchor's SEP endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AnchorEndpointProbe {
+    pub anchor_id: String,
+    /// `sep1`, `sep6`, `sep10`, `sep24`, `sep31` or `sep38`
+    pub sep: String,
+    pub url: String,
+    /// `None` when no response was received
+    pub status_code: Option<i64>,
+    pub latency_ms: i64,
+    /// Got a 2xx response
+    pub up: bool,
+    /// The response body has the fields the SEP requires
+    pub schema_valid: bool,
+    pub error: Option<String>,
+    pub probed_at: DateTime<Utc>,
+}
+
+/// Probe results for one SEP endpoint over a window
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AnchorEndpointUptime {
+    pub sep: String,
+    pub url: String,
+    pub probes: i64,
+    pub up_probes: i64,
+    pub schema_valid_probes: i64,
+    pub uptime_percentage: f64,
+    /// Mean latency of the probes that got a 2xx response
+    pub avg_latency_ms: Option<f64>,
+    pub last_probed_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -180,7 +298,24 @@ pub struct PaymentRecord {
     pub destination_asset_issuer: String,
     pub amount: f64,
     #[sqlx(default)]
+    pub operation_type: Option<String>,
+    /// Amount debited in the source asset, for path payments
+    #[sqlx(default)]
+    pub source_amount: Option<f64>,
+    /// Most a strict-receive path payment allowed to be debited
+    #[sqlx(default)]
+    pub send_max: Option<f64>,
+    /// Least a strict-send path payment allowed to be delivered
+    #[sqlx(default)]
+    pub dest_min: Option<f64>,
+    /// JSON array of the intermediate assets a path payment crossed
+    #[sqlx(default)]
+    pub path: Option<String>,
+    #[sqlx(default)]
     pub successful: bool,
+    /// Horizon result code, e.g. `op_success` or `op_underfunded`
+    #[sqlx(default)]
+    pub result_code: Option<String>,
     #[sqlx(default)]
     pub timestamp: Option<DateTime<Utc>>,
     #[sqlx(default)]
@@ -192,23 +327,31 @@ pub struct PaymentRecord {
 
 impl PaymentRecord {
     pub fn get_corridor(&self) -> crate::models::corridor::Corridor {
+        // Native XLM has no code or issuer on the wire
+        let asset_code = || self.asset_code.clone().unwrap_or_else(|| "XLM".to_string());
+        let asset_issuer = || {
+            self.asset_issuer
+                .clone()
+                .unwrap_or_else(|| "native".to_string())
+        };
+
         let src_code = if self.source_asset_code.is_empty() {
-            self.asset_code.clone().unwrap_or_default()
+            asset_code()
         } else {
             self.source_asset_code.clone()
         };
         let src_issuer = if self.source_asset_issuer.is_empty() {
-            self.asset_issuer.clone().unwrap_or_default()
+            asset_issuer()
         } else {
             self.source_asset_issuer.clone()
         };
         let dst_code = if self.destination_asset_code.is_empty() {
-            self.asset_code.clone().unwrap_or_default()
+            asset_code()
         } else {
             self.destination_asset_code.clone()
         };
         let dst_issuer = if self.destination_asset_issuer.is_empty() {
-            self.asset_issuer.clone().unwrap_or_default()
+            asset_issuer()
         } else {
             self.destination_asset_issuer.clone()
         };
@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, sqlx::FromRow)]
@@ -37,6 +38,26 @@ impl Corridor {
         }
     }
 
+    /// Parses a `CODE:ISSUER->CODE:ISSUER` key, in either asset order
+    pub fn from_key(key: &str) -> Option<Self> {
+        let (a, b) = key.split_once("->")?;
+        let (a_code, a_issuer) = a.split_once(':')?;
+        let (b_code, b_issuer) = b.split_once(':')?;
+        if [a_code, a_issuer, b_code, b_issuer]
+            .iter()
+            .any(|s| s.is_empty())
+        {
+            return None;
+        }
+
+        Some(Self::new(
+            a_code.to_string(),
+            a_issuer.to_string(),
+            b_code.to_string(),
+            b_issuer.to_string(),
+        ))
+    }
+
     pub fn to_string_key(&self) -> String {
         format!(
             "{}:{}->{}:{}",
@@ -63,8 +84,28 @@ pub struct CorridorMetrics {
     /// Median settlement latency in milliseconds
     #[sqlx(default)]
     pub median_settlement_latency_ms: Option<i32>,
+    /// 90th percentile settlement latency in milliseconds
+    #[serde(default)]
+    #[sqlx(default)]
+    pub p90_settlement_latency_ms: Option<i32>,
+    /// 99th percentile settlement latency in milliseconds
+    #[serde(default)]
+    #[sqlx(default)]
+    pub p99_settlement_latency_ms: Option<i32>,
     #[serde(default)]
     pub liquidity_depth_usd: f64,
+    /// Volume-weighted FX rate in units of asset B per asset A, for cross-asset corridors
+    #[serde(default)]
+    #[sqlx(default)]
+    pub avg_fx_rate: Option<f64>,
+    /// Mean realised slippage of path payments against their own limits
+    #[serde(default)]
+    #[sqlx(default)]
+    pub avg_slippage_bps: Option<f64>,
+    /// Failed payments per result code, e.g. `op_underfunded` or `op_no_trust`
+    #[serde(default)]
+    #[sqlx(skip)]
+    pub failure_breakdown: BTreeMap<String, i64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -100,8 +141,24 @@ pub struct PaymentRecord {
     pub source_asset_issuer: String,
     pub destination_asset_code: String,
     pub destination_asset_issuer: String,
+    /// Amount delivered, in the destination asset
     pub amount: f64,
+    /// Amount debited in the source asset, known for path payments
+    #[serde(default)]
+    pub source_amount: Option<f64>,
+    /// Most a strict-receive path payment allowed to be debited
+    #[serde(default)]
+    pub send_max: Option<f64>,
+    /// Least a strict-send path payment allowed to be delivered
+    #[serde(default)]
+    pub dest_min: Option<f64>,
+    /// Intermediate assets a path payment was routed through
+    #[serde(default)]
+    pub path: Vec<String>,
     pub successful: bool,
+    /// Result code explaining a failed payment, e.g. `op_underfunded`
+    #[serde(default)]
+    pub failure_reason: Option<String>,
     pub timestamp: DateTime<Utc>,
     /// Time when the transaction was submitted
     pub submission_time: Option<DateTime<Utc>>,
@@ -121,6 +178,33 @@ impl PaymentRecord {
         }
     }
 
+    /// Whether the payment converted between two different assets
+    pub fn is_cross_asset(&self) -> bool {
+        self.source_asset_code != self.destination_asset_code
+            || self.source_asset_issuer != self.destination_asset_issuer
+    }
+
+    /// Destination units received per source unit, for cross-asset payments
+    pub fn effective_fx_rate(&self) -> Option<f64> {
+        let source_amount = self.source_amount?;
+        if !self.is_cross_asset() || source_amount <= 0.0 || self.amount <= 0.0 {
+            return None;
+        }
+        Some(self.amount / source_amount)
+    }
+
+    /// Worst destination-per-source rate the payment's own limit accepted:
+    /// `dest_min` over the amount sent for strict sends, the amount delivered
+    /// over `send_max` for strict receives
+    pub fn limit_fx_rate(&self) -> Option<f64> {
+        let rate = match (self.dest_min, self.send_max) {
+            (Some(dest_min), _) => dest_min / self.source_amount?,
+            (None, Some(send_max)) => self.amount / send_max,
+            (None, None) => return None,
+        };
+        (rate.is_finite() && rate > 0.0).then_some(rate)
+    }
+
     /// Extracts the corridor from source and destination assets.
     pub fn get_corridor(&self) -> Corridor {
         Corridor::new(
@@ -147,6 +231,16 @@ pub fn compute_median(values: &mut [i64]) -> Option<i64> {
     }
 }
 
+/// Nearest-rank percentile (0-100) of a slice of i64 latency measurements.
+pub fn compute_percentile(values: &mut [i64], percentile: f64) -> Option<i64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let rank = (percentile / 100.0 * values.len() as f64).ceil() as usize;
+    Some(values[rank.clamp(1, values.len()) - 1])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -202,6 +296,17 @@ mod tests {
         assert!(key.contains("->"));
     }
 
+    #[test]
+    fn test_corridor_from_key_either_direction() {
+        let forward = Corridor::from_key("USDC:issuer1->EURC:issuer2").unwrap();
+        let reverse = Corridor::from_key("EURC:issuer2->USDC:issuer1").unwrap();
+        assert_eq!(forward, reverse);
+        assert_eq!(forward.to_string_key(), "EURC:issuer2->USDC:issuer1");
+
+        assert!(Corridor::from_key("USDC:issuer1").is_none());
+        assert!(Corridor::from_key("USDC->EURC:issuer2").is_none());
+    }
+
     #[test]
     fn test_payment_record_get_corridor() {
         let payment = PaymentRecord {
@@ -211,7 +316,12 @@ mod tests {
             destination_asset_code: "EURC".to_string(),
             destination_asset_issuer: "issuer2".to_string(),
             amount: 100.0,
+            source_amount: None,
+            send_max: None,
+            dest_min: None,
+            path: Vec::new(),
             successful: true,
+            failure_reason: None,
             timestamp: Utc::now(),
             submission_time: None,
             confirmation_time: None,
@@ -234,7 +344,12 @@ mod tests {
             destination_asset_code: "EURC".to_string(),
             destination_asset_issuer: "issuer2".to_string(),
             amount: 100.0,
+            source_amount: None,
+            send_max: None,
+            dest_min: None,
+            path: Vec::new(),
             successful: true,
+            failure_reason: None,
             timestamp: now,
             submission_time: Some(submitted),
             confirmation_time: Some(now),
@@ -252,7 +367,12 @@ mod tests {
             destination_asset_code: "EURC".to_string(),
             destination_asset_issuer: "issuer2".to_string(),
             amount: 100.0,
+            source_amount: None,
+            send_max: None,
+            dest_min: None,
+            path: Vec::new(),
             successful: true,
+            failure_reason: None,
             timestamp: Utc::now(),
             submission_time: None,
             confirmation_time: None,
@@ -284,4 +404,41 @@ mod tests {
         let mut values = vec![5000];
         assert_eq!(compute_median(&mut values), Some(5000));
     }
+
+    #[test]
+    fn test_compute_percentile() {
+        let mut values: Vec<i64> = (1..=100).rev().map(|v| v * 100).collect();
+        assert_eq!(compute_percentile(&mut values, 50.0), Some(5000));
+        assert_eq!(compute_percentile(&mut values, 90.0), Some(9000));
+        assert_eq!(compute_percentile(&mut values, 99.0), Some(9900));
+        assert_eq!(compute_percentile(&mut [7000], 99.0), Some(7000));
+        assert_eq!(compute_percentile(&mut [], 50.0), None);
+    }
+
+    #[test]
+    fn test_payment_record_effective_fx_rate() {
+        let mut payment = PaymentRecord {
+            id: Uuid::new_v4(),
+            source_asset_code: "USDC".to_string(),
+            source_asset_issuer: "issuer1".to_string(),
+            destination_asset_code: "EURC".to_string(),
+            destination_asset_issuer: "issuer2".to_string(),
+            amount: 92.0,
+            source_amount: Some(100.0),
+            send_max: None,
+            dest_min: None,
+            path: Vec::new(),
+            successful: true,
+            failure_reason: None,
+            timestamp: Utc::now(),
+            submission_time: None,
+            confirmation_time: None,
+        };
+        assert_eq!(payment.effective_fx_rate(), Some(0.92));
+
+        payment.destination_asset_code = "USDC".to_string();
+        payment.destination_asset_issuer = "issuer1".to_string();
+        assert!(!payment.is_cross_asset());
+        assert_eq!(payment.effective_fx_rate(), None);
+    }
 }
@@ -3,9 +3,10 @@ use std::collections::HashMap;
 use tokio::time::{interval, Duration};
 
 use crate::alerts::AlertManager;
-use crate::api::corridors_cached::CorridorResponse;
+use crate::api::corridors_cached::{extract_asset_pair_from_payment, CorridorResponse};
 use crate::cache::CacheManager;
 use crate::rpc::StellarRpcClient;
+use crate::services::settlement_latency::LatencySummary;
 
 pub struct CorridorMonitor {
     alert_manager: Arc<AlertManager>,
@@ -49,22 +50,37 @@ impl CorridorMonitor {
     async fn check_corridors(&self) -> anyhow::Result<()> {
         let payments = self.rpc_client.fetch_payments(200, None).await?;
         
+        // Key corridors the same way the corridor API does, so path payments
+        // land in their source -> destination corridor
         let mut corridor_map: HashMap<String, Vec<&crate::rpc::Payment>> = HashMap::new();
         for payment in &payments {
-            let key = format!(
-                "{}:{}->XLM:native",
-                payment.asset_code.as_deref().unwrap_or("XLM"),
-                payment.asset_issuer.as_deref().unwrap_or("native")
-            );
-            corridor_map.entry(key).or_insert_with(Vec::new).push(payment);
+            let Some(asset_pair) = extract_asset_pair_from_payment(payment) else {
+                continue;
+            };
+            corridor_map
+                .entry(asset_pair.to_corridor_key())
+                .or_insert_with(Vec::new)
+                .push(payment);
         }
 
         let mut prev_state = self.previous_state.write().await;
 
         for (corridor_id, payments) in corridor_map {
-            let success_rate = 100.0;
-            let latency = 400.0 + (success_rate * 2.0);
+            let successful = payments.iter().filter(|p| p.transaction_successful).count();
+            let success_rate = successful as f64 / payments.len() as f64 * 100.0;
+            // Without timed payments this round, carry the last latency forward
+            let latency = LatencySummary::from_samples(
+                payments
+                    .iter()
+                    .filter(|p| p.transaction_successful)
+                    .filter_map(|p| p.settlement_latency_ms())
+                    .collect(),
+            )
+            .map(|summary| summary.average_ms)
+            .or_else(|| prev_state.get(&corridor_id).map(|state| state.latency))
+            .unwrap_or(0.0);
             let liquidity: f64 = payments.iter()
+                .filter(|p| p.transaction_successful)
                 .filter_map(|p| p.amount.parse::<f64>().ok())
                 .sum();
 
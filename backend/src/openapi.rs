@@ -22,33 +22,79 @@ use utoipa::OpenApi;
     paths(
         crate::api::anchors_cached::get_anchors,
         crate::api::corridors_cached::list_corridors,
+        crate::api::anchors_cached::get_anchor_failures,
         crate::api::corridors_cached::get_corridor_detail,
+        crate::api::corridors_cached::get_corridor_failures,
+        crate::api::corridors_cached::get_corridor_distributions,
+        crate::api::corridors_cached::get_corridor_depth,
+        crate::api::corridors_cached::get_corridor_depth_history,
+        crate::api::corridors_cached::get_corridor_routes,
         crate::api::price_feed::get_price,
         crate::api::price_feed::get_prices,
         crate::api::price_feed::convert_to_usd,
         crate::api::price_feed::get_cache_stats,
+        crate::api::route_estimator::estimate_route,
+        crate::api::paths::strict_send,
+        crate::api::paths::strict_receive,
+        crate::api::scoring::list_models,
+        crate::api::scoring::compare_models,
     ),
     components(
         schemas(
             crate::api::anchors_cached::AnchorsResponse,
             crate::api::anchors_cached::AnchorMetricsResponse,
+            crate::api::anchors_cached::AnchorFailuresResponse,
+            crate::api::anchors_cached::CorridorFailureCount,
             crate::api::corridors_cached::CorridorResponse,
             crate::api::corridors_cached::CorridorDetailResponse,
             crate::api::corridors_cached::SuccessRateDataPoint,
             crate::api::corridors_cached::LatencyDataPoint,
             crate::api::corridors_cached::LiquidityDataPoint,
+            crate::api::corridors_cached::CorridorFailuresResponse,
+            crate::services::failure_analysis::FailureBreakdown,
+            crate::services::failure_analysis::FailureReasonStat,
+            crate::api::corridors_cached::CorridorDistributionsResponse,
+            crate::services::distribution::CorridorDistributions,
+            crate::services::distribution::DistributionSummary,
+            crate::services::order_book_depth::OrderBookDepth,
+            crate::services::order_book_depth::DepthPoint,
+            crate::services::order_book_depth::SlippageQuote,
+            crate::api::corridors_cached::CorridorDepthHistoryResponse,
+            crate::api::corridors_cached::CorridorRoutesResponse,
+            crate::analytics::corridor::PathShare,
+            crate::services::order_book_depth::DepthSnapshot,
             crate::api::price_feed::PriceResponse,
             crate::api::price_feed::PricesResponse,
             crate::api::price_feed::ConvertResponse,
             crate::api::price_feed::CacheStatsResponse,
+            crate::services::route_estimator::RouteEstimate,
+            crate::services::route_estimator::VenueFill,
+            crate::api::paths::PathsResponse,
+            crate::services::path_finder::PathQuote,
+            crate::services::path_finder::PathHop,
+            crate::api::scoring::ScoringModelsResponse,
+            crate::scoring::ScoringModel,
+            crate::scoring::CorridorHealthModel,
+            crate::scoring::AnchorMetricsModel,
+            crate::scoring::AnchorStatusModel,
+            crate::scoring::AnchorUptimeModel,
+            crate::scoring::AnchorReliabilityModel,
+            crate::api::scoring::ScoringComparisonResponse,
+            crate::api::scoring::ComparisonSummary,
+            crate::api::scoring::ScoreDeltaSummary,
+            crate::api::scoring::AnchorMetricsComparison,
+            crate::api::scoring::AnchorReliabilityComparison,
+            crate::api::scoring::CorridorHealthComparison,
         )
     ),
     tags(
         (name = "Anchors", description = "Anchor management and metrics endpoints"),
         (name = "Corridors", description = "Payment corridor analytics endpoints"),
         (name = "Prices", description = "Real-time asset price feed endpoints"),
+        (name = "Routes", description = "Route estimation and path finding endpoints"),
         (name = "RPC", description = "Stellar RPC integration endpoints"),
         (name = "Fee Bumps", description = "Fee bump transaction tracking"),
+        (name = "Scoring", description = "Versioned scoring models and candidate comparison"),
         (name = "Cache", description = "Cache management and statistics"),
         (name = "Metrics", description = "System metrics and monitoring")
     )
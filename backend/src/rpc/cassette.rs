@@ -0,0 +1,290 @@
+use anyhow::{Context, Result};
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Which of the client's two endpoints a request went to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CassetteTarget {
+    Rpc,
+    Horizon,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub target: CassetteTarget,
+    /// Path and query relative to the endpoint's base URL
+    pub path: String,
+    /// JSON-RPC payload, for RPC requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub body: Value,
+}
+
+/// One request/response pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+impl Interaction {
+    fn match_key(&self) -> String {
+        match_key(
+            self.request.target,
+            &self.request.path,
+            self.request.body.as_ref(),
+        )
+    }
+}
+
+/// Recorded traffic, stored as JSON Lines with one interaction per line
+#[derive(Debug, Clone, Default)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read cassette {}", path.display()))?;
+        Self::from_jsonl(&contents).with_context(|| format!("Invalid cassette {}", path.display()))
+    }
+
+    pub fn from_jsonl(contents: &str) -> Result<Self> {
+        let interactions = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("Invalid interaction on line {}", i + 1))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { interactions })
+    }
+}
+
+/// Appends every interaction to a cassette file as it happens, so a capture
+/// survives the process being killed
+pub struct CassetteRecorder {
+    path: PathBuf,
+    file: Mutex<std::fs::File>,
+}
+
+impl CassetteRecorder {
+    /// Open `path` for appending, creating it if needed
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open cassette {}", path.display()))?;
+
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn record(&self, interaction: &Interaction) -> Result<()> {
+        let mut line = serde_json::to_string(interaction)?;
+        line.push('\n');
+
+        let mut file = self.file.lock().unwrap();
+        file.write_all(line.as_bytes())?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+/// JSON-RPC request ids change between runs, so only the method and params
+/// identify a call
+fn match_key(target: CassetteTarget, path: &str, body: Option<&Value>) -> String {
+    let call = body
+        .map(|b| json!({ "method": b.get("method"), "params": b.get("params") }).to_string())
+        .unwrap_or_default();
+    format!("{:?} {} {}", target, path, call)
+}
+
+// ============================================================================
+// Replay Server
+// ============================================================================
+
+struct ReplayState {
+    /// Responses per request, served in recorded order; the last one repeats
+    responses: Mutex<HashMap<String, (Vec<RecordedResponse>, usize)>>,
+}
+
+/// A local stand-in for RPC and Horizon that serves a cassette.
+///
+/// Point a client at `rpc_url()` and `horizon_url()`. Requests that weren't
+/// recorded get a 404 so gaps in a capture show up as failures.
+pub struct ReplayServer {
+    addr: SocketAddr,
+    handle: JoinHandle<()>,
+}
+
+impl ReplayServer {
+    /// Serve `cassette` on an ephemeral localhost port
+    pub async fn start(cassette: Cassette) -> Result<Self> {
+        Self::bind(cassette, SocketAddr::from(([127, 0, 0, 1], 0))).await
+    }
+
+    pub async fn bind(cassette: Cassette, addr: SocketAddr) -> Result<Self> {
+        let mut responses: HashMap<String, (Vec<RecordedResponse>, usize)> = HashMap::new();
+        for interaction in cassette.interactions {
+            responses
+                .entry(interaction.match_key())
+                .or_default()
+                .0
+                .push(interaction.response);
+        }
+
+        let state = Arc::new(ReplayState {
+            responses: Mutex::new(responses),
+        });
+        let app = Router::new().fallback(replay).with_state(state);
+
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind replay server to {}", addr))?;
+        let addr = listener.local_addr()?;
+        let handle = tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                warn!("Cassette replay server stopped: {}", e);
+            }
+        });
+
+        info!("Replaying cassette on http://{}", addr);
+        Ok(Self { addr, handle })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn rpc_url(&self) -> String {
+        format!("http://{}/rpc", self.addr)
+    }
+
+    pub fn horizon_url(&self) -> String {
+        format!("http://{}/horizon", self.addr)
+    }
+}
+
+impl Drop for ReplayServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn replay(State(state): State<Arc<ReplayState>>, uri: Uri, body: Bytes) -> Response {
+    let path_and_query = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    let (target, path) = if let Some(path) = path_and_query.strip_prefix("/rpc") {
+        (CassetteTarget::Rpc, path)
+    } else if let Some(path) = path_and_query.strip_prefix("/horizon") {
+        (CassetteTarget::Horizon, path)
+    } else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "unknown endpoint", "path": path_and_query })),
+        )
+            .into_response();
+    };
+
+    let body: Option<Value> = serde_json::from_slice(&body).ok();
+    let key = match_key(target, path, body.as_ref());
+
+    let recorded = {
+        let mut responses = state.responses.lock().unwrap();
+        responses.get_mut(&key).map(|(queue, next)| {
+            let response = queue[(*next).min(queue.len() - 1)].clone();
+            *next += 1;
+            response
+        })
+    };
+
+    match recorded {
+        Some(response) => (
+            StatusCode::from_u16(response.status).unwrap_or(StatusCode::OK),
+            Json(response.body),
+        )
+            .into_response(),
+        None => {
+            warn!("No recorded interaction for {:?} {}", target, path);
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({ "error": "no recorded interaction", "path": path })),
+            )
+                .into_response()
+        }
+    }
+}
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    Record,
+    Replay,
+}
+
+#[derive(Debug, Clone)]
+pub struct CassetteConfig {
+    pub mode: CassetteMode,
+    pub path: PathBuf,
+}
+
+impl CassetteConfig {
+    /// Read `RPC_CASSETTE_MODE` (record or replay) and `RPC_CASSETTE_PATH`
+    pub fn from_env() -> Option<Self> {
+        let mode = match std::env::var("RPC_CASSETTE_MODE")
+            .ok()?
+            .to_lowercase()
+            .as_str()
+        {
+            "record" => CassetteMode::Record,
+            "replay" => CassetteMode::Replay,
+            other => {
+                warn!("Invalid RPC_CASSETTE_MODE value '{}', ignoring", other);
+                return None;
+            }
+        };
+
+        let Ok(path) = std::env::var("RPC_CASSETTE_PATH") else {
+            warn!("RPC_CASSETTE_MODE is set but RPC_CASSETTE_PATH is not, ignoring");
+            return None;
+        };
+
+        Some(Self {
+            mode,
+            path: PathBuf::from(path),
+        })
+    }
+}
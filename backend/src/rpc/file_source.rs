@@ -0,0 +1,525 @@
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use stellar_xdr::curr::{
+    Asset, LedgerCloseMeta, LedgerEntryChange, LedgerEntryChanges, LedgerEntryData,
+    LedgerHeaderHistoryEntry, LedgerKey, Limited, Limits, LiquidityPoolEntry,
+    LiquidityPoolEntryBody, ReadXdr, TransactionMeta, VecM, WriteXdr,
+};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::ingestion::ledger_meta::{asset_code, asset_issuer, format_amount};
+use crate::rpc::ledger_source::LedgerSource;
+use crate::rpc::stellar::{
+    GetLedgersResult, HealthResponse, HorizonLiquidityPool, HorizonPoolReserve, RpcLedger, Trade,
+};
+
+/// Extension of uncompressed batch files
+const BATCH_EXTENSION: &str = "xdr";
+
+/// A `LedgerCloseMetaBatch`: consecutive ledgers stored in one file
+#[derive(Debug, Clone)]
+pub struct LedgerBatch {
+    pub start_sequence: u32,
+    pub end_sequence: u32,
+    pub ledgers: Vec<LedgerCloseMeta>,
+}
+
+#[derive(Debug, Clone)]
+struct BatchFile {
+    end: u32,
+    path: PathBuf,
+}
+
+/// Ledgers from a directory of `LedgerCloseMetaBatch` files, the format
+/// Galexie exports to a data lake.
+///
+/// Files are discovered recursively, so partitioned exports work as-is. Only
+/// uncompressed `.xdr` batches are read; zstd exports need decompressing
+/// first. Asking for a ledger past the newest known one re-scans the
+/// directory, so an export that is still being written can be tailed.
+///
+/// Pool states are rebuilt from the close meta, replaying each ledger once as
+/// batches appear. Pool trade history isn't, so `fetch_pool_trades` returns
+/// nothing.
+pub struct FileLedgerSource {
+    root: PathBuf,
+    network_passphrase: String,
+    /// Batch files keyed by their first ledger
+    index: RwLock<BTreeMap<u32, BatchFile>>,
+    pools: Mutex<PoolState>,
+}
+
+/// Every pool's latest state as of ledger `through`
+#[derive(Default)]
+struct PoolState {
+    through: Option<u32>,
+    pools: BTreeMap<String, HorizonLiquidityPool>,
+}
+
+impl FileLedgerSource {
+    /// Index the batch files under `root`
+    pub fn open(root: impl Into<PathBuf>, network_passphrase: &str) -> Result<Self> {
+        let root = root.into();
+        anyhow::ensure!(
+            root.is_dir(),
+            "Ledger source path {} is not a directory",
+            root.display()
+        );
+
+        let source = Self {
+            root,
+            network_passphrase: network_passphrase.to_string(),
+            index: RwLock::new(BTreeMap::new()),
+            pools: Mutex::new(PoolState::default()),
+        };
+        let files = source.install(scan_batch_files(&source.root)?);
+        info!(
+            "Indexed {} ledger batch files under {}",
+            files,
+            source.root.display()
+        );
+
+        Ok(source)
+    }
+
+    /// Re-scan the directory for batch files, returning how many were found.
+    /// The scan runs on the blocking pool so a large data lake doesn't stall
+    /// the runtime.
+    pub async fn refresh(&self) -> Result<usize> {
+        let root = self.root.clone();
+        let index = tokio::task::spawn_blocking(move || scan_batch_files(&root))
+            .await
+            .context("Ledger batch scan panicked")??;
+        Ok(self.install(index))
+    }
+
+    fn install(&self, index: BTreeMap<u32, BatchFile>) -> usize {
+        let count = index.len();
+        *self.index.write().unwrap() = index;
+        count
+    }
+
+    /// First and last ledger available, if any
+    pub fn ledger_bounds(&self) -> Option<(u32, u32)> {
+        let index = self.index.read().unwrap();
+        let (first, _) = index.first_key_value()?;
+        let last = index.values().map(|f| f.end).max()?;
+        Some((*first, last))
+    }
+
+    /// Ledgers in `from..=to` that are present on disk, in order
+    pub async fn read_ledgers(&self, from: u32, to: u32) -> Result<Vec<LedgerCloseMeta>> {
+        let files: Vec<BatchFile> = {
+            let index = self.index.read().unwrap();
+            index
+                .range(..=to)
+                .filter(|(_, file)| file.end >= from)
+                .map(|(_, file)| file.clone())
+                .collect()
+        };
+
+        let mut ledgers = Vec::new();
+        for file in files {
+            let batch = read_batch_file(&file.path).await?;
+            ledgers.extend(batch.ledgers.into_iter().filter(|meta| {
+                let sequence = ledger_header(meta).header.ledger_seq;
+                sequence >= from && sequence <= to
+            }));
+        }
+
+        Ok(ledgers)
+    }
+}
+
+#[async_trait::async_trait]
+impl LedgerSource for FileLedgerSource {
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    fn network_passphrase(&self) -> &str {
+        &self.network_passphrase
+    }
+
+    async fn check_health(&self) -> Result<HealthResponse> {
+        self.refresh().await?;
+        let (oldest, latest) = self.ledger_bounds().unwrap_or((0, 0));
+
+        Ok(HealthResponse {
+            status: if latest > 0 { "healthy" } else { "empty" }.to_string(),
+            latest_ledger: latest as u64,
+            oldest_ledger: oldest as u64,
+            ledger_retention_window: latest.saturating_sub(oldest) as u64,
+        })
+    }
+
+    async fn fetch_ledgers(
+        &self,
+        start_ledger: Option<u64>,
+        limit: u32,
+        cursor: Option<&str>,
+    ) -> Result<GetLedgersResult> {
+        let requested = cursor
+            .and_then(|c| c.parse::<u64>().ok())
+            .map(|last| last + 1)
+            .or(start_ledger);
+
+        let known_latest = self.ledger_bounds().map(|(_, latest)| latest as u64);
+        if known_latest.is_none_or(|latest| requested.is_some_and(|start| start > latest)) {
+            self.refresh().await?;
+        }
+
+        let Some((oldest, latest)) = self.ledger_bounds() else {
+            return Ok(GetLedgersResult {
+                ledgers: Vec::new(),
+                latest_ledger: 0,
+                oldest_ledger: 0,
+                cursor: cursor.map(str::to_string),
+            });
+        };
+
+        let start = requested.unwrap_or(oldest as u64).max(oldest as u64);
+        let end = start
+            .saturating_add(limit.max(1) as u64 - 1)
+            .min(latest as u64);
+
+        let ledgers = if start > end {
+            Vec::new()
+        } else {
+            self.read_ledgers(start as u32, end as u32)
+                .await?
+                .iter()
+                .map(rpc_ledger)
+                .collect::<Result<Vec<_>>>()?
+        };
+
+        let cursor = ledgers
+            .last()
+            .map(|l| l.sequence.to_string())
+            .or_else(|| cursor.map(str::to_string));
+
+        Ok(GetLedgersResult {
+            ledgers,
+            latest_ledger: latest as u64,
+            oldest_ledger: oldest as u64,
+            cursor,
+        })
+    }
+
+    async fn fetch_liquidity_pools(
+        &self,
+        limit: u32,
+        cursor: Option<&str>,
+    ) -> Result<Vec<HorizonLiquidityPool>> {
+        let mut state = self.pools.lock().await;
+        self.refresh().await?;
+        let files: Vec<BatchFile> = {
+            let index = self.index.read().unwrap();
+            index
+                .values()
+                .filter(|file| state.through.is_none_or(|through| file.end > through))
+                .cloned()
+                .collect()
+        };
+
+        // Replaying the pool entry changes of each ledger after the last one
+        // applied leaves every pool at its latest state. A missing ledger
+        // stops the replay until it shows up.
+        'files: for file in files {
+            for meta in read_batch_file(&file.path).await?.ledgers {
+                let sequence = ledger_header(&meta).header.ledger_seq;
+                match state.through {
+                    Some(through) if sequence <= through => continue,
+                    Some(through) if sequence > through + 1 => {
+                        warn!(
+                            "Ledger {} is missing, pool states stay at ledger {}",
+                            through + 1,
+                            through
+                        );
+                        break 'files;
+                    }
+                    _ => {}
+                }
+                apply_pool_changes(&meta, &mut state.pools);
+                state.through = Some(sequence);
+            }
+        }
+
+        Ok(state
+            .pools
+            .values()
+            .filter(|pool| cursor.is_none_or(|c| pool.id.as_str() > c))
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn fetch_pool_trades(&self, _pool_id: &str, _limit: u32) -> Result<Vec<Trade>> {
+        Ok(Vec::new())
+    }
+}
+
+// ============================================================================
+// Batch Files
+// ============================================================================
+
+/// Galexie's object name for a batch: the reversed start sequence as a sort
+/// prefix, then the covered range
+pub fn batch_file_name(start_sequence: u32, end_sequence: u32) -> String {
+    let prefix = format!("{:08X}--{}", u32::MAX - start_sequence, start_sequence);
+    if start_sequence == end_sequence {
+        format!("{}.{}", prefix, BATCH_EXTENSION)
+    } else {
+        format!("{}-{}.{}", prefix, end_sequence, BATCH_EXTENSION)
+    }
+}
+
+/// Decode an uncompressed `LedgerCloseMetaBatch`
+pub fn decode_batch(bytes: &[u8]) -> Result<LedgerBatch> {
+    let mut reader = Limited::new(Cursor::new(bytes), Limits::none());
+    let start_sequence = u32::read_xdr(&mut reader).context("Failed to read batch start")?;
+    let end_sequence = u32::read_xdr(&mut reader).context("Failed to read batch end")?;
+    let ledgers =
+        VecM::<LedgerCloseMeta>::read_xdr(&mut reader).context("Failed to decode batch ledgers")?;
+
+    Ok(LedgerBatch {
+        start_sequence,
+        end_sequence,
+        ledgers: ledgers.into(),
+    })
+}
+
+/// Encode consecutive ledgers as a `LedgerCloseMetaBatch`
+pub fn encode_batch(ledgers: &[LedgerCloseMeta]) -> Result<Vec<u8>> {
+    let first = ledgers.first().context("A ledger batch can't be empty")?;
+    let last = ledgers.last().context("A ledger batch can't be empty")?;
+
+    let mut writer = Limited::new(Vec::new(), Limits::none());
+    ledger_header(first)
+        .header
+        .ledger_seq
+        .write_xdr(&mut writer)?;
+    ledger_header(last)
+        .header
+        .ledger_seq
+        .write_xdr(&mut writer)?;
+    VecM::<LedgerCloseMeta>::try_from(ledgers.to_vec())?.write_xdr(&mut writer)?;
+
+    Ok(writer.inner)
+}
+
+/// Write ledgers into `dir` as a Galexie-named batch file
+pub fn write_batch(dir: &Path, ledgers: &[LedgerCloseMeta]) -> Result<PathBuf> {
+    let bytes = encode_batch(ledgers)?;
+    let batch = decode_batch(&bytes)?;
+    let path = dir.join(batch_file_name(batch.start_sequence, batch.end_sequence));
+    std::fs::write(&path, bytes).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+async fn read_batch_file(path: &Path) -> Result<LedgerBatch> {
+    let bytes = tokio::fs::read(path)
+        .await
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    decode_batch(&bytes).with_context(|| format!("Invalid ledger batch {}", path.display()))
+}
+
+/// A batch's range from its first eight bytes, without decoding the ledgers
+/// Batch files under `root`, found recursively and keyed by their first ledger
+fn scan_batch_files(root: &Path) -> Result<BTreeMap<u32, BatchFile>> {
+    let mut index = BTreeMap::new();
+    let mut compressed = 0usize;
+    let mut dirs = vec![root.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        for entry in
+            std::fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))?
+        {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+
+            match path.extension().and_then(|e| e.to_str()) {
+                Some(BATCH_EXTENSION) => {
+                    let (start, end) = read_batch_bounds(&path)?;
+                    index.insert(start, BatchFile { end, path });
+                }
+                Some("zst") | Some("zstd") => compressed += 1,
+                _ => {}
+            }
+        }
+    }
+
+    if compressed > 0 {
+        warn!(
+            "Skipping {} compressed ledger batches under {}; decompress them to .xdr to ingest",
+            compressed,
+            root.display()
+        );
+    }
+
+    Ok(index)
+}
+
+fn read_batch_bounds(path: &Path) -> Result<(u32, u32)> {
+    let mut header = [0u8; 8];
+    std::fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .with_context(|| format!("Failed to read batch header of {}", path.display()))?;
+
+    let start = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+    let end = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+    anyhow::ensure!(
+        start <= end,
+        "Batch {} has an inverted range",
+        path.display()
+    );
+    Ok((start, end))
+}
+
+// ============================================================================
+// Conversions
+// ============================================================================
+
+fn ledger_header(meta: &LedgerCloseMeta) -> &LedgerHeaderHistoryEntry {
+    match meta {
+        LedgerCloseMeta::V0(v0) => &v0.ledger_header,
+        LedgerCloseMeta::V1(v1) => &v1.ledger_header,
+        LedgerCloseMeta::V2(v2) => &v2.ledger_header,
+    }
+}
+
+/// The `getLedgers` view of a ledger
+fn rpc_ledger(meta: &LedgerCloseMeta) -> Result<RpcLedger> {
+    let header = ledger_header(meta);
+
+    Ok(RpcLedger {
+        hash: hex::encode(header.hash.0),
+        sequence: header.header.ledger_seq as u64,
+        ledger_close_time: header.header.scp_value.close_time.0.to_string(),
+        header_xdr: Some(BASE64.encode(header.to_xdr(Limits::none())?)),
+        metadata_xdr: Some(BASE64.encode(meta.to_xdr(Limits::none())?)),
+    })
+}
+
+fn apply_pool_changes(meta: &LedgerCloseMeta, pools: &mut BTreeMap<String, HorizonLiquidityPool>) {
+    let per_transaction: Vec<Vec<&LedgerEntryChange>> = match meta {
+        LedgerCloseMeta::V0(v0) => v0
+            .tx_processing
+            .iter()
+            .map(|m| transaction_changes(&m.fee_processing, &m.tx_apply_processing, None))
+            .collect(),
+        LedgerCloseMeta::V1(v1) => v1
+            .tx_processing
+            .iter()
+            .map(|m| transaction_changes(&m.fee_processing, &m.tx_apply_processing, None))
+            .collect(),
+        LedgerCloseMeta::V2(v2) => v2
+            .tx_processing
+            .iter()
+            .map(|m| {
+                transaction_changes(
+                    &m.fee_processing,
+                    &m.tx_apply_processing,
+                    Some(&m.post_tx_apply_fee_processing),
+                )
+            })
+            .collect(),
+    };
+
+    for changes in per_transaction {
+        for change in changes {
+            match change {
+                LedgerEntryChange::Created(entry) | LedgerEntryChange::Updated(entry) => {
+                    if let LedgerEntryData::LiquidityPool(pool) = &entry.data {
+                        let record = pool_record(pool);
+                        pools.insert(record.id.clone(), record);
+                    }
+                }
+                LedgerEntryChange::Removed(LedgerKey::LiquidityPool(key)) => {
+                    pools.remove(&hex::encode(key.liquidity_pool_id.0 .0));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Every ledger entry change a transaction made, in order. V2 meta (from
+/// protocol 23) records fee refunds after the apply meta.
+fn transaction_changes<'a>(
+    fee_processing: &'a LedgerEntryChanges,
+    tx_apply_processing: &'a TransactionMeta,
+    post_apply_fee_processing: Option<&'a LedgerEntryChanges>,
+) -> Vec<&'a LedgerEntryChange> {
+    let mut changes: Vec<&LedgerEntryChange> = fee_processing.0.iter().collect();
+    match tx_apply_processing {
+        TransactionMeta::V0(operations) => {
+            changes.extend(operations.iter().flat_map(|op| op.changes.0.iter()));
+        }
+        TransactionMeta::V1(v1) => {
+            changes.extend(v1.tx_changes.0.iter());
+            changes.extend(v1.operations.iter().flat_map(|op| op.changes.0.iter()));
+        }
+        TransactionMeta::V2(v2) => {
+            changes.extend(v2.tx_changes_before.0.iter());
+            changes.extend(v2.operations.iter().flat_map(|op| op.changes.0.iter()));
+            changes.extend(v2.tx_changes_after.0.iter());
+        }
+        TransactionMeta::V3(v3) => {
+            changes.extend(v3.tx_changes_before.0.iter());
+            changes.extend(v3.operations.iter().flat_map(|op| op.changes.0.iter()));
+            changes.extend(v3.tx_changes_after.0.iter());
+        }
+        TransactionMeta::V4(v4) => {
+            changes.extend(v4.tx_changes_before.0.iter());
+            changes.extend(v4.operations.iter().flat_map(|op| op.changes.0.iter()));
+            changes.extend(v4.tx_changes_after.0.iter());
+        }
+    }
+    changes.extend(
+        post_apply_fee_processing
+            .into_iter()
+            .flat_map(|c| c.0.iter()),
+    );
+    changes
+}
+
+fn pool_record(entry: &LiquidityPoolEntry) -> HorizonLiquidityPool {
+    let LiquidityPoolEntryBody::LiquidityPoolConstantProduct(pool) = &entry.body;
+    let id = hex::encode(entry.liquidity_pool_id.0 .0);
+
+    HorizonLiquidityPool {
+        id: id.clone(),
+        fee_bp: pool.params.fee as u32,
+        pool_type: "constant_product".to_string(),
+        total_trustlines: pool.pool_shares_trust_line_count as u64,
+        total_shares: format_amount(pool.total_pool_shares),
+        reserves: vec![
+            HorizonPoolReserve {
+                asset: horizon_asset(&pool.params.asset_a),
+                amount: format_amount(pool.reserve_a),
+            },
+            HorizonPoolReserve {
+                asset: horizon_asset(&pool.params.asset_b),
+                amount: format_amount(pool.reserve_b),
+            },
+        ],
+        paging_token: Some(id),
+    }
+}
+
+/// Horizon's canonical asset string: `native` or `CODE:ISSUER`
+fn horizon_asset(asset: &Asset) -> String {
+    match (asset_code(asset), asset_issuer(asset)) {
+        (Some(code), Some(issuer)) => format!("{}:{}", code, issuer),
+        _ => "native".to_string(),
+    }
+}
@@ -0,0 +1,245 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::rpc::file_source::FileLedgerSource;
+use crate::rpc::stellar::{
+    GetLedgersResult, HealthResponse, HorizonLiquidityPool, StellarRpcClient, Trade,
+};
+
+/// Where ledger-derived data comes from.
+///
+/// Ledger ingestion and pool analysis depend on this trait instead of a
+/// transport, so the same services run against Soroban RPC, Horizon or
+/// archived ledger files.
+#[async_trait::async_trait]
+pub trait LedgerSource: Send + Sync {
+    /// Short backend name for logs
+    fn name(&self) -> &str;
+
+    /// Passphrase of the network the ledgers belong to, needed to hash transactions
+    fn network_passphrase(&self) -> &str;
+
+    /// Whether `fetch_ledgers` serves ledgers with their close meta. Sources
+    /// that don't can back pool analysis but not ledger ingestion.
+    fn serves_ledgers(&self) -> bool {
+        true
+    }
+
+    /// Oldest and latest ledgers this source can serve
+    async fn check_health(&self) -> Result<HealthResponse>;
+
+    /// Fetch up to `limit` consecutive ledgers from `start_ledger`, or after `cursor`
+    async fn fetch_ledgers(
+        &self,
+        start_ledger: Option<u64>,
+        limit: u32,
+        cursor: Option<&str>,
+    ) -> Result<GetLedgersResult>;
+
+    /// Current liquidity pool states
+    async fn fetch_liquidity_pools(
+        &self,
+        limit: u32,
+        cursor: Option<&str>,
+    ) -> Result<Vec<HorizonLiquidityPool>>;
+
+    /// Recent trades against a liquidity pool
+    async fn fetch_pool_trades(&self, pool_id: &str, limit: u32) -> Result<Vec<Trade>>;
+}
+
+// ============================================================================
+// Soroban RPC
+// ============================================================================
+
+/// Ledgers from Soroban RPC `getLedgers`.
+///
+/// RPC doesn't index pools or trades, so those still come from
+/// the client's Horizon endpoint.
+pub struct RpcLedgerSource {
+    client: Arc<StellarRpcClient>,
+}
+
+impl RpcLedgerSource {
+    pub fn new(client: Arc<StellarRpcClient>) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait::async_trait]
+impl LedgerSource for RpcLedgerSource {
+    fn name(&self) -> &str {
+        "rpc"
+    }
+
+    fn network_passphrase(&self) -> &str {
+        &self.client.network_config().network_passphrase
+    }
+
+    async fn check_health(&self) -> Result<HealthResponse> {
+        self.client.check_health().await
+    }
+
+    async fn fetch_ledgers(
+        &self,
+        start_ledger: Option<u64>,
+        limit: u32,
+        cursor: Option<&str>,
+    ) -> Result<GetLedgersResult> {
+        self.client.fetch_ledgers(start_ledger, limit, cursor).await
+    }
+
+    async fn fetch_liquidity_pools(
+        &self,
+        limit: u32,
+        cursor: Option<&str>,
+    ) -> Result<Vec<HorizonLiquidityPool>> {
+        self.client.fetch_liquidity_pools(limit, cursor).await
+    }
+
+    async fn fetch_pool_trades(&self, pool_id: &str, limit: u32) -> Result<Vec<Trade>> {
+        self.client.fetch_pool_trades(pool_id, limit).await
+    }
+}
+
+// ============================================================================
+// Horizon
+// ============================================================================
+
+/// Pools, trades and the ingested ledger range from Horizon.
+///
+/// Horizon doesn't serve `LedgerCloseMeta`, so ledgers fetched from it would
+/// carry no transactions; `fetch_ledgers` refuses rather than ingest them
+/// empty.
+pub struct HorizonLedgerSource {
+    client: Arc<StellarRpcClient>,
+}
+
+impl HorizonLedgerSource {
+    pub fn new(client: Arc<StellarRpcClient>) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait::async_trait]
+impl LedgerSource for HorizonLedgerSource {
+    fn name(&self) -> &str {
+        "horizon"
+    }
+
+    fn network_passphrase(&self) -> &str {
+        &self.client.network_config().network_passphrase
+    }
+
+    fn serves_ledgers(&self) -> bool {
+        false
+    }
+
+    async fn check_health(&self) -> Result<HealthResponse> {
+        let root = self.client.fetch_horizon_root().await?;
+        Ok(HealthResponse {
+            status: "healthy".to_string(),
+            latest_ledger: root.history_latest_ledger,
+            oldest_ledger: root.history_elder_ledger,
+            ledger_retention_window: root
+                .history_latest_ledger
+                .saturating_sub(root.history_elder_ledger),
+        })
+    }
+
+    async fn fetch_ledgers(
+        &self,
+        _start_ledger: Option<u64>,
+        _limit: u32,
+        _cursor: Option<&str>,
+    ) -> Result<GetLedgersResult> {
+        anyhow::bail!(
+            "Horizon doesn't serve LedgerCloseMeta, so ledgers can't be ingested from it. \
+             Use the 'rpc' or 'file' ledger source"
+        )
+    }
+
+    async fn fetch_liquidity_pools(
+        &self,
+        limit: u32,
+        cursor: Option<&str>,
+    ) -> Result<Vec<HorizonLiquidityPool>> {
+        self.client.fetch_liquidity_pools(limit, cursor).await
+    }
+
+    async fn fetch_pool_trades(&self, pool_id: &str, limit: u32) -> Result<Vec<Trade>> {
+        self.client.fetch_pool_trades(pool_id, limit).await
+    }
+}
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerSourceKind {
+    Rpc,
+    /// Pools and trades only; ledger ingestion is skipped
+    Horizon,
+    File,
+}
+
+impl std::str::FromStr for LedgerSourceKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "rpc" => Ok(LedgerSourceKind::Rpc),
+            "horizon" => Ok(LedgerSourceKind::Horizon),
+            "file" => Ok(LedgerSourceKind::File),
+            _ => Err(format!(
+                "Invalid ledger source: {}. Must be 'rpc', 'horizon' or 'file'",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LedgerSourceConfig {
+    pub kind: LedgerSourceKind,
+    /// Directory of ledger batch files, for the file backend
+    pub path: Option<PathBuf>,
+}
+
+impl LedgerSourceConfig {
+    /// Read `LEDGER_SOURCE` (rpc, horizon or file) and `LEDGER_SOURCE_PATH`
+    pub fn from_env() -> Self {
+        let kind_str = std::env::var("LEDGER_SOURCE").unwrap_or_else(|_| "rpc".to_string());
+        let kind = kind_str.parse::<LedgerSourceKind>().unwrap_or_else(|_| {
+            tracing::warn!(
+                "Invalid LEDGER_SOURCE value '{}', defaulting to rpc",
+                kind_str
+            );
+            LedgerSourceKind::Rpc
+        });
+
+        Self {
+            kind,
+            path: std::env::var("LEDGER_SOURCE_PATH").ok().map(PathBuf::from),
+        }
+    }
+
+    /// Build the configured source on top of the shared client
+    pub fn build(&self, client: Arc<StellarRpcClient>) -> Result<Arc<dyn LedgerSource>> {
+        Ok(match self.kind {
+            LedgerSourceKind::Rpc => Arc::new(RpcLedgerSource::new(client)),
+            LedgerSourceKind::Horizon => Arc::new(HorizonLedgerSource::new(client)),
+            LedgerSourceKind::File => {
+                let path = self
+                    .path
+                    .clone()
+                    .context("LEDGER_SOURCE_PATH is required for the file ledger source")?;
+                Arc::new(FileLedgerSource::open(
+                    path,
+                    &client.network_config().network_passphrase,
+                )?)
+            }
+        })
+    }
+}
@@ -1,8 +1,15 @@
+pub mod cassette;
+pub mod file_source;
+pub mod ledger_source;
 pub mod stellar;
 
+pub use cassette::{Cassette, CassetteConfig, CassetteMode, CassetteRecorder, ReplayServer};
+pub use file_source::FileLedgerSource;
+pub use ledger_source::{
+    HorizonLedgerSource, LedgerSource, LedgerSourceConfig, LedgerSourceKind, RpcLedgerSource,
+};
 pub use stellar::{
-    Asset, FeeBumpTransactionInfo, GetLedgersResult, HealthResponse, HorizonAsset, HorizonEffect,
-    HorizonLiquidityPool, HorizonOperation, HorizonPoolReserve, HorizonTransaction,
-    InnerTransaction, LedgerInfo, OrderBook, OrderBookEntry, Payment, Price, RpcLedger,
-    StellarRpcClient, Trade,
+    Asset, GetLedgersResult, HealthResponse, HorizonAsset, HorizonEffect, HorizonLiquidityPool,
+    HorizonPoolReserve, HorizonRoot, LedgerInfo, OrderBook, OrderBookEntry, Payment, Price,
+    RpcLedger, StellarRpcClient, Trade,
 };
@@ -1,16 +1,21 @@
+use crate::ingestion::ledger_meta::payment_result_code_from_xdr;
 use crate::network::{NetworkConfig, StellarNetwork};
+use crate::rpc::cassette::{
+    CassetteRecorder, CassetteTarget, Interaction, RecordedRequest, RecordedResponse,
+};
+use crate::services::settlement_latency::settlement_latency_ms;
 use anyhow::{Context, Result};
 use reqwest::Client;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
 const MAX_RETRIES: u32 = 3;
 const INITIAL_BACKOFF_MS: u64 = 100;
 const BACKOFF_MULTIPLIER: u64 = 2;
-const MOCK_OLDEST_LEDGER: u64 = 51_565_760;
-const MOCK_LATEST_LEDGER: u64 = 51_565_820;
 
 /// Stellar RPC Client for interacting with Stellar network via RPC and Horizon API
 // Asset Models (Horizon API)
@@ -59,7 +64,18 @@ pub struct StellarRpcClient {
     rpc_url: String,
     horizon_url: String,
     network_config: NetworkConfig,
-    mock_mode: bool,
+    recorder: Option<Arc<CassetteRecorder>>,
+}
+
+/// Response body read up front, so it can be written to a cassette before parsing
+struct CapturedResponse {
+    body: Vec<u8>,
+}
+
+impl CapturedResponse {
+    async fn json<T: DeserializeOwned>(self) -> serde_json::Result<T> {
+        serde_json::from_slice(&self.body)
+    }
 }
 
 // ============================================================================
@@ -126,24 +142,83 @@ pub struct Payment {
     pub source_asset_code: Option<String>,
     pub source_asset_issuer: Option<String>,
     pub source_amount: Option<String>,
+    // Limits of path payments: the most a strict receive may debit and the
+    // least a strict send must deliver
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_max: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination_min: Option<String>,
+    // Intermediate assets a path payment was routed through
+    #[serde(default)]
+    pub path: Vec<Asset>,
     // For regular payments, 'from' field
     pub from: Option<String>,
     // For regular payments, 'to' field
     pub to: Option<String>,
+    // Failed payments are only returned when requested with include_failed=true
+    #[serde(default = "default_transaction_successful")]
+    pub transaction_successful: bool,
+    // Enclosing transaction, embedded when requested with join=transactions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<PaymentTransaction>,
+}
+
+fn default_transaction_successful() -> bool {
+    true
 }
 
+/// The parts of a joined transaction needed to explain a failed payment and
+/// time its settlement
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct HorizonOperation {
-    pub id: String,
-    pub paging_token: String,
-    pub transaction_hash: String,
-    pub source_account: String,
-    #[serde(rename = "type")]
-    pub operation_type: String,
-    pub created_at: String,
-    pub account: Option<String>,
-    pub into: Option<String>,
-    pub amount: Option<String>,
+pub struct PaymentTransaction {
+    pub result_xdr: String,
+    /// Lower time bound, present when the transaction has time bounds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_after: Option<String>,
+}
+
+impl Payment {
+    /// Result code for a failed payment (e.g. `op_underfunded`), decoded from
+    /// the joined transaction result. Falls back to `tx_failed` when the
+    /// result is unavailable, and is `None` for successful payments.
+    pub fn failure_reason(&self) -> Option<String> {
+        if self.transaction_successful {
+            return None;
+        }
+
+        // The low 12 bits of an operation id are its 1-based index in the transaction
+        let op_index = self
+            .id
+            .parse::<u64>()
+            .ok()
+            .map(|toid| ((toid & 0xfff) as usize).saturating_sub(1));
+        let code = self
+            .transaction
+            .as_ref()
+            .zip(op_index)
+            .and_then(|(tx, op_index)| payment_result_code_from_xdr(&tx.result_xdr, op_index).ok())
+            .unwrap_or("tx_failed");
+
+        Some(code.to_string())
+    }
+
+    /// When the enclosing transaction became valid, taken as its submission
+    /// time. `None` without time bounds or with an unset (zero) lower bound.
+    pub fn submission_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let valid_after = self.transaction.as_ref()?.valid_after.as_deref()?;
+        let valid_after = chrono::DateTime::parse_from_rfc3339(valid_after).ok()?;
+        (valid_after.timestamp() > 0).then(|| valid_after.with_timezone(&chrono::Utc))
+    }
+
+    /// Milliseconds from submission to the close of the ledger the payment
+    /// landed in (Horizon's `created_at`)
+    pub fn settlement_latency_ms(&self) -> Option<i64> {
+        let closed_at = chrono::DateTime::parse_from_rfc3339(&self.created_at).ok()?;
+        settlement_latency_ms(
+            self.submission_time(),
+            closed_at.with_timezone(&chrono::Utc),
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -156,42 +231,6 @@ pub struct HorizonEffect {
     pub asset_type: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct HorizonTransaction {
-    pub id: String,
-    pub hash: String,
-    pub ledger: u64,
-    pub created_at: String,
-    pub source_account: String,
-    #[serde(rename = "fee_account")]
-    pub fee_account: Option<String>,
-    #[serde(rename = "fee_charged")]
-    pub fee_charged: Option<String>, // Can be number or string, Horizon usually string
-    #[serde(rename = "max_fee")]
-    pub max_fee: Option<String>,
-    pub operation_count: u32,
-    pub successful: bool,
-    pub paging_token: String,
-    #[serde(rename = "fee_bump_transaction")]
-    pub fee_bump_transaction: Option<FeeBumpTransactionInfo>,
-    #[serde(rename = "inner_transaction")]
-    pub inner_transaction: Option<InnerTransaction>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FeeBumpTransactionInfo {
-    pub hash: String,
-    pub signatures: Vec<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct InnerTransaction {
-    pub hash: String,
-    #[serde(rename = "max_fee")]
-    pub max_fee: Option<String>,
-    pub signatures: Vec<String>,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Trade {
     pub id: String,
@@ -238,6 +277,30 @@ pub struct Asset {
     pub asset_issuer: Option<String>,
 }
 
+impl Asset {
+    /// Asset for a code and issuer as they appear in corridor keys, where
+    /// XLM's issuer is `native`
+    pub fn from_code_and_issuer(code: &str, issuer: &str) -> Self {
+        if issuer == "native" {
+            return Self {
+                asset_type: "native".to_string(),
+                asset_code: None,
+                asset_issuer: None,
+            };
+        }
+        let asset_type = if code.len() <= 4 {
+            "credit_alphanum4"
+        } else {
+            "credit_alphanum12"
+        };
+        Self {
+            asset_type: asset_type.to_string(),
+            asset_code: Some(code.to_string()),
+            asset_issuer: Some(issuer.to_string()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HorizonResponse<T> {
     #[serde(rename = "_embedded")]
@@ -274,6 +337,22 @@ pub struct GetLedgersResult {
     pub cursor: Option<String>,
 }
 
+/// Ledger range reported by the Horizon root endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HorizonRoot {
+    pub history_latest_ledger: u64,
+    pub history_elder_ledger: u64,
+}
+
+/// The parts of a Horizon account record used for anchor discovery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HorizonAccount {
+    pub account_id: String,
+    /// Domain hosting the account's stellar.toml, if set
+    #[serde(default)]
+    pub home_domain: Option<String>,
+}
+
 // ============================================================================
 // Liquidity Pool Models (Horizon API)
 // ============================================================================
@@ -309,8 +388,7 @@ impl StellarRpcClient {
     /// # Arguments
     /// * `rpc_url` - The Stellar RPC endpoint URL (e.g., OnFinality)
     /// * `horizon_url` - The Horizon API endpoint URL
-    /// * `mock_mode` - If true, returns mock data instead of making real API calls
-    pub fn new(rpc_url: String, horizon_url: String, mock_mode: bool) -> Self {
+    pub fn new(rpc_url: String, horizon_url: String) -> Self {
         let client = Client::builder()
             .timeout(Duration::from_secs(30))
             .build()
@@ -330,14 +408,17 @@ impl StellarRpcClient {
             rpc_url,
             horizon_url,
             network_config,
-            mock_mode,
+            recorder: None,
         }
     }
 
     /// Create a new client with network configuration
-    pub fn new_with_network(network: StellarNetwork, mock_mode: bool) -> Self {
-        let network_config = NetworkConfig::for_network(network);
-        
+    pub fn new_with_network(network: StellarNetwork) -> Self {
+        Self::new_with_config(NetworkConfig::for_network(network))
+    }
+
+    /// Create a new client from an explicit network configuration
+    pub fn new_with_config(network_config: NetworkConfig) -> Self {
         let client = Client::builder()
             .timeout(Duration::from_secs(30))
             .build()
@@ -348,13 +429,19 @@ impl StellarRpcClient {
             rpc_url: network_config.rpc_url.clone(),
             horizon_url: network_config.horizon_url.clone(),
             network_config,
-            mock_mode,
+            recorder: None,
         }
     }
 
+    /// Write every successful RPC and Horizon exchange to a cassette
+    pub fn with_recorder(mut self, recorder: Arc<CassetteRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
     /// Create a new client with default OnFinality RPC and Horizon URLs (mainnet)
-    pub fn new_with_defaults(mock_mode: bool) -> Self {
-        Self::new_with_network(StellarNetwork::Mainnet, mock_mode)
+    pub fn new_with_defaults() -> Self {
+        Self::new_with_network(StellarNetwork::Mainnet)
     }
 
     /// Get the current network configuration
@@ -379,10 +466,6 @@ impl StellarRpcClient {
 
     /// Check the health of the RPC endpoint
     pub async fn check_health(&self) -> Result<HealthResponse> {
-        if self.mock_mode {
-            return Ok(Self::mock_health_response());
-        }
-
         info!("Checking RPC health at {}", self.rpc_url);
 
         let payload = json!({
@@ -392,7 +475,7 @@ impl StellarRpcClient {
         });
 
         let response = self
-            .retry_request(|| async { self.client.post(&self.rpc_url).json(&payload).send().await })
+            .rpc_post(&payload)
             .await
             .context("Failed to check RPC health")?;
 
@@ -410,16 +493,12 @@ impl StellarRpcClient {
 
     /// Fetch latest ledger information
     pub async fn fetch_latest_ledger(&self) -> Result<LedgerInfo> {
-        if self.mock_mode {
-            return Ok(Self::mock_ledger_info());
-        }
-
         info!("Fetching latest ledger from Horizon API");
 
         let url = format!("{}/ledgers?order=desc&limit=1", self.horizon_url);
 
         let response = self
-            .retry_request(|| async { self.client.get(&url).send().await })
+            .horizon_get(&url)
             .await
             .context("Failed to fetch latest ledger")?;
 
@@ -443,18 +522,6 @@ impl StellarRpcClient {
         limit: u32,
         cursor: Option<&str>,
     ) -> Result<GetLedgersResult> {
-        if self.mock_mode {
-            let start = if let Some(c) = cursor {
-                c.parse::<u64>()
-                    .ok()
-                    .map(|v| v.saturating_add(1))
-                    .unwrap_or_else(|| start_ledger.unwrap_or(MOCK_OLDEST_LEDGER))
-            } else {
-                start_ledger.unwrap_or(MOCK_OLDEST_LEDGER)
-            };
-            return Ok(Self::mock_get_ledgers(start, limit));
-        }
-
         info!("Fetching ledgers via RPC getLedgers");
 
         let mut params = serde_json::Map::new();
@@ -480,7 +547,7 @@ impl StellarRpcClient {
         });
 
         let response = self
-            .retry_request(|| async { self.client.post(&self.rpc_url).json(&payload).send().await })
+            .rpc_post(&payload)
             .await
             .context("Failed to fetch ledgers")?;
 
@@ -498,22 +565,50 @@ impl StellarRpcClient {
             .context("No result in getLedgers response")
     }
 
+    /// Fetch the ledger range Horizon has ingested
+    pub async fn fetch_horizon_root(&self) -> Result<HorizonRoot> {
+        let response = self
+            .horizon_get(&self.horizon_url)
+            .await
+            .context("Failed to fetch Horizon root")?;
+
+        response
+            .json()
+            .await
+            .context("Failed to parse Horizon root response")
+    }
+
+    /// Fetch an account, for its home domain
+    pub async fn fetch_account(&self, account_id: &str) -> Result<HorizonAccount> {
+        let url = format!("{}/accounts/{}", self.horizon_url, account_id);
+        let response = self
+            .horizon_get(&url)
+            .await
+            .context("Failed to fetch account")?;
+
+        response
+            .json()
+            .await
+            .context("Failed to parse account response")
+    }
+
     /// Fetch recent payments
     pub async fn fetch_payments(&self, limit: u32, cursor: Option<&str>) -> Result<Vec<Payment>> {
-        if self.mock_mode {
-            return Ok(Self::mock_payments(limit));
-        }
-
         info!("Fetching {} payments from Horizon API", limit);
 
-        let mut url = format!("{}/payments?order=desc&limit={}", self.horizon_url, limit);
+        // Failed payments are included so success rates can be computed; the
+        // joined transaction carries the result codes explaining them
+        let mut url = format!(
+            "{}/payments?order=desc&limit={}&include_failed=true&join=transactions",
+            self.horizon_url, limit
+        );
 
         if let Some(cursor) = cursor {
             url.push_str(&format!("&cursor={}", cursor));
         }
 
         let response = self
-            .retry_request(|| async { self.client.get(&url).send().await })
+            .horizon_get(&url)
             .await
             .context("Failed to fetch payments")?;
 
@@ -532,10 +627,6 @@ impl StellarRpcClient {
 
     /// Fetch recent trades
     pub async fn fetch_trades(&self, limit: u32, cursor: Option<&str>) -> Result<Vec<Trade>> {
-        if self.mock_mode {
-            return Ok(Self::mock_trades(limit));
-        }
-
         info!("Fetching {} trades from Horizon API", limit);
 
         let mut url = format!("{}/trades?order=desc&limit={}", self.horizon_url, limit);
@@ -545,7 +636,7 @@ impl StellarRpcClient {
         }
 
         let response = self
-            .retry_request(|| async { self.client.get(&url).send().await })
+            .horizon_get(&url)
             .await
             .context("Failed to fetch trades")?;
 
@@ -569,10 +660,6 @@ impl StellarRpcClient {
         buying_asset: &Asset,
         limit: u32,
     ) -> Result<OrderBook> {
-        if self.mock_mode {
-            return Ok(Self::mock_order_book(selling_asset, buying_asset));
-        }
-
         info!("Fetching order book from Horizon API");
 
         let selling_params = Self::asset_to_query_params("selling", selling_asset);
@@ -584,7 +671,7 @@ impl StellarRpcClient {
         );
 
         let response = self
-            .retry_request(|| async { self.client.get(&url).send().await })
+            .horizon_get(&url)
             .await
             .context("Failed to fetch order book")?;
 
@@ -596,105 +683,15 @@ impl StellarRpcClient {
         Ok(order_book)
     }
 
-    pub async fn fetch_payments_for_ledger(&self, sequence: u64) -> Result<Vec<Payment>> {
-        if self.mock_mode {
-            return Ok(Self::mock_payments(5));
-        }
-
-        let url = format!(
-            "{}/ledgers/{}/payments?limit=200",
-            self.horizon_url, sequence
-        );
-
-        let response = self
-            .retry_request(|| async { self.client.get(&url).send().await })
-            .await
-            .context("Failed to fetch ledger payments")?;
-
-        let horizon_response: HorizonResponse<Payment> = response
-            .json()
-            .await
-            .context("Failed to parse ledger payments response")?;
-
-        Ok(horizon_response
-            .embedded
-            .map(|e| e.records)
-            .unwrap_or_default())
-    }
-
-    /// Fetch transactions for a specific ledger
-    pub async fn fetch_transactions_for_ledger(
-        &self,
-        sequence: u64,
-    ) -> Result<Vec<HorizonTransaction>> {
-        if self.mock_mode {
-            return Ok(Self::mock_transactions(5, sequence));
-        }
-
-        let url = format!(
-            "{}/ledgers/{}/transactions?limit=200&include_failed=true",
-            self.horizon_url, sequence
-        );
-
-        let response = self
-            .retry_request(|| async { self.client.get(&url).send().await })
-            .await
-            .context("Failed to fetch ledger transactions")?;
-
-        let horizon_response: HorizonResponse<HorizonTransaction> = response
-            .json()
-            .await
-            .context("Failed to parse ledger transactions response")?;
-
-        Ok(horizon_response
-            .embedded
-            .map(|e| e.records)
-            .unwrap_or_default())
-    }
-
-    /// Fetch operations for a specific ledger
-    pub async fn fetch_operations_for_ledger(
-        &self,
-        sequence: u64,
-    ) -> Result<Vec<HorizonOperation>> {
-        if self.mock_mode {
-            return Ok(Self::mock_operations_for_ledger(sequence));
-        }
-
-        let url = format!(
-            "{}/ledgers/{}/operations?limit=200",
-            self.horizon_url, sequence
-        );
-
-        let response = self
-            .retry_request(|| async { self.client.get(&url).send().await })
-            .await
-            .context("Failed to fetch ledger operations")?;
-
-        let horizon_response: HorizonResponse<HorizonOperation> = response
-            .json()
-            .await
-            .context("Failed to parse ledger operations response")?;
-
-        Ok(horizon_response
-            .embedded
-            .map(|e| e.records)
-            .unwrap_or_default())
-    }
-
     /// Fetch effects for a specific operation
     pub async fn fetch_operation_effects(&self, operation_id: &str) -> Result<Vec<HorizonEffect>> {
-        if self.mock_mode {
-            return Ok(Self::mock_effects_for_operation(operation_id));
-        }
-
         let url = format!(
             "{}/operations/{}/effects?limit=200",
             self.horizon_url, operation_id
         );
 
         let response = self
-            .retry_request(|| async { self.client.get(&url).send().await })
+            .horizon_get(&url)
             .await
             .context("Failed to fetch operation effects")?;
 
@@ -715,10 +712,6 @@ impl StellarRpcClient {
         account_id: &str,
         limit: u32,
     ) -> Result<Vec<Payment>> {
-        if self.mock_mode {
-            return Ok(Self::mock_payments(limit));
-        }
-
         info!(
             "Fetching {} payments for account {} from Horizon API",
             limit, account_id
@@ -730,7 +723,7 @@ impl StellarRpcClient {
         );
 
         let response = self
-            .retry_request(|| async { self.client.get(&url).send().await })
+            .horizon_get(&url)
             .await
             .context("Failed to fetch account payments")?;
 
@@ -768,6 +761,66 @@ impl StellarRpcClient {
         }
     }
 
+    /// POST a JSON-RPC payload to the RPC endpoint
+    async fn rpc_post(&self, payload: &serde_json::Value) -> Result<CapturedResponse> {
+        let response = self
+            .retry_request(|| async { self.client.post(&self.rpc_url).json(payload).send().await })
+            .await?;
+        self.capture(CassetteTarget::Rpc, "", Some(payload), response)
+            .await
+    }
+
+    /// GET a URL under the Horizon endpoint
+    async fn horizon_get(&self, url: &str) -> Result<CapturedResponse> {
+        let response = self
+            .retry_request(|| async { self.client.get(url).send().await })
+            .await?;
+        let path = url.strip_prefix(self.horizon_url.as_str()).unwrap_or(url);
+        self.capture(CassetteTarget::Horizon, path, None, response)
+            .await
+    }
+
+    /// Read the body, recording the exchange when a cassette is attached
+    async fn capture(
+        &self,
+        target: CassetteTarget,
+        path: &str,
+        payload: Option<&serde_json::Value>,
+        response: reqwest::Response,
+    ) -> Result<CapturedResponse> {
+        let status = response.status().as_u16();
+        let body = response
+            .bytes()
+            .await
+            .context("Failed to read response body")?
+            .to_vec();
+
+        if let Some(recorder) = &self.recorder {
+            let interaction = Interaction {
+                request: RecordedRequest {
+                    target,
+                    path: path.to_string(),
+                    body: payload.cloned(),
+                },
+                response: RecordedResponse {
+                    status,
+                    body: serde_json::from_slice(&body).unwrap_or_else(|_| {
+                        serde_json::Value::String(String::from_utf8_lossy(&body).into_owned())
+                    }),
+                },
+            };
+            if let Err(e) = recorder.record(&interaction) {
+                warn!(
+                    "Failed to record interaction to {}: {}",
+                    recorder.path().display(),
+                    e
+                );
+            }
+        }
+
+        Ok(CapturedResponse { body })
+    }
+
     /// Retry a request with exponential backoff
     async fn retry_request<F, Fut>(&self, request_fn: F) -> Result<reqwest::Response>
     where
@@ -840,355 +893,6 @@ impl StellarRpcClient {
         }
     }
 
-    // ============================================================================
-    // Mock Data Methods
-    // ============================================================================
-
-    fn mock_health_response() -> HealthResponse {
-        HealthResponse {
-            status: "healthy".to_string(),
-            latest_ledger: MOCK_LATEST_LEDGER,
-            oldest_ledger: MOCK_OLDEST_LEDGER,
-            ledger_retention_window: 60,
-        }
-    }
-
-    fn mock_ledger_info() -> LedgerInfo {
-        LedgerInfo {
-            sequence: 51583040,
-            hash: "abc123def456".to_string(),
-            previous_hash: "xyz789uvw012".to_string(),
-            transaction_count: 245,
-            operation_count: 1203,
-            closed_at: "2026-01-22T10:30:00Z".to_string(),
-            total_coins: "105443902087.3472865".to_string(),
-            fee_pool: "3145678.9012345".to_string(),
-            base_fee: 100,
-            base_reserve: "0.5".to_string(),
-        }
-    }
-
-    // I'm mocking getLedgers response for testing
-    fn mock_get_ledgers(start: u64, limit: u32) -> GetLedgersResult {
-        if start > MOCK_LATEST_LEDGER {
-            return GetLedgersResult {
-                ledgers: Vec::new(),
-                latest_ledger: MOCK_LATEST_LEDGER,
-                oldest_ledger: MOCK_OLDEST_LEDGER,
-                cursor: Some(MOCK_LATEST_LEDGER.to_string()),
-            };
-        }
-
-        let end = (start.saturating_add(limit as u64).saturating_sub(1)).min(MOCK_LATEST_LEDGER);
-        let ledgers = (start..=end)
-            .enumerate()
-            .map(|(i, seq)| RpcLedger {
-                hash: format!("hash_{}", seq),
-                sequence: seq,
-                ledger_close_time: format!("{}", 1734032457 + i as u64 * 5),
-                header_xdr: Some("mock_header".to_string()),
-                metadata_xdr: Some("mock_metadata".to_string()),
-            })
-            .collect();
-
-        GetLedgersResult {
-            ledgers,
-            latest_ledger: MOCK_LATEST_LEDGER,
-            oldest_ledger: MOCK_OLDEST_LEDGER,
-            cursor: Some(end.to_string()),
-        }
-    }
-
-    fn mock_payments(limit: u32) -> Vec<Payment> {
-        (0..limit)
-            .map(|i| {
-                let is_path_payment = i % 5 == 0;
-                let is_native_source = i % 3 == 0;
-                let is_native_dest = i % 4 == 0;
-
-                Payment {
-                    id: format!("payment_{}", i),
-                    paging_token: format!("paging_{}", i),
-                    transaction_hash: format!("txhash_{}", i),
-                    source_account: format!(
-                        "GXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX{:03}",
-                        i
-                    ),
-                    destination: format!(
-                        "GDYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYY{:03}",
-                        i
-                    ),
-                    // For regular payments, asset_type/code/issuer represent the transferred asset
-                    // For path payments, they represent the destination asset
-                    asset_type: if is_native_dest {
-                        "native".to_string()
-                    } else if i % 2 == 0 {
-                        "credit_alphanum4".to_string()
-                    } else {
-                        "credit_alphanum12".to_string()
-                    },
-                    asset_code: if is_native_dest {
-                        None
-                    } else if i % 2 == 0 {
-                        Some(["USDC", "EURT", "BRL", "NGNT"][i as usize % 4].to_string())
-                    } else {
-                        Some("LONGASSETCODE".to_string())
-                    },
-                    asset_issuer: if is_native_dest {
-                        None
-                    } else {
-                        Some(format!(
-                            "GISSUER{:02}XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX",
-                            i % 10
-                        ))
-                    },
-                    amount: format!("{}.0000000", 100 + i * 10),
-                    created_at: format!("2026-01-22T10:{:02}:00Z", i % 60),
-                    operation_type: if is_path_payment {
-                        Some(if i % 2 == 0 {
-                            "path_payment_strict_send".to_string()
-                        } else {
-                            "path_payment_strict_receive".to_string()
-                        })
-                    } else {
-                        Some("payment".to_string())
-                    },
-                    // Source asset for path payments
-                    source_asset_type: if is_path_payment {
-                        Some(if is_native_source {
-                            "native".to_string()
-                        } else {
-                            "credit_alphanum4".to_string()
-                        })
-                    } else {
-                        None
-                    },
-                    source_asset_code: if is_path_payment && !is_native_source {
-                        Some(["USD", "EUR", "GBP", "JPY"][i as usize % 4].to_string())
-                    } else {
-                        None
-                    },
-                    source_asset_issuer: if is_path_payment && !is_native_source {
-                        Some(format!(
-                            "GSRCISSUER{:02}XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX",
-                            i % 10
-                        ))
-                    } else {
-                        None
-                    },
-                    source_amount: if is_path_payment {
-                        Some(format!("{}.0000000", 90 + i * 10))
-                    } else {
-                        None
-                    },
-                    from: Some(format!(
-                        "GXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX{:03}",
-                        i
-                    )),
-                    to: Some(format!(
-                        "GDYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYY{:03}",
-                        i
-                    )),
-                }
-            })
-            .collect()
-    }
-
-    fn mock_trades(limit: u32) -> Vec<Trade> {
-        (0..limit)
-            .map(|i| Trade {
-                id: format!("trade_{}", i),
-                ledger_close_time: format!("2026-01-22T10:{:02}:00Z", i % 60),
-                base_account: format!("GXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX{:03}", i),
-                base_amount: format!("{}.0000000", 1000 + i * 100),
-                base_asset_type: "native".to_string(),
-                base_asset_code: None,
-                base_asset_issuer: None,
-                counter_account: format!(
-                    "GDYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYYY{:03}",
-                    i
-                ),
-                counter_amount: format!("{}.0000000", 500 + i * 50),
-                counter_asset_type: "credit_alphanum4".to_string(),
-                counter_asset_code: Some("USDC".to_string()),
-                counter_asset_issuer: Some(
-                    "GBXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX".to_string(),
-                ),
-                price: Price {
-                    n: 2 + i as i64,
-                    d: 1,
-                },
-                trade_type: "orderbook".to_string(),
-            })
-            .collect()
-    }
-
-    fn mock_order_book(selling_asset: &Asset, buying_asset: &Asset) -> OrderBook {
-        let bids = vec![
-            OrderBookEntry {
-                price: "0.9950".to_string(),
-                amount: "1000.0000000".to_string(),
-                price_r: Price { n: 199, d: 200 },
-            },
-            OrderBookEntry {
-                price: "0.9900".to_string(),
-                amount: "2500.0000000".to_string(),
-                price_r: Price { n: 99, d: 100 },
-            },
-            OrderBookEntry {
-                price: "0.9850".to_string(),
-                amount: "5000.0000000".to_string(),
-                price_r: Price { n: 197, d: 200 },
-            },
-        ];
-
-        let asks = vec![
-            OrderBookEntry {
-                price: "1.0050".to_string(),
-                amount: "1200.0000000".to_string(),
-                price_r: Price { n: 201, d: 200 },
-            },
-            OrderBookEntry {
-                price: "1.0100".to_string(),
-                amount: "3000.0000000".to_string(),
-                price_r: Price { n: 101, d: 100 },
-            },
-            OrderBookEntry {
-                price: "1.0150".to_string(),
-                amount: "4500.0000000".to_string(),
-                price_r: Price { n: 203, d: 200 },
-            },
-        ];
-
-        OrderBook {
-            bids,
-            asks,
-            base: selling_asset.clone(),
-            counter: buying_asset.clone(),
-        }
-    }
-
-    fn mock_transactions(limit: u32, ledger_sequence: u64) -> Vec<HorizonTransaction> {
-        (0..limit)
-            .map(|i| {
-                let is_fee_bump = i % 2 == 0;
-                HorizonTransaction {
-                    id: format!("tx_{}", i),
-                    hash: format!("txhash_{}", i),
-                    ledger: ledger_sequence,
-                    created_at: "2026-01-22T10:30:00Z".to_string(),
-                    source_account: "GXX".to_string(),
-                    fee_account: Some("GXX".to_string()),
-                    fee_charged: Some("100".to_string()),
-                    max_fee: Some("1000".to_string()),
-                    operation_count: 1,
-                    successful: true,
-                    paging_token: format!("pt_{}", i),
-                    fee_bump_transaction: if is_fee_bump {
-                        Some(FeeBumpTransactionInfo {
-                            hash: format!("fb_hash_{}", i),
-                            signatures: vec!["sig1".to_string()],
-                        })
-                    } else {
-                        None
-                    },
-                    inner_transaction: if is_fee_bump {
-                        Some(InnerTransaction {
-                            hash: format!("inner_hash_{}", i),
-                            max_fee: Some("500".to_string()),
-                            signatures: vec!["sig1".to_string()],
-                        })
-                    } else {
-                        None
-                    },
-                }
-            })
-            .collect()
-    }
-
-    fn mock_operations_for_ledger(sequence: u64) -> Vec<HorizonOperation> {
-        let source_a = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA".to_string();
-        let source_b = "GBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBB".to_string();
-        let dest_a = "GDESTAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA".to_string();
-        let dest_b = "GDESTBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBB".to_string();
-
-        vec![
-            HorizonOperation {
-                id: format!("op_{}_0", sequence),
-                paging_token: format!("pt_{}_0", sequence),
-                transaction_hash: format!("txhash_{}_0", sequence),
-                source_account: source_a.clone(),
-                operation_type: "account_merge".to_string(),
-                created_at: "2026-01-22T10:30:00Z".to_string(),
-                account: Some(source_a),
-                into: Some(dest_a),
-                amount: None,
-            },
-            HorizonOperation {
-                id: format!("op_{}_1", sequence),
-                paging_token: format!("pt_{}_1", sequence),
-                transaction_hash: format!("txhash_{}_1", sequence),
-                source_account: "GCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCC"
-                    .to_string(),
-                operation_type: "payment".to_string(),
-                created_at: "2026-01-22T10:31:00Z".to_string(),
-                account: None,
-                into: None,
-                amount: Some("25.0000000".to_string()),
-            },
-            HorizonOperation {
-                id: format!("op_{}_2", sequence),
-                paging_token: format!("pt_{}_2", sequence),
-                transaction_hash: format!("txhash_{}_2", sequence),
-                source_account: source_b.clone(),
-                operation_type: "account_merge".to_string(),
-                created_at: "2026-01-22T10:32:00Z".to_string(),
-                account: Some(source_b),
-                into: Some(dest_b),
-                amount: None,
-            },
-        ]
-    }
-
-    fn mock_effects_for_operation(operation_id: &str) -> Vec<HorizonEffect> {
-        if operation_id.ends_with("_0") {
-            return vec![HorizonEffect {
-                id: format!("effect_{}_0", operation_id),
-                effect_type: "account_credited".to_string(),
-                account: Some(
-                    "GDESTAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA".to_string(),
-                ),
-                amount: Some("125.5000000".to_string()),
-                asset_type: Some("native".to_string()),
-            }];
-        }
-
-        if operation_id.ends_with("_2") {
-            return vec![
-                HorizonEffect {
-                    id: format!("effect_{}_0", operation_id),
-                    effect_type: "account_credited".to_string(),
-                    account: Some(
-                        "GDESTBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBB".to_string(),
-                    ),
-                    amount: Some("10.0000000".to_string()),
-                    asset_type: Some("native".to_string()),
-                },
-                HorizonEffect {
-                    id: format!("effect_{}_1", operation_id),
-                    effect_type: "account_credited".to_string(),
-                    account: Some(
-                        "GDESTBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBB".to_string(),
-                    ),
-                    amount: Some("0.5000000".to_string()),
-                    asset_type: Some("native".to_string()),
-                },
-            ];
-        }
-
-        Vec::new()
-    }
-
     // ============================================================================
     // Liquidity Pool Methods
     // ============================================================================
@@ -1199,10 +903,6 @@ impl StellarRpcClient {
         limit: u32,
         cursor: Option<&str>,
     ) -> Result<Vec<HorizonLiquidityPool>> {
-        if self.mock_mode {
-            return Ok(Self::mock_liquidity_pools(limit));
-        }
-
         info!("Fetching {} liquidity pools from Horizon API", limit);
 
         let mut url = format!(
@@ -1215,7 +915,7 @@ impl StellarRpcClient {
         }
 
         let response = self
-            .retry_request(|| async { self.client.get(&url).send().await })
+            .horizon_get(&url)
             .await
             .context("Failed to fetch liquidity pools")?;
 
@@ -1232,19 +932,12 @@ impl StellarRpcClient {
 
     /// Fetch a single liquidity pool by ID
     pub async fn fetch_liquidity_pool(&self, pool_id: &str) -> Result<HorizonLiquidityPool> {
-        if self.mock_mode {
-            let pools = Self::mock_liquidity_pools(1);
-            let mut pool = pools.into_iter().next().unwrap();
-            pool.id = pool_id.to_string();
-            return Ok(pool);
-        }
-
         info!("Fetching liquidity pool {} from Horizon API", pool_id);
 
         let url = format!("{}/liquidity_pools/{}", self.horizon_url, pool_id);
 
         let response = self
-            .retry_request(|| async { self.client.get(&url).send().await })
+            .horizon_get(&url)
             .await
             .context("Failed to fetch liquidity pool")?;
 
@@ -1258,10 +951,6 @@ impl StellarRpcClient {
 
     /// Fetch trades for a specific liquidity pool
     pub async fn fetch_pool_trades(&self, pool_id: &str, limit: u32) -> Result<Vec<Trade>> {
-        if self.mock_mode {
-            return Ok(Self::mock_trades(limit));
-        }
-
         info!(
             "Fetching {} trades for pool {} from Horizon API",
             limit, pool_id
@@ -1273,7 +962,7 @@ impl StellarRpcClient {
         );
 
         let response = self
-            .retry_request(|| async { self.client.get(&url).send().await })
+            .horizon_get(&url)
             .await
             .context("Failed to fetch pool trades")?;
 
@@ -1290,10 +979,6 @@ impl StellarRpcClient {
 
     /// Fetch assets from Horizon API, sorted by rating
     pub async fn fetch_assets(&self, limit: u32, rating_sort: bool) -> Result<Vec<HorizonAsset>> {
-        if self.mock_mode {
-            return Ok(Self::mock_assets(limit));
-        }
-
         info!("Fetching {} assets from Horizon API", limit);
         let mut url = format!("{}/assets?limit={}", self.horizon_url, limit);
         if rating_sort {
@@ -1303,7 +988,7 @@ impl StellarRpcClient {
         }
 
         let response = self
-            .retry_request(|| async { self.client.get(&url).send().await })
+            .horizon_get(&url)
             .await
             .context("Failed to fetch assets")?;
 
@@ -1317,280 +1002,4 @@ impl StellarRpcClient {
             .map(|e| e.records)
             .unwrap_or_default())
     }
-
-    // ============================================================================
-    // Liquidity Pool Mock Data
-    // ============================================================================
-
-    fn mock_liquidity_pools(limit: u32) -> Vec<HorizonLiquidityPool> {
-        let pool_configs = vec![
-            (
-                "USDC",
-                "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN",
-                "XLM",
-                "",
-                "500000.0",
-                "1200000.0",
-                "850000.0",
-            ),
-            (
-                "USDC",
-                "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN",
-                "EURC",
-                "GDHU6WRG4IEQXM5NZ4BMPKOXHW76MZM4Y36DAVIZA67CE7BKBHP4V2OA",
-                "320000.0",
-                "295000.0",
-                "610000.0",
-            ),
-            (
-                "XLM",
-                "",
-                "BTC",
-                "GDPJALI4AZKUU2W426U5WKMAT6CN3AJRPIIRYR2YM54TL2GDEMNQERFT",
-                "450000.0",
-                "12.5",
-                "750000.0",
-            ),
-            (
-                "USDC",
-                "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN",
-                "yUSDC",
-                "GDGTVWSM4MGS2T7Z7GVZE5SAEVLSWM5SGY5Q2EMUQWRMEV2RNYY3YFG6",
-                "180000.0",
-                "179500.0",
-                "360000.0",
-            ),
-            (
-                "XLM",
-                "",
-                "AQUA",
-                "GBNZILSTVQZ4R7IKQDGHYGY2QXL5QOFJYQMXPKWRRM5PAV7Y4M67AQUA",
-                "800000.0",
-                "5000000.0",
-                "420000.0",
-            ),
-        ];
-
-        pool_configs
-            .iter()
-            .take(limit as usize)
-            .enumerate()
-            .map(
-                |(i, (code_a, issuer_a, code_b, issuer_b, amt_a, amt_b, shares))| {
-                    let asset_a = if issuer_a.is_empty() {
-                        "native".to_string()
-                    } else {
-                        format!("{}:{}", code_a, issuer_a)
-                    };
-                    let asset_b = if issuer_b.is_empty() {
-                        "native".to_string()
-                    } else {
-                        format!("{}:{}", code_b, issuer_b)
-                    };
-
-                    HorizonLiquidityPool {
-                        id: format!("pool_{:064x}", i + 1),
-                        fee_bp: 30,
-                        pool_type: "constant_product".to_string(),
-                        total_trustlines: 100 + (i as u64 * 50),
-                        total_shares: shares.to_string(),
-                        reserves: vec![
-                            HorizonPoolReserve {
-                                asset: asset_a,
-                                amount: amt_a.to_string(),
-                            },
-                            HorizonPoolReserve {
-                                asset: asset_b,
-                                amount: amt_b.to_string(),
-                            },
-                        ],
-                        paging_token: Some(format!("pt_pool_{}", i)),
-                    }
-                },
-            )
-            .collect()
-    }
-
-    fn mock_assets(limit: u32) -> Vec<HorizonAsset> {
-        let mut assets = Vec::new();
-        let issues = vec![
-            (
-                "USDC",
-                "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN",
-            ),
-            (
-                "AQUA",
-                "GBNZILSTVQZ4R7IKQDGHYGY2QXL5QOFJYQMXPKWRRM5PAV7Y4M67AQUA",
-            ),
-            (
-                "yXLM",
-                "GARDNV3Q7YGT4AKSDF25A9NTVAMQUD8UAKGHXONL6R2FMBXVGFZDFZEM",
-            ),
-            (
-                "BTC",
-                "GDPJALI4AZKUU2W426U5WKMAT6CN3AJRPIIRYR2YM54TL2GDEMNQERFT",
-            ),
-        ];
-
-        for (i, (code, issuer)) in issues.iter().take(limit as usize).enumerate() {
-            let base_trustlines = 10000 - (i as i32 * 2000);
-            assets.push(HorizonAsset {
-                asset_type: "credit_alphanum4".to_string(),
-                asset_code: code.to_string(),
-                asset_issuer: issuer.to_string(),
-                num_claimable_balances: 0,
-                num_liquidity_pools: 0,
-                num_contracts: 0,
-                accounts: AssetAccounts {
-                    authorized: base_trustlines,
-                    authorized_to_maintain_liabilities: 0,
-                    unauthorized: base_trustlines / 20,
-                },
-                claimable_balances_amount: "0.0".to_string(),
-                liquidity_pools_amount: "0.0".to_string(),
-                contracts_amount: "0.0".to_string(),
-                balances: AssetBalances {
-                    authorized: format!("{}.0000000", base_trustlines * 1000),
-                    authorized_to_maintain_liabilities: "0.0".to_string(),
-                    unauthorized: "0.0".to_string(),
-                },
-                flags: AssetFlags {
-                    auth_required: false,
-                    auth_revocable: false,
-                    auth_immutable: false,
-                    auth_clawback_enabled: false,
-                },
-            })
-        }
-        assets
-    }
-}
-
-// ============================================================================
-// Tests
-// ============================================================================
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[tokio::test]
-    async fn test_mock_health_check() {
-        let client = StellarRpcClient::new_with_defaults(true);
-        let health = client.check_health().await.unwrap();
-
-        assert_eq!(health.status, "healthy");
-        assert!(health.latest_ledger > 0);
-    }
-
-    #[tokio::test]
-    async fn test_mock_fetch_ledger() {
-        let client = StellarRpcClient::new_with_defaults(true);
-        let ledger = client.fetch_latest_ledger().await.unwrap();
-
-        assert!(ledger.sequence > 0);
-        assert!(!ledger.hash.is_empty());
-    }
-
-    #[tokio::test]
-    async fn test_mock_fetch_payments() {
-        let client = StellarRpcClient::new_with_defaults(true);
-        let payments = client.fetch_payments(5, None).await.unwrap();
-
-        assert_eq!(payments.len(), 5);
-        assert!(!payments[0].id.is_empty());
-    }
-
-    #[tokio::test]
-    async fn test_mock_fetch_trades() {
-        let client = StellarRpcClient::new_with_defaults(true);
-        let trades = client.fetch_trades(3, None).await.unwrap();
-
-        assert_eq!(trades.len(), 3);
-        assert!(!trades[0].id.is_empty());
-    }
-
-    #[tokio::test]
-    async fn test_mock_fetch_order_book() {
-        let client = StellarRpcClient::new_with_defaults(true);
-
-        let selling = Asset {
-            asset_type: "native".to_string(),
-            asset_code: None,
-            asset_issuer: None,
-        };
-
-        let buying = Asset {
-            asset_type: "credit_alphanum4".to_string(),
-            asset_code: Some("USDC".to_string()),
-            asset_issuer: Some("GBXXXXXXX".to_string()),
-        };
-
-        let order_book = client
-            .fetch_order_book(&selling, &buying, 10)
-            .await
-            .unwrap();
-
-        assert!(!order_book.bids.is_empty());
-        assert!(!order_book.asks.is_empty());
-    }
-
-    #[tokio::test]
-    async fn test_mock_fetch_liquidity_pools() {
-        let client = StellarRpcClient::new_with_defaults(true);
-        let pools = client.fetch_liquidity_pools(3, None).await.unwrap();
-
-        assert_eq!(pools.len(), 3);
-        assert!(!pools[0].id.is_empty());
-        assert_eq!(pools[0].reserves.len(), 2);
-        assert_eq!(pools[0].fee_bp, 30);
-    }
-
-    #[tokio::test]
-    async fn test_mock_fetch_single_liquidity_pool() {
-        let client = StellarRpcClient::new_with_defaults(true);
-        let pool = client.fetch_liquidity_pool("test_pool_id").await.unwrap();
-
-        assert_eq!(pool.id, "test_pool_id");
-        assert_eq!(pool.reserves.len(), 2);
-    }
-
-    #[tokio::test]
-    async fn test_mock_fetch_pool_trades() {
-        let client = StellarRpcClient::new_with_defaults(true);
-        let trades = client.fetch_pool_trades("test_pool_id", 5).await.unwrap();
-
-        assert_eq!(trades.len(), 5);
-        assert!(!trades[0].id.is_empty());
-    }
-
-    #[tokio::test]
-    async fn test_mock_fetch_operations_for_ledger() {
-        let client = StellarRpcClient::new_with_defaults(true);
-        let operations = client.fetch_operations_for_ledger(123).await.unwrap();
-
-        assert_eq!(operations.len(), 3);
-        assert_eq!(operations[0].operation_type, "account_merge");
-    }
-
-    #[tokio::test]
-    async fn test_mock_fetch_operation_effects() {
-        let client = StellarRpcClient::new_with_defaults(true);
-        let effects = client.fetch_operation_effects("op_123_0").await.unwrap();
-
-        assert_eq!(effects.len(), 1);
-        assert_eq!(effects[0].effect_type, "account_credited");
-    }
-
-    #[tokio::test]
-    async fn test_mock_fetch_ledgers_stops_at_latest() {
-        let client = StellarRpcClient::new_with_defaults(true);
-        let result = client
-            .fetch_ledgers(Some(MOCK_LATEST_LEDGER.saturating_add(1)), 5, None)
-            .await
-            .unwrap();
-
-        assert!(result.ledgers.is_empty());
-        assert_eq!(result.latest_ledger, MOCK_LATEST_LEDGER);
-    }
 }
@@ -0,0 +1,448 @@
+//! Named, versioned scoring models for corridor health and anchor reliability.
+//!
+//! Every formula weight and status threshold lives in a [`ScoringModel`].
+//! Models are loaded from a JSON file (`SCORING_MODELS_PATH`, default
+//! `scoring_models.json`) that names the active version; scores record the
+//! version that produced them, and candidate versions can be compared against
+//! the active one before they are rolled out.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::OnceLock;
+use utoipa::ToSchema;
+
+use crate::models::AnchorStatus;
+
+pub const DEFAULT_SCORING_MODELS_PATH: &str = "scoring_models.json";
+
+/// Corridor health from success rate, volume and transaction count
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CorridorHealthModel {
+    pub success_weight: f64,
+    pub volume_weight: f64,
+    pub transaction_weight: f64,
+    /// Natural log of the USD volume that scores 100
+    pub volume_log_scale: f64,
+    /// Natural log of the transaction count that scores 100
+    pub transaction_log_scale: f64,
+}
+
+/// Anchor reliability from success rate and settlement time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AnchorMetricsModel {
+    pub success_weight: f64,
+    pub settlement_weight: f64,
+    /// Settlement at or under this scores 100
+    pub fast_settlement_ms: i32,
+    /// Settlement at or over this scores 0
+    pub slow_settlement_ms: i32,
+    /// Settlement score when no settlement times are known
+    pub unknown_settlement_score: f64,
+}
+
+/// Success and failure rate thresholds for anchor status
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AnchorStatusModel {
+    /// Green needs a success rate strictly above this
+    pub green_min_success_rate: f64,
+    pub green_max_failure_rate: f64,
+    pub yellow_min_success_rate: f64,
+    pub yellow_max_failure_rate: f64,
+}
+
+/// SEP endpoint uptime thresholds for anchor status
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AnchorUptimeModel {
+    /// Green needs an uptime percentage at or above this
+    pub green_min_uptime: f64,
+    pub yellow_min_uptime: f64,
+}
+
+impl Default for AnchorUptimeModel {
+    fn default() -> Self {
+        Self {
+            green_min_uptime: 99.0,
+            yellow_min_uptime: 95.0,
+        }
+    }
+}
+
+/// Composite anchor reliability from per-asset performance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AnchorReliabilityModel {
+    pub performance_weight: f64,
+    pub volume_weight: f64,
+    pub diversity_weight: f64,
+    /// Number of assets that earns a full diversity score
+    pub diversity_target_assets: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ScoringModel {
+    #[schema(example = "v1")]
+    pub version: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub corridor_health: CorridorHealthModel,
+    pub anchor_metrics: AnchorMetricsModel,
+    pub anchor_status: AnchorStatusModel,
+    /// Models written before endpoints were probed use the default thresholds
+    #[serde(default)]
+    pub anchor_uptime: AnchorUptimeModel,
+    pub anchor_reliability: AnchorReliabilityModel,
+}
+
+impl Default for ScoringModel {
+    /// The original hardcoded formulas
+    fn default() -> Self {
+        Self {
+            version: "v1".to_string(),
+            description: Some("Original weights and thresholds".to_string()),
+            corridor_health: CorridorHealthModel {
+                success_weight: 0.6,
+                volume_weight: 0.2,
+                transaction_weight: 0.2,
+                volume_log_scale: 15.0,
+                transaction_log_scale: 10.0,
+            },
+            anchor_metrics: AnchorMetricsModel {
+                success_weight: 0.7,
+                settlement_weight: 0.3,
+                fast_settlement_ms: 1000,
+                slow_settlement_ms: 10000,
+                unknown_settlement_score: 50.0,
+            },
+            anchor_status: AnchorStatusModel {
+                green_min_success_rate: 98.0,
+                green_max_failure_rate: 1.0,
+                yellow_min_success_rate: 95.0,
+                yellow_max_failure_rate: 5.0,
+            },
+            anchor_uptime: AnchorUptimeModel::default(),
+            anchor_reliability: AnchorReliabilityModel {
+                performance_weight: 0.6,
+                volume_weight: 0.3,
+                diversity_weight: 0.1,
+                diversity_target_assets: 10,
+            },
+        }
+    }
+}
+
+impl ScoringModel {
+    /// Corridor health score (0-100)
+    pub fn corridor_health_score(
+        &self,
+        success_rate: f64,
+        total_transactions: i64,
+        volume_usd: f64,
+    ) -> f64 {
+        let model = &self.corridor_health;
+
+        // Normalize volume and transactions (using logarithmic scale)
+        let volume_score = if volume_usd > 0.0 {
+            ((volume_usd.ln() / model.volume_log_scale) * 100.0).min(100.0)
+        } else {
+            0.0
+        };
+
+        let transaction_score = if total_transactions > 0 {
+            ((total_transactions as f64).ln() / model.transaction_log_scale * 100.0).min(100.0)
+        } else {
+            0.0
+        };
+
+        success_rate * model.success_weight
+            + volume_score * model.volume_weight
+            + transaction_score * model.transaction_weight
+    }
+
+    /// Settlement time score (0-100); lower settlement time scores higher
+    pub fn settlement_time_score(&self, avg_settlement_time_ms: Option<i32>) -> f64 {
+        let model = &self.anchor_metrics;
+
+        match avg_settlement_time_ms {
+            Some(time_ms) if time_ms <= model.fast_settlement_ms => 100.0,
+            Some(time_ms) if time_ms >= model.slow_settlement_ms => 0.0,
+            Some(time_ms) => {
+                let slow = model.slow_settlement_ms as f64;
+                (slow - time_ms as f64) / (slow - model.fast_settlement_ms as f64) * 100.0
+            }
+            None => model.unknown_settlement_score,
+        }
+    }
+
+    /// Anchor reliability score from success rate and settlement time
+    pub fn anchor_reliability_score(
+        &self,
+        success_rate: f64,
+        avg_settlement_time_ms: Option<i32>,
+    ) -> f64 {
+        success_rate * self.anchor_metrics.success_weight
+            + self.settlement_time_score(avg_settlement_time_ms)
+                * self.anchor_metrics.settlement_weight
+    }
+
+    pub fn anchor_status(&self, success_rate: f64, failure_rate: f64) -> AnchorStatus {
+        let model = &self.anchor_status;
+
+        if success_rate > model.green_min_success_rate
+            && failure_rate <= model.green_max_failure_rate
+        {
+            AnchorStatus::Green
+        } else if success_rate >= model.yellow_min_success_rate
+            && failure_rate <= model.yellow_max_failure_rate
+        {
+            AnchorStatus::Yellow
+        } else {
+            AnchorStatus::Red
+        }
+    }
+
+    /// Status from the share of SEP endpoint probes that got a response
+    pub fn uptime_status(&self, uptime_percentage: f64) -> AnchorStatus {
+        let model = &self.anchor_uptime;
+
+        if uptime_percentage >= model.green_min_uptime {
+            AnchorStatus::Green
+        } else if uptime_percentage >= model.yellow_min_uptime {
+            AnchorStatus::Yellow
+        } else {
+            AnchorStatus::Red
+        }
+    }
+
+    /// Asset diversity score (0-100), capped at the target number of assets
+    pub fn asset_diversity_score(&self, total_assets: usize) -> f64 {
+        let target = self.anchor_reliability.diversity_target_assets.max(1) as f64;
+        (total_assets as f64 / target).min(1.0) * 100.0
+    }
+
+    /// Composite anchor reliability score from its component scores
+    pub fn composite_reliability_score(
+        &self,
+        asset_performance_score: f64,
+        volume_score: f64,
+        asset_diversity_score: f64,
+    ) -> f64 {
+        let model = &self.anchor_reliability;
+        model.performance_weight * asset_performance_score
+            + model.volume_weight * volume_score
+            + model.diversity_weight * asset_diversity_score
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.version.trim().is_empty() {
+            bail!("Scoring model version must not be empty");
+        }
+
+        let weights = [
+            self.corridor_health.success_weight,
+            self.corridor_health.volume_weight,
+            self.corridor_health.transaction_weight,
+            self.anchor_metrics.success_weight,
+            self.anchor_metrics.settlement_weight,
+            self.anchor_reliability.performance_weight,
+            self.anchor_reliability.volume_weight,
+            self.anchor_reliability.diversity_weight,
+        ];
+        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            bail!("Scoring model {} has a negative weight", self.version);
+        }
+        if self.corridor_health.volume_log_scale <= 0.0
+            || self.corridor_health.transaction_log_scale <= 0.0
+        {
+            bail!(
+                "Scoring model {} has a non-positive log scale",
+                self.version
+            );
+        }
+        if self.anchor_metrics.fast_settlement_ms >= self.anchor_metrics.slow_settlement_ms {
+            bail!(
+                "Scoring model {} needs fast_settlement_ms below slow_settlement_ms",
+                self.version
+            );
+        }
+
+        if self.anchor_uptime.yellow_min_uptime > self.anchor_uptime.green_min_uptime {
+            bail!(
+                "Scoring model {} needs yellow_min_uptime at or below green_min_uptime",
+                self.version
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Every known scoring model and the version currently in use
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoringModels {
+    active: String,
+    models: Vec<ScoringModel>,
+}
+
+impl Default for ScoringModels {
+    fn default() -> Self {
+        let model = ScoringModel::default();
+        Self {
+            active: model.version.clone(),
+            models: vec![model],
+        }
+    }
+}
+
+impl ScoringModels {
+    pub fn from_json(json: &str) -> Result<Self> {
+        let models: Self = serde_json::from_str(json).context("Invalid scoring models file")?;
+
+        let mut versions = HashSet::new();
+        for model in &models.models {
+            model.validate()?;
+            if !versions.insert(model.version.as_str()) {
+                bail!("Scoring model {} is defined twice", model.version);
+            }
+        }
+        if !versions.contains(models.active.as_str()) {
+            bail!("Active scoring model {} is not defined", models.active);
+        }
+
+        Ok(models)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read scoring models from {}", path.display()))?;
+        Self::from_json(&json)
+    }
+
+    /// Load from `SCORING_MODELS_PATH`, or from `scoring_models.json` when it
+    /// exists, falling back to the built-in model
+    pub fn from_env() -> Result<Self> {
+        match std::env::var("SCORING_MODELS_PATH") {
+            Ok(path) => Self::load(path),
+            Err(_) if Path::new(DEFAULT_SCORING_MODELS_PATH).exists() => {
+                Self::load(DEFAULT_SCORING_MODELS_PATH)
+            }
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    pub fn active(&self) -> &ScoringModel {
+        self.get(&self.active)
+            .expect("active scoring model is validated on load")
+    }
+
+    pub fn get(&self, version: &str) -> Option<&ScoringModel> {
+        self.models.iter().find(|m| m.version == version)
+    }
+
+    pub fn models(&self) -> &[ScoringModel] {
+        &self.models
+    }
+}
+
+static SCORING_MODELS: OnceLock<ScoringModels> = OnceLock::new();
+
+/// Make `models` the process-wide scoring models. Only the first call wins, so
+/// this belongs at startup before any score is computed.
+pub fn install(models: ScoringModels) -> Result<()> {
+    let active = models.active.clone();
+    SCORING_MODELS
+        .set(models)
+        .map_err(|_| anyhow!("Scoring models are already installed"))?;
+    tracing::info!("Scoring model {} is active", active);
+    Ok(())
+}
+
+/// The process-wide scoring models, the built-in model unless others were
+/// installed
+pub fn scoring_models() -> &'static ScoringModels {
+    SCORING_MODELS.get_or_init(ScoringModels::default)
+}
+
+pub fn active_model() -> &'static ScoringModel {
+    scoring_models().active()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate_json() -> String {
+        let mut candidate = ScoringModel {
+            version: "v2".to_string(),
+            ..ScoringModel::default()
+        };
+        candidate.corridor_health.success_weight = 0.8;
+        candidate.corridor_health.volume_weight = 0.1;
+        candidate.corridor_health.transaction_weight = 0.1;
+        serde_json::json!({
+            "active": "v1",
+            "models": [ScoringModel::default(), candidate],
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_default_model_matches_original_formulas() {
+        let model = ScoringModel::default();
+
+        let health = model.corridor_health_score(95.0, 1000, 1_000_000.0);
+        let expected = 95.0 * 0.6
+            + (1_000_000f64.ln() / 15.0 * 100.0) * 0.2
+            + (1000f64.ln() / 10.0 * 100.0) * 0.2;
+        assert!((health - expected).abs() < 1e-9);
+
+        assert_eq!(model.settlement_time_score(Some(500)), 100.0);
+        assert_eq!(model.settlement_time_score(Some(12000)), 0.0);
+        assert_eq!(model.settlement_time_score(None), 50.0);
+        assert_eq!(model.anchor_reliability_score(100.0, Some(1000)), 100.0);
+        assert_eq!(model.anchor_status(98.1, 0.9), AnchorStatus::Green);
+        assert_eq!(model.anchor_status(98.0, 2.0), AnchorStatus::Yellow);
+        assert_eq!(model.uptime_status(99.5), AnchorStatus::Green);
+        assert_eq!(model.uptime_status(97.0), AnchorStatus::Yellow);
+        assert_eq!(model.uptime_status(50.0), AnchorStatus::Red);
+        assert_eq!(model.asset_diversity_score(15), 100.0);
+    }
+
+    #[test]
+    fn test_load_models_from_json() {
+        let models = ScoringModels::from_json(&candidate_json()).unwrap();
+        assert_eq!(models.active().version, "v1");
+        assert_eq!(models.models().len(), 2);
+
+        let candidate = models.get("v2").unwrap();
+        assert!(
+            candidate.corridor_health_score(99.0, 10, 100.0)
+                > models.active().corridor_health_score(99.0, 10, 100.0)
+        );
+        assert!(models.get("v3").is_none());
+    }
+
+    #[test]
+    fn test_shipped_models_file_is_the_default() {
+        let models = ScoringModels::load(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join(DEFAULT_SCORING_MODELS_PATH),
+        )
+        .unwrap();
+        assert_eq!(models.active(), &ScoringModel::default());
+    }
+
+    #[test]
+    fn test_invalid_models_are_rejected() {
+        let unknown_active = candidate_json().replace("\"active\":\"v1\"", "\"active\":\"v9\"");
+        assert!(ScoringModels::from_json(&unknown_active).is_err());
+
+        let duplicate = candidate_json().replace("\"version\":\"v2\"", "\"version\":\"v1\"");
+        assert!(ScoringModels::from_json(&duplicate).is_err());
+
+        let mut negative = ScoringModel::default();
+        negative.anchor_metrics.settlement_weight = -0.3;
+        let json = serde_json::json!({ "active": "v1", "models": [negative] }).to_string();
+        assert!(ScoringModels::from_json(&json).is_err());
+    }
+}
@@ -2,10 +2,7 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::Serialize;
 use sqlx::{Pool, Sqlite};
-use std::sync::Arc;
-use tracing::{info, warn};
-
-use crate::rpc::{HorizonOperation, StellarRpcClient};
+use tracing::info;
 
 #[derive(Debug, Clone, Serialize, sqlx::FromRow)]
 pub struct AccountMergeEvent {
@@ -35,112 +32,30 @@ pub struct DestinationAccountPattern {
 
 pub struct AccountMergeDetector {
     pool: Pool<Sqlite>,
-    rpc_client: Arc<StellarRpcClient>,
 }
 
 impl AccountMergeDetector {
-    pub fn new(pool: Pool<Sqlite>, rpc_client: Arc<StellarRpcClient>) -> Self {
-        Self { pool, rpc_client }
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
     }
 
-    /// Fetches operations for a ledger, extracts account merges, and persists merge events.
-    pub async fn process_ledger_operations(&self, ledger_sequence: u64) -> Result<u64> {
-        let operations = self
-            .rpc_client
-            .fetch_operations_for_ledger(ledger_sequence)
-            .await?;
-
+    /// Persists merge events already decoded from ledger close meta.
+    pub async fn record_merges(&self, events: &[AccountMergeEvent]) -> Result<u64> {
         let mut inserted = 0_u64;
 
-        for operation in operations
-            .iter()
-            .filter(|op| op.operation_type == "account_merge")
-        {
-            if self
-                .persist_merge_from_operation(ledger_sequence, operation)
-                .await?
-            {
+        for event in events {
+            if self.persist_merge_event(event).await? {
                 inserted += 1;
             }
         }
 
         if inserted > 0 {
-            info!(
-                "Detected and stored {} account merge operations for ledger {}",
-                inserted, ledger_sequence
-            );
+            info!("Stored {} decoded account merge operations", inserted);
         }
 
         Ok(inserted)
     }
 
-    async fn persist_merge_from_operation(
-        &self,
-        ledger_sequence: u64,
-        operation: &HorizonOperation,
-    ) -> Result<bool> {
-        let destination_account = match operation.into.clone() {
-            Some(account) => account,
-            None => {
-                warn!(
-                    "Skipping account_merge operation {} without destination account",
-                    operation.id
-                );
-                return Ok(false);
-            }
-        };
-
-        let source_account = operation
-            .account
-            .clone()
-            .unwrap_or_else(|| operation.source_account.clone());
-
-        let merged_balance = self
-            .resolve_merged_balance(&operation.id, &destination_account)
-            .await;
-
-        let created_at = DateTime::parse_from_rfc3339(&operation.created_at)
-            .map(|dt| dt.with_timezone(&Utc))
-            .unwrap_or_else(|_| Utc::now());
-
-        let event = AccountMergeEvent {
-            operation_id: operation.id.clone(),
-            transaction_hash: operation.transaction_hash.clone(),
-            ledger_sequence: ledger_sequence as i64,
-            source_account,
-            destination_account,
-            merged_balance,
-            created_at,
-        };
-
-        self.persist_merge_event(&event).await
-    }
-
-    async fn resolve_merged_balance(&self, operation_id: &str, destination: &str) -> f64 {
-        match self.rpc_client.fetch_operation_effects(operation_id).await {
-            Ok(effects) => {
-                let credited_amount: f64 = effects
-                    .into_iter()
-                    .filter(|effect| effect.effect_type == "account_credited")
-                    .filter(|effect| effect.account.as_deref() == Some(destination))
-                    .filter_map(|effect| effect.amount.and_then(|value| value.parse::<f64>().ok()))
-                    .sum();
-
-                if credited_amount > 0.0 {
-                    return credited_amount;
-                }
-            }
-            Err(error) => {
-                warn!(
-                    "Failed to fetch effects for operation {} while resolving merge amount: {}",
-                    operation_id, error
-                );
-            }
-        }
-
-        0.0
-    }
-
     async fn persist_merge_event(&self, event: &AccountMergeEvent) -> Result<bool> {
         let result = sqlx::query(
             r#"
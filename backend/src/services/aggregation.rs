@@ -1,13 +1,15 @@
 use anyhow::{Context, Result};
-use chrono::{DateTime, Duration, Timelike, Utc};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveTime, Timelike, Utc};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use tokio::time::{interval, Duration as TokioDuration};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
 use crate::database::Database;
-use crate::models::corridor::CorridorMetrics;
+use crate::models::corridor::{CorridorMetrics, PaymentRecord};
 use crate::services::analytics::compute_metrics_from_payments;
+use crate::services::distribution::{sketches_from_payments, CorridorSketches};
 
 const MAX_RETRIES: i32 = 3;
 const RETRY_DELAY_SECS: u64 = 60;
@@ -17,14 +19,18 @@ pub struct AggregationConfig {
     pub interval_hours: u64,
     pub lookback_hours: i64,
     pub batch_size: i64,
+    /// How long after an hour ends before it is aggregated, so payments
+    /// ingested late still land in their hour
+    pub grace_minutes: i64,
 }
 
 impl Default for AggregationConfig {
     fn default() -> Self {
         Self {
             interval_hours: 1, // Run every hour
-            lookback_hours: 2, // Process last 2 hours of data
+            lookback_hours: 2, // First run aggregates the last 2 whole hours
             batch_size: 10000, // Process 10k payments at a time
+            grace_minutes: 15, // Hours close a quarter hour after they end
         }
     }
 }
@@ -59,7 +65,7 @@ impl AggregationService {
             }
 
             // Run new aggregation
-            if let Err(e) = self.run_hourly_aggregation().await {
+            if let Err(e) = self.run_hourly_aggregation(Utc::now()).await {
                 error!("Hourly aggregation failed: {}", e);
                 // Continue running despite errors
             }
@@ -74,10 +80,10 @@ impl AggregationService {
         Ok(())
     }
 
-    /// Run the hourly aggregation job
-    pub async fn run_hourly_aggregation(&self) -> Result<()> {
+    /// Run the hourly aggregation job for the hours ended by `now`, returning
+    /// how many hourly corridor metrics it stored
+    pub async fn run_hourly_aggregation(&self, now: DateTime<Utc>) -> Result<usize> {
         let job_id = Uuid::new_v4().to_string();
-        let now = Utc::now();
 
         // Create job record
         self.create_job_record(&job_id, "hourly").await?;
@@ -92,7 +98,7 @@ impl AggregationService {
                     metrics_count
                 );
                 self.update_job_status(&job_id, "completed", None).await?;
-                Ok(())
+                Ok(metrics_count)
             }
             Err(e) => {
                 error!("Aggregation failed: {}", e);
@@ -102,118 +108,260 @@ impl AggregationService {
         }
     }
 
-    /// Execute the actual aggregation logic
+    /// Aggregate every whole hour after the last one a completed job
+    /// processed, up to the hour still in progress `grace_minutes` ago.
+    /// Once ledgers are being ingested, an hour is only closed after a ledger
+    /// that closed past its end has been ingested, so downtime or ingestion
+    /// lag leaves hours open instead of closing them with partial data.
+    /// Payments are filed under the hour they were created in and each
+    /// hour's metrics replace the stored ones, so an hour aggregated twice
+    /// comes out the same.
     async fn execute_aggregation(&self, job_id: &str, now: DateTime<Utc>) -> Result<usize> {
-        // Calculate time window for aggregation
-        let end_time = now;
-        let start_time = end_time - Duration::hours(self.config.lookback_hours);
+        let mut end_time =
+            self.truncate_to_hour(now - Duration::minutes(self.config.grace_minutes));
+        if let Some(ingested_through) = self
+            .db
+            .aggregation_db()
+            .latest_ledger_close_time()
+            .await?
+        {
+            end_time = end_time.min(self.truncate_to_hour(ingested_through));
+        }
+        let mut hour = match self
+            .db
+            .aggregation_db()
+            .last_processed_hour("hourly")
+            .await?
+        {
+            Some(last_hour) => self.truncate_to_hour(last_hour) + Duration::hours(1),
+            None => end_time - Duration::hours(self.config.lookback_hours),
+        };
+
+        if hour >= end_time {
+            info!("No whole hours to aggregate");
+            return Ok(0);
+        }
 
         info!(
             "Aggregating corridor metrics from {} to {}",
-            start_time.to_rfc3339(),
+            hour.to_rfc3339(),
             end_time.to_rfc3339()
         );
 
-        // Fetch payments from the time window
-        let payments = self
-            .db
-            .fetch_payments_by_timerange(start_time, end_time, self.config.batch_size)
-            .await
-            .context("Failed to fetch payments for aggregation")?;
+        let mut stored_count = 0;
+        while hour < end_time {
+            let payments = self.fetch_hour_payments(hour).await?;
+            if !payments.is_empty() {
+                info!("Processing {} payments from {}", payments.len(), hour);
+                stored_count += self
+                    .store_hourly_metrics(self.hourly_metrics(hour, &payments))
+                    .await?;
+            }
 
-        if payments.is_empty() {
-            info!("No payments found in time window");
-            return Ok(0);
+            self.update_last_processed_hour(job_id, hour).await?;
+            hour += Duration::hours(1);
         }
 
-        info!("Processing {} payments", payments.len());
+        Ok(stored_count)
+    }
 
-        // Compute metrics for each corridor
-        let corridor_metrics = compute_metrics_from_payments(&payments);
+    /// Every payment created in the hour starting at `hour`, fetched
+    /// `batch_size` at a time
+    async fn fetch_hour_payments(&self, hour: DateTime<Utc>) -> Result<Vec<PaymentRecord>> {
+        let aggregation_db = self.db.aggregation_db();
+        let mut payments = Vec::new();
+        let mut offset = 0;
 
-        if corridor_metrics.is_empty() {
-            info!("No corridor metrics computed");
-            return Ok(0);
+        loop {
+            let page = aggregation_db
+                .fetch_payments_page(
+                    hour,
+                    hour + Duration::hours(1),
+                    self.config.batch_size,
+                    offset,
+                )
+                .await
+                .context("Failed to fetch payments for aggregation")?;
+            if page.is_empty() {
+                return Ok(payments);
+            }
+            payments.extend(page);
+            offset += self.config.batch_size;
         }
+    }
+
+    /// Start the rollup scheduler, deriving daily, weekly and monthly corridor
+    /// metrics from the hourly buckets
+    pub async fn start_rollup_scheduler(self: Arc<Self>) {
+        info!(
+            "Starting corridor rollup scheduler (interval: {} hours)",
+            self.config.interval_hours
+        );
+
+        let mut ticker = interval(TokioDuration::from_secs(self.config.interval_hours * 3600));
+
+        loop {
+            ticker.tick().await;
 
-        // Group metrics by hour bucket
-        let hourly_metrics = self.group_by_hour_bucket(corridor_metrics, start_time);
+            for period in RollupPeriod::ALL {
+                if let Err(e) = self.run_rollup(period, Utc::now()).await {
+                    error!("{} corridor rollup failed: {}", period.as_str(), e);
+                }
+            }
+        }
+    }
 
-        // Store aggregated metrics
-        let stored_count = self.store_hourly_metrics(hourly_metrics).await?;
+    /// Run a rollup job for one period as of `now`, returning how many
+    /// rollups it stored
+    pub async fn run_rollup(&self, period: RollupPeriod, now: DateTime<Utc>) -> Result<usize> {
+        let job_id = Uuid::new_v4().to_string();
 
-        // Update last processed hour
-        let last_hour = self.truncate_to_hour(end_time);
-        self.update_last_processed_hour(job_id, last_hour).await?;
+        self.create_job_record(&job_id, period.as_str()).await?;
+        self.update_job_status(&job_id, "running", None).await?;
+
+        match self.execute_rollup(&job_id, period, now).await {
+            Ok(count) => {
+                info!(
+                    "{} rollup completed successfully. Stored {} corridor rollups",
+                    period.as_str(),
+                    count
+                );
+                self.update_job_status(&job_id, "completed", None).await?;
+                Ok(count)
+            }
+            Err(e) => {
+                error!("{} rollup failed: {}", period.as_str(), e);
+                self.handle_job_failure(&job_id, &e.to_string()).await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Rebuild every period from the one after the last closed period a
+    /// previous job finished, through the period still in progress. Each
+    /// rollup is recomputed from the hourly table and replaces the stored
+    /// one, so re-running never double counts. A period closes once the
+    /// hourly job has aggregated its last hour, after which its hourly rows
+    /// no longer change.
+    async fn execute_rollup(
+        &self,
+        job_id: &str,
+        period: RollupPeriod,
+        now: DateTime<Utc>,
+    ) -> Result<usize> {
+        let aggregation_db = self.db.aggregation_db();
+        let current = period.start_of(now);
+        let aggregated_until = aggregation_db
+            .last_processed_hour("hourly")
+            .await?
+            .map(|last_hour| last_hour + Duration::hours(1));
+        let mut start = match aggregation_db.last_processed_hour(period.as_str()).await? {
+            Some(last_closed) => period.next_start(last_closed).min(current),
+            None => match aggregation_db.earliest_hour_bucket().await? {
+                Some(first_hour) => period.start_of(first_hour),
+                None => {
+                    info!("No hourly corridor metrics to roll up");
+                    return Ok(0);
+                }
+            },
+        };
+
+        let mut stored_count = 0;
+        while start <= current {
+            let end = period.next_start(start);
+            stored_count += self.store_rollups(period, start).await?;
+
+            if aggregated_until.is_some_and(|until| end <= until) {
+                self.update_last_processed_hour(job_id, start).await?;
+            }
+            start = end;
+        }
 
         Ok(stored_count)
     }
 
-    /// Group metrics by hour bucket
-    fn group_by_hour_bucket(
+    /// Recompute and store every corridor's rollup for the period starting at
+    /// `start` from its hourly buckets
+    async fn store_rollups(&self, period: RollupPeriod, start: DateTime<Utc>) -> Result<usize> {
+        let end = period.next_start(start);
+        let hourly: Vec<HourlyCorridorMetrics> = self
+            .db
+            .fetch_hourly_metrics_by_timerange(start, end)
+            .await
+            .context("Failed to fetch hourly metrics for rollup")?
+            .into_iter()
+            .filter(|m| m.hour_bucket < end)
+            .collect();
+
+        let aggregation_db = self.db.aggregation_db();
+        let mut stored_count = 0;
+        for rollup in rollup_hourly_metrics(period, start, hourly) {
+            aggregation_db
+                .upsert_corridor_rollup(&rollup)
+                .await
+                .context("Failed to store corridor rollup")?;
+            stored_count += 1;
+        }
+
+        Ok(stored_count)
+    }
+
+    /// Re-aggregate every hour from the one containing `start` through the
+    /// one containing `end`, then rebuild the rollups covering them. Used
+    /// after a backfill fills in hours the hourly job already moved past;
+    /// the job's own progress is left alone. Returns how many hourly
+    /// corridor metrics it stored.
+    pub async fn reaggregate_hours(
         &self,
-        metrics: Vec<CorridorMetrics>,
-        _start_time: DateTime<Utc>,
-    ) -> Vec<HourlyCorridorMetrics> {
-        use std::collections::HashMap;
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<usize> {
+        let first_hour = self.truncate_to_hour(start);
+        let last_hour = self.truncate_to_hour(end);
 
-        let mut hourly_map: HashMap<(String, String), HourlyCorridorMetrics> = HashMap::new();
+        info!(
+            "Re-aggregating corridor metrics from {} to {}",
+            first_hour.to_rfc3339(),
+            last_hour.to_rfc3339()
+        );
 
-        for metric in metrics {
-            let hour_bucket = self.truncate_to_hour(metric.date);
-            let key = (metric.corridor_key.clone(), hour_bucket.to_rfc3339());
-
-            hourly_map
-                .entry(key)
-                .and_modify(|existing| {
-                    existing.total_transactions += metric.total_transactions;
-                    existing.successful_transactions += metric.successful_transactions;
-                    existing.failed_transactions += metric.failed_transactions;
-                    existing.volume_usd += metric.volume_usd;
-
-                    // Update averages (weighted by transaction count)
-                    if let Some(latency) = metric.avg_settlement_latency_ms {
-                        let total_latency = existing.avg_settlement_latency_ms.unwrap_or(0) as i64
-                            * existing.total_transactions
-                            + latency as i64 * metric.total_transactions;
-                        existing.avg_settlement_latency_ms = Some(
-                            (total_latency
-                                / (existing.total_transactions + metric.total_transactions))
-                                as i32,
-                        );
-                    }
+        let mut stored_count = 0;
+        let mut hour = first_hour;
+        while hour <= last_hour {
+            let payments = self.fetch_hour_payments(hour).await?;
+            if !payments.is_empty() {
+                stored_count += self
+                    .store_hourly_metrics(self.hourly_metrics(hour, &payments))
+                    .await?;
+            }
+            hour += Duration::hours(1);
+        }
 
-                    existing.liquidity_depth_usd =
-                        (existing.liquidity_depth_usd + metric.liquidity_depth_usd) / 2.0;
-                })
-                .or_insert_with(|| HourlyCorridorMetrics {
-                    id: Uuid::new_v4().to_string(),
-                    corridor_key: metric.corridor_key.clone(),
-                    asset_a_code: metric.asset_a_code.clone(),
-                    asset_a_issuer: metric.asset_a_issuer.clone(),
-                    asset_b_code: metric.asset_b_code.clone(),
-                    asset_b_issuer: metric.asset_b_issuer.clone(),
-                    hour_bucket,
-                    total_transactions: metric.total_transactions,
-                    successful_transactions: metric.successful_transactions,
-                    failed_transactions: metric.failed_transactions,
-                    success_rate: metric.success_rate,
-                    volume_usd: metric.volume_usd,
-                    avg_slippage_bps: 0.0, // TODO: Calculate from order book data
-                    avg_settlement_latency_ms: metric.avg_settlement_latency_ms,
-                    liquidity_depth_usd: metric.liquidity_depth_usd,
-                });
+        for period in RollupPeriod::ALL {
+            let mut period_start = period.start_of(first_hour);
+            while period_start <= last_hour {
+                self.store_rollups(period, period_start).await?;
+                period_start = period.next_start(period_start);
+            }
         }
 
-        // Recalculate success rates
-        hourly_map
-            .into_values()
-            .map(|mut m| {
-                if m.total_transactions > 0 {
-                    m.success_rate =
-                        (m.successful_transactions as f64 / m.total_transactions as f64) * 100.0;
-                }
-                m
+        Ok(stored_count)
+    }
+
+    /// Metrics per corridor, with their latency and payment size sketches,
+    /// for the payments created in the hour starting at `hour_bucket`
+    fn hourly_metrics(
+        &self,
+        hour_bucket: DateTime<Utc>,
+        payments: &[PaymentRecord],
+    ) -> Vec<HourlyCorridorMetrics> {
+        let mut sketches = sketches_from_payments(payments);
+
+        compute_metrics_from_payments(payments)
+            .iter()
+            .map(|metric| {
+                let corridor_sketches = sketches.remove(&metric.corridor_key).unwrap_or_default();
+                HourlyCorridorMetrics::from_corridor_metrics(metric, hour_bucket, corridor_sketches)
             })
             .collect()
     }
@@ -326,8 +474,6 @@ impl AggregationService {
 
     /// Compute volume trends from hourly metrics
     fn compute_volume_trends(&self, metrics: Vec<HourlyCorridorMetrics>) -> Vec<VolumeTrend> {
-        use std::collections::HashMap;
-
         let mut corridor_volumes: HashMap<String, Vec<(DateTime<Utc>, f64)>> = HashMap::new();
 
         for metric in metrics {
@@ -395,6 +541,25 @@ impl Clone for AggregationService {
     }
 }
 
+/// Transaction-weighted mean of two latency figures, either of which may be missing
+fn blend_latency(a: Option<i32>, b: Option<i32>, (a_weight, b_weight): (i64, i64)) -> Option<i32> {
+    match (a, b) {
+        (Some(a), Some(b)) if a_weight + b_weight > 0 => {
+            Some(((a as i64 * a_weight + b as i64 * b_weight) / (a_weight + b_weight)) as i32)
+        }
+        (a, b) => a.or(b),
+    }
+}
+
+/// Mean of two means weighted by how many samples each covers
+fn weighted_mean(a: f64, b: f64, (a_weight, b_weight): (i64, i64)) -> f64 {
+    if a_weight + b_weight > 0 {
+        (a * a_weight as f64 + b * b_weight as f64) / (a_weight + b_weight) as f64
+    } else {
+        (a + b) / 2.0
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct HourlyCorridorMetrics {
     pub id: String,
@@ -410,8 +575,223 @@ pub struct HourlyCorridorMetrics {
     pub success_rate: f64,
     pub volume_usd: f64,
     pub avg_slippage_bps: f64,
+    /// Asset B per asset A, for cross-asset corridors
+    pub avg_fx_rate: Option<f64>,
     pub avg_settlement_latency_ms: Option<i32>,
+    /// Settlement latency percentiles in milliseconds
+    pub p50_settlement_latency_ms: Option<i32>,
+    pub p90_settlement_latency_ms: Option<i32>,
+    pub p99_settlement_latency_ms: Option<i32>,
     pub liquidity_depth_usd: f64,
+    /// Failed payments per result code
+    pub failure_breakdown: BTreeMap<String, i64>,
+    /// Settlement latency and payment size distributions, mergeable across
+    /// buckets
+    pub sketches: CorridorSketches,
+}
+
+impl HourlyCorridorMetrics {
+    /// One corridor's metrics for a batch of payments, filed under `hour_bucket`
+    pub fn from_corridor_metrics(
+        metric: &CorridorMetrics,
+        hour_bucket: DateTime<Utc>,
+        sketches: CorridorSketches,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            corridor_key: metric.corridor_key.clone(),
+            asset_a_code: metric.asset_a_code.clone(),
+            asset_a_issuer: metric.asset_a_issuer.clone(),
+            asset_b_code: metric.asset_b_code.clone(),
+            asset_b_issuer: metric.asset_b_issuer.clone(),
+            hour_bucket,
+            total_transactions: metric.total_transactions,
+            successful_transactions: metric.successful_transactions,
+            failed_transactions: metric.failed_transactions,
+            success_rate: metric.success_rate,
+            volume_usd: metric.volume_usd,
+            avg_slippage_bps: metric.avg_slippage_bps.unwrap_or(0.0),
+            avg_fx_rate: metric.avg_fx_rate,
+            avg_settlement_latency_ms: metric.avg_settlement_latency_ms,
+            p50_settlement_latency_ms: metric.median_settlement_latency_ms,
+            p90_settlement_latency_ms: metric.p90_settlement_latency_ms,
+            p99_settlement_latency_ms: metric.p99_settlement_latency_ms,
+            liquidity_depth_usd: metric.liquidity_depth_usd,
+            failure_breakdown: metric.failure_breakdown.clone(),
+            sketches,
+        }
+    }
+
+    /// Folds another batch for the same corridor and hour into this one.
+    /// Means are weighted by the transactions behind them and percentiles
+    /// are re-read from the merged latency sketch.
+    pub fn merge(&mut self, other: &HourlyCorridorMetrics) {
+        let weights = (self.total_transactions, other.total_transactions);
+        // Only settled payments carry a latency
+        let latency_weights = (self.successful_transactions, other.successful_transactions);
+
+        self.avg_settlement_latency_ms = blend_latency(
+            self.avg_settlement_latency_ms,
+            other.avg_settlement_latency_ms,
+            latency_weights,
+        );
+        self.avg_slippage_bps =
+            weighted_mean(self.avg_slippage_bps, other.avg_slippage_bps, weights);
+        self.liquidity_depth_usd =
+            weighted_mean(self.liquidity_depth_usd, other.liquidity_depth_usd, weights);
+        self.avg_fx_rate = match (self.avg_fx_rate, other.avg_fx_rate) {
+            (Some(a), Some(b)) => Some(weighted_mean(a, b, weights)),
+            (a, b) => a.or(b),
+        };
+
+        self.sketches.merge(&other.sketches);
+        let latency = &self.sketches.settlement_latency_ms;
+        if latency.is_empty() {
+            // Buckets written before sketches existed only have percentiles
+            self.p50_settlement_latency_ms = blend_latency(
+                self.p50_settlement_latency_ms,
+                other.p50_settlement_latency_ms,
+                latency_weights,
+            );
+            self.p90_settlement_latency_ms = blend_latency(
+                self.p90_settlement_latency_ms,
+                other.p90_settlement_latency_ms,
+                latency_weights,
+            );
+            self.p99_settlement_latency_ms = blend_latency(
+                self.p99_settlement_latency_ms,
+                other.p99_settlement_latency_ms,
+                latency_weights,
+            );
+        } else {
+            let percentile = |q: f64| latency.quantile(q).map(|v| v.min(i32::MAX as u64) as i32);
+            self.p50_settlement_latency_ms = percentile(0.50);
+            self.p90_settlement_latency_ms = percentile(0.90);
+            self.p99_settlement_latency_ms = percentile(0.99);
+        }
+
+        self.total_transactions += other.total_transactions;
+        self.successful_transactions += other.successful_transactions;
+        self.failed_transactions += other.failed_transactions;
+        self.volume_usd += other.volume_usd;
+        if self.total_transactions > 0 {
+            self.success_rate =
+                (self.successful_transactions as f64 / self.total_transactions as f64) * 100.0;
+        }
+        for (code, count) in &other.failure_breakdown {
+            *self.failure_breakdown.entry(code.clone()).or_default() += count;
+        }
+    }
+}
+
+/// Granularity of a rollup of hourly corridor metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollupPeriod {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl RollupPeriod {
+    pub const ALL: [RollupPeriod; 3] = [
+        RollupPeriod::Daily,
+        RollupPeriod::Weekly,
+        RollupPeriod::Monthly,
+    ];
+
+    /// Stored in `corridor_metrics.period` and as the rollup's job type
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RollupPeriod::Daily => "daily",
+            RollupPeriod::Weekly => "weekly",
+            RollupPeriod::Monthly => "monthly",
+        }
+    }
+
+    /// Start of the period holding `time`: UTC midnight, on the Monday for
+    /// weeks and the 1st for months
+    pub fn start_of(&self, time: DateTime<Utc>) -> DateTime<Utc> {
+        let date = time.date_naive();
+        let date = match self {
+            RollupPeriod::Daily => date,
+            RollupPeriod::Weekly => {
+                date - Duration::days(date.weekday().num_days_from_monday() as i64)
+            }
+            RollupPeriod::Monthly => date.with_day(1).unwrap_or(date),
+        };
+        date.and_time(NaiveTime::MIN).and_utc()
+    }
+
+    /// Start of the period following the one that starts at `start`
+    pub fn next_start(&self, start: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            RollupPeriod::Daily => start + Duration::days(1),
+            RollupPeriod::Weekly => start + Duration::weeks(1),
+            RollupPeriod::Monthly => start
+                .checked_add_months(Months::new(1))
+                .unwrap_or(start + Duration::days(31)),
+        }
+    }
+}
+
+/// Corridor metrics rolled up over a day, week or month
+#[derive(Debug, Clone)]
+pub struct CorridorRollup {
+    pub period: RollupPeriod,
+    /// Hourly buckets merged into the rollup
+    pub hours: i64,
+    /// Merged metrics, with `hour_bucket` holding the start of the period
+    pub metrics: HourlyCorridorMetrics,
+}
+
+/// Merges metrics per corridor, in corridor key order, with how many buckets
+/// went into each
+pub fn merge_by_corridor(
+    metrics: impl IntoIterator<Item = HourlyCorridorMetrics>,
+) -> Vec<(HourlyCorridorMetrics, i64)> {
+    let mut merged: BTreeMap<String, (HourlyCorridorMetrics, i64)> = BTreeMap::new();
+
+    for metric in metrics {
+        match merged.get_mut(&metric.corridor_key) {
+            Some((existing, buckets)) => {
+                existing.merge(&metric);
+                *buckets += 1;
+            }
+            None => {
+                merged.insert(metric.corridor_key.clone(), (metric, 1));
+            }
+        }
+    }
+
+    merged.into_values().collect()
+}
+
+/// Rolls the hourly buckets of one period up into a rollup per corridor.
+/// Rollup ids derive from the corridor, period and start, so a rebuild or a
+/// replay of the same payments produces the same rows.
+pub fn rollup_hourly_metrics(
+    period: RollupPeriod,
+    period_start: DateTime<Utc>,
+    hourly: Vec<HourlyCorridorMetrics>,
+) -> Vec<CorridorRollup> {
+    merge_by_corridor(hourly)
+        .into_iter()
+        .map(|(mut metrics, hours)| {
+            let key = format!(
+                "{}/{}/{}",
+                metrics.corridor_key,
+                period.as_str(),
+                period_start.to_rfc3339()
+            );
+            metrics.id = Uuid::new_v5(&Uuid::NAMESPACE_OID, key.as_bytes()).to_string();
+            metrics.hour_bucket = period_start;
+            CorridorRollup {
+                period,
+                hours,
+                metrics,
+            }
+        })
+        .collect()
 }
 
 #[derive(Debug, Clone)]
@@ -469,8 +849,14 @@ mod tests {
                 success_rate: 95.0,
                 volume_usd: 1000.0,
                 avg_slippage_bps: 10.0,
+                avg_fx_rate: None,
                 avg_settlement_latency_ms: Some(500),
+                p50_settlement_latency_ms: None,
+                p90_settlement_latency_ms: None,
+                p99_settlement_latency_ms: None,
                 liquidity_depth_usd: 50000.0,
+                failure_breakdown: BTreeMap::new(),
+                sketches: CorridorSketches::default(),
             },
             HourlyCorridorMetrics {
                 id: "2".to_string(),
@@ -486,8 +872,14 @@ mod tests {
                 success_rate: 96.7,
                 volume_usd: 1500.0,
                 avg_slippage_bps: 12.0,
+                avg_fx_rate: None,
                 avg_settlement_latency_ms: Some(450),
+                p50_settlement_latency_ms: None,
+                p90_settlement_latency_ms: None,
+                p99_settlement_latency_ms: None,
                 liquidity_depth_usd: 55000.0,
+                failure_breakdown: BTreeMap::new(),
+                sketches: CorridorSketches::default(),
             },
         ];
 
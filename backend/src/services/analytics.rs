@@ -1,5 +1,8 @@
-use crate::models::corridor::{compute_median, CorridorMetrics, PaymentRecord};
-use std::collections::HashMap;
+use crate::models::corridor::{
+    compute_median, compute_percentile, Corridor, CorridorMetrics, PaymentRecord,
+};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
 
 #[derive(Debug, Clone)]
 pub struct CorridorTransaction {
@@ -74,7 +77,12 @@ pub fn compute_corridor_metrics(
             success_rate: 0.0,
             avg_settlement_latency_ms: None,
             median_settlement_latency_ms: None,
+            p90_settlement_latency_ms: None,
+            p99_settlement_latency_ms: None,
             liquidity_depth_usd: 0.0,
+            avg_fx_rate: None,
+            avg_slippage_bps: None,
+            failure_breakdown: BTreeMap::new(),
             volume_usd: 0.0,
             total_transactions: 0,
             successful_transactions: 0,
@@ -113,6 +121,8 @@ pub fn compute_corridor_metrics(
         None
     };
     let median_settlement_latency_ms = compute_median(&mut latency_values).map(|v| v as i32);
+    let p90_settlement_latency_ms = compute_percentile(&mut latency_values, 90.0).map(|v| v as i32);
+    let p99_settlement_latency_ms = compute_percentile(&mut latency_values, 99.0).map(|v| v as i32);
 
     // Compute liquidity depth using order book snapshot if provided
     let liquidity_depth_usd = order_book
@@ -134,13 +144,80 @@ pub fn compute_corridor_metrics(
         volume_usd,
         avg_settlement_latency_ms,
         median_settlement_latency_ms,
+        p90_settlement_latency_ms,
+        p99_settlement_latency_ms,
         liquidity_depth_usd,
+        avg_fx_rate: None,
+        avg_slippage_bps: None,
+        failure_breakdown: BTreeMap::new(),
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
     }
 }
 
+/// Effective FX rate and realised slippage of one cross-asset payment
+#[derive(Debug, Clone)]
+pub struct PaymentFx {
+    pub payment_id: Uuid,
+    pub corridor_key: String,
+    /// Units of the corridor's asset B per unit of asset A
+    pub fx_rate: f64,
+    /// Amount of asset A converted, sent or received
+    pub asset_a_amount: f64,
+    /// Basis points between the rate the payment converted at and the worst
+    /// rate its own limit (`dest_min` or `send_max`) accepted; `None` when the
+    /// limit isn't known
+    pub slippage_bps: Option<f64>,
+}
+
+/// Computes the effective FX rate of every successful cross-asset payment and
+/// its realised slippage against the limit it was submitted with.
+///
+/// Corridors are unordered, so rates are expressed as asset B per asset A.
+/// Slippage depends only on the payment itself, so it is the same whichever
+/// batch the payment is aggregated in.
+pub fn compute_payment_fx(payments: &[PaymentRecord]) -> Vec<PaymentFx> {
+    let mut results = Vec::new();
+
+    for payment in payments.iter().filter(|p| p.successful) {
+        let Some(rate) = payment.effective_fx_rate() else {
+            continue;
+        };
+        let corridor = payment.get_corridor();
+        let sells_asset_a = is_asset_a_source(payment, &corridor);
+
+        // Both rates are destination units per source unit, which the sender
+        // always wants more of
+        let slippage_bps = payment
+            .limit_fx_rate()
+            .map(|limit| ((rate - limit) / rate * 10_000.0).max(0.0));
+        let (fx_rate, asset_a_amount) = if sells_asset_a {
+            (rate, payment.source_amount.unwrap_or_default())
+        } else {
+            (1.0 / rate, payment.amount)
+        };
+
+        results.push(PaymentFx {
+            payment_id: payment.id,
+            corridor_key: corridor.to_string_key(),
+            fx_rate,
+            asset_a_amount,
+            slippage_bps,
+        });
+    }
+
+    results
+}
+
+fn is_asset_a_source(payment: &PaymentRecord, corridor: &Corridor) -> bool {
+    payment.source_asset_code == corridor.asset_a_code
+        && payment.source_asset_issuer == corridor.asset_a_issuer
+}
+
 /// Computes corridor metrics from payment records, aggregating settlement latency (both average and median) per corridor.
+///
+/// Cross-asset corridors also get a volume-weighted FX rate and the mean
+/// realised slippage of their path payments.
 pub fn compute_metrics_from_payments(payments: &[PaymentRecord]) -> Vec<CorridorMetrics> {
     let mut corridor_map: HashMap<String, Vec<&PaymentRecord>> = HashMap::new();
 
@@ -151,6 +228,14 @@ pub fn compute_metrics_from_payments(payments: &[PaymentRecord]) -> Vec<Corridor
         corridor_map.entry(key).or_default().push(payment);
     }
 
+    let mut corridor_fx: HashMap<String, Vec<PaymentFx>> = HashMap::new();
+    for fx in compute_payment_fx(payments) {
+        corridor_fx
+            .entry(fx.corridor_key.clone())
+            .or_default()
+            .push(fx);
+    }
+
     let mut results = Vec::new();
 
     for (key, corridor_payments) in corridor_map {
@@ -165,6 +250,7 @@ pub fn compute_metrics_from_payments(payments: &[PaymentRecord]) -> Vec<Corridor
         let mut volume_usd = 0.0;
         let mut latency_sum = 0i64;
         let mut latency_values: Vec<i64> = Vec::new();
+        let mut failure_breakdown: BTreeMap<String, i64> = BTreeMap::new();
 
         for p in &corridor_payments {
             if p.successful {
@@ -180,6 +266,8 @@ pub fn compute_metrics_from_payments(payments: &[PaymentRecord]) -> Vec<Corridor
                 }
             } else {
                 failed_transactions += 1;
+                let reason = p.failure_reason.as_deref().unwrap_or("tx_failed");
+                *failure_breakdown.entry(reason.to_string()).or_default() += 1;
             }
         }
 
@@ -195,6 +283,23 @@ pub fn compute_metrics_from_payments(payments: &[PaymentRecord]) -> Vec<Corridor
             None
         };
         let median_settlement_latency_ms = compute_median(&mut latency_values).map(|v| v as i32);
+        let p90_settlement_latency_ms =
+            compute_percentile(&mut latency_values, 90.0).map(|v| v as i32);
+        let p99_settlement_latency_ms =
+            compute_percentile(&mut latency_values, 99.0).map(|v| v as i32);
+
+        let (avg_fx_rate, avg_slippage_bps) = match corridor_fx.get(&key) {
+            Some(fx) => {
+                let asset_a_volume: f64 = fx.iter().map(|f| f.asset_a_amount).sum();
+                let asset_b_volume: f64 = fx.iter().map(|f| f.fx_rate * f.asset_a_amount).sum();
+                let avg_fx_rate = (asset_a_volume > 0.0).then(|| asset_b_volume / asset_a_volume);
+                let slippage: Vec<f64> = fx.iter().filter_map(|f| f.slippage_bps).collect();
+                let avg_slippage_bps = (!slippage.is_empty())
+                    .then(|| slippage.iter().sum::<f64>() / slippage.len() as f64);
+                (avg_fx_rate, avg_slippage_bps)
+            }
+            None => (None, None),
+        };
 
         results.push(CorridorMetrics {
             id: uuid::Uuid::new_v4().to_string(), // Generate new ID for this snapshot
@@ -211,7 +316,12 @@ pub fn compute_metrics_from_payments(payments: &[PaymentRecord]) -> Vec<Corridor
             volume_usd,
             avg_settlement_latency_ms,
             median_settlement_latency_ms,
+            p90_settlement_latency_ms,
+            p99_settlement_latency_ms,
             liquidity_depth_usd: 0.0, // Needs order book
+            avg_fx_rate,
+            avg_slippage_bps,
+            failure_breakdown,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         });
@@ -256,7 +366,12 @@ mod tests {
             destination_asset_code: dest_code.to_string(),
             destination_asset_issuer: "issuer2".to_string(),
             amount,
+            source_amount: None,
+            send_max: None,
+            dest_min: None,
+            path: Vec::new(),
             successful,
+            failure_reason: None,
             timestamp,
             submission_time: None,
             confirmation_time: None,
@@ -279,7 +394,12 @@ mod tests {
             destination_asset_code: dest_code.to_string(),
             destination_asset_issuer: "issuer2".to_string(),
             amount,
+            source_amount: None,
+            send_max: None,
+            dest_min: None,
+            path: Vec::new(),
             successful,
+            failure_reason: None,
             timestamp,
             submission_time: Some(submission),
             confirmation_time: Some(timestamp),
@@ -309,6 +429,34 @@ mod tests {
         assert_eq!(usdc_metrics.volume_usd, 150.0);
     }
 
+    #[test]
+    fn test_failure_breakdown_by_result_code() {
+        let failed = |reason: Option<&str>| PaymentRecord {
+            failure_reason: reason.map(str::to_string),
+            ..create_test_payment_record("USDC", "EURC", 10.0, false, Utc::now())
+        };
+        let payments = vec![
+            create_test_payment_record("USDC", "EURC", 100.0, true, Utc::now()),
+            failed(Some("op_underfunded")),
+            failed(Some("op_underfunded")),
+            failed(Some("op_no_trust")),
+            failed(None),
+        ];
+
+        let metrics = compute_metrics_from_payments(&payments);
+        assert_eq!(metrics.len(), 1);
+
+        let m = &metrics[0];
+        assert_eq!(m.total_transactions, 5);
+        assert_eq!(m.failed_transactions, 4);
+        assert_eq!(m.success_rate, 20.0);
+        // Failed payments carry no volume
+        assert_eq!(m.volume_usd, 100.0);
+        assert_eq!(m.failure_breakdown.get("op_underfunded"), Some(&2));
+        assert_eq!(m.failure_breakdown.get("op_no_trust"), Some(&1));
+        assert_eq!(m.failure_breakdown.get("tx_failed"), Some(&1));
+    }
+
     #[test]
     fn test_compute_metrics_by_window() {
         let now = Utc::now();
@@ -431,6 +579,8 @@ mod tests {
         assert_eq!(m.successful_transactions, 3);
         assert_eq!(m.avg_settlement_latency_ms, Some(2000)); // (1000 + 2000 + 3000) / 3
         assert_eq!(m.median_settlement_latency_ms, Some(2000)); // Median of [1000, 2000, 3000]
+        assert_eq!(m.p90_settlement_latency_ms, Some(3000));
+        assert_eq!(m.p99_settlement_latency_ms, Some(3000));
     }
 
     #[test]
@@ -468,4 +618,99 @@ mod tests {
         assert_eq!(m.avg_settlement_latency_ms, Some(2000)); // (1000 + 3000) / 2
         assert_eq!(m.median_settlement_latency_ms, Some(2000)); // Median of [1000, 3000]
     }
+
+    fn create_path_payment(
+        source_code: &str,
+        dest_code: &str,
+        source_amount: f64,
+        amount: f64,
+    ) -> PaymentRecord {
+        PaymentRecord {
+            source_amount: Some(source_amount),
+            path: vec!["XLM:native".to_string()],
+            ..create_test_payment_record(source_code, dest_code, amount, true, Utc::now())
+        }
+    }
+
+    #[test]
+    fn test_path_payments_form_cross_asset_corridor() {
+        let payments = vec![
+            create_path_payment("USDC", "EURC", 100.0, 92.0),
+            create_test_payment_record("USDC", "USDC", 50.0, true, Utc::now()),
+        ];
+
+        let metrics = compute_metrics_from_payments(&payments);
+        assert_eq!(metrics.len(), 2);
+
+        let cross = metrics
+            .iter()
+            .find(|m| m.asset_a_code != m.asset_b_code)
+            .expect("Should find the USDC/EURC corridor");
+        assert_eq!(cross.corridor_key, "EURC:issuer2->USDC:issuer1");
+        assert!(cross.avg_fx_rate.is_some());
+
+        let same_asset = metrics
+            .iter()
+            .find(|m| m.asset_a_code == m.asset_b_code)
+            .unwrap();
+        assert_eq!(same_asset.avg_fx_rate, None);
+        assert_eq!(same_asset.avg_slippage_bps, None);
+    }
+
+    #[test]
+    fn test_payment_fx_rate_and_slippage() {
+        // Corridor is EURC (A) / USDC (B), so rates are USDC per EURC
+        let strict_send = PaymentRecord {
+            dest_min: Some(107.8),
+            ..create_path_payment("EURC", "USDC", 100.0, 110.0)
+        };
+        let strict_receive = PaymentRecord {
+            source_asset_issuer: "issuer2".to_string(),
+            destination_asset_issuer: "issuer1".to_string(),
+            send_max: Some(114.24),
+            ..create_path_payment("USDC", "EURC", 112.0, 100.0)
+        };
+        let payments = vec![
+            strict_send,
+            create_path_payment("EURC", "USDC", 100.0, 108.0),
+            strict_receive,
+        ];
+
+        let fx = compute_payment_fx(&payments);
+        assert_eq!(fx.len(), 3);
+
+        let rates: Vec<f64> = fx.iter().map(|f| f.fx_rate).collect();
+        assert!(rates.iter().any(|r| (r - 1.10).abs() < 1e-9));
+        assert!(rates.iter().any(|r| (r - 1.08).abs() < 1e-9));
+        assert!(rates.iter().any(|r| (r - 1.12).abs() < 1e-9));
+
+        // Each payment is measured against its own limit: 110 delivered with
+        // a floor of 107.8, and 112 debited with a cap of 114.24
+        let slippage = |id| fx.iter().find(|f| f.payment_id == id).unwrap().slippage_bps;
+        assert!((slippage(payments[0].id).unwrap() - 200.0).abs() < 1e-6);
+        assert_eq!(slippage(payments[1].id), None);
+        assert!((slippage(payments[2].id).unwrap() - 2.24 / 114.24 * 10_000.0).abs() < 1e-6);
+
+        // A payment's slippage doesn't depend on what it is aggregated with
+        let alone = compute_payment_fx(&payments[..1]);
+        assert_eq!(alone[0].slippage_bps, slippage(payments[0].id));
+
+        let metrics = compute_metrics_from_payments(&payments);
+        assert_eq!(metrics.len(), 1);
+        // (110 + 108 + 112) USDC over 300 EURC
+        assert!((metrics[0].avg_fx_rate.unwrap() - 1.10).abs() < 1e-9);
+        // Payments without a known limit are left out of the mean
+        assert!(
+            (metrics[0].avg_slippage_bps.unwrap() - (200.0 + 2.24 / 114.24 * 10_000.0) / 2.0).abs()
+                < 1e-6
+        );
+    }
+
+    #[test]
+    fn test_failed_path_payments_have_no_fx() {
+        let mut payment = create_path_payment("EURC", "USDC", 100.0, 110.0);
+        payment.successful = false;
+
+        assert!(compute_payment_fx(&[payment]).is_empty());
+    }
 }
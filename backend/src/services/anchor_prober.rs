@@ -0,0 +1,502 @@
+//! Anchor availability from their SEP endpoints.
+//!
+//! Every endpoint an anchor's stellar.toml lists is probed on a schedule: the
+//! stellar.toml itself (SEP-1), the `/info` endpoints of SEP-6, 24, 31 and 38,
+//! and a SEP-10 challenge. Each probe records its status code, latency and
+//! whether the body has the fields the SEP requires. The share of probes
+//! answered over the uptime window becomes the anchor's uptime, which is
+//! folded into its status; a status change is pushed to WebSocket clients.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use futures::future::join_all;
+use reqwest::Client;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::time::{interval, Duration as TokioDuration};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::analytics::{apply_anchor_uptime, compute_anchor_metrics};
+use crate::broadcast::broadcast_anchor_update;
+use crate::database::Database;
+use crate::models::{AnchorEndpointProbe, AnchorStellarTomlRecord};
+use crate::services::stellar_toml::{
+    is_public_https_url, read_body_capped, untrusted_client, StellarToml, STELLAR_TOML_PATH,
+};
+use crate::websocket::WsState;
+
+/// Responses over this size are cut off; `/info` bodies are far smaller
+const MAX_PROBE_BODY_BYTES: usize = 256 * 1024;
+
+/// A SEP endpoint that can be probed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SepEndpoint {
+    Sep1,
+    Sep6,
+    Sep10,
+    Sep24,
+    Sep31,
+    Sep38,
+}
+
+impl SepEndpoint {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SepEndpoint::Sep1 => "sep1",
+            SepEndpoint::Sep6 => "sep6",
+            SepEndpoint::Sep10 => "sep10",
+            SepEndpoint::Sep24 => "sep24",
+            SepEndpoint::Sep31 => "sep31",
+            SepEndpoint::Sep38 => "sep38",
+        }
+    }
+
+    /// Whether a response body has the fields this SEP requires
+    pub fn is_valid_response(&self, body: &str) -> bool {
+        // Bodies that aren't JSON have none of the fields
+        let json = || serde_json::from_str::<Value>(body).unwrap_or(Value::Null);
+
+        match self {
+            SepEndpoint::Sep1 => StellarToml::parse(body).is_ok(),
+            SepEndpoint::Sep6 | SepEndpoint::Sep24 => {
+                let json = json();
+                json["deposit"].is_object() && json["withdraw"].is_object()
+            }
+            SepEndpoint::Sep10 => json()["transaction"].is_string(),
+            SepEndpoint::Sep31 => json()["receive"].is_object(),
+            SepEndpoint::Sep38 => json()["assets"].is_array(),
+        }
+    }
+}
+
+/// An endpoint to probe and the URL to request
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProbeTarget {
+    pub sep: SepEndpoint,
+    pub url: String,
+}
+
+/// The endpoints listed in an anchor's stellar.toml, leaving out any not
+/// served over https from a public domain. The SEP-10 challenge is requested
+/// for `account`, which only has to be a valid account ID.
+pub fn probe_targets(record: &AnchorStellarTomlRecord, account: &str) -> Vec<ProbeTarget> {
+    let toml = &record.toml;
+    let info = |base: &str| format!("{}/info", base.trim_end_matches('/'));
+
+    let mut targets = vec![ProbeTarget {
+        sep: SepEndpoint::Sep1,
+        url: format!("https://{}{}", record.home_domain, STELLAR_TOML_PATH),
+    }];
+    for (sep, server) in [
+        (SepEndpoint::Sep6, &toml.transfer_server),
+        (SepEndpoint::Sep24, &toml.transfer_server_sep0024),
+        (SepEndpoint::Sep31, &toml.direct_payment_server),
+        (SepEndpoint::Sep38, &toml.anchor_quote_server),
+    ] {
+        if let Some(server) = server {
+            targets.push(ProbeTarget {
+                sep,
+                url: info(server),
+            });
+        }
+    }
+    if let Some(endpoint) = &toml.web_auth_endpoint {
+        let separator = if endpoint.contains('?') { '&' } else { '?' };
+        targets.push(ProbeTarget {
+            sep: SepEndpoint::Sep10,
+            url: format!("{}{}account={}", endpoint, separator, account),
+        });
+    }
+
+    // Files stored before endpoints were checked may still name internal hosts
+    targets.retain(|target| is_public_https_url(&target.url));
+    targets
+}
+
+/// A response to a probe, whatever its status
+#[derive(Debug, Clone)]
+pub struct ProbeResponse {
+    pub status_code: u16,
+    pub body: String,
+}
+
+/// How endpoints are requested
+#[async_trait::async_trait]
+pub trait ProbeClient: Send + Sync {
+    /// GET `url`; errors only when no response was received
+    async fn get(&self, url: &str) -> Result<ProbeResponse>;
+}
+
+/// Plain HTTP requests; a request that times out counts as down
+pub struct HttpProbeClient {
+    client: Client,
+}
+
+impl HttpProbeClient {
+    pub fn new() -> Self {
+        Self {
+            client: untrusted_client(),
+        }
+    }
+}
+
+impl Default for HttpProbeClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl ProbeClient for HttpProbeClient {
+    async fn get(&self, url: &str) -> Result<ProbeResponse> {
+        let response = self
+            .client
+            .get(url)
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach {}", url))?;
+
+        let status_code = response.status().as_u16();
+        let body = read_body_capped(response, MAX_PROBE_BODY_BYTES, url).await?;
+
+        Ok(ProbeResponse {
+            status_code,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AnchorProberConfig {
+    pub interval_minutes: u64,
+    /// Hours of probes the uptime percentage covers
+    pub uptime_window_hours: i64,
+    /// Days probes are kept for
+    pub retention_days: i64,
+}
+
+impl Default for AnchorProberConfig {
+    fn default() -> Self {
+        Self {
+            interval_minutes: 5,
+            uptime_window_hours: 24,
+            retention_days: 30,
+        }
+    }
+}
+
+/// What a round of probes found
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ProbeSummary {
+    pub anchors: usize,
+    pub probes: usize,
+    /// Probes without a 2xx response
+    pub failed: usize,
+    /// Anchors whose status changed
+    pub status_changes: usize,
+    /// Anchors whose probes or uptime couldn't be stored
+    pub errors: usize,
+}
+
+/// Probes anchors' SEP endpoints and keeps their uptime current
+pub struct AnchorProber {
+    db: Arc<Database>,
+    client: Arc<dyn ProbeClient>,
+    ws_state: Arc<WsState>,
+    config: AnchorProberConfig,
+}
+
+impl AnchorProber {
+    pub fn new(
+        db: Arc<Database>,
+        client: Arc<dyn ProbeClient>,
+        ws_state: Arc<WsState>,
+        config: AnchorProberConfig,
+    ) -> Self {
+        Self {
+            db,
+            client,
+            ws_state,
+            config,
+        }
+    }
+
+    /// Start the periodic probes
+    pub async fn start_scheduler(self: Arc<Self>) {
+        info!(
+            "Starting anchor endpoint prober (interval: {} minutes)",
+            self.config.interval_minutes
+        );
+
+        let mut ticker = interval(TokioDuration::from_secs(self.config.interval_minutes * 60));
+
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = self.probe_all().await {
+                error!("Anchor endpoint probing failed: {}", e);
+            }
+        }
+    }
+
+    /// Probe every anchor with a crawled stellar.toml and update its uptime
+    pub async fn probe_all(&self) -> Result<ProbeSummary> {
+        let records = self
+            .db
+            .get_anchor_stellar_tomls()
+            .await
+            .context("Failed to load anchor stellar.toml files")?;
+
+        let mut summary = ProbeSummary::default();
+        for record in records {
+            let anchor_id = match Uuid::parse_str(&record.anchor_id) {
+                Ok(id) => id,
+                Err(_) => continue,
+            };
+            if let Err(e) = self.probe_anchor(anchor_id, &record, &mut summary).await {
+                error!("Failed to probe anchor {}: {:#}", anchor_id, e);
+                summary.errors += 1;
+            }
+        }
+
+        let cutoff = Utc::now() - Duration::days(self.config.retention_days);
+        self.db.delete_anchor_endpoint_probes_before(cutoff).await?;
+
+        info!(
+            "Probed {} endpoints of {} anchors: {} failed, {} status changes, {} errors",
+            summary.probes, summary.anchors, summary.failed, summary.status_changes, summary.errors
+        );
+        Ok(summary)
+    }
+
+    async fn probe_anchor(
+        &self,
+        anchor_id: Uuid,
+        record: &AnchorStellarTomlRecord,
+        summary: &mut ProbeSummary,
+    ) -> Result<()> {
+        let anchor = match self.db.get_anchor_by_id(anchor_id).await? {
+            Some(anchor) => anchor,
+            None => return Ok(()),
+        };
+
+        let targets = probe_targets(record, &anchor.stellar_account);
+        let probes = join_all(targets.iter().map(|t| self.probe(&record.anchor_id, t))).await;
+        for probe in &probes {
+            if !probe.up {
+                warn!(
+                    "{} endpoint {} of anchor {} is down: {}",
+                    probe.sep,
+                    probe.url,
+                    anchor.name,
+                    probe.error.as_deref().unwrap_or("no error")
+                );
+                summary.failed += 1;
+            }
+            self.db.record_anchor_endpoint_probe(probe).await?;
+        }
+        summary.probes += probes.len();
+        summary.anchors += 1;
+
+        if self.update_uptime(anchor_id).await? {
+            summary.status_changes += 1;
+        }
+        Ok(())
+    }
+
+    async fn probe(&self, anchor_id: &str, target: &ProbeTarget) -> AnchorEndpointProbe {
+        let probed_at: DateTime<Utc> = Utc::now();
+        let started = Instant::now();
+        let result = self.client.get(&target.url).await;
+        let latency_ms = started.elapsed().as_millis() as i64;
+
+        let (status_code, up, schema_valid, error) = match result {
+            Ok(response) => {
+                let up = (200..300).contains(&response.status_code);
+                let schema_valid = up && target.sep.is_valid_response(&response.body);
+                let error = if !up {
+                    Some(format!("{} returned {}", target.url, response.status_code))
+                } else if !schema_valid {
+                    Some(format!(
+                        "{} response is missing fields {} requires",
+                        target.url,
+                        target.sep.as_str().to_uppercase()
+                    ))
+                } else {
+                    None
+                };
+                (Some(response.status_code as i64), up, schema_valid, error)
+            }
+            Err(e) => (None, false, false, Some(e.to_string())),
+        };
+
+        AnchorEndpointProbe {
+            anchor_id: anchor_id.to_string(),
+            sep: target.sep.as_str().to_string(),
+            url: target.url.clone(),
+            status_code,
+            latency_ms,
+            up,
+            schema_valid,
+            error,
+            probed_at,
+        }
+    }
+
+    /// Recompute an anchor's uptime and fold it into its status, returning
+    /// whether the status changed
+    async fn update_uptime(&self, anchor_id: Uuid) -> Result<bool> {
+        let since = Utc::now() - Duration::hours(self.config.uptime_window_hours);
+        let uptime = self.db.get_anchor_uptime(anchor_id, since).await?;
+
+        let anchor = match self.db.get_anchor_by_id(anchor_id).await? {
+            Some(anchor) => anchor,
+            None => return Ok(false),
+        };
+        let metrics = apply_anchor_uptime(
+            compute_anchor_metrics(
+                anchor.total_transactions,
+                anchor.successful_transactions,
+                anchor.failed_transactions,
+                (anchor.avg_settlement_time_ms > 0).then_some(anchor.avg_settlement_time_ms),
+            ),
+            uptime,
+        );
+        let status = metrics.status.as_str();
+        self.db
+            .update_anchor_uptime(anchor_id, uptime, status)
+            .await?;
+
+        if anchor.status == status {
+            return Ok(false);
+        }
+
+        info!(
+            "Anchor {} status changed from {} to {} (uptime {:.2}%)",
+            anchor.name,
+            anchor.status,
+            status,
+            uptime.unwrap_or_default()
+        );
+        if let Some(anchor) = self.db.get_anchor_by_id(anchor_id).await? {
+            broadcast_anchor_update(&self.ws_state, &anchor);
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(toml: StellarToml) -> AnchorStellarTomlRecord {
+        AnchorStellarTomlRecord {
+            anchor_id: Uuid::new_v4().to_string(),
+            home_domain: "anchor.example.com".to_string(),
+            toml,
+            toml_hash: String::new(),
+            validation_errors: Vec::new(),
+            fetch_error: None,
+            changed_at: Utc::now(),
+            checked_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_probe_targets_follow_the_stellar_toml() {
+        let toml = StellarToml {
+            transfer_server_sep0024: Some("https://anchor.example.com/sep24/".to_string()),
+            anchor_quote_server: Some("https://anchor.example.com/sep38".to_string()),
+            web_auth_endpoint: Some("https://anchor.example.com/auth".to_string()),
+            ..StellarToml::default()
+        };
+
+        let targets = probe_targets(&record(toml), "GACCOUNT");
+        let urls: Vec<_> = targets
+            .iter()
+            .map(|t| (t.sep.as_str(), t.url.as_str()))
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                (
+                    "sep1",
+                    "https://anchor.example.com/.well-known/stellar.toml"
+                ),
+                ("sep24", "https://anchor.example.com/sep24/info"),
+                ("sep38", "https://anchor.example.com/sep38/info"),
+                ("sep10", "https://anchor.example.com/auth?account=GACCOUNT"),
+            ]
+        );
+
+        // Nothing but the stellar.toml itself
+        assert_eq!(probe_targets(&record(StellarToml::default()), "G").len(), 1);
+
+        // Endpoints on internal hosts are never probed
+        let toml = StellarToml {
+            transfer_server: Some("https://10.0.0.5/sep6".to_string()),
+            anchor_quote_server: Some("https://anchor.example.com:8443/sep38".to_string()),
+            ..StellarToml::default()
+        };
+        assert_eq!(probe_targets(&record(toml), "G").len(), 1);
+    }
+
+    #[test]
+    fn test_response_schemas() {
+        let info = r#"{"deposit": {"USDC": {"enabled": true}}, "withdraw": {}}"#;
+        assert!(SepEndpoint::Sep6.is_valid_response(info));
+        assert!(SepEndpoint::Sep24.is_valid_response(info));
+        assert!(!SepEndpoint::Sep24.is_valid_response(r#"{"deposit": {}}"#));
+        assert!(!SepEndpoint::Sep6.is_valid_response("<html>Maintenance</html>"));
+
+        assert!(SepEndpoint::Sep31.is_valid_response(r#"{"receive": {}}"#));
+        assert!(SepEndpoint::Sep38.is_valid_response(r#"{"assets": []}"#));
+        assert!(!SepEndpoint::Sep38.is_valid_response(r#"{"assets": {}}"#));
+        assert!(SepEndpoint::Sep10.is_valid_response(r#"{"transaction": "AAAA"}"#));
+        assert!(!SepEndpoint::Sep10.is_valid_response(r#"{"error": "bad account"}"#));
+
+        assert!(SepEndpoint::Sep1.is_valid_response("VERSION = \"2.0.0\""));
+        assert!(!SepEndpoint::Sep1.is_valid_response("not toml ["));
+    }
+
+    #[tokio::test]
+    async fn test_bodies_are_capped_and_redirects_not_followed() {
+        use axum::{body::Body, routing::get, Router};
+
+        let chunk = || Ok::<_, std::io::Error>(vec![b'{'; 16 * 1024]);
+        let app = Router::new()
+            .route(
+                "/info",
+                get(move || async move { Body::from_stream(futures::stream::repeat_with(chunk)) }),
+            )
+            .route("/small", get(|| async { "{}" }))
+            .route(
+                "/moved",
+                get(|| async { axum::response::Redirect::temporary("http://10.0.0.5/") }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = HttpProbeClient::new();
+        let error = client
+            .get(&format!("http://{}/info", addr))
+            .await
+            .unwrap_err();
+        assert!(
+            error.to_string().ends_with("is larger than 256KB"),
+            "{}",
+            error
+        );
+
+        let response = client.get(&format!("http://{}/small", addr)).await.unwrap();
+        assert_eq!((response.status_code, response.body.as_str()), (200, "{}"));
+
+        // Redirects are reported, not followed
+        let response = client.get(&format!("http://{}/moved", addr)).await.unwrap();
+        assert_eq!(response.status_code, 307);
+    }
+}
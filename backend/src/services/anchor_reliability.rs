@@ -0,0 +1,144 @@
+use anyhow::{Context, Result};
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::time::{interval, Duration as TokioDuration};
+use tracing::{debug, error, info};
+
+use crate::analytics::{compute_anchor_reliability_score, AnchorAssetPerformance};
+use crate::database::Database;
+use crate::models::AnchorReliabilityRecord;
+use crate::services::price_feed::PriceFeedClient;
+
+#[derive(Debug, Clone)]
+pub struct AnchorReliabilityConfig {
+    pub interval_hours: u64,
+    /// Days of payments each score covers
+    pub window_days: i64,
+}
+
+impl Default for AnchorReliabilityConfig {
+    fn default() -> Self {
+        Self {
+            interval_hours: 1,
+            window_days: 7,
+        }
+    }
+}
+
+/// Scores every anchor from the ingested payments of the assets it issues
+pub struct AnchorReliabilityService {
+    db: Arc<Database>,
+    price_feed: Arc<PriceFeedClient>,
+    config: AnchorReliabilityConfig,
+}
+
+impl AnchorReliabilityService {
+    pub fn new(
+        db: Arc<Database>,
+        price_feed: Arc<PriceFeedClient>,
+        config: AnchorReliabilityConfig,
+    ) -> Self {
+        Self {
+            db,
+            price_feed,
+            config,
+        }
+    }
+
+    /// Start the periodic scoring job
+    pub async fn start_scheduler(self: Arc<Self>) {
+        info!(
+            "Starting anchor reliability scoring (interval: {} hours, window: {} days)",
+            self.config.interval_hours, self.config.window_days
+        );
+
+        let mut ticker = interval(TokioDuration::from_secs(self.config.interval_hours * 3600));
+
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = self.score_anchors().await {
+                error!("Anchor reliability scoring failed: {}", e);
+            }
+        }
+    }
+
+    /// Compute and store a score for every anchor with registered assets,
+    /// returning how many were scored.
+    ///
+    /// Volume is priced in USD and scored against the busiest anchor in the
+    /// same run, so scores from one run are comparable with each other.
+    /// Assets without a price add no volume.
+    pub async fn score_anchors(&self) -> Result<usize> {
+        let now = Utc::now();
+        let window_start = now - Duration::days(self.config.window_days);
+
+        let mut performance = self
+            .db
+            .get_anchor_asset_performance(window_start)
+            .await
+            .context("Failed to load anchor asset performance")?;
+        self.price_volumes(performance.values_mut().flatten()).await;
+
+        let network_max_volume = performance
+            .values()
+            .map(|assets| assets.iter().map(|a| a.total_volume_usd).sum::<f64>())
+            .fold(0.0, f64::max);
+
+        let mut scored = 0;
+        for (anchor_id, assets) in performance {
+            let score = compute_anchor_reliability_score(&assets, network_max_volume);
+
+            self.db
+                .record_anchor_reliability_score(&AnchorReliabilityRecord {
+                    anchor_id,
+                    composite_score: score.composite_score,
+                    asset_performance_score: score.asset_performance_score,
+                    volume_score: score.volume_score,
+                    asset_diversity_score: score.asset_diversity_score,
+                    weighted_success_rate: score.weighted_success_rate,
+                    total_assets: score.total_assets as i64,
+                    total_volume_usd: score.total_volume_usd,
+                    scoring_version: Some(score.scoring_version),
+                    assets,
+                    window_start,
+                    computed_at: now,
+                })
+                .await
+                .context("Failed to store anchor reliability score")?;
+            scored += 1;
+        }
+
+        info!("Scored reliability for {} anchors", scored);
+        Ok(scored)
+    }
+
+    /// Fill in each asset's USD volume from the price feed
+    async fn price_volumes<'a>(
+        &self,
+        assets: impl Iterator<Item = &'a mut AnchorAssetPerformance>,
+    ) {
+        let mut prices: HashMap<String, Option<f64>> = HashMap::new();
+        for asset in assets.filter(|asset| asset.total_volume > 0.0) {
+            let key = price_key(asset);
+            let price = match prices.get(&key) {
+                Some(price) => *price,
+                None => {
+                    let price = self.price_feed.get_price(&key).await.ok();
+                    if price.is_none() {
+                        debug!("No USD price for {}, its volume is not scored", key);
+                    }
+                    prices.insert(key, price);
+                    price
+                }
+            };
+            asset.total_volume_usd = price.map_or(0.0, |price| asset.total_volume * price);
+        }
+    }
+}
+
+/// The price feed's key for an asset
+fn price_key(asset: &AnchorAssetPerformance) -> String {
+    format!("{}:{}", asset.asset_code, asset.asset_issuer)
+}
@@ -90,6 +90,15 @@ pub struct SubmissionResult {
     pub timestamp: u64,
 }
 
+/// Snapshot hash held by the contract for an epoch
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct OnChainSnapshot {
+    /// Hex-encoded hash
+    pub hash: String,
+    /// Ledger the contract state was read at, when the RPC reports it
+    pub ledger: Option<u64>,
+}
+
 impl ContractService {
     /// Create a new contract service instance
     pub fn new(config: ContractConfig) -> Result<Self> {
@@ -545,6 +554,15 @@ impl ContractService {
 
     /// Get snapshot data for a specific epoch from the contract
     pub async fn get_snapshot_by_epoch(&self, epoch: u64) -> Result<Option<String>> {
+        Ok(self
+            .get_snapshot_at_epoch(epoch)
+            .await?
+            .map(|snapshot| snapshot.hash))
+    }
+
+    /// Get the snapshot hash the contract holds for an epoch, with the ledger
+    /// the contract state was read at
+    pub async fn get_snapshot_at_epoch(&self, epoch: u64) -> Result<Option<OnChainSnapshot>> {
         debug!("Getting snapshot for epoch {}", epoch);
 
         let get_args = json!({
@@ -581,22 +599,131 @@ impl ContractService {
             .context("Failed to parse get snapshot response")?;
 
         if let Some(error) = body.error {
-            if error.message.contains("not found") {
+            // The contract panics with "No snapshot found for epoch N"
+            if error.message.contains("not found") || error.message.contains("No snapshot found") {
                 return Ok(None);
             }
             return Err(anyhow::anyhow!("Get snapshot failed: {}", error.message));
         }
 
-        if let Some(result) = body.result {
-            let hash_hex = result
-                .get("returnValue")
-                .and_then(|rv| rv.as_str())
-                .map(|s| s.to_string());
+        Ok(body.result.and_then(|result| {
+            let hash = result.get("returnValue")?.as_str()?.to_string();
+            Some(OnChainSnapshot {
+                hash,
+                ledger: result.get("latestLedger").and_then(|l| l.as_u64()),
+            })
+        }))
+    }
 
-            Ok(hash_hex)
-        } else {
-            Ok(None)
+    /// Every epoch the contract holds a snapshot for, in ascending order
+    pub async fn get_all_epochs(&self) -> Result<Vec<u64>> {
+        debug!("Listing snapshot epochs");
+
+        let list_args = json!({
+            "contractId": self.config.contract_id,
+            "function": "get_all_epochs",
+            "args": []
+        });
+
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: 1,
+            method: "simulateTransaction".to_string(),
+            params: json!({
+                "transaction": list_args
+            }),
+        };
+
+        let response = self
+            .client
+            .post(&self.config.rpc_url)
+            .json(&request)
+            .send()
+            .await
+            .context("Failed to send list epochs request")?;
+
+        let body: JsonRpcResponse<serde_json::Value> = response
+            .json()
+            .await
+            .context("Failed to parse list epochs response")?;
+
+        if let Some(error) = body.error {
+            return Err(anyhow::anyhow!("List epochs failed: {}", error.message));
+        }
+
+        let Some(epochs) = body
+            .result
+            .as_ref()
+            .and_then(|result| result.get("returnValue"))
+            .and_then(|rv| rv.as_array())
+        else {
+            return Ok(Vec::new());
+        };
+
+        // u64 values are sent as strings, so accept either form
+        epochs
+            .iter()
+            .map(|epoch| {
+                epoch
+                    .as_u64()
+                    .or_else(|| epoch.as_str()?.parse().ok())
+                    .ok_or_else(|| anyhow::anyhow!("Invalid epoch in contract response: {}", epoch))
+            })
+            .collect()
+    }
+
+    /// Check a hash against the snapshot the contract holds for an epoch
+    pub async fn verify_snapshot_at_epoch(&self, hash: &str, epoch: u64) -> Result<bool> {
+        debug!("Verifying snapshot hash {} at epoch {}", hash, epoch);
+
+        let verify_args = json!({
+            "contractId": self.config.contract_id,
+            "function": "verify_snapshot_at_epoch",
+            "args": [
+                {
+                    "type": "bytes",
+                    "value": hash
+                },
+                {
+                    "type": "u64",
+                    "value": epoch.to_string()
+                }
+            ]
+        });
+
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: 1,
+            method: "simulateTransaction".to_string(),
+            params: json!({
+                "transaction": verify_args
+            }),
+        };
+
+        let response = self
+            .client
+            .post(&self.config.rpc_url)
+            .json(&request)
+            .send()
+            .await
+            .context("Failed to send verification request")?;
+
+        let body: JsonRpcResponse<serde_json::Value> = response
+            .json()
+            .await
+            .context("Failed to parse verification response")?;
+
+        if let Some(error) = body.error {
+            return Err(anyhow::anyhow!(
+                "Verification request failed: {}",
+                error.message
+            ));
         }
+
+        Ok(body
+            .result
+            .and_then(|result| result.get("returnValue").and_then(|rv| rv.as_bool()))
+            .unwrap_or(false))
     }
 }
 
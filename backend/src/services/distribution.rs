@@ -0,0 +1,261 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use hdrhistogram::serialization::{Deserializer, Serializer, V2Serializer};
+use hdrhistogram::Histogram;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::models::corridor::PaymentRecord;
+use crate::services::aggregation::HourlyCorridorMetrics;
+
+/// Significant digits kept by each sketch: quantiles are within 1% of the
+/// exact value, which keeps a month-long rollup to a few kilobytes per corridor
+const SIGNIFICANT_DIGITS: u8 = 2;
+
+/// Payment sizes are recorded in hundredths of the delivered asset
+const HUNDREDTHS_PER_UNIT: f64 = 100.0;
+
+/// Mergeable quantile sketch over non-negative integers, backed by an HDR
+/// histogram. Unlike averages or percentiles, two sketches merge without
+/// losing accuracy, so hourly buckets roll up into any longer window.
+#[derive(Debug, Clone)]
+pub struct Sketch(Histogram<u64>);
+
+impl Default for Sketch {
+    fn default() -> Self {
+        Self(Histogram::new(SIGNIFICANT_DIGITS).expect("valid significant digits"))
+    }
+}
+
+impl Sketch {
+    pub fn record(&mut self, value: u64) {
+        // Auto-resizing histograms accept any u64
+        let _ = self.0.record(value);
+    }
+
+    pub fn merge(&mut self, other: &Sketch) {
+        let _ = self.0.add(&other.0);
+    }
+
+    /// Number of recorded values
+    pub fn len(&self) -> u64 {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Value at `quantile` (0.0 to 1.0); `None` when nothing was recorded
+    pub fn quantile(&self, quantile: f64) -> Option<u64> {
+        (!self.is_empty()).then(|| self.0.value_at_quantile(quantile))
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (!self.is_empty()).then(|| self.0.mean())
+    }
+
+    pub fn max(&self) -> Option<u64> {
+        (!self.is_empty()).then(|| self.0.max())
+    }
+
+    /// Encodes the sketch in the HDR histogram V2 format
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        V2Serializer::new()
+            .serialize(&self.0, &mut bytes)
+            .map_err(|e| anyhow!("Failed to encode sketch: {:?}", e))?;
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut histogram: Histogram<u64> = Deserializer::new()
+            .deserialize(&mut &bytes[..])
+            .map_err(|e| anyhow!("Failed to decode sketch: {:?}", e))?;
+        histogram.auto(true);
+        Ok(Self(histogram))
+    }
+}
+
+/// Settlement latency and payment size sketches for one corridor
+#[derive(Debug, Clone, Default)]
+pub struct CorridorSketches {
+    /// Settlement latency in milliseconds
+    pub settlement_latency_ms: Sketch,
+    /// Delivered amount in hundredths of the corridor's destination asset
+    pub payment_size: Sketch,
+}
+
+impl CorridorSketches {
+    /// Records a successful payment; failed payments carry no latency or volume
+    pub fn record(&mut self, payment: &PaymentRecord) {
+        if !payment.successful {
+            return;
+        }
+        if let Some(latency_ms) = payment.settlement_latency_ms() {
+            self.settlement_latency_ms
+                .record(u64::try_from(latency_ms).unwrap_or(0));
+        }
+        if payment.amount.is_finite() && payment.amount >= 0.0 {
+            self.payment_size
+                .record((payment.amount * HUNDREDTHS_PER_UNIT).round() as u64);
+        }
+    }
+
+    pub fn merge(&mut self, other: &CorridorSketches) {
+        self.settlement_latency_ms
+            .merge(&other.settlement_latency_ms);
+        self.payment_size.merge(&other.payment_size);
+    }
+}
+
+/// Builds sketches per corridor, keyed like `compute_metrics_from_payments`
+pub fn sketches_from_payments(payments: &[PaymentRecord]) -> HashMap<String, CorridorSketches> {
+    let mut sketches: HashMap<String, CorridorSketches> = HashMap::new();
+    for payment in payments {
+        sketches
+            .entry(payment.get_corridor().to_string_key())
+            .or_default()
+            .record(payment);
+    }
+    sketches
+}
+
+/// Percentiles of a distribution
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DistributionSummary {
+    /// Number of measurements
+    #[schema(example = 1250)]
+    pub samples: u64,
+    pub mean: Option<f64>,
+    #[schema(example = 4000.0)]
+    pub p50: Option<f64>,
+    pub p90: Option<f64>,
+    #[schema(example = 9000.0)]
+    pub p95: Option<f64>,
+    pub p99: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl DistributionSummary {
+    /// Summarises a sketch, dividing every value by `scale`
+    pub fn from_sketch(sketch: &Sketch, scale: f64) -> Self {
+        let value = |v: Option<u64>| v.map(|v| v as f64 / scale);
+        Self {
+            samples: sketch.len(),
+            mean: sketch.mean().map(|m| m / scale),
+            p50: value(sketch.quantile(0.50)),
+            p90: value(sketch.quantile(0.90)),
+            p95: value(sketch.quantile(0.95)),
+            p99: value(sketch.quantile(0.99)),
+            max: value(sketch.max()),
+        }
+    }
+}
+
+/// Latency and payment size distributions over a time window
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CorridorDistributions {
+    /// Window length, e.g. `24h` or `30d`
+    #[schema(example = "7d")]
+    pub window: String,
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    /// Hourly buckets merged into the distributions
+    pub hours: usize,
+    /// Settlement latency in milliseconds
+    pub settlement_latency_ms: DistributionSummary,
+    /// Delivered amount per successful payment, in units of the corridor's
+    /// destination asset
+    pub payment_size: DistributionSummary,
+}
+
+/// Merges the sketches of hourly buckets in `[window_start, window_end]`
+pub fn summarize_distributions(
+    metrics: &[HourlyCorridorMetrics],
+    window: &str,
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+) -> CorridorDistributions {
+    let mut merged = CorridorSketches::default();
+    let mut hours = 0;
+    for metric in metrics
+        .iter()
+        .filter(|m| m.hour_bucket >= window_start && m.hour_bucket <= window_end)
+    {
+        merged.merge(&metric.sketches);
+        hours += 1;
+    }
+
+    CorridorDistributions {
+        window: window.to_string(),
+        window_start,
+        window_end,
+        hours,
+        settlement_latency_ms: DistributionSummary::from_sketch(&merged.settlement_latency_ms, 1.0),
+        payment_size: DistributionSummary::from_sketch(&merged.payment_size, HUNDREDTHS_PER_UNIT),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Within the sketch's 1% relative error
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.expect("a value");
+        assert!(
+            (actual - expected).abs() <= expected * 0.01,
+            "{} is not within 1% of {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_merged_sketches_match_a_single_sketch() {
+        let mut whole = Sketch::default();
+        let mut first = Sketch::default();
+        let mut second = Sketch::default();
+        for v in 1..=1_000u64 {
+            whole.record(v * 100);
+            if v % 3 == 0 {
+                first.record(v * 100);
+            } else {
+                second.record(v * 100);
+            }
+        }
+        first.merge(&second);
+
+        assert_eq!(first.len(), 1_000);
+        for q in [0.5, 0.9, 0.95, 0.99] {
+            assert_eq!(first.quantile(q), whole.quantile(q));
+        }
+        assert_close(first.quantile(0.5).map(|v| v as f64), 50_000.0);
+        assert_close(first.quantile(0.99).map(|v| v as f64), 99_000.0);
+    }
+
+    #[test]
+    fn test_sketch_round_trips_through_bytes() {
+        let mut sketch = Sketch::default();
+        for v in [5, 1_200, 86_400_000] {
+            sketch.record(v);
+        }
+
+        let mut decoded = Sketch::from_bytes(&sketch.to_bytes().unwrap()).unwrap();
+        assert_eq!(decoded.len(), 3);
+        assert_eq!(decoded.quantile(0.5), sketch.quantile(0.5));
+
+        // Decoded sketches keep growing past their recorded range
+        decoded.record(u64::MAX / 4);
+        assert_eq!(decoded.len(), 4);
+        assert!(Sketch::from_bytes(b"not a sketch").is_err());
+    }
+
+    #[test]
+    fn test_empty_summary() {
+        let summary = DistributionSummary::from_sketch(&Sketch::default(), 1.0);
+        assert_eq!(summary, DistributionSummary::default());
+    }
+}
@@ -0,0 +1,284 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+use crate::services::aggregation::HourlyCorridorMetrics;
+
+/// Default window when none is requested
+pub const DEFAULT_WINDOW: &str = "24h";
+
+/// Longest window we'll aggregate over
+const MAX_WINDOW_DAYS: i64 = 90;
+
+/// Failed payments for one result code, compared with the previous window
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FailureReasonStat {
+    /// Horizon result code
+    #[schema(example = "op_underfunded")]
+    pub result_code: String,
+    /// Failures with this code in the window
+    #[schema(example = 42)]
+    pub count: i64,
+    /// Share of all failures in the window, as a percentage
+    #[schema(example = 61.8)]
+    pub share_pct: f64,
+    /// Failures with this code in the previous window
+    #[schema(example = 20)]
+    pub previous_count: i64,
+    /// Percentage change versus the previous window; absent when the code
+    /// did not occur before
+    #[schema(example = 110.0)]
+    pub change_pct: Option<f64>,
+}
+
+/// Failure taxonomy over a time window with trend versus the window before it
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FailureBreakdown {
+    /// Window length, e.g. `24h` or `7d`
+    #[schema(example = "24h")]
+    pub window: String,
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    pub total_transactions: i64,
+    pub failed_transactions: i64,
+    /// Success rate in the window, as a percentage
+    pub success_rate: f64,
+    pub previous_total_transactions: i64,
+    pub previous_failed_transactions: i64,
+    /// Success rate in the previous window; absent without traffic
+    pub previous_success_rate: Option<f64>,
+    /// Failure reasons, most frequent first
+    pub reasons: Vec<FailureReasonStat>,
+}
+
+/// Parses a window such as `6h`, `24h` or `7d`
+pub fn parse_window(window: &str) -> Option<Duration> {
+    let window = window.trim();
+    let duration = if let Some(hours) = window.strip_suffix('h') {
+        Duration::try_hours(parse_amount(hours)?)?
+    } else if let Some(days) = window.strip_suffix('d') {
+        Duration::try_days(parse_amount(days)?)?
+    } else {
+        return None;
+    };
+
+    (duration <= Duration::days(MAX_WINDOW_DAYS)).then_some(duration)
+}
+
+fn parse_amount(amount: &str) -> Option<i64> {
+    amount.parse().ok().filter(|n| *n > 0)
+}
+
+/// Summarises failures in `[window_start, window_end]` from hourly corridor
+/// metrics, comparing against the equally long window that precedes it.
+/// Rows outside both windows are ignored.
+pub fn summarize_failures(
+    metrics: &[HourlyCorridorMetrics],
+    window: &str,
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+) -> FailureBreakdown {
+    let previous_start = window_start - (window_end - window_start);
+
+    let mut current = WindowTotals::default();
+    let mut previous = WindowTotals::default();
+    for metric in metrics {
+        if metric.hour_bucket >= window_start && metric.hour_bucket <= window_end {
+            current.add(metric);
+        } else if metric.hour_bucket >= previous_start && metric.hour_bucket < window_start {
+            previous.add(metric);
+        }
+    }
+
+    let mut reasons: Vec<FailureReasonStat> = current
+        .reasons
+        .iter()
+        .map(|(code, &count)| {
+            let previous_count = previous.reasons.get(code).copied().unwrap_or(0);
+            FailureReasonStat {
+                result_code: code.clone(),
+                count,
+                share_pct: percentage(count, current.failed),
+                previous_count,
+                change_pct: (previous_count > 0)
+                    .then(|| (count - previous_count) as f64 / previous_count as f64 * 100.0),
+            }
+        })
+        .collect();
+
+    // Codes that stopped occurring still matter when reading the trend
+    for (code, &previous_count) in &previous.reasons {
+        if !current.reasons.contains_key(code) {
+            reasons.push(FailureReasonStat {
+                result_code: code.clone(),
+                count: 0,
+                share_pct: 0.0,
+                previous_count,
+                change_pct: Some(-100.0),
+            });
+        }
+    }
+    reasons.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then(b.previous_count.cmp(&a.previous_count))
+            .then_with(|| a.result_code.cmp(&b.result_code))
+    });
+
+    FailureBreakdown {
+        window: window.to_string(),
+        window_start,
+        window_end,
+        total_transactions: current.total,
+        failed_transactions: current.failed,
+        success_rate: if current.total > 0 {
+            percentage(current.total - current.failed, current.total)
+        } else {
+            0.0
+        },
+        previous_total_transactions: previous.total,
+        previous_failed_transactions: previous.failed,
+        previous_success_rate: (previous.total > 0)
+            .then(|| percentage(previous.total - previous.failed, previous.total)),
+        reasons,
+    }
+}
+
+#[derive(Default)]
+struct WindowTotals {
+    total: i64,
+    failed: i64,
+    reasons: BTreeMap<String, i64>,
+}
+
+impl WindowTotals {
+    fn add(&mut self, metric: &HourlyCorridorMetrics) {
+        self.total += metric.total_transactions;
+        self.failed += metric.failed_transactions;
+
+        // Hours aggregated before result codes were recorded only have a count
+        let attributed: i64 = metric.failure_breakdown.values().sum();
+        for (code, count) in &metric.failure_breakdown {
+            *self.reasons.entry(code.clone()).or_default() += count;
+        }
+        if metric.failed_transactions > attributed {
+            *self.reasons.entry("unknown".to_string()).or_default() +=
+                metric.failed_transactions - attributed;
+        }
+    }
+}
+
+fn percentage(part: i64, whole: i64) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64 * 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::distribution::CorridorSketches;
+
+    fn hour(hours_ago: i64, failures: &[(&str, i64)], total: i64) -> HourlyCorridorMetrics {
+        let failed: i64 = failures.iter().map(|(_, count)| count).sum();
+        HourlyCorridorMetrics {
+            id: format!("h{}", hours_ago),
+            corridor_key: "USDC:issuer1->XLM:native".to_string(),
+            asset_a_code: "USDC".to_string(),
+            asset_a_issuer: "issuer1".to_string(),
+            asset_b_code: "XLM".to_string(),
+            asset_b_issuer: "native".to_string(),
+            hour_bucket: now() - Duration::hours(hours_ago),
+            total_transactions: total,
+            successful_transactions: total - failed,
+            failed_transactions: failed,
+            success_rate: 0.0,
+            volume_usd: 0.0,
+            avg_slippage_bps: 0.0,
+            avg_fx_rate: None,
+            avg_settlement_latency_ms: None,
+            p50_settlement_latency_ms: None,
+            p90_settlement_latency_ms: None,
+            p99_settlement_latency_ms: None,
+            liquidity_depth_usd: 0.0,
+            failure_breakdown: failures
+                .iter()
+                .map(|(code, count)| (code.to_string(), *count))
+                .collect(),
+            sketches: CorridorSketches::default(),
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_window() {
+        assert_eq!(parse_window("24h"), Some(Duration::hours(24)));
+        assert_eq!(parse_window("7d"), Some(Duration::days(7)));
+        assert_eq!(parse_window("0h"), None);
+        assert_eq!(parse_window("5m"), None);
+        assert_eq!(parse_window("365d"), None);
+        assert_eq!(parse_window(""), None);
+        assert_eq!(parse_window("99999999999999d"), None);
+        assert_eq!(parse_window("99999999999999999h"), None);
+        assert_eq!(parse_window("1é"), None);
+        assert_eq!(parse_window("é"), None);
+    }
+
+    #[test]
+    fn test_summarize_failures_with_trend() {
+        let metrics = vec![
+            // Current window: the last 6 hours
+            hour(1, &[("op_underfunded", 6), ("op_no_trust", 2)], 100),
+            hour(3, &[("op_underfunded", 4)], 100),
+            // Previous window
+            hour(7, &[("op_underfunded", 5), ("tx_bad_seq", 3)], 100),
+            // Older than both windows
+            hour(20, &[("op_line_full", 50)], 100),
+        ];
+
+        let summary = summarize_failures(&metrics, "6h", now() - Duration::hours(6), now());
+
+        assert_eq!(summary.total_transactions, 200);
+        assert_eq!(summary.failed_transactions, 12);
+        assert_eq!(summary.success_rate, 94.0);
+        assert_eq!(summary.previous_failed_transactions, 8);
+        assert_eq!(summary.previous_success_rate, Some(92.0));
+
+        let codes: Vec<&str> = summary
+            .reasons
+            .iter()
+            .map(|r| r.result_code.as_str())
+            .collect();
+        assert_eq!(codes, vec!["op_underfunded", "op_no_trust", "tx_bad_seq"]);
+
+        let underfunded = &summary.reasons[0];
+        assert_eq!(underfunded.count, 10);
+        assert_eq!(underfunded.previous_count, 5);
+        assert_eq!(underfunded.change_pct, Some(100.0));
+        assert!((underfunded.share_pct - 83.333).abs() < 0.01);
+
+        assert_eq!(summary.reasons[1].change_pct, None);
+        assert_eq!(summary.reasons[2].count, 0);
+        assert_eq!(summary.reasons[2].change_pct, Some(-100.0));
+    }
+
+    #[test]
+    fn test_unattributed_failures_are_unknown() {
+        let mut legacy = hour(1, &[], 10);
+        legacy.failed_transactions = 3;
+
+        let summary = summarize_failures(&[legacy], "24h", now() - Duration::hours(24), now());
+        assert_eq!(summary.reasons.len(), 1);
+        assert_eq!(summary.reasons[0].result_code, "unknown");
+        assert_eq!(summary.reasons[0].count, 3);
+        assert_eq!(summary.previous_success_rate, None);
+    }
+}
@@ -1,10 +1,8 @@
 use anyhow::Result;
-use chrono::{DateTime, Utc};
 use sqlx::{Pool, Sqlite};
 use tracing::{info, warn};
 
 use crate::models::{FeeBumpStats, FeeBumpTransaction};
-use crate::rpc::HorizonTransaction; // Changed from StellarRpcClient as we process data structs
 
 pub struct FeeBumpTrackerService {
     pool: Pool<Sqlite>,
@@ -15,63 +13,23 @@ impl FeeBumpTrackerService {
         Self { pool }
     }
 
-    /// Process a batch of transactions and persist fee bump transactions
-    pub async fn process_transactions(&self, transactions: &[HorizonTransaction]) -> Result<u64> {
+    /// Persist fee bump transactions already decoded from ledger close meta
+    pub async fn record_fee_bumps(&self, fee_bumps: &[FeeBumpTransaction]) -> Result<u64> {
         let mut count = 0;
 
-        for tx in transactions {
-            if let Some(fee_bump) = &tx.fee_bump_transaction {
-                if let Some(inner) = &tx.inner_transaction {
-                    // Extract data safely
-                    let fee_charged = tx
-                        .fee_charged
-                        .as_ref()
-                        .and_then(|f| f.parse::<i64>().ok())
-                        .unwrap_or(0);
-
-                    let max_fee = tx
-                        .max_fee
-                        .as_ref()
-                        .and_then(|f| f.parse::<i64>().ok())
-                        .unwrap_or(0);
-
-                    let inner_max_fee = inner
-                        .max_fee
-                        .as_ref()
-                        .and_then(|f| f.parse::<i64>().ok())
-                        .unwrap_or(0);
-
-                    // Parse created_at
-                    let created_at = DateTime::parse_from_rfc3339(&tx.created_at)
-                        .map(|dt| dt.with_timezone(&Utc))
-                        .unwrap_or_else(|_| Utc::now());
-
-                    let fee_bump_tx = FeeBumpTransaction {
-                        transaction_hash: tx.hash.clone(),
-                        ledger_sequence: tx.ledger as i64,
-                        fee_source: tx
-                            .fee_account
-                            .clone()
-                            .unwrap_or_else(|| tx.source_account.clone()),
-                        fee_charged,
-                        max_fee,
-                        inner_transaction_hash: inner.hash.clone(),
-                        inner_max_fee,
-                        signatures_count: fee_bump.signatures.len() as i32,
-                        created_at,
-                    };
-
-                    if let Err(e) = self.persist_fee_bump(&fee_bump_tx).await {
-                        warn!("Failed to persist fee bump transaction {}: {}", tx.hash, e);
-                    } else {
-                        count += 1;
-                    }
-                }
+        for fee_bump in fee_bumps {
+            if let Err(e) = self.persist_fee_bump(fee_bump).await {
+                warn!(
+                    "Failed to persist fee bump transaction {}: {}",
+                    fee_bump.transaction_hash, e
+                );
+            } else {
+                count += 1;
             }
         }
 
         if count > 0 {
-            info!("Processed {} fee bump transactions", count);
+            info!("Recorded {} fee bump transactions", count);
         }
 
         Ok(count)
@@ -1,11 +1,12 @@
 use anyhow::{Context, Result};
 use chrono::DateTime;
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::database::Database;
 use crate::models::PaymentRecord;
 use crate::rpc::StellarRpcClient;
+use crate::services::settlement_latency::SettlementLatencyService;
 
 pub struct IndexingService {
     rpc_client: Arc<StellarRpcClient>,
@@ -17,8 +18,9 @@ impl IndexingService {
         Self { rpc_client, db }
     }
 
-    /// Run payment ingestion starting from the last saved cursor
-    pub async fn run_payment_ingestion(&self) -> Result<()> {
+    /// Run payment ingestion starting from the last saved cursor, returning
+    /// how many payments were ingested
+    pub async fn run_payment_ingestion(&self) -> Result<usize> {
         let task_name = "payment_ingestion";
         let last_cursor = self.db.get_ingestion_cursor(task_name).await?;
 
@@ -36,7 +38,7 @@ impl IndexingService {
 
         if payments.is_empty() {
             info!("No new payments to ingest");
-            return Ok(());
+            return Ok(0);
         }
 
         let last_paging_token = payments.last().map(|p| p.paging_token.clone());
@@ -50,6 +52,48 @@ impl IndexingService {
                     .ok()?
                     .with_timezone(&chrono::Utc);
 
+                // Path payments report the delivered asset in asset_*, and
+                // what the sender paid in source_asset_*
+                let is_path_payment = p
+                    .operation_type
+                    .as_deref()
+                    .is_some_and(|t| t.starts_with("path_payment"));
+                let (source_asset_code, source_asset_issuer, source_amount) = if is_path_payment {
+                    (
+                        p.source_asset_code
+                            .clone()
+                            .unwrap_or_else(|| "XLM".to_string()),
+                        p.source_asset_issuer
+                            .clone()
+                            .unwrap_or_else(|| "native".to_string()),
+                        p.source_amount.as_deref().and_then(|a| a.parse().ok()),
+                    )
+                } else {
+                    (
+                        p.asset_code.clone().unwrap_or_else(|| "XLM".to_string()),
+                        p.asset_issuer
+                            .clone()
+                            .unwrap_or_else(|| "native".to_string()),
+                        None,
+                    )
+                };
+                let path = (!p.path.is_empty()).then(|| {
+                    let keys: Vec<String> = p
+                        .path
+                        .iter()
+                        .map(|asset| match (&asset.asset_code, &asset.asset_issuer) {
+                            (Some(code), Some(issuer)) => format!("{}:{}", code, issuer),
+                            _ => "XLM:native".to_string(),
+                        })
+                        .collect();
+                    serde_json::to_string(&keys).unwrap_or_default()
+                });
+
+                let send_max = p.source_max.as_deref().and_then(|a| a.parse().ok());
+                let dest_min = p.destination_min.as_deref().and_then(|a| a.parse().ok());
+                let failure_reason = p.failure_reason();
+                let submission_time = p.submission_time();
+
                 Some(PaymentRecord {
                     id: p.id,
                     transaction_hash: p.transaction_hash,
@@ -58,15 +102,24 @@ impl IndexingService {
                     asset_type: p.asset_type.clone(),
                     asset_code: p.asset_code.clone(),
                     asset_issuer: p.asset_issuer.clone(),
-                    source_asset_code: p.asset_code.clone().unwrap_or_default(),
-                    source_asset_issuer: p.asset_issuer.clone().unwrap_or_default(),
-                    destination_asset_code: p.asset_code.unwrap_or_default(),
-                    destination_asset_issuer: p.asset_issuer.unwrap_or_default(),
+                    source_asset_code,
+                    source_asset_issuer,
+                    destination_asset_code: p.asset_code.unwrap_or_else(|| "XLM".to_string()),
+                    destination_asset_issuer: p
+                        .asset_issuer
+                        .unwrap_or_else(|| "native".to_string()),
                     amount,
-                    successful: true,
+                    operation_type: p.operation_type,
+                    source_amount,
+                    send_max,
+                    dest_min,
+                    path,
+                    successful: p.transaction_successful,
+                    result_code: Some(failure_reason.unwrap_or_else(|| "op_success".to_string())),
                     timestamp: Some(created_at),
-                    submission_time: None,
-                    confirmation_time: None,
+                    submission_time,
+                    // Horizon's created_at is the ledger close time
+                    confirmation_time: Some(created_at),
                     created_at,
                 })
             })
@@ -80,6 +133,13 @@ impl IndexingService {
             .await
             .context("Failed to save payments to database")?;
 
+        if let Err(e) = SettlementLatencyService::new(self.db.pool().clone())
+            .reconcile()
+            .await
+        {
+            warn!("Failed to time payments from anchor transactions: {}", e);
+        }
+
         // Update cursor
         if let Some(cursor) = last_paging_token {
             self.db
@@ -89,6 +149,6 @@ impl IndexingService {
             info!("Ingested {} payments. New cursor: {}", count, cursor);
         }
 
-        Ok(())
+        Ok(count)
     }
 }
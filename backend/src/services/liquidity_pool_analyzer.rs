@@ -5,26 +5,29 @@ use std::sync::Arc;
 use tracing::info;
 
 use crate::models::{LiquidityPool, LiquidityPoolSnapshot, LiquidityPoolStats};
-use crate::rpc::StellarRpcClient;
+use crate::rpc::LedgerSource;
 
 pub struct LiquidityPoolAnalyzer {
     pool: Pool<Sqlite>,
-    rpc_client: Arc<StellarRpcClient>,
+    ledger_source: Arc<dyn LedgerSource>,
 }
 
 impl LiquidityPoolAnalyzer {
-    pub fn new(pool: Pool<Sqlite>, rpc_client: Arc<StellarRpcClient>) -> Self {
-        Self { pool, rpc_client }
+    pub fn new(pool: Pool<Sqlite>, ledger_source: Arc<dyn LedgerSource>) -> Self {
+        Self {
+            pool,
+            ledger_source,
+        }
     }
 
     // ========================================================================
-    // Sync from Horizon
+    // Sync from the ledger source
     // ========================================================================
 
-    /// Fetch liquidity pools from Horizon and upsert into the database.
+    /// Fetch liquidity pools from the ledger source and upsert into the database.
     /// Returns the number of pools synced.
     pub async fn sync_pools(&self) -> Result<u64> {
-        let horizon_pools = self.rpc_client.fetch_liquidity_pools(50, None).await?;
+        let horizon_pools = self.ledger_source.fetch_liquidity_pools(50, None).await?;
         let mut count = 0u64;
 
         for hp in &horizon_pools {
@@ -38,7 +41,7 @@ impl LiquidityPoolAnalyzer {
 
             // Compute volume from recent trades
             let trades = self
-                .rpc_client
+                .ledger_source
                 .fetch_pool_trades(&hp.id, 100)
                 .await
                 .unwrap_or_default();
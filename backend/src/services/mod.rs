@@ -1,13 +1,23 @@
 pub mod account_merge_detector;
 pub mod aggregation;
 pub mod analytics;
+pub mod anchor_prober;
+pub mod anchor_reliability;
+pub mod failure_analysis;
 pub mod contract;
+pub mod distribution;
 pub mod fee_bump_tracker;
 pub mod indexing;
 pub mod liquidity_pool_analyzer;
+pub mod order_book_depth;
+pub mod path_finder;
 pub mod price_feed;
 pub mod realtime_broadcaster;
+pub mod route_estimator;
+pub mod settlement_latency;
 pub mod snapshot;
+pub mod snapshot_scheduler;
+pub mod stellar_toml;
 pub mod trustline_analyzer;
 
 #[cfg(test)]
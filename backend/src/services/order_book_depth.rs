@@ -0,0 +1,445 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use crate::models::corridor::Corridor;
+use crate::rpc::{Asset, OrderBook, OrderBookEntry, StellarRpcClient};
+use crate::services::price_feed::PriceFeedClient;
+
+/// Slippage thresholds every corridor is quoted at, in basis points
+pub const QUOTE_BPS: [u32; 4] = [10, 50, 100, 200];
+
+/// Order book levels fetched per side, Horizon's maximum
+const ORDER_BOOK_LIMIT: u32 = 200;
+
+/// Corridors snapshotted per run, by volume over the last day
+const SNAPSHOT_CORRIDORS: i64 = 20;
+
+const BPS: f64 = 10_000.0;
+
+/// A point on a cumulative depth curve
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DepthPoint {
+    /// Level price, in counter units per base unit
+    #[schema(example = 0.995)]
+    pub price: f64,
+    /// Distance from the mid price, in basis points
+    #[schema(example = 50.0)]
+    pub bps_from_mid: f64,
+    /// USD value offered at this level and every better one
+    #[schema(example = 125000.0)]
+    pub cumulative_usd: f64,
+}
+
+/// How much can be traded before the average execution price slips past a
+/// threshold from the mid price
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SlippageQuote {
+    #[schema(example = 50)]
+    pub slippage_bps: u32,
+    /// USD value of the base asset that can be sold into the bids
+    #[schema(example = 100500.0)]
+    pub sell_base_usd: f64,
+    /// USD value of the counter asset that can be spent on the asks
+    #[schema(example = 120600.0)]
+    pub buy_base_usd: f64,
+}
+
+/// Depth curves and slippage quotes for a corridor's order book, with the
+/// corridor's asset A as the base
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrderBookDepth {
+    /// Normalized corridor identifier
+    #[schema(example = "USDC:GISSUER->XLM:native")]
+    pub corridor_key: String,
+    /// Midpoint of the best bid and ask, in counter units per base unit
+    pub mid_price: f64,
+    pub base_usd_price: f64,
+    pub counter_usd_price: f64,
+    /// Bid side, best price first
+    pub bids: Vec<DepthPoint>,
+    /// Ask side, best price first
+    pub asks: Vec<DepthPoint>,
+    /// Quotes at each of `QUOTE_BPS`
+    pub quotes: Vec<SlippageQuote>,
+    pub captured_at: DateTime<Utc>,
+}
+
+impl OrderBookDepth {
+    pub fn bid_depth_usd(&self) -> f64 {
+        self.bids.last().map_or(0.0, |p| p.cumulative_usd)
+    }
+
+    pub fn ask_depth_usd(&self) -> f64 {
+        self.asks.last().map_or(0.0, |p| p.cumulative_usd)
+    }
+
+    /// USD offered on both sides within `bps` of the mid price
+    pub fn depth_within_bps(&self, bps: f64) -> f64 {
+        let side = |points: &[DepthPoint]| {
+            points
+                .iter()
+                .take_while(|p| p.bps_from_mid <= bps)
+                .last()
+                .map_or(0.0, |p| p.cumulative_usd)
+        };
+        side(&self.bids) + side(&self.asks)
+    }
+}
+
+/// A stored depth snapshot
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DepthSnapshot {
+    pub corridor_key: String,
+    pub mid_price: f64,
+    pub base_usd_price: f64,
+    pub counter_usd_price: f64,
+    pub bid_depth_usd: f64,
+    pub ask_depth_usd: f64,
+    pub quotes: Vec<SlippageQuote>,
+    pub snapshot_at: DateTime<Utc>,
+}
+
+/// Price and base units of each parseable level
+fn parse_levels(entries: &[OrderBookEntry]) -> Vec<(f64, f64)> {
+    entries
+        .iter()
+        .filter_map(|e| Some((e.price.parse().ok()?, e.amount.parse().ok()?)))
+        .filter(|&(price, amount): &(f64, f64)| price > 0.0 && amount > 0.0)
+        .collect()
+}
+
+/// Fills levels best first while the average price stays at or below
+/// `limit`, returning the base units bought and the counter units spent.
+/// Bids are walked with negated prices and limit.
+fn fill_within(levels: &[(f64, f64)], limit: f64) -> (f64, f64) {
+    let (mut base, mut counter) = (0.0, 0.0);
+    for &(price, amount) in levels {
+        if price <= limit {
+            base += amount;
+            counter += price * amount;
+            continue;
+        }
+        // Part of this level brings the average up to the limit exactly
+        let partial = ((limit * base - counter) / (price - limit)).clamp(0.0, amount);
+        base += partial;
+        counter += price * partial;
+        break;
+    }
+    (base, counter)
+}
+
+fn cumulative(
+    levels: &[(f64, f64)],
+    mid_price: f64,
+    usd_per_unit: impl Fn(f64, f64) -> f64,
+) -> Vec<DepthPoint> {
+    let mut total = 0.0;
+    levels
+        .iter()
+        .map(|&(price, amount)| {
+            total += usd_per_unit(price, amount);
+            DepthPoint {
+                price,
+                bps_from_mid: ((price - mid_price).abs() / mid_price) * BPS,
+                cumulative_usd: total,
+            }
+        })
+        .collect()
+}
+
+/// Builds depth curves and slippage quotes from a Horizon order book. Asks
+/// offer base units and bids counter units. When only one asset has a USD
+/// price the other is derived through the mid price. `None` for a one-sided
+/// book or when neither asset is priced.
+pub fn depth_from_order_book(
+    corridor_key: &str,
+    order_book: &OrderBook,
+    base_usd: Option<f64>,
+    counter_usd: Option<f64>,
+    captured_at: DateTime<Utc>,
+) -> Option<OrderBookDepth> {
+    // Bids as (price, base units)
+    let bids: Vec<(f64, f64)> = parse_levels(&order_book.bids)
+        .into_iter()
+        .map(|(price, counter)| (price, counter / price))
+        .collect();
+    let asks = parse_levels(&order_book.asks);
+    let mid_price = (bids.first()?.0 + asks.first()?.0) / 2.0;
+
+    let (base_usd, counter_usd) = match (base_usd, counter_usd) {
+        (Some(base), Some(counter)) => (base, counter),
+        (Some(base), None) => (base, base / mid_price),
+        (None, Some(counter)) => (counter * mid_price, counter),
+        (None, None) => return None,
+    };
+
+    let negated_bids: Vec<(f64, f64)> = bids.iter().map(|&(p, a)| (-p, a)).collect();
+    let quotes = QUOTE_BPS
+        .iter()
+        .map(|&bps| {
+            let slippage = bps as f64 / BPS;
+            let (sold, _) = fill_within(&negated_bids, -mid_price * (1.0 - slippage));
+            let (_, spent) = fill_within(&asks, mid_price * (1.0 + slippage));
+            SlippageQuote {
+                slippage_bps: bps,
+                sell_base_usd: sold * base_usd,
+                buy_base_usd: spent * counter_usd,
+            }
+        })
+        .collect();
+
+    Some(OrderBookDepth {
+        corridor_key: corridor_key.to_string(),
+        mid_price,
+        base_usd_price: base_usd,
+        counter_usd_price: counter_usd,
+        bids: cumulative(&bids, mid_price, |price, base| price * base * counter_usd),
+        asks: cumulative(&asks, mid_price, |_, base| base * base_usd),
+        quotes,
+        captured_at,
+    })
+}
+
+/// Prices corridor order books and keeps periodic depth snapshots
+pub struct OrderBookDepthService {
+    pool: SqlitePool,
+    rpc_client: Arc<StellarRpcClient>,
+    price_feed: Arc<PriceFeedClient>,
+}
+
+impl OrderBookDepthService {
+    pub fn new(
+        pool: SqlitePool,
+        rpc_client: Arc<StellarRpcClient>,
+        price_feed: Arc<PriceFeedClient>,
+    ) -> Self {
+        Self {
+            pool,
+            rpc_client,
+            price_feed,
+        }
+    }
+
+    /// Live depth for a corridor; `None` when the book is one-sided or
+    /// neither asset has a USD price
+    pub async fn fetch_depth(&self, corridor: &Corridor) -> Result<Option<OrderBookDepth>> {
+        let base = format!("{}:{}", corridor.asset_a_code, corridor.asset_a_issuer);
+        let counter = format!("{}:{}", corridor.asset_b_code, corridor.asset_b_issuer);
+
+        let order_book = self
+            .rpc_client
+            .fetch_order_book(
+                &Asset::from_code_and_issuer(&corridor.asset_a_code, &corridor.asset_a_issuer),
+                &Asset::from_code_and_issuer(&corridor.asset_b_code, &corridor.asset_b_issuer),
+                ORDER_BOOK_LIMIT,
+            )
+            .await
+            .with_context(|| format!("Failed to fetch order book for {}->{}", base, counter))?;
+
+        Ok(depth_from_order_book(
+            &corridor.to_string_key(),
+            &order_book,
+            self.price_feed.get_price(&base).await.ok(),
+            self.price_feed.get_price(&counter).await.ok(),
+            Utc::now(),
+        ))
+    }
+
+    /// Snapshots the depth of the corridors with the most volume over the last day
+    pub async fn take_snapshots(&self) -> Result<u64> {
+        let corridor_keys: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT corridor_key FROM corridor_metrics_hourly
+            WHERE hour_bucket >= $1
+            GROUP BY corridor_key
+            ORDER BY SUM(volume_usd) DESC
+            LIMIT $2
+            "#,
+        )
+        .bind((Utc::now() - Duration::hours(24)).to_rfc3339())
+        .bind(SNAPSHOT_CORRIDORS)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch corridors to snapshot")?;
+
+        let mut count = 0u64;
+        for (corridor_key,) in corridor_keys {
+            let Some(corridor) = Corridor::from_key(&corridor_key) else {
+                continue;
+            };
+            match self.fetch_depth(&corridor).await {
+                Ok(Some(depth)) => {
+                    self.store_snapshot(&depth).await?;
+                    count += 1;
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Skipping depth snapshot for {}: {}", corridor_key, e),
+            }
+        }
+
+        if count > 0 {
+            info!("Created {} order book depth snapshots", count);
+        }
+        Ok(count)
+    }
+
+    pub async fn store_snapshot(&self, depth: &OrderBookDepth) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO order_book_depth_snapshots (
+                corridor_key, mid_price, base_usd_price, counter_usd_price,
+                bid_depth_usd, ask_depth_usd, quotes, snapshot_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(&depth.corridor_key)
+        .bind(depth.mid_price)
+        .bind(depth.base_usd_price)
+        .bind(depth.counter_usd_price)
+        .bind(depth.bid_depth_usd())
+        .bind(depth.ask_depth_usd())
+        .bind(serde_json::to_string(&depth.quotes)?)
+        .bind(depth.captured_at)
+        .execute(&self.pool)
+        .await
+        .context("Failed to store order book depth snapshot")?;
+
+        Ok(())
+    }
+
+    /// A corridor's snapshots since `since`, oldest first
+    pub async fn get_snapshots(
+        &self,
+        corridor_key: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<DepthSnapshot>> {
+        let rows: Vec<DepthSnapshotRow> = sqlx::query_as(
+            r#"
+            SELECT corridor_key, mid_price, base_usd_price, counter_usd_price,
+                   bid_depth_usd, ask_depth_usd, quotes, snapshot_at
+            FROM order_book_depth_snapshots
+            WHERE corridor_key = $1 AND snapshot_at >= $2
+            ORDER BY snapshot_at ASC
+            "#,
+        )
+        .bind(corridor_key)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch order book depth snapshots")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DepthSnapshot {
+                corridor_key: row.corridor_key,
+                mid_price: row.mid_price,
+                base_usd_price: row.base_usd_price,
+                counter_usd_price: row.counter_usd_price,
+                bid_depth_usd: row.bid_depth_usd,
+                ask_depth_usd: row.ask_depth_usd,
+                quotes: serde_json::from_str(&row.quotes).unwrap_or_default(),
+                snapshot_at: row.snapshot_at,
+            })
+            .collect())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct DepthSnapshotRow {
+    corridor_key: String,
+    mid_price: f64,
+    base_usd_price: f64,
+    counter_usd_price: f64,
+    bid_depth_usd: f64,
+    ask_depth_usd: f64,
+    quotes: String,
+    snapshot_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::Price;
+
+    fn entry(price: &str, amount: &str) -> OrderBookEntry {
+        OrderBookEntry {
+            price: price.to_string(),
+            amount: amount.to_string(),
+            price_r: Price { n: 1, d: 1 },
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "{} is not {}",
+            actual,
+            expected
+        );
+    }
+
+    /// Bids offer counter units, asks base units; the spread is 80 bps
+    fn order_book() -> OrderBook {
+        let xlm = Asset::from_code_and_issuer("XLM", "native");
+        OrderBook {
+            bids: vec![entry("0.996", "996"), entry("0.99", "1980")],
+            asks: vec![
+                entry("1.004", "1000"),
+                entry("1.008", "2000"),
+                entry("1.016", "4000"),
+            ],
+            base: xlm.clone(),
+            counter: xlm,
+        }
+    }
+
+    #[test]
+    fn test_depth_curves() {
+        let depth =
+            depth_from_order_book("A->B", &order_book(), Some(2.0), Some(1.0), Utc::now()).unwrap();
+        assert_close(depth.mid_price, 1.0);
+
+        assert_close(depth.bids[0].bps_from_mid, 40.0);
+        assert_close(depth.bids[1].cumulative_usd, 2_976.0);
+        assert_close(depth.asks[2].bps_from_mid, 160.0);
+        assert_close(depth.ask_depth_usd(), 14_000.0);
+        assert_close(depth.depth_within_bps(90.0), 996.0 + 6_000.0);
+    }
+
+    #[test]
+    fn test_slippage_quotes() {
+        let depth =
+            depth_from_order_book("A->B", &order_book(), Some(1.0), None, Utc::now()).unwrap();
+        assert_close(depth.counter_usd_price, 1.0);
+        let quotes = &depth.quotes;
+        assert_eq!(
+            quotes.iter().map(|q| q.slippage_bps).collect::<Vec<_>>(),
+            QUOTE_BPS
+        );
+
+        // The spread alone is wider than 10 bps
+        assert_close(quotes[0].sell_base_usd, 0.0);
+        assert_close(quotes[0].buy_base_usd, 0.0);
+        // The best level, then enough of the next to average out at the limit
+        assert_close(quotes[1].sell_base_usd, 1_200.0);
+        assert_close(quotes[1].buy_base_usd, 1_004.0 + 1.008 / 0.003);
+        assert_close(quotes[2].buy_base_usd, 3_020.0 + 1.016 * 10.0 / 0.006);
+        // Every bid stays within 200 bps
+        assert_close(quotes[3].sell_base_usd, 3_000.0);
+    }
+
+    #[test]
+    fn test_unpriced_or_one_sided_books() {
+        assert!(depth_from_order_book("A->B", &order_book(), None, None, Utc::now()).is_none());
+
+        let mut one_sided = order_book();
+        one_sided.bids.clear();
+        assert!(depth_from_order_book("A->B", &one_sided, Some(1.0), None, Utc::now()).is_none());
+    }
+}
@@ -0,0 +1,550 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use crate::models::corridor::Corridor;
+use crate::models::LiquidityPool;
+use crate::rpc::StellarRpcClient;
+use crate::services::route_estimator::{normalize_asset, parse_asset, PoolReserves, RouteVenues};
+
+/// Most intermediate assets a Stellar path payment can route through
+pub const MAX_PATH_LENGTH: usize = 5;
+
+/// Partial paths kept per asset at each hop; more find rarer routes at the
+/// cost of slower searches
+const STATES_PER_ASSET: usize = 4;
+
+/// Conversions tried per search before it settles for the paths found so
+/// far, bounding searches of large, densely connected graphs
+pub const MAX_PATHS_EXPLORED: usize = 20_000;
+
+/// Order book levels fetched per pair, Horizon's maximum
+const ORDER_BOOK_LIMIT: u32 = 200;
+
+/// Asset pairs whose order books are loaded into the graph
+const ORDER_BOOK_PAIRS: i64 = 50;
+
+/// One conversion along a path
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PathHop {
+    #[schema(example = "BRL:GISSUER")]
+    pub source_asset: String,
+    #[schema(example = "USDC:GISSUER")]
+    pub destination_asset: String,
+    pub source_amount: f64,
+    pub destination_amount: f64,
+}
+
+/// A route between two assets with the amounts it is expected to move
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PathQuote {
+    #[schema(example = "BRL:GISSUER")]
+    pub source_asset: String,
+    pub source_amount: f64,
+    #[schema(example = "NGN:GISSUER")]
+    pub destination_asset: String,
+    pub destination_amount: f64,
+    /// Intermediate assets, as in a path payment's `path`
+    pub path: Vec<String>,
+    pub hops: Vec<PathHop>,
+}
+
+impl PathQuote {
+    fn from_hops(hops: Vec<PathHop>) -> Option<Self> {
+        let first = hops.first()?;
+        let last = hops.last()?;
+        Some(Self {
+            source_asset: first.source_asset.clone(),
+            source_amount: first.source_amount,
+            destination_asset: last.destination_asset.clone(),
+            destination_amount: last.destination_amount,
+            path: hops[1..].iter().map(|h| h.source_asset.clone()).collect(),
+            hops,
+        })
+    }
+}
+
+/// Directed graph of assets, with the order book offers and liquidity pools
+/// that convert between each pair as its edges
+#[derive(Debug, Clone, Default)]
+pub struct AssetGraph {
+    edges: HashMap<String, HashMap<String, RouteVenues>>,
+}
+
+impl AssetGraph {
+    fn edge_mut(&mut self, source: &str, destination: &str) -> &mut RouteVenues {
+        self.edges
+            .entry(normalize_asset(source))
+            .or_default()
+            .entry(normalize_asset(destination))
+            .or_default()
+    }
+
+    /// Offers taking `source` for `destination`, as
+    /// `RouteVenues::offers_from_order_book` builds them
+    pub fn add_offers(&mut self, source: &str, destination: &str, offers: Vec<(f64, f64)>) {
+        if !offers.is_empty() {
+            self.edge_mut(source, destination).offers = offers;
+        }
+    }
+
+    /// Adds a pool in both directions
+    pub fn add_pool(&mut self, pool: &LiquidityPool) {
+        let a = normalize_asset(&format!(
+            "{}:{}",
+            pool.reserve_a_asset_code,
+            pool.reserve_a_asset_issuer.as_deref().unwrap_or("native")
+        ));
+        let b = normalize_asset(&format!(
+            "{}:{}",
+            pool.reserve_b_asset_code,
+            pool.reserve_b_asset_issuer.as_deref().unwrap_or("native")
+        ));
+        for (source, destination) in [(&a, &b), (&b, &a)] {
+            if let Some(reserves) = PoolReserves::from_pool(pool, source) {
+                self.edge_mut(source, destination).pools.push(reserves);
+            }
+        }
+    }
+
+    pub fn edge(&self, source: &str, destination: &str) -> Option<&RouteVenues> {
+        self.edges
+            .get(&normalize_asset(source))?
+            .get(&normalize_asset(destination))
+    }
+
+    pub fn asset_count(&self) -> usize {
+        self.edges
+            .iter()
+            .flat_map(|(source, out)| std::iter::once(source).chain(out.keys()))
+            .collect::<BTreeSet<_>>()
+            .len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.values().map(HashMap::len).sum()
+    }
+
+    /// Paths sending exactly `source_amount`, most received first
+    pub fn strict_send(
+        &self,
+        source: &str,
+        source_amount: f64,
+        destination: &str,
+        limit: usize,
+    ) -> Vec<PathQuote> {
+        self.strict_send_within(
+            source,
+            source_amount,
+            destination,
+            limit,
+            MAX_PATHS_EXPLORED,
+        )
+    }
+
+    fn strict_send_within(
+        &self,
+        source: &str,
+        source_amount: f64,
+        destination: &str,
+        limit: usize,
+        max_explored: usize,
+    ) -> Vec<PathQuote> {
+        let source = normalize_asset(source);
+        let destination = normalize_asset(destination);
+
+        let mut found = Vec::new();
+        // Partial paths by the asset they reached, with the amount held there
+        let mut frontier: Vec<(String, f64, Vec<PathHop>)> =
+            vec![(source, source_amount, Vec::new())];
+        let mut explored = 0;
+        'search: for _ in 0..=MAX_PATH_LENGTH {
+            let mut reached: HashMap<String, Vec<(f64, Vec<PathHop>)>> = HashMap::new();
+            for (asset, amount, hops) in &frontier {
+                let Some(out) = self.edges.get(asset) else {
+                    continue;
+                };
+                for (next, venues) in out {
+                    if hops.iter().any(|h| &h.source_asset == next) {
+                        continue;
+                    }
+                    if explored == max_explored {
+                        break 'search;
+                    }
+                    explored += 1;
+                    let fill = venues.fill(*amount);
+                    // Every unit has to get through
+                    if fill.source_amount < amount * (1.0 - 1e-9) || fill.destination_amount <= 0.0
+                    {
+                        continue;
+                    }
+                    let mut hops = hops.clone();
+                    hops.push(PathHop {
+                        source_asset: asset.clone(),
+                        destination_asset: next.clone(),
+                        source_amount: *amount,
+                        destination_amount: fill.destination_amount,
+                    });
+                    if *next == destination {
+                        found.extend(PathQuote::from_hops(hops));
+                    } else {
+                        reached
+                            .entry(next.clone())
+                            .or_default()
+                            .push((fill.destination_amount, hops));
+                    }
+                }
+            }
+
+            frontier = reached
+                .into_iter()
+                .flat_map(|(asset, mut states)| {
+                    states.sort_by(|a, b| b.0.total_cmp(&a.0));
+                    states.truncate(STATES_PER_ASSET);
+                    states
+                        .into_iter()
+                        .map(move |(amount, hops)| (asset.clone(), amount, hops))
+                })
+                .collect();
+        }
+
+        found.sort_by(|a, b| b.destination_amount.total_cmp(&a.destination_amount));
+        found.truncate(limit);
+        found
+    }
+
+    /// Paths delivering exactly `destination_amount`, least sent first
+    pub fn strict_receive(
+        &self,
+        source: &str,
+        destination: &str,
+        destination_amount: f64,
+        limit: usize,
+    ) -> Vec<PathQuote> {
+        self.strict_receive_within(
+            source,
+            destination,
+            destination_amount,
+            limit,
+            MAX_PATHS_EXPLORED,
+        )
+    }
+
+    fn strict_receive_within(
+        &self,
+        source: &str,
+        destination: &str,
+        destination_amount: f64,
+        limit: usize,
+        max_explored: usize,
+    ) -> Vec<PathQuote> {
+        let source = normalize_asset(source);
+        let destination = normalize_asset(destination);
+
+        let mut incoming: HashMap<&str, Vec<(&str, &RouteVenues)>> = HashMap::new();
+        for (from, out) in &self.edges {
+            for (to, venues) in out {
+                incoming.entry(to).or_default().push((from, venues));
+            }
+        }
+
+        let mut found = Vec::new();
+        // Partial paths by the asset they start from, with the amount needed there
+        let mut frontier: Vec<(String, f64, Vec<PathHop>)> =
+            vec![(destination, destination_amount, Vec::new())];
+        let mut explored = 0;
+        'search: for _ in 0..=MAX_PATH_LENGTH {
+            let mut reached: HashMap<String, Vec<(f64, Vec<PathHop>)>> = HashMap::new();
+            for (asset, amount, hops) in &frontier {
+                let Some(into) = incoming.get(asset.as_str()) else {
+                    continue;
+                };
+                for &(previous, venues) in into {
+                    if hops.iter().any(|h| h.destination_asset == previous) {
+                        continue;
+                    }
+                    if explored == max_explored {
+                        break 'search;
+                    }
+                    explored += 1;
+                    let Some(needed) = venues.source_needed(*amount) else {
+                        continue;
+                    };
+                    let mut hops = hops.clone();
+                    hops.insert(
+                        0,
+                        PathHop {
+                            source_asset: previous.to_string(),
+                            destination_asset: asset.clone(),
+                            source_amount: needed,
+                            destination_amount: *amount,
+                        },
+                    );
+                    if previous == source {
+                        found.extend(PathQuote::from_hops(hops));
+                    } else {
+                        reached
+                            .entry(previous.to_string())
+                            .or_default()
+                            .push((needed, hops));
+                    }
+                }
+            }
+
+            frontier = reached
+                .into_iter()
+                .flat_map(|(asset, mut states)| {
+                    states.sort_by(|a, b| a.0.total_cmp(&b.0));
+                    states.truncate(STATES_PER_ASSET);
+                    states
+                        .into_iter()
+                        .map(move |(amount, hops)| (asset.clone(), amount, hops))
+                })
+                .collect();
+        }
+
+        found.sort_by(|a, b| a.source_amount.total_cmp(&b.source_amount));
+        found.truncate(limit);
+        found
+    }
+}
+
+/// Keeps an in-memory asset graph of order books and liquidity pools, so
+/// paths are found locally rather than through Horizon's path endpoints
+pub struct PathFinder {
+    pool: SqlitePool,
+    rpc_client: Arc<StellarRpcClient>,
+    graph: RwLock<Arc<AssetGraph>>,
+    built_at: RwLock<Option<DateTime<Utc>>>,
+}
+
+impl PathFinder {
+    pub fn new(pool: SqlitePool, rpc_client: Arc<StellarRpcClient>) -> Self {
+        Self {
+            pool,
+            rpc_client,
+            graph: RwLock::new(Arc::new(AssetGraph::default())),
+            built_at: RwLock::new(None),
+        }
+    }
+
+    /// The current graph and when it was built
+    pub async fn graph(&self) -> (Arc<AssetGraph>, Option<DateTime<Utc>>) {
+        (
+            Arc::clone(&*self.graph.read().await),
+            *self.built_at.read().await,
+        )
+    }
+
+    /// Replaces the graph for what-if queries, e.g. with a pool removed
+    pub async fn set_graph(&self, graph: AssetGraph) {
+        *self.graph.write().await = Arc::new(graph);
+        *self.built_at.write().await = Some(Utc::now());
+    }
+
+    /// Rebuilds the graph from the stored liquidity pools and the order books
+    /// of every pool pair and the busiest corridors. Returns the edge count.
+    pub async fn refresh(&self) -> Result<usize> {
+        let pools = sqlx::query_as::<_, LiquidityPool>("SELECT * FROM liquidity_pools")
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to fetch liquidity pools")?;
+        let corridor_keys: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT corridor_key FROM corridor_metrics_hourly
+            WHERE hour_bucket >= $1
+            GROUP BY corridor_key
+            ORDER BY SUM(volume_usd) DESC
+            LIMIT $2
+            "#,
+        )
+        .bind((Utc::now() - Duration::hours(24)).to_rfc3339())
+        .bind(ORDER_BOOK_PAIRS)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch corridors for the path graph")?;
+
+        let mut graph = AssetGraph::default();
+        for pool in &pools {
+            graph.add_pool(pool);
+        }
+
+        let mut pairs: BTreeSet<(String, String)> = corridor_keys
+            .iter()
+            .filter_map(|(key,)| Corridor::from_key(key))
+            .map(|c| {
+                (
+                    normalize_asset(&format!("{}:{}", c.asset_a_code, c.asset_a_issuer)),
+                    normalize_asset(&format!("{}:{}", c.asset_b_code, c.asset_b_issuer)),
+                )
+            })
+            .collect();
+        for (source, out) in &graph.edges {
+            for destination in out.keys() {
+                if source < destination {
+                    pairs.insert((source.clone(), destination.clone()));
+                }
+            }
+        }
+
+        for (a, b) in &pairs {
+            for (source, destination) in [(a, b), (b, a)] {
+                let (Some(selling), Some(buying)) = (parse_asset(source), parse_asset(destination))
+                else {
+                    continue;
+                };
+                match self
+                    .rpc_client
+                    .fetch_order_book(&selling, &buying, ORDER_BOOK_LIMIT)
+                    .await
+                {
+                    Ok(order_book) => graph.add_offers(
+                        source,
+                        destination,
+                        RouteVenues::offers_from_order_book(&order_book),
+                    ),
+                    Err(e) => warn!(
+                        "Failed to fetch order book for {}->{}: {}",
+                        source, destination, e
+                    ),
+                }
+            }
+        }
+
+        let edges = graph.edge_count();
+        info!(
+            "Path graph rebuilt with {} assets and {} edges",
+            graph.asset_count(),
+            edges
+        );
+        self.set_graph(graph).await;
+        Ok(edges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(pool_id: &str, a: &str, b: &str, reserve_a: f64, reserve_b: f64) -> LiquidityPool {
+        let now = Utc::now();
+        let (a_code, a_issuer) = a.split_once(':').unwrap();
+        let (b_code, b_issuer) = b.split_once(':').unwrap();
+        LiquidityPool {
+            pool_id: pool_id.to_string(),
+            pool_type: "constant_product".to_string(),
+            fee_bp: 30,
+            total_trustlines: 0,
+            total_shares: "0".to_string(),
+            reserve_a_asset_code: a_code.to_string(),
+            reserve_a_asset_issuer: Some(a_issuer.to_string()),
+            reserve_a_amount: reserve_a,
+            reserve_b_asset_code: b_code.to_string(),
+            reserve_b_asset_issuer: Some(b_issuer.to_string()),
+            reserve_b_amount: reserve_b,
+            total_value_usd: 0.0,
+            volume_24h_usd: 0.0,
+            fees_earned_24h_usd: 0.0,
+            apy: 0.0,
+            impermanent_loss_pct: 0.0,
+            trade_count_24h: 0,
+            last_synced_at: now,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// A thin direct BRL/NGN pool and a deep route through USDC
+    fn graph() -> AssetGraph {
+        let mut graph = AssetGraph::default();
+        graph.add_pool(&pool("direct", "BRL:G1", "NGN:G2", 100.0, 100.0));
+        graph.add_pool(&pool("brl-usdc", "BRL:G1", "USDC:G3", 10_000.0, 10_000.0));
+        graph.add_pool(&pool("usdc-ngn", "USDC:G3", "NGN:G2", 10_000.0, 10_000.0));
+        graph.add_offers("BRL:G1", "USDC:G3", vec![(1.0, 10.0)]);
+        graph
+    }
+
+    #[test]
+    fn test_strict_send_ranks_paths() {
+        let paths = graph().strict_send("BRL:G1", 50.0, "NGN:G2", 5);
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0].path, vec!["USDC:G3".to_string()]);
+        assert!(paths[0].destination_amount > paths[1].destination_amount);
+        assert!(paths[1].path.is_empty());
+
+        // Hops chain into each other
+        let hops = &paths[0].hops;
+        assert_eq!(hops[0].destination_amount, hops[1].source_amount);
+        assert_eq!(paths[0].destination_amount, hops[1].destination_amount);
+
+        assert_eq!(graph().strict_send("BRL:G1", 50.0, "NGN:G2", 1).len(), 1);
+        assert!(graph().strict_send("BRL:G1", 50.0, "EUR:G4", 5).is_empty());
+    }
+
+    #[test]
+    fn test_strict_receive_inverts_strict_send() {
+        let sent = &graph().strict_send("BRL:G1", 50.0, "NGN:G2", 1)[0];
+        let received = &graph().strict_receive("BRL:G1", "NGN:G2", sent.destination_amount, 5)[0];
+        assert_eq!(received.path, sent.path);
+        assert!((received.source_amount - 50.0).abs() < 1e-6);
+        assert_eq!(
+            received.hops[0].destination_amount,
+            received.hops[1].source_amount
+        );
+
+        // The thin direct pool can't deliver this much at all
+        let paths = graph().strict_receive("BRL:G1", "NGN:G2", 1_000.0, 5);
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].path, vec!["USDC:G3".to_string()]);
+    }
+
+    #[test]
+    fn test_paths_stop_at_five_intermediate_assets() {
+        let mut graph = AssetGraph::default();
+        for hop in 0..7 {
+            graph.add_pool(&pool(
+                &format!("pool-{}", hop),
+                &format!("A{}:G", hop),
+                &format!("A{}:G", hop + 1),
+                1_000.0,
+                1_000.0,
+            ));
+        }
+
+        let longest = graph.strict_send("A0:G", 1.0, "A6:G", 5);
+        assert_eq!(longest.len(), 1);
+        assert_eq!(longest[0].path.len(), MAX_PATH_LENGTH);
+        assert!(graph.strict_send("A0:G", 1.0, "A7:G", 5).is_empty());
+        assert!(graph.strict_receive("A0:G", "A7:G", 0.5, 5).is_empty());
+        assert_eq!(graph.asset_count(), 8);
+        assert_eq!(graph.edge_count(), 14);
+    }
+
+    #[test]
+    fn test_searches_stop_after_max_paths_explored() {
+        // Two tries reach NGN directly and USDC, leaving none to carry on
+        // from USDC
+        assert!(graph()
+            .strict_send_within("BRL:G1", 50.0, "NGN:G2", 5, 0)
+            .is_empty());
+        assert_eq!(
+            graph()
+                .strict_send_within("BRL:G1", 50.0, "NGN:G2", 5, 2)
+                .len(),
+            1
+        );
+        assert_eq!(
+            graph()
+                .strict_receive_within("BRL:G1", "NGN:G2", 10.0, 5, 2)
+                .len(),
+            1
+        );
+        assert_eq!(graph().strict_send("BRL:G1", 50.0, "NGN:G2", 5).len(), 2);
+    }
+}
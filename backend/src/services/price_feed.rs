@@ -197,6 +197,15 @@ impl PriceFeedClient {
 
         info!("Initialized price feed client with provider: {}", provider.name());
 
+        Self::with_provider(provider, config, asset_mapping)
+    }
+
+    /// Create a price feed client backed by a specific provider
+    pub fn with_provider(
+        provider: Arc<dyn PriceFeedProvider>,
+        config: PriceFeedConfig,
+        asset_mapping: HashMap<String, String>,
+    ) -> Self {
         Self {
             provider,
             cache: Arc::new(RwLock::new(HashMap::new())),
@@ -203,7 +203,12 @@ impl RealtimeBroadcaster {
                         volume_usd: 0.0,
                         avg_settlement_latency_ms: None,
                         median_settlement_latency_ms: None,
+                        p90_settlement_latency_ms: None,
+                        p99_settlement_latency_ms: None,
                         liquidity_depth_usd: 0.0,
+                        avg_fx_rate: None,
+                        avg_slippage_bps: None,
+                        failure_breakdown: Default::default(),
                         created_at: now,
                         updated_at: now,
                     };
@@ -383,6 +388,7 @@ impl WsMessage {
                     name: "unknown".to_string(),
                     reliability_score: anchor.reliability_score,
                     status: anchor.status.as_str().to_string(),
+                    uptime_percentage: anchor.uptime_percentage,
                 }
             }
             BroadcastMessage::NewPayment { payment, .. } => {
@@ -422,7 +428,6 @@ mod tests {
         let _rpc_client = Arc::new(StellarRpcClient::new(
             "test".to_string(),
             "test".to_string(),
-            true,
         ));
         // Note: This test would need a mock CacheManager
         // let cache = Arc::new(CacheManager::new_mock());
@@ -0,0 +1,623 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tracing::warn;
+use utoipa::ToSchema;
+
+use crate::models::corridor::Corridor;
+use crate::models::LiquidityPool;
+use crate::rpc::{Asset, OrderBook, StellarRpcClient};
+use crate::services::price_feed::PriceFeedClient;
+
+/// Order book levels fetched per route, Horizon's maximum
+const ORDER_BOOK_LIMIT: u32 = 200;
+
+/// Bisection steps when solving for a clearing rate
+const BISECTION_STEPS: usize = 100;
+
+/// Times the amount sent may double while searching for enough to deliver a
+/// strict-receive amount, before pools drained towards their reserves give up
+const MAX_DOUBLINGS: usize = 64;
+
+const BPS: f64 = 10_000.0;
+
+/// Constant-product pool reserves, oriented from the asset sent to the asset
+/// received
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolReserves {
+    pub pool_id: String,
+    pub reserve_in: f64,
+    pub reserve_out: f64,
+    pub fee_bp: i32,
+}
+
+impl PoolReserves {
+    /// Orients a stored pool for sending `source`; `None` when the pool
+    /// doesn't hold it or is empty
+    pub fn from_pool(pool: &LiquidityPool, source: &str) -> Option<Self> {
+        let a = asset_key(
+            &pool.reserve_a_asset_code,
+            pool.reserve_a_asset_issuer.as_deref(),
+        );
+        let b = asset_key(
+            &pool.reserve_b_asset_code,
+            pool.reserve_b_asset_issuer.as_deref(),
+        );
+        let source = normalize_asset(source);
+        let (reserve_in, reserve_out) = if a == source {
+            (pool.reserve_a_amount, pool.reserve_b_amount)
+        } else if b == source {
+            (pool.reserve_b_amount, pool.reserve_a_amount)
+        } else {
+            return None;
+        };
+
+        (reserve_in > 0.0 && reserve_out > 0.0 && pool.fee_bp < BPS as i32).then(|| Self {
+            pool_id: pool.pool_id.clone(),
+            reserve_in,
+            reserve_out,
+            fee_bp: pool.fee_bp,
+        })
+    }
+
+    /// Share of the amount sent left after the pool fee
+    fn fee_factor(&self) -> f64 {
+        1.0 - self.fee_bp as f64 / BPS
+    }
+
+    /// Amount received for `amount_in`, after the pool fee
+    pub fn amount_out(&self, amount_in: f64) -> f64 {
+        let effective_in = amount_in * self.fee_factor();
+        self.reserve_out * effective_in / (self.reserve_in + effective_in)
+    }
+
+    /// Rate received for the next unit once `amount_in` has been sent
+    pub fn marginal_rate(&self, amount_in: f64) -> f64 {
+        let gamma = self.fee_factor();
+        let depth = self.reserve_in + amount_in * gamma;
+        self.reserve_out * self.reserve_in * gamma / (depth * depth)
+    }
+
+    /// Amount that can be sent before the marginal rate falls to `rate`
+    fn amount_in_at_rate(&self, rate: f64) -> f64 {
+        if rate <= 0.0 {
+            return f64::INFINITY;
+        }
+        let gamma = self.fee_factor();
+        let depth = (self.reserve_out * self.reserve_in * gamma / rate).sqrt();
+        ((depth - self.reserve_in) / gamma).max(0.0)
+    }
+}
+
+/// Amount routed through one venue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct VenueFill {
+    /// `order_book` or `liquidity_pool`
+    #[schema(example = "liquidity_pool")]
+    pub venue: String,
+    /// Set for liquidity pools
+    pub pool_id: Option<String>,
+    pub source_amount: f64,
+    pub destination_amount: f64,
+}
+
+/// Best split of an amount across the venues between two assets
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteFill {
+    /// Amount sent; less than requested when every venue ran dry
+    pub source_amount: f64,
+    pub destination_amount: f64,
+    pub fills: Vec<VenueFill>,
+}
+
+/// Order book offers and liquidity pools that take one asset for another
+#[derive(Debug, Clone, Default)]
+pub struct RouteVenues {
+    /// Offers as (destination units per source unit, source units taken),
+    /// best rate first
+    pub offers: Vec<(f64, f64)>,
+    pub pools: Vec<PoolReserves>,
+}
+
+impl RouteVenues {
+    /// Offers from a Horizon order book with the source asset as the base.
+    /// Bids buy the base and offer counter units, so their capacity in the
+    /// source asset is the amount over the price.
+    pub fn offers_from_order_book(order_book: &OrderBook) -> Vec<(f64, f64)> {
+        let mut offers: Vec<(f64, f64)> = order_book
+            .bids
+            .iter()
+            .filter_map(|e| Some((e.price.parse::<f64>().ok()?, e.amount.parse::<f64>().ok()?)))
+            .filter(|&(price, amount)| price > 0.0 && amount > 0.0)
+            .map(|(price, amount)| (price, amount / price))
+            .collect();
+        offers.sort_by(|a, b| b.0.total_cmp(&a.0));
+        offers
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offers.is_empty() && self.pools.is_empty()
+    }
+
+    /// Rate of the first unit sent through the best venue
+    pub fn best_rate(&self) -> Option<f64> {
+        self.offers
+            .first()
+            .map(|&(price, _)| price)
+            .into_iter()
+            .chain(self.pools.iter().map(|p| p.marginal_rate(0.0)))
+            .max_by(f64::total_cmp)
+    }
+
+    fn pools_in_at_rate(&self, rate: f64) -> f64 {
+        self.pools.iter().map(|p| p.amount_in_at_rate(rate)).sum()
+    }
+
+    /// Amount that can be sent while every unit gets at least `rate`
+    pub fn capacity_at_rate(&self, rate: f64) -> f64 {
+        let offers: f64 = self
+            .offers
+            .iter()
+            .take_while(|&&(price, _)| price >= rate)
+            .map(|&(_, capacity)| capacity)
+            .sum();
+        offers + self.pools_in_at_rate(rate)
+    }
+
+    /// Amount that can be sent before the marginal rate slips `bps` below
+    /// the best rate
+    pub fn depth_within_bps(&self, bps: f64) -> f64 {
+        self.best_rate()
+            .map_or(0.0, |best| self.capacity_at_rate(best * (1.0 - bps / BPS)))
+    }
+
+    /// Splits `amount` across the venues to receive as much as possible,
+    /// which sends each unit to whichever venue pays the highest marginal
+    /// rate until every venue's rate has fallen to the same clearing rate
+    pub fn fill(&self, amount: f64) -> RouteFill {
+        let mut upper = self.best_rate().unwrap_or(0.0);
+        let mut offers_taken = 0.0;
+        for (level, &(price, capacity)) in self.offers.iter().enumerate() {
+            let pools_in = self.pools_in_at_rate(price);
+            if offers_taken + pools_in >= amount {
+                // Clears between the previous level and this one
+                let rate = self.pools_clearing_rate(amount - offers_taken, price, upper);
+                return self.allocate(level, 0.0, rate, amount - offers_taken);
+            }
+            if offers_taken + capacity + pools_in >= amount {
+                // Clears part way through this level
+                let partial = amount - offers_taken - pools_in;
+                return self.allocate(level, partial, price, pools_in);
+            }
+            offers_taken += capacity;
+            upper = price;
+        }
+
+        if self.pools.is_empty() {
+            return self.allocate(self.offers.len(), 0.0, 0.0, 0.0);
+        }
+        let rate = self.pools_clearing_rate(amount - offers_taken, 0.0, upper);
+        self.allocate(self.offers.len(), 0.0, rate, amount - offers_taken)
+    }
+
+    /// Least amount to send for `destination_amount` to arrive; `None` when
+    /// the venues can't deliver that much
+    pub fn source_needed(&self, destination_amount: f64) -> Option<f64> {
+        let best_rate = self.best_rate()?;
+        let delivers = |amount: f64| self.fill(amount).destination_amount >= destination_amount;
+
+        let mut upper = destination_amount / best_rate;
+        let mut grown = 0;
+        while !delivers(upper) {
+            let fill = self.fill(upper);
+            // Every offer taken and no pool to fall back on
+            if fill.source_amount < upper * (1.0 - 1e-9) || grown == MAX_DOUBLINGS {
+                return None;
+            }
+            upper *= 2.0;
+            grown += 1;
+        }
+
+        let mut lower = 0.0;
+        for _ in 0..BISECTION_STEPS {
+            let amount = (lower + upper) / 2.0;
+            if delivers(amount) {
+                upper = amount;
+            } else {
+                lower = amount;
+            }
+        }
+        Some(upper)
+    }
+
+    /// Rate in `[lower, upper]` at which the pools take `target` between them
+    fn pools_clearing_rate(&self, target: f64, mut lower: f64, mut upper: f64) -> f64 {
+        for _ in 0..BISECTION_STEPS {
+            let rate = (lower + upper) / 2.0;
+            if self.pools_in_at_rate(rate) >= target {
+                lower = rate;
+            } else {
+                upper = rate;
+            }
+        }
+        lower
+    }
+
+    /// Takes every offer before `level`, `partial` of that level, and
+    /// `pools_target` across the pools at their clearing `rate`
+    fn allocate(&self, level: usize, partial: f64, rate: f64, pools_target: f64) -> RouteFill {
+        let mut fills = Vec::new();
+
+        let (mut book_in, mut book_out) = (0.0, 0.0);
+        for &(price, capacity) in &self.offers[..level] {
+            book_in += capacity;
+            book_out += capacity * price;
+        }
+        if let Some(&(price, _)) = self.offers.get(level) {
+            book_in += partial;
+            book_out += partial * price;
+        }
+        if book_in > 0.0 {
+            fills.push(VenueFill {
+                venue: "order_book".to_string(),
+                pool_id: None,
+                source_amount: book_in,
+                destination_amount: book_out,
+            });
+        }
+
+        // Rescaled so the pools take exactly their share despite bisection error
+        let pool_inputs: Vec<f64> = self
+            .pools
+            .iter()
+            .map(|p| p.amount_in_at_rate(rate))
+            .collect();
+        let total: f64 = pool_inputs.iter().sum();
+        for (pool, input) in self.pools.iter().zip(pool_inputs) {
+            let input = if total > 0.0 {
+                input * pools_target / total
+            } else {
+                0.0
+            };
+            if input > 0.0 {
+                fills.push(VenueFill {
+                    venue: "liquidity_pool".to_string(),
+                    pool_id: Some(pool.pool_id.clone()),
+                    source_amount: input,
+                    destination_amount: pool.amount_out(input),
+                });
+            }
+        }
+
+        RouteFill {
+            source_amount: fills.iter().map(|f| f.source_amount).sum(),
+            destination_amount: fills.iter().map(|f| f.destination_amount).sum(),
+            fills,
+        }
+    }
+}
+
+/// Best execution for sending an amount of one asset for another
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RouteEstimate {
+    #[schema(example = "USDC:GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN")]
+    pub source_asset: String,
+    #[schema(example = "XLM:native")]
+    pub destination_asset: String,
+    /// Amount asked to be sent
+    #[schema(example = 1000.0)]
+    pub requested_amount: f64,
+    /// Amount the venues can take, up to the requested amount
+    pub source_amount: f64,
+    pub destination_amount: f64,
+    /// Average rate received, in destination units per source unit
+    pub price: f64,
+    /// Rate of the first unit through the best venue
+    pub best_price: f64,
+    /// How far the average rate falls short of the best one, in basis points
+    pub slippage_bps: f64,
+    /// Whether the whole requested amount can be sent
+    pub fully_filled: bool,
+    pub fills: Vec<VenueFill>,
+}
+
+/// "CODE:ISSUER", with "XLM:native" for lumens
+fn asset_key(code: &str, issuer: Option<&str>) -> String {
+    match issuer {
+        Some(issuer) if issuer != "native" => format!("{}:{}", code, issuer),
+        _ => "XLM:native".to_string(),
+    }
+}
+
+/// Canonical "CODE:ISSUER" form of an asset identifier, with "XLM:native"
+/// for lumens
+pub fn normalize_asset(asset: &str) -> String {
+    match asset.split_once(':') {
+        Some((code, issuer)) => asset_key(code, Some(issuer)),
+        None => asset_key(asset, None),
+    }
+}
+
+/// Horizon asset for a "CODE:ISSUER" or "XLM:native" identifier
+pub fn parse_asset(asset: &str) -> Option<Asset> {
+    if asset == "native" || asset == "XLM:native" {
+        return Some(Asset::from_code_and_issuer("XLM", "native"));
+    }
+    let (code, issuer) = asset.split_once(':')?;
+    (!code.is_empty() && !issuer.is_empty() && issuer != "native")
+        .then(|| Asset::from_code_and_issuer(code, issuer))
+}
+
+/// Estimates execution across order book offers and AMM pools between two
+/// assets, from the live order book and the pools `LiquidityPoolAnalyzer`
+/// keeps in sync
+pub struct RouteEstimator {
+    pool: SqlitePool,
+    rpc_client: Arc<StellarRpcClient>,
+    price_feed: Arc<PriceFeedClient>,
+}
+
+impl RouteEstimator {
+    pub fn new(
+        pool: SqlitePool,
+        rpc_client: Arc<StellarRpcClient>,
+        price_feed: Arc<PriceFeedClient>,
+    ) -> Self {
+        Self {
+            pool,
+            rpc_client,
+            price_feed,
+        }
+    }
+
+    /// Offers and pools taking `source` for `destination`. An unavailable
+    /// order book leaves the pools alone rather than failing the route.
+    pub async fn fetch_venues(&self, source: &str, destination: &str) -> Result<RouteVenues> {
+        let (Some(selling), Some(buying)) = (parse_asset(source), parse_asset(destination)) else {
+            return Ok(RouteVenues::default());
+        };
+
+        let offers = match self
+            .rpc_client
+            .fetch_order_book(&selling, &buying, ORDER_BOOK_LIMIT)
+            .await
+        {
+            Ok(order_book) => RouteVenues::offers_from_order_book(&order_book),
+            Err(e) => {
+                warn!(
+                    "Failed to fetch order book for {}->{}: {}",
+                    source, destination, e
+                );
+                Vec::new()
+            }
+        };
+
+        Ok(RouteVenues {
+            offers,
+            pools: self.fetch_pools(source, destination).await?,
+        })
+    }
+
+    /// Stored pools holding both assets, oriented from `source`
+    pub async fn fetch_pools(&self, source: &str, destination: &str) -> Result<Vec<PoolReserves>> {
+        let (source_code, source_issuer) = asset_parts(source);
+        let (destination_code, destination_issuer) = asset_parts(destination);
+
+        let pools = sqlx::query_as::<_, LiquidityPool>(
+            r#"
+            SELECT * FROM liquidity_pools
+            WHERE (reserve_a_asset_code = $1 AND reserve_a_asset_issuer IS $2
+                   AND reserve_b_asset_code = $3 AND reserve_b_asset_issuer IS $4)
+               OR (reserve_a_asset_code = $3 AND reserve_a_asset_issuer IS $4
+                   AND reserve_b_asset_code = $1 AND reserve_b_asset_issuer IS $2)
+            "#,
+        )
+        .bind(source_code)
+        .bind(source_issuer)
+        .bind(destination_code)
+        .bind(destination_issuer)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch liquidity pools")?;
+
+        Ok(pools
+            .iter()
+            .filter_map(|p| PoolReserves::from_pool(p, source))
+            .collect())
+    }
+
+    /// Best execution for sending `amount` of `source`; `None` when no offer
+    /// or pool takes it
+    pub async fn estimate(
+        &self,
+        source: &str,
+        destination: &str,
+        amount: f64,
+    ) -> Result<Option<RouteEstimate>> {
+        let venues = self.fetch_venues(source, destination).await?;
+        let Some(best_price) = venues.best_rate() else {
+            return Ok(None);
+        };
+
+        let fill = venues.fill(amount);
+        let price = if fill.source_amount > 0.0 {
+            fill.destination_amount / fill.source_amount
+        } else {
+            best_price
+        };
+
+        Ok(Some(RouteEstimate {
+            source_asset: normalize_asset(source),
+            destination_asset: normalize_asset(destination),
+            requested_amount: amount,
+            source_amount: fill.source_amount,
+            destination_amount: fill.destination_amount,
+            price,
+            best_price,
+            slippage_bps: ((1.0 - price / best_price) * BPS).max(0.0),
+            fully_filled: fill.source_amount >= amount * (1.0 - 1e-9),
+            fills: fill.fills,
+        }))
+    }
+
+    /// USD that can be sent each way through the corridor before the rate
+    /// slips `bps` below the best one; `None` when neither direction has a
+    /// venue and a USD price
+    pub async fn liquidity_depth_usd(&self, corridor: &Corridor, bps: f64) -> Result<Option<f64>> {
+        let a = asset_key(&corridor.asset_a_code, Some(&corridor.asset_a_issuer));
+        let b = asset_key(&corridor.asset_b_code, Some(&corridor.asset_b_issuer));
+
+        let mut total = None;
+        for (source, destination) in [(&a, &b), (&b, &a)] {
+            let venues = self.fetch_venues(source, destination).await?;
+            let Some(best_rate) = venues.best_rate() else {
+                continue;
+            };
+            let depth = venues.depth_within_bps(bps);
+            let usd = match self.price_feed.get_price(source).await {
+                Ok(price) => depth * price,
+                Err(_) => match self.price_feed.get_price(destination).await {
+                    Ok(price) => depth * best_rate * price,
+                    Err(_) => continue,
+                },
+            };
+            *total.get_or_insert(0.0) += usd;
+        }
+
+        Ok(total)
+    }
+}
+
+/// Code and issuer as `liquidity_pools` stores them, with no issuer for lumens
+fn asset_parts(asset: &str) -> (String, Option<String>) {
+    match normalize_asset(asset).split_once(':') {
+        Some((code, "native")) => (code.to_string(), None),
+        Some((code, issuer)) => (code.to_string(), Some(issuer.to_string())),
+        None => (asset.to_string(), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "{} is not {}",
+            actual,
+            expected
+        );
+    }
+
+    /// 1000 of each asset with the usual 30 bps fee
+    fn pool() -> PoolReserves {
+        PoolReserves {
+            pool_id: "pool".to_string(),
+            reserve_in: 1_000.0,
+            reserve_out: 1_000.0,
+            fee_bp: 30,
+        }
+    }
+
+    #[test]
+    fn test_pool_math() {
+        let pool = pool();
+        assert_close(pool.amount_out(100.0), 1_000.0 * 99.7 / 1_099.7);
+        assert_close(pool.marginal_rate(0.0), 0.997);
+        let input = pool.amount_in_at_rate(0.95);
+        assert_close(pool.marginal_rate(input), 0.95);
+        assert_eq!(pool.amount_in_at_rate(1.0), 0.0);
+    }
+
+    #[test]
+    fn test_fill_walks_offers_then_pools() {
+        let venues = RouteVenues {
+            offers: vec![(1.0, 100.0), (0.9, 100.0)],
+            pools: Vec::new(),
+        };
+        let fill = venues.fill(150.0);
+        assert_close(fill.source_amount, 150.0);
+        assert_close(fill.destination_amount, 145.0);
+
+        // Without pools the offers run dry
+        let fill = venues.fill(500.0);
+        assert_close(fill.source_amount, 200.0);
+
+        let venues = RouteVenues {
+            offers: vec![(1.0, 100.0)],
+            pools: vec![pool()],
+        };
+        let fill = venues.fill(200.0);
+        assert_eq!(fill.fills.len(), 2);
+        assert_close(fill.fills[0].destination_amount, 100.0);
+        assert_close(fill.fills[1].source_amount, 100.0);
+        assert_close(fill.destination_amount, 100.0 + pool().amount_out(100.0));
+    }
+
+    #[test]
+    fn test_fill_equalizes_marginal_rates() {
+        let venues = RouteVenues {
+            offers: vec![(1.0, 100.0), (0.95, 1_000.0)],
+            pools: vec![pool()],
+        };
+        let fill = venues.fill(300.0);
+        assert_close(fill.source_amount, 300.0);
+
+        // The pool takes units until its rate matches the partly taken offer
+        let pool_in = fill.fills[1].source_amount;
+        assert_close(pool().marginal_rate(pool_in), 0.95);
+        assert_close(fill.fills[0].source_amount, 300.0 - pool_in);
+        assert_close(
+            fill.destination_amount,
+            100.0 + 0.95 * (200.0 - pool_in) + pool().amount_out(pool_in),
+        );
+    }
+
+    #[test]
+    fn test_source_needed_inverts_fill() {
+        let venues = RouteVenues {
+            offers: vec![(1.0, 100.0), (0.95, 1_000.0)],
+            pools: vec![pool()],
+        };
+        let needed = venues.source_needed(500.0).unwrap();
+        assert!((venues.fill(needed).destination_amount - 500.0).abs() < 1e-6);
+
+        // Pools keep paying out ever less, while offers run dry
+        let pool_only = RouteVenues {
+            offers: Vec::new(),
+            pools: vec![pool()],
+        };
+        let needed = pool_only.source_needed(990.0).unwrap();
+        assert!((pool().amount_out(needed) - 990.0).abs() < 1e-6);
+        let offers_only = RouteVenues {
+            offers: vec![(1.0, 100.0)],
+            pools: Vec::new(),
+        };
+        assert!(offers_only.source_needed(101.0).is_none());
+    }
+
+    #[test]
+    fn test_depth_within_bps() {
+        let venues = RouteVenues {
+            offers: vec![(1.0, 100.0), (0.95, 1_000.0)],
+            pools: vec![pool()],
+        };
+        assert_eq!(venues.best_rate(), Some(1.0));
+        let pool_in = ((1_000_000.0 * 0.997 / 0.99_f64).sqrt() - 1_000.0) / 0.997;
+        assert_close(venues.depth_within_bps(100.0), 100.0 + pool_in);
+        assert_eq!(RouteVenues::default().depth_within_bps(100.0), 0.0);
+    }
+
+    #[test]
+    fn test_asset_identifiers() {
+        assert_eq!(normalize_asset("native"), "XLM:native");
+        assert_eq!(asset_parts("XLM:native"), ("XLM".to_string(), None));
+        assert_eq!(
+            asset_parts("USDC:GISSUER"),
+            ("USDC".to_string(), Some("GISSUER".to_string()))
+        );
+        assert!(parse_asset("USDC").is_none());
+    }
+}
@@ -0,0 +1,374 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tracing::info;
+
+use crate::models::corridor::compute_percentile;
+
+/// Where a payment's settlement clock started
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatencySource {
+    /// The transaction's `valid_after` bound
+    OnChain,
+    /// A SEP-6 deposit or withdrawal's `started_at`
+    Sep6,
+    /// A SEP-24 deposit or withdrawal's `started_at`
+    Sep24,
+    /// A SEP-31 cross-border payment's `started_at`
+    Sep31,
+}
+
+impl LatencySource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LatencySource::OnChain => "onchain",
+            LatencySource::Sep6 => "sep6",
+            LatencySource::Sep24 => "sep24",
+            LatencySource::Sep31 => "sep31",
+        }
+    }
+}
+
+/// Milliseconds from submission to ledger close. Unknown submissions and
+/// negative spans (clock skew between anchor and network) yield `None`.
+pub fn settlement_latency_ms(
+    submission_time: Option<DateTime<Utc>>,
+    confirmation_time: DateTime<Utc>,
+) -> Option<i64> {
+    let latency = (confirmation_time - submission_time?).num_milliseconds();
+    (latency >= 0).then_some(latency)
+}
+
+/// Submission time from a transaction's `valid_after` bound, in unix seconds.
+/// A zero bound means the sender didn't set one.
+pub fn submission_time_from_valid_after(min_time: u64) -> Option<DateTime<Utc>> {
+    if min_time == 0 {
+        return None;
+    }
+    Utc.timestamp_opt(i64::try_from(min_time).ok()?, 0).single()
+}
+
+/// Average and percentile settlement latency over a set of measurements
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LatencySummary {
+    pub samples: usize,
+    pub average_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+}
+
+impl LatencySummary {
+    /// `None` without any measurements
+    pub fn from_samples(mut samples: Vec<i64>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let average_ms = samples.iter().sum::<i64>() as f64 / samples.len() as f64;
+        let mut percentile = |p: f64| compute_percentile(&mut samples, p).unwrap_or(0) as f64;
+
+        Some(Self {
+            average_ms,
+            p50_ms: percentile(50.0),
+            p90_ms: percentile(90.0),
+            p95_ms: percentile(95.0),
+            p99_ms: percentile(99.0),
+            samples: samples.len(),
+        })
+    }
+}
+
+/// An anchor transaction as reported by a SEP-6, SEP-24 or SEP-31 transfer server
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnchorTransactionTiming {
+    pub id: String,
+    pub protocol: LatencySource,
+    pub transfer_server: String,
+    pub kind: Option<String>,
+    pub status: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Hash of the Stellar transaction that moved the funds, once known
+    pub stellar_transaction_id: Option<String>,
+}
+
+/// Reads the transactions out of a SEP-6/SEP-24/SEP-31 `/transaction` or
+/// `/transactions` response body. Entries without an id or a parseable
+/// `started_at` are skipped.
+pub fn anchor_transactions_from_response(
+    protocol: LatencySource,
+    transfer_server: &str,
+    body: &Value,
+) -> Vec<AnchorTransactionTiming> {
+    let entries: Vec<&Value> = match (body.get("transaction"), body.get("transactions")) {
+        (Some(tx), _) => vec![tx],
+        (None, Some(Value::Array(txs))) => txs.iter().collect(),
+        _ => Vec::new(),
+    };
+
+    let text = |tx: &Value, field: &str| {
+        tx.get(field)
+            .and_then(Value::as_str)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    let time = |tx: &Value, field: &str| {
+        text(tx, field)
+            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+            .map(|dt| dt.with_timezone(&Utc))
+    };
+
+    entries
+        .into_iter()
+        .filter_map(|tx| {
+            Some(AnchorTransactionTiming {
+                id: text(tx, "id")?,
+                protocol,
+                transfer_server: transfer_server.trim_end_matches('/').to_string(),
+                kind: text(tx, "kind"),
+                status: text(tx, "status"),
+                started_at: time(tx, "started_at")?,
+                completed_at: time(tx, "completed_at"),
+                stellar_transaction_id: text(tx, "stellar_transaction_id"),
+            })
+        })
+        .collect()
+}
+
+/// Tables holding ingested payments, each with the latency columns
+const PAYMENT_TABLES: [&str; 2] = ["payments", "ledger_payments"];
+
+/// Keeps anchor transactions and times the payments that settle them from
+/// the anchor's start instead of the on-chain submission
+pub struct SettlementLatencyService {
+    pool: SqlitePool,
+}
+
+impl SettlementLatencyService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Records anchor transactions, then re-times any payments they settled with
+    pub async fn record_anchor_transactions(
+        &self,
+        transactions: &[AnchorTransactionTiming],
+    ) -> Result<u64> {
+        for tx in transactions {
+            sqlx::query(
+                r#"
+                INSERT INTO anchor_transactions (
+                    id, protocol, transfer_server, kind, status,
+                    started_at, completed_at, stellar_transaction_id, updated_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, CURRENT_TIMESTAMP)
+                ON CONFLICT (transfer_server, id) DO UPDATE SET
+                    status = excluded.status,
+                    completed_at = COALESCE(excluded.completed_at, completed_at),
+                    stellar_transaction_id = COALESCE(excluded.stellar_transaction_id, stellar_transaction_id),
+                    updated_at = CURRENT_TIMESTAMP
+                "#,
+            )
+            .bind(&tx.id)
+            .bind(tx.protocol.as_str())
+            .bind(&tx.transfer_server)
+            .bind(&tx.kind)
+            .bind(&tx.status)
+            .bind(tx.started_at)
+            .bind(tx.completed_at)
+            .bind(&tx.stellar_transaction_id)
+            .execute(&self.pool)
+            .await
+            .context("Failed to record anchor transaction")?;
+        }
+
+        if transactions
+            .iter()
+            .any(|tx| tx.stellar_transaction_id.is_some())
+        {
+            self.reconcile().await
+        } else {
+            Ok(0)
+        }
+    }
+
+    /// Whether `transfer_server` belongs to a known anchor: the `protocol`
+    /// endpoint of a crawled stellar.toml, or a host under an anchor's home
+    /// domain. Timings from anywhere else could be forged by the caller.
+    pub async fn is_known_transfer_server(
+        &self,
+        protocol: LatencySource,
+        transfer_server: &str,
+    ) -> Result<bool> {
+        let toml_field = match protocol {
+            LatencySource::OnChain => return Ok(false),
+            LatencySource::Sep6 => "transfer_server",
+            LatencySource::Sep24 => "transfer_server_sep0024",
+            LatencySource::Sep31 => "direct_payment_server",
+        };
+        let transfer_server = transfer_server.trim().trim_end_matches('/');
+
+        let in_toml: bool = sqlx::query_scalar(&format!(
+            "SELECT EXISTS (SELECT 1 FROM anchor_stellar_toml WHERE RTRIM({}, '/') = $1)",
+            toml_field
+        ))
+        .bind(transfer_server)
+        .fetch_one(&self.pool)
+        .await
+        .context("Failed to look up stellar.toml transfer servers")?;
+        if in_toml {
+            return Ok(true);
+        }
+
+        let Some(host) = reqwest::Url::parse(transfer_server)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_lowercase))
+        else {
+            return Ok(false);
+        };
+        let home_domains: Vec<String> = sqlx::query_scalar(
+            "SELECT LOWER(home_domain) FROM anchors WHERE home_domain IS NOT NULL AND home_domain != ''",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to look up anchor home domains")?;
+
+        Ok(home_domains
+            .iter()
+            .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain))))
+    }
+
+    /// Re-times payments whose Stellar transaction settles a recorded anchor
+    /// transaction. Payments already timed from an anchor are left alone.
+    pub async fn reconcile(&self) -> Result<u64> {
+        let mut updated = 0;
+
+        for table in PAYMENT_TABLES {
+            let rows: Vec<(i64, DateTime<Utc>, DateTime<Utc>, String)> = sqlx::query_as(&format!(
+                r#"
+                SELECT p.rowid, p.confirmation_time, a.started_at, a.protocol
+                FROM {table} p
+                JOIN anchor_transactions a ON a.stellar_transaction_id = p.transaction_hash
+                WHERE p.confirmation_time IS NOT NULL
+                  AND COALESCE(p.latency_source, 'onchain') = 'onchain'
+                "#
+            ))
+            .fetch_all(&self.pool)
+            .await
+            .with_context(|| format!("Failed to match anchor transactions to {}", table))?;
+
+            for (row_id, confirmation_time, started_at, protocol) in rows {
+                sqlx::query(&format!(
+                    r#"
+                    UPDATE {table}
+                    SET submission_time = $1, settlement_latency_ms = $2, latency_source = $3
+                    WHERE rowid = $4
+                    "#
+                ))
+                .bind(started_at)
+                .bind(settlement_latency_ms(Some(started_at), confirmation_time))
+                .bind(&protocol)
+                .bind(row_id)
+                .execute(&self.pool)
+                .await?;
+                updated += 1;
+            }
+        }
+
+        if updated > 0 {
+            info!("Timed {} payments from their anchor transactions", updated);
+        }
+
+        Ok(updated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_settlement_latency_ms() {
+        let closed = at("2026-01-22T10:30:05Z");
+        assert_eq!(
+            settlement_latency_ms(Some(at("2026-01-22T10:30:00Z")), closed),
+            Some(5_000)
+        );
+        assert_eq!(settlement_latency_ms(None, closed), None);
+        assert_eq!(
+            settlement_latency_ms(Some(at("2026-01-22T10:31:00Z")), closed),
+            None
+        );
+    }
+
+    #[test]
+    fn test_submission_time_from_valid_after() {
+        assert_eq!(submission_time_from_valid_after(0), None);
+        assert_eq!(
+            submission_time_from_valid_after(1_769_077_800),
+            Some(at("2026-01-22T10:30:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_latency_summary() {
+        assert_eq!(LatencySummary::from_samples(Vec::new()), None);
+
+        let summary = LatencySummary::from_samples((1..=20).map(|s| s * 1_000).collect()).unwrap();
+        assert_eq!(summary.samples, 20);
+        assert_eq!(summary.average_ms, 10_500.0);
+        assert_eq!(summary.p50_ms, 10_000.0);
+        assert_eq!(summary.p90_ms, 18_000.0);
+        assert_eq!(summary.p95_ms, 19_000.0);
+        assert_eq!(summary.p99_ms, 20_000.0);
+    }
+
+    #[test]
+    fn test_anchor_transactions_from_response() {
+        let list = json!({
+            "transactions": [
+                {
+                    "id": "82fhs729f63dh0v4",
+                    "kind": "withdrawal",
+                    "status": "completed",
+                    "started_at": "2026-01-22T10:25:00Z",
+                    "completed_at": "2026-01-22T10:31:00Z",
+                    "stellar_transaction_id": "17a670bc424ff5ce3b386dbfaae9990b66a2a37b4fbe51547e8794962a3f9e6a"
+                },
+                { "id": "no-start", "status": "incomplete" }
+            ]
+        });
+        let txs = anchor_transactions_from_response(
+            LatencySource::Sep24,
+            "https://testanchor.stellar.org/sep24/",
+            &list,
+        );
+        assert_eq!(txs.len(), 1);
+        assert_eq!(
+            txs[0].transfer_server,
+            "https://testanchor.stellar.org/sep24"
+        );
+        assert_eq!(txs[0].kind.as_deref(), Some("withdrawal"));
+        assert_eq!(txs[0].started_at, at("2026-01-22T10:25:00Z"));
+
+        let single = json!({
+            "transaction": {
+                "id": "sep31-1",
+                "status": "pending_receiver",
+                "started_at": "2026-01-22T10:25:00Z",
+                "stellar_transaction_id": ""
+            }
+        });
+        let txs = anchor_transactions_from_response(LatencySource::Sep31, "https://a", &single);
+        assert_eq!(txs.len(), 1);
+        assert_eq!(txs[0].protocol, LatencySource::Sep31);
+        assert_eq!(txs[0].stellar_transaction_id, None);
+    }
+}
@@ -1,14 +1,15 @@
 use crate::database::Database;
+use crate::snapshot::encoding;
+use crate::snapshot::merkle::{self, MerkleTree, ProofStep, SiblingPosition};
 use crate::snapshot::schema::{
     AnalyticsSnapshot, SnapshotAnchorMetrics, SnapshotCorridorMetrics, SCHEMA_VERSION,
 };
-use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
-use serde::Serialize;
-use serde_json::{Map, Value};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sha2::{Digest, Sha256};
 use sqlx::Row;
-use std::collections::BTreeMap;
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
@@ -21,6 +22,8 @@ pub struct SnapshotGenerationResult {
     pub snapshot_id: String,
     pub epoch: u64,
     pub hash: String,
+    /// Merkle root over the snapshot's records, the value committed on-chain
+    pub merkle_root: String,
     pub canonical_json: String,
     pub anchor_count: usize,
     pub corridor_count: usize,
@@ -29,13 +32,152 @@ pub struct SnapshotGenerationResult {
     pub timestamp: DateTime<Utc>,
 }
 
+/// Kind of snapshot record a Merkle leaf commits to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapshotLeafKind {
+    Anchor,
+    Corridor,
+}
+
+impl SnapshotLeafKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SnapshotLeafKind::Anchor => "anchor",
+            SnapshotLeafKind::Corridor => "corridor",
+        }
+    }
+}
+
+/// A snapshot record encoded as a Merkle leaf
+///
+/// `data` is the canonical JSON `{"kind":...,"record":{...}}`; its bytes are
+/// what gets hashed into the leaf.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotLeaf {
+    pub kind: SnapshotLeafKind,
+    pub id: Uuid,
+    pub data: String,
+}
+
+/// Everything a third party needs to check one record against the root
+/// committed on-chain for an epoch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotInclusionProof {
+    pub epoch: u64,
+    pub schema_version: u32,
+    pub snapshot_id: String,
+    /// Hex-encoded Merkle root
+    pub merkle_root: String,
+    pub leaf: SnapshotProofLeaf,
+    pub leaf_count: usize,
+    /// Sibling hashes from the leaf up to the root
+    pub siblings: Vec<SnapshotProofStep>,
+    /// The transaction that committed the root, once submitted
+    pub transaction: Option<SnapshotTransaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotProofLeaf {
+    pub kind: SnapshotLeafKind,
+    pub id: Uuid,
+    /// Position among the snapshot's leaves: anchors, then corridors
+    pub index: usize,
+    /// Canonical leaf JSON, hashed as-is
+    pub data: String,
+    /// Hex-encoded leaf hash
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotProofStep {
+    /// Hex-encoded sibling hash
+    pub hash: String,
+    pub position: SiblingPosition,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotTransaction {
+    pub transaction_hash: String,
+    pub ledger: Option<u64>,
+}
+
+/// A stored snapshot with the Merkle root committed for its epoch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommittedSnapshot {
+    pub snapshot_id: String,
+    pub epoch: u64,
+    /// Hex-encoded SHA-256 of the full canonical snapshot
+    pub hash: Option<String>,
+    /// Hex-encoded Merkle root
+    pub merkle_root: String,
+    pub transaction: Option<SnapshotTransaction>,
+    pub snapshot: AnalyticsSnapshot,
+}
+
+/// Result of re-hashing one stored snapshot with its schema version's encoder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredSnapshotCheck {
+    pub snapshot_id: String,
+    pub epoch: Option<u64>,
+    pub schema_version: Option<u32>,
+    /// Hex-encoded hash recomputed from the stored data
+    pub hash: Option<String>,
+    pub hash_matches: bool,
+    /// `None` for snapshots stored without a Merkle root
+    pub merkle_root_matches: Option<bool>,
+    /// Why the snapshot couldn't be re-hashed
+    pub error: Option<String>,
+}
+
+impl StoredSnapshotCheck {
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.hash_matches && self.merkle_root_matches != Some(false)
+    }
+}
+
+impl SnapshotInclusionProof {
+    /// Recompute the leaf hash from its data and fold it up the sibling path,
+    /// checking the result against the Merkle root
+    pub fn verify(&self) -> Result<bool> {
+        let leaf = merkle::leaf_hash(self.leaf.data.as_bytes());
+        if hex::encode(leaf) != self.leaf.hash {
+            return Ok(false);
+        }
+
+        let steps = self
+            .siblings
+            .iter()
+            .map(|step| {
+                Ok(ProofStep {
+                    hash: decode_hash(&step.hash)?,
+                    position: step.position,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(merkle::verify_proof(
+            leaf,
+            &steps,
+            decode_hash(&self.merkle_root)?,
+        ))
+    }
+}
+
+pub(crate) fn decode_hash(hex_hash: &str) -> Result<[u8; 32]> {
+    hex::decode(hex_hash)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| anyhow!("Invalid 32-byte hex hash: {}", hex_hash))
+}
+
 /// Service for creating cryptographically verifiable analytics snapshots
 ///
 /// This service ensures that:
 /// 1. Metrics are aggregated from all data sources
 /// 2. Snapshots are serialized deterministically (same input = same output)
-/// 3. SHA-256 hashes are computed and stored
-/// 4. Hashes are submitted to smart contracts
+/// 3. SHA-256 hashes and Merkle roots over the records are computed and stored
+/// 4. Merkle roots are submitted to smart contracts
 /// 5. Submission success is verified
 pub struct SnapshotService {
     db: Arc<Database>,
@@ -58,17 +200,65 @@ impl SnapshotService {
     /// 2. Serialize to deterministic JSON
     /// 3. Compute SHA-256 hash
     /// 4. Store hash in database
-    /// 5. Submit to smart contract
+    /// 5. Submit the Merkle root to smart contract
     /// 6. Verify submission success
     pub async fn generate_and_submit_snapshot(
         &self,
         epoch: u64,
+    ) -> Result<SnapshotGenerationResult> {
+        let mut result = self.generate_and_store_snapshot(epoch, Utc::now()).await?;
+        let merkle_root = decode_hash(&result.merkle_root)?;
+
+        // Step 5: Submit to smart contract (if configured)
+        let submission_result = if let Some(contract_service) = &self.contract_service {
+            match contract_service.submit_snapshot(merkle_root, epoch).await {
+                Ok(submission) => {
+                    info!(
+                        "Successfully submitted snapshot to contract: {:?}",
+                        submission
+                    );
+                    self.record_submission(&result.snapshot_id, &submission)
+                        .await
+                        .context("Failed to record snapshot submission")?;
+                    Some(submission)
+                }
+                Err(e) => {
+                    error!("Failed to submit snapshot to contract: {}", e);
+                    return Err(e.context("Contract submission failed"));
+                }
+            }
+        } else {
+            warn!("Contract service not configured, skipping on-chain submission");
+            None
+        };
+
+        // Step 6: Verify submission success (if submitted)
+        result.verification_successful = if let Some(ref submission) = submission_result {
+            self.verify_submission_success(&result.merkle_root, epoch, submission)
+                .await
+                .context("Failed to verify submission success")?
+        } else {
+            false
+        };
+        result.submission_result = submission_result;
+
+        Ok(result)
+    }
+
+    /// Generate and store a snapshot as of `now` without submitting it
+    ///
+    /// Steps 1 to 4 of [`Self::generate_and_submit_snapshot`]; the snapshot
+    /// scheduler submits through its queue instead.
+    pub async fn generate_and_store_snapshot(
+        &self,
+        epoch: u64,
+        now: DateTime<Utc>,
     ) -> Result<SnapshotGenerationResult> {
         info!("Starting snapshot generation for epoch {}", epoch);
 
         // Step 1: Aggregate all metrics
         let snapshot = self
-            .aggregate_all_metrics(epoch)
+            .aggregate_all_metrics(epoch, now)
             .await
             .context("Failed to aggregate metrics")?;
 
@@ -88,57 +278,41 @@ impl SnapshotService {
 
         info!("Generated snapshot hash: {}", hash_hex);
 
+        let merkle_root = Self::merkle_root(snapshot.clone())
+            .context("Failed to compute snapshot Merkle root")?;
+        let merkle_root_hex = hex::encode(merkle_root);
+
+        info!("Generated snapshot Merkle root: {}", merkle_root_hex);
+
         // Step 4: Store hash in database
         let snapshot_id = self
-            .store_snapshot_in_database(&snapshot, &hash_hex, &canonical_json)
+            .store_snapshot_in_database(&snapshot, &hash_hex, &merkle_root_hex, &canonical_json)
             .await
             .context("Failed to store snapshot in database")?;
 
         info!("Stored snapshot in database with ID: {}", snapshot_id);
 
-        // Step 5: Submit to smart contract (if configured)
-        let submission_result = if let Some(contract_service) = &self.contract_service {
-            match contract_service.submit_snapshot(hash, epoch).await {
-                Ok(result) => {
-                    info!("Successfully submitted snapshot to contract: {:?}", result);
-                    Some(result)
-                }
-                Err(e) => {
-                    error!("Failed to submit snapshot to contract: {}", e);
-                    return Err(e.context("Contract submission failed"));
-                }
-            }
-        } else {
-            warn!("Contract service not configured, skipping on-chain submission");
-            None
-        };
-
-        // Step 6: Verify submission success (if submitted)
-        let verification_result = if let Some(ref submission) = submission_result {
-            self.verify_submission_success(&hash_hex, epoch, submission)
-                .await
-                .context("Failed to verify submission success")?
-        } else {
-            false
-        };
-
         Ok(SnapshotGenerationResult {
             snapshot_id,
             epoch,
             hash: hash_hex,
+            merkle_root: merkle_root_hex,
             canonical_json,
             anchor_count: snapshot.anchor_metrics.len(),
             corridor_count: snapshot.corridor_metrics.len(),
-            submission_result,
-            verification_successful: verification_result,
+            submission_result: None,
+            verification_successful: false,
             timestamp: snapshot.timestamp,
         })
     }
 
-    /// Aggregate all metrics from the database into a snapshot
-    pub async fn aggregate_all_metrics(&self, epoch: u64) -> Result<AnalyticsSnapshot> {
-        let timestamp = Utc::now();
-        let mut snapshot = AnalyticsSnapshot::new(epoch, timestamp);
+    /// Aggregate all metrics from the database into a snapshot taken at `now`
+    pub async fn aggregate_all_metrics(
+        &self,
+        epoch: u64,
+        now: DateTime<Utc>,
+    ) -> Result<AnalyticsSnapshot> {
+        let mut snapshot = AnalyticsSnapshot::new(epoch, now);
 
         // Aggregate anchor metrics
         let anchor_metrics = self
@@ -152,7 +326,7 @@ impl SnapshotService {
 
         // Aggregate corridor metrics
         let corridor_metrics = self
-            .aggregate_corridor_metrics()
+            .aggregate_corridor_metrics(now)
             .await
             .context("Failed to aggregate corridor metrics")?;
 
@@ -229,8 +403,11 @@ impl SnapshotService {
         Ok(metrics)
     }
 
-    /// Aggregate corridor metrics from database
-    async fn aggregate_corridor_metrics(&self) -> Result<Vec<SnapshotCorridorMetrics>> {
+    /// Each corridor's latest daily rollup from the day before `now` onwards
+    async fn aggregate_corridor_metrics(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<SnapshotCorridorMetrics>> {
         let query = r#"
             SELECT 
                 cm.id,
@@ -247,13 +424,18 @@ impl SnapshotService {
                 cm.avg_settlement_latency_ms,
                 cm.liquidity_depth_usd
             FROM corridor_metrics cm
-            WHERE cm.date >= datetime('now', '-1 day')
-            GROUP BY cm.corridor_key
-            HAVING cm.date = MAX(cm.date)
+            WHERE cm.period = 'daily'
+              AND datetime(cm.date) >= datetime(?)
+              AND cm.date = (
+                  SELECT MAX(latest.date)
+                  FROM corridor_metrics latest
+                  WHERE latest.corridor_key = cm.corridor_key AND latest.period = 'daily'
+              )
             ORDER BY cm.corridor_key
         "#;
 
         let rows = sqlx::query(query)
+            .bind((now - Duration::days(1)).to_rfc3339())
             .fetch_all(self.db.pool())
             .await
             .context("Failed to fetch corridor metrics")?;
@@ -290,14 +472,15 @@ impl SnapshotService {
         &self,
         snapshot: &AnalyticsSnapshot,
         hash: &str,
+        merkle_root: &str,
         canonical_json: &str,
     ) -> Result<String> {
         let snapshot_id = Uuid::new_v4().to_string();
 
         let query = r#"
             INSERT INTO snapshots (
-                id, entity_id, entity_type, data, hash, epoch, timestamp, created_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                id, entity_id, entity_type, data, hash, merkle_root, epoch, timestamp, created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#;
 
         sqlx::query(query)
@@ -306,6 +489,7 @@ impl SnapshotService {
             .bind("analytics_snapshot") // entity_type
             .bind(canonical_json)
             .bind(hash)
+            .bind(merkle_root)
             .bind(snapshot.epoch as i64)
             .bind(snapshot.timestamp)
             .bind(Utc::now())
@@ -316,6 +500,218 @@ impl SnapshotService {
         Ok(snapshot_id)
     }
 
+    /// Record the transaction that committed a stored snapshot's Merkle root
+    pub async fn record_submission(
+        &self,
+        snapshot_id: &str,
+        submission: &SubmissionResult,
+    ) -> Result<()> {
+        sqlx::query("UPDATE snapshots SET transaction_hash = ?, ledger = ? WHERE id = ?")
+            .bind(&submission.transaction_hash)
+            .bind(submission.ledger as i64)
+            .bind(snapshot_id)
+            .execute(self.db.pool())
+            .await
+            .context("Failed to record snapshot submission")?;
+        Ok(())
+    }
+
+    /// The snapshot committed for `epoch`, as stored
+    ///
+    /// Prefers the submitted snapshot when an epoch was generated more than
+    /// once. Returns `None` when there is no Merkle-committed snapshot for the
+    /// epoch.
+    pub async fn get_committed_snapshot(&self, epoch: u64) -> Result<Option<CommittedSnapshot>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, data, hash, merkle_root, transaction_hash, ledger
+            FROM snapshots
+            WHERE entity_type = 'analytics_snapshot'
+              AND epoch = ?
+              AND merkle_root IS NOT NULL
+            ORDER BY transaction_hash IS NULL, created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(epoch as i64)
+        .fetch_optional(self.db.pool())
+        .await
+        .context("Failed to fetch snapshot")?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        Ok(Some(CommittedSnapshot {
+            snapshot_id: row.get("id"),
+            epoch,
+            hash: row.get("hash"),
+            merkle_root: row.get("merkle_root"),
+            transaction: row
+                .get::<Option<String>, _>("transaction_hash")
+                .map(|transaction_hash| SnapshotTransaction {
+                    transaction_hash,
+                    ledger: row.get::<Option<i64>, _>("ledger").map(|l| l as u64),
+                }),
+            snapshot: serde_json::from_str(&row.get::<String, _>("data"))
+                .context("Failed to parse stored snapshot")?,
+        }))
+    }
+
+    /// The snapshot stored for `epoch`, whether or not it was committed
+    ///
+    /// Prefers the submitted snapshot when an epoch was generated more than
+    /// once.
+    pub async fn get_snapshot(&self, epoch: u64) -> Result<Option<AnalyticsSnapshot>> {
+        let data: Option<String> = sqlx::query_scalar(
+            r#"
+            SELECT data
+            FROM snapshots
+            WHERE entity_type = 'analytics_snapshot'
+              AND epoch = ?
+            ORDER BY transaction_hash IS NULL, created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(epoch as i64)
+        .fetch_optional(self.db.pool())
+        .await
+        .context("Failed to fetch snapshot")?;
+
+        data.map(|data| serde_json::from_str(&data).context("Failed to parse stored snapshot"))
+            .transpose()
+    }
+
+    /// Re-hash every stored snapshot with the encoder for its schema version
+    ///
+    /// Each stored hash (and Merkle root, when present) must still match what
+    /// was committed; a mismatch means an encoder changed under snapshots
+    /// already stored with it.
+    pub async fn rehash_stored_snapshots(&self) -> Result<Vec<StoredSnapshotCheck>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, data, hash, merkle_root, epoch
+            FROM snapshots
+            WHERE entity_type = 'analytics_snapshot'
+            ORDER BY epoch, created_at
+            "#,
+        )
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to fetch snapshots")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let mut check = StoredSnapshotCheck {
+                    snapshot_id: row.get("id"),
+                    epoch: row.get::<Option<i64>, _>("epoch").map(|e| e as u64),
+                    schema_version: None,
+                    hash: None,
+                    hash_matches: false,
+                    merkle_root_matches: None,
+                    error: None,
+                };
+
+                let rehashed =
+                    serde_json::from_str::<AnalyticsSnapshot>(&row.get::<String, _>("data"))
+                        .and_then(|snapshot| {
+                            check.schema_version = Some(snapshot.schema_version);
+                            Ok((
+                                Self::hash_snapshot_hex(snapshot.clone())?,
+                                hex::encode(Self::merkle_root(snapshot)?),
+                            ))
+                        });
+
+                match rehashed {
+                    Ok((hash, merkle_root)) => {
+                        check.hash_matches =
+                            row.get::<Option<String>, _>("hash").as_ref() == Some(&hash);
+                        check.merkle_root_matches = row
+                            .get::<Option<String>, _>("merkle_root")
+                            .map(|stored| stored == merkle_root);
+                        check.hash = Some(hash);
+                    }
+                    Err(e) => check.error = Some(e.to_string()),
+                }
+                check
+            })
+            .collect())
+    }
+
+    /// Inclusion proof for one anchor or corridor record of the snapshot
+    /// committed for `epoch`
+    ///
+    /// Returns `None` when there is no Merkle-committed snapshot for the epoch
+    /// or the record isn't in it.
+    pub async fn inclusion_proof(
+        &self,
+        epoch: u64,
+        kind: SnapshotLeafKind,
+        id: Uuid,
+    ) -> Result<Option<SnapshotInclusionProof>> {
+        let Some(CommittedSnapshot {
+            snapshot_id,
+            merkle_root,
+            transaction,
+            snapshot,
+            ..
+        }) = self.get_committed_snapshot(epoch).await?
+        else {
+            return Ok(None);
+        };
+        let schema_version = snapshot.schema_version;
+
+        let leaves = Self::merkle_leaves(snapshot)?;
+        let tree = MerkleTree::from_leaves(
+            &leaves
+                .iter()
+                .map(|leaf| leaf.data.as_bytes())
+                .collect::<Vec<_>>(),
+        );
+        if hex::encode(tree.root()) != merkle_root {
+            bail!(
+                "Snapshot {} for epoch {} no longer matches its Merkle root",
+                snapshot_id,
+                epoch
+            );
+        }
+
+        let Some(index) = leaves
+            .iter()
+            .position(|leaf| leaf.kind == kind && leaf.id == id)
+        else {
+            return Ok(None);
+        };
+        let siblings = tree
+            .proof(index)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|step| SnapshotProofStep {
+                hash: hex::encode(step.hash),
+                position: step.position,
+            })
+            .collect();
+        let leaf = &leaves[index];
+
+        Ok(Some(SnapshotInclusionProof {
+            epoch,
+            schema_version,
+            snapshot_id,
+            merkle_root,
+            leaf: SnapshotProofLeaf {
+                kind,
+                id,
+                index,
+                data: leaf.data.clone(),
+                hash: hex::encode(merkle::leaf_hash(leaf.data.as_bytes())),
+            },
+            leaf_count: tree.leaf_count(),
+            siblings,
+            transaction,
+        }))
+    }
+
     /// Verify that the submission was successful by querying the contract
     async fn verify_submission_success(
         &self,
@@ -362,217 +758,18 @@ impl SnapshotService {
     /// - Floating point numbers are serialized consistently
     /// - No extra whitespace or formatting variations
     ///
+    /// The encoding is the one for the snapshot's own `schema_version`, so
+    /// snapshots of unsupported versions are rejected.
+    ///
     /// # Arguments
     /// * `snapshot` - The analytics snapshot to serialize
     ///
     /// # Returns
     /// A canonical JSON string representation suitable for hashing
     pub fn serialize_deterministically(
-        mut snapshot: AnalyticsSnapshot,
+        snapshot: AnalyticsSnapshot,
     ) -> Result<String, serde_json::Error> {
-        // Normalize the snapshot (sort all arrays by ID)
-        snapshot.normalize();
-
-        // Build a BTreeMap to ensure key ordering
-        let mut map = BTreeMap::new();
-
-        // Add fields in a fixed order
-        map.insert(
-            "schema_version".to_string(),
-            Value::Number(snapshot.schema_version.into()),
-        );
-        map.insert("epoch".to_string(), Value::Number(snapshot.epoch.into()));
-
-        // Serialize timestamp as ISO 8601 string (deterministic format)
-        map.insert(
-            "timestamp".to_string(),
-            Value::String(snapshot.timestamp.to_rfc3339()),
-        );
-
-        // Serialize anchor metrics array (already sorted by normalize())
-        let anchor_metrics: Vec<Value> = snapshot
-            .anchor_metrics
-            .into_iter()
-            .map(|m| Self::serialize_anchor_metrics(&m))
-            .collect();
-        map.insert("anchor_metrics".to_string(), Value::Array(anchor_metrics));
-
-        // Serialize corridor metrics array (already sorted by normalize())
-        let corridor_metrics: Vec<Value> = snapshot
-            .corridor_metrics
-            .into_iter()
-            .map(|m| Self::serialize_corridor_metrics(&m))
-            .collect();
-        map.insert(
-            "corridor_metrics".to_string(),
-            Value::Array(corridor_metrics),
-        );
-
-        // Convert to JSON string with no extra whitespace
-        // Note: serde_json::Map uses IndexMap internally which preserves insertion order.
-        // Since we iterate over BTreeMap (sorted), insertion order is sorted, ensuring determinism.
-        let mut json_map = Map::new();
-        for (k, v) in map {
-            json_map.insert(k, v);
-        }
-        serde_json::to_string(&Value::Object(json_map))
-    }
-
-    /// Serialize anchor metrics to a deterministic JSON value
-    fn serialize_anchor_metrics(metrics: &SnapshotAnchorMetrics) -> Value {
-        let mut map = BTreeMap::new();
-
-        map.insert("id".to_string(), Value::String(metrics.id.to_string()));
-        map.insert("name".to_string(), Value::String(metrics.name.clone()));
-        map.insert(
-            "stellar_account".to_string(),
-            Value::String(metrics.stellar_account.clone()),
-        );
-        map.insert(
-            "success_rate".to_string(),
-            Self::serialize_f64(metrics.success_rate),
-        );
-        map.insert(
-            "failure_rate".to_string(),
-            Self::serialize_f64(metrics.failure_rate),
-        );
-        map.insert(
-            "reliability_score".to_string(),
-            Self::serialize_f64(metrics.reliability_score),
-        );
-        map.insert(
-            "total_transactions".to_string(),
-            Value::Number(metrics.total_transactions.into()),
-        );
-        map.insert(
-            "successful_transactions".to_string(),
-            Value::Number(metrics.successful_transactions.into()),
-        );
-        map.insert(
-            "failed_transactions".to_string(),
-            Value::Number(metrics.failed_transactions.into()),
-        );
-
-        if let Some(ms) = metrics.avg_settlement_time_ms {
-            map.insert(
-                "avg_settlement_time_ms".to_string(),
-                Value::Number(ms.into()),
-            );
-        } else {
-            map.insert("avg_settlement_time_ms".to_string(), Value::Null);
-        }
-
-        if let Some(volume) = metrics.volume_usd {
-            map.insert("volume_usd".to_string(), Self::serialize_f64(volume));
-        } else {
-            map.insert("volume_usd".to_string(), Value::Null);
-        }
-
-        map.insert("status".to_string(), Value::String(metrics.status.clone()));
-
-        // serde_json::Map preserves insertion order (uses IndexMap internally)
-        // Since BTreeMap iteration is sorted, insertion order is sorted
-        let mut json_map = Map::new();
-        for (k, v) in map {
-            json_map.insert(k, v);
-        }
-        Value::Object(json_map)
-    }
-
-    /// Serialize corridor metrics to a deterministic JSON value
-    fn serialize_corridor_metrics(metrics: &SnapshotCorridorMetrics) -> Value {
-        let mut map = BTreeMap::new();
-
-        map.insert("id".to_string(), Value::String(metrics.id.to_string()));
-        map.insert(
-            "corridor_key".to_string(),
-            Value::String(metrics.corridor_key.clone()),
-        );
-        map.insert(
-            "asset_a_code".to_string(),
-            Value::String(metrics.asset_a_code.clone()),
-        );
-        map.insert(
-            "asset_a_issuer".to_string(),
-            Value::String(metrics.asset_a_issuer.clone()),
-        );
-        map.insert(
-            "asset_b_code".to_string(),
-            Value::String(metrics.asset_b_code.clone()),
-        );
-        map.insert(
-            "asset_b_issuer".to_string(),
-            Value::String(metrics.asset_b_issuer.clone()),
-        );
-        map.insert(
-            "total_transactions".to_string(),
-            Value::Number(metrics.total_transactions.into()),
-        );
-        map.insert(
-            "successful_transactions".to_string(),
-            Value::Number(metrics.successful_transactions.into()),
-        );
-        map.insert(
-            "failed_transactions".to_string(),
-            Value::Number(metrics.failed_transactions.into()),
-        );
-        map.insert(
-            "success_rate".to_string(),
-            Self::serialize_f64(metrics.success_rate),
-        );
-        map.insert(
-            "volume_usd".to_string(),
-            Self::serialize_f64(metrics.volume_usd),
-        );
-
-        if let Some(ms) = metrics.avg_settlement_latency_ms {
-            map.insert(
-                "avg_settlement_latency_ms".to_string(),
-                Value::Number(ms.into()),
-            );
-        } else {
-            map.insert("avg_settlement_latency_ms".to_string(), Value::Null);
-        }
-
-        map.insert(
-            "liquidity_depth_usd".to_string(),
-            Self::serialize_f64(metrics.liquidity_depth_usd),
-        );
-
-        // serde_json::Map preserves insertion order (uses IndexMap internally)
-        // Since BTreeMap iteration is sorted, insertion order is sorted
-        let mut json_map = Map::new();
-        for (k, v) in map {
-            json_map.insert(k, v);
-        }
-        Value::Object(json_map)
-    }
-
-    /// Serialize f64 to a deterministic JSON number representation
-    ///
-    /// This ensures that floating point numbers are always serialized
-    /// in the same way. serde_json handles this deterministically,
-    /// but we ensure special cases are handled consistently.
-    fn serialize_f64(value: f64) -> Value {
-        if value.is_finite() {
-            // serde_json::Number::from_f64 uses ryu algorithm which is deterministic
-            serde_json::Number::from_f64(value)
-                .map(Value::Number)
-                .unwrap_or_else(|| {
-                    // Fallback for edge cases (shouldn't happen for normal values)
-                    Value::String(value.to_string())
-                })
-        } else if value.is_nan() {
-            Value::String("NaN".to_string())
-        } else if value.is_infinite() {
-            if value.is_sign_positive() {
-                Value::String("Infinity".to_string())
-            } else {
-                Value::String("-Infinity".to_string())
-            }
-        } else {
-            Value::Null
-        }
+        encoding::to_canonical_json(snapshot)
     }
 
     /// Compute SHA-256 hash of a string and return the bytes
@@ -619,6 +816,56 @@ impl SnapshotService {
         Ok(hex::encode(hash))
     }
 
+    /// Encode each record of the snapshot as a Merkle leaf
+    ///
+    /// Leaves follow the canonical order: anchor metrics sorted by ID, then
+    /// corridor metrics sorted by ID.
+    pub fn merkle_leaves(
+        mut snapshot: AnalyticsSnapshot,
+    ) -> Result<Vec<SnapshotLeaf>, serde_json::Error> {
+        let encoder = encoding::encoder_for(snapshot.schema_version)?;
+        snapshot.normalize();
+
+        let anchors = snapshot
+            .anchor_metrics
+            .iter()
+            .map(|m| (SnapshotLeafKind::Anchor, m.id, encoder.encode_anchor(m)));
+        let corridors = snapshot
+            .corridor_metrics
+            .iter()
+            .map(|m| (SnapshotLeafKind::Corridor, m.id, encoder.encode_corridor(m)));
+
+        anchors
+            .chain(corridors)
+            .map(|(kind, id, record)| {
+                let leaf = encoding::object([
+                    ("kind", Value::String(kind.as_str().to_string())),
+                    ("record", record),
+                ]);
+                Ok(SnapshotLeaf {
+                    kind,
+                    id,
+                    data: serde_json::to_string(&leaf)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Merkle root over the snapshot's records
+    ///
+    /// Unlike [`Self::hash_snapshot`], a single record can be checked against
+    /// this root with its sibling path alone.
+    pub fn merkle_root(snapshot: AnalyticsSnapshot) -> Result<[u8; 32], serde_json::Error> {
+        let leaves = Self::merkle_leaves(snapshot)?;
+        Ok(MerkleTree::from_leaves(
+            &leaves
+                .iter()
+                .map(|leaf| leaf.data.as_bytes())
+                .collect::<Vec<_>>(),
+        )
+        .root())
+    }
+
     /// Create a versioned snapshot with hash
     ///
     /// This method creates a snapshot with the current schema version and
@@ -638,20 +885,20 @@ impl SnapshotService {
         Ok((hash, hash_hex, SCHEMA_VERSION))
     }
 
-    /// Create snapshot, hash it, and submit to on-chain contract
+    /// Create snapshot, compute its Merkle root, and submit to on-chain contract
     ///
     /// This method combines snapshot creation with automatic submission to the
     /// Soroban smart contract. It handles the complete workflow:
-    /// 1. Generate snapshot hash
+    /// 1. Generate snapshot Merkle root
     /// 2. Submit to contract with retry logic
-    /// 3. Return both hash and submission result
+    /// 3. Return both root and submission result
     ///
     /// # Arguments
-    /// * `snapshot` - The analytics snapshot to hash and submit
+    /// * `snapshot` - The analytics snapshot to commit and submit
     /// * `contract_service` - Contract service for blockchain submission
     ///
     /// # Returns
-    /// Tuple of (hash_bytes, hash_hex, schema_version, submission_result)
+    /// Tuple of (root_bytes, root_hex, schema_version, submission_result)
     pub async fn version_hash_and_submit(
         snapshot: AnalyticsSnapshot,
         contract_service: &ContractService,
@@ -661,23 +908,25 @@ impl SnapshotService {
         // Get epoch before consuming snapshot
         let epoch = snapshot.epoch;
 
-        // Generate hash
-        let (hash_bytes, hash_hex, version) = Self::version_and_hash(snapshot)
+        // Generate Merkle root
+        let root = Self::merkle_root(snapshot)
             .map_err(|e| anyhow::anyhow!("Failed to hash snapshot: {}", e))?;
+        let root_hex = hex::encode(root);
 
-        info!("Generated snapshot hash for epoch {}: {}", epoch, hash_hex);
+        info!(
+            "Generated snapshot Merkle root for epoch {}: {}",
+            epoch, root_hex
+        );
 
         // Submit to contract
-        let submission = contract_service
-            .submit_snapshot_hash(hash_bytes, epoch)
-            .await?;
+        let submission = contract_service.submit_snapshot_hash(root, epoch).await?;
 
         info!(
             "Successfully submitted snapshot for epoch {} to contract",
             epoch
         );
 
-        Ok((hash_bytes, hash_hex, version, submission))
+        Ok((root, root_hex, SCHEMA_VERSION, submission))
     }
 }
 
@@ -875,6 +1124,47 @@ mod tests {
         assert_eq!(hash1, hash2);
     }
 
+    #[test]
+    fn test_merkle_root_commits_to_each_record() {
+        let now = Utc::now();
+        let anchor_id1 = Uuid::from_u128(1);
+        let anchor_id2 = Uuid::from_u128(2);
+        let corridor_id = Uuid::from_u128(3);
+
+        let mut snapshot1 = AnalyticsSnapshot::new(7, now);
+        snapshot1.add_corridor_metrics(create_test_corridor_metrics(corridor_id, "corridor1"));
+        snapshot1.add_anchor_metrics(create_test_anchor_metrics(anchor_id2, "Anchor2"));
+        snapshot1.add_anchor_metrics(create_test_anchor_metrics(anchor_id1, "Anchor1"));
+
+        // Anchors first, then corridors, each sorted by ID
+        let leaves = SnapshotService::merkle_leaves(snapshot1.clone()).unwrap();
+        assert_eq!(
+            leaves
+                .iter()
+                .map(|leaf| (leaf.kind, leaf.id))
+                .collect::<Vec<_>>(),
+            vec![
+                (SnapshotLeafKind::Anchor, anchor_id1),
+                (SnapshotLeafKind::Anchor, anchor_id2),
+                (SnapshotLeafKind::Corridor, corridor_id),
+            ]
+        );
+        assert!(leaves[0].data.starts_with(r#"{"kind":"anchor","record":{"#));
+
+        let mut snapshot2 = snapshot1.clone();
+        snapshot2.anchor_metrics.reverse();
+        assert_eq!(
+            SnapshotService::merkle_root(snapshot1.clone()).unwrap(),
+            SnapshotService::merkle_root(snapshot2.clone()).unwrap()
+        );
+
+        snapshot2.anchor_metrics[0].reliability_score = 0.5;
+        assert_ne!(
+            SnapshotService::merkle_root(snapshot1).unwrap(),
+            SnapshotService::merkle_root(snapshot2).unwrap()
+        );
+    }
+
     #[test]
     fn test_deterministic_json_no_extra_whitespace() {
         let now = Utc::now();
@@ -0,0 +1,771 @@
+//! Scheduled snapshot epochs and their on-chain submission.
+//!
+//! Epoch `n` is the `n`th period of `epoch_seconds` since `genesis`. Once a
+//! period has ended, a snapshot is generated for its epoch and queued in
+//! `snapshot_submissions`, where it moves through
+//! `pending -> submitted -> confirmed`, or to `failed` once `max_attempts`
+//! submissions have failed. The queue is persisted, so submissions cut short
+//! by a failure or a restart are retried with backoff.
+//!
+//! The contract is read for the epoch before every submission: it rejects a
+//! second submission for an epoch, and an epoch that was committed but never
+//! recorded is confirmed instead.
+//!
+//! The reconciler checks every epoch the contract lists, every epoch stored
+//! locally, and every scheduled epoch from the first queued one up to the
+//! latest ended period, reading the hash of each listed epoch from the
+//! contract.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::Serialize;
+use sqlx::Row;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use tokio::time::{interval, Duration as TokioDuration, Instant};
+use tracing::{debug, error, info, warn};
+
+use crate::database::Database;
+use crate::services::contract::{ContractService, OnChainSnapshot, SubmissionResult};
+use crate::services::snapshot::{decode_hash, SnapshotService};
+
+/// Where a queued submission stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubmissionStatus {
+    Pending,
+    Submitted,
+    Confirmed,
+    Failed,
+}
+
+impl SubmissionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubmissionStatus::Pending => "pending",
+            SubmissionStatus::Submitted => "submitted",
+            SubmissionStatus::Confirmed => "confirmed",
+            SubmissionStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(status: &str) -> Option<Self> {
+        match status {
+            "pending" => Some(SubmissionStatus::Pending),
+            "submitted" => Some(SubmissionStatus::Submitted),
+            "confirmed" => Some(SubmissionStatus::Confirmed),
+            "failed" => Some(SubmissionStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// A snapshot queued for on-chain submission
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SnapshotSubmission {
+    pub epoch: u64,
+    pub snapshot_id: String,
+    /// Hex-encoded Merkle root to commit
+    pub merkle_root: String,
+    pub status: SubmissionStatus,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub transaction_hash: Option<String>,
+    pub ledger: Option<u64>,
+    pub next_attempt_at: DateTime<Utc>,
+}
+
+/// How snapshots reach the snapshot contract
+#[async_trait::async_trait]
+pub trait SnapshotContractClient: Send + Sync {
+    async fn submit_snapshot(&self, hash: [u8; 32], epoch: u64) -> Result<SubmissionResult>;
+
+    /// The hash the contract holds for an epoch, if any
+    async fn get_snapshot_at_epoch(&self, epoch: u64) -> Result<Option<OnChainSnapshot>>;
+
+    /// Every epoch the contract holds a snapshot for
+    async fn get_all_epochs(&self) -> Result<Vec<u64>>;
+}
+
+#[async_trait::async_trait]
+impl SnapshotContractClient for ContractService {
+    async fn submit_snapshot(&self, hash: [u8; 32], epoch: u64) -> Result<SubmissionResult> {
+        ContractService::submit_snapshot(self, hash, epoch).await
+    }
+
+    async fn get_snapshot_at_epoch(&self, epoch: u64) -> Result<Option<OnChainSnapshot>> {
+        ContractService::get_snapshot_at_epoch(self, epoch).await
+    }
+
+    async fn get_all_epochs(&self) -> Result<Vec<u64>> {
+        ContractService::get_all_epochs(self).await
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SnapshotSchedulerConfig {
+    /// Length of each epoch
+    pub epoch_seconds: u64,
+    /// Start of epoch 1
+    pub genesis: DateTime<Utc>,
+    pub interval_seconds: u64,
+    /// Submissions tried before an epoch is marked failed
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubling with each further attempt
+    pub retry_base_seconds: i64,
+    pub retry_max_seconds: i64,
+    pub reconcile_interval_seconds: u64,
+}
+
+impl Default for SnapshotSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            epoch_seconds: 86_400,
+            genesis: Utc.timestamp_opt(0, 0).unwrap(),
+            interval_seconds: 60,
+            max_attempts: 8,
+            retry_base_seconds: 60,
+            retry_max_seconds: 3600,
+            reconcile_interval_seconds: 3600,
+        }
+    }
+}
+
+impl SnapshotSchedulerConfig {
+    /// The latest epoch whose period has ended by `now`; 0 before the end of
+    /// epoch 1
+    pub fn epoch_at(&self, now: DateTime<Utc>) -> u64 {
+        let elapsed = (now - self.genesis).num_seconds();
+        if elapsed < 0 {
+            0
+        } else {
+            elapsed as u64 / self.epoch_seconds
+        }
+    }
+
+    /// When an epoch's period ends
+    pub fn epoch_end(&self, epoch: u64) -> DateTime<Utc> {
+        self.genesis + Duration::seconds((epoch * self.epoch_seconds) as i64)
+    }
+
+    /// Delay before the next submission after `attempts` failed ones
+    fn retry_delay(&self, attempts: u32) -> Duration {
+        let factor = 1_i64 << attempts.saturating_sub(1).min(30);
+        Duration::seconds(
+            self.retry_base_seconds
+                .saturating_mul(factor)
+                .min(self.retry_max_seconds),
+        )
+    }
+}
+
+/// What a pass over the submission queue did
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct QueueSummary {
+    pub submitted: usize,
+    pub confirmed: usize,
+    /// Attempts that failed and will be retried
+    pub retried: usize,
+    pub failed: usize,
+}
+
+/// How an epoch's local snapshot compares with the contract
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EpochState {
+    /// The contract holds the hash or Merkle root of a stored snapshot
+    Committed,
+    /// Stored, but the contract has nothing for the epoch
+    MissingOnChain,
+    /// The contract has a hash but no snapshot is stored for the epoch
+    MissingLocally,
+    /// The contract's hash matches none of the epoch's stored snapshots
+    Mismatch,
+    /// A scheduled epoch with no snapshot anywhere
+    Missing,
+    /// The contract lists the epoch but its hash couldn't be read
+    Unreadable,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EpochReconciliation {
+    pub epoch: u64,
+    pub state: EpochState,
+    /// Merkle root (or, for older snapshots, hash) of the stored snapshot
+    pub local_hash: Option<String>,
+    pub on_chain_hash: Option<String>,
+    /// Whether the epoch was queued for submission again
+    pub requeued: bool,
+    /// Why the contract couldn't be read for the epoch
+    pub error: Option<String>,
+}
+
+/// Differences between the stored snapshots and the contract
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct ReconciliationReport {
+    pub checked: usize,
+    pub committed: usize,
+    /// Every epoch that isn't committed
+    pub gaps: Vec<EpochReconciliation>,
+}
+
+/// A stored snapshot as the reconciler sees it
+struct LocalSnapshot {
+    id: String,
+    hash: Option<String>,
+    merkle_root: Option<String>,
+}
+
+/// Generates a snapshot for each epoch as it ends and commits it on-chain
+pub struct SnapshotScheduler {
+    db: Arc<Database>,
+    snapshots: Arc<SnapshotService>,
+    contract: Option<Arc<dyn SnapshotContractClient>>,
+    config: SnapshotSchedulerConfig,
+}
+
+impl SnapshotScheduler {
+    /// Without a contract client snapshots are still generated and queued;
+    /// they are submitted once one is configured
+    pub fn new(
+        db: Arc<Database>,
+        snapshots: Arc<SnapshotService>,
+        contract: Option<Arc<dyn SnapshotContractClient>>,
+        config: SnapshotSchedulerConfig,
+    ) -> Self {
+        Self {
+            db,
+            snapshots,
+            contract,
+            config,
+        }
+    }
+
+    /// Start the periodic scheduling, submission and reconciliation job
+    pub async fn start_scheduler(self: Arc<Self>) {
+        info!(
+            "Starting snapshot scheduler (epoch: {} seconds, interval: {} seconds)",
+            self.config.epoch_seconds, self.config.interval_seconds
+        );
+
+        let mut ticker = interval(TokioDuration::from_secs(self.config.interval_seconds));
+        let reconcile_every = TokioDuration::from_secs(self.config.reconcile_interval_seconds);
+        let mut next_reconcile = Instant::now();
+
+        loop {
+            ticker.tick().await;
+            let now = Utc::now();
+
+            if let Err(e) = self.schedule(now).await {
+                error!("Snapshot scheduling failed: {}", e);
+            }
+            if let Err(e) = self.process_queue(now).await {
+                error!("Snapshot submission failed: {}", e);
+            }
+
+            if self.contract.is_some() && Instant::now() >= next_reconcile {
+                next_reconcile = Instant::now() + reconcile_every;
+                match self.reconcile(now, true).await {
+                    Ok(report) => {
+                        for gap in &report.gaps {
+                            warn!(
+                                "Snapshot epoch {} is {:?} (local: {:?}, on-chain: {:?}, requeued: {}, error: {:?})",
+                                gap.epoch, gap.state, gap.local_hash, gap.on_chain_hash, gap.requeued, gap.error
+                            );
+                        }
+                    }
+                    Err(e) => error!("Snapshot reconciliation failed: {}", e),
+                }
+            }
+        }
+    }
+
+    /// Generate and queue a snapshot for the latest ended epoch, unless it
+    /// or a later one is already queued. Epochs that ended while the
+    /// scheduler was down are not snapshotted: the metrics of their periods
+    /// are gone, so they are left without a snapshot and the reconciler
+    /// reports them as `Missing`. Returns the epochs queued.
+    pub async fn schedule(&self, now: DateTime<Utc>) -> Result<Vec<u64>> {
+        let latest = self.config.epoch_at(now);
+        let last_queued: Option<i64> =
+            sqlx::query_scalar("SELECT MAX(epoch) FROM snapshot_submissions")
+                .fetch_one(self.db.pool())
+                .await
+                .context("Failed to fetch last scheduled epoch")?;
+        if latest == 0 || last_queued.is_some_and(|last| last as u64 >= latest) {
+            return Ok(Vec::new());
+        }
+        if let Some(last) = last_queued.map(|last| last as u64) {
+            if last + 1 < latest {
+                warn!(
+                    "Snapshot epochs {} to {} ended while the scheduler was down and won't be snapshotted",
+                    last + 1,
+                    latest - 1
+                );
+            }
+        }
+
+        let generated = self
+            .snapshots
+            .generate_and_store_snapshot(latest, now)
+            .await
+            .context("Failed to generate scheduled snapshot")?;
+        self.enqueue(latest, &generated.snapshot_id, &generated.merkle_root, now)
+            .await?;
+
+        info!("Queued snapshot for epoch {} for submission", latest);
+        Ok(vec![latest])
+    }
+
+    /// Advance every queued submission that is due
+    pub async fn process_queue(&self, now: DateTime<Utc>) -> Result<QueueSummary> {
+        let mut summary = QueueSummary::default();
+        let Some(contract) = &self.contract else {
+            debug!("Snapshot contract not configured, leaving submissions queued");
+            return Ok(summary);
+        };
+
+        let due = sqlx::query(
+            r#"
+            SELECT * FROM snapshot_submissions
+            WHERE status IN ('pending', 'submitted') AND next_attempt_at <= ?
+            ORDER BY epoch
+            "#,
+        )
+        .bind(now)
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to fetch due snapshot submissions")?;
+
+        for row in &due {
+            let submission = submission_from_row(row)?;
+            let epoch = submission.epoch;
+            if let Err(e) = self
+                .advance(contract.as_ref(), submission, now, &mut summary)
+                .await
+            {
+                error!(
+                    "Failed to advance snapshot submission for epoch {}: {:#}",
+                    epoch, e
+                );
+            }
+        }
+
+        Ok(summary)
+    }
+
+    async fn advance(
+        &self,
+        contract: &dyn SnapshotContractClient,
+        mut submission: SnapshotSubmission,
+        now: DateTime<Utc>,
+        summary: &mut QueueSummary,
+    ) -> Result<()> {
+        let epoch = submission.epoch;
+
+        let attempt = match contract.get_snapshot_at_epoch(epoch).await {
+            Ok(Some(on_chain)) if on_chain.hash == submission.merkle_root => {
+                info!("Snapshot for epoch {} confirmed on-chain", epoch);
+                submission.status = SubmissionStatus::Confirmed;
+                submission.ledger = submission.ledger.or(on_chain.ledger);
+                submission.last_error = None;
+                summary.confirmed += 1;
+                return self.update(&submission, now).await;
+            }
+            Ok(Some(on_chain)) => {
+                error!(
+                    "Epoch {} is committed on-chain as {}, not {}",
+                    epoch, on_chain.hash, submission.merkle_root
+                );
+                submission.status = SubmissionStatus::Failed;
+                submission.last_error = Some(format!(
+                    "Epoch already committed on-chain with hash {}",
+                    on_chain.hash
+                ));
+                summary.failed += 1;
+                return self.update(&submission, now).await;
+            }
+            Ok(None) if submission.status == SubmissionStatus::Submitted => Err(anyhow!(
+                "Submitted transaction {} is not reflected on-chain",
+                submission
+                    .transaction_hash
+                    .as_deref()
+                    .unwrap_or("(unknown)")
+            )),
+            Ok(None) => {
+                let root = decode_hash(&submission.merkle_root)?;
+                contract.submit_snapshot(root, epoch).await
+            }
+            Err(e) => Err(e.context("Failed to read the contract")),
+        };
+
+        match attempt {
+            Ok(result) => {
+                self.snapshots
+                    .record_submission(&submission.snapshot_id, &result)
+                    .await?;
+                submission.status = SubmissionStatus::Submitted;
+                submission.transaction_hash = Some(result.transaction_hash);
+                submission.ledger = Some(result.ledger);
+                submission.last_error = None;
+                // Confirmed against the contract on the next pass
+                submission.next_attempt_at = now;
+                summary.submitted += 1;
+            }
+            Err(e) => {
+                submission.attempts += 1;
+                submission.last_error = Some(format!("{:#}", e));
+                if submission.attempts >= self.config.max_attempts {
+                    error!(
+                        "Giving up on snapshot submission for epoch {} after {} attempts: {:#}",
+                        epoch, submission.attempts, e
+                    );
+                    submission.status = SubmissionStatus::Failed;
+                    summary.failed += 1;
+                } else {
+                    warn!(
+                        "Snapshot submission for epoch {} failed (attempt {}/{}): {:#}",
+                        epoch, submission.attempts, self.config.max_attempts, e
+                    );
+                    submission.status = SubmissionStatus::Pending;
+                    submission.next_attempt_at = now + self.config.retry_delay(submission.attempts);
+                    summary.retried += 1;
+                }
+            }
+        }
+
+        self.update(&submission, now).await
+    }
+
+    /// Compare the stored snapshots with the contract, epoch by epoch
+    ///
+    /// With `repair`, epochs stored with a Merkle root but missing on-chain
+    /// are queued for submission again, and queued epochs found committed are
+    /// marked confirmed. Other gaps can only be reported: the contract can't
+    /// be rewritten, and past metrics can't be regenerated. An epoch whose
+    /// hash can't be read is reported as unreadable and the rest are still
+    /// checked.
+    pub async fn reconcile(
+        &self,
+        now: DateTime<Utc>,
+        repair: bool,
+    ) -> Result<ReconciliationReport> {
+        let contract = self
+            .contract
+            .as_ref()
+            .ok_or_else(|| anyhow!("Snapshot contract not configured"))?;
+
+        let mut local = self.local_snapshots().await?;
+        let first_scheduled: Option<i64> =
+            sqlx::query_scalar("SELECT MIN(epoch) FROM snapshot_submissions")
+                .fetch_one(self.db.pool())
+                .await
+                .context("Failed to fetch first scheduled epoch")?;
+        if let Some(first) = first_scheduled {
+            for epoch in first as u64..=self.config.epoch_at(now) {
+                local.entry(epoch).or_default();
+            }
+        }
+        let listed: BTreeSet<u64> = contract
+            .get_all_epochs()
+            .await
+            .context("Failed to list epochs from the contract")?
+            .into_iter()
+            .collect();
+        for &epoch in &listed {
+            local.entry(epoch).or_default();
+        }
+
+        let mut report = ReconciliationReport::default();
+        for (epoch, stored) in local {
+            report.checked += 1;
+            // Stored snapshots come submitted first, then newest first
+            let preferred = stored.first();
+            let local_hash = preferred.and_then(|s| s.merkle_root.clone().or(s.hash.clone()));
+
+            // Only listed epochs have a hash to read
+            let on_chain = if listed.contains(&epoch) {
+                match contract.get_snapshot_at_epoch(epoch).await {
+                    Ok(snapshot) => snapshot.map(|snapshot| snapshot.hash),
+                    Err(e) => {
+                        report.gaps.push(EpochReconciliation {
+                            epoch,
+                            state: EpochState::Unreadable,
+                            local_hash,
+                            on_chain_hash: None,
+                            requeued: false,
+                            error: Some(format!("{:#}", e)),
+                        });
+                        continue;
+                    }
+                }
+            } else {
+                None
+            };
+
+            let committed = on_chain.as_ref().and_then(|hash| {
+                stored
+                    .iter()
+                    .find(|s| s.merkle_root.as_ref() == Some(hash) || s.hash.as_ref() == Some(hash))
+            });
+            if let Some(snapshot) = committed {
+                report.committed += 1;
+                if repair {
+                    self.mark_confirmed(epoch, &snapshot.id, now).await?;
+                }
+                continue;
+            }
+
+            let state = match (&on_chain, preferred) {
+                (Some(_), Some(_)) => EpochState::Mismatch,
+                (Some(_), None) => EpochState::MissingLocally,
+                (None, Some(_)) => EpochState::MissingOnChain,
+                (None, None) => EpochState::Missing,
+            };
+
+            let mut requeued = false;
+            if repair && state == EpochState::MissingOnChain {
+                let committable = stored
+                    .iter()
+                    .find_map(|s| Some((s.id.as_str(), s.merkle_root.as_deref()?)));
+                if let Some((snapshot_id, merkle_root)) = committable {
+                    requeued = self.requeue(epoch, snapshot_id, merkle_root, now).await?;
+                }
+            }
+
+            report.gaps.push(EpochReconciliation {
+                epoch,
+                state,
+                local_hash,
+                on_chain_hash: on_chain,
+                requeued,
+                error: None,
+            });
+        }
+
+        info!(
+            "Reconciled {} snapshot epochs: {} committed, {} gaps",
+            report.checked,
+            report.committed,
+            report.gaps.len()
+        );
+        Ok(report)
+    }
+
+    /// The queued submission for an epoch
+    pub async fn submission(&self, epoch: u64) -> Result<Option<SnapshotSubmission>> {
+        sqlx::query("SELECT * FROM snapshot_submissions WHERE epoch = ?")
+            .bind(epoch as i64)
+            .fetch_optional(self.db.pool())
+            .await
+            .context("Failed to fetch snapshot submission")?
+            .map(|row| submission_from_row(&row))
+            .transpose()
+    }
+
+    /// Every queued submission, by epoch
+    pub async fn submissions(&self) -> Result<Vec<SnapshotSubmission>> {
+        sqlx::query("SELECT * FROM snapshot_submissions ORDER BY epoch")
+            .fetch_all(self.db.pool())
+            .await
+            .context("Failed to fetch snapshot submissions")?
+            .iter()
+            .map(submission_from_row)
+            .collect()
+    }
+
+    /// Queue a stored snapshot for submission, replacing whatever was queued
+    /// for its epoch
+    async fn enqueue(
+        &self,
+        epoch: u64,
+        snapshot_id: &str,
+        merkle_root: &str,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO snapshot_submissions (
+                epoch, snapshot_id, merkle_root, status, attempts, next_attempt_at,
+                created_at, updated_at
+            ) VALUES (?, ?, ?, 'pending', 0, ?, ?, ?)
+            ON CONFLICT(epoch) DO UPDATE SET
+                snapshot_id = excluded.snapshot_id,
+                merkle_root = excluded.merkle_root,
+                status = 'pending',
+                attempts = 0,
+                last_error = NULL,
+                transaction_hash = NULL,
+                ledger = NULL,
+                next_attempt_at = excluded.next_attempt_at,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(epoch as i64)
+        .bind(snapshot_id)
+        .bind(merkle_root)
+        .bind(now)
+        .bind(now)
+        .bind(now)
+        .execute(self.db.pool())
+        .await
+        .context("Failed to queue snapshot submission")?;
+        Ok(())
+    }
+
+    /// Queue an epoch missing on-chain again, unless it is already in flight
+    async fn requeue(
+        &self,
+        epoch: u64,
+        snapshot_id: &str,
+        merkle_root: &str,
+        now: DateTime<Utc>,
+    ) -> Result<bool> {
+        let in_flight = self.submission(epoch).await?.is_some_and(|queued| {
+            matches!(
+                queued.status,
+                SubmissionStatus::Pending | SubmissionStatus::Submitted
+            )
+        });
+        if in_flight {
+            return Ok(false);
+        }
+
+        info!("Requeueing snapshot for epoch {}, missing on-chain", epoch);
+        self.enqueue(epoch, snapshot_id, merkle_root, now).await?;
+        Ok(true)
+    }
+
+    async fn mark_confirmed(
+        &self,
+        epoch: u64,
+        snapshot_id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE snapshot_submissions
+            SET status = 'confirmed', snapshot_id = ?, last_error = NULL, updated_at = ?
+            WHERE epoch = ? AND status != 'confirmed'
+            "#,
+        )
+        .bind(snapshot_id)
+        .bind(now)
+        .bind(epoch as i64)
+        .execute(self.db.pool())
+        .await
+        .context("Failed to confirm snapshot submission")?;
+        Ok(())
+    }
+
+    async fn update(&self, submission: &SnapshotSubmission, now: DateTime<Utc>) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE snapshot_submissions
+            SET status = ?, attempts = ?, last_error = ?, transaction_hash = ?, ledger = ?,
+                next_attempt_at = ?, updated_at = ?
+            WHERE epoch = ?
+            "#,
+        )
+        .bind(submission.status.as_str())
+        .bind(submission.attempts as i64)
+        .bind(&submission.last_error)
+        .bind(&submission.transaction_hash)
+        .bind(submission.ledger.map(|l| l as i64))
+        .bind(submission.next_attempt_at)
+        .bind(now)
+        .bind(submission.epoch as i64)
+        .execute(self.db.pool())
+        .await
+        .context("Failed to update snapshot submission")?;
+        Ok(())
+    }
+
+    /// Stored snapshots by epoch, submitted first, then newest first
+    async fn local_snapshots(&self) -> Result<BTreeMap<u64, Vec<LocalSnapshot>>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, epoch, hash, merkle_root
+            FROM snapshots
+            WHERE entity_type = 'analytics_snapshot' AND epoch > 0
+            ORDER BY epoch, transaction_hash IS NULL, created_at DESC
+            "#,
+        )
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to fetch stored snapshots")?;
+
+        let mut local: BTreeMap<u64, Vec<LocalSnapshot>> = BTreeMap::new();
+        for row in rows {
+            local
+                .entry(row.get::<i64, _>("epoch") as u64)
+                .or_default()
+                .push(LocalSnapshot {
+                    id: row.get("id"),
+                    hash: row.get("hash"),
+                    merkle_root: row.get("merkle_root"),
+                });
+        }
+        Ok(local)
+    }
+}
+
+fn submission_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<SnapshotSubmission> {
+    let status: String = row.get("status");
+    Ok(SnapshotSubmission {
+        epoch: row.get::<i64, _>("epoch") as u64,
+        snapshot_id: row.get("snapshot_id"),
+        merkle_root: row.get("merkle_root"),
+        status: SubmissionStatus::parse(&status)
+            .ok_or_else(|| anyhow!("Unknown snapshot submission status {}", status))?,
+        attempts: row.get::<i64, _>("attempts") as u32,
+        last_error: row.get("last_error"),
+        transaction_hash: row.get("transaction_hash"),
+        ledger: row.get::<Option<i64>, _>("ledger").map(|l| l as u64),
+        next_attempt_at: row.get("next_attempt_at"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epochs_follow_wall_clock_periods() {
+        let config = SnapshotSchedulerConfig {
+            epoch_seconds: 3600,
+            genesis: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.epoch_at(Utc.with_ymd_and_hms(2025, 12, 31, 0, 0, 0).unwrap()),
+            0
+        );
+        assert_eq!(
+            config.epoch_at(Utc.with_ymd_and_hms(2026, 1, 1, 0, 59, 59).unwrap()),
+            0
+        );
+        assert_eq!(
+            config.epoch_at(Utc.with_ymd_and_hms(2026, 1, 1, 1, 0, 0).unwrap()),
+            1
+        );
+        assert_eq!(
+            config.epoch_at(Utc.with_ymd_and_hms(2026, 1, 2, 0, 30, 0).unwrap()),
+            24
+        );
+        assert_eq!(
+            config.epoch_end(24),
+            Utc.with_ymd_and_hms(2026, 1, 2, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_to_a_cap() {
+        let config = SnapshotSchedulerConfig::default();
+        let delays: Vec<i64> = (1..=8)
+            .map(|attempts| config.retry_delay(attempts).num_seconds())
+            .collect();
+        assert_eq!(delays, [60, 120, 240, 480, 960, 1920, 3600, 3600]);
+    }
+}
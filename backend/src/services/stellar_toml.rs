@@ -0,0 +1,852 @@
+//! Anchor discovery from stellar.toml files (SEP-1).
+//!
+//! Issuers seen in trustline stats and payments are resolved to their home
+//! domains, and each domain's stellar.toml is fetched, parsed and checked
+//! against SEP-1. Domains with a valid file become anchors, their verified
+//! currencies become the anchor's assets, and every change to the file is kept.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{Duration, Utc};
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use reqwest::redirect::Policy;
+use reqwest::{Client, Response, Url};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use tokio::time::{interval, Duration as TokioDuration};
+use toml_edit::{DocumentMut, Item, Table, Value};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+use crate::database::Database;
+use crate::muxed::G_ADDRESS_LEN;
+use crate::rpc::StellarRpcClient;
+
+pub const STELLAR_TOML_PATH: &str = "/.well-known/stellar.toml";
+
+/// SEP-1 asks for files under 100KB
+const MAX_STELLAR_TOML_BYTES: usize = 100 * 1024;
+
+/// Organization details from the `[DOCUMENTATION]` table
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct OrgInfo {
+    pub org_name: Option<String>,
+    pub org_dba: Option<String>,
+    pub org_url: Option<String>,
+    pub org_logo: Option<String>,
+    pub org_description: Option<String>,
+    pub org_official_email: Option<String>,
+    pub org_support_email: Option<String>,
+}
+
+/// An entry of `[[CURRENCIES]]`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct TomlCurrency {
+    pub code: Option<String>,
+    pub issuer: Option<String>,
+    pub status: Option<String>,
+    pub display_decimals: Option<i64>,
+    pub name: Option<String>,
+    pub desc: Option<String>,
+    pub is_asset_anchored: Option<bool>,
+    pub anchor_asset_type: Option<String>,
+    pub anchor_asset: Option<String>,
+}
+
+/// The fields of a stellar.toml that anchor discovery and the SEP proxies use
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct StellarToml {
+    pub version: Option<String>,
+    pub network_passphrase: Option<String>,
+    pub federation_server: Option<String>,
+    /// SEP-6 transfer server
+    pub transfer_server: Option<String>,
+    /// SEP-24 interactive transfer server
+    pub transfer_server_sep0024: Option<String>,
+    /// SEP-12 KYC server
+    pub kyc_server: Option<String>,
+    /// SEP-10 web authentication endpoint
+    pub web_auth_endpoint: Option<String>,
+    /// Key SEP-10 challenges are signed with
+    pub signing_key: Option<String>,
+    /// SEP-31 direct payment server
+    pub direct_payment_server: Option<String>,
+    /// SEP-38 quote server
+    pub anchor_quote_server: Option<String>,
+    #[serde(default)]
+    pub accounts: Vec<String>,
+    #[serde(default)]
+    pub documentation: OrgInfo,
+    #[serde(default)]
+    pub currencies: Vec<TomlCurrency>,
+}
+
+impl StellarToml {
+    /// Parse a stellar.toml, returning it with every SEP-1 problem found.
+    /// Only files that aren't TOML at all are an error.
+    pub fn parse(text: &str) -> Result<(Self, Vec<String>)> {
+        let doc: DocumentMut = text
+            .parse()
+            .map_err(|e| anyhow!("Invalid stellar.toml: {}", e))?;
+        let mut issues = Vec::new();
+
+        let mut toml = StellarToml {
+            version: string(&doc, "VERSION", &mut issues),
+            network_passphrase: string(&doc, "NETWORK_PASSPHRASE", &mut issues),
+            federation_server: url(&doc, "FEDERATION_SERVER", &mut issues),
+            transfer_server: url(&doc, "TRANSFER_SERVER", &mut issues),
+            transfer_server_sep0024: url(&doc, "TRANSFER_SERVER_SEP0024", &mut issues),
+            kyc_server: url(&doc, "KYC_SERVER", &mut issues),
+            web_auth_endpoint: url(&doc, "WEB_AUTH_ENDPOINT", &mut issues),
+            signing_key: string(&doc, "SIGNING_KEY", &mut issues),
+            direct_payment_server: url(&doc, "DIRECT_PAYMENT_SERVER", &mut issues),
+            anchor_quote_server: url(&doc, "ANCHOR_QUOTE_SERVER", &mut issues),
+            ..Default::default()
+        };
+
+        if let Some(key) = &toml.signing_key {
+            if !is_account_id(key) {
+                issues.push(format!("SIGNING_KEY {} is not a Stellar account ID", key));
+            }
+        }
+        if toml.web_auth_endpoint.is_some() && toml.signing_key.is_none() {
+            issues.push("WEB_AUTH_ENDPOINT is set without a SIGNING_KEY".to_string());
+        }
+
+        match doc.get("ACCOUNTS") {
+            None => {}
+            Some(item) => match item.as_array() {
+                Some(accounts) => {
+                    for (index, account) in accounts.iter().enumerate() {
+                        match account.as_str() {
+                            Some(account) if is_account_id(account) => {
+                                toml.accounts.push(account.to_string())
+                            }
+                            Some(account) => issues.push(format!(
+                                "ACCOUNTS entry {} is not a Stellar account ID",
+                                account
+                            )),
+                            None => issues.push(format!("ACCOUNTS[{}] must be a string", index)),
+                        }
+                    }
+                }
+                None => issues.push("ACCOUNTS must be an array".to_string()),
+            },
+        }
+
+        match doc.get("DOCUMENTATION") {
+            None => {}
+            Some(item) => match item.as_table() {
+                Some(table) => toml.documentation = org_info(table, &mut issues),
+                None => issues.push("DOCUMENTATION must be a table".to_string()),
+            },
+        }
+
+        match doc.get("CURRENCIES") {
+            None => {}
+            Some(Item::ArrayOfTables(tables)) => {
+                for (index, table) in tables.iter().enumerate() {
+                    toml.currencies.push(currency(table, index, &mut issues));
+                }
+            }
+            // Also accept an inline array of inline tables
+            Some(Item::Value(Value::Array(array))) => {
+                for (index, value) in array.iter().enumerate() {
+                    match value.as_inline_table() {
+                        Some(inline) => {
+                            let table = inline.clone().into_table();
+                            toml.currencies.push(currency(&table, index, &mut issues));
+                        }
+                        None => issues.push(format!("CURRENCIES[{}] must be a table", index)),
+                    }
+                }
+            }
+            Some(_) => issues.push("CURRENCIES must be an array of tables".to_string()),
+        }
+
+        Ok((toml, issues))
+    }
+
+    /// Hash of the parsed contents, unaffected by formatting or comments
+    pub fn content_hash(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        hex::encode(Sha256::digest(&json))
+    }
+}
+
+fn is_account_id(value: &str) -> bool {
+    value.len() == G_ADDRESS_LEN
+        && value.starts_with('G')
+        && value
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || ('2'..='7').contains(&c))
+}
+
+fn string_in(table: &Table, key: &str, label: &str, issues: &mut Vec<String>) -> Option<String> {
+    let item = table.get(key)?;
+    match item.as_str() {
+        Some(value) => Some(value.trim().to_string()).filter(|v| !v.is_empty()),
+        None => {
+            issues.push(format!("{} must be a string", label));
+            None
+        }
+    }
+}
+
+fn string(doc: &DocumentMut, key: &str, issues: &mut Vec<String>) -> Option<String> {
+    string_in(doc.as_table(), key, key, issues)
+}
+
+/// SEP-1 endpoints must be served over https from a public domain; any other
+/// URL is reported and left out so nothing requests it
+fn url(doc: &DocumentMut, key: &str, issues: &mut Vec<String>) -> Option<String> {
+    let value = string(doc, key, issues)?;
+    if !value.starts_with("https://") {
+        issues.push(format!("{} {} is not an https URL", key, value));
+        return None;
+    }
+    if !is_public_https_url(&value) {
+        issues.push(format!("{} {} is not on a public domain", key, value));
+        return None;
+    }
+    Some(value.trim_end_matches('/').to_string())
+}
+
+fn org_info(table: &Table, issues: &mut Vec<String>) -> OrgInfo {
+    let field = |key: &str, issues: &mut Vec<String>| {
+        string_in(table, key, &format!("DOCUMENTATION.{}", key), issues)
+    };
+
+    OrgInfo {
+        org_name: field("ORG_NAME", issues),
+        org_dba: field("ORG_DBA", issues),
+        org_url: field("ORG_URL", issues),
+        org_logo: field("ORG_LOGO", issues),
+        org_description: field("ORG_DESCRIPTION", issues),
+        org_official_email: field("ORG_OFFICIAL_EMAIL", issues),
+        org_support_email: field("ORG_SUPPORT_EMAIL", issues),
+    }
+}
+
+fn currency(table: &Table, index: usize, issues: &mut Vec<String>) -> TomlCurrency {
+    let label = |key: &str| format!("CURRENCIES[{}].{}", index, key);
+
+    let currency = TomlCurrency {
+        code: string_in(table, "code", &label("code"), issues),
+        issuer: string_in(table, "issuer", &label("issuer"), issues),
+        status: string_in(table, "status", &label("status"), issues),
+        display_decimals: table.get("display_decimals").and_then(Item::as_integer),
+        name: string_in(table, "name", &label("name"), issues),
+        desc: string_in(table, "desc", &label("desc"), issues),
+        is_asset_anchored: table.get("is_asset_anchored").and_then(Item::as_bool),
+        anchor_asset_type: string_in(
+            table,
+            "anchor_asset_type",
+            &label("anchor_asset_type"),
+            issues,
+        ),
+        anchor_asset: string_in(table, "anchor_asset", &label("anchor_asset"), issues),
+    };
+
+    // Currencies may instead link to a separate file with their details
+    if currency.code.is_none() && table.get("toml").is_none() {
+        issues.push(format!("{} is missing", label("code")));
+    }
+    match &currency.issuer {
+        Some(issuer) if !is_account_id(issuer) => issues.push(format!(
+            "{} {} is not a Stellar account ID",
+            label("issuer"),
+            issuer
+        )),
+        None if currency.code.is_some() && currency.code.as_deref() != Some("native") => {
+            issues.push(format!("{} is missing", label("issuer")))
+        }
+        _ => {}
+    }
+
+    currency
+}
+
+/// Where account home domains and stellar.toml files come from
+#[async_trait::async_trait]
+pub trait StellarTomlSource: Send + Sync {
+    /// The home domain set on an account, if any
+    async fn home_domain(&self, account_id: &str) -> Result<Option<String>>;
+
+    /// The raw stellar.toml served by a domain
+    async fn fetch_toml(&self, domain: &str) -> Result<String>;
+}
+
+/// Home domains from Horizon, stellar.toml files over https
+pub struct HttpStellarTomlSource {
+    client: Client,
+    rpc_client: Arc<StellarRpcClient>,
+}
+
+impl HttpStellarTomlSource {
+    pub fn new(rpc_client: Arc<StellarRpcClient>) -> Self {
+        Self {
+            client: untrusted_client(),
+            rpc_client,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl StellarTomlSource for HttpStellarTomlSource {
+    async fn home_domain(&self, account_id: &str) -> Result<Option<String>> {
+        Ok(self.rpc_client.fetch_account(account_id).await?.home_domain)
+    }
+
+    async fn fetch_toml(&self, domain: &str) -> Result<String> {
+        if !is_public_domain(domain) {
+            return Err(anyhow!("{} is not a public domain", domain));
+        }
+        let url = format!("https://{}{}", domain, STELLAR_TOML_PATH);
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch {}", url))?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("{} returned {}", url, response.status()));
+        }
+
+        let body = read_body_capped(response, MAX_STELLAR_TOML_BYTES, &url).await?;
+        String::from_utf8(body).with_context(|| format!("{} is not UTF-8", url))
+    }
+}
+
+/// HTTP client for domains and URLs that anchors publish: redirects aren't
+/// followed, and names that resolve to non-public addresses are refused
+pub(crate) fn untrusted_client() -> Client {
+    Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .redirect(Policy::none())
+        .dns_resolver(Arc::new(PublicResolver))
+        .build()
+        .expect("Failed to build HTTP client")
+}
+
+/// System DNS, failing for any name with a loopback, private or otherwise
+/// non-public address
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0)).await?.collect();
+            if let Some(addr) = addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+                return Err(
+                    format!("{} resolves to non-public address {}", host, addr.ip()).into(),
+                );
+            }
+            if addrs.is_empty() {
+                return Err(format!("{} has no addresses", host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Whether an address is reachable on the public internet
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                || a == 0
+                // Shared address space, benchmarking and reserved ranges
+                || (a == 100 && (64..128).contains(&b))
+                || (a == 198 && (b & 0xfe) == 18)
+                || a >= 240)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(ip));
+            }
+            let [first, second, ..] = ip.segments();
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                // Unique local, link-local and documentation ranges
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+                || (first == 0x2001 && second == 0x0db8))
+        }
+    }
+}
+
+/// Whether `host` is a public domain name: not an IP literal, no port, and
+/// not a single-label or local name that only resolves inside a network
+pub(crate) fn is_public_domain(host: &str) -> bool {
+    let labels: Vec<&str> = host.split('.').collect();
+    let Some(tld) = labels.last() else {
+        return false;
+    };
+
+    labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        })
+        // A numeric last label is an IPv4 address in some form
+        && !tld.chars().all(|c| c.is_ascii_digit())
+        && !matches!(*tld, "localhost" | "local" | "localdomain" | "internal" | "lan")
+}
+
+/// Whether `url` is https on a public domain's default port
+pub(crate) fn is_public_https_url(url: &str) -> bool {
+    let Ok(url) = Url::parse(url) else {
+        return false;
+    };
+    url.scheme() == "https" && url.port().is_none() && url.host_str().is_some_and(is_public_domain)
+}
+
+/// Read a response body a chunk at a time, giving up as soon as it passes
+/// `max_bytes` instead of buffering whatever the server sends
+pub(crate) async fn read_body_capped(
+    mut response: Response,
+    max_bytes: usize,
+    url: &str,
+) -> Result<Vec<u8>> {
+    let too_large = || anyhow!("{} is larger than {}KB", url, max_bytes / 1024);
+    if response
+        .content_length()
+        .is_some_and(|length| length > max_bytes as u64)
+    {
+        return Err(too_large());
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .with_context(|| format!("Failed to read {}", url))?
+    {
+        if body.len() + chunk.len() > max_bytes {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(body)
+}
+
+#[derive(Debug, Clone)]
+pub struct StellarTomlCrawlerConfig {
+    pub interval_hours: u64,
+    /// Days of payments to collect issuer accounts from
+    pub window_days: i64,
+    /// Most issuer accounts to resolve per crawl
+    pub max_issuers: i64,
+}
+
+impl Default for StellarTomlCrawlerConfig {
+    fn default() -> Self {
+        Self {
+            interval_hours: 6,
+            window_days: 30,
+            max_issuers: 500,
+        }
+    }
+}
+
+/// What a crawl found
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CrawlSummary {
+    pub domains: usize,
+    /// Anchors whose stellar.toml was fetched and stored
+    pub anchors: usize,
+    /// Anchors whose stellar.toml differs from the last crawl
+    pub changed: usize,
+    /// Domains whose stellar.toml couldn't be fetched or parsed
+    pub failed: usize,
+}
+
+/// Discovers anchors and keeps their SEP-1 metadata current
+pub struct StellarTomlCrawler {
+    db: Arc<Database>,
+    source: Arc<dyn StellarTomlSource>,
+    config: StellarTomlCrawlerConfig,
+}
+
+impl StellarTomlCrawler {
+    pub fn new(
+        db: Arc<Database>,
+        source: Arc<dyn StellarTomlSource>,
+        config: StellarTomlCrawlerConfig,
+    ) -> Self {
+        Self { db, source, config }
+    }
+
+    /// Start the periodic crawl
+    pub async fn start_scheduler(self: Arc<Self>) {
+        info!(
+            "Starting stellar.toml crawler (interval: {} hours)",
+            self.config.interval_hours
+        );
+
+        let mut ticker = interval(TokioDuration::from_secs(self.config.interval_hours * 3600));
+
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = self.crawl().await {
+                error!("stellar.toml crawl failed: {}", e);
+            }
+        }
+    }
+
+    /// Resolve issuers to home domains and crawl every domain found, along
+    /// with the home domains of known anchors
+    pub async fn crawl(&self) -> Result<CrawlSummary> {
+        let since = Utc::now() - Duration::days(self.config.window_days);
+        let issuers = self
+            .db
+            .get_known_issuers(since, self.config.max_issuers)
+            .await
+            .context("Failed to load issuer accounts")?;
+
+        // Issuers per domain, each having named that domain as its home domain
+        let mut domains: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for issuer in issuers {
+            match self.source.home_domain(&issuer).await {
+                Ok(Some(domain)) => {
+                    if let Some(domain) = normalize_domain(&domain) {
+                        domains.entry(domain).or_default().insert(issuer);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Failed to resolve home domain of {}: {}", issuer, e),
+            }
+        }
+        for domain in self.db.get_anchor_home_domains().await? {
+            if let Some(domain) = normalize_domain(&domain) {
+                domains.entry(domain).or_default();
+            }
+        }
+
+        let mut summary = CrawlSummary {
+            domains: domains.len(),
+            ..Default::default()
+        };
+        for (domain, issuers) in domains {
+            match self.crawl_domain(&domain, issuers).await {
+                Ok(Some(changed)) => {
+                    summary.anchors += 1;
+                    summary.changed += changed as usize;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    warn!("Failed to crawl stellar.toml of {}: {}", domain, e);
+                    summary.failed += 1;
+                    self.db
+                        .record_stellar_toml_error(&domain, &e.to_string(), Utc::now())
+                        .await?;
+                }
+            }
+        }
+
+        info!(
+            "Crawled {} stellar.toml files: {} anchors, {} changed, {} failed",
+            summary.domains, summary.anchors, summary.changed, summary.failed
+        );
+        Ok(summary)
+    }
+
+    /// Fetch and store one domain's stellar.toml, returning whether it
+    /// changed, or `None` when the domain has no anchor to attach it to
+    async fn crawl_domain(
+        &self,
+        domain: &str,
+        mut verified_issuers: BTreeSet<String>,
+    ) -> Result<Option<bool>> {
+        let text = self.source.fetch_toml(domain).await?;
+        let (toml, issues) = StellarToml::parse(&text)?;
+
+        // Only trust currencies whose issuer names this domain as its home
+        // domain, so a stellar.toml can't claim another anchor's assets
+        for issuer in toml.currencies.iter().filter_map(|c| c.issuer.as_deref()) {
+            if verified_issuers.contains(issuer) || !is_account_id(issuer) {
+                continue;
+            }
+            if let Ok(Some(home_domain)) = self.source.home_domain(issuer).await {
+                if normalize_domain(&home_domain).as_deref() == Some(domain) {
+                    verified_issuers.insert(issuer.to_string());
+                }
+            }
+        }
+
+        let anchor_id = match verified_issuers.first() {
+            Some(account) => {
+                let name = toml
+                    .documentation
+                    .org_name
+                    .clone()
+                    .unwrap_or_else(|| domain.to_string());
+                self.db
+                    .upsert_discovered_anchor(&name, account, domain)
+                    .await?
+            }
+            None => match self.db.get_anchor_id_by_home_domain(domain).await? {
+                Some(anchor_id) => anchor_id,
+                None => return Ok(None),
+            },
+        };
+
+        for currency in &toml.currencies {
+            if let (Some(code), Some(issuer)) = (&currency.code, &currency.issuer) {
+                if verified_issuers.contains(issuer) {
+                    self.db
+                        .upsert_discovered_asset(anchor_id, code, issuer)
+                        .await?;
+                }
+            }
+        }
+
+        let changed = self
+            .db
+            .record_stellar_toml(anchor_id, domain, &toml, &issues, Utc::now())
+            .await?;
+        Ok(Some(changed))
+    }
+}
+
+/// Lowercase host name without a scheme, path or trailing dot
+fn normalize_domain(domain: &str) -> Option<String> {
+    let domain = domain.trim();
+    let domain = domain
+        .strip_prefix("https://")
+        .or_else(|| domain.strip_prefix("http://"))
+        .unwrap_or(domain);
+    let domain = domain
+        .split('/')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('.')
+        .to_ascii_lowercase();
+
+    is_public_domain(&domain).then_some(domain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISSUER: &str = "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN";
+
+    #[test]
+    fn test_parse_stellar_toml() {
+        let text = format!(
+            r#"
+VERSION = "2.0.0"
+NETWORK_PASSPHRASE = "Public Global Stellar Network ; September 2015"
+TRANSFER_SERVER_SEP0024 = "https://anchor.example.com/sep24/"
+DIRECT_PAYMENT_SERVER = "https://anchor.example.com/sep31"
+WEB_AUTH_ENDPOINT = "https://anchor.example.com/auth"
+SIGNING_KEY = "{issuer}"
+ACCOUNTS = ["{issuer}"]
+
+[DOCUMENTATION]
+ORG_NAME = "Example Anchor"
+ORG_URL = "https://example.com"
+
+[[CURRENCIES]]
+code = "USDC"
+issuer = "{issuer}"
+display_decimals = 2
+is_asset_anchored = true
+"#,
+            issuer = ISSUER
+        );
+
+        let (toml, issues) = StellarToml::parse(&text).unwrap();
+        assert!(issues.is_empty(), "{:?}", issues);
+        assert_eq!(
+            toml.transfer_server_sep0024.as_deref(),
+            Some("https://anchor.example.com/sep24")
+        );
+        assert_eq!(toml.signing_key.as_deref(), Some(ISSUER));
+        assert_eq!(toml.accounts, vec![ISSUER.to_string()]);
+        assert_eq!(
+            toml.documentation.org_name.as_deref(),
+            Some("Example Anchor")
+        );
+        assert_eq!(toml.currencies.len(), 1);
+        assert_eq!(toml.currencies[0].code.as_deref(), Some("USDC"));
+        assert_eq!(toml.currencies[0].display_decimals, Some(2));
+        assert_eq!(toml.currencies[0].is_asset_anchored, Some(true));
+
+        // Formatting doesn't change the hash
+        let (reformatted, _) = StellarToml::parse(&text.replace(" = ", "=")).unwrap();
+        assert_eq!(toml.content_hash(), reformatted.content_hash());
+    }
+
+    #[test]
+    fn test_validation_errors_are_collected() {
+        let text = r#"
+WEB_AUTH_ENDPOINT = "https://anchor.example.com/auth"
+KYC_SERVER = "http://anchor.example.com/kyc"
+TRANSFER_SERVER = 42
+ACCOUNTS = ["not-an-account"]
+CURRENCIES = [{ code = "USDC", issuer = "GBAD" }, { issuer = "GBAD" }]
+"#;
+
+        let (toml, issues) = StellarToml::parse(text).unwrap();
+        assert_eq!(toml.currencies.len(), 2);
+        assert!(toml.accounts.is_empty());
+        assert_eq!(toml.kyc_server, None);
+        for expected in [
+            "KYC_SERVER http://anchor.example.com/kyc is not an https URL",
+            "WEB_AUTH_ENDPOINT is set without a SIGNING_KEY",
+            "TRANSFER_SERVER must be a string",
+            "ACCOUNTS entry not-an-account is not a Stellar account ID",
+            "CURRENCIES[0].issuer GBAD is not a Stellar account ID",
+            "CURRENCIES[1].code is missing",
+        ] {
+            assert!(
+                issues.iter().any(|i| i == expected),
+                "{} in {:?}",
+                expected,
+                issues
+            );
+        }
+
+        assert!(StellarToml::parse("not toml [").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_oversized_bodies_are_refused_without_buffering() {
+        use axum::{body::Body, routing::get, Router};
+
+        let chunk = || Ok::<_, std::io::Error>(vec![b'#'; 16 * 1024]);
+        let app = Router::new()
+            .route("/sized", get(|| async { "#".repeat(200 * 1024) }))
+            .route(
+                "/streamed",
+                get(move || async move { Body::from_stream(futures::stream::repeat_with(chunk)) }),
+            )
+            .route("/small", get(|| async { "VERSION = \"2.0.0\"" }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = Client::new();
+        let read = |path: &'static str| {
+            let client = client.clone();
+            async move {
+                let url = format!("http://{}{}", addr, path);
+                let response = client.get(&url).send().await.unwrap();
+                read_body_capped(response, MAX_STELLAR_TOML_BYTES, &url).await
+            }
+        };
+
+        // Refused on the declared length, and mid-stream for an endless body
+        let error = read("/sized").await.unwrap_err().to_string();
+        assert!(error.ends_with("is larger than 100KB"), "{}", error);
+        assert!(read("/streamed").await.is_err());
+        assert_eq!(read("/small").await.unwrap(), b"VERSION = \"2.0.0\"");
+    }
+
+    #[test]
+    fn test_normalize_domain() {
+        assert_eq!(
+            normalize_domain("Example.com."),
+            Some("example.com".to_string())
+        );
+        assert_eq!(
+            normalize_domain("https://example.com/.well-known/stellar.toml"),
+            Some("example.com".to_string())
+        );
+        assert_eq!(normalize_domain("  "), None);
+        for internal in [
+            "127.0.0.1",
+            "https://10.0.0.5/.well-known/stellar.toml",
+            "127.1",
+            "[::1]",
+            "example.com:8443",
+            "localhost",
+            "metadata",
+            "vault.internal",
+        ] {
+            assert_eq!(normalize_domain(internal), None, "{}", internal);
+        }
+    }
+
+    #[test]
+    fn test_endpoints_must_be_public() {
+        assert!(is_public_https_url("https://anchor.example.com/sep24"));
+        for url in [
+            "http://anchor.example.com/sep24",
+            "https://127.0.0.1/sep24",
+            "https://[::1]/sep24",
+            "https://anchor.example.com:8443/sep24",
+            "https://intranet/sep24",
+        ] {
+            assert!(!is_public_https_url(url), "{}", url);
+        }
+
+        let (toml, issues) =
+            StellarToml::parse("TRANSFER_SERVER_SEP0024 = \"https://169.254.169.254/sep24\"")
+                .unwrap();
+        assert_eq!(toml.transfer_server_sep0024, None);
+        assert_eq!(
+            issues,
+            vec!["TRANSFER_SERVER_SEP0024 https://169.254.169.254/sep24 is not on a public domain"]
+        );
+    }
+
+    #[test]
+    fn test_is_public_ip() {
+        for ip in ["1.1.1.1", "93.184.216.34", "2606:4700:4700::1111"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:10.0.0.1",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_names_resolving_to_private_addresses_are_refused() {
+        let error = untrusted_client()
+            .get("http://localhost/")
+            .send()
+            .await
+            .unwrap_err();
+        assert!(
+            format!("{:?}", error).contains("non-public address"),
+            "{:?}",
+            error
+        );
+    }
+}
@@ -0,0 +1,497 @@
+//! What changed between two analytics snapshots
+//!
+//! Anchors are matched by ID. Corridors are matched by `corridor_key`: their
+//! IDs are those of the daily `corridor_metrics` rows and change from one
+//! snapshot to the next.
+//!
+//! Two output shapes are available: a [`SnapshotDiff`] summary with per-field
+//! deltas and top movers, and a JSON Patch ([RFC 6902]) over a document that
+//! keys records rather than indexing them:
+//!
+//! ```json
+//! { "anchors": { "<anchor id>": { ... } }, "corridors": { "<corridor key>": { ... } } }
+//! ```
+//!
+//! [RFC 6902]: https://www.rfc-editor.org/rfc/rfc6902
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use uuid::Uuid;
+
+use crate::services::snapshot::SnapshotLeafKind;
+use crate::snapshot::schema::{AnalyticsSnapshot, SnapshotAnchorMetrics, SnapshotCorridorMetrics};
+
+/// Movers listed when the caller doesn't ask for a number
+pub const DEFAULT_TOP_MOVERS: usize = 10;
+
+/// How a diff is returned
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffFormat {
+    #[default]
+    Summary,
+    Patch,
+}
+
+/// A numeric field that changed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Delta {
+    pub from: f64,
+    pub to: f64,
+    pub delta: f64,
+}
+
+impl Delta {
+    fn between(from: f64, to: f64) -> Option<Self> {
+        (from != to).then_some(Self {
+            from,
+            to,
+            delta: to - from,
+        })
+    }
+
+    /// Change relative to the old value; `None` when the old value was zero
+    pub fn percent_change(&self) -> Option<f64> {
+        (self.from != 0.0).then(|| self.delta / self.from.abs() * 100.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusChange {
+    pub from: String,
+    pub to: String,
+}
+
+/// An anchor in both snapshots; only the fields that changed are set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnchorChange {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success_rate: Option<Delta>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reliability_score: Option<Delta>,
+    /// A missing volume counts as zero
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume_usd: Option<Delta>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<StatusChange>,
+}
+
+/// A corridor in both snapshots; only the fields that changed are set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorridorChange {
+    pub corridor_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success_rate: Option<Delta>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume_usd: Option<Delta>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AnchorDiff {
+    pub added: Vec<SnapshotAnchorMetrics>,
+    pub removed: Vec<SnapshotAnchorMetrics>,
+    pub changed: Vec<AnchorChange>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CorridorDiff {
+    pub added: Vec<SnapshotCorridorMetrics>,
+    pub removed: Vec<SnapshotCorridorMetrics>,
+    pub changed: Vec<CorridorChange>,
+}
+
+/// One field of one record, ranked among the largest relative changes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mover {
+    pub kind: SnapshotLeafKind,
+    /// Anchor ID or corridor key
+    pub key: String,
+    /// Anchor name or corridor key
+    pub label: String,
+    pub field: String,
+    #[serde(flatten)]
+    pub delta: Delta,
+    /// `None` when the old value was zero; those rank first
+    pub percent_change: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    pub from_epoch: u64,
+    pub to_epoch: u64,
+    pub from_timestamp: DateTime<Utc>,
+    pub to_timestamp: DateTime<Utc>,
+    pub anchors: AnchorDiff,
+    pub corridors: CorridorDiff,
+    pub top_movers: Vec<Mover>,
+}
+
+/// One JSON Patch operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+}
+
+/// Summarize what changed from `from` to `to`, listing at most `top` movers
+pub fn diff_snapshots(
+    from: &AnalyticsSnapshot,
+    to: &AnalyticsSnapshot,
+    top: usize,
+) -> SnapshotDiff {
+    let mut anchors = AnchorDiff::default();
+    let old_anchors = anchors_by_id(from);
+    let new_anchors = anchors_by_id(to);
+    for (id, old) in &old_anchors {
+        match new_anchors.get(id) {
+            Some(new) => {
+                let change = AnchorChange {
+                    id: new.id,
+                    name: new.name.clone(),
+                    success_rate: Delta::between(old.success_rate, new.success_rate),
+                    reliability_score: Delta::between(old.reliability_score, new.reliability_score),
+                    volume_usd: Delta::between(
+                        old.volume_usd.unwrap_or(0.0),
+                        new.volume_usd.unwrap_or(0.0),
+                    ),
+                    status: (old.status != new.status).then(|| StatusChange {
+                        from: old.status.clone(),
+                        to: new.status.clone(),
+                    }),
+                };
+                if change.success_rate.is_some()
+                    || change.reliability_score.is_some()
+                    || change.volume_usd.is_some()
+                    || change.status.is_some()
+                {
+                    anchors.changed.push(change);
+                }
+            }
+            None => anchors.removed.push((*old).clone()),
+        }
+    }
+    anchors.added = new_anchors
+        .iter()
+        .filter(|(id, _)| !old_anchors.contains_key(id))
+        .map(|(_, new)| (*new).clone())
+        .collect();
+
+    let mut corridors = CorridorDiff::default();
+    let old_corridors = corridors_by_key(from);
+    let new_corridors = corridors_by_key(to);
+    for (key, old) in &old_corridors {
+        match new_corridors.get(key) {
+            Some(new) => {
+                let change = CorridorChange {
+                    corridor_key: key.to_string(),
+                    success_rate: Delta::between(old.success_rate, new.success_rate),
+                    volume_usd: Delta::between(old.volume_usd, new.volume_usd),
+                };
+                if change.success_rate.is_some() || change.volume_usd.is_some() {
+                    corridors.changed.push(change);
+                }
+            }
+            None => corridors.removed.push((*old).clone()),
+        }
+    }
+    corridors.added = new_corridors
+        .iter()
+        .filter(|(key, _)| !old_corridors.contains_key(*key))
+        .map(|(_, new)| (*new).clone())
+        .collect();
+
+    let top_movers = top_movers(&anchors, &corridors, top);
+    SnapshotDiff {
+        from_epoch: from.epoch,
+        to_epoch: to.epoch,
+        from_timestamp: from.timestamp,
+        to_timestamp: to.timestamp,
+        anchors,
+        corridors,
+        top_movers,
+    }
+}
+
+/// JSON Patch turning `from`'s records into `to`'s, covering every field of
+/// every record
+pub fn json_patch(from: &AnalyticsSnapshot, to: &AnalyticsSnapshot) -> Vec<PatchOperation> {
+    let mut operations = Vec::new();
+    diff_records(
+        "/anchors",
+        &anchors_by_id(from)
+            .into_iter()
+            .map(|(id, m)| (id.to_string(), to_value(m)))
+            .collect(),
+        &anchors_by_id(to)
+            .into_iter()
+            .map(|(id, m)| (id.to_string(), to_value(m)))
+            .collect(),
+        &mut operations,
+    );
+    diff_records(
+        "/corridors",
+        &corridors_by_key(from)
+            .into_iter()
+            .map(|(key, m)| (key.to_string(), to_value(m)))
+            .collect(),
+        &corridors_by_key(to)
+            .into_iter()
+            .map(|(key, m)| (key.to_string(), to_value(m)))
+            .collect(),
+        &mut operations,
+    );
+    operations
+}
+
+fn anchors_by_id(snapshot: &AnalyticsSnapshot) -> BTreeMap<Uuid, &SnapshotAnchorMetrics> {
+    snapshot.anchor_metrics.iter().map(|m| (m.id, m)).collect()
+}
+
+fn corridors_by_key(snapshot: &AnalyticsSnapshot) -> BTreeMap<&str, &SnapshotCorridorMetrics> {
+    snapshot
+        .corridor_metrics
+        .iter()
+        .map(|m| (m.corridor_key.as_str(), m))
+        .collect()
+}
+
+fn top_movers(anchors: &AnchorDiff, corridors: &CorridorDiff, top: usize) -> Vec<Mover> {
+    let mut movers = Vec::new();
+    let mut push = |kind, key: &str, label: &str, field: &str, delta: &Option<Delta>| {
+        if let Some(delta) = delta {
+            movers.push(Mover {
+                kind,
+                key: key.to_string(),
+                label: label.to_string(),
+                field: field.to_string(),
+                delta: *delta,
+                percent_change: delta.percent_change(),
+            });
+        }
+    };
+    for change in &anchors.changed {
+        let key = change.id.to_string();
+        let kind = SnapshotLeafKind::Anchor;
+        push(
+            kind,
+            &key,
+            &change.name,
+            "success_rate",
+            &change.success_rate,
+        );
+        push(
+            kind,
+            &key,
+            &change.name,
+            "reliability_score",
+            &change.reliability_score,
+        );
+        push(kind, &key, &change.name, "volume_usd", &change.volume_usd);
+    }
+    for change in &corridors.changed {
+        let key = &change.corridor_key;
+        let kind = SnapshotLeafKind::Corridor;
+        push(kind, key, key, "success_rate", &change.success_rate);
+        push(kind, key, key, "volume_usd", &change.volume_usd);
+    }
+
+    let magnitude = |mover: &Mover| mover.percent_change.map_or(f64::INFINITY, f64::abs);
+    movers.sort_by(|a, b| {
+        magnitude(b)
+            .total_cmp(&magnitude(a))
+            .then_with(|| a.key.cmp(&b.key))
+            .then_with(|| a.field.cmp(&b.field))
+    });
+    movers.truncate(top);
+    movers
+}
+
+fn to_value<T: Serialize>(record: &T) -> Map<String, Value> {
+    match serde_json::to_value(record) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+fn diff_records(
+    prefix: &str,
+    old: &BTreeMap<String, Map<String, Value>>,
+    new: &BTreeMap<String, Map<String, Value>>,
+    operations: &mut Vec<PatchOperation>,
+) {
+    let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    for key in keys {
+        let path = format!("{}/{}", prefix, escape_pointer(key));
+        match (old.get(key), new.get(key)) {
+            (Some(_), None) => operations.push(PatchOperation::Remove { path }),
+            (None, Some(record)) => operations.push(PatchOperation::Add {
+                path,
+                value: Value::Object(record.clone()),
+            }),
+            (Some(old), Some(new)) => {
+                let fields: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+                for field in fields {
+                    let path = format!("{}/{}", path, escape_pointer(field));
+                    match (old.get(field), new.get(field)) {
+                        (Some(_), None) => operations.push(PatchOperation::Remove { path }),
+                        (None, Some(value)) => operations.push(PatchOperation::Add {
+                            path,
+                            value: value.clone(),
+                        }),
+                        (Some(a), Some(b)) if a != b => operations.push(PatchOperation::Replace {
+                            path,
+                            value: b.clone(),
+                        }),
+                        _ => {}
+                    }
+                }
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+/// Escape a key for use as a JSON Pointer ([RFC 6901]) segment
+///
+/// [RFC 6901]: https://www.rfc-editor.org/rfc/rfc6901
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn anchor(id: u128, reliability_score: f64, status: &str) -> SnapshotAnchorMetrics {
+        SnapshotAnchorMetrics {
+            id: Uuid::from_u128(id),
+            name: format!("Anchor{}", id),
+            stellar_account: format!("GANCHOR{}", id),
+            success_rate: 0.99,
+            failure_rate: 0.01,
+            reliability_score,
+            total_transactions: 1000,
+            successful_transactions: 990,
+            failed_transactions: 10,
+            avg_settlement_time_ms: Some(2000),
+            volume_usd: None,
+            status: status.to_string(),
+        }
+    }
+
+    fn corridor(id: u128, key: &str, volume_usd: f64) -> SnapshotCorridorMetrics {
+        SnapshotCorridorMetrics {
+            id: Uuid::from_u128(id),
+            corridor_key: key.to_string(),
+            asset_a_code: "USDC".to_string(),
+            asset_a_issuer: "ISSUER1".to_string(),
+            asset_b_code: "EURC".to_string(),
+            asset_b_issuer: "ISSUER2".to_string(),
+            total_transactions: 500,
+            successful_transactions: 475,
+            failed_transactions: 25,
+            success_rate: 95.0,
+            volume_usd,
+            avg_settlement_latency_ms: None,
+            liquidity_depth_usd: 100000.0,
+        }
+    }
+
+    fn snapshots() -> (AnalyticsSnapshot, AnalyticsSnapshot) {
+        let mut from = AnalyticsSnapshot::new(1, Utc::now());
+        from.add_anchor_metrics(anchor(1, 90.0, "green"));
+        from.add_anchor_metrics(anchor(2, 80.0, "green"));
+        from.add_anchor_metrics(anchor(3, 70.0, "yellow"));
+        from.add_corridor_metrics(corridor(10, "USDC->EURC", 1000.0));
+
+        let mut to = AnalyticsSnapshot::new(2, Utc::now());
+        to.add_anchor_metrics(anchor(1, 90.0, "green"));
+        to.add_anchor_metrics(anchor(2, 40.0, "red"));
+        to.add_anchor_metrics(anchor(4, 60.0, "green"));
+        // Same corridor under the next day's metrics row
+        to.add_corridor_metrics(corridor(11, "USDC->EURC", 1100.0));
+        (from, to)
+    }
+
+    #[test]
+    fn test_diff_snapshots() {
+        let (from, to) = snapshots();
+        let diff = diff_snapshots(&from, &to, DEFAULT_TOP_MOVERS);
+
+        assert_eq!(diff.anchors.added, vec![anchor(4, 60.0, "green")]);
+        assert_eq!(diff.anchors.removed, vec![anchor(3, 70.0, "yellow")]);
+        assert_eq!(diff.anchors.changed.len(), 1);
+        let change = &diff.anchors.changed[0];
+        assert_eq!(change.id, Uuid::from_u128(2));
+        assert_eq!(change.reliability_score.unwrap().delta, -40.0);
+        assert_eq!(change.success_rate, None);
+        assert_eq!(
+            change.status,
+            Some(StatusChange {
+                from: "green".to_string(),
+                to: "red".to_string()
+            })
+        );
+
+        assert!(diff.corridors.added.is_empty() && diff.corridors.removed.is_empty());
+        assert_eq!(diff.corridors.changed[0].volume_usd.unwrap().delta, 100.0);
+
+        // -50% outranks +10%
+        assert_eq!(diff.top_movers.len(), 2);
+        assert_eq!(diff.top_movers[0].field, "reliability_score");
+        assert_eq!(diff.top_movers[0].percent_change, Some(-50.0));
+        assert_eq!(diff.top_movers[1].kind, SnapshotLeafKind::Corridor);
+        assert_eq!(diff_snapshots(&from, &to, 1).top_movers.len(), 1);
+
+        assert_eq!(diff_snapshots(&from, &from, 10).top_movers, vec![]);
+    }
+
+    #[test]
+    fn test_json_patch() {
+        let (from, to) = snapshots();
+        let patch = json_patch(&from, &to);
+        let anchor_path = |id: u128| format!("/anchors/{}", Uuid::from_u128(id));
+
+        assert_eq!(
+            patch,
+            vec![
+                PatchOperation::Replace {
+                    path: format!("{}/reliability_score", anchor_path(2)),
+                    value: 40.0.into(),
+                },
+                PatchOperation::Replace {
+                    path: format!("{}/status", anchor_path(2)),
+                    value: "red".into(),
+                },
+                PatchOperation::Remove {
+                    path: anchor_path(3),
+                },
+                PatchOperation::Add {
+                    path: anchor_path(4),
+                    value: serde_json::to_value(anchor(4, 60.0, "green")).unwrap(),
+                },
+                PatchOperation::Replace {
+                    path: "/corridors/USDC->EURC/id".to_string(),
+                    value: Uuid::from_u128(11).to_string().into(),
+                },
+                PatchOperation::Replace {
+                    path: "/corridors/USDC->EURC/volume_usd".to_string(),
+                    value: 1100.0.into(),
+                },
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(&patch[2]).unwrap(),
+            serde_json::json!({ "op": "remove", "path": anchor_path(3) })
+        );
+        assert!(json_patch(&from, &from).is_empty());
+        assert_eq!(escape_pointer("a/b~c"), "a~1b~0c");
+    }
+}
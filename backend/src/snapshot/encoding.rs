@@ -0,0 +1,240 @@
+//! Canonical snapshot encoders, one per schema version
+//!
+//! A snapshot is always encoded with the encoder for its own
+//! `schema_version`, so snapshots stored under an older version keep
+//! re-hashing to the values committed for them. Encoders list their fields
+//! explicitly: a field added to the schema structs changes no hash until a
+//! new version encodes it.
+//!
+//! To evolve the schema, add the field (with `#[serde(default)]` so older
+//! stored snapshots still parse), add an encoder for the next version to
+//! [`ENCODERS`], bump [`SCHEMA_VERSION`](super::schema::SCHEMA_VERSION) and
+//! pin the new version's golden hashes in `tests/snapshot_schema_test.rs`.
+
+use serde_json::{Map, Number, Value};
+use std::collections::BTreeMap;
+
+use super::schema::{AnalyticsSnapshot, SnapshotAnchorMetrics, SnapshotCorridorMetrics};
+
+/// Encodes snapshots of one schema version into canonical JSON
+pub trait SnapshotEncoder: Send + Sync {
+    fn schema_version(&self) -> u32;
+
+    fn encode_anchor(&self, metrics: &SnapshotAnchorMetrics) -> Value;
+
+    fn encode_corridor(&self, metrics: &SnapshotCorridorMetrics) -> Value;
+
+    /// Encode the whole snapshot; arrays are encoded in their current order,
+    /// so callers normalize first
+    fn encode_snapshot(&self, snapshot: &AnalyticsSnapshot) -> Value {
+        object([
+            (
+                "schema_version",
+                Value::Number(snapshot.schema_version.into()),
+            ),
+            ("epoch", Value::Number(snapshot.epoch.into())),
+            ("timestamp", Value::String(snapshot.timestamp.to_rfc3339())),
+            (
+                "anchor_metrics",
+                Value::Array(
+                    snapshot
+                        .anchor_metrics
+                        .iter()
+                        .map(|m| self.encode_anchor(m))
+                        .collect(),
+                ),
+            ),
+            (
+                "corridor_metrics",
+                Value::Array(
+                    snapshot
+                        .corridor_metrics
+                        .iter()
+                        .map(|m| self.encode_corridor(m))
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+}
+
+/// The original layout: every field of the version 1 schema structs,
+/// encoded exactly as `SnapshotService::serialize_deterministically` wrote
+/// them to the `snapshots` table before versioned encoders existed
+pub struct SnapshotEncoderV1;
+
+impl SnapshotEncoder for SnapshotEncoderV1 {
+    fn schema_version(&self) -> u32 {
+        1
+    }
+
+    fn encode_anchor(&self, metrics: &SnapshotAnchorMetrics) -> Value {
+        object([
+            ("id", Value::String(metrics.id.to_string())),
+            ("name", Value::String(metrics.name.clone())),
+            (
+                "stellar_account",
+                Value::String(metrics.stellar_account.clone()),
+            ),
+            ("success_rate", encode_f64(metrics.success_rate)),
+            ("failure_rate", encode_f64(metrics.failure_rate)),
+            ("reliability_score", encode_f64(metrics.reliability_score)),
+            (
+                "total_transactions",
+                Value::Number(metrics.total_transactions.into()),
+            ),
+            (
+                "successful_transactions",
+                Value::Number(metrics.successful_transactions.into()),
+            ),
+            (
+                "failed_transactions",
+                Value::Number(metrics.failed_transactions.into()),
+            ),
+            (
+                "avg_settlement_time_ms",
+                metrics
+                    .avg_settlement_time_ms
+                    .map_or(Value::Null, |ms| Value::Number(ms.into())),
+            ),
+            (
+                "volume_usd",
+                metrics.volume_usd.map_or(Value::Null, encode_f64),
+            ),
+            ("status", Value::String(metrics.status.clone())),
+        ])
+    }
+
+    fn encode_corridor(&self, metrics: &SnapshotCorridorMetrics) -> Value {
+        object([
+            ("id", Value::String(metrics.id.to_string())),
+            ("corridor_key", Value::String(metrics.corridor_key.clone())),
+            ("asset_a_code", Value::String(metrics.asset_a_code.clone())),
+            (
+                "asset_a_issuer",
+                Value::String(metrics.asset_a_issuer.clone()),
+            ),
+            ("asset_b_code", Value::String(metrics.asset_b_code.clone())),
+            (
+                "asset_b_issuer",
+                Value::String(metrics.asset_b_issuer.clone()),
+            ),
+            (
+                "total_transactions",
+                Value::Number(metrics.total_transactions.into()),
+            ),
+            (
+                "successful_transactions",
+                Value::Number(metrics.successful_transactions.into()),
+            ),
+            (
+                "failed_transactions",
+                Value::Number(metrics.failed_transactions.into()),
+            ),
+            ("success_rate", encode_f64(metrics.success_rate)),
+            ("volume_usd", encode_f64(metrics.volume_usd)),
+            (
+                "avg_settlement_latency_ms",
+                metrics
+                    .avg_settlement_latency_ms
+                    .map_or(Value::Null, |ms| Value::Number(ms.into())),
+            ),
+            (
+                "liquidity_depth_usd",
+                encode_f64(metrics.liquidity_depth_usd),
+            ),
+        ])
+    }
+}
+
+/// Every schema version's encoder, oldest first
+static ENCODERS: &[&dyn SnapshotEncoder] = &[&SnapshotEncoderV1];
+
+/// Schema versions that can be encoded, oldest first
+pub fn supported_schema_versions() -> impl Iterator<Item = u32> {
+    ENCODERS.iter().map(|encoder| encoder.schema_version())
+}
+
+/// The encoder for a schema version
+///
+/// Fails as a `serde_json::Error` so callers keep their serialization error
+/// type.
+pub fn encoder_for(schema_version: u32) -> Result<&'static dyn SnapshotEncoder, serde_json::Error> {
+    ENCODERS
+        .iter()
+        .copied()
+        .find(|encoder| encoder.schema_version() == schema_version)
+        .ok_or_else(|| {
+            <serde_json::Error as serde::ser::Error>::custom(format!(
+                "Unsupported snapshot schema version {}",
+                schema_version
+            ))
+        })
+}
+
+/// Canonical JSON of a snapshot, encoded for its own schema version
+pub fn to_canonical_json(mut snapshot: AnalyticsSnapshot) -> Result<String, serde_json::Error> {
+    let encoder = encoder_for(snapshot.schema_version)?;
+    snapshot.normalize();
+    serde_json::to_string(&encoder.encode_snapshot(&snapshot))
+}
+
+/// JSON object with its keys in sorted order
+pub(crate) fn object<'a>(fields: impl IntoIterator<Item = (&'a str, Value)>) -> Value {
+    let sorted: BTreeMap<&str, Value> = fields.into_iter().collect();
+    let mut map = Map::new();
+    for (key, value) in sorted {
+        map.insert(key.to_string(), value);
+    }
+    Value::Object(map)
+}
+
+/// Deterministic JSON for an f64
+///
+/// Finite values use serde_json's shortest round-trip representation;
+/// NaN and infinities, which JSON numbers can't hold, become strings.
+fn encode_f64(value: f64) -> Value {
+    if value.is_nan() {
+        Value::String("NaN".to_string())
+    } else if value.is_infinite() {
+        Value::String(if value.is_sign_positive() {
+            "Infinity".to_string()
+        } else {
+            "-Infinity".to_string()
+        })
+    } else {
+        Number::from_f64(value)
+            .map(Value::Number)
+            .unwrap_or_else(|| Value::String(value.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_unknown_versions_are_rejected() {
+        assert!(encoder_for(1).is_ok());
+        assert!(encoder_for(0).is_err());
+
+        let mut snapshot = AnalyticsSnapshot::new(1, Utc::now());
+        snapshot.schema_version = 99;
+        let err = to_canonical_json(snapshot).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unsupported snapshot schema version 99"));
+    }
+
+    #[test]
+    fn test_encode_f64() {
+        assert_eq!(encode_f64(0.1).to_string(), "0.1");
+        assert_eq!(encode_f64(95.0).to_string(), "95.0");
+        assert_eq!(encode_f64(f64::NAN), Value::String("NaN".to_string()));
+        assert_eq!(
+            encode_f64(f64::NEG_INFINITY),
+            Value::String("-Infinity".to_string())
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::snapshot::encoding;
 use crate::snapshot::schema::AnalyticsSnapshot;
 use sha2::{Digest, Sha256};
 
@@ -11,16 +12,10 @@ impl SnapshotGenerator {
     /// 1. All arrays are sorted by object identifiers
     /// 2. JSON is serialized in canonical form (no extra whitespace, sorted keys)
     /// 3. Result is suitable for hashing
-    pub fn to_canonical_json(mut snapshot: AnalyticsSnapshot) -> Result<String, serde_json::Error> {
-        // Normalize the snapshot (sort all arrays)
-        snapshot.normalize();
-
-        // Convert to a JSON value to ensure key ordering
-        let value = serde_json::to_value(&snapshot)?;
-
-        // Serialize with no extra whitespace and keys sorted
-        // This produces canonical JSON suitable for hashing
-        serde_json::to_string(&value)
+    ///
+    /// Fields are encoded as the snapshot's `schema_version` defines them.
+    pub fn to_canonical_json(snapshot: AnalyticsSnapshot) -> Result<String, serde_json::Error> {
+        encoding::to_canonical_json(snapshot)
     }
 
     /// Generate SHA-256 hash of the snapshot
@@ -0,0 +1,207 @@
+//! Binary Merkle tree over snapshot records
+//!
+//! Leaves and interior nodes are hashed with distinct prefixes so a leaf can
+//! never be passed off as an interior node:
+//! - leaf: `SHA-256(0x00 || leaf bytes)`
+//! - node: `SHA-256(0x01 || left || right)`
+//!
+//! A level with an odd number of nodes promotes its last node unchanged to
+//! the next level. The root of an empty tree is `SHA-256("")`.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Hash the bytes of a single leaf
+pub fn leaf_hash(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+/// Hash two child nodes into their parent
+pub fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Which side of the path a sibling sits on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SiblingPosition {
+    Left,
+    Right,
+}
+
+/// One step of an inclusion proof, from the leaf up towards the root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofStep {
+    pub hash: [u8; 32],
+    pub position: SiblingPosition,
+}
+
+/// Merkle tree keeping every level so proofs can be read off directly
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    /// `levels[0]` holds the leaf hashes, the last level holds the root
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    /// Build a tree over already-hashed leaves, in the given order
+    pub fn from_leaf_hashes(leaves: Vec<[u8; 32]>) -> Self {
+        let mut levels = vec![leaves];
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(left, right),
+                    [last] => *last,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Self { levels }
+    }
+
+    /// Build a tree over raw leaf bytes, in the given order
+    pub fn from_leaves<T: AsRef<[u8]>>(leaves: &[T]) -> Self {
+        Self::from_leaf_hashes(leaves.iter().map(|l| leaf_hash(l.as_ref())).collect())
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    pub fn leaf(&self, index: usize) -> Option<[u8; 32]> {
+        self.levels[0].get(index).copied()
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        match self.levels.last().and_then(|level| level.first()) {
+            Some(root) => *root,
+            None => Sha256::digest([]).into(),
+        }
+    }
+
+    /// Sibling path proving the leaf at `index` is included under the root
+    pub fn proof(&self, index: usize) -> Option<Vec<ProofStep>> {
+        if index >= self.leaf_count() {
+            return None;
+        }
+
+        let mut steps = Vec::new();
+        let mut index = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            let position = if index & 1 == 0 {
+                SiblingPosition::Right
+            } else {
+                SiblingPosition::Left
+            };
+            // A promoted node has no sibling at this level
+            if let Some(hash) = level.get(index ^ 1) {
+                steps.push(ProofStep {
+                    hash: *hash,
+                    position,
+                });
+            }
+            index /= 2;
+        }
+        Some(steps)
+    }
+}
+
+/// Fold a leaf hash up its sibling path
+pub fn root_from_proof(leaf: [u8; 32], proof: &[ProofStep]) -> [u8; 32] {
+    proof.iter().fold(leaf, |hash, step| match step.position {
+        SiblingPosition::Left => node_hash(&step.hash, &hash),
+        SiblingPosition::Right => node_hash(&hash, &step.hash),
+    })
+}
+
+/// Whether `leaf` is included under `root` by the given sibling path
+pub fn verify_proof(leaf: [u8; 32], proof: &[ProofStep], root: [u8; 32]) -> bool {
+    root_from_proof(leaf, proof) == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("leaf-{}", i)).collect()
+    }
+
+    #[test]
+    fn test_every_leaf_proves_against_the_root() {
+        for n in 1..=9 {
+            let data = leaves(n);
+            let tree = MerkleTree::from_leaves(&data);
+            for (i, leaf) in data.iter().enumerate() {
+                let proof = tree.proof(i).unwrap();
+                assert!(
+                    verify_proof(leaf_hash(leaf.as_bytes()), &proof, tree.root()),
+                    "leaf {} of {}",
+                    i,
+                    n
+                );
+            }
+            assert!(tree.proof(n).is_none());
+        }
+    }
+
+    #[test]
+    fn test_small_trees() {
+        let single = MerkleTree::from_leaves(&["a"]);
+        assert_eq!(single.root(), leaf_hash(b"a"));
+        assert!(single.proof(0).unwrap().is_empty());
+
+        // The odd leaf is promoted rather than paired with itself
+        let three = MerkleTree::from_leaves(&["a", "b", "c"]);
+        let ab = node_hash(&leaf_hash(b"a"), &leaf_hash(b"b"));
+        assert_eq!(three.root(), node_hash(&ab, &leaf_hash(b"c")));
+        assert_eq!(
+            three.proof(2).unwrap(),
+            vec![ProofStep {
+                hash: ab,
+                position: SiblingPosition::Left,
+            }]
+        );
+
+        let empty = MerkleTree::from_leaf_hashes(Vec::new());
+        assert_eq!(
+            hex::encode(empty.root()),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_tampered_proofs_fail() {
+        let data = leaves(5);
+        let tree = MerkleTree::from_leaves(&data);
+        let proof = tree.proof(1).unwrap();
+
+        assert!(!verify_proof(leaf_hash(b"leaf-9"), &proof, tree.root()));
+
+        let mut flipped = proof.clone();
+        flipped[0].position = SiblingPosition::Right;
+        assert!(!verify_proof(leaf_hash(b"leaf-1"), &flipped, tree.root()));
+
+        // The children of an interior node can't be passed off as a leaf
+        let children = [leaf_hash(b"leaf-0"), leaf_hash(b"leaf-1")].concat();
+        assert!(!verify_proof(
+            leaf_hash(&children),
+            &proof[1..],
+            tree.root()
+        ));
+    }
+}
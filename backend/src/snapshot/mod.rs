@@ -1,7 +1,12 @@
+pub mod diff;
+pub mod encoding;
 pub mod generator;
+pub mod merkle;
 pub mod schema;
+pub mod verify;
 
 pub use generator::SnapshotGenerator;
+pub use merkle::MerkleTree;
 pub use schema::{
     AnalyticsSnapshot, SnapshotAnchorMetrics, SnapshotCorridorMetrics, SCHEMA_VERSION,
 };
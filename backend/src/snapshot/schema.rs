@@ -3,6 +3,9 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Snapshot schema version for backward compatibility
+///
+/// New snapshots are encoded with this version's encoder in
+/// [`encoding`](super::encoding); stored snapshots keep their own.
 pub const SCHEMA_VERSION: u32 = 1;
 
 /// Individual anchor metrics within a snapshot
@@ -0,0 +1,253 @@
+//! Independent verification of a published snapshot against the snapshot
+//! contract, used by the `stellar-insights-verify` binary
+//!
+//! The snapshot is re-encoded and re-hashed locally, then compared with the
+//! hash the contract holds for its epoch. Epochs are committed as the Merkle
+//! root over the snapshot's records; the SHA-256 of the whole canonical
+//! snapshot is accepted as well.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+use crate::services::contract::ContractService;
+use crate::services::snapshot::{CommittedSnapshot, SnapshotService, SnapshotTransaction};
+use crate::snapshot::generator::SnapshotGenerator;
+use crate::snapshot::schema::AnalyticsSnapshot;
+
+/// Which locally computed hash the contract's value matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Commitment {
+    MerkleRoot,
+    SnapshotHash,
+}
+
+impl Commitment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Commitment::MerkleRoot => "Merkle root",
+            Commitment::SnapshotHash => "snapshot hash",
+        }
+    }
+}
+
+/// A snapshot to verify and, when published by the API, the transaction
+/// that committed it
+#[derive(Debug, Clone)]
+pub struct PublishedSnapshot {
+    pub snapshot: AnalyticsSnapshot,
+    pub transaction: Option<SnapshotTransaction>,
+}
+
+impl PublishedSnapshot {
+    /// Parse either a bare snapshot or the API's committed snapshot response
+    pub fn from_json(json: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(json).context("Snapshot is not valid JSON")?;
+        if value.get("snapshot").is_some() {
+            let committed: CommittedSnapshot =
+                serde_json::from_value(value).context("Invalid committed snapshot")?;
+            return Ok(committed.into());
+        }
+        Ok(Self {
+            snapshot: serde_json::from_value(value).context("Invalid analytics snapshot")?,
+            transaction: None,
+        })
+    }
+
+    /// Fetch the snapshot committed for `epoch` from a Stellar Insights API
+    pub async fn fetch(api_url: &str, epoch: u64) -> Result<Self> {
+        let url = format!("{}/api/snapshots/{}", api_url.trim_end_matches('/'), epoch);
+        let response = reqwest::get(&url)
+            .await
+            .with_context(|| format!("Failed to fetch {}", url))?;
+        let status = response.status();
+        let body = response.text().await.context("Failed to read snapshot")?;
+        if !status.is_success() {
+            return Err(anyhow!("{} returned {}: {}", url, status, body));
+        }
+        Self::from_json(&body)
+    }
+}
+
+impl From<CommittedSnapshot> for PublishedSnapshot {
+    fn from(committed: CommittedSnapshot) -> Self {
+        Self {
+            snapshot: committed.snapshot,
+            transaction: committed.transaction,
+        }
+    }
+}
+
+/// Outcome of checking a snapshot against the contract
+#[derive(Debug, Clone, Serialize)]
+pub struct VerificationReport {
+    pub epoch: u64,
+    pub schema_version: u32,
+    pub snapshot_timestamp: DateTime<Utc>,
+    pub anchor_count: usize,
+    pub corridor_count: usize,
+    /// SHA-256 of the canonical snapshot JSON
+    pub snapshot_hash: String,
+    pub merkle_root: String,
+    /// Hash the contract holds for the epoch, if any
+    pub on_chain_hash: Option<String>,
+    /// Ledger the contract state was read at
+    pub read_ledger: Option<u64>,
+    pub commitment: Option<Commitment>,
+    /// The contract's `verify_snapshot_at_epoch` answer for the matched hash
+    pub verified_at_epoch: bool,
+    pub transaction: Option<SnapshotTransaction>,
+}
+
+impl VerificationReport {
+    pub fn passed(&self) -> bool {
+        self.commitment.is_some() && self.verified_at_epoch
+    }
+}
+
+/// Re-hash the snapshot and check it against the contract's record for its
+/// epoch
+pub async fn verify_snapshot(
+    published: PublishedSnapshot,
+    contract: &ContractService,
+) -> Result<VerificationReport> {
+    let PublishedSnapshot {
+        snapshot,
+        transaction,
+    } = published;
+    let epoch = snapshot.epoch;
+
+    let snapshot_hash = SnapshotGenerator::generate_hash_hex(snapshot.clone())
+        .context("Failed to hash snapshot")?;
+    let merkle_root = hex::encode(
+        SnapshotService::merkle_root(snapshot.clone())
+            .context("Failed to compute snapshot Merkle root")?,
+    );
+
+    let on_chain = contract
+        .get_snapshot_at_epoch(epoch)
+        .await
+        .context("Failed to read snapshot from contract")?;
+    let on_chain_hash = on_chain.as_ref().map(|s| s.hash.to_lowercase());
+
+    let commitment = match on_chain_hash.as_deref() {
+        Some(hash) if hash == merkle_root => Some(Commitment::MerkleRoot),
+        Some(hash) if hash == snapshot_hash => Some(Commitment::SnapshotHash),
+        _ => None,
+    };
+    let verified_at_epoch = match commitment {
+        Some(Commitment::MerkleRoot) => {
+            contract
+                .verify_snapshot_at_epoch(&merkle_root, epoch)
+                .await?
+        }
+        Some(Commitment::SnapshotHash) => {
+            contract
+                .verify_snapshot_at_epoch(&snapshot_hash, epoch)
+                .await?
+        }
+        None => false,
+    };
+
+    Ok(VerificationReport {
+        epoch,
+        schema_version: snapshot.schema_version,
+        snapshot_timestamp: snapshot.timestamp,
+        anchor_count: snapshot.anchor_metrics.len(),
+        corridor_count: snapshot.corridor_metrics.len(),
+        snapshot_hash,
+        merkle_root,
+        on_chain_hash,
+        read_ledger: on_chain.and_then(|s| s.ledger),
+        commitment,
+        verified_at_epoch,
+        transaction,
+    })
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let or_none = |v: Option<String>| v.unwrap_or_else(|| "-".to_string());
+
+        writeln!(f, "Snapshot verification for epoch {}", self.epoch)?;
+        writeln!(f, "  Schema version:    {}", self.schema_version)?;
+        writeln!(
+            f,
+            "  Snapshot time:     {}",
+            self.snapshot_timestamp.to_rfc3339()
+        )?;
+        writeln!(
+            f,
+            "  Records:           {} anchors, {} corridors",
+            self.anchor_count, self.corridor_count
+        )?;
+        writeln!(f, "  Snapshot hash:     {}", self.snapshot_hash)?;
+        writeln!(f, "  Merkle root:       {}", self.merkle_root)?;
+        writeln!(
+            f,
+            "  On-chain hash:     {}",
+            or_none(self.on_chain_hash.clone())
+        )?;
+        writeln!(
+            f,
+            "  Read at ledger:    {}",
+            or_none(self.read_ledger.map(|l| l.to_string()))
+        )?;
+        if let Some(tx) = &self.transaction {
+            writeln!(f, "  Committed in tx:   {}", tx.transaction_hash)?;
+            writeln!(
+                f,
+                "  Committed ledger:  {}",
+                or_none(tx.ledger.map(|l| l.to_string()))
+            )?;
+        }
+
+        let outcome = match (&self.on_chain_hash, self.commitment) {
+            (None, _) => "no snapshot on-chain for this epoch".to_string(),
+            (Some(_), None) => "on-chain hash matches neither local hash".to_string(),
+            (Some(_), Some(commitment)) if !self.verified_at_epoch => format!(
+                "{} matches get_snapshot but verify_snapshot_at_epoch rejected it",
+                commitment.as_str()
+            ),
+            (Some(_), Some(commitment)) => format!("{} matches on-chain", commitment.as_str()),
+        };
+        write!(
+            f,
+            "Result: {} ({})",
+            if self.passed() { "PASS" } else { "FAIL" },
+            outcome
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_bare_and_committed_snapshots() {
+        let snapshot = AnalyticsSnapshot::new(3, Utc::now());
+
+        let bare = serde_json::to_string(&snapshot).unwrap();
+        let published = PublishedSnapshot::from_json(&bare).unwrap();
+        assert_eq!(published.snapshot.epoch, 3);
+        assert!(published.transaction.is_none());
+
+        let committed = serde_json::json!({
+            "snapshot_id": "s1",
+            "epoch": 3,
+            "hash": null,
+            "merkle_root": "00",
+            "transaction": { "transaction_hash": "abc", "ledger": 7 },
+            "snapshot": snapshot,
+        });
+        let published = PublishedSnapshot::from_json(&committed.to_string()).unwrap();
+        assert_eq!(published.snapshot.epoch, 3);
+        assert_eq!(published.transaction.unwrap().ledger, Some(7));
+
+        assert!(PublishedSnapshot::from_json("{\"epoch\": 3}").is_err());
+    }
+}
@@ -1,14 +1,25 @@
 //! HTTP handlers for snapshot generation and submission
 
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{error, info};
+use uuid::Uuid;
 
 use crate::database::Database;
 use crate::services::contract::ContractService;
-use crate::services::snapshot::SnapshotService;
+use crate::services::snapshot::{
+    CommittedSnapshot, SnapshotInclusionProof, SnapshotLeafKind, SnapshotService,
+};
+use crate::snapshot::diff::{self, DiffFormat, DEFAULT_TOP_MOVERS};
+use crate::snapshot::{AnalyticsSnapshot, SCHEMA_VERSION};
 
 /// Response for snapshot generation
 #[derive(Debug, Serialize)]
@@ -16,6 +27,7 @@ pub struct SnapshotResponse {
     pub epoch: u64,
     pub timestamp: String,
     pub hash: String,
+    pub merkle_root: String,
     pub schema_version: u32,
     pub anchor_count: usize,
     pub corridor_count: usize,
@@ -71,7 +83,8 @@ pub async fn generate_snapshot(
                 epoch: result.epoch,
                 timestamp: result.timestamp.to_rfc3339(),
                 hash: result.hash,
-                schema_version: 1, // From SCHEMA_VERSION
+                merkle_root: result.merkle_root,
+                schema_version: SCHEMA_VERSION,
                 anchor_count: result.anchor_count,
                 corridor_count: result.corridor_count,
                 submission: result.submission_result.map(|sr| SubmissionInfo {
@@ -102,6 +115,124 @@ pub async fn generate_snapshot(
     }
 }
 
+/// The snapshot committed for an epoch, with its hashes and the transaction
+/// that committed it
+///
+/// GET /api/snapshots/:epoch
+pub async fn get_committed_snapshot(
+    State(state): State<SnapshotAppState>,
+    Path(epoch): Path<u64>,
+) -> Result<Json<CommittedSnapshot>, SnapshotError> {
+    state
+        .snapshot_service
+        .get_committed_snapshot(epoch)
+        .await
+        .map_err(|e| {
+            error!("Failed to load snapshot for epoch {}: {}", epoch, e);
+            SnapshotError::GenerationError(e.to_string())
+        })?
+        .map(Json)
+        .ok_or_else(|| {
+            SnapshotError::NotFound(format!("No committed snapshot for epoch {}", epoch))
+        })
+}
+
+/// Query for an inclusion proof: exactly one record to prove
+#[derive(Debug, Deserialize)]
+pub struct ProofQuery {
+    pub anchor_id: Option<Uuid>,
+    pub corridor_id: Option<Uuid>,
+}
+
+/// Inclusion proof for one record of the snapshot committed for an epoch:
+/// the leaf, its sibling path up to the Merkle root, and the transaction that
+/// committed the root
+///
+/// GET /api/snapshots/:epoch/proof?anchor_id=<uuid>
+/// GET /api/snapshots/:epoch/proof?corridor_id=<uuid>
+pub async fn get_inclusion_proof(
+    State(state): State<SnapshotAppState>,
+    Path(epoch): Path<u64>,
+    Query(query): Query<ProofQuery>,
+) -> Result<Json<SnapshotInclusionProof>, SnapshotError> {
+    let (kind, id) = match (query.anchor_id, query.corridor_id) {
+        (Some(id), None) => (SnapshotLeafKind::Anchor, id),
+        (None, Some(id)) => (SnapshotLeafKind::Corridor, id),
+        _ => {
+            return Err(SnapshotError::InvalidRequest(
+                "Exactly one of anchor_id or corridor_id is required".to_string(),
+            ))
+        }
+    };
+
+    state
+        .snapshot_service
+        .inclusion_proof(epoch, kind, id)
+        .await
+        .map_err(|e| {
+            error!("Failed to build inclusion proof for epoch {}: {}", epoch, e);
+            SnapshotError::GenerationError(e.to_string())
+        })?
+        .map(Json)
+        .ok_or_else(|| {
+            SnapshotError::NotFound(format!(
+                "No {} {} in a committed snapshot for epoch {}",
+                kind.as_str(),
+                id,
+                epoch
+            ))
+        })
+}
+
+/// Query for a diff between the snapshots of two epochs
+#[derive(Debug, Deserialize)]
+pub struct DiffQuery {
+    pub from: u64,
+    pub to: u64,
+    #[serde(default)]
+    pub format: DiffFormat,
+    /// Number of top movers to list (summary format only)
+    pub top: Option<usize>,
+}
+
+/// What changed between the snapshots stored for two epochs: added and
+/// removed records, per-field deltas and top movers, or a JSON Patch with
+/// `format=patch`
+///
+/// GET /api/snapshots/diff?from=<epoch>&to=<epoch>[&format=summary|patch][&top=<n>]
+pub async fn get_snapshot_diff(
+    State(state): State<SnapshotAppState>,
+    Query(query): Query<DiffQuery>,
+) -> Result<Response, SnapshotError> {
+    let from = load_snapshot(&state, query.from).await?;
+    let to = load_snapshot(&state, query.to).await?;
+
+    Ok(match query.format {
+        DiffFormat::Summary => Json(diff::diff_snapshots(
+            &from,
+            &to,
+            query.top.unwrap_or(DEFAULT_TOP_MOVERS),
+        ))
+        .into_response(),
+        DiffFormat::Patch => Json(diff::json_patch(&from, &to)).into_response(),
+    })
+}
+
+async fn load_snapshot(
+    state: &SnapshotAppState,
+    epoch: u64,
+) -> Result<AnalyticsSnapshot, SnapshotError> {
+    state
+        .snapshot_service
+        .get_snapshot(epoch)
+        .await
+        .map_err(|e| {
+            error!("Failed to load snapshot for epoch {}: {}", epoch, e);
+            SnapshotError::GenerationError(e.to_string())
+        })?
+        .ok_or_else(|| SnapshotError::NotFound(format!("No snapshot for epoch {}", epoch)))
+}
+
 /// Health check for contract service
 ///
 /// GET /api/snapshots/contract/health
@@ -139,6 +270,8 @@ pub enum SnapshotError {
     SubmissionError(String),
     ConnectionError(String),
     ConfigError(String),
+    InvalidRequest(String),
+    NotFound(String),
 }
 
 impl IntoResponse for SnapshotError {
@@ -150,6 +283,8 @@ impl IntoResponse for SnapshotError {
             SnapshotError::SubmissionError(msg) => (StatusCode::BAD_GATEWAY, msg),
             SnapshotError::ConnectionError(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
             SnapshotError::ConfigError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            SnapshotError::InvalidRequest(msg) => (StatusCode::BAD_REQUEST, msg),
+            SnapshotError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
         };
 
         (
@@ -162,3 +297,14 @@ impl IntoResponse for SnapshotError {
             .into_response()
     }
 }
+
+/// Build the public snapshot routes: committed snapshots, inclusion proofs,
+/// diffs and contract health
+pub fn routes(state: SnapshotAppState) -> Router {
+    Router::new()
+        .route("/api/snapshots/diff", get(get_snapshot_diff))
+        .route("/api/snapshots/:epoch", get(get_committed_snapshot))
+        .route("/api/snapshots/:epoch/proof", get(get_inclusion_proof))
+        .route("/api/snapshots/contract/health", get(contract_health_check))
+        .with_state(state)
+}
@@ -136,6 +136,8 @@ pub enum WsMessage {
         name: String,
         reliability_score: f64,
         status: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        uptime_percentage: Option<f64>,
     },
     /// New payment event
     NewPayment {
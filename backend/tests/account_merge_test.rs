@@ -1,16 +1,26 @@
+mod common;
+
 use sqlx::SqlitePool;
 use std::sync::Arc;
 
 use axum::body::{to_bytes, Body};
 use axum::http::{Request, StatusCode};
-use stellar_insights_backend::rpc::StellarRpcClient;
-use stellar_insights_backend::services::account_merge_detector::AccountMergeDetector;
+use common::{merge_ledger, PASSPHRASE};
+use stellar_insights_backend::ingestion::ledger_meta::decode_meta;
+use stellar_insights_backend::services::account_merge_detector::{
+    AccountMergeDetector, AccountMergeEvent,
+};
 use tower::util::ServiceExt;
 
+/// The two account merges, worth 136 XLM in total, decoded from a ledger
+fn merges(sequence: u32) -> Vec<AccountMergeEvent> {
+    let ledger = merge_ledger(sequence, &[(1, 11, 1_255_000_000), (2, 12, 105_000_000)]);
+    decode_meta(&ledger, PASSPHRASE).unwrap().account_merges
+}
+
 #[sqlx::test]
 async fn test_account_merge_detector_process_and_stats(pool: SqlitePool) {
-    let rpc_client = Arc::new(StellarRpcClient::new_with_defaults(true));
-    let detector = AccountMergeDetector::new(pool.clone(), rpc_client);
+    let detector = AccountMergeDetector::new(pool.clone());
 
     sqlx::query(
         "INSERT INTO ledgers (sequence, hash, close_time, transaction_count, operation_count) VALUES (200, 'ledger_hash_200', '2026-01-22T10:30:00Z', 0, 0)",
@@ -20,9 +30,9 @@ async fn test_account_merge_detector_process_and_stats(pool: SqlitePool) {
     .expect("failed to insert ledger row");
 
     let detected = detector
-        .process_ledger_operations(200)
+        .record_merges(&merges(200))
         .await
-        .expect("failed to process account merges");
+        .expect("failed to record account merges");
     assert_eq!(detected, 2);
 
     let recent = detector.get_recent_merges(10).await.unwrap();
@@ -42,8 +52,7 @@ async fn test_account_merge_detector_process_and_stats(pool: SqlitePool) {
 
 #[sqlx::test]
 async fn test_account_merge_detector_is_idempotent(pool: SqlitePool) {
-    let rpc_client = Arc::new(StellarRpcClient::new_with_defaults(true));
-    let detector = AccountMergeDetector::new(pool.clone(), rpc_client);
+    let detector = AccountMergeDetector::new(pool.clone());
 
     sqlx::query(
         "INSERT INTO ledgers (sequence, hash, close_time, transaction_count, operation_count) VALUES (201, 'ledger_hash_201', '2026-01-22T10:31:00Z', 0, 0)",
@@ -52,8 +61,8 @@ async fn test_account_merge_detector_is_idempotent(pool: SqlitePool) {
     .await
     .expect("failed to insert ledger row");
 
-    let first = detector.process_ledger_operations(201).await.unwrap();
-    let second = detector.process_ledger_operations(201).await.unwrap();
+    let first = detector.record_merges(&merges(201)).await.unwrap();
+    let second = detector.record_merges(&merges(201)).await.unwrap();
 
     assert_eq!(first, 2);
     assert_eq!(second, 0);
@@ -64,8 +73,7 @@ async fn test_account_merge_detector_is_idempotent(pool: SqlitePool) {
 
 #[sqlx::test]
 async fn test_account_merge_routes(pool: SqlitePool) {
-    let rpc_client = Arc::new(StellarRpcClient::new_with_defaults(true));
-    let detector = Arc::new(AccountMergeDetector::new(pool.clone(), rpc_client));
+    let detector = Arc::new(AccountMergeDetector::new(pool.clone()));
 
     sqlx::query(
         "INSERT INTO ledgers (sequence, hash, close_time, transaction_count, operation_count) VALUES (202, 'ledger_hash_202', '2026-01-22T10:32:00Z', 0, 0)",
@@ -74,7 +82,7 @@ async fn test_account_merge_routes(pool: SqlitePool) {
     .await
     .expect("failed to insert ledger row");
 
-    detector.process_ledger_operations(202).await.unwrap();
+    detector.record_merges(&merges(202)).await.unwrap();
 
     let app = stellar_insights_backend::api::account_merges::routes(detector);
 
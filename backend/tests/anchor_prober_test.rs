@@ -0,0 +1,210 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use stellar_insights_backend::database::Database;
+use stellar_insights_backend::services::anchor_prober::{
+    AnchorProber, AnchorProberConfig, ProbeClient, ProbeResponse, ProbeSummary,
+};
+use stellar_insights_backend::services::stellar_toml::StellarToml;
+use stellar_insights_backend::websocket::{WsMessage, WsState};
+use uuid::Uuid;
+
+/// Seeded green anchor: 99% of 10,000 transactions succeeded
+const CIRCLE_ID: &str = "c1b1f1a1-1111-4111-a111-111111111111";
+const CIRCLE_ACCOUNT: &str = "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN";
+/// Seeded yellow anchor
+const ANCHORUSD_ID: &str = "c2b2f2a2-2222-4222-a222-222222222222";
+
+/// Responses held in memory; URLs without one can't be reached
+#[derive(Default)]
+struct FakeClient {
+    responses: Mutex<HashMap<String, (u16, String)>>,
+}
+
+impl FakeClient {
+    fn serve(&self, url: &str, response: Option<(u16, &str)>) {
+        let mut responses = self.responses.lock().unwrap();
+        match response {
+            Some((status, body)) => responses.insert(url.to_string(), (status, body.to_string())),
+            None => responses.remove(url),
+        };
+    }
+}
+
+#[async_trait::async_trait]
+impl ProbeClient for FakeClient {
+    async fn get(&self, url: &str) -> Result<ProbeResponse> {
+        self.responses
+            .lock()
+            .unwrap()
+            .get(url)
+            .map(|(status_code, body)| ProbeResponse {
+                status_code: *status_code,
+                body: body.clone(),
+            })
+            .ok_or_else(|| anyhow!("Failed to reach {}", url))
+    }
+}
+
+const INFO: &str =
+    r#"{"deposit": {"USDC": {"enabled": true}}, "withdraw": {"USDC": {"enabled": true}}}"#;
+
+#[sqlx::test]
+async fn test_probes_roll_up_into_uptime_and_status(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool));
+    let anchor_id = Uuid::parse_str(CIRCLE_ID).unwrap();
+    let toml_text = format!(
+        r#"
+TRANSFER_SERVER_SEP0024 = "https://api.circle.com/sep24"
+DIRECT_PAYMENT_SERVER = "https://api.circle.com/sep31"
+WEB_AUTH_ENDPOINT = "https://api.circle.com/auth"
+SIGNING_KEY = "{}"
+"#,
+        CIRCLE_ACCOUNT
+    );
+    let (toml, issues) = StellarToml::parse(&toml_text).unwrap();
+    db.record_stellar_toml(anchor_id, "circle.com", &toml, &issues, Utc::now())
+        .await
+        .unwrap();
+
+    let sep10_url = format!("https://api.circle.com/auth?account={}", CIRCLE_ACCOUNT);
+    let client = Arc::new(FakeClient::default());
+    client.serve(
+        "https://circle.com/.well-known/stellar.toml",
+        Some((200, &toml_text)),
+    );
+    client.serve("https://api.circle.com/sep24/info", Some((200, INFO)));
+    client.serve(
+        "https://api.circle.com/sep31/info",
+        Some((200, r#"{"receive": {}}"#)),
+    );
+    client.serve(&sep10_url, Some((200, r#"{"transaction": "AAAA"}"#)));
+
+    let ws_state = Arc::new(WsState::new());
+    let mut updates = ws_state.tx.subscribe();
+    let prober = AnchorProber::new(
+        Arc::clone(&db),
+        client.clone(),
+        Arc::clone(&ws_state),
+        AnchorProberConfig::default(),
+    );
+
+    assert_eq!(
+        prober.probe_all().await.unwrap(),
+        ProbeSummary {
+            anchors: 1,
+            probes: 4,
+            failed: 0,
+            status_changes: 0,
+            errors: 0,
+        }
+    );
+    let anchor = db.get_anchor_by_id(anchor_id).await.unwrap().unwrap();
+    assert_eq!(anchor.uptime_percentage, Some(100.0));
+    assert_eq!(anchor.status, "green");
+    assert!(updates.try_recv().is_err());
+
+    // SEP-24 is down for maintenance, SEP-31 unreachable and SEP-10 answers
+    // without a challenge
+    client.serve("https://api.circle.com/sep24/info", Some((503, "")));
+    client.serve("https://api.circle.com/sep31/info", None);
+    client.serve(&sep10_url, Some((200, r#"{"error": "try later"}"#)));
+
+    let summary = prober.probe_all().await.unwrap();
+    assert_eq!((summary.failed, summary.status_changes), (2, 1));
+
+    // 6 of 8 probes answered
+    let anchor = db.get_anchor_by_id(anchor_id).await.unwrap().unwrap();
+    assert_eq!(anchor.uptime_percentage, Some(75.0));
+    assert_eq!(anchor.status, "red");
+    match updates.try_recv().unwrap() {
+        WsMessage::AnchorUpdate {
+            anchor_id,
+            status,
+            uptime_percentage,
+            ..
+        } => {
+            assert_eq!(anchor_id, CIRCLE_ID);
+            assert_eq!(status, "red");
+            assert_eq!(uptime_percentage, Some(75.0));
+        }
+        other => panic!("unexpected message {:?}", other),
+    }
+
+    let detail = db.get_anchor_detail(anchor_id).await.unwrap().unwrap();
+    let endpoints: HashMap<_, _> = detail
+        .endpoint_uptime
+        .iter()
+        .map(|e| (e.sep.as_str(), e))
+        .collect();
+    assert_eq!(endpoints.len(), 4);
+    assert_eq!(endpoints["sep1"].uptime_percentage, 100.0);
+    assert_eq!(endpoints["sep24"].probes, 2);
+    assert_eq!(endpoints["sep24"].up_probes, 1);
+    assert_eq!(endpoints["sep24"].uptime_percentage, 50.0);
+    assert_eq!(endpoints["sep31"].up_probes, 1);
+    assert_eq!(endpoints["sep10"].up_probes, 2);
+    assert_eq!(endpoints["sep10"].schema_valid_probes, 1);
+    assert_eq!(endpoints["sep10"].url, sep10_url);
+
+    // On-chain metrics updates keep the status in line with the uptime
+    let anchor = db
+        .update_anchor_metrics(anchor_id, 10000, 9950, 50, Some(2000), None)
+        .await
+        .unwrap();
+    assert_eq!(anchor.status, "red");
+    assert_eq!(anchor.uptime_percentage, Some(75.0));
+}
+
+#[sqlx::test]
+async fn test_an_anchor_that_cant_be_stored_doesnt_stop_the_round(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool.clone()));
+    for (id, domain) in [(CIRCLE_ID, "circle.com"), (ANCHORUSD_ID, "anchorusd.com")] {
+        let (toml, issues) = StellarToml::parse("").unwrap();
+        db.record_stellar_toml(
+            Uuid::parse_str(id).unwrap(),
+            domain,
+            &toml,
+            &issues,
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+    }
+    sqlx::query(&format!(
+        "CREATE TRIGGER reject_circle_probes BEFORE INSERT ON anchor_endpoint_probes
+         WHEN NEW.anchor_id = '{}' BEGIN SELECT RAISE(ABORT, 'disk full'); END",
+        CIRCLE_ID
+    ))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let client = Arc::new(FakeClient::default());
+    client.serve(
+        "https://circle.com/.well-known/stellar.toml",
+        Some((200, "")),
+    );
+    client.serve(
+        "https://anchorusd.com/.well-known/stellar.toml",
+        Some((200, "")),
+    );
+    let prober = AnchorProber::new(
+        Arc::clone(&db),
+        client,
+        Arc::new(WsState::new()),
+        AnchorProberConfig::default(),
+    );
+
+    let summary = prober.probe_all().await.unwrap();
+    assert_eq!((summary.anchors, summary.errors), (1, 1));
+
+    let anchor = db
+        .get_anchor_by_id(Uuid::parse_str(ANCHORUSD_ID).unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(anchor.uptime_percentage, Some(100.0));
+}
@@ -0,0 +1,210 @@
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use common::static_price_feed;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use stellar_insights_backend::database::Database;
+use stellar_insights_backend::ingestion::DataIngestionService;
+use stellar_insights_backend::models::{CreateAnchorRequest, PaymentRecord};
+use stellar_insights_backend::scoring::active_model;
+use stellar_insights_backend::services::anchor_reliability::{
+    AnchorReliabilityConfig, AnchorReliabilityService,
+};
+use stellar_insights_backend::services::price_feed::PriceFeedClient;
+use uuid::Uuid;
+
+fn price_feed() -> Arc<PriceFeedClient> {
+    static_price_feed(HashMap::from([
+        ("USDC:GUSDCISSUER".to_string(), "usd-coin".to_string()),
+        ("NGN:GNGNISSUER".to_string(), "naira".to_string()),
+    ]))
+}
+
+fn payment(
+    created_at: DateTime<Utc>,
+    source: (&str, &str),
+    destination: (&str, &str),
+    amount: f64,
+    successful: bool,
+) -> PaymentRecord {
+    let cross_asset = source != destination;
+    PaymentRecord {
+        id: Uuid::new_v4().to_string(),
+        transaction_hash: "txhash".to_string(),
+        source_account: "GSOURCE".to_string(),
+        destination_account: "GDEST".to_string(),
+        asset_type: "credit_alphanum4".to_string(),
+        asset_code: Some(destination.0.to_string()),
+        asset_issuer: Some(destination.1.to_string()),
+        source_asset_code: source.0.to_string(),
+        source_asset_issuer: source.1.to_string(),
+        destination_asset_code: destination.0.to_string(),
+        destination_asset_issuer: destination.1.to_string(),
+        amount,
+        operation_type: Some(
+            if cross_asset {
+                "path_payment_strict_send"
+            } else {
+                "payment"
+            }
+            .to_string(),
+        ),
+        source_amount: cross_asset.then_some(amount / 1500.0),
+        send_max: None,
+        dest_min: None,
+        path: None,
+        successful,
+        result_code: Some(
+            if successful {
+                "op_success"
+            } else {
+                "op_underfunded"
+            }
+            .to_string(),
+        ),
+        timestamp: Some(created_at),
+        submission_time: None,
+        confirmation_time: None,
+        created_at,
+    }
+}
+
+async fn anchor(db: &Database, name: &str, account: &str, asset_code: &str) -> Uuid {
+    let anchor = db
+        .create_anchor(CreateAnchorRequest {
+            name: name.to_string(),
+            stellar_account: account.to_string(),
+            home_domain: None,
+        })
+        .await
+        .unwrap();
+    let id = Uuid::parse_str(&anchor.id).unwrap();
+    db.create_asset(id, asset_code.to_string(), account.to_string())
+        .await
+        .unwrap();
+    id
+}
+
+#[sqlx::test]
+async fn test_scores_anchors_from_their_asset_payments(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool));
+    let usdc_anchor = anchor(&db, "USDC Anchor", "GUSDCISSUER", "USDC").await;
+    let ngn_anchor = anchor(&db, "NGN Anchor", "GNGNISSUER", "NGN").await;
+    let idle_anchor = db
+        .create_anchor(CreateAnchorRequest {
+            name: "No Assets".to_string(),
+            stellar_account: "GIDLE".to_string(),
+            home_domain: None,
+        })
+        .await
+        .unwrap();
+
+    let usdc = ("USDC", "GUSDCISSUER");
+    let ngn = ("NGN", "GNGNISSUER");
+    let now = Utc::now();
+    db.save_payments(vec![
+        payment(now, usdc, usdc, 100.0, true),
+        payment(now, usdc, usdc, 100.0, true),
+        payment(now, usdc, usdc, 100.0, false),
+        // Sends 100 USDC and delivers 150000 NGN
+        payment(now, usdc, ngn, 150_000.0, true),
+        // Older than the scoring window
+        payment(now - Duration::days(30), usdc, usdc, 5000.0, false),
+    ])
+    .await
+    .unwrap();
+
+    let service = AnchorReliabilityService::new(
+        Arc::clone(&db),
+        price_feed(),
+        AnchorReliabilityConfig::default(),
+    );
+    // Seeded anchors with assets are scored too
+    let seeded: i64 = sqlx::query_scalar("SELECT COUNT(DISTINCT anchor_id) FROM assets")
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+    assert_eq!(service.score_anchors().await.unwrap() as i64, seeded);
+
+    let detail = db.get_anchor_detail(usdc_anchor).await.unwrap().unwrap();
+    let reliability = detail.reliability.unwrap();
+    assert_eq!(reliability.total_assets, 1);
+    assert_eq!(reliability.total_volume_usd, 300.0);
+    assert_eq!(reliability.weighted_success_rate, 75.0);
+    assert_eq!(reliability.asset_diversity_score, 10.0);
+    assert_eq!(reliability.assets[0].asset_code, "USDC");
+    assert_eq!(reliability.assets[0].total_transactions, 4);
+    assert_eq!(reliability.assets[0].failed_transactions, 1);
+    assert_eq!(reliability.assets[0].total_volume, 300.0);
+    assert_eq!(detail.reliability_history.len(), 1);
+
+    // 150000 NGN is worth 100 USD, so the USDC anchor carries the most volume
+    // even though the NGN anchor moved more units
+    let ngn_detail = db.get_anchor_detail(ngn_anchor).await.unwrap().unwrap();
+    let ngn_reliability = ngn_detail.reliability.unwrap();
+    assert_eq!(ngn_reliability.assets[0].total_volume, 150_000.0);
+    assert!((ngn_reliability.total_volume_usd - 100.0).abs() < 1e-9);
+    assert_eq!(ngn_reliability.weighted_success_rate, 100.0);
+    assert_eq!(reliability.volume_score, 100.0);
+    assert!(ngn_reliability.volume_score < 100.0);
+
+    let idle_detail = db
+        .get_anchor_detail(Uuid::parse_str(&idle_anchor.id).unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(idle_detail.reliability.is_none());
+    assert!(idle_detail.reliability_history.is_empty());
+
+    // Each run adds to the history, newest first
+    service.score_anchors().await.unwrap();
+    let detail = db.get_anchor_detail(usdc_anchor).await.unwrap().unwrap();
+    assert_eq!(detail.reliability_history.len(), 2);
+    assert_eq!(
+        detail.reliability.unwrap().computed_at,
+        detail.reliability_history[0].computed_at
+    );
+}
+
+#[sqlx::test]
+async fn test_anchor_sync_counts_failed_payments(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool));
+    db.create_anchor(CreateAnchorRequest {
+        name: "Payee".to_string(),
+        stellar_account: "GDEST".to_string(),
+        home_domain: None,
+    })
+    .await
+    .unwrap();
+
+    let usdc = ("USDC", "GUSDCISSUER");
+    let now = Utc::now();
+    db.save_payments(vec![
+        payment(now, usdc, usdc, 100.0, true),
+        payment(now, usdc, usdc, 100.0, true),
+        payment(now, usdc, usdc, 100.0, true),
+        payment(now, usdc, usdc, 100.0, false),
+    ])
+    .await
+    .unwrap();
+
+    let ingestion = DataIngestionService::new(Arc::new(common::horizon::client()), Arc::clone(&db));
+    ingestion.sync_anchor_metrics().await.unwrap();
+
+    let anchor = db
+        .get_anchor_by_stellar_account("GDEST")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(anchor.total_transactions, 4);
+    assert_eq!(anchor.successful_transactions, 3);
+    assert_eq!(anchor.failed_transactions, 1);
+    // Only the applied payments moved funds
+    assert_eq!(anchor.total_volume_usd, 300.0);
+    assert_eq!(
+        anchor.scoring_version.as_deref(),
+        Some(active_model().version.as_str())
+    );
+}
@@ -1,3 +1,5 @@
+mod common;
+
 use axum::{
     body::Body,
     http::{Request, StatusCode},
@@ -14,7 +16,6 @@ use stellar_insights_backend::database::Database;
 use stellar_insights_backend::state::AppState;
 use stellar_insights_backend::websocket::WsState;
 use stellar_insights_backend::ingestion::DataIngestionService;
-use stellar_insights_backend::rpc::StellarRpcClient;
 
 async fn setup_test_db() -> SqlitePool {
     let pool = SqlitePool::connect(":memory:").await.unwrap();
@@ -27,7 +28,7 @@ async fn setup_test_db() -> SqlitePool {
 
 fn create_test_router(db: Arc<Database>) -> Router {
     let ws_state = Arc::new(WsState::new());
-    let rpc_client = Arc::new(StellarRpcClient::new_with_defaults(true));
+    let rpc_client = Arc::new(common::horizon::client());
     let ingestion = Arc::new(DataIngestionService::new(rpc_client, Arc::clone(&db)));
     let state = AppState { db, ws_state, ingestion };
     Router::new()
@@ -0,0 +1,197 @@
+// A local stand-in for Horizon and RPC serving fixed fixtures, so tests never
+// reach the network. Endpoints without a fixture answer with no records.
+
+use axum::extract::Query;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use stellar_insights_backend::network::{NetworkConfig, StellarNetwork};
+use stellar_insights_backend::rpc::StellarRpcClient;
+use stellar_xdr::curr::{
+    Limits, OperationResult, OperationResultTr, PaymentResult, TransactionResult,
+    TransactionResultExt, TransactionResultResult, WriteXdr,
+};
+
+/// Issuers of the fixture assets, most trustlines first
+pub const ASSETS: [(&str, &str); 4] = [
+    (
+        "USDC",
+        "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN",
+    ),
+    (
+        "AQUA",
+        "GBNZILSTVQZ4R7IKQDGHYGY2QXL5QOFJYQMXPKWRRM5PAV7Y4M67AQUA",
+    ),
+    (
+        "yXLM",
+        "GARDNV3Q7YGT4AKSDF25A9NTVAMQUD8UAKGHXONL6R2FMBXVGFZDFZEM",
+    ),
+    (
+        "BTC",
+        "GDPJALI4AZKUU2W426U5WKMAT6CN3AJRPIIRYR2YM54TL2GDEMNQERFT",
+    ),
+];
+
+/// A client for a stub served on an ephemeral localhost port. The stub runs
+/// on the test's runtime and stops with it.
+pub fn client() -> StellarRpcClient {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let addr = listener.local_addr().unwrap();
+    let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+
+    let app = Router::new()
+        .route("/horizon/order_book", get(order_book))
+        .route("/horizon/assets", get(assets))
+        .route("/horizon/payments", get(payments))
+        .route("/rpc", axum::routing::post(rpc))
+        .fallback(no_records);
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    StellarRpcClient::new_with_config(NetworkConfig {
+        rpc_url: format!("http://{}/rpc", addr),
+        horizon_url: format!("http://{}/horizon", addr),
+        ..NetworkConfig::for_network(StellarNetwork::Mainnet)
+    })
+}
+
+fn records(records: Vec<Value>) -> Json<Value> {
+    Json(json!({ "_embedded": { "records": records } }))
+}
+
+async fn no_records() -> Json<Value> {
+    records(Vec::new())
+}
+
+async fn rpc() -> Json<Value> {
+    Json(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "error": { "code": -32601, "message": "method not found" }
+    }))
+}
+
+fn offer(price: &str, amount: &str, n: i64, d: i64) -> Value {
+    json!({ "price": price, "amount": amount, "price_r": { "n": n, "d": d } })
+}
+
+/// The asset named by Horizon's `<side>_asset_*` query parameters
+fn query_asset(query: &HashMap<String, String>, side: &str) -> Value {
+    json!({
+        "asset_type": query.get(&format!("{}_asset_type", side)).cloned().unwrap_or_default(),
+        "asset_code": query.get(&format!("{}_asset_code", side)),
+        "asset_issuer": query.get(&format!("{}_asset_issuer", side)),
+    })
+}
+
+/// The same three bids and asks for every pair
+async fn order_book(Query(query): Query<HashMap<String, String>>) -> Json<Value> {
+    Json(json!({
+        "bids": [
+            offer("0.9950", "1000.0000000", 199, 200),
+            offer("0.9900", "2500.0000000", 99, 100),
+            offer("0.9850", "5000.0000000", 197, 200),
+        ],
+        "asks": [
+            offer("1.0050", "1200.0000000", 201, 200),
+            offer("1.0100", "3000.0000000", 101, 100),
+            offer("1.0150", "4500.0000000", 203, 200),
+        ],
+        "base": query_asset(&query, "selling"),
+        "counter": query_asset(&query, "buying"),
+    }))
+}
+
+async fn assets() -> Json<Value> {
+    records(
+        ASSETS
+            .iter()
+            .enumerate()
+            .map(|(i, (code, issuer))| {
+                let trustlines = 10_000 - i as i64 * 2_000;
+                json!({
+                    "asset_type": "credit_alphanum4",
+                    "asset_code": code,
+                    "asset_issuer": issuer,
+                    "num_claimable_balances": 0,
+                    "num_liquidity_pools": 0,
+                    "num_contracts": 0,
+                    "accounts": {
+                        "authorized": trustlines,
+                        "authorized_to_maintain_liabilities": 0,
+                        "unauthorized": trustlines / 20,
+                    },
+                    "claimable_balances_amount": "0.0",
+                    "liquidity_pools_amount": "0.0",
+                    "contracts_amount": "0.0",
+                    "balances": {
+                        "authorized": format!("{}.0000000", trustlines * 1_000),
+                        "authorized_to_maintain_liabilities": "0.0",
+                        "unauthorized": "0.0",
+                    },
+                    "flags": {
+                        "auth_required": false,
+                        "auth_revocable": false,
+                        "auth_immutable": false,
+                        "auth_clawback_enabled": false,
+                    },
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Recent USDC payments in ledger 51565800: two applied and, in between, one
+/// whose transaction failed with `op_underfunded`
+pub const PAYMENT_IDS: [&str; 3] = [
+    "221473424592080897",
+    "221473424592084993",
+    "221473424592089089",
+];
+
+async fn payments() -> Json<Value> {
+    let failed = TransactionResult {
+        fee_charged: 100,
+        result: TransactionResultResult::TxFailed(
+            vec![OperationResult::OpInner(OperationResultTr::Payment(
+                PaymentResult::Underfunded,
+            ))]
+            .try_into()
+            .unwrap(),
+        ),
+        ext: TransactionResultExt::V0,
+    };
+    let failed_xdr = base64::Engine::encode(
+        &base64::engine::general_purpose::STANDARD,
+        failed.to_xdr(Limits::none()).unwrap(),
+    );
+    let (usdc, issuer) = ASSETS[0];
+
+    records(
+        PAYMENT_IDS
+            .iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let successful = i != 1;
+                json!({
+                    "id": id,
+                    "paging_token": id,
+                    "transaction_hash": format!("{:064x}", i + 1),
+                    "source_account": "GSOURCE",
+                    "destination": "GDEST",
+                    "asset_type": "credit_alphanum4",
+                    "asset_code": usdc,
+                    "asset_issuer": issuer,
+                    "amount": "100.0000000",
+                    "created_at": "2026-01-22T10:30:00Z",
+                    "type": "payment",
+                    "transaction_successful": successful,
+                    "transaction": {
+                        "result_xdr": if successful { "" } else { failed_xdr.as_str() },
+                    },
+                })
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,518 @@
+// Shared fixtures: XDR builders and on-disk ledger batches for the file
+// source, corridor payments and hourly metrics, and corridor API plumbing
+#![allow(dead_code)]
+
+pub mod horizon;
+
+use anyhow::{anyhow, Result};
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::Router;
+use chrono::{DateTime, Duration, Timelike, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use stellar_insights_backend::cache::CacheManager;
+use stellar_insights_backend::database::Database;
+use stellar_insights_backend::ingestion::ledger_meta::transaction_hash;
+use stellar_insights_backend::models::corridor::PaymentRecord;
+use stellar_insights_backend::rpc::file_source::write_batch;
+use stellar_insights_backend::rpc::{FileLedgerSource, LedgerSource, StellarRpcClient};
+use stellar_insights_backend::services::aggregation::HourlyCorridorMetrics;
+use stellar_insights_backend::services::analytics::compute_metrics_from_payments;
+use stellar_insights_backend::services::distribution::sketches_from_payments;
+use stellar_insights_backend::services::price_feed::{
+    default_asset_mapping, PriceFeedClient, PriceFeedConfig, PriceFeedProvider,
+};
+use stellar_xdr::curr::{
+    AccountId, AccountMergeResult, AlphaNum4, Asset, AssetCode4, DependentTxCluster,
+    ExtensionPoint, GeneralizedTransactionSet, Hash, LedgerCloseMeta, LedgerCloseMetaExt,
+    LedgerCloseMetaV1, LedgerCloseMetaV2, LedgerEntry, LedgerEntryChange, LedgerEntryChanges,
+    LedgerEntryData, LedgerEntryExt, LedgerHeader, LedgerHeaderExt, LedgerHeaderHistoryEntry,
+    LedgerHeaderHistoryEntryExt, LiquidityPoolConstantProductParameters, LiquidityPoolEntry,
+    LiquidityPoolEntryBody, LiquidityPoolEntryConstantProduct, Memo, MuxedAccount, Operation,
+    OperationBody, OperationMeta, OperationResult, OperationResultTr, ParallelTxExecutionStage,
+    ParallelTxsComponent, PaymentOp, PaymentResult, PoolId, Preconditions, PublicKey,
+    SequenceNumber, StellarValue, StellarValueExt, TimePoint, Transaction, TransactionEnvelope,
+    TransactionExt, TransactionMeta, TransactionMetaV1, TransactionMetaV4, TransactionPhase,
+    TransactionResult, TransactionResultExt, TransactionResultMeta, TransactionResultMetaV1,
+    TransactionResultPair, TransactionResultResult, TransactionSetV1, TransactionV1Envelope,
+    TxSetComponent, TxSetComponentTxsMaybeDiscountedFee, Uint256, VecM,
+};
+use tempfile::TempDir;
+use tower::util::ServiceExt;
+
+pub const PASSPHRASE: &str = "Test SDF Network ; September 2015";
+pub const CLOSE_TIME: u64 = 1_734_032_457;
+
+pub fn account(byte: u8) -> MuxedAccount {
+    MuxedAccount::Ed25519(Uint256([byte; 32]))
+}
+
+pub fn account_id(byte: u8) -> AccountId {
+    AccountId(PublicKey::PublicKeyTypeEd25519(Uint256([byte; 32])))
+}
+
+pub fn usdc() -> Asset {
+    Asset::CreditAlphanum4(AlphaNum4 {
+        asset_code: AssetCode4(*b"USDC"),
+        issuer: account_id(9),
+    })
+}
+
+pub fn transaction(source: u8, seq: i64, operations: Vec<OperationBody>) -> Transaction {
+    Transaction {
+        source_account: account(source),
+        fee: 100,
+        seq_num: SequenceNumber(seq),
+        cond: Preconditions::None,
+        memo: Memo::None,
+        operations: operations
+            .into_iter()
+            .map(|body| Operation {
+                source_account: None,
+                body,
+            })
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap(),
+        ext: TransactionExt::V0,
+    }
+}
+
+pub fn v1_envelope(tx: Transaction) -> TransactionEnvelope {
+    TransactionEnvelope::Tx(TransactionV1Envelope {
+        tx,
+        signatures: VecM::default(),
+    })
+}
+
+pub fn result_meta(
+    envelope: &TransactionEnvelope,
+    fee_charged: i64,
+    result: TransactionResultResult,
+) -> TransactionResultMeta {
+    TransactionResultMeta {
+        result: TransactionResultPair {
+            transaction_hash: Hash(transaction_hash(envelope, PASSPHRASE).unwrap()),
+            result: TransactionResult {
+                fee_charged,
+                result,
+                ext: TransactionResultExt::V0,
+            },
+        },
+        fee_processing: LedgerEntryChanges(VecM::default()),
+        tx_apply_processing: TransactionMeta::V0(VecM::default()),
+    }
+}
+
+/// Attach ledger entry changes to a transaction's first operation
+pub fn with_changes(
+    mut meta: TransactionResultMeta,
+    changes: Vec<LedgerEntryChange>,
+) -> TransactionResultMeta {
+    meta.tx_apply_processing = TransactionMeta::V1(TransactionMetaV1 {
+        tx_changes: LedgerEntryChanges(VecM::default()),
+        operations: vec![OperationMeta {
+            changes: LedgerEntryChanges(changes.try_into().unwrap()),
+        }]
+        .try_into()
+        .unwrap(),
+    });
+    meta
+}
+
+fn ledger_header(sequence: u32, close_time: u64, ledger_version: u32) -> LedgerHeaderHistoryEntry {
+    let zero = || Hash([0; 32]);
+    let mut ledger_hash = [0u8; 32];
+    ledger_hash[..4].copy_from_slice(&sequence.to_be_bytes());
+
+    let header = LedgerHeader {
+        ledger_version,
+        previous_ledger_hash: zero(),
+        scp_value: StellarValue {
+            tx_set_hash: zero(),
+            close_time: TimePoint(close_time),
+            upgrades: VecM::default(),
+            ext: StellarValueExt::Basic,
+        },
+        tx_set_result_hash: zero(),
+        bucket_list_hash: zero(),
+        ledger_seq: sequence,
+        total_coins: 0,
+        fee_pool: 0,
+        inflation_seq: 0,
+        id_pool: 0,
+        base_fee: 100,
+        base_reserve: 5_000_000,
+        max_tx_set_size: 1000,
+        skip_list: [zero(), zero(), zero(), zero()],
+        ext: LedgerHeaderExt::V0,
+    };
+
+    LedgerHeaderHistoryEntry {
+        hash: Hash(ledger_hash),
+        header,
+        ext: LedgerHeaderHistoryEntryExt::V0,
+    }
+}
+
+/// The classic phase of a generalized tx set
+fn classic_phase(envelopes: Vec<TransactionEnvelope>) -> TransactionPhase {
+    let component =
+        TxSetComponent::TxsetCompTxsMaybeDiscountedFee(TxSetComponentTxsMaybeDiscountedFee {
+            base_fee: None,
+            txs: envelopes.try_into().unwrap(),
+        });
+    TransactionPhase::V0(vec![component].try_into().unwrap())
+}
+
+pub fn ledger_close_meta(
+    sequence: u32,
+    close_time: u64,
+    envelopes: Vec<TransactionEnvelope>,
+    tx_processing: Vec<TransactionResultMeta>,
+) -> LedgerCloseMeta {
+    LedgerCloseMeta::V1(LedgerCloseMetaV1 {
+        ext: LedgerCloseMetaExt::V0,
+        ledger_header: ledger_header(sequence, close_time, 21),
+        tx_set: GeneralizedTransactionSet::V1(TransactionSetV1 {
+            previous_ledger_hash: Hash([0; 32]),
+            phases: vec![classic_phase(envelopes)].try_into().unwrap(),
+        }),
+        tx_processing: tx_processing.try_into().unwrap(),
+        upgrades_processing: VecM::default(),
+        scp_info: VecM::default(),
+        total_byte_size_of_live_soroban_state: 0,
+        evicted_keys: VecM::default(),
+        unused: VecM::default(),
+    })
+}
+
+/// A protocol 23 ledger: `classic` transactions in the classic phase,
+/// `soroban` ones in a single parallel execution stage, and every
+/// transaction's meta as `TransactionMetaV4`
+pub fn ledger_close_meta_v2(
+    sequence: u32,
+    close_time: u64,
+    classic: Vec<TransactionEnvelope>,
+    soroban: Vec<TransactionEnvelope>,
+    tx_processing: Vec<TransactionResultMeta>,
+) -> LedgerCloseMeta {
+    let soroban_phase = TransactionPhase::V1(ParallelTxsComponent {
+        base_fee: None,
+        execution_stages: vec![ParallelTxExecutionStage(
+            vec![DependentTxCluster(soroban.try_into().unwrap())]
+                .try_into()
+                .unwrap(),
+        )]
+        .try_into()
+        .unwrap(),
+    });
+    let tx_processing: Vec<TransactionResultMetaV1> = tx_processing
+        .into_iter()
+        .map(|meta| TransactionResultMetaV1 {
+            ext: ExtensionPoint::V0,
+            result: meta.result,
+            fee_processing: meta.fee_processing,
+            tx_apply_processing: TransactionMeta::V4(TransactionMetaV4 {
+                ext: ExtensionPoint::V0,
+                tx_changes_before: LedgerEntryChanges(VecM::default()),
+                operations: VecM::default(),
+                tx_changes_after: LedgerEntryChanges(VecM::default()),
+                soroban_meta: None,
+                events: VecM::default(),
+                diagnostic_events: VecM::default(),
+            }),
+            post_tx_apply_fee_processing: LedgerEntryChanges(VecM::default()),
+        })
+        .collect();
+
+    LedgerCloseMeta::V2(LedgerCloseMetaV2 {
+        ext: LedgerCloseMetaExt::V0,
+        ledger_header: ledger_header(sequence, close_time, 23),
+        tx_set: GeneralizedTransactionSet::V1(TransactionSetV1 {
+            previous_ledger_hash: Hash([0; 32]),
+            phases: vec![classic_phase(classic), soroban_phase]
+                .try_into()
+                .unwrap(),
+        }),
+        tx_processing: tx_processing.try_into().unwrap(),
+        upgrades_processing: VecM::default(),
+        scp_info: VecM::default(),
+        total_byte_size_of_live_soroban_state: 0,
+        evicted_keys: VecM::default(),
+    })
+}
+
+/// A ledger without transactions, closing five seconds after its predecessor
+pub fn empty_ledger(sequence: u32) -> LedgerCloseMeta {
+    ledger_close_meta(
+        sequence,
+        CLOSE_TIME + sequence as u64 * 5,
+        Vec::new(),
+        Vec::new(),
+    )
+}
+
+/// A ledger with one account merge transaction per `(source, destination, stroops)`
+pub fn merge_ledger(sequence: u32, merges: &[(u8, u8, i64)]) -> LedgerCloseMeta {
+    let mut envelopes = Vec::new();
+    let mut tx_processing = Vec::new();
+
+    for (source, destination, stroops) in merges {
+        let envelope = v1_envelope(transaction(
+            *source,
+            1,
+            vec![OperationBody::AccountMerge(account(*destination))],
+        ));
+        tx_processing.push(result_meta(
+            &envelope,
+            100,
+            TransactionResultResult::TxSuccess(
+                vec![OperationResult::OpInner(OperationResultTr::AccountMerge(
+                    AccountMergeResult::Success(*stroops),
+                ))]
+                .try_into()
+                .unwrap(),
+            ),
+        ));
+        envelopes.push(envelope);
+    }
+
+    ledger_close_meta(
+        sequence,
+        CLOSE_TIME + sequence as u64 * 5,
+        envelopes,
+        tx_processing,
+    )
+}
+
+/// A ledger with one successful USDC payment of `stroops`
+pub fn payment_ledger(sequence: u32, stroops: i64) -> LedgerCloseMeta {
+    let envelope = v1_envelope(transaction(
+        1,
+        sequence as i64,
+        vec![OperationBody::Payment(PaymentOp {
+            destination: account(2),
+            asset: usdc(),
+            amount: stroops,
+        })],
+    ));
+    let meta = result_meta(
+        &envelope,
+        100,
+        TransactionResultResult::TxSuccess(
+            vec![OperationResult::OpInner(OperationResultTr::Payment(
+                PaymentResult::Success,
+            ))]
+            .try_into()
+            .unwrap(),
+        ),
+    );
+    ledger_close_meta(
+        sequence,
+        CLOSE_TIME + sequence as u64 * 5,
+        vec![envelope],
+        vec![meta],
+    )
+}
+
+/// A constant-product pool entry change
+pub fn pool_change(
+    id: u8,
+    asset_a: Asset,
+    asset_b: Asset,
+    reserve_a: i64,
+    reserve_b: i64,
+) -> LedgerEntryChange {
+    LedgerEntryChange::Created(LedgerEntry {
+        last_modified_ledger_seq: 0,
+        data: LedgerEntryData::LiquidityPool(LiquidityPoolEntry {
+            liquidity_pool_id: PoolId(Hash([id; 32])),
+            body: LiquidityPoolEntryBody::LiquidityPoolConstantProduct(
+                LiquidityPoolEntryConstantProduct {
+                    params: LiquidityPoolConstantProductParameters {
+                        asset_a,
+                        asset_b,
+                        fee: 30,
+                    },
+                    reserve_a,
+                    reserve_b,
+                    total_pool_shares: (reserve_a + reserve_b) / 2,
+                    pool_shares_trust_line_count: 100 + id as i64,
+                },
+            ),
+        }),
+        ext: LedgerEntryExt::V0,
+    })
+}
+
+/// A ledger whose single transaction writes the given ledger entry changes
+pub fn changes_ledger(sequence: u32, changes: Vec<LedgerEntryChange>) -> LedgerCloseMeta {
+    let envelope = v1_envelope(transaction(1, sequence as i64, Vec::new()));
+    let meta = with_changes(
+        result_meta(
+            &envelope,
+            100,
+            TransactionResultResult::TxSuccess(VecM::default()),
+        ),
+        changes,
+    );
+    ledger_close_meta(
+        sequence,
+        CLOSE_TIME + sequence as u64 * 5,
+        vec![envelope],
+        vec![meta],
+    )
+}
+
+/// Write ledgers into a temp directory as batches of `per_file` ledgers
+pub fn fixture_dir(ledgers: &[LedgerCloseMeta], per_file: usize) -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    for batch in ledgers.chunks(per_file) {
+        write_batch(dir.path(), batch).unwrap();
+    }
+    dir
+}
+
+/// Empty ledgers `start..=end` on disk, plus a file source over them
+pub fn empty_ledger_source(
+    start: u32,
+    end: u32,
+    per_file: usize,
+) -> (TempDir, Arc<dyn LedgerSource>) {
+    let ledgers: Vec<_> = (start..=end).map(empty_ledger).collect();
+    let dir = fixture_dir(&ledgers, per_file);
+    let source = file_source(&dir);
+    (dir, source)
+}
+
+pub fn file_source(dir: &TempDir) -> Arc<dyn LedgerSource> {
+    Arc::new(FileLedgerSource::open(dir.path(), PASSPHRASE).unwrap())
+}
+
+// ============================================================================
+// Corridor Metrics
+// ============================================================================
+
+pub fn current_hour() -> DateTime<Utc> {
+    Utc::now()
+        .with_minute(0)
+        .and_then(|t| t.with_second(0))
+        .and_then(|t| t.with_nanosecond(0))
+        .unwrap()
+}
+
+/// A payment of `amount` from `source` to `destination`, confirmed at
+/// `confirmed` and settled in `settle_seconds`
+pub fn settled_payment(
+    source: (&str, &str),
+    destination: (&str, &str),
+    amount: f64,
+    confirmed: DateTime<Utc>,
+    settle_seconds: i64,
+) -> PaymentRecord {
+    PaymentRecord {
+        id: uuid::Uuid::new_v4(),
+        source_asset_code: source.0.to_string(),
+        source_asset_issuer: source.1.to_string(),
+        destination_asset_code: destination.0.to_string(),
+        destination_asset_issuer: destination.1.to_string(),
+        amount,
+        source_amount: None,
+        send_max: None,
+        dest_min: None,
+        path: Vec::new(),
+        successful: true,
+        failure_reason: None,
+        timestamp: confirmed,
+        submission_time: Some(confirmed - Duration::seconds(settle_seconds)),
+        confirmation_time: Some(confirmed),
+    }
+}
+
+/// The hourly metrics of one corridor's payments, as `AggregationService`
+/// builds them
+pub fn hourly(hour_bucket: DateTime<Utc>, payments: &[PaymentRecord]) -> HourlyCorridorMetrics {
+    let metrics = compute_metrics_from_payments(payments);
+    assert_eq!(metrics.len(), 1, "payments span more than one corridor");
+    let mut sketches = sketches_from_payments(payments);
+    let sketch = sketches.remove(&metrics[0].corridor_key).unwrap();
+
+    HourlyCorridorMetrics::from_corridor_metrics(&metrics[0], hour_bucket, sketch)
+}
+
+// ============================================================================
+// Corridor API
+// ============================================================================
+
+/// Fixed prices, so tests never reach CoinGecko: a dime per XLM, a dollar per
+/// USDC and 1500 NGN to the dollar
+pub struct StaticPrices;
+
+#[async_trait::async_trait]
+impl PriceFeedProvider for StaticPrices {
+    async fn fetch_price(&self, asset_id: &str) -> Result<f64> {
+        match asset_id {
+            "stellar" => Ok(0.1),
+            "usd-coin" => Ok(1.0),
+            "naira" => Ok(1.0 / 1500.0),
+            _ => Err(anyhow!("No price for {}", asset_id)),
+        }
+    }
+
+    async fn fetch_prices(&self, asset_ids: &[String]) -> Result<HashMap<String, f64>> {
+        let mut prices = HashMap::new();
+        for id in asset_ids {
+            prices.insert(id.clone(), self.fetch_price(id).await?);
+        }
+        Ok(prices)
+    }
+
+    fn name(&self) -> &str {
+        "static"
+    }
+}
+
+/// `StaticPrices` for the assets in `mapping`
+pub fn static_price_feed(mapping: HashMap<String, String>) -> Arc<PriceFeedClient> {
+    Arc::new(PriceFeedClient::with_provider(
+        Arc::new(StaticPrices),
+        PriceFeedConfig::default(),
+        mapping,
+    ))
+}
+
+pub type ApiState = (
+    Arc<Database>,
+    Arc<CacheManager>,
+    Arc<StellarRpcClient>,
+    Arc<PriceFeedClient>,
+);
+
+/// State for the corridor handlers over `db`, the Horizon stub and
+/// `StaticPrices`
+pub async fn api_state(db: Arc<Database>) -> ApiState {
+    let cache = Arc::new(CacheManager::new(Default::default()).await.unwrap());
+    let rpc_client = Arc::new(horizon::client());
+    (
+        db,
+        cache,
+        rpc_client,
+        static_price_feed(default_asset_mapping()),
+    )
+}
+
+/// Status and JSON body of a GET, `Value::Null` when the body isn't JSON
+pub async fn get_json(app: Router, uri: &str) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
@@ -0,0 +1,277 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use stellar_insights_backend::database::Database;
+use stellar_insights_backend::models::PaymentRecord;
+use stellar_insights_backend::services::aggregation::{
+    AggregationConfig, AggregationService, RollupPeriod,
+};
+use uuid::Uuid;
+
+/// Monday 2 March 2026, 10:00 UTC
+fn hour() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 3, 2, 10, 0, 0).unwrap()
+}
+
+/// A USDC payment created at `created_at`, settled in `latency_ms` or failed
+/// with `op_underfunded` when there is none
+fn payment(created_at: DateTime<Utc>, latency_ms: Option<i64>) -> PaymentRecord {
+    let result_code = match latency_ms {
+        Some(_) => "op_success",
+        None => "op_underfunded",
+    };
+
+    PaymentRecord {
+        id: Uuid::new_v4().to_string(),
+        transaction_hash: Uuid::new_v4().to_string(),
+        source_account: "GSOURCE".to_string(),
+        destination_account: "GDEST".to_string(),
+        asset_type: "credit_alphanum4".to_string(),
+        asset_code: Some("USDC".to_string()),
+        asset_issuer: Some("GISSUER".to_string()),
+        source_asset_code: "USDC".to_string(),
+        source_asset_issuer: "GISSUER".to_string(),
+        destination_asset_code: "USDC".to_string(),
+        destination_asset_issuer: "GISSUER".to_string(),
+        amount: 25.0,
+        operation_type: Some("payment".to_string()),
+        source_amount: None,
+        send_max: None,
+        dest_min: None,
+        path: None,
+        successful: latency_ms.is_some(),
+        result_code: Some(result_code.to_string()),
+        timestamp: Some(created_at),
+        submission_time: latency_ms.map(|ms| created_at - Duration::milliseconds(ms)),
+        confirmation_time: latency_ms.map(|_| created_at),
+        created_at,
+    }
+}
+
+/// Ten payments settled in 1 to 10 seconds in the first hour, five settled in
+/// 20 to 24 seconds and one failure in the second, and three still coming in
+/// during the third
+async fn seed_payments(db: &Database) {
+    let mut payments: Vec<PaymentRecord> = (1..=10)
+        .map(|s| payment(hour() + Duration::minutes(s * 5), Some(s * 1_000)))
+        .collect();
+    let (second, third) = (hour() + Duration::hours(1), hour() + Duration::hours(2));
+    for s in 20..25 {
+        payments.push(payment(second + Duration::minutes(s), Some(s * 1_000)));
+    }
+    payments.push(payment(second + Duration::minutes(59), None));
+    for m in 0..3 {
+        payments.push(payment(third + Duration::minutes(m), Some(1_000)));
+    }
+    db.save_payments(payments).await.unwrap();
+}
+
+type HourlyTotals = (DateTime<Utc>, i64, i64, Option<i32>, Option<i32>, u64);
+
+/// Transactions, failures, p50 and p90 latency and latency samples per hour
+async fn hourly_totals(db: &Database) -> Vec<HourlyTotals> {
+    db.fetch_hourly_metrics_by_timerange(hour() - Duration::days(1), hour() + Duration::days(1))
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|m| {
+            (
+                m.hour_bucket,
+                m.total_transactions,
+                m.failed_transactions,
+                m.p50_settlement_latency_ms,
+                m.p90_settlement_latency_ms,
+                m.sketches.settlement_latency_ms.len(),
+            )
+        })
+        .collect()
+}
+
+fn service(db: &Arc<Database>, lookback_hours: i64) -> AggregationService {
+    AggregationService::new(
+        Arc::clone(db),
+        AggregationConfig {
+            lookback_hours,
+            batch_size: 4,
+            ..Default::default()
+        },
+    )
+}
+
+#[sqlx::test]
+async fn test_overlapping_aggregation_runs_leave_hourly_metrics_unchanged(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool.clone()));
+    seed_payments(&db).await;
+
+    // Only the whole hour before the one in progress, in batches of four
+    let now = hour() + Duration::minutes(90);
+    let aggregation = service(&db, 2);
+    assert_eq!(aggregation.run_hourly_aggregation(now).await.unwrap(), 1);
+    let first_hour = (hour(), 10, 0, Some(5_500), Some(9_000), 10);
+    assert_eq!(hourly_totals(&db).await, [first_hour]);
+
+    // A later run picks up from the last processed hour
+    let now = hour() + Duration::minutes(150);
+    assert_eq!(aggregation.run_hourly_aggregation(now).await.unwrap(), 1);
+    assert_eq!(aggregation.run_hourly_aggregation(now).await.unwrap(), 0);
+    let second = hour() + Duration::hours(1);
+    let second_hour = (second, 6, 1, Some(22_000), Some(24_000), 5);
+    assert_eq!(hourly_totals(&db).await, [first_hour, second_hour]);
+
+    // As if neither run had completed, so the next one aggregates both hours
+    // again over a wider window
+    sqlx::query("UPDATE aggregation_jobs SET status = 'failed'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let now = hour() + Duration::minutes(170);
+    let wider = service(&db, 4);
+    assert_eq!(wider.run_hourly_aggregation(now).await.unwrap(), 2);
+    assert_eq!(hourly_totals(&db).await, [first_hour, second_hour]);
+}
+
+#[sqlx::test]
+async fn test_hour_stays_open_for_late_payments(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool.clone()));
+    seed_payments(&db).await;
+
+    // Five minutes after the first hour ends it is still within the grace delay
+    let aggregation = service(&db, 2);
+    let now = hour() + Duration::minutes(65);
+    assert_eq!(aggregation.run_hourly_aggregation(now).await.unwrap(), 0);
+    assert!(hourly_totals(&db).await.is_empty());
+
+    // A payment from the end of the hour ingested late still counts
+    db.save_payments(vec![payment(hour() + Duration::minutes(59), Some(500))])
+        .await
+        .unwrap();
+    let now = hour() + Duration::minutes(80);
+    assert_eq!(aggregation.run_hourly_aggregation(now).await.unwrap(), 1);
+    let totals = hourly_totals(&db).await;
+    assert_eq!((totals[0].0, totals[0].1), (hour(), 11));
+}
+
+async fn ingest_ledger(pool: &SqlitePool, sequence: i64, close_time: DateTime<Utc>) {
+    sqlx::query(
+        "INSERT INTO ledgers (sequence, hash, close_time, transaction_count, operation_count) VALUES ($1, $2, $3, 0, 0)",
+    )
+    .bind(sequence)
+    .bind(format!("hash{}", sequence))
+    .bind(close_time)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[sqlx::test]
+async fn test_hours_close_only_up_to_the_latest_ingested_ledger(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool.clone()));
+    seed_payments(&db).await;
+
+    // Ingestion stalled during the second hour, so only the first is closed
+    // however late the job runs
+    ingest_ledger(&pool, 1, hour() + Duration::minutes(30)).await;
+    ingest_ledger(&pool, 2, hour() + Duration::minutes(75)).await;
+    let aggregation = service(&db, 4);
+    let now = hour() + Duration::hours(3);
+    assert_eq!(aggregation.run_hourly_aggregation(now).await.unwrap(), 1);
+    assert_eq!(hourly_totals(&db).await.len(), 1);
+
+    // Once ingestion catches up the second hour is closed with all its
+    // payments
+    ingest_ledger(&pool, 3, hour() + Duration::minutes(125)).await;
+    assert_eq!(aggregation.run_hourly_aggregation(now).await.unwrap(), 1);
+    let totals = hourly_totals(&db).await;
+    assert_eq!((totals[1].0, totals[1].1), (hour() + Duration::hours(1), 6));
+}
+
+/// Hours, transactions, failures, p50 latency and latency samples in the
+/// daily rollup of the seeded day
+async fn daily_totals(db: &Database) -> (i64, i64, i64, Option<i32>, u64) {
+    let day = RollupPeriod::Daily.start_of(hour());
+    let rollups = db
+        .aggregation_db()
+        .fetch_corridor_rollups(RollupPeriod::Daily, day, day)
+        .await
+        .unwrap();
+    assert_eq!(rollups.len(), 1);
+    let metrics = &rollups[0].metrics;
+    (
+        rollups[0].hours,
+        metrics.total_transactions,
+        metrics.failed_transactions,
+        metrics.p50_settlement_latency_ms,
+        metrics.sketches.settlement_latency_ms.len(),
+    )
+}
+
+#[sqlx::test]
+async fn test_daily_rollup_of_overlapping_aggregation_runs(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool.clone()));
+    seed_payments(&db).await;
+
+    // Two runs, then both hours again over a wider window
+    let aggregation = service(&db, 2);
+    aggregation
+        .run_hourly_aggregation(hour() + Duration::minutes(90))
+        .await
+        .unwrap();
+    aggregation
+        .run_hourly_aggregation(hour() + Duration::minutes(150))
+        .await
+        .unwrap();
+    sqlx::query("UPDATE aggregation_jobs SET status = 'failed'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let now = hour() + Duration::minutes(170);
+    service(&db, 4).run_hourly_aggregation(now).await.unwrap();
+
+    // Every payment of both hours counted once, and the day stays open
+    // while its hours are still being aggregated
+    assert_eq!(
+        aggregation
+            .run_rollup(RollupPeriod::Daily, now)
+            .await
+            .unwrap(),
+        1
+    );
+    let open_day = daily_totals(&db).await;
+    assert_eq!(
+        (open_day.0, open_day.1, open_day.2, open_day.4),
+        (2, 16, 1, 15)
+    );
+    assert_eq!(
+        aggregation
+            .run_rollup(RollupPeriod::Daily, now)
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(daily_totals(&db).await, open_day);
+
+    // Once the day's last hour is aggregated it closes with the payments
+    // that came in later
+    let now = RollupPeriod::Daily.next_start(hour()) + Duration::minutes(30);
+    assert_eq!(aggregation.run_hourly_aggregation(now).await.unwrap(), 1);
+    assert_eq!(
+        aggregation
+            .run_rollup(RollupPeriod::Daily, now)
+            .await
+            .unwrap(),
+        1
+    );
+    let closed_day = daily_totals(&db).await;
+    assert_eq!(
+        (closed_day.0, closed_day.1, closed_day.2, closed_day.4),
+        (3, 19, 1, 18)
+    );
+    assert_eq!(
+        aggregation
+            .run_rollup(RollupPeriod::Daily, now)
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(daily_totals(&db).await, closed_day);
+}
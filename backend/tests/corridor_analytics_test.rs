@@ -22,7 +22,12 @@ fn create_test_payment(
         destination_asset_code: dest_code.to_string(),
         destination_asset_issuer: dest_issuer.to_string(),
         amount,
+        source_amount: None,
+        send_max: None,
+        dest_min: None,
+        path: Vec::new(),
         successful,
+        failure_reason: None,
         timestamp,
         submission_time: None,
         confirmation_time: None,
@@ -0,0 +1,115 @@
+mod common;
+
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::Router;
+use chrono::{DateTime, Duration, Utc};
+use common::{api_state, current_hour, get_json, hourly, settled_payment};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use stellar_insights_backend::api::corridors_cached::get_corridor_detail;
+use stellar_insights_backend::database::Database;
+use stellar_insights_backend::models::corridor::PaymentRecord;
+
+const CORRIDOR_KEY: &str = "USDC:GISSUER->XLM:native";
+
+/// Nine settled $10 payments and `failed` underfunded ones in the hour
+fn hour_payments(hour_bucket: DateTime<Utc>, failed: usize) -> Vec<PaymentRecord> {
+    let payment = |successful: bool| PaymentRecord {
+        successful,
+        failure_reason: (!successful).then(|| "op_underfunded".to_string()),
+        ..settled_payment(
+            ("USDC", "GISSUER"),
+            ("XLM", "native"),
+            10.0,
+            hour_bucket + Duration::minutes(1),
+            5,
+        )
+    };
+    (0..9)
+        .map(|_| payment(true))
+        .chain((0..failed).map(|_| payment(false)))
+        .collect()
+}
+
+async fn detail_app(db: Arc<Database>) -> Router {
+    Router::new()
+        .route("/api/corridors/:corridor_key", get(get_corridor_detail))
+        .with_state(api_state(db).await)
+}
+
+#[sqlx::test]
+async fn test_corridor_detail_from_hourly_metrics(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool));
+    let hour = current_hour();
+    for (bucket, failed) in [
+        (hour, 1),
+        (hour - Duration::hours(1), 0),
+        (hour - Duration::hours(3), 1),
+        // Only counts towards the failure trend
+        (hour - Duration::days(2), 0),
+    ] {
+        db.upsert_hourly_corridor_metric(&hourly(bucket, &hour_payments(bucket, failed)))
+            .await
+            .unwrap();
+    }
+
+    let (status, json) = get_json(
+        detail_app(Arc::clone(&db)).await,
+        "/api/corridors/XLM%3Anative-%3EUSDC%3AGISSUER?window=24h&resolution=1h",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let corridor = &json["corridor"];
+    assert_eq!(corridor["id"], CORRIDOR_KEY);
+    assert_eq!(corridor["total_attempts"], 29);
+    assert_eq!(corridor["failed_payments"], 2);
+    assert_eq!(corridor["liquidity_volume_24h_usd"], 270.0);
+
+    let points = json["historical_success_rate"].as_array().unwrap();
+    let attempts: Vec<i64> = points
+        .iter()
+        .map(|p| p["attempts"].as_i64().unwrap())
+        .collect();
+    assert_eq!(attempts, vec![10, 9, 10]);
+    assert_eq!(points[2]["timestamp"], hour.to_rfc3339());
+    assert_eq!(json["liquidity_trends"].as_array().unwrap().len(), 3);
+
+    let reasons = json["failure_reasons"].as_array().unwrap();
+    assert_eq!(reasons.len(), 1);
+    assert_eq!(reasons[0]["result_code"], "op_underfunded");
+    assert_eq!(reasons[0]["count"], 2);
+
+    // Daily points add up to the same attempts
+    let (status, json) = get_json(
+        detail_app(Arc::clone(&db)).await,
+        "/api/corridors/USDC%3AGISSUER-%3EXLM%3Anative?window=7d&resolution=1d",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["corridor"]["total_attempts"], 38);
+    let daily: i64 = json["historical_success_rate"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["attempts"].as_i64().unwrap())
+        .sum();
+    assert_eq!(daily, 38);
+
+    let (status, _) = get_json(
+        detail_app(Arc::clone(&db)).await,
+        "/api/corridors/USDC%3AGISSUER-%3EXLM%3Anative?window=1h&resolution=1d",
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = get_json(
+        detail_app(Arc::clone(&db)).await,
+        "/api/corridors/EURC%3AGOTHER-%3EXLM%3Anative",
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = get_json(detail_app(db).await, "/api/corridors/not-a-corridor").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::Router;
+use chrono::{DateTime, Duration, Utc};
+use common::{api_state, current_hour, get_json, hourly, settled_payment};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use stellar_insights_backend::api::corridors_cached::get_corridor_distributions;
+use stellar_insights_backend::database::Database;
+use stellar_insights_backend::models::corridor::PaymentRecord;
+use stellar_insights_backend::services::aggregation::HourlyCorridorMetrics;
+
+const CORRIDOR_KEY: &str = "USDC:GISSUER->XLM:native";
+
+/// The corridor's metrics for a batch of payments of `seconds` dollars that
+/// took `seconds` to settle
+fn batch(
+    hour_bucket: DateTime<Utc>,
+    seconds: std::ops::RangeInclusive<i64>,
+    liquidity_depth_usd: f64,
+) -> HourlyCorridorMetrics {
+    let confirmed = hour_bucket + Duration::minutes(1);
+    let payments: Vec<PaymentRecord> = seconds
+        .map(|s| {
+            settled_payment(
+                ("USDC", "GISSUER"),
+                ("XLM", "native"),
+                s as f64,
+                confirmed,
+                s,
+            )
+        })
+        .collect();
+
+    let mut hourly = hourly(hour_bucket, &payments);
+    hourly.liquidity_depth_usd = liquidity_depth_usd;
+    hourly
+}
+
+#[sqlx::test]
+async fn test_hourly_sketches_merge_across_batches(pool: SqlitePool) {
+    let db = Database::new(pool);
+    let hour = current_hour();
+
+    let mut merged = batch(hour, 1..=10, 1_000.0);
+    merged.merge(&batch(hour, 11..=40, 5_000.0));
+    db.upsert_hourly_corridor_metric(&merged).await.unwrap();
+
+    let stored = db
+        .aggregation_db()
+        .fetch_corridor_hourly_metrics(CORRIDOR_KEY, hour, hour + Duration::hours(1))
+        .await
+        .unwrap();
+    assert_eq!(stored.len(), 1);
+    let stored = &stored[0];
+    assert_eq!(stored.total_transactions, 40);
+    // Weighted by transactions rather than halved pairwise
+    assert_eq!(stored.liquidity_depth_usd, 4_000.0);
+    assert_eq!(stored.avg_settlement_latency_ms, Some(20_500));
+
+    // Merged sketches agree exactly with one built over every payment
+    let all = batch(hour, 1..=40, 0.0).sketches;
+    let latency = &stored.sketches.settlement_latency_ms;
+    assert_eq!(latency.len(), 40);
+    for q in [0.5, 0.9, 0.95, 0.99] {
+        assert_eq!(latency.quantile(q), all.settlement_latency_ms.quantile(q));
+        assert_eq!(
+            stored.sketches.payment_size.quantile(q),
+            all.payment_size.quantile(q)
+        );
+    }
+    let p50 = stored.p50_settlement_latency_ms.unwrap();
+    assert!((20_000..=20_200).contains(&p50), "p50 was {}", p50);
+    let p99 = stored.p99_settlement_latency_ms.unwrap();
+    assert!((40_000..=40_400).contains(&p99), "p99 was {}", p99);
+}
+
+async fn distributions_app(db: Arc<Database>) -> Router {
+    Router::new()
+        .route(
+            "/api/corridors/:corridor_key/distributions",
+            get(get_corridor_distributions),
+        )
+        .with_state(api_state(db).await)
+}
+
+/// Sketch values are within 1% of the recorded ones
+fn assert_close(actual: &Value, expected: f64) {
+    let actual = actual.as_f64().expect("a number");
+    assert!(
+        (actual - expected).abs() <= expected * 0.01,
+        "{} is not within 1% of {}",
+        actual,
+        expected
+    );
+}
+
+#[sqlx::test]
+async fn test_corridor_distributions_endpoint(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool));
+    let hour = current_hour();
+    db.upsert_hourly_corridor_metric(&batch(hour, 1..=10, 0.0))
+        .await
+        .unwrap();
+    db.upsert_hourly_corridor_metric(&batch(hour - Duration::days(3), 91..=100, 0.0))
+        .await
+        .unwrap();
+
+    let (status, json) = get_json(
+        distributions_app(Arc::clone(&db)).await,
+        "/api/corridors/XLM%3Anative-%3EUSDC%3AGISSUER/distributions?window=1h",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["corridor_key"], CORRIDOR_KEY);
+    let distributions = &json["distributions"];
+    assert_eq!(distributions["hours"], 1);
+    assert_eq!(distributions["settlement_latency_ms"]["samples"], 10);
+    assert_close(&distributions["payment_size"]["max"], 10.0);
+
+    let (status, json) = get_json(
+        distributions_app(Arc::clone(&db)).await,
+        "/api/corridors/USDC%3AGISSUER-%3EXLM%3Anative/distributions?window=7d",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let distributions = &json["distributions"];
+    assert_eq!(distributions["hours"], 2);
+    assert_eq!(distributions["payment_size"]["samples"], 20);
+    assert_close(&distributions["payment_size"]["p50"], 10.0);
+    assert_close(&distributions["payment_size"]["max"], 100.0);
+
+    let (status, _) = get_json(
+        distributions_app(Arc::clone(&db)).await,
+        "/api/corridors/USDC%3AGISSUER-%3EXLM%3Anative/distributions?window=forever",
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = get_json(
+        distributions_app(db).await,
+        "/api/corridors/EURC%3AGOTHER-%3EXLM%3Anative/distributions",
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,178 @@
+mod common;
+
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::Router;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use common::{api_state, current_hour, get_json, hourly, settled_payment};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use stellar_insights_backend::api::corridors_cached::list_corridors;
+use stellar_insights_backend::database::Database;
+use stellar_insights_backend::models::corridor::PaymentRecord;
+use stellar_insights_backend::services::aggregation::{
+    AggregationConfig, AggregationService, RollupPeriod,
+};
+use uuid::Uuid;
+
+const CORRIDOR_KEY: &str = "USDC:GISSUER->XLM:native";
+
+fn at(y: i32, m: u32, d: u32, h: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap()
+}
+
+/// Ten settled $20 payments in the hour, taking 1 to 10 seconds each
+fn hour_payments(hour_bucket: DateTime<Utc>) -> Vec<PaymentRecord> {
+    (1..=10)
+        .map(|seconds| {
+            settled_payment(
+                ("USDC", "GISSUER"),
+                ("XLM", "native"),
+                20.0,
+                hour_bucket + Duration::minutes(1),
+                seconds,
+            )
+        })
+        .collect()
+}
+
+/// Records a completed hourly aggregation job that got through `last_hour`
+async fn mark_aggregated_through(db: &Database, last_hour: DateTime<Utc>) {
+    let job_id = Uuid::new_v4().to_string();
+    db.create_aggregation_job(&job_id, "hourly").await.unwrap();
+    db.update_last_processed_hour(&job_id, &last_hour.to_rfc3339())
+        .await
+        .unwrap();
+    db.update_aggregation_job_status(&job_id, "completed", None)
+        .await
+        .unwrap();
+}
+
+async fn rollup_totals(db: &Database, period: RollupPeriod) -> Vec<(DateTime<Utc>, i64, i64)> {
+    db.aggregation_db()
+        .fetch_corridor_rollups(period, at(2026, 1, 1, 0), Utc::now())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| (r.metrics.hour_bucket, r.hours, r.metrics.total_transactions))
+        .collect()
+}
+
+#[sqlx::test]
+async fn test_rollups_resume_and_replace_without_double_counting(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool.clone()));
+    // Monday 5 January, Tuesday 6 January and Tuesday 3 February
+    for hour in [
+        at(2026, 1, 5, 10),
+        at(2026, 1, 5, 15),
+        at(2026, 1, 6, 9),
+        at(2026, 2, 3, 12),
+    ] {
+        db.upsert_hourly_corridor_metric(&hourly(hour, &hour_payments(hour)))
+            .await
+            .unwrap();
+    }
+
+    let now = at(2026, 3, 2, 12);
+    mark_aggregated_through(&db, now - Duration::hours(1)).await;
+
+    let service = AggregationService::new(Arc::clone(&db), AggregationConfig::default());
+    // One rollup per corridor and period holding data
+    for (period, stored) in RollupPeriod::ALL.into_iter().zip([3, 2, 2]) {
+        assert_eq!(service.run_rollup(period, now).await.unwrap(), stored);
+    }
+
+    let daily = vec![
+        (at(2026, 1, 5, 0), 2, 20),
+        (at(2026, 1, 6, 0), 1, 10),
+        (at(2026, 2, 3, 0), 1, 10),
+    ];
+    let weekly = vec![(at(2026, 1, 5, 0), 3, 30), (at(2026, 2, 2, 0), 1, 10)];
+    let monthly = vec![(at(2026, 1, 1, 0), 3, 30), (at(2026, 2, 1, 0), 1, 10)];
+    assert_eq!(rollup_totals(&db, RollupPeriod::Daily).await, daily);
+    assert_eq!(rollup_totals(&db, RollupPeriod::Weekly).await, weekly);
+    assert_eq!(rollup_totals(&db, RollupPeriod::Monthly).await, monthly);
+
+    // The weekly rollup merges every payment's latency
+    let week = &db
+        .aggregation_db()
+        .fetch_corridor_rollups(RollupPeriod::Weekly, at(2026, 1, 5, 0), at(2026, 1, 5, 0))
+        .await
+        .unwrap()[0];
+    assert_eq!(week.metrics.sketches.settlement_latency_ms.len(), 30);
+    assert_eq!(week.metrics.success_rate, 100.0);
+
+    // Closed periods were recorded, so a re-run starts from the current one
+    // and leaves the stored rollups as they were
+    for period in [RollupPeriod::Daily, RollupPeriod::Monthly] {
+        assert_eq!(service.run_rollup(period, now).await.unwrap(), 0);
+    }
+    assert_eq!(rollup_totals(&db, RollupPeriod::Daily).await, daily);
+    assert_eq!(rollup_totals(&db, RollupPeriod::Monthly).await, monthly);
+
+    let (completed, last_processed_hour): (i64, Option<String>) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*), MAX(last_processed_hour) FROM aggregation_jobs
+        WHERE job_type = 'daily' AND status = 'completed'
+        "#,
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(completed, 2);
+    // Today stays open while its hours are still being aggregated
+    let last = DateTime::parse_from_rfc3339(&last_processed_hour.unwrap()).unwrap();
+    assert_eq!(last, at(2026, 3, 1, 0));
+}
+
+async fn corridors_app(db: Arc<Database>) -> Router {
+    Router::new()
+        .route("/api/corridors", get(list_corridors))
+        .with_state(api_state(db).await)
+}
+
+#[sqlx::test]
+async fn test_list_corridors_time_period(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool));
+    let hour = current_hour();
+    for bucket in [hour, hour - Duration::days(3), hour - Duration::days(20)] {
+        db.upsert_hourly_corridor_metric(&hourly(bucket, &hour_payments(bucket)))
+            .await
+            .unwrap();
+    }
+    // Earlier days are read from their closed rollups, and today from its
+    // hourly buckets
+    mark_aggregated_through(&db, hour - Duration::hours(1)).await;
+    AggregationService::new(Arc::clone(&db), AggregationConfig::default())
+        .run_rollup(RollupPeriod::Daily, Utc::now())
+        .await
+        .unwrap();
+
+    for (period, attempts) in [("24h", 10), ("7d", 20), ("30d", 30)] {
+        let (status, json) = get_json(
+            corridors_app(Arc::clone(&db)).await,
+            &format!("/api/corridors?time_period={}", period),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", period);
+        let corridors = json.as_array().unwrap();
+        assert_eq!(corridors.len(), 1, "{}", period);
+        assert_eq!(corridors[0]["id"], CORRIDOR_KEY);
+        assert_eq!(corridors[0]["total_attempts"], attempts, "{}", period);
+        assert_eq!(corridors[0]["success_rate"], 100.0);
+        assert_eq!(corridors[0]["liquidity_depth_usd"], attempts as f64 * 20.0);
+    }
+
+    // Filters still apply to stored metrics
+    let (status, json) = get_json(
+        corridors_app(Arc::clone(&db)).await,
+        "/api/corridors?time_period=30d&asset_code=EURC",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json, Value::Array(Vec::new()));
+
+    let (status, _) = get_json(corridors_app(db).await, "/api/corridors?time_period=1y").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
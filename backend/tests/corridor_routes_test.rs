@@ -0,0 +1,179 @@
+mod common;
+
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::Router;
+use chrono::{DateTime, Duration, Utc};
+use common::{api_state, get_json};
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use stellar_insights_backend::api::corridors_cached::get_corridor_routes;
+use stellar_insights_backend::database::Database;
+use stellar_insights_backend::models::corridor::Corridor;
+use stellar_insights_backend::models::PaymentRecord;
+use uuid::Uuid;
+
+/// A strict-send path payment delivering NGN for BRL, submitted with a floor
+/// of `dest_min`
+fn path_payment(
+    created_at: DateTime<Utc>,
+    source_amount: f64,
+    amount: f64,
+    dest_min: f64,
+    path: &[&str],
+) -> PaymentRecord {
+    PaymentRecord {
+        id: Uuid::new_v4().to_string(),
+        transaction_hash: "txhash".to_string(),
+        source_account: "GSOURCE".to_string(),
+        destination_account: "GDEST".to_string(),
+        asset_type: "credit_alphanum4".to_string(),
+        asset_code: Some("NGN".to_string()),
+        asset_issuer: Some("GNGNISSUER".to_string()),
+        source_asset_code: "BRL".to_string(),
+        source_asset_issuer: "GBRLISSUER".to_string(),
+        destination_asset_code: "NGN".to_string(),
+        destination_asset_issuer: "GNGNISSUER".to_string(),
+        amount,
+        operation_type: Some("path_payment_strict_send".to_string()),
+        source_amount: Some(source_amount),
+        send_max: None,
+        dest_min: Some(dest_min),
+        path: Some(serde_json::to_string(path).unwrap()),
+        successful: true,
+        result_code: Some("op_success".to_string()),
+        timestamp: Some(created_at),
+        submission_time: None,
+        confirmation_time: None,
+        created_at,
+    }
+}
+
+async fn routes_app(db: Arc<Database>) -> Router {
+    Router::new()
+        .route(
+            "/api/corridors/:corridor_key/routes",
+            get(get_corridor_routes),
+        )
+        .with_state(api_state(db).await)
+}
+
+#[sqlx::test]
+async fn test_corridor_routes_endpoint(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool));
+    let now = Utc::now();
+    db.save_payments(vec![
+        path_payment(now, 100.0, 300.0, 300.0, &["USDC:GUSDCISSUER"]),
+        path_payment(now, 300.0, 900.0, 900.0, &["USDC:GUSDCISSUER"]),
+        // 290 delivered against a floor of 261
+        path_payment(now, 100.0, 290.0, 261.0, &["XLM:native"]),
+        // Outside a 6h window
+        path_payment(
+            now - Duration::hours(12),
+            1000.0,
+            3000.0,
+            2700.0,
+            &["XLM:native"],
+        ),
+    ])
+    .await
+    .unwrap();
+
+    // The key is accepted in either asset order
+    let (status, json) = get_json(
+        routes_app(Arc::clone(&db)).await,
+        "/api/corridors/NGN%3AGNGNISSUER-%3EBRL%3AGBRLISSUER/routes?window=6h",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["corridor_key"], "BRL:GBRLISSUER->NGN:GNGNISSUER");
+    assert_eq!(json["window"], "6h");
+    assert_eq!(json["total_payments"], 3);
+    assert_eq!(json["total_volume"], 500.0);
+    assert_eq!(json["truncated"], false);
+
+    let paths = json["paths"].as_array().unwrap();
+    assert_eq!(paths.len(), 2);
+    assert_eq!(paths[0]["path"], json!(["USDC:GUSDCISSUER"]));
+    assert_eq!(paths[0]["payment_count"], 2);
+    assert_eq!(paths[0]["volume_share"], 80.0);
+    assert_eq!(paths[0]["avg_slippage_bps"], 0.0);
+    assert_eq!(paths[1]["path"], json!(["XLM:native"]));
+    assert!((paths[1]["avg_slippage_bps"].as_f64().unwrap() - 1000.0).abs() < 1e-6);
+
+    // A quiet corridor has nothing to break down
+    let (status, json) = get_json(
+        routes_app(Arc::clone(&db)).await,
+        "/api/corridors/EURC%3AGOTHER-%3EXLM%3Anative/routes",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["total_payments"], 0);
+    assert_eq!(json["paths"], json!([]));
+
+    let (status, _) = get_json(
+        routes_app(db).await,
+        "/api/corridors/BRL%3AGBRLISSUER-%3ENGN%3AGNGNISSUER/routes?window=soon",
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_corridor_payments_are_filtered_before_the_limit(pool: SqlitePool) {
+    let db = Database::new(pool.clone());
+    let now = Utc::now();
+    // Other corridors earlier in the window: XLM paid for NGN, and a plain
+    // XLM payment stored as ingestion stores it, without asset columns
+    let mut xlm_to_ngn = path_payment(now - Duration::hours(3), 50.0, 150.0, 150.0, &[]);
+    xlm_to_ngn.source_asset_code = "XLM".to_string();
+    xlm_to_ngn.source_asset_issuer = "native".to_string();
+    db.save_payments(vec![
+        xlm_to_ngn,
+        path_payment(now - Duration::hours(1), 100.0, 300.0, 300.0, &[]),
+        path_payment(now, 200.0, 600.0, 600.0, &[]),
+    ])
+    .await
+    .unwrap();
+    sqlx::query(
+        r#"
+        INSERT INTO payments (
+            id, transaction_hash, source_account, destination_account, asset_type, amount,
+            operation_type, successful, created_at
+        )
+        VALUES ($1, 'txhash', 'GSOURCE', 'GDEST', 'native', 10.0, 'payment', 1, $2)
+        "#,
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(now - Duration::hours(2))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let amounts = |key: &'static str| {
+        let aggregation_db = db.aggregation_db();
+        async move {
+            aggregation_db
+                .fetch_corridor_payments_by_timerange(
+                    &Corridor::from_key(key).unwrap(),
+                    now - Duration::hours(6),
+                    now,
+                    2,
+                )
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|p| p.amount)
+                .collect::<Vec<_>>()
+        }
+    };
+
+    // Either asset order, and not crowded out by the earlier payments
+    assert_eq!(
+        amounts("NGN:GNGNISSUER->BRL:GBRLISSUER").await,
+        [300.0, 600.0]
+    );
+    assert_eq!(amounts("XLM:native->NGN:GNGNISSUER").await, [150.0]);
+    assert_eq!(amounts("XLM:native->XLM:native").await, [10.0]);
+}
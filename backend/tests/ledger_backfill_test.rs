@@ -0,0 +1,311 @@
+mod common;
+
+use common::{empty_ledger_source, file_source, fixture_dir, payment_ledger};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use stellar_insights_backend::database::Database;
+use stellar_insights_backend::ingestion::ledger::{
+    backfill_task_name, BackfillConfig, LedgerIngestionService,
+};
+use stellar_insights_backend::rpc::file_source::batch_file_name;
+use stellar_insights_backend::services::account_merge_detector::AccountMergeDetector;
+use stellar_insights_backend::services::aggregation::{AggregationConfig, AggregationService};
+use stellar_insights_backend::services::fee_bump_tracker::FeeBumpTrackerService;
+use tempfile::TempDir;
+
+const OLDEST_LEDGER: u64 = 1_000;
+const LATEST_LEDGER: u64 = 1_039;
+
+/// An ingestion service over empty fixture ledgers `OLDEST_LEDGER..=LATEST_LEDGER`
+fn service(pool: &SqlitePool) -> (TempDir, LedgerIngestionService) {
+    let (dir, ledger_source) = empty_ledger_source(OLDEST_LEDGER as u32, LATEST_LEDGER as u32, 8);
+    let service = LedgerIngestionService::new(
+        ledger_source,
+        Arc::new(FeeBumpTrackerService::new(pool.clone())),
+        Arc::new(AccountMergeDetector::new(pool.clone())),
+        pool.clone(),
+    );
+    (dir, service)
+}
+
+fn config(start: u64, end: u64) -> BackfillConfig {
+    BackfillConfig {
+        start_ledger: start,
+        end_ledger: end,
+        chunk_size: 10,
+        workers: 3,
+        batch_size: 4,
+    }
+}
+
+async fn chunk_cursor(pool: &SqlitePool, start: u64, end: u64) -> Option<String> {
+    sqlx::query_scalar("SELECT last_cursor FROM ingestion_state WHERE task_name = $1")
+        .bind(backfill_task_name(start, end))
+        .fetch_optional(pool)
+        .await
+        .unwrap()
+}
+
+async fn ledger_count(pool: &SqlitePool) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM ledgers")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[test]
+fn test_backfill_chunks_cover_range() {
+    let chunks = config(100, 125).chunks();
+    assert_eq!(chunks, vec![(100, 109), (110, 119), (120, 125)]);
+
+    let single = config(100, 100).chunks();
+    assert_eq!(single, vec![(100, 100)]);
+
+    assert!(config(101, 100).chunks().is_empty());
+}
+
+#[sqlx::test]
+async fn test_backfill_processes_all_chunks(pool: SqlitePool) {
+    let (_dir, service) = service(&pool);
+    let start = OLDEST_LEDGER;
+    let end = OLDEST_LEDGER + 24;
+
+    let summary = service.run_backfill(&config(start, end)).await.unwrap();
+
+    assert_eq!(summary.chunks_total, 3);
+    assert_eq!(summary.chunks_completed, 3);
+    assert_eq!(summary.chunks_failed, 0);
+    assert_eq!(summary.ledgers_processed, 25);
+    assert_eq!(ledger_count(&pool).await, 25);
+
+    assert_eq!(
+        chunk_cursor(&pool, start, start + 9).await,
+        Some((start + 9).to_string())
+    );
+    assert_eq!(
+        chunk_cursor(&pool, start + 20, end).await,
+        Some(end.to_string())
+    );
+
+    // The live tail cursor is left alone
+    let live: Option<i64> =
+        sqlx::query_scalar("SELECT last_ledger_sequence FROM ingestion_cursor WHERE id = 1")
+            .fetch_optional(&pool)
+            .await
+            .unwrap();
+    assert!(live.is_none());
+}
+
+#[sqlx::test]
+async fn test_backfill_rerun_skips_completed_chunks(pool: SqlitePool) {
+    let (_dir, service) = service(&pool);
+    let config = config(OLDEST_LEDGER, OLDEST_LEDGER + 19);
+
+    service.run_backfill(&config).await.unwrap();
+    let rerun = service.run_backfill(&config).await.unwrap();
+
+    assert_eq!(rerun.chunks_skipped, 2);
+    assert_eq!(rerun.chunks_completed, 0);
+    assert_eq!(rerun.ledgers_processed, 0);
+    assert_eq!(ledger_count(&pool).await, 20);
+}
+
+#[sqlx::test]
+async fn test_backfill_resumes_partial_chunk(pool: SqlitePool) {
+    let (_dir, service) = service(&pool);
+    let start = OLDEST_LEDGER;
+    let end = OLDEST_LEDGER + 9;
+
+    // Simulate a crash after the first six ledgers of the chunk
+    sqlx::query(
+        "INSERT INTO ingestion_state (task_name, last_cursor, updated_at) VALUES ($1, $2, CURRENT_TIMESTAMP)",
+    )
+    .bind(backfill_task_name(start, end))
+    .bind((start + 5).to_string())
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let summary = service.run_backfill(&config(start, end)).await.unwrap();
+
+    assert_eq!(summary.chunks_completed, 1);
+    assert_eq!(summary.ledgers_processed, 4);
+    assert_eq!(chunk_cursor(&pool, start, end).await, Some(end.to_string()));
+}
+
+#[sqlx::test]
+async fn test_backfill_stops_at_live_cursor(pool: SqlitePool) {
+    let (_dir, service) = service(&pool);
+    let start = OLDEST_LEDGER;
+
+    sqlx::query(
+        "INSERT INTO ingestion_cursor (id, last_ledger_sequence, cursor, updated_at) VALUES (1, $1, NULL, CURRENT_TIMESTAMP)",
+    )
+    .bind((start + 14) as i64)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let summary = service
+        .run_backfill(&config(start, start + 29))
+        .await
+        .unwrap();
+
+    assert_eq!(summary.chunks_total, 2);
+    assert_eq!(summary.ledgers_processed, 15);
+    assert_eq!(ledger_count(&pool).await, 15);
+    // The clipped chunk keeps its progress under its configured range
+    assert_eq!(
+        chunk_cursor(&pool, start + 10, start + 19).await,
+        Some((start + 14).to_string())
+    );
+
+    // Once the live tail has moved on, the clipped chunk resumes where it
+    // stopped and the rest of the range is backfilled
+    sqlx::query("UPDATE ingestion_cursor SET last_ledger_sequence = $1 WHERE id = 1")
+        .bind((start + 29) as i64)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let summary = service
+        .run_backfill(&config(start, start + 29))
+        .await
+        .unwrap();
+
+    assert_eq!(summary.chunks_skipped, 1);
+    assert_eq!(summary.chunks_completed, 2);
+    assert_eq!(summary.ledgers_processed, 15);
+    assert_eq!(ledger_count(&pool).await, 30);
+}
+
+#[sqlx::test]
+async fn test_backfill_reports_missing_ledgers(pool: SqlitePool) {
+    let (_dir, service) = service(&pool);
+
+    // The second chunk lies past the end of the fixture ledgers
+    let summary = service
+        .run_backfill(&config(LATEST_LEDGER - 9, LATEST_LEDGER + 10))
+        .await
+        .unwrap();
+
+    assert_eq!(summary.chunks_completed, 1);
+    assert_eq!(summary.chunks_failed, 1);
+    assert_eq!(
+        chunk_cursor(&pool, LATEST_LEDGER + 1, LATEST_LEDGER + 10).await,
+        None
+    );
+}
+
+#[sqlx::test]
+async fn test_backfill_stops_at_a_gap_in_the_source(pool: SqlitePool) {
+    let start = OLDEST_LEDGER as u32;
+    let end = start + 9;
+    let (dir, ledger_source) = empty_ledger_source(start, end, 2);
+    // Lose ledgers start+4..=start+5, so the second fetch returns the two
+    // after them
+    std::fs::remove_file(dir.path().join(batch_file_name(start + 4, start + 5))).unwrap();
+    ledger_source.check_health().await.unwrap();
+    let service = LedgerIngestionService::new(
+        ledger_source,
+        Arc::new(FeeBumpTrackerService::new(pool.clone())),
+        Arc::new(AccountMergeDetector::new(pool.clone())),
+        pool.clone(),
+    );
+
+    let summary = service
+        .run_backfill(&config(start as u64, end as u64))
+        .await
+        .unwrap();
+
+    assert_eq!(summary.chunks_failed, 1);
+    assert_eq!(summary.chunks_completed, 0);
+    assert_eq!(
+        chunk_cursor(&pool, start as u64, end as u64).await,
+        Some((start + 3).to_string())
+    );
+    assert_eq!(ledger_count(&pool).await, 4);
+}
+
+#[sqlx::test]
+async fn test_backfill_checkpoint_stops_before_failed_ledger(pool: SqlitePool) {
+    let (_dir, service) = service(&pool);
+    let start = OLDEST_LEDGER;
+    let end = OLDEST_LEDGER + 9;
+    let broken = start + 5;
+
+    sqlx::query(&format!(
+        "CREATE TRIGGER fail_ledger BEFORE INSERT ON ledgers WHEN NEW.sequence = {} \
+         BEGIN SELECT RAISE(ABORT, 'disk I/O error'); END",
+        broken
+    ))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let summary = service.run_backfill(&config(start, end)).await.unwrap();
+    assert_eq!(summary.chunks_failed, 1);
+    // Only the first batch of four was checkpointed
+    assert_eq!(
+        chunk_cursor(&pool, start, end).await,
+        Some((start + 3).to_string())
+    );
+
+    sqlx::query("DROP TRIGGER fail_ledger")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let summary = service.run_backfill(&config(start, end)).await.unwrap();
+    assert_eq!(summary.chunks_completed, 1);
+    assert_eq!(summary.ledgers_processed, 6);
+    assert_eq!(chunk_cursor(&pool, start, end).await, Some(end.to_string()));
+    assert_eq!(ledger_count(&pool).await, 10);
+}
+
+#[sqlx::test]
+async fn test_backfilled_payments_reach_corridor_metrics(pool: SqlitePool) {
+    let start = OLDEST_LEDGER as u32;
+    let end = start + 19;
+    let ledgers: Vec<_> = (start..=end)
+        .map(|seq| payment_ledger(seq, 100_000_000))
+        .collect();
+    let dir = fixture_dir(&ledgers, 8);
+    let service = LedgerIngestionService::new(
+        file_source(&dir),
+        Arc::new(FeeBumpTrackerService::new(pool.clone())),
+        Arc::new(AccountMergeDetector::new(pool.clone())),
+        pool.clone(),
+    );
+
+    let summary = service
+        .run_backfill(&config(start as u64, end as u64))
+        .await
+        .unwrap();
+    assert_eq!(summary.chunks_completed, 2);
+    assert_eq!(summary.completed_windows.len(), 2);
+
+    let aggregation = AggregationService::new(
+        Arc::new(Database::new(pool.clone())),
+        AggregationConfig::default(),
+    );
+    for (first, last) in &summary.completed_windows {
+        aggregation.reaggregate_hours(*first, *last).await.unwrap();
+    }
+
+    let (rows, transactions): (i64, i64) =
+        sqlx::query_as("SELECT COUNT(*), SUM(total_transactions) FROM corridor_metrics_hourly")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(rows >= 1);
+    assert_eq!(transactions, 20);
+
+    // The rollups covering the backfilled hours were rebuilt too
+    let daily: i64 = sqlx::query_scalar(
+        "SELECT SUM(total_transactions) FROM corridor_metrics WHERE period = 'daily'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(daily, 20);
+}
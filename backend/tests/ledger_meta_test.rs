@@ -0,0 +1,666 @@
+mod common;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use common::{
+    account, account_id, empty_ledger, file_source, fixture_dir, ledger_close_meta,
+    ledger_close_meta_v2, result_meta, transaction, usdc, v1_envelope, CLOSE_TIME, PASSPHRASE,
+};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use stellar_insights_backend::ingestion::ledger::LedgerIngestionService;
+use stellar_insights_backend::ingestion::ledger_meta::{
+    decode_ledger_close_meta, decode_meta, payment_result_code_from_xdr, transaction_hash,
+};
+use stellar_insights_backend::services::account_merge_detector::AccountMergeDetector;
+use stellar_insights_backend::services::fee_bump_tracker::FeeBumpTrackerService;
+use stellar_xdr::curr::{
+    AccountMergeResult, AlphaNum4, Asset, AssetCode4, ClaimAtom, ClaimOfferAtom,
+    DecoratedSignature, ExtendFootprintTtlOp, ExtendFootprintTtlResult, ExtensionPoint,
+    FeeBumpTransaction, FeeBumpTransactionEnvelope, FeeBumpTransactionExt,
+    FeeBumpTransactionInnerTx, Hash, InnerTransactionResult, InnerTransactionResultExt,
+    InnerTransactionResultPair, InnerTransactionResultResult, LedgerCloseMeta, Limits,
+    OperationBody, OperationResult, OperationResultTr, PathPaymentStrictReceiveOp,
+    PathPaymentStrictReceiveResult, PathPaymentStrictReceiveResultSuccess, PathPaymentStrictSendOp,
+    PathPaymentStrictSendResult, PathPaymentStrictSendResultSuccess, PaymentOp, PaymentResult,
+    Signature, SignatureHint, SimplePaymentResult, TransactionEnvelope, TransactionResult,
+    TransactionResultExt, TransactionResultResult, TransactionV1Envelope, VecM, WriteXdr,
+};
+
+const LEDGER_SEQ: u32 = 500;
+
+/// A ledger with a payment, a fee-bumped path payment + merge, and a failed payment
+fn sample_metadata_xdr() -> String {
+    let payment_env = v1_envelope(transaction(
+        1,
+        1,
+        vec![OperationBody::Payment(PaymentOp {
+            destination: account(2),
+            asset: usdc(),
+            amount: 1_250_000_000,
+        })],
+    ));
+
+    let inner_tx = transaction(
+        3,
+        7,
+        vec![
+            OperationBody::PathPaymentStrictSend(PathPaymentStrictSendOp {
+                send_asset: Asset::Native,
+                send_amount: 500_000_000,
+                destination: account(4),
+                dest_asset: usdc(),
+                dest_min: 40_000_000,
+                path: VecM::default(),
+            }),
+            OperationBody::AccountMerge(account(5)),
+        ],
+    );
+    let inner_env = v1_envelope(inner_tx.clone());
+    let fee_bump_env = TransactionEnvelope::TxFeeBump(FeeBumpTransactionEnvelope {
+        tx: FeeBumpTransaction {
+            fee_source: account(6),
+            fee: 1_000,
+            inner_tx: FeeBumpTransactionInnerTx::Tx(TransactionV1Envelope {
+                tx: inner_tx,
+                signatures: VecM::default(),
+            }),
+            ext: FeeBumpTransactionExt::V0,
+        },
+        signatures: vec![DecoratedSignature {
+            hint: SignatureHint([0; 4]),
+            signature: Signature(vec![0; 64].try_into().unwrap()),
+        }]
+        .try_into()
+        .unwrap(),
+    });
+
+    let failed_env = v1_envelope(transaction(
+        7,
+        2,
+        vec![OperationBody::Payment(PaymentOp {
+            destination: account(8),
+            asset: Asset::Native,
+            amount: 10_000_000,
+        })],
+    ));
+
+    let fee_bump_ops = vec![
+        OperationResult::OpInner(OperationResultTr::PathPaymentStrictSend(
+            PathPaymentStrictSendResult::Success(PathPaymentStrictSendResultSuccess {
+                offers: VecM::default(),
+                last: SimplePaymentResult {
+                    destination: account_id(4),
+                    asset: usdc(),
+                    amount: 45_500_000,
+                },
+            }),
+        )),
+        OperationResult::OpInner(OperationResultTr::AccountMerge(
+            AccountMergeResult::Success(1_255_000_000),
+        )),
+    ];
+
+    let tx_processing = vec![
+        result_meta(
+            &payment_env,
+            100,
+            TransactionResultResult::TxSuccess(
+                vec![OperationResult::OpInner(OperationResultTr::Payment(
+                    PaymentResult::Success,
+                ))]
+                .try_into()
+                .unwrap(),
+            ),
+        ),
+        result_meta(
+            &fee_bump_env,
+            300,
+            TransactionResultResult::TxFeeBumpInnerSuccess(InnerTransactionResultPair {
+                transaction_hash: Hash(transaction_hash(&inner_env, PASSPHRASE).unwrap()),
+                result: InnerTransactionResult {
+                    fee_charged: 200,
+                    result: InnerTransactionResultResult::TxSuccess(
+                        fee_bump_ops.try_into().unwrap(),
+                    ),
+                    ext: InnerTransactionResultExt::V0,
+                },
+            }),
+        ),
+        result_meta(
+            &failed_env,
+            100,
+            TransactionResultResult::TxFailed(
+                vec![OperationResult::OpInner(OperationResultTr::Payment(
+                    PaymentResult::Underfunded,
+                ))]
+                .try_into()
+                .unwrap(),
+            ),
+        ),
+    ];
+
+    // The tx set is hash-ordered on-chain; reversing it checks we don't rely on position
+    let meta = ledger_close_meta(
+        LEDGER_SEQ,
+        CLOSE_TIME,
+        vec![failed_env, fee_bump_env, payment_env],
+        tx_processing,
+    );
+    BASE64.encode(meta.to_xdr(Limits::none()).unwrap())
+}
+
+#[test]
+fn test_decode_ledger_header_and_transactions() {
+    let decoded = decode_ledger_close_meta(&sample_metadata_xdr(), PASSPHRASE).unwrap();
+
+    assert_eq!(decoded.sequence, LEDGER_SEQ as u64);
+    assert_eq!(decoded.close_time.timestamp(), CLOSE_TIME as i64);
+    assert_eq!(decoded.transactions.len(), 3);
+    assert_eq!(decoded.operation_count, 4);
+
+    let successful: Vec<bool> = decoded.transactions.iter().map(|t| t.successful).collect();
+    assert_eq!(successful, vec![true, true, false]);
+    assert_eq!(decoded.transactions[1].fee_charged, 300);
+    assert_eq!(decoded.transactions[1].hash.len(), 64);
+}
+
+#[test]
+fn test_decode_payments_and_path_payments() {
+    let decoded = decode_ledger_close_meta(&sample_metadata_xdr(), PASSPHRASE).unwrap();
+
+    // The failed transaction's payment is extracted too, flagged as failed
+    assert_eq!(decoded.payments.len(), 3);
+
+    let payment = &decoded.payments[0];
+    assert_eq!(payment.operation_type, "payment");
+    assert_eq!(payment.asset_code.as_deref(), Some("USDC"));
+    assert_eq!(payment.amount, "125.0000000");
+    assert!(payment.source_account.starts_with('G'));
+    assert_eq!(payment.ledger_sequence, LEDGER_SEQ as u64);
+    assert!(payment.successful);
+    assert_eq!(payment.result_code, "op_success");
+
+    let path_payment = &decoded.payments[1];
+    assert_eq!(path_payment.operation_type, "path_payment_strict_send");
+    assert_eq!(path_payment.amount, "4.5500000");
+    assert_eq!(path_payment.source_asset_code.as_deref(), Some("XLM"));
+    assert_eq!(path_payment.source_asset_issuer.as_deref(), Some("native"));
+    assert_eq!(path_payment.source_amount.as_deref(), Some("50.0000000"));
+    assert_eq!(path_payment.dest_min.as_deref(), Some("4.0000000"));
+    assert!(path_payment.send_max.is_none());
+    assert_eq!(path_payment.transaction_hash, decoded.transactions[1].hash);
+
+    // Plain payments move a single asset
+    assert!(payment.source_asset_code.is_none());
+    assert!(payment.source_amount.is_none());
+    assert!(payment.path.is_empty());
+
+    let failed = &decoded.payments[2];
+    assert!(!failed.successful);
+    assert_eq!(failed.result_code, "op_underfunded");
+    assert_eq!(failed.amount, "1.0000000");
+}
+
+/// A ledger with a failed strict-send path payment next to a sibling payment
+/// that succeeded on its own, and a transaction rejected with a bad sequence
+fn failed_payments_ledger(sequence: u32) -> LedgerCloseMeta {
+    let path_payment_env = v1_envelope(transaction(
+        1,
+        1,
+        vec![
+            OperationBody::Payment(PaymentOp {
+                destination: account(2),
+                asset: usdc(),
+                amount: 10_000_000,
+            }),
+            OperationBody::PathPaymentStrictSend(PathPaymentStrictSendOp {
+                send_asset: Asset::Native,
+                send_amount: 500_000_000,
+                destination: account(2),
+                dest_asset: usdc(),
+                dest_min: 60_000_000,
+                path: VecM::default(),
+            }),
+        ],
+    ));
+    let bad_seq_env = v1_envelope(transaction(
+        3,
+        9,
+        vec![OperationBody::Payment(PaymentOp {
+            destination: account(4),
+            asset: usdc(),
+            amount: 20_000_000,
+        })],
+    ));
+
+    let tx_processing = vec![
+        result_meta(
+            &path_payment_env,
+            200,
+            TransactionResultResult::TxFailed(
+                vec![
+                    OperationResult::OpInner(OperationResultTr::Payment(PaymentResult::Success)),
+                    OperationResult::OpInner(OperationResultTr::PathPaymentStrictSend(
+                        PathPaymentStrictSendResult::UnderDestmin,
+                    )),
+                ]
+                .try_into()
+                .unwrap(),
+            ),
+        ),
+        result_meta(&bad_seq_env, 100, TransactionResultResult::TxBadSeq),
+    ];
+
+    ledger_close_meta(
+        sequence,
+        CLOSE_TIME,
+        vec![path_payment_env, bad_seq_env],
+        tx_processing,
+    )
+}
+
+#[test]
+fn test_decode_failed_payment_result_codes() {
+    let decoded = decode_meta(&failed_payments_ledger(LEDGER_SEQ), PASSPHRASE).unwrap();
+    assert_eq!(decoded.payments.len(), 3);
+    assert!(decoded.payments.iter().all(|p| !p.successful));
+
+    let codes: Vec<&str> = decoded
+        .payments
+        .iter()
+        .map(|p| p.result_code.as_str())
+        .collect();
+    // The payment that applied on its own is rolled back with its transaction
+    assert_eq!(codes, vec!["tx_failed", "op_under_dest_min", "tx_bad_seq"]);
+
+    // Nothing was delivered, so the requested minimum stands in for the amount
+    assert_eq!(decoded.payments[1].amount, "6.0000000");
+}
+
+#[test]
+fn test_payment_result_code_from_result_xdr() {
+    let result = TransactionResult {
+        fee_charged: 100,
+        result: TransactionResultResult::TxFailed(
+            vec![OperationResult::OpInner(OperationResultTr::Payment(
+                PaymentResult::NoTrust,
+            ))]
+            .try_into()
+            .unwrap(),
+        ),
+        ext: TransactionResultExt::V0,
+    };
+    let result_xdr = BASE64.encode(result.to_xdr(Limits::none()).unwrap());
+
+    assert_eq!(
+        payment_result_code_from_xdr(&result_xdr, 0).unwrap(),
+        "op_no_trust"
+    );
+    assert!(payment_result_code_from_xdr("not xdr", 0).is_err());
+}
+
+#[sqlx::test]
+async fn test_failed_payments_are_persisted_with_result_code(pool: SqlitePool) {
+    let dir = fixture_dir(&[failed_payments_ledger(LEDGER_SEQ)], 1);
+    let ledger_source = file_source(&dir);
+    let service = LedgerIngestionService::new(
+        ledger_source,
+        Arc::new(FeeBumpTrackerService::new(pool.clone())),
+        Arc::new(AccountMergeDetector::new(pool.clone())),
+        pool.clone(),
+    );
+    assert_eq!(service.run_ingestion(10).await.unwrap(), 1);
+
+    let rows: Vec<(bool, String)> =
+        sqlx::query_as("SELECT successful, result_code FROM ledger_payments ORDER BY operation_id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+
+    assert_eq!(
+        rows,
+        vec![
+            (false, "tx_failed".to_string()),
+            (false, "op_under_dest_min".to_string()),
+            (false, "tx_bad_seq".to_string()),
+        ]
+    );
+}
+
+/// A ledger with one strict-receive path payment of XLM -> EURT -> USDC that
+/// delivers 5 USDC for 52 XLM
+fn strict_receive_ledger(sequence: u32) -> LedgerCloseMeta {
+    let eurt = Asset::CreditAlphanum4(AlphaNum4 {
+        asset_code: AssetCode4(*b"EURT"),
+        issuer: account_id(10),
+    });
+    let envelope = v1_envelope(transaction(
+        1,
+        3,
+        vec![OperationBody::PathPaymentStrictReceive(
+            PathPaymentStrictReceiveOp {
+                send_asset: Asset::Native,
+                send_max: 600_000_000,
+                destination: account(2),
+                dest_asset: usdc(),
+                dest_amount: 50_000_000,
+                path: vec![eurt.clone()].try_into().unwrap(),
+            },
+        )],
+    ));
+
+    let claim = |asset_sold: Asset, amount_sold, asset_bought: Asset, amount_bought| {
+        ClaimAtom::OrderBook(ClaimOfferAtom {
+            seller_id: account_id(11),
+            offer_id: 1,
+            asset_sold,
+            amount_sold,
+            asset_bought,
+            amount_bought,
+        })
+    };
+    let result = TransactionResultResult::TxSuccess(
+        vec![OperationResult::OpInner(
+            OperationResultTr::PathPaymentStrictReceive(PathPaymentStrictReceiveResult::Success(
+                PathPaymentStrictReceiveResultSuccess {
+                    offers: vec![
+                        claim(eurt.clone(), 46_000_000, Asset::Native, 320_000_000),
+                        claim(eurt.clone(), 30_000_000, Asset::Native, 200_000_000),
+                        claim(usdc(), 50_000_000, eurt, 76_000_000),
+                    ]
+                    .try_into()
+                    .unwrap(),
+                    last: SimplePaymentResult {
+                        destination: account_id(2),
+                        asset: usdc(),
+                        amount: 50_000_000,
+                    },
+                },
+            )),
+        )]
+        .try_into()
+        .unwrap(),
+    );
+
+    let meta = result_meta(&envelope, 100, result);
+    ledger_close_meta(sequence, CLOSE_TIME, vec![envelope], vec![meta])
+}
+
+#[test]
+fn test_decode_strict_receive_path_payment() {
+    let decoded = decode_meta(&strict_receive_ledger(LEDGER_SEQ), PASSPHRASE).unwrap();
+    assert_eq!(decoded.payments.len(), 1);
+
+    let payment = &decoded.payments[0];
+    assert_eq!(payment.operation_type, "path_payment_strict_receive");
+    assert_eq!(payment.asset_code.as_deref(), Some("USDC"));
+    assert_eq!(payment.amount, "5.0000000");
+    // Only the first hop's XLM counts towards what was sent
+    assert_eq!(payment.source_asset_code.as_deref(), Some("XLM"));
+    assert_eq!(payment.source_amount.as_deref(), Some("52.0000000"));
+    assert_eq!(payment.send_max.as_deref(), Some("60.0000000"));
+    assert!(payment.dest_min.is_none());
+    assert_eq!(payment.path.len(), 1);
+    assert!(payment.path[0].starts_with("EURT:G"));
+}
+
+#[sqlx::test]
+async fn test_path_payment_is_persisted_with_source_side(pool: SqlitePool) {
+    let dir = fixture_dir(&[strict_receive_ledger(LEDGER_SEQ)], 1);
+    let ledger_source = file_source(&dir);
+    let service = LedgerIngestionService::new(
+        ledger_source,
+        Arc::new(FeeBumpTrackerService::new(pool.clone())),
+        Arc::new(AccountMergeDetector::new(pool.clone())),
+        pool.clone(),
+    );
+    assert_eq!(service.run_ingestion(10).await.unwrap(), 1);
+
+    let row: (
+        String,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+    ) = sqlx::query_as(
+        "SELECT amount, asset_code, source_asset_code, source_amount, path FROM ledger_payments",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    assert_eq!(row.0, "5.0000000");
+    assert_eq!(row.1.as_deref(), Some("USDC"));
+    assert_eq!(row.2.as_deref(), Some("XLM"));
+    assert_eq!(row.3.as_deref(), Some("52.0000000"));
+    let path: Vec<String> = serde_json::from_str(&row.4.unwrap()).unwrap();
+    assert_eq!(path.len(), 1);
+
+    // The limit reaches the payments aggregation reads
+    let limits: (Option<f64>, Option<f64>) =
+        sqlx::query_as("SELECT send_max, dest_min FROM payments")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(limits, (Some(60.0), None));
+}
+
+#[test]
+fn test_decode_fee_bumps_and_account_merges() {
+    let decoded = decode_ledger_close_meta(&sample_metadata_xdr(), PASSPHRASE).unwrap();
+
+    assert_eq!(decoded.fee_bumps.len(), 1);
+    let fee_bump = &decoded.fee_bumps[0];
+    assert_eq!(fee_bump.fee_charged, 300);
+    assert_eq!(fee_bump.max_fee, 1_000);
+    assert_eq!(fee_bump.inner_max_fee, 100);
+    assert_eq!(fee_bump.signatures_count, 1);
+    assert_ne!(fee_bump.inner_transaction_hash, fee_bump.transaction_hash);
+
+    assert_eq!(decoded.account_merges.len(), 1);
+    let merge = &decoded.account_merges[0];
+    assert!((merge.merged_balance - 125.5).abs() < f64::EPSILON);
+    // TOID: ledger 500, second applied transaction, second operation
+    assert_eq!(
+        merge.operation_id,
+        ((500u64 << 32) | (2 << 12) | 2).to_string()
+    );
+}
+
+/// A protocol 23 ledger: a payment and a failed payment in the classic
+/// phase, and a TTL extension in the Soroban phase's parallel stage
+fn protocol_23_ledger() -> LedgerCloseMeta {
+    let payment_env = v1_envelope(transaction(
+        1,
+        1,
+        vec![OperationBody::Payment(PaymentOp {
+            destination: account(2),
+            asset: usdc(),
+            amount: 30_000_000,
+        })],
+    ));
+    let failed_env = v1_envelope(transaction(
+        3,
+        1,
+        vec![OperationBody::Payment(PaymentOp {
+            destination: account(4),
+            asset: usdc(),
+            amount: 70_000_000,
+        })],
+    ));
+    let soroban_env = v1_envelope(transaction(
+        5,
+        1,
+        vec![OperationBody::ExtendFootprintTtl(ExtendFootprintTtlOp {
+            ext: ExtensionPoint::V0,
+            extend_to: 100_000,
+        })],
+    ));
+
+    let payment_result = |result| {
+        vec![OperationResult::OpInner(OperationResultTr::Payment(result))]
+            .try_into()
+            .unwrap()
+    };
+    let tx_processing = vec![
+        result_meta(
+            &payment_env,
+            100,
+            TransactionResultResult::TxSuccess(payment_result(PaymentResult::Success)),
+        ),
+        result_meta(
+            &failed_env,
+            100,
+            TransactionResultResult::TxFailed(payment_result(PaymentResult::NoTrust)),
+        ),
+        result_meta(
+            &soroban_env,
+            5_000,
+            TransactionResultResult::TxSuccess(
+                vec![OperationResult::OpInner(
+                    OperationResultTr::ExtendFootprintTtl(ExtendFootprintTtlResult::Success),
+                )]
+                .try_into()
+                .unwrap(),
+            ),
+        ),
+    ];
+
+    ledger_close_meta_v2(
+        LEDGER_SEQ,
+        CLOSE_TIME,
+        vec![failed_env, payment_env],
+        vec![soroban_env],
+        tx_processing,
+    )
+}
+
+#[test]
+fn test_decode_protocol_23_ledger() {
+    let meta = protocol_23_ledger();
+    let metadata_xdr = BASE64.encode(meta.to_xdr(Limits::none()).unwrap());
+    let decoded = decode_ledger_close_meta(&metadata_xdr, PASSPHRASE).unwrap();
+
+    assert_eq!(decoded.sequence, LEDGER_SEQ as u64);
+    assert_eq!(decoded.close_time.timestamp(), CLOSE_TIME as i64);
+    assert_eq!(decoded.operation_count, 3);
+    let fees: Vec<i64> = decoded.transactions.iter().map(|t| t.fee_charged).collect();
+    assert_eq!(fees, [100, 100, 5_000]);
+
+    let payments: Vec<_> = decoded
+        .payments
+        .iter()
+        .map(|p| (p.amount.as_str(), p.successful, p.result_code.as_str()))
+        .collect();
+    assert_eq!(
+        payments,
+        [
+            ("3.0000000", true, "op_success"),
+            ("7.0000000", false, "op_no_trust"),
+        ]
+    );
+}
+
+#[test]
+fn test_decode_rejects_unrepresentable_close_time() {
+    let meta = ledger_close_meta(LEDGER_SEQ, u64::MAX, Vec::new(), Vec::new());
+    let error = decode_meta(&meta, PASSPHRASE).unwrap_err();
+    assert!(error.to_string().contains("close time"));
+}
+
+#[sqlx::test]
+async fn test_live_tail_records_undecodable_ledger_and_advances(pool: SqlitePool) {
+    let broken = ledger_close_meta(LEDGER_SEQ + 1, u64::MAX, Vec::new(), Vec::new());
+    let dir = fixture_dir(
+        &[
+            empty_ledger(LEDGER_SEQ),
+            broken,
+            empty_ledger(LEDGER_SEQ + 2),
+        ],
+        3,
+    );
+    let service = LedgerIngestionService::new(
+        file_source(&dir),
+        Arc::new(FeeBumpTrackerService::new(pool.clone())),
+        Arc::new(AccountMergeDetector::new(pool.clone())),
+        pool.clone(),
+    );
+
+    assert_eq!(service.run_ingestion(10).await.unwrap(), 2);
+
+    let errors: Vec<(i64, String)> =
+        sqlx::query_as("SELECT ledger_sequence, error FROM ledger_decode_errors")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0, LEDGER_SEQ as i64 + 1);
+    assert!(errors[0].1.contains("close time"));
+
+    // The cursor moved past the broken ledger, so the next run doesn't retry it
+    assert_eq!(service.run_ingestion(10).await.unwrap(), 0);
+    let cursor: i64 =
+        sqlx::query_scalar("SELECT last_ledger_sequence FROM ingestion_cursor WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(cursor, LEDGER_SEQ as i64 + 2);
+}
+
+#[test]
+fn test_decode_with_wrong_network_matches_nothing() {
+    let decoded = decode_ledger_close_meta(
+        &sample_metadata_xdr(),
+        "Public Global Stellar Network ; September 2015",
+    )
+    .unwrap();
+
+    // Hashes no longer match the results, so nothing is attributed
+    assert!(decoded.transactions.is_empty());
+    assert!(decoded.payments.is_empty());
+}
+
+#[test]
+fn test_decode_invalid_xdr() {
+    assert!(decode_ledger_close_meta("mock_metadata", PASSPHRASE).is_err());
+}
+
+#[sqlx::test]
+async fn test_decoded_ledger_is_recorded(pool: SqlitePool) {
+    let decoded = decode_ledger_close_meta(&sample_metadata_xdr(), PASSPHRASE).unwrap();
+
+    sqlx::query(
+        "INSERT INTO ledgers (sequence, hash, close_time, transaction_count, operation_count) VALUES (500, 'ledger_hash_500', '2026-01-22T10:30:00Z', 3, 4)",
+    )
+    .execute(&pool)
+    .await
+    .expect("failed to insert ledger row");
+
+    let fee_bump_tracker = FeeBumpTrackerService::new(pool.clone());
+    assert_eq!(
+        fee_bump_tracker
+            .record_fee_bumps(&decoded.fee_bumps)
+            .await
+            .unwrap(),
+        1
+    );
+
+    let detector = AccountMergeDetector::new(pool.clone());
+    assert_eq!(
+        detector
+            .record_merges(&decoded.account_merges)
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        detector
+            .record_merges(&decoded.account_merges)
+            .await
+            .unwrap(),
+        0
+    );
+
+    let stats = detector.get_merge_stats().await.unwrap();
+    assert_eq!(stats.total_merges, 1);
+}
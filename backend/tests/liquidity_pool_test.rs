@@ -1,17 +1,76 @@
+mod common;
+
+use common::{changes_ledger, file_source, fixture_dir, pool_change, usdc};
 use sqlx::SqlitePool;
-use std::sync::Arc;
-use stellar_insights_backend::rpc::StellarRpcClient;
+use stellar_insights_backend::rpc::file_source::{batch_file_name, write_batch};
 use stellar_insights_backend::services::liquidity_pool_analyzer::LiquidityPoolAnalyzer;
+use stellar_xdr::curr::Asset;
+use tempfile::TempDir;
+
+/// Five pools created across two ledgers, one of them updated later
+fn pool_fixtures() -> TempDir {
+    let ledgers = vec![
+        changes_ledger(
+            300,
+            vec![
+                pool_change(
+                    1,
+                    Asset::Native,
+                    usdc(),
+                    12_000_000_000_000,
+                    5_000_000_000_000,
+                ),
+                pool_change(2, Asset::Native, usdc(), 4_000_000_000, 1_000_000_000),
+                pool_change(
+                    3,
+                    usdc(),
+                    Asset::Native,
+                    3_200_000_000_000,
+                    2_950_000_000_000,
+                ),
+            ],
+        ),
+        changes_ledger(
+            301,
+            vec![
+                pool_change(
+                    4,
+                    Asset::Native,
+                    usdc(),
+                    8_000_000_000_000,
+                    50_000_000_000_000,
+                ),
+                pool_change(
+                    5,
+                    usdc(),
+                    Asset::Native,
+                    1_800_000_000_000,
+                    1_795_000_000_000,
+                ),
+            ],
+        ),
+        changes_ledger(
+            302,
+            vec![pool_change(
+                2,
+                Asset::Native,
+                usdc(),
+                4_500_000_000,
+                900_000_000,
+            )],
+        ),
+    ];
+    fixture_dir(&ledgers, 2)
+}
 
 #[sqlx::test]
 async fn test_liquidity_pool_sync_and_query(pool: SqlitePool) {
-    // Create a mock RPC client
-    let rpc_client = Arc::new(StellarRpcClient::new_with_defaults(true));
-    let analyzer = LiquidityPoolAnalyzer::new(pool.clone(), rpc_client);
+    let dir = pool_fixtures();
+    let analyzer = LiquidityPoolAnalyzer::new(pool.clone(), file_source(&dir));
 
-    // Sync pools from mock Horizon data
+    // Sync pools replayed from fixture ledgers
     let count = analyzer.sync_pools().await.unwrap();
-    assert_eq!(count, 5); // Mock returns 5 pools
+    assert_eq!(count, 5); // Fixtures create 5 pools
 
     // Verify all pools are stored
     let pools = analyzer.get_all_pools().await.unwrap();
@@ -33,8 +92,8 @@ async fn test_liquidity_pool_sync_and_query(pool: SqlitePool) {
 
 #[sqlx::test]
 async fn test_liquidity_pool_rankings(pool: SqlitePool) {
-    let rpc_client = Arc::new(StellarRpcClient::new_with_defaults(true));
-    let analyzer = LiquidityPoolAnalyzer::new(pool.clone(), rpc_client);
+    let dir = pool_fixtures();
+    let analyzer = LiquidityPoolAnalyzer::new(pool.clone(), file_source(&dir));
 
     // Sync first
     analyzer.sync_pools().await.unwrap();
@@ -54,8 +113,8 @@ async fn test_liquidity_pool_rankings(pool: SqlitePool) {
 
 #[sqlx::test]
 async fn test_liquidity_pool_snapshots(pool: SqlitePool) {
-    let rpc_client = Arc::new(StellarRpcClient::new_with_defaults(true));
-    let analyzer = LiquidityPoolAnalyzer::new(pool.clone(), rpc_client);
+    let dir = pool_fixtures();
+    let analyzer = LiquidityPoolAnalyzer::new(pool.clone(), file_source(&dir));
 
     // Sync pools first
     analyzer.sync_pools().await.unwrap();
@@ -76,8 +135,8 @@ async fn test_liquidity_pool_snapshots(pool: SqlitePool) {
 
 #[sqlx::test]
 async fn test_liquidity_pool_detail(pool: SqlitePool) {
-    let rpc_client = Arc::new(StellarRpcClient::new_with_defaults(true));
-    let analyzer = LiquidityPoolAnalyzer::new(pool.clone(), rpc_client);
+    let dir = pool_fixtures();
+    let analyzer = LiquidityPoolAnalyzer::new(pool.clone(), file_source(&dir));
 
     // Sync and snapshot
     analyzer.sync_pools().await.unwrap();
@@ -110,3 +169,68 @@ fn test_impermanent_loss_computation() {
     let il = LiquidityPoolAnalyzer::compute_impermanent_loss(0.0, 100.0, 100.0, 100.0);
     assert_eq!(il, 0.0);
 }
+
+#[sqlx::test]
+async fn test_liquidity_pool_sync_uses_latest_state(pool: SqlitePool) {
+    let dir = pool_fixtures();
+    let analyzer = LiquidityPoolAnalyzer::new(pool.clone(), file_source(&dir));
+
+    analyzer.sync_pools().await.unwrap();
+
+    // Pool 2 was updated in a later ledger than it was created in
+    let (detail, _) = analyzer
+        .get_pool_detail(&hex::encode([2u8; 32]))
+        .await
+        .unwrap();
+    assert!((detail.reserve_a_amount - 450.0).abs() < 1e-9);
+    assert!((detail.reserve_b_amount - 90.0).abs() < 1e-9);
+    assert_eq!(detail.total_trustlines, 102);
+}
+
+#[sqlx::test]
+async fn test_liquidity_pool_sync_applies_new_ledgers_only(pool: SqlitePool) {
+    let dir = pool_fixtures();
+    let analyzer = LiquidityPoolAnalyzer::new(pool.clone(), file_source(&dir));
+    assert_eq!(analyzer.sync_pools().await.unwrap(), 5);
+
+    // Batches already replayed aren't read again, so pruning them keeps the
+    // pools they created
+    std::fs::remove_file(dir.path().join(batch_file_name(300, 301))).unwrap();
+    write_batch(
+        dir.path(),
+        &[changes_ledger(
+            303,
+            vec![pool_change(
+                2,
+                Asset::Native,
+                usdc(),
+                5_000_000_000,
+                800_000_000,
+            )],
+        )],
+    )
+    .unwrap();
+    // A ledger after a gap waits for the gap to be filled
+    write_batch(
+        dir.path(),
+        &[changes_ledger(
+            305,
+            vec![pool_change(
+                2,
+                Asset::Native,
+                usdc(),
+                1_000_000_000,
+                1_000_000_000,
+            )],
+        )],
+    )
+    .unwrap();
+
+    assert_eq!(analyzer.sync_pools().await.unwrap(), 5);
+    let (detail, _) = analyzer
+        .get_pool_detail(&hex::encode([2u8; 32]))
+        .await
+        .unwrap();
+    assert!((detail.reserve_a_amount - 500.0).abs() < 1e-9);
+    assert!((detail.reserve_b_amount - 80.0).abs() < 1e-9);
+}
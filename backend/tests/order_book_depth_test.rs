@@ -0,0 +1,139 @@
+mod common;
+
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::Router;
+use chrono::Duration;
+use common::{api_state, current_hour, get_json, hourly, settled_payment, static_price_feed};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use stellar_insights_backend::api::corridors_cached::{
+    get_corridor_depth, get_corridor_depth_history,
+};
+use stellar_insights_backend::database::Database;
+use stellar_insights_backend::services::order_book_depth::OrderBookDepthService;
+use stellar_insights_backend::services::price_feed::default_asset_mapping;
+
+const USDC_ISSUER: &str = "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN";
+
+async fn depth_app(db: Arc<Database>) -> Router {
+    Router::new()
+        .route(
+            "/api/corridors/:corridor_key/depth",
+            get(get_corridor_depth),
+        )
+        .route(
+            "/api/corridors/:corridor_key/depth/history",
+            get(get_corridor_depth_history),
+        )
+        .with_state(api_state(db).await)
+}
+
+#[sqlx::test]
+async fn test_corridor_depth_endpoint(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool));
+
+    // The stub order book: bids of 1000, 2500 and 5000 XLM, asks of 1200,
+    // 3000 and 4500 USDC, with USDC as the base
+    let (status, json) = get_json(
+        depth_app(Arc::clone(&db)).await,
+        &format!(
+            "/api/corridors/XLM%3Anative-%3EUSDC%3A{}/depth",
+            USDC_ISSUER
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        json["corridor_key"],
+        format!("USDC:{}->XLM:native", USDC_ISSUER)
+    );
+    assert_eq!(json["base_usd_price"], 1.0);
+    assert_eq!(json["counter_usd_price"], 0.1);
+
+    let bids = json["bids"].as_array().unwrap();
+    assert_eq!(bids.len(), 3);
+    assert!((bids[2]["cumulative_usd"].as_f64().unwrap() - 850.0).abs() < 1e-6);
+    let asks = json["asks"].as_array().unwrap();
+    assert!((asks[2]["cumulative_usd"].as_f64().unwrap() - 8_700.0).abs() < 1e-6);
+
+    let slippage_bps: Vec<u64> = json["quotes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|q| q["slippage_bps"].as_u64().unwrap())
+        .collect();
+    assert_eq!(slippage_bps, vec![10, 50, 100, 200]);
+
+    // Neither asset has a price
+    let (status, _) = get_json(
+        depth_app(Arc::clone(&db)).await,
+        "/api/corridors/AAA%3AGONE-%3EBBB%3AGTWO/depth",
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = get_json(depth_app(db).await, "/api/corridors/not-a-corridor/depth").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_depth_snapshots_of_active_corridors(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool.clone()));
+    // One settled payment in each corridor this hour; the second is active
+    // but unpriced, so it has no snapshot
+    let hour = current_hour();
+    for (source, destination, amount) in [
+        (("USDC", USDC_ISSUER), ("XLM", "native"), 500.0),
+        (("AAA", "GONE"), ("BBB", "GTWO"), 100.0),
+    ] {
+        let payment = settled_payment(source, destination, amount, hour + Duration::minutes(1), 5);
+        db.upsert_hourly_corridor_metric(&hourly(hour, &[payment]))
+            .await
+            .unwrap();
+    }
+
+    let service = OrderBookDepthService::new(
+        pool,
+        Arc::new(common::horizon::client()),
+        static_price_feed(default_asset_mapping()),
+    );
+    assert_eq!(service.take_snapshots().await.unwrap(), 1);
+    assert_eq!(service.take_snapshots().await.unwrap(), 1);
+
+    let (status, json) = get_json(
+        depth_app(Arc::clone(&db)).await,
+        &format!(
+            "/api/corridors/USDC%3A{}-%3EXLM%3Anative/depth/history?window=7d",
+            USDC_ISSUER
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["window"], "7d");
+    let snapshots = json["snapshots"].as_array().unwrap();
+    assert_eq!(snapshots.len(), 2);
+    assert!(snapshots[0]["snapshot_at"].as_str() <= snapshots[1]["snapshot_at"].as_str());
+    assert!((snapshots[0]["bid_depth_usd"].as_f64().unwrap() - 850.0).abs() < 1e-6);
+    assert!((snapshots[0]["ask_depth_usd"].as_f64().unwrap() - 8_700.0).abs() < 1e-6);
+    assert_eq!(snapshots[0]["quotes"].as_array().unwrap().len(), 4);
+
+    let (status, json) = get_json(
+        depth_app(Arc::clone(&db)).await,
+        "/api/corridors/AAA%3AGONE-%3EBBB%3AGTWO/depth/history",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["snapshots"], Value::Array(Vec::new()));
+
+    let (status, _) = get_json(
+        depth_app(db).await,
+        &format!(
+            "/api/corridors/USDC%3A{}-%3EXLM%3Anative/depth/history?window=forever",
+            USDC_ISSUER
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
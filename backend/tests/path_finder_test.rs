@@ -0,0 +1,108 @@
+mod common;
+
+use axum::http::StatusCode;
+use axum::Router;
+use common::get_json;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use stellar_insights_backend::api::paths::routes;
+use stellar_insights_backend::services::path_finder::PathFinder;
+
+const BRL: &str = "BRL:GBRLISSUER";
+const USDC: &str = "USDC:GUSDCISSUER";
+const NGN: &str = "NGN:GNGNISSUER";
+
+/// BRL and NGN only meet through USDC pools, each pair also getting the stub
+/// order book
+async fn path_finder(pool: SqlitePool) -> Arc<PathFinder> {
+    for (pool_id, a, b) in [("brl-usdc", BRL, USDC), ("usdc-ngn", USDC, NGN)] {
+        let (a_code, a_issuer) = a.split_once(':').unwrap();
+        let (b_code, b_issuer) = b.split_once(':').unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO liquidity_pools (
+                pool_id, fee_bp, reserve_a_asset_code, reserve_a_asset_issuer, reserve_a_amount,
+                reserve_b_asset_code, reserve_b_asset_issuer, reserve_b_amount
+            )
+            VALUES ($1, 30, $2, $3, 50000.0, $4, $5, 50000.0)
+            "#,
+        )
+        .bind(pool_id)
+        .bind(a_code)
+        .bind(a_issuer)
+        .bind(b_code)
+        .bind(b_issuer)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let path_finder = Arc::new(PathFinder::new(pool, Arc::new(common::horizon::client())));
+    assert_eq!(path_finder.refresh().await.unwrap(), 4);
+    path_finder
+}
+
+fn paths_app(path_finder: Arc<PathFinder>) -> Router {
+    Router::new().nest("/api/paths", routes(path_finder))
+}
+
+#[sqlx::test]
+async fn test_strict_send_and_receive_from_the_graph(pool: SqlitePool) {
+    let path_finder = path_finder(pool).await;
+
+    let (status, json) = get_json(
+        paths_app(Arc::clone(&path_finder)),
+        &format!(
+            "/api/paths/strict-send?source_asset={}&source_amount=1000&destination_asset={}",
+            BRL, NGN
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(json["graph_built_at"].is_string());
+    let paths = json["paths"].as_array().unwrap();
+    assert_eq!(paths.len(), 1);
+    assert_eq!(paths[0]["path"], serde_json::json!([USDC]));
+    assert_eq!(paths[0]["source_amount"], 1000.0);
+    assert_eq!(paths[0]["hops"].as_array().unwrap().len(), 2);
+    let received = paths[0]["destination_amount"].as_f64().unwrap();
+    assert!(received > 900.0 && received < 1000.0, "{}", received);
+
+    // Asking to receive that much costs what was sent
+    let (status, json) = get_json(
+        paths_app(Arc::clone(&path_finder)),
+        &format!(
+            "/api/paths/strict-receive?source_asset={}&destination_asset={}&destination_amount={}",
+            BRL, NGN, received
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let paths = json["paths"].as_array().unwrap();
+    assert_eq!(paths[0]["path"], serde_json::json!([USDC]));
+    let sent = paths[0]["source_amount"].as_f64().unwrap();
+    assert!((sent - 1000.0).abs() < 1e-6, "{}", sent);
+
+    // Unconnected assets have no paths, and bad input is rejected
+    let (status, json) = get_json(
+        paths_app(Arc::clone(&path_finder)),
+        &format!(
+            "/api/paths/strict-send?source_asset={}&source_amount=10&destination_asset=EUR:GEURISSUER",
+            BRL
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["paths"], Value::Array(Vec::new()));
+
+    let (status, _) = get_json(
+        paths_app(path_finder),
+        &format!(
+            "/api/paths/strict-receive?source_asset={}&destination_asset={}&destination_amount=0",
+            BRL, NGN
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
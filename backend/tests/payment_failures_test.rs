@@ -0,0 +1,261 @@
+mod common;
+
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::Router;
+use chrono::{DateTime, Duration, Utc};
+use common::{api_state, current_hour, get_json};
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use stellar_insights_backend::api::anchors_cached::get_anchor_failures;
+use stellar_insights_backend::api::corridors_cached::get_corridor_failures;
+use stellar_insights_backend::database::Database;
+use stellar_insights_backend::models::{CreateAnchorRequest, PaymentRecord};
+use stellar_insights_backend::services::aggregation::HourlyCorridorMetrics;
+use stellar_insights_backend::services::analytics::compute_metrics_from_payments;
+use stellar_insights_backend::services::distribution::CorridorSketches;
+use uuid::Uuid;
+
+fn payment(created_at: DateTime<Utc>, result_code: &str) -> PaymentRecord {
+    PaymentRecord {
+        id: Uuid::new_v4().to_string(),
+        transaction_hash: "txhash".to_string(),
+        source_account: "GSOURCE".to_string(),
+        destination_account: "GDEST".to_string(),
+        asset_type: "credit_alphanum4".to_string(),
+        asset_code: Some("USDC".to_string()),
+        asset_issuer: Some("GISSUER".to_string()),
+        source_asset_code: "USDC".to_string(),
+        source_asset_issuer: "GISSUER".to_string(),
+        destination_asset_code: "USDC".to_string(),
+        destination_asset_issuer: "GISSUER".to_string(),
+        amount: 25.0,
+        operation_type: Some("payment".to_string()),
+        source_amount: None,
+        send_max: None,
+        dest_min: None,
+        path: None,
+        successful: result_code == "op_success",
+        result_code: Some(result_code.to_string()),
+        timestamp: Some(created_at),
+        submission_time: None,
+        confirmation_time: None,
+        created_at,
+    }
+}
+
+fn hour_with_failures(
+    hour_bucket: DateTime<Utc>,
+    breakdown: &[(&str, i64)],
+) -> HourlyCorridorMetrics {
+    let failed: i64 = breakdown.iter().map(|(_, count)| count).sum();
+    HourlyCorridorMetrics {
+        id: Uuid::new_v4().to_string(),
+        corridor_key: "USDC:GISSUER->XLM:native".to_string(),
+        asset_a_code: "USDC".to_string(),
+        asset_a_issuer: "GISSUER".to_string(),
+        asset_b_code: "XLM".to_string(),
+        asset_b_issuer: "native".to_string(),
+        hour_bucket,
+        total_transactions: failed + 10,
+        successful_transactions: 10,
+        failed_transactions: failed,
+        success_rate: 10.0 / (failed + 10) as f64 * 100.0,
+        volume_usd: 100.0,
+        avg_slippage_bps: 0.0,
+        avg_fx_rate: None,
+        avg_settlement_latency_ms: None,
+        p50_settlement_latency_ms: None,
+        p90_settlement_latency_ms: None,
+        p99_settlement_latency_ms: None,
+        liquidity_depth_usd: 0.0,
+        failure_breakdown: breakdown
+            .iter()
+            .map(|(code, count)| (code.to_string(), *count))
+            .collect(),
+        sketches: CorridorSketches::default(),
+    }
+}
+
+#[sqlx::test]
+async fn test_failed_payments_drive_success_rate(pool: SqlitePool) {
+    let db = Database::new(pool);
+    let now = Utc::now();
+    db.save_payments(vec![
+        payment(now, "op_success"),
+        payment(now, "op_success"),
+        payment(now, "op_success"),
+        payment(now, "op_underfunded"),
+    ])
+    .await
+    .unwrap();
+
+    let payments = db
+        .fetch_payments_by_timerange(now - Duration::minutes(1), now + Duration::minutes(1), 100)
+        .await
+        .unwrap();
+    assert_eq!(payments.len(), 4);
+
+    let metrics = compute_metrics_from_payments(&payments);
+    assert_eq!(metrics.len(), 1);
+    assert_eq!(metrics[0].successful_transactions, 3);
+    assert_eq!(metrics[0].failed_transactions, 1);
+    assert_eq!(metrics[0].success_rate, 75.0);
+    assert_eq!(
+        metrics[0].failure_breakdown,
+        BTreeMap::from([("op_underfunded".to_string(), 1)])
+    );
+}
+
+#[sqlx::test]
+async fn test_hourly_failure_breakdown_is_replaced_by_a_rerun(pool: SqlitePool) {
+    let db = Database::new(pool);
+    let hour = current_hour();
+
+    db.upsert_hourly_corridor_metric(&hour_with_failures(hour, &[("op_underfunded", 2)]))
+        .await
+        .unwrap();
+    db.upsert_hourly_corridor_metric(&hour_with_failures(
+        hour,
+        &[("op_underfunded", 1), ("op_no_trust", 3)],
+    ))
+    .await
+    .unwrap();
+
+    let stored = db
+        .aggregation_db()
+        .fetch_hourly_metrics_by_timerange(hour, hour + Duration::hours(1))
+        .await
+        .unwrap();
+    // The hour was recomputed from all its payments, so the rerun's counts
+    // stand on their own
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].failed_transactions, 4);
+    assert_eq!(
+        stored[0].failure_breakdown,
+        BTreeMap::from([
+            ("op_no_trust".to_string(), 3),
+            ("op_underfunded".to_string(), 1),
+        ])
+    );
+}
+
+async fn failures_app(db: Arc<Database>) -> Router {
+    Router::new()
+        .route(
+            "/api/corridors/:corridor_key/failures",
+            get(get_corridor_failures),
+        )
+        .route("/api/anchors/:id/failures", get(get_anchor_failures))
+        .with_state(api_state(db).await)
+}
+
+#[sqlx::test]
+async fn test_corridor_failures_endpoint(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool));
+    let hour = current_hour();
+    db.upsert_hourly_corridor_metric(&hour_with_failures(
+        hour,
+        &[("op_underfunded", 4), ("op_no_trust", 1)],
+    ))
+    .await
+    .unwrap();
+    db.upsert_hourly_corridor_metric(&hour_with_failures(
+        hour - Duration::hours(7),
+        &[("op_underfunded", 2)],
+    ))
+    .await
+    .unwrap();
+
+    // The key is accepted in either asset order
+    let (status, json) = get_json(
+        failures_app(Arc::clone(&db)).await,
+        "/api/corridors/XLM%3Anative-%3EUSDC%3AGISSUER/failures?window=6h",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["corridor_key"], "USDC:GISSUER->XLM:native");
+
+    let failures = &json["failures"];
+    assert_eq!(failures["window"], "6h");
+    assert_eq!(failures["failed_transactions"], 5);
+    assert_eq!(failures["previous_failed_transactions"], 2);
+    assert_eq!(failures["reasons"][0]["result_code"], "op_underfunded");
+    assert_eq!(failures["reasons"][0]["count"], 4);
+    assert_eq!(failures["reasons"][0]["change_pct"], 100.0);
+
+    let (status, _) = get_json(
+        failures_app(Arc::clone(&db)).await,
+        "/api/corridors/USDC%3AGISSUER-%3EXLM%3Anative/failures?window=soon",
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = get_json(
+        failures_app(db).await,
+        "/api/corridors/EURC%3AGOTHER-%3EXLM%3Anative/failures",
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_anchor_failures_endpoint(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool));
+    let anchor = db
+        .create_anchor(CreateAnchorRequest {
+            name: "Test Anchor".to_string(),
+            stellar_account: "GISSUER".to_string(),
+            home_domain: None,
+        })
+        .await
+        .unwrap();
+    db.upsert_hourly_corridor_metric(&hour_with_failures(current_hour(), &[("op_line_full", 3)]))
+        .await
+        .unwrap();
+
+    let (status, json) = get_json(
+        failures_app(Arc::clone(&db)).await,
+        &format!("/api/anchors/{}/failures", anchor.id),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["failures"]["window"], "24h");
+    assert_eq!(
+        json["failures"]["reasons"][0]["result_code"],
+        "op_line_full"
+    );
+    assert_eq!(
+        json["corridors"][0]["corridor_key"],
+        "USDC:GISSUER->XLM:native"
+    );
+    assert_eq!(json["corridors"][0]["failed_transactions"], 3);
+
+    let (status, _) = get_json(
+        failures_app(db).await,
+        &format!("/api/anchors/{}/failures", Uuid::new_v4()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_failed_horizon_payments_carry_their_result_code() {
+    let client = common::horizon::client();
+
+    let payments = client.fetch_payments(200, None).await.unwrap();
+    let reasons: Vec<_> = payments
+        .iter()
+        .map(|p| (p.id.as_str(), p.failure_reason()))
+        .collect();
+    let ids = common::horizon::PAYMENT_IDS;
+    assert_eq!(
+        reasons,
+        [
+            (ids[0], None),
+            (ids[1], Some("op_underfunded".to_string())),
+            (ids[2], None),
+        ]
+    );
+}
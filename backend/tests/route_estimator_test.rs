@@ -0,0 +1,123 @@
+mod common;
+
+use axum::http::StatusCode;
+use axum::Router;
+use common::{get_json, static_price_feed};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use stellar_insights_backend::api::route_estimator::routes;
+use stellar_insights_backend::models::corridor::Corridor;
+use stellar_insights_backend::services::price_feed::default_asset_mapping;
+use stellar_insights_backend::services::route_estimator::{PoolReserves, RouteEstimator};
+
+const USDC: &str = "USDC:GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN";
+const USDC_ISSUER: &str = "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN";
+
+/// 5000 USDC against 5000 XLM, alongside the stub order book's bids at
+/// 0.995, 0.99 and 0.985
+async fn estimator(pool: SqlitePool) -> RouteEstimator {
+    sqlx::query(
+        r#"
+        INSERT INTO liquidity_pools (
+            pool_id, fee_bp, reserve_a_asset_code, reserve_a_asset_issuer, reserve_a_amount,
+            reserve_b_asset_code, reserve_b_asset_issuer, reserve_b_amount
+        )
+        VALUES ('pool-usdc-xlm', 30, 'XLM', NULL, 5000.0, 'USDC', $1, 5000.0)
+        "#,
+    )
+    .bind(USDC_ISSUER)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    RouteEstimator::new(
+        pool,
+        Arc::new(common::horizon::client()),
+        static_price_feed(default_asset_mapping()),
+    )
+}
+
+fn routes_app(estimator: Arc<RouteEstimator>) -> Router {
+    Router::new().nest("/api/routes", routes(estimator))
+}
+
+#[sqlx::test]
+async fn test_estimate_combines_order_book_and_pool(pool: SqlitePool) {
+    let estimator = Arc::new(estimator(pool).await);
+    let (status, json) = get_json(
+        routes_app(Arc::clone(&estimator)),
+        &format!(
+            "/api/routes/estimate?source_asset={}&destination_asset=XLM:native&amount=1000",
+            USDC
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["source_asset"], USDC);
+    assert_eq!(json["fully_filled"], true);
+    assert!((json["best_price"].as_f64().unwrap() - 0.997).abs() < 1e-9);
+
+    let fills = json["fills"].as_array().unwrap();
+    assert_eq!(fills.len(), 2);
+    assert_eq!(fills[0]["venue"], "order_book");
+    assert_eq!(fills[1]["pool_id"], "pool-usdc-xlm");
+    let sent: f64 = fills
+        .iter()
+        .map(|f| f["source_amount"].as_f64().unwrap())
+        .sum();
+    assert!((sent - 1000.0).abs() < 1e-6);
+
+    // Better than either venue alone
+    let received = json["destination_amount"].as_f64().unwrap();
+    let pool_only = PoolReserves {
+        pool_id: "pool-usdc-xlm".to_string(),
+        reserve_in: 5000.0,
+        reserve_out: 5000.0,
+        fee_bp: 30,
+    }
+    .amount_out(1000.0);
+    assert!(received > 995.0 && received > pool_only, "{}", received);
+    assert!(json["slippage_bps"].as_f64().unwrap() > 0.0);
+
+    for query in [
+        "source_asset=USDC&destination_asset=XLM:native&amount=10",
+        "source_asset=XLM:native&destination_asset=XLM:native&amount=10",
+        "source_asset=XLM:native&destination_asset=EURC:GISSUER&amount=-1",
+    ] {
+        let (status, _) = get_json(
+            routes_app(Arc::clone(&estimator)),
+            &format!("/api/routes/estimate?{}", query),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", query);
+    }
+}
+
+#[sqlx::test]
+async fn test_corridor_liquidity_depth_covers_both_directions(pool: SqlitePool) {
+    let estimator = estimator(pool).await;
+    let corridor = Corridor::from_key(&format!("XLM:native->{}", USDC)).unwrap();
+
+    // The stub order book and the balanced pool are the same each way, so
+    // XLM's depth is the same amount at a tenth of the price
+    let venues = estimator.fetch_venues(USDC, "XLM:native").await.unwrap();
+    assert_eq!(venues.pools.len(), 1);
+    let depth = venues.depth_within_bps(100.0);
+    assert!(depth > 0.0);
+
+    let depth_usd = estimator
+        .liquidity_depth_usd(&corridor, 100.0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!((depth_usd - depth * 1.1).abs() < 1e-6, "{}", depth_usd);
+
+    let unpriced = Corridor::from_key("AAA:GONE->BBB:GTWO").unwrap();
+    assert_eq!(
+        estimator
+            .liquidity_depth_usd(&unpriced, 100.0)
+            .await
+            .unwrap(),
+        None
+    );
+}
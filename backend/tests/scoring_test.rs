@@ -0,0 +1,185 @@
+mod common;
+
+use axum::http::StatusCode;
+use axum::Router;
+use chrono::{Duration, Utc};
+use common::{current_hour, get_json, hourly, settled_payment};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use stellar_insights_backend::api::scoring::routes;
+use stellar_insights_backend::database::Database;
+use stellar_insights_backend::models::corridor::PaymentRecord;
+use stellar_insights_backend::models::{AnchorReliabilityRecord, CreateAnchorRequest};
+use stellar_insights_backend::scoring::{ScoringModel, ScoringModels};
+use stellar_insights_backend::services::aggregation::HourlyCorridorMetrics;
+use uuid::Uuid;
+
+const CORRIDOR_KEY: &str = "USDC:GISSUER->XLM:native";
+
+/// The built-in v1 model, active, and a stricter v2 that weighs success rate
+/// more heavily
+fn models() -> Arc<ScoringModels> {
+    let mut candidate = ScoringModel {
+        version: "v2".to_string(),
+        ..ScoringModel::default()
+    };
+    candidate.corridor_health.success_weight = 0.8;
+    candidate.corridor_health.volume_weight = 0.1;
+    candidate.corridor_health.transaction_weight = 0.1;
+    candidate.anchor_metrics.success_weight = 0.9;
+    candidate.anchor_metrics.settlement_weight = 0.1;
+    candidate.anchor_status.green_min_success_rate = 99.5;
+    candidate.anchor_reliability.diversity_target_assets = 2;
+
+    let json = serde_json::json!({
+        "active": "v1",
+        "models": [ScoringModel::default(), candidate],
+    });
+    Arc::new(ScoringModels::from_json(&json.to_string()).unwrap())
+}
+
+/// Ten settled $20 payments in the current hour, without settlement times
+fn current_hour_metrics() -> HourlyCorridorMetrics {
+    let hour_bucket = current_hour();
+    let payments: Vec<PaymentRecord> = (0..10)
+        .map(|_| PaymentRecord {
+            submission_time: None,
+            confirmation_time: None,
+            ..settled_payment(("USDC", "GISSUER"), ("XLM", "native"), 20.0, hour_bucket, 0)
+        })
+        .collect();
+    hourly(hour_bucket, &payments)
+}
+
+fn scoring_app(db: Arc<Database>) -> Router {
+    Router::new().nest("/api/scoring", routes(db, models()))
+}
+
+#[sqlx::test]
+async fn test_compare_candidate_against_stored_scores(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool));
+    let anchor = db
+        .create_anchor(CreateAnchorRequest {
+            name: "Scored Anchor".to_string(),
+            stellar_account: "GSCOREDANCHOR".to_string(),
+            home_domain: None,
+        })
+        .await
+        .unwrap();
+    let anchor_id = Uuid::parse_str(&anchor.id).unwrap();
+
+    // 99% success rate: green under v1, yellow under v2
+    let updated = db
+        .update_anchor_metrics(anchor_id, 1000, 990, 10, Some(2000), Some(5000.0))
+        .await
+        .unwrap();
+    assert_eq!(updated.scoring_version.as_deref(), Some("v1"));
+
+    db.record_anchor_reliability_score(&AnchorReliabilityRecord {
+        anchor_id: anchor.id.clone(),
+        composite_score: 0.6 * 90.0 + 0.3 * 50.0 + 0.1 * 20.0,
+        asset_performance_score: 90.0,
+        volume_score: 50.0,
+        asset_diversity_score: 20.0,
+        weighted_success_rate: 90.0,
+        total_assets: 2,
+        total_volume_usd: 5000.0,
+        scoring_version: Some("v1".to_string()),
+        assets: Vec::new(),
+        window_start: Utc::now() - Duration::days(7),
+        computed_at: Utc::now(),
+    })
+    .await
+    .unwrap();
+
+    db.upsert_hourly_corridor_metric(&current_hour_metrics())
+        .await
+        .unwrap();
+
+    let (status, json) = get_json(
+        scoring_app(Arc::clone(&db)),
+        "/api/scoring/compare?candidate=v2&window=24h",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["baseline_version"], "v1");
+    assert_eq!(json["candidate_version"], "v2");
+
+    let metrics = json["anchor_metrics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["anchor_id"] == anchor.id.as_str())
+        .unwrap();
+    assert_eq!(metrics["recorded_version"], "v1");
+    let recorded = metrics["recorded_score"].as_f64().unwrap();
+    assert!((metrics["baseline_score"].as_f64().unwrap() - recorded).abs() < 1e-9);
+    let expected = 99.0 * 0.9 + (10000.0 - 2000.0) / 9000.0 * 100.0 * 0.1;
+    assert!((metrics["candidate_score"].as_f64().unwrap() - expected).abs() < 1e-9);
+    assert_eq!(metrics["baseline_status"], "green");
+    assert_eq!(metrics["candidate_status"], "yellow");
+    assert!(json["summary"]["anchor_status_changes"].as_u64().unwrap() >= 1);
+
+    // Only the diversity component depends on the model
+    let reliability = json["anchor_reliability"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["anchor_id"] == anchor.id.as_str())
+        .unwrap();
+    let recorded = reliability["recorded_score"].as_f64().unwrap();
+    assert!((reliability["baseline_score"].as_f64().unwrap() - recorded).abs() < 1e-9);
+    let expected = 0.6 * 90.0 + 0.3 * 50.0 + 0.1 * 100.0;
+    assert!((reliability["candidate_score"].as_f64().unwrap() - expected).abs() < 1e-9);
+
+    let corridors = json["corridor_health"].as_array().unwrap();
+    assert_eq!(corridors.len(), 1);
+    assert_eq!(corridors[0]["corridor_key"], CORRIDOR_KEY);
+    assert!(
+        corridors[0]["candidate_score"].as_f64().unwrap()
+            > corridors[0]["baseline_score"].as_f64().unwrap()
+    );
+    assert_eq!(json["summary"]["corridor_health"]["compared"], 1);
+}
+
+#[sqlx::test]
+async fn test_models_and_rejected_comparisons(pool: SqlitePool) {
+    let db = Arc::new(Database::new(pool));
+
+    let (status, json) = get_json(scoring_app(Arc::clone(&db)), "/api/scoring/models").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["active"], "v1");
+    assert_eq!(json["models"].as_array().unwrap().len(), 2);
+
+    // Comparing a version with itself changes nothing
+    let (status, json) = get_json(
+        scoring_app(Arc::clone(&db)),
+        "/api/scoring/compare?candidate=v1",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["window"], "24h");
+    assert_eq!(json["summary"]["anchor_status_changes"], 0);
+    assert_eq!(json["summary"]["corridor_health"]["max_abs_delta"], 0.0);
+
+    let (status, _) = get_json(
+        scoring_app(Arc::clone(&db)),
+        "/api/scoring/compare?candidate=v9",
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = get_json(
+        scoring_app(Arc::clone(&db)),
+        "/api/scoring/compare?candidate=v2&baseline=v9",
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = get_json(
+        scoring_app(db),
+        "/api/scoring/compare?candidate=v2&window=1y",
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
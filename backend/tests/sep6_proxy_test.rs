@@ -0,0 +1,175 @@
+mod common;
+
+use axum::extract::Query;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::get;
+use axum::{Json, Router};
+use common::get_json;
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use stellar_insights_backend::api::sep6_proxy::{router, Sep6State};
+use stellar_insights_backend::services::settlement_latency::SettlementLatencyService;
+
+/// Echoes the query and Authorization header it was called with
+async fn echo(headers: HeaderMap, Query(query): Query<HashMap<String, String>>) -> Json<Value> {
+    Json(json!({
+        "query": query,
+        "authorization": headers
+            .get("authorization")
+            .and_then(|v| v.to_str().ok()),
+    }))
+}
+
+/// A SEP-6 transfer server on a local port, returning its base URL
+async fn spawn_anchor() -> String {
+    let app = Router::new()
+        .route(
+            "/sep6/info",
+            get(|| async { Json(json!({ "deposit": {}, "withdraw": {} })) }),
+        )
+        .route("/sep6/deposit", get(echo))
+        .route("/sep6/deposit-exchange", get(echo))
+        .route(
+            "/sep6/withdraw",
+            get(|| async {
+                (
+                    StatusCode::FORBIDDEN,
+                    Json(json!({
+                        "type": "non_interactive_customer_info_needed",
+                        "fields": ["first_name", "bank_account_number"],
+                    })),
+                )
+            }),
+        )
+        .route(
+            "/sep6/transactions",
+            get(|| async {
+                Json(json!({
+                    "transactions": [{
+                        "id": "sep6-deposit-1",
+                        "kind": "deposit",
+                        "status": "pending_external",
+                        "started_at": "2026-02-20T10:00:00Z",
+                    }]
+                }))
+            }),
+        );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}/sep6/", addr)
+}
+
+#[sqlx::test]
+async fn test_proxies_sep6_endpoints(pool: SqlitePool) {
+    let transfer_server = spawn_anchor().await;
+    let server = urlencoding::encode(&transfer_server).into_owned();
+
+    // Timings are only recorded from a listed anchor's stellar.toml endpoint
+    std::env::set_var("SEP6_ALLOWED_ORIGINS", "http://127.0.0.1");
+    sqlx::query(
+        r#"
+        INSERT INTO anchor_stellar_toml (
+            anchor_id, home_domain, transfer_server, toml_hash, toml,
+            validation_errors, changed_at, checked_at
+        )
+        VALUES ($1, 'circle.com', $2, 'hash', '{}', '[]', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
+        "#,
+    )
+    .bind("c1b1f1a1-1111-4111-a111-111111111111")
+    .bind(&transfer_server)
+    .execute(&pool)
+    .await
+    .unwrap();
+    let app = router(
+        Sep6State::new()
+            .with_settlement_tracking(Arc::new(SettlementLatencyService::new(pool.clone()))),
+    );
+
+    let (status, json) = get_json(
+        app.clone(),
+        &format!("/api/sep6/info?transfer_server={}", server),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(json["deposit"].is_object());
+
+    // Anchor parameters pass through; the JWT becomes a bearer token
+    let (status, json) = get_json(
+        app.clone(),
+        &format!(
+            "/api/sep6/deposit?transfer_server={}&asset_code=USDC&account=GACCOUNT&type=SEPA&jwt=token",
+            server
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        json["query"],
+        json!({ "asset_code": "USDC", "account": "GACCOUNT", "type": "SEPA" })
+    );
+    assert_eq!(json["authorization"], "Bearer token");
+
+    let (status, json) = get_json(
+        app.clone(),
+        &format!(
+            "/api/sep6/deposit-exchange?transfer_server={}&source_asset=iso4217%3AEUR&destination_asset=USDC&amount=10",
+            server
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["query"]["source_asset"], "iso4217:EUR");
+    assert_eq!(json["authorization"], Value::Null);
+
+    // The anchor's status and body come back unchanged
+    let (status, json) = get_json(
+        app.clone(),
+        &format!(
+            "/api/sep6/withdraw?transfer_server={}&asset_code=USDC&type=bank_account",
+            server
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(json["type"], "non_interactive_customer_info_needed");
+
+    let (status, json) = get_json(
+        app.clone(),
+        &format!(
+            "/api/sep6/transactions?transfer_server={}&jwt=token",
+            server
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["transactions"][0]["id"], "sep6-deposit-1");
+
+    let recorded: Vec<(String, String, String)> =
+        sqlx::query_as("SELECT id, protocol, transfer_server FROM anchor_transactions")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        recorded,
+        vec![(
+            "sep6-deposit-1".to_string(),
+            "sep6".to_string(),
+            transfer_server.trim_end_matches('/').to_string(),
+        )]
+    );
+
+    // Nothing listens here
+    let (status, json) = get_json(
+        app.clone(),
+        "/api/sep6/transaction?transfer_server=http%3A%2F%2F127.0.0.1%3A1&id=x",
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+    assert_eq!(json["error"], "proxy");
+}
@@ -0,0 +1,134 @@
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use stellar_insights_backend::api::{sep24_proxy, sep31_proxy};
+use stellar_insights_backend::services::settlement_latency::SettlementLatencyService;
+use tower::util::ServiceExt;
+
+const CIRCLE_ID: &str = "c1b1f1a1-1111-4111-a111-111111111111";
+
+/// An anchor on a local port listing one transaction at `path`, returning its
+/// port. Every anchor serves the same transaction, so only what the proxy
+/// decides to record tells them apart.
+async fn spawn_anchor(path: &'static str) -> u16 {
+    let app = Router::new().route(
+        path,
+        get(|| async {
+            Json(json!({
+                "transactions": [{
+                    "id": "anchor-tx-1",
+                    "kind": "deposit",
+                    "status": "pending_external",
+                    "started_at": "2026-02-20T10:00:00Z",
+                }]
+            }))
+        }),
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    port
+}
+
+async fn get_status(app: &Router, uri: &str) -> StatusCode {
+    app.clone()
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .status()
+}
+
+async fn recorded_servers(pool: &SqlitePool) -> Vec<String> {
+    sqlx::query_scalar("SELECT transfer_server FROM anchor_transactions")
+        .fetch_all(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn test_sep24_timings_are_recorded_only_for_stellar_toml_servers(pool: SqlitePool) {
+    let listed = format!(
+        "http://127.0.0.1:{}/sep24",
+        spawn_anchor("/sep24/transactions").await
+    );
+    let unlisted = format!(
+        "http://127.0.0.1:{}/sep24",
+        spawn_anchor("/sep24/transactions").await
+    );
+
+    sqlx::query(
+        r#"
+        INSERT INTO anchor_stellar_toml (
+            anchor_id, home_domain, transfer_server_sep0024, toml_hash, toml,
+            validation_errors, changed_at, checked_at
+        )
+        VALUES ($1, 'circle.com', $2, 'hash', '{}', '[]', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
+        "#,
+    )
+    .bind(CIRCLE_ID)
+    .bind(format!("{}/", listed))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let app = sep24_proxy::router(
+        sep24_proxy::Sep24State::new()
+            .with_settlement_tracking(Arc::new(SettlementLatencyService::new(pool.clone()))),
+    );
+    let uri = |server: &str| {
+        format!(
+            "/api/sep24/transactions?transfer_server={}",
+            urlencoding::encode(server)
+        )
+    };
+
+    // Without an allowlist the proxy is open to anyone, so nothing is recorded
+    std::env::remove_var("SEP24_ALLOWED_ORIGINS");
+    assert_eq!(get_status(&app, &uri(&listed)).await, StatusCode::OK);
+    assert!(recorded_servers(&pool).await.is_empty());
+
+    std::env::set_var("SEP24_ALLOWED_ORIGINS", "http://127.0.0.1");
+    assert_eq!(get_status(&app, &uri(&unlisted)).await, StatusCode::OK);
+    assert!(recorded_servers(&pool).await.is_empty());
+
+    assert_eq!(get_status(&app, &uri(&listed)).await, StatusCode::OK);
+    assert_eq!(recorded_servers(&pool).await, [listed]);
+}
+
+#[sqlx::test]
+async fn test_sep31_timings_are_recorded_only_for_anchor_domains(pool: SqlitePool) {
+    let port = spawn_anchor("/sep31/transactions").await;
+    let listed = format!("http://localhost:{}/sep31", port);
+    let unlisted = format!("http://127.0.0.1:{}/sep31", port);
+
+    sqlx::query("UPDATE anchors SET home_domain = 'localhost' WHERE id = $1")
+        .bind(CIRCLE_ID)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let app = sep31_proxy::router(
+        sep31_proxy::Sep31State::new()
+            .with_settlement_tracking(Arc::new(SettlementLatencyService::new(pool.clone()))),
+    );
+    let uri = |server: &str| {
+        format!(
+            "/api/sep31/transactions?transfer_server={}",
+            urlencoding::encode(server)
+        )
+    };
+
+    std::env::set_var("SEP31_ALLOWED_ORIGINS", "http://localhost,http://127.0.0.1");
+    assert_eq!(get_status(&app, &uri(&unlisted)).await, StatusCode::OK);
+    assert!(recorded_servers(&pool).await.is_empty());
+
+    assert_eq!(get_status(&app, &uri(&listed)).await, StatusCode::OK);
+    assert_eq!(recorded_servers(&pool).await, [listed]);
+}
@@ -0,0 +1,202 @@
+mod common;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use common::{
+    account, file_source, fixture_dir, ledger_close_meta, result_meta, transaction, usdc,
+    v1_envelope, CLOSE_TIME, PASSPHRASE,
+};
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use stellar_insights_backend::database::Database;
+use stellar_insights_backend::ingestion::ledger::LedgerIngestionService;
+use stellar_insights_backend::ingestion::ledger_meta::transaction_hash;
+use stellar_insights_backend::models::PaymentRecord;
+use stellar_insights_backend::services::account_merge_detector::AccountMergeDetector;
+use stellar_insights_backend::services::analytics::compute_metrics_from_payments;
+use stellar_insights_backend::services::fee_bump_tracker::FeeBumpTrackerService;
+use stellar_insights_backend::services::settlement_latency::{
+    anchor_transactions_from_response, LatencySource, SettlementLatencyService,
+};
+use stellar_xdr::curr::{
+    LedgerCloseMeta, OperationBody, OperationResult, OperationResultTr, PaymentOp, PaymentResult,
+    Preconditions, TimeBounds, TimePoint, TransactionEnvelope, TransactionResultResult,
+};
+use uuid::Uuid;
+
+const LEDGER_SEQ: u32 = 700;
+
+fn payment_envelope(source: u8, min_time: Option<u64>) -> TransactionEnvelope {
+    let mut tx = transaction(
+        source,
+        1,
+        vec![OperationBody::Payment(PaymentOp {
+            destination: account(2),
+            asset: usdc(),
+            amount: 10_000_000,
+        })],
+    );
+    if let Some(min_time) = min_time {
+        tx.cond = Preconditions::Time(TimeBounds {
+            min_time: TimePoint(min_time),
+            max_time: TimePoint(0),
+        });
+    }
+    v1_envelope(tx)
+}
+
+/// A ledger with a payment submitted 6 seconds before close and one
+/// without time bounds
+fn timed_payments_ledger() -> (LedgerCloseMeta, String) {
+    let timed = payment_envelope(1, Some(CLOSE_TIME - 6));
+    let untimed = payment_envelope(3, None);
+    let success = || {
+        TransactionResultResult::TxSuccess(
+            vec![OperationResult::OpInner(OperationResultTr::Payment(
+                PaymentResult::Success,
+            ))]
+            .try_into()
+            .unwrap(),
+        )
+    };
+    let tx_processing = vec![
+        result_meta(&timed, 100, success()),
+        result_meta(&untimed, 100, success()),
+    ];
+    let timed_hash = hex::encode(transaction_hash(&timed, PASSPHRASE).unwrap());
+
+    (
+        ledger_close_meta(LEDGER_SEQ, CLOSE_TIME, vec![timed, untimed], tx_processing),
+        timed_hash,
+    )
+}
+
+async fn ingest(pool: &SqlitePool, ledger: LedgerCloseMeta) {
+    let dir = fixture_dir(&[ledger], 1);
+    let ledger_source = file_source(&dir);
+    let service = LedgerIngestionService::new(
+        ledger_source,
+        Arc::new(FeeBumpTrackerService::new(pool.clone())),
+        Arc::new(AccountMergeDetector::new(pool.clone())),
+        pool.clone(),
+    );
+    assert_eq!(service.run_ingestion(10).await.unwrap(), 1);
+}
+
+async fn ledger_payment_latencies(pool: &SqlitePool) -> Vec<(Option<i64>, Option<String>)> {
+    sqlx::query_as(
+        "SELECT settlement_latency_ms, latency_source FROM ledger_payments ORDER BY operation_id",
+    )
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+#[sqlx::test]
+async fn test_ledger_payments_are_timed_from_valid_after(pool: SqlitePool) {
+    let (ledger, _) = timed_payments_ledger();
+    ingest(&pool, ledger).await;
+
+    assert_eq!(
+        ledger_payment_latencies(&pool).await,
+        vec![
+            (Some(6_000), Some("onchain".to_string())),
+            (None, Some("onchain".to_string())),
+        ]
+    );
+}
+
+#[sqlx::test]
+async fn test_anchor_transaction_retimes_settling_payment(pool: SqlitePool) {
+    let (ledger, timed_hash) = timed_payments_ledger();
+    ingest(&pool, ledger).await;
+
+    let close_time = Utc.timestamp_opt(CLOSE_TIME as i64, 0).unwrap();
+    let body = json!({
+        "transaction": {
+            "id": "withdrawal-1",
+            "kind": "withdrawal",
+            "status": "completed",
+            "started_at": (close_time - Duration::minutes(5)).to_rfc3339(),
+            "stellar_transaction_id": timed_hash,
+        }
+    });
+    let timings = anchor_transactions_from_response(
+        LatencySource::Sep24,
+        "https://anchor.example/sep24",
+        &body,
+    );
+
+    // The payment is stored in both ledger_payments and payments
+    let service = SettlementLatencyService::new(pool.clone());
+    assert_eq!(
+        service.record_anchor_transactions(&timings).await.unwrap(),
+        2
+    );
+    assert_eq!(
+        ledger_payment_latencies(&pool).await,
+        vec![
+            (Some(300_000), Some("sep24".to_string())),
+            (None, Some("onchain".to_string())),
+        ]
+    );
+
+    // Already timed from the anchor, so nothing changes on a second pass
+    assert_eq!(service.reconcile().await.unwrap(), 0);
+}
+
+fn timed_payment(confirmed: DateTime<Utc>, latency_ms: i64) -> PaymentRecord {
+    PaymentRecord {
+        id: Uuid::new_v4().to_string(),
+        transaction_hash: Uuid::new_v4().to_string(),
+        source_account: "GSOURCE".to_string(),
+        destination_account: "GDEST".to_string(),
+        asset_type: "credit_alphanum4".to_string(),
+        asset_code: Some("USDC".to_string()),
+        asset_issuer: Some("GISSUER".to_string()),
+        source_asset_code: "USDC".to_string(),
+        source_asset_issuer: "GISSUER".to_string(),
+        destination_asset_code: "USDC".to_string(),
+        destination_asset_issuer: "GISSUER".to_string(),
+        amount: 25.0,
+        operation_type: Some("payment".to_string()),
+        source_amount: None,
+        send_max: None,
+        dest_min: None,
+        path: None,
+        successful: true,
+        result_code: Some("op_success".to_string()),
+        timestamp: Some(confirmed),
+        submission_time: Some(confirmed - Duration::milliseconds(latency_ms)),
+        confirmation_time: Some(confirmed),
+        created_at: confirmed,
+    }
+}
+
+#[sqlx::test]
+async fn test_corridor_metrics_carry_latency_percentiles(pool: SqlitePool) {
+    let db = Database::new(pool.clone());
+    let now = Utc::now();
+    db.save_payments((1..=10).map(|s| timed_payment(now, s * 1_000)).collect())
+        .await
+        .unwrap();
+
+    let stored: Vec<(Option<i64>, String)> = sqlx::query_as(
+        "SELECT settlement_latency_ms, latency_source FROM payments ORDER BY settlement_latency_ms",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(stored[0], (Some(1_000), "onchain".to_string()));
+
+    let payments = db
+        .fetch_payments_by_timerange(now - Duration::minutes(1), now + Duration::minutes(1), 100)
+        .await
+        .unwrap();
+    let metrics = compute_metrics_from_payments(&payments);
+    assert_eq!(metrics.len(), 1);
+    assert_eq!(metrics[0].avg_settlement_latency_ms, Some(5_500));
+    assert_eq!(metrics[0].median_settlement_latency_ms, Some(5_500));
+    assert_eq!(metrics[0].p90_settlement_latency_ms, Some(9_000));
+    assert_eq!(metrics[0].p99_settlement_latency_ms, Some(10_000));
+}
@@ -0,0 +1,176 @@
+mod common;
+
+use axum::http::StatusCode;
+use axum::Router;
+use chrono::{TimeZone, Utc};
+use common::get_json;
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use stellar_insights_backend::database::Database;
+use stellar_insights_backend::services::snapshot::SnapshotService;
+use stellar_insights_backend::snapshot::{
+    AnalyticsSnapshot, SnapshotAnchorMetrics, SnapshotCorridorMetrics,
+};
+use stellar_insights_backend::snapshot_handlers::{routes, SnapshotAppState};
+use uuid::Uuid;
+
+fn anchor(id: u128, name: &str, reliability_score: f64, status: &str) -> SnapshotAnchorMetrics {
+    SnapshotAnchorMetrics {
+        id: Uuid::from_u128(id),
+        name: name.to_string(),
+        stellar_account: format!("G{}", name.to_uppercase()),
+        success_rate: 0.99,
+        failure_rate: 0.01,
+        reliability_score,
+        total_transactions: 1000,
+        successful_transactions: 990,
+        failed_transactions: 10,
+        avg_settlement_time_ms: Some(2000),
+        volume_usd: Some(50000.0),
+        status: status.to_string(),
+    }
+}
+
+fn corridor(id: u128, success_rate: f64) -> SnapshotCorridorMetrics {
+    SnapshotCorridorMetrics {
+        id: Uuid::from_u128(id),
+        corridor_key: "USDC:ISSUER1->EURC:ISSUER2".to_string(),
+        asset_a_code: "USDC".to_string(),
+        asset_a_issuer: "ISSUER1".to_string(),
+        asset_b_code: "EURC".to_string(),
+        asset_b_issuer: "ISSUER2".to_string(),
+        total_transactions: 500,
+        successful_transactions: 475,
+        failed_transactions: 25,
+        success_rate,
+        volume_usd: 50000.0,
+        avg_settlement_latency_ms: Some(250),
+        liquidity_depth_usd: 100000.0,
+    }
+}
+
+/// Store a snapshot for each epoch the way snapshot generation does
+async fn setup(pool: &SqlitePool) -> Router {
+    let mut yesterday =
+        AnalyticsSnapshot::new(1, Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap());
+    yesterday.add_anchor_metrics(anchor(1, "Circle", 95.0, "green"));
+    yesterday.add_anchor_metrics(anchor(2, "MoneyGram", 90.0, "green"));
+    yesterday.add_corridor_metrics(corridor(10, 95.0));
+
+    let mut today = AnalyticsSnapshot::new(2, Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap());
+    today.add_anchor_metrics(anchor(1, "Circle", 95.0, "green"));
+    today.add_anchor_metrics(anchor(3, "AnchorUSD", 85.0, "green"));
+    today.add_corridor_metrics(corridor(11, 76.0));
+
+    for snapshot in [yesterday, today] {
+        sqlx::query(
+            r#"
+            INSERT INTO snapshots (id, entity_id, entity_type, data, hash, epoch, timestamp)
+            VALUES (?, 'system', 'analytics_snapshot', ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(SnapshotService::serialize_deterministically(snapshot.clone()).unwrap())
+        .bind(SnapshotService::hash_snapshot_hex(snapshot.clone()).unwrap())
+        .bind(snapshot.epoch as i64)
+        .bind(snapshot.timestamp)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    let db = Arc::new(Database::new(pool.clone()));
+    routes(SnapshotAppState {
+        snapshot_service: Arc::new(SnapshotService::new(Arc::clone(&db), None)),
+        db,
+        contract_service: None,
+    })
+}
+
+#[sqlx::test]
+async fn test_diff_between_epochs(pool: SqlitePool) {
+    let app = setup(&pool).await;
+
+    let (status, diff) = get_json(app.clone(), "/api/snapshots/diff?from=1&to=2").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(diff["from_epoch"], 1);
+    assert_eq!(diff["to_epoch"], 2);
+    assert_eq!(diff["anchors"]["added"][0]["name"], "AnchorUSD");
+    assert_eq!(diff["anchors"]["removed"][0]["name"], "MoneyGram");
+    assert_eq!(diff["anchors"]["changed"], json!([]));
+    assert_eq!(diff["corridors"]["added"], json!([]));
+    assert_eq!(
+        diff["corridors"]["changed"],
+        json!([{
+            "corridor_key": "USDC:ISSUER1->EURC:ISSUER2",
+            "success_rate": { "from": 95.0, "to": 76.0, "delta": -19.0 }
+        }])
+    );
+    assert_eq!(
+        diff["top_movers"],
+        json!([{
+            "kind": "corridor",
+            "key": "USDC:ISSUER1->EURC:ISSUER2",
+            "label": "USDC:ISSUER1->EURC:ISSUER2",
+            "field": "success_rate",
+            "from": 95.0,
+            "to": 76.0,
+            "delta": -19.0,
+            "percent_change": -20.0
+        }])
+    );
+
+    let (_, diff) = get_json(app.clone(), "/api/snapshots/diff?from=1&to=2&top=0").await;
+    assert_eq!(diff["top_movers"], json!([]));
+
+    // Reversed, the same records are added and removed the other way round
+    let (_, diff) = get_json(app.clone(), "/api/snapshots/diff?from=2&to=1").await;
+    assert_eq!(diff["anchors"]["added"][0]["name"], "MoneyGram");
+}
+
+#[sqlx::test]
+async fn test_diff_as_json_patch(pool: SqlitePool) {
+    let app = setup(&pool).await;
+
+    let (status, patch) =
+        get_json(app.clone(), "/api/snapshots/diff?from=1&to=2&format=patch").await;
+    assert_eq!(status, StatusCode::OK);
+    let ops = patch.as_array().unwrap();
+    assert_eq!(ops.len(), 4);
+    assert_eq!(
+        ops[0],
+        json!({ "op": "remove", "path": format!("/anchors/{}", Uuid::from_u128(2)) })
+    );
+    assert_eq!(ops[1]["op"], "add");
+    assert_eq!(ops[1]["value"]["name"], "AnchorUSD");
+    assert_eq!(
+        ops[3],
+        json!({
+            "op": "replace",
+            "path": "/corridors/USDC:ISSUER1->EURC:ISSUER2/success_rate",
+            "value": 76.0
+        })
+    );
+
+    let (_, patch) = get_json(app.clone(), "/api/snapshots/diff?from=1&to=1&format=patch").await;
+    assert_eq!(patch, json!([]));
+}
+
+#[sqlx::test]
+async fn test_diff_request_errors(pool: SqlitePool) {
+    let app = setup(&pool).await;
+
+    let (status, body) = get_json(app.clone(), "/api/snapshots/diff?from=1&to=3").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"], "No snapshot for epoch 3");
+
+    let (status, _) = get_json(app.clone(), "/api/snapshots/diff?from=1").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = get_json(app.clone(), "/api/snapshots/diff?from=1&to=2&format=yaml").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Epoch routes still match alongside the diff route
+    let (status, _) = get_json(app.clone(), "/api/snapshots/1").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
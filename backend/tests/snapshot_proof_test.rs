@@ -0,0 +1,146 @@
+mod common;
+
+use axum::http::StatusCode;
+use axum::Router;
+use common::get_json;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use stellar_insights_backend::database::Database;
+use stellar_insights_backend::services::contract::SubmissionResult;
+use stellar_insights_backend::services::snapshot::{
+    SnapshotInclusionProof, SnapshotLeafKind, SnapshotService,
+};
+use stellar_insights_backend::snapshot_handlers::{routes, SnapshotAppState};
+use uuid::Uuid;
+
+/// Seeded anchor
+const CIRCLE_ID: &str = "c1b1f1a1-1111-4111-a111-111111111111";
+const CORRIDOR_ID: &str = "0c0c0c0c-0000-4000-8000-000000000001";
+
+async fn setup(pool: &SqlitePool) -> (Arc<SnapshotService>, Router) {
+    sqlx::query(
+        r#"
+        INSERT INTO corridor_metrics (
+            id, corridor_key, asset_a_code, asset_a_issuer, asset_b_code, asset_b_issuer,
+            date, total_transactions, successful_transactions, failed_transactions,
+            success_rate, volume_usd, avg_settlement_latency_ms, liquidity_depth_usd
+        ) VALUES (?, 'USDC:ISSUER1->EURC:ISSUER2', 'USDC', 'ISSUER1', 'EURC', 'ISSUER2',
+            datetime('now'), 500, 475, 25, 95.0, 50000.0, 250, 100000.0)
+        "#,
+    )
+    .bind(CORRIDOR_ID)
+    .execute(pool)
+    .await
+    .unwrap();
+
+    let db = Arc::new(Database::new(pool.clone()));
+    let service = Arc::new(SnapshotService::new(Arc::clone(&db), None));
+    let app = routes(SnapshotAppState {
+        db,
+        contract_service: None,
+        snapshot_service: Arc::clone(&service),
+    });
+    (service, app)
+}
+
+#[sqlx::test]
+async fn test_records_prove_against_the_committed_root(pool: SqlitePool) {
+    let (service, app) = setup(&pool).await;
+    let result = service.generate_and_submit_snapshot(42).await.unwrap();
+    assert_ne!(result.merkle_root, result.hash);
+
+    let (status, json) = get_json(
+        app.clone(),
+        &format!("/api/snapshots/42/proof?anchor_id={}", CIRCLE_ID),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let proof: SnapshotInclusionProof = serde_json::from_value(json).unwrap();
+    assert_eq!(proof.merkle_root, result.merkle_root);
+    assert_eq!(proof.leaf.kind, SnapshotLeafKind::Anchor);
+    assert_eq!(
+        proof.leaf_count,
+        result.anchor_count + result.corridor_count
+    );
+    assert!(proof.transaction.is_none());
+    assert!(proof.verify().unwrap());
+
+    // The leaf carries the record itself
+    let leaf: Value = serde_json::from_str(&proof.leaf.data).unwrap();
+    assert_eq!(leaf["kind"], "anchor");
+    assert_eq!(leaf["record"]["id"], CIRCLE_ID);
+    assert_eq!(leaf["record"]["total_transactions"], 10000);
+
+    // Once submitted, the proof points at the transaction
+    service
+        .record_submission(
+            &result.snapshot_id,
+            &SubmissionResult {
+                transaction_hash: "abc123".to_string(),
+                epoch: 42,
+                ledger: 51000,
+                timestamp: 1_760_000_000,
+            },
+        )
+        .await
+        .unwrap();
+
+    let corridor_id = Uuid::parse_str(CORRIDOR_ID).unwrap();
+    let proof = service
+        .inclusion_proof(42, SnapshotLeafKind::Corridor, corridor_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(proof.leaf.index, result.anchor_count);
+    assert!(proof.verify().unwrap());
+    let transaction = proof.transaction.clone().unwrap();
+    assert_eq!(transaction.transaction_hash, "abc123");
+    assert_eq!(transaction.ledger, Some(51000));
+
+    // A record edited after the fact no longer proves
+    let mut tampered = proof.clone();
+    tampered.leaf.data = tampered.leaf.data.replace("50000", "90000");
+    assert!(!tampered.verify().unwrap());
+
+    // The corridor isn't an anchor
+    assert!(service
+        .inclusion_proof(42, SnapshotLeafKind::Anchor, corridor_id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[sqlx::test]
+async fn test_proof_request_errors(pool: SqlitePool) {
+    let (service, app) = setup(&pool).await;
+    service.generate_and_submit_snapshot(1).await.unwrap();
+
+    let (status, _) = get_json(app.clone(), "/api/snapshots/1/proof").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = get_json(
+        app.clone(),
+        &format!(
+            "/api/snapshots/1/proof?anchor_id={}&corridor_id={}",
+            CIRCLE_ID, CORRIDOR_ID
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, json) = get_json(
+        app.clone(),
+        &format!("/api/snapshots/2/proof?anchor_id={}", CIRCLE_ID),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(json["error"].as_str().unwrap().contains("epoch 2"));
+
+    let (status, _) = get_json(
+        app.clone(),
+        &format!("/api/snapshots/1/proof?anchor_id={}", Uuid::nil()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
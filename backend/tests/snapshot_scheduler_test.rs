@@ -0,0 +1,330 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use stellar_insights_backend::database::Database;
+use stellar_insights_backend::services::contract::{OnChainSnapshot, SubmissionResult};
+use stellar_insights_backend::services::snapshot::SnapshotService;
+use stellar_insights_backend::services::snapshot_scheduler::{
+    EpochState, QueueSummary, SnapshotContractClient, SnapshotScheduler, SnapshotSchedulerConfig,
+    SubmissionStatus,
+};
+
+/// Snapshot hashes by epoch, held the way the snapshot contract holds them
+#[derive(Default)]
+struct FakeContract {
+    epochs: Mutex<HashMap<u64, String>>,
+    /// Submissions to fail before accepting one
+    failures: Mutex<usize>,
+    /// Epochs whose hash can't be read
+    unreadable: Mutex<HashSet<u64>>,
+}
+
+impl FakeContract {
+    fn fail_next(&self, submissions: usize) {
+        *self.failures.lock().unwrap() = submissions;
+    }
+
+    fn commit(&self, epoch: u64, hash: &str) {
+        self.epochs.lock().unwrap().insert(epoch, hash.to_string());
+    }
+
+    fn set_unreadable(&self, epochs: &[u64]) {
+        *self.unreadable.lock().unwrap() = epochs.iter().copied().collect();
+    }
+}
+
+#[async_trait::async_trait]
+impl SnapshotContractClient for FakeContract {
+    async fn submit_snapshot(&self, hash: [u8; 32], epoch: u64) -> Result<SubmissionResult> {
+        let mut failures = self.failures.lock().unwrap();
+        if *failures > 0 {
+            *failures -= 1;
+            return Err(anyhow!("Transaction simulation failed: RPC unavailable"));
+        }
+        let mut epochs = self.epochs.lock().unwrap();
+        if epochs.contains_key(&epoch) {
+            return Err(anyhow!("Snapshot for epoch {} already exists", epoch));
+        }
+        epochs.insert(epoch, hex::encode(hash));
+        Ok(SubmissionResult {
+            transaction_hash: format!("tx-{}", epoch),
+            epoch,
+            ledger: 1000 + epoch,
+            timestamp: 0,
+        })
+    }
+
+    async fn get_snapshot_at_epoch(&self, epoch: u64) -> Result<Option<OnChainSnapshot>> {
+        if self.unreadable.lock().unwrap().contains(&epoch) {
+            return Err(anyhow!("Get snapshot failed: RPC unavailable"));
+        }
+        Ok(self
+            .epochs
+            .lock()
+            .unwrap()
+            .get(&epoch)
+            .map(|hash| OnChainSnapshot {
+                hash: hash.clone(),
+                ledger: Some(2000),
+            }))
+    }
+
+    async fn get_all_epochs(&self) -> Result<Vec<u64>> {
+        let mut epochs: Vec<u64> = self.epochs.lock().unwrap().keys().copied().collect();
+        epochs.sort_unstable();
+        Ok(epochs)
+    }
+}
+
+fn genesis() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap()
+}
+
+/// Hourly epochs from `genesis`
+fn scheduler(
+    pool: &SqlitePool,
+    contract: &Arc<FakeContract>,
+) -> (Arc<SnapshotService>, SnapshotScheduler) {
+    let db = Arc::new(Database::new(pool.clone()));
+    let snapshots = Arc::new(SnapshotService::new(Arc::clone(&db), None));
+    let scheduler = SnapshotScheduler::new(
+        db,
+        Arc::clone(&snapshots),
+        Some(Arc::clone(contract) as Arc<dyn SnapshotContractClient>),
+        SnapshotSchedulerConfig {
+            epoch_seconds: 3600,
+            genesis: genesis(),
+            max_attempts: 3,
+            ..Default::default()
+        },
+    );
+    (snapshots, scheduler)
+}
+
+#[sqlx::test]
+async fn test_scheduled_epochs_are_submitted_with_retries(pool: SqlitePool) {
+    let contract = Arc::new(FakeContract::default());
+    let (snapshots, scheduler) = scheduler(&pool, &contract);
+
+    // Nothing has ended before the first hour is up
+    let now = genesis() + Duration::minutes(30);
+    assert!(scheduler.schedule(now).await.unwrap().is_empty());
+
+    // The first run queues only the latest ended epoch
+    let now = genesis() + Duration::minutes(150);
+    assert_eq!(scheduler.schedule(now).await.unwrap(), [2]);
+    assert!(scheduler.schedule(now).await.unwrap().is_empty());
+    let queued = scheduler.submission(2).await.unwrap().unwrap();
+    assert_eq!(queued.status, SubmissionStatus::Pending);
+
+    contract.fail_next(2);
+    let summary = scheduler.process_queue(now).await.unwrap();
+    assert_eq!(summary.retried, 1);
+    let queued = scheduler.submission(2).await.unwrap().unwrap();
+    assert_eq!(queued.status, SubmissionStatus::Pending);
+    assert_eq!(queued.attempts, 1);
+    assert_eq!(queued.next_attempt_at, now + Duration::seconds(60));
+    assert!(queued.last_error.unwrap().contains("RPC unavailable"));
+
+    // Not retried before its backoff is up
+    assert_eq!(
+        scheduler.process_queue(now).await.unwrap(),
+        QueueSummary::default()
+    );
+    let now = now + Duration::seconds(60);
+    assert_eq!(scheduler.process_queue(now).await.unwrap().retried, 1);
+
+    // The queue outlives the scheduler, as it would a restart
+    let (_, scheduler) = self::scheduler(&pool, &contract);
+    let now = now + Duration::seconds(120);
+    assert_eq!(scheduler.process_queue(now).await.unwrap().submitted, 1);
+    let submitted = scheduler.submission(2).await.unwrap().unwrap();
+    assert_eq!(submitted.status, SubmissionStatus::Submitted);
+    assert_eq!(submitted.transaction_hash.as_deref(), Some("tx-2"));
+    assert_eq!(
+        contract.epochs.lock().unwrap().get(&2),
+        Some(&queued.merkle_root)
+    );
+
+    assert_eq!(scheduler.process_queue(now).await.unwrap().confirmed, 1);
+    let confirmed = scheduler.submission(2).await.unwrap().unwrap();
+    assert_eq!(confirmed.status, SubmissionStatus::Confirmed);
+    assert_eq!(confirmed.ledger, Some(1002));
+    assert_eq!(
+        scheduler.process_queue(now).await.unwrap(),
+        QueueSummary::default()
+    );
+
+    let committed = snapshots.get_committed_snapshot(2).await.unwrap().unwrap();
+    assert_eq!(committed.transaction.unwrap().transaction_hash, "tx-2");
+}
+
+#[sqlx::test]
+async fn test_failed_submissions_are_repaired_by_reconciliation(pool: SqlitePool) {
+    let contract = Arc::new(FakeContract::default());
+    let (_, scheduler) = scheduler(&pool, &contract);
+
+    let mut now = genesis() + Duration::hours(1);
+    scheduler.schedule(now).await.unwrap();
+    contract.fail_next(3);
+    for _ in 0..3 {
+        now += Duration::hours(1);
+        scheduler.process_queue(now).await.unwrap();
+    }
+    let failed = scheduler.submission(1).await.unwrap().unwrap();
+    assert_eq!(failed.status, SubmissionStatus::Failed);
+    assert_eq!(failed.attempts, 3);
+
+    let report = scheduler.reconcile(now, true).await.unwrap();
+    assert_eq!(report.committed, 0);
+    let gap = report.gaps.iter().find(|g| g.epoch == 1).unwrap();
+    assert_eq!(gap.state, EpochState::MissingOnChain);
+    assert!(gap.requeued);
+    let requeued = scheduler.submission(1).await.unwrap().unwrap();
+    assert_eq!(requeued.status, SubmissionStatus::Pending);
+    assert_eq!(requeued.attempts, 0);
+
+    // Committed elsewhere before the retry: confirmed without resubmitting
+    contract.commit(1, &requeued.merkle_root);
+    let summary = scheduler.process_queue(now).await.unwrap();
+    assert_eq!(summary.confirmed, 1);
+    assert_eq!(summary.submitted, 0);
+
+    // An epoch committed with another hash can't be submitted
+    now = genesis() + Duration::hours(9);
+    scheduler.schedule(now).await.unwrap();
+    contract.commit(9, &"ab".repeat(32));
+    assert_eq!(scheduler.process_queue(now).await.unwrap().failed, 1);
+    let failed = scheduler.submission(9).await.unwrap().unwrap();
+    assert_eq!(failed.status, SubmissionStatus::Failed);
+    assert!(failed.last_error.unwrap().contains("already committed"));
+}
+
+#[sqlx::test]
+async fn test_missed_epochs_are_left_for_reconciliation(pool: SqlitePool) {
+    let contract = Arc::new(FakeContract::default());
+    let (_, scheduler) = scheduler(&pool, &contract);
+
+    let now = genesis() + Duration::hours(1);
+    assert_eq!(scheduler.schedule(now).await.unwrap(), [1]);
+
+    // Down for three epochs: only the latest is snapshotted, as the metrics
+    // of the others' periods are gone
+    let now = genesis() + Duration::minutes(270);
+    assert_eq!(scheduler.schedule(now).await.unwrap(), [4]);
+    assert!(scheduler.schedule(now).await.unwrap().is_empty());
+    assert!(scheduler.submission(2).await.unwrap().is_none());
+    assert!(scheduler.submission(3).await.unwrap().is_none());
+
+    // A submission that can't be advanced doesn't hold up the rest
+    sqlx::query("UPDATE snapshot_submissions SET merkle_root = 'not-hex' WHERE epoch = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(scheduler.process_queue(now).await.unwrap().submitted, 1);
+    let stuck = scheduler.submission(1).await.unwrap().unwrap();
+    assert_eq!(stuck.status, SubmissionStatus::Pending);
+    let committed: Vec<u64> = contract.epochs.lock().unwrap().keys().copied().collect();
+    assert_eq!(committed, [4]);
+
+    // The missed epochs are reported as gaps alongside the stuck one
+    let report = scheduler.reconcile(now, false).await.unwrap();
+    let gaps: Vec<_> = report.gaps.iter().map(|g| (g.epoch, g.state)).collect();
+    assert_eq!(
+        gaps,
+        [
+            (1, EpochState::MissingOnChain),
+            (2, EpochState::Missing),
+            (3, EpochState::Missing),
+        ]
+    );
+    assert_eq!(report.committed, 1);
+}
+
+#[sqlx::test]
+async fn test_reconciliation_reports_gaps(pool: SqlitePool) {
+    let contract = Arc::new(FakeContract::default());
+    let (_, scheduler) = scheduler(&pool, &contract);
+
+    // Epoch 2 submitted and epoch 3 queued, then the scheduler was down
+    // until epoch 6
+    let now = genesis() + Duration::hours(2);
+    scheduler.schedule(now).await.unwrap();
+    scheduler.process_queue(now).await.unwrap();
+    assert_eq!(
+        scheduler
+            .schedule(genesis() + Duration::hours(3))
+            .await
+            .unwrap(),
+        [3]
+    );
+    let now = genesis() + Duration::hours(6);
+    assert_eq!(scheduler.schedule(now).await.unwrap(), [6]);
+
+    // Committed by someone else before anything was scheduled; stored but
+    // dropped from the queue; committed with a hash that isn't stored; and
+    // listed by the contract but unreadable
+    contract.commit(1, &"cd".repeat(32));
+    sqlx::query("DELETE FROM snapshot_submissions WHERE epoch = 3")
+        .execute(&pool)
+        .await
+        .unwrap();
+    contract.commit(6, &"ef".repeat(32));
+    contract.set_unreadable(&[2]);
+
+    let report = scheduler.reconcile(now, false).await.unwrap();
+    assert_eq!(report.checked, 6);
+    assert_eq!(report.committed, 0);
+    let gaps: Vec<_> = report
+        .gaps
+        .iter()
+        .map(|g| (g.epoch, g.state, g.requeued))
+        .collect();
+    assert_eq!(
+        gaps,
+        [
+            (1, EpochState::MissingLocally, false),
+            (2, EpochState::Unreadable, false),
+            (3, EpochState::MissingOnChain, false),
+            (4, EpochState::Missing, false),
+            (5, EpochState::Missing, false),
+            (6, EpochState::Mismatch, false),
+        ]
+    );
+    assert_eq!(report.gaps[0].on_chain_hash, Some("cd".repeat(32)));
+    assert_eq!(report.gaps[0].local_hash, None);
+    assert!(report.gaps[1]
+        .error
+        .as_ref()
+        .unwrap()
+        .contains("RPC unavailable"));
+
+    // Only the epoch that isn't already queued is queued for repair
+    contract.set_unreadable(&[]);
+    let report = scheduler.reconcile(now, true).await.unwrap();
+    assert_eq!(report.committed, 1);
+    let requeued: Vec<_> = report
+        .gaps
+        .iter()
+        .filter(|g| g.requeued)
+        .map(|g| g.epoch)
+        .collect();
+    assert_eq!(requeued, [3]);
+
+    let summary = scheduler.process_queue(now).await.unwrap();
+    assert_eq!((summary.submitted, summary.failed), (1, 1));
+    scheduler.process_queue(now).await.unwrap();
+    let report = scheduler.reconcile(now, true).await.unwrap();
+    assert_eq!(report.committed, 2);
+    assert_eq!(
+        report.gaps.iter().map(|g| g.state).collect::<Vec<_>>(),
+        [
+            EpochState::MissingLocally,
+            EpochState::Missing,
+            EpochState::Missing,
+            EpochState::Mismatch
+        ]
+    );
+}
@@ -0,0 +1,217 @@
+//! Golden hashes for every snapshot schema version
+//!
+//! Each supported version must have an entry here. The values are what
+//! snapshots of that version were committed with; if one of these tests fails
+//! the encoder for an existing version changed, and stored snapshots of that
+//! version no longer re-hash to their on-chain commitments.
+
+use chrono::{TimeZone, Utc};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use stellar_insights_backend::database::Database;
+use stellar_insights_backend::services::snapshot::SnapshotService;
+use stellar_insights_backend::snapshot::encoding::{supported_schema_versions, to_canonical_json};
+use stellar_insights_backend::snapshot::{
+    AnalyticsSnapshot, SnapshotAnchorMetrics, SnapshotCorridorMetrics, SnapshotGenerator,
+    SCHEMA_VERSION,
+};
+use uuid::Uuid;
+
+struct Golden {
+    schema_version: u32,
+    /// SHA-256 of the canonical snapshot JSON
+    hash: &'static str,
+    merkle_root: &'static str,
+}
+
+const GOLDEN: &[Golden] = &[Golden {
+    schema_version: 1,
+    hash: "390492a3ddf1595296f1e8ef6809402a5f9f1769fc51b9630267caf6b0ef90da",
+    merkle_root: "20fdc504cec04cfcaee70c35ee17c71ab843b8d4854d8ec4377ff1da5ccc53ba",
+}];
+
+/// The same records for every version, with optional fields both set and
+/// unset
+fn fixture(schema_version: u32) -> AnalyticsSnapshot {
+    let mut snapshot =
+        AnalyticsSnapshot::new(1024, Utc.with_ymd_and_hms(2026, 1, 15, 12, 0, 0).unwrap());
+    snapshot.schema_version = schema_version;
+
+    snapshot.add_anchor_metrics(SnapshotAnchorMetrics {
+        id: Uuid::from_u128(0x2b),
+        name: "MoneyGram".to_string(),
+        stellar_account: "GA7FCCMTTSUIC37PODEL6EOOSPDRILP6OQI5FWCWDDVDBLJV72W6RINZ".to_string(),
+        success_rate: 0.9875,
+        failure_rate: 0.0125,
+        reliability_score: 91.25,
+        total_transactions: 8000,
+        successful_transactions: 7900,
+        failed_transactions: 100,
+        avg_settlement_time_ms: None,
+        volume_usd: None,
+        status: "yellow".to_string(),
+    });
+    snapshot.add_anchor_metrics(SnapshotAnchorMetrics {
+        id: Uuid::from_u128(0x1a),
+        name: "Circle".to_string(),
+        stellar_account: "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN".to_string(),
+        success_rate: 0.99,
+        failure_rate: 0.01,
+        reliability_score: 98.5,
+        total_transactions: 10000,
+        successful_transactions: 9900,
+        failed_transactions: 100,
+        avg_settlement_time_ms: Some(2300),
+        volume_usd: Some(1_250_000.5),
+        status: "green".to_string(),
+    });
+    snapshot.add_corridor_metrics(SnapshotCorridorMetrics {
+        id: Uuid::from_u128(0x3c),
+        corridor_key: "USDC:GA5Z->EURC:GDHU".to_string(),
+        asset_a_code: "USDC".to_string(),
+        asset_a_issuer: "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN".to_string(),
+        asset_b_code: "EURC".to_string(),
+        asset_b_issuer: "GDHU6WRG4IEQXM5NZ4BMPKOXHW76MZM4Y2IEMFDVXBSDP6SJY4ITNPP2".to_string(),
+        total_transactions: 500,
+        successful_transactions: 475,
+        failed_transactions: 25,
+        success_rate: 95.0,
+        volume_usd: 50000.0,
+        avg_settlement_latency_ms: None,
+        liquidity_depth_usd: 100000.0,
+    });
+    snapshot
+}
+
+#[test]
+fn test_every_schema_version_is_pinned() {
+    assert_eq!(
+        supported_schema_versions().collect::<Vec<_>>(),
+        GOLDEN.iter().map(|g| g.schema_version).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        supported_schema_versions().last(),
+        Some(SCHEMA_VERSION),
+        "new snapshots must be encodable"
+    );
+}
+
+#[test]
+fn test_golden_hashes() {
+    for golden in GOLDEN {
+        let snapshot = fixture(golden.schema_version);
+        assert_eq!(
+            SnapshotService::hash_snapshot_hex(snapshot.clone()).unwrap(),
+            golden.hash,
+            "schema version {} hash",
+            golden.schema_version
+        );
+        assert_eq!(
+            SnapshotGenerator::generate_hash_hex(snapshot.clone()).unwrap(),
+            golden.hash,
+            "schema version {} generator hash",
+            golden.schema_version
+        );
+        assert_eq!(
+            hex::encode(SnapshotService::merkle_root(snapshot).unwrap()),
+            golden.merkle_root,
+            "schema version {} Merkle root",
+            golden.schema_version
+        );
+    }
+}
+
+/// Canonical JSON and hash that `SnapshotService::serialize_deterministically`
+/// produced before the versioned encoders existed, for a snapshot with a
+/// fractional-second timestamp and non-finite values. Every stored version 1
+/// row was written by it, so the v1 encoder must keep producing this output.
+const BASELINE_ODD_JSON: &str = r#"{"anchor_metrics":[{"avg_settlement_time_ms":2300,"failed_transactions":100,"failure_rate":0.01,"id":"00000000-0000-0000-0000-00000000001a","name":"Circle","reliability_score":98.5,"status":"green","stellar_account":"GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN","success_rate":0.99,"successful_transactions":9900,"total_transactions":10000,"volume_usd":"Infinity"},{"avg_settlement_time_ms":null,"failed_transactions":100,"failure_rate":0.0125,"id":"00000000-0000-0000-0000-00000000002b","name":"MoneyGram","reliability_score":"NaN","status":"yellow","stellar_account":"GA7FCCMTTSUIC37PODEL6EOOSPDRILP6OQI5FWCWDDVDBLJV72W6RINZ","success_rate":0.9875,"successful_transactions":7900,"total_transactions":8000,"volume_usd":null}],"corridor_metrics":[{"asset_a_code":"USDC","asset_a_issuer":"GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN","asset_b_code":"EURC","asset_b_issuer":"GDHU6WRG4IEQXM5NZ4BMPKOXHW76MZM4Y2IEMFDVXBSDP6SJY4ITNPP2","avg_settlement_latency_ms":null,"corridor_key":"USDC:GA5Z->EURC:GDHU","failed_transactions":25,"id":"00000000-0000-0000-0000-00000000003c","liquidity_depth_usd":"-Infinity","success_rate":95.0,"successful_transactions":475,"total_transactions":500,"volume_usd":50000.0}],"epoch":1024,"schema_version":1,"timestamp":"2026-01-15T12:00:00.250+00:00"}"#;
+const BASELINE_ODD_HASH: &str = "86e657d2f6c343d31ccb425b9c6691fea141c55131d5d80e49210488e1b8af24";
+
+#[test]
+fn test_v1_matches_baseline_service_encoding() {
+    let mut odd = fixture(1);
+    odd.timestamp = Utc
+        .with_ymd_and_hms(2026, 1, 15, 12, 0, 0)
+        .unwrap()
+        .checked_add_signed(chrono::Duration::milliseconds(250))
+        .unwrap();
+    odd.anchor_metrics[0].reliability_score = f64::NAN;
+    odd.anchor_metrics[1].volume_usd = Some(f64::INFINITY);
+    odd.corridor_metrics[0].liquidity_depth_usd = f64::NEG_INFINITY;
+
+    assert_eq!(to_canonical_json(odd.clone()).unwrap(), BASELINE_ODD_JSON);
+    assert_eq!(
+        SnapshotService::hash_snapshot_hex(odd).unwrap(),
+        BASELINE_ODD_HASH
+    );
+}
+
+#[sqlx::test]
+async fn test_stored_snapshots_rehash_with_their_version(pool: SqlitePool) {
+    let service = SnapshotService::new(Arc::new(Database::new(pool.clone())), None);
+    service.generate_and_submit_snapshot(7).await.unwrap();
+
+    // A golden snapshot stored as committed, and one of a version with no
+    // encoder
+    let golden = &GOLDEN[0];
+    let mut unsupported = fixture(golden.schema_version);
+    unsupported.epoch = 9;
+    unsupported.schema_version = SCHEMA_VERSION + 1;
+    for (id, epoch, snapshot, hash, merkle_root) in [
+        (
+            "golden",
+            8,
+            fixture(golden.schema_version),
+            golden.hash,
+            Some(golden.merkle_root),
+        ),
+        ("unsupported", 9, unsupported, golden.hash, None),
+    ] {
+        sqlx::query(
+            r#"
+            INSERT INTO snapshots (id, entity_id, entity_type, data, hash, merkle_root, epoch, timestamp)
+            VALUES (?, 'system', 'analytics_snapshot', ?, ?, ?, ?, datetime('now'))
+            "#,
+        )
+        .bind(id)
+        .bind(serde_json::to_string(&snapshot).unwrap())
+        .bind(hash)
+        .bind(merkle_root)
+        .bind(epoch)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let checks = service.rehash_stored_snapshots().await.unwrap();
+    assert_eq!(
+        checks.iter().map(|c| c.epoch).collect::<Vec<_>>(),
+        [Some(7), Some(8), Some(9)]
+    );
+    assert!(checks[0].passed(), "{:?}", checks[0]);
+    assert_eq!(checks[0].merkle_root_matches, Some(true));
+    assert!(checks[1].passed(), "{:?}", checks[1]);
+    assert_eq!(checks[1].hash.as_deref(), Some(golden.hash));
+
+    assert!(!checks[2].passed());
+    assert_eq!(checks[2].schema_version, Some(SCHEMA_VERSION + 1));
+    assert!(checks[2]
+        .error
+        .as_deref()
+        .unwrap()
+        .contains("Unsupported snapshot schema version"));
+
+    // A stored record edited after the fact no longer matches its hash
+    let mut tampered = fixture(golden.schema_version);
+    tampered.anchor_metrics[0].reliability_score = 99.0;
+    sqlx::query("UPDATE snapshots SET data = ? WHERE id = 'golden'")
+        .bind(serde_json::to_string(&tampered).unwrap())
+        .execute(&pool)
+        .await
+        .unwrap();
+    let checks = service.rehash_stored_snapshots().await.unwrap();
+    assert!(!checks[1].hash_matches);
+    assert_eq!(checks[1].merkle_root_matches, Some(false));
+    assert!(checks[1].error.is_none());
+}
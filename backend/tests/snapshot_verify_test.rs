@@ -0,0 +1,209 @@
+use axum::extract::State;
+use axum::routing::post;
+use axum::{Json, Router};
+use chrono::{TimeZone, Utc};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use stellar_insights_backend::services::contract::{ContractConfig, ContractService};
+use stellar_insights_backend::services::snapshot::SnapshotService;
+use stellar_insights_backend::snapshot::verify::{verify_snapshot, Commitment, PublishedSnapshot};
+use stellar_insights_backend::snapshot::{
+    AnalyticsSnapshot, SnapshotAnchorMetrics, SnapshotGenerator,
+};
+use uuid::Uuid;
+
+const CONTRACT_ID: &str = "CBGTG4JJFEQE3SPBGQFP3X5HM46N47LXZPXQACVKB7QA6X2XB2IG5CTA";
+const LEDGER: u64 = 51234;
+
+/// Snapshot hashes by epoch, answering the contract's read-only calls the
+/// way a Soroban RPC simulation would
+type Contract = Arc<Mutex<HashMap<u64, String>>>;
+
+async fn simulate(State(contract): State<Contract>, Json(request): Json<Value>) -> Json<Value> {
+    let tx = &request["params"]["transaction"];
+    assert_eq!(tx["contractId"], CONTRACT_ID);
+    let args = tx["args"].as_array().unwrap();
+    let epoch_arg = |i: usize| args[i]["value"].as_str().unwrap().parse::<u64>().unwrap();
+    let snapshots = contract.lock().unwrap();
+
+    let result = match tx["function"].as_str().unwrap() {
+        "get_snapshot" => {
+            let epoch = epoch_arg(0);
+            match snapshots.get(&epoch) {
+                Some(hash) => json!({ "returnValue": hash, "latestLedger": LEDGER }),
+                None => {
+                    return Json(json!({
+                        "jsonrpc": "2.0",
+                        "id": request["id"],
+                        "error": {
+                            "code": -32000,
+                            "message": format!("HostError: No snapshot found for epoch {}", epoch),
+                        },
+                    }))
+                }
+            }
+        }
+        "verify_snapshot_at_epoch" => {
+            let hash = args[0]["value"].as_str().unwrap();
+            let matches = snapshots.get(&epoch_arg(1)).map(String::as_str) == Some(hash);
+            json!({ "returnValue": matches, "latestLedger": LEDGER })
+        }
+        other => panic!("unexpected contract call {}", other),
+    };
+    Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }))
+}
+
+/// A Soroban RPC stand-in on a local port, returning its URL
+async fn spawn_rpc(contract: Contract) -> String {
+    let app = Router::new()
+        .route("/", post(simulate))
+        .with_state(contract);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}/", addr)
+}
+
+fn contract_service(rpc_url: &str) -> ContractService {
+    ContractService::new(ContractConfig {
+        rpc_url: rpc_url.to_string(),
+        contract_id: CONTRACT_ID.to_string(),
+        network_passphrase: "Test SDF Network ; September 2015".to_string(),
+        source_secret_key: String::new(),
+    })
+    .unwrap()
+}
+
+fn snapshot(epoch: u64) -> AnalyticsSnapshot {
+    let mut snapshot =
+        AnalyticsSnapshot::new(epoch, Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap());
+    for (i, name) in ["Circle", "MoneyGram", "AnchorUSD"].iter().enumerate() {
+        snapshot.add_anchor_metrics(SnapshotAnchorMetrics {
+            id: Uuid::from_u128(i as u128 + 1),
+            name: name.to_string(),
+            stellar_account: format!("G{}", name.to_uppercase()),
+            success_rate: 0.99,
+            failure_rate: 0.01,
+            reliability_score: 95.0,
+            total_transactions: 1000,
+            successful_transactions: 990,
+            failed_transactions: 10,
+            avg_settlement_time_ms: Some(2000),
+            volume_usd: Some(50000.0),
+            status: "green".to_string(),
+        });
+    }
+    snapshot
+}
+
+fn root_hex(snapshot: &AnalyticsSnapshot) -> String {
+    hex::encode(SnapshotService::merkle_root(snapshot.clone()).unwrap())
+}
+
+fn published(snapshot: AnalyticsSnapshot) -> PublishedSnapshot {
+    PublishedSnapshot {
+        snapshot,
+        transaction: None,
+    }
+}
+
+#[tokio::test]
+async fn test_verifies_snapshots_against_the_contract() {
+    let contract = Contract::default();
+    let rpc_url = spawn_rpc(Arc::clone(&contract)).await;
+    let service = contract_service(&rpc_url);
+
+    let committed = snapshot(7);
+    let legacy = snapshot(6);
+    {
+        let mut snapshots = contract.lock().unwrap();
+        snapshots.insert(7, root_hex(&committed));
+        snapshots.insert(
+            6,
+            SnapshotGenerator::generate_hash_hex(legacy.clone()).unwrap(),
+        );
+    }
+
+    let report = verify_snapshot(published(committed.clone()), &service)
+        .await
+        .unwrap();
+    assert!(report.passed());
+    assert_eq!(report.commitment, Some(Commitment::MerkleRoot));
+    assert_eq!(report.read_ledger, Some(LEDGER));
+    assert_eq!(report.anchor_count, 3);
+    assert!(report
+        .to_string()
+        .ends_with("Result: PASS (Merkle root matches on-chain)"));
+
+    // Epochs committed as the hash of the whole snapshot still verify
+    let report = verify_snapshot(published(legacy), &service).await.unwrap();
+    assert!(report.passed());
+    assert_eq!(report.commitment, Some(Commitment::SnapshotHash));
+
+    // One edited record changes both hashes
+    let mut tampered = committed;
+    tampered.anchor_metrics[1].reliability_score = 99.0;
+    let report = verify_snapshot(published(tampered), &service)
+        .await
+        .unwrap();
+    assert!(!report.passed());
+    assert_eq!(report.commitment, None);
+    assert!(report.to_string().contains("FAIL"));
+
+    let report = verify_snapshot(published(snapshot(8)), &service)
+        .await
+        .unwrap();
+    assert!(!report.passed());
+    assert_eq!(report.on_chain_hash, None);
+}
+
+#[tokio::test]
+async fn test_cli_reports_and_exits_with_the_outcome() {
+    let contract = Contract::default();
+    let rpc_url = spawn_rpc(Arc::clone(&contract)).await;
+
+    let snapshot = snapshot(9);
+    contract.lock().unwrap().insert(9, root_hex(&snapshot));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("snapshot.json");
+    std::fs::write(&path, serde_json::to_string(&snapshot).unwrap()).unwrap();
+
+    let run = |contract_id: &str| {
+        tokio::process::Command::new(env!("CARGO_BIN_EXE_stellar-insights-verify"))
+            .args([
+                "--file",
+                path.to_str().unwrap(),
+                "--rpc-url",
+                &rpc_url,
+                "--contract-id",
+                contract_id,
+            ])
+            .output()
+    };
+
+    let output = run(CONTRACT_ID).await.unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(output.status.code(), Some(0), "{}", stdout);
+    assert!(stdout.contains("Snapshot verification for epoch 9"));
+    assert!(stdout.contains(&format!("Read at ledger:    {}", LEDGER)));
+    assert!(stdout.contains("2026-03-01T00:00:00+00:00"));
+    assert!(stdout.contains("Result: PASS"));
+
+    contract.lock().unwrap().insert(9, "00".repeat(32));
+    let output = run(CONTRACT_ID).await.unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("Result: FAIL (on-chain hash matches neither local hash)"));
+
+    let output = tokio::process::Command::new(env!("CARGO_BIN_EXE_stellar-insights-verify"))
+        .args(["--epoch", "9"])
+        .output()
+        .await
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+}
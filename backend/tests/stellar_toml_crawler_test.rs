@@ -0,0 +1,217 @@
+use anyhow::{anyhow, Result};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use stellar_insights_backend::database::Database;
+use stellar_insights_backend::services::stellar_toml::{
+    CrawlSummary, StellarTomlCrawler, StellarTomlCrawlerConfig, StellarTomlSource,
+};
+use uuid::Uuid;
+
+const ISSUER_A: &str = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+const ISSUER_B: &str = "GBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBB";
+const ISSUER_C: &str = "GCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCC";
+const ISSUER_D: &str = "GDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDD";
+
+/// Home domains and stellar.toml files held in memory; domains without a
+/// file fail to fetch
+#[derive(Default)]
+struct FakeSource {
+    home_domains: HashMap<&'static str, &'static str>,
+    tomls: Mutex<HashMap<String, String>>,
+}
+
+impl FakeSource {
+    fn serve(&self, domain: &str, toml: Option<String>) {
+        let mut tomls = self.tomls.lock().unwrap();
+        match toml {
+            Some(toml) => tomls.insert(domain.to_string(), toml),
+            None => tomls.remove(domain),
+        };
+    }
+}
+
+#[async_trait::async_trait]
+impl StellarTomlSource for FakeSource {
+    async fn home_domain(&self, account_id: &str) -> Result<Option<String>> {
+        Ok(self.home_domains.get(account_id).map(|d| d.to_string()))
+    }
+
+    async fn fetch_toml(&self, domain: &str) -> Result<String> {
+        self.tomls
+            .lock()
+            .unwrap()
+            .get(domain)
+            .cloned()
+            .ok_or_else(|| anyhow!("https://{}/.well-known/stellar.toml returned 404", domain))
+    }
+}
+
+/// Lists a currency from an issuer that names another home domain, and an
+/// insecure SEP-6 server
+fn anchor_toml(extra: &str) -> String {
+    format!(
+        r#"
+TRANSFER_SERVER = "http://anchor.example/sep6"
+TRANSFER_SERVER_SEP0024 = "https://anchor.example/sep24"
+WEB_AUTH_ENDPOINT = "https://anchor.example/auth"
+SIGNING_KEY = "{a}"
+{extra}
+
+[DOCUMENTATION]
+ORG_NAME = "Example Anchor"
+
+[[CURRENCIES]]
+code = "USDC"
+issuer = "{a}"
+
+[[CURRENCIES]]
+code = "BAR"
+issuer = "{d}"
+
+[[CURRENCIES]]
+code = "FOO"
+issuer = "{c}"
+"#,
+        a = ISSUER_A,
+        c = ISSUER_C,
+        d = ISSUER_D,
+        extra = extra
+    )
+}
+
+async fn trustline(pool: &SqlitePool, code: &str, issuer: &str) {
+    sqlx::query("INSERT INTO trustline_stats (asset_code, asset_issuer) VALUES ($1, $2)")
+        .bind(code)
+        .bind(issuer)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn test_crawl_discovers_anchors_and_tracks_changes(pool: SqlitePool) {
+    trustline(&pool, "USDC", ISSUER_A).await;
+    trustline(&pool, "EURT", ISSUER_B).await;
+    trustline(&pool, "XYZ", ISSUER_C).await;
+
+    let source = Arc::new(FakeSource {
+        home_domains: HashMap::from([
+            (ISSUER_A, "Anchor.Example"),
+            (ISSUER_B, "broken.example"),
+            (ISSUER_D, "anchor.example"),
+        ]),
+        ..Default::default()
+    });
+    source.serve("anchor.example", Some(anchor_toml("")));
+    // A seeded anchor, found by its home domain
+    source.serve(
+        "circle.com",
+        Some("[DOCUMENTATION]\nORG_NAME = \"Circle\"\n".to_string()),
+    );
+
+    let db = Arc::new(Database::new(pool));
+    let crawler = StellarTomlCrawler::new(
+        Arc::clone(&db),
+        source.clone(),
+        StellarTomlCrawlerConfig::default(),
+    );
+
+    // anchor.example, broken.example and the three seeded anchors' domains
+    assert_eq!(
+        crawler.crawl().await.unwrap(),
+        CrawlSummary {
+            domains: 5,
+            anchors: 2,
+            changed: 2,
+            failed: 3,
+        }
+    );
+
+    let anchor = db
+        .get_anchor_by_stellar_account(ISSUER_A)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(anchor.name, "Example Anchor");
+    assert_eq!(anchor.home_domain.as_deref(), Some("anchor.example"));
+    let anchor_id = Uuid::parse_str(&anchor.id).unwrap();
+
+    let detail = db.get_anchor_detail(anchor_id).await.unwrap().unwrap();
+    let mut assets: Vec<_> = detail
+        .assets
+        .iter()
+        .map(|a| format!("{}:{}", a.asset_code, a.asset_issuer))
+        .collect();
+    assets.sort();
+    // FOO's issuer doesn't point back at anchor.example
+    assert_eq!(
+        assets,
+        vec![format!("BAR:{}", ISSUER_D), format!("USDC:{}", ISSUER_A)]
+    );
+
+    let toml = detail.stellar_toml.unwrap();
+    assert_eq!(
+        toml.toml.transfer_server_sep0024.as_deref(),
+        Some("https://anchor.example/sep24")
+    );
+    assert_eq!(toml.toml.signing_key.as_deref(), Some(ISSUER_A));
+    assert_eq!(toml.toml.transfer_server, None);
+    assert_eq!(
+        toml.validation_errors,
+        vec!["TRANSFER_SERVER http://anchor.example/sep6 is not an https URL".to_string()]
+    );
+    assert_eq!(toml.fetch_error, None);
+    assert_eq!(detail.stellar_toml_history.len(), 1);
+
+    let circle = db
+        .get_anchor_detail(Uuid::parse_str("c1b1f1a1-1111-4111-a111-111111111111").unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(circle.stellar_toml.unwrap().home_domain, "circle.com");
+
+    // An unchanged file isn't added to the history again
+    let summary = crawler.crawl().await.unwrap();
+    assert_eq!((summary.anchors, summary.changed), (2, 0));
+    let detail = db.get_anchor_detail(anchor_id).await.unwrap().unwrap();
+    assert_eq!(detail.stellar_toml_history.len(), 1);
+
+    source.serve(
+        "anchor.example",
+        Some(anchor_toml(
+            "DIRECT_PAYMENT_SERVER = \"https://anchor.example/sep31\"",
+        )),
+    );
+    let summary = crawler.crawl().await.unwrap();
+    assert_eq!((summary.anchors, summary.changed), (2, 1));
+    let detail = db.get_anchor_detail(anchor_id).await.unwrap().unwrap();
+    assert_eq!(detail.stellar_toml_history.len(), 2);
+    assert_eq!(
+        detail.stellar_toml_history[0]
+            .toml
+            .direct_payment_server
+            .as_deref(),
+        Some("https://anchor.example/sep31")
+    );
+    assert_eq!(
+        detail.stellar_toml_history[1].toml.direct_payment_server,
+        None
+    );
+
+    // A failed fetch is flagged without losing the last good file
+    source.serve("anchor.example", None);
+    crawler.crawl().await.unwrap();
+    let toml = db
+        .get_anchor_detail(anchor_id)
+        .await
+        .unwrap()
+        .unwrap()
+        .stellar_toml
+        .unwrap();
+    assert!(toml.fetch_error.unwrap().contains("404"));
+    assert_eq!(
+        toml.toml.direct_payment_server.as_deref(),
+        Some("https://anchor.example/sep31")
+    );
+}
@@ -1,17 +1,18 @@
+mod common;
+
 use sqlx::SqlitePool;
 use std::sync::Arc;
-use stellar_insights_backend::rpc::StellarRpcClient;
 use stellar_insights_backend::services::trustline_analyzer::TrustlineAnalyzer;
 
 #[sqlx::test]
 async fn test_trustlines_sync_and_query(pool: SqlitePool) {
-    // Create a mock RPC client
-    let rpc_client = Arc::new(StellarRpcClient::new_with_defaults(true));
+    // A client for the stub Horizon
+    let rpc_client = Arc::new(common::horizon::client());
     let analyzer = TrustlineAnalyzer::new(pool.clone(), rpc_client);
 
-    // Sync assets from mock Horizon data
+    // Sync assets from the stub Horizon fixtures
     let count = analyzer.sync_assets().await.unwrap();
-    assert_eq!(count, 4); // Mock data returns 4 assets
+    assert_eq!(count, 4); // The fixtures hold 4 assets
 
     // Verify metrics
     let stats = analyzer.get_metrics().await.unwrap();
@@ -21,12 +22,12 @@ async fn test_trustlines_sync_and_query(pool: SqlitePool) {
     // Verify rankings
     let rankings = analyzer.get_trustline_rankings(5).await.unwrap();
     assert_eq!(rankings.len(), 4);
-    assert_eq!(rankings[0].asset_code, "USDC"); // USDC has the most trustlines in mock
+    assert_eq!(rankings[0].asset_code, "USDC"); // USDC has the most trustlines in the fixtures
 }
 
 #[sqlx::test]
 async fn test_trustlines_snapshots(pool: SqlitePool) {
-    let rpc_client = Arc::new(StellarRpcClient::new_with_defaults(true));
+    let rpc_client = Arc::new(common::horizon::client());
     let analyzer = TrustlineAnalyzer::new(pool.clone(), rpc_client);
 
     // Sync and snapshot
@@ -1,5 +1,5 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Bytes, Env, Map};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Bytes, Env, Map, Vec};
 
 const HASH_SIZE: u32 = 32;
 
@@ -104,6 +104,17 @@ impl SnapshotContract {
         }
     }
 
+    /// Get every epoch that has a snapshot, in ascending order
+    pub fn get_all_epochs(env: Env) -> Vec<u64> {
+        let snapshots: Map<u64, Snapshot> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Snapshots)
+            .unwrap_or_else(|| Map::new(&env));
+
+        snapshots.keys()
+    }
+
     pub fn latest_snapshot(env: Env) -> Option<Snapshot> {
         let latest_epoch: Option<u64> = env.storage().persistent().get(&DataKey::LatestEpoch);
 
@@ -314,6 +325,28 @@ mod test {
         assert_eq!(client.get_snapshot(&epoch2), hash2);
     }
 
+    #[test]
+    fn test_get_all_epochs() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register_contract(None, SnapshotContract);
+        let client = SnapshotContractClient::new(&env, &contract_id);
+
+        assert_eq!(client.get_all_epochs().len(), 0);
+
+        let hash = bytes!(
+            &env,
+            0x3333333333333333333333333333333333333333333333333333333333333333
+        );
+        client.submit_snapshot(&hash, &7);
+        client.submit_snapshot(&hash, &3);
+        client.submit_snapshot(&hash, &5);
+
+        let epochs = client.get_all_epochs();
+        assert_eq!(epochs, soroban_sdk::vec![&env, 3u64, 5u64, 7u64]);
+    }
+
     #[test]
     fn test_latest_snapshot() {
         let env = Env::default();
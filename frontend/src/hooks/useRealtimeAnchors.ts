@@ -6,6 +6,7 @@ export interface AnchorUpdate {
   name: string;
   reliability_score: number;
   status: string;
+  uptime_percentage?: number;
 }
 
 export interface UseRealtimeAnchorsOptions {
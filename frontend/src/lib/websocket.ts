@@ -34,6 +34,7 @@ export interface WsAnchorUpdate {
   name: string;
   reliability_score: number;
   status: string;
+  uptime_percentage?: number;
 }
 
 export interface WsPing {